@@ -1,9 +1,44 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
 
-use grabme_project_model::{event::InputEvent, timeline::Timeline, LoadedProject};
+use grabme_common::config::AppConfig;
+use grabme_processing_core::auto_zoom::{AutoZoomAnalyzer, AutoZoomConfig};
+use grabme_processing_core::progress::AnalysisProgress;
+use grabme_project_model::comments::{ReviewComment, ReviewComments};
+use grabme_project_model::event::{
+    decimate_pointer_events, events_in_range, parse_events, parse_events_header, InputEvent,
+};
+use grabme_project_model::project::{ExportConfig, ExportFormat, OverscanConfig};
+use grabme_project_model::projection::project_events_to_capture_space;
+use grabme_project_model::timeline::{EasingFunction, Timeline};
+use grabme_project_model::viewport::{AspectLock, Viewport};
+use grabme_project_model::LoadedProject;
+use grabme_render_engine::clipboard::copy_file_to_clipboard;
+use grabme_render_engine::detect_captured_cursor;
+use grabme_render_engine::preroll::detect_project_preroll;
+use grabme_render_engine::resources::ExportResources;
+use grabme_render_engine::stills::write_preview_frame;
+use grabme_render_engine::tail::detect_project_tail;
+use grabme_render_engine::webcam_thumbs::{
+    generate_webcam_thumbnails, list_webcam_thumbnails, webcam_thumbs_dir, WebcamThumb,
+    DEFAULT_WEBCAM_THUMB_INTERVAL_SECS,
+};
+use grabme_render_engine::{export_project, ExportJob, ExportProgress, ExportStage};
 use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, State};
+use tauri_plugin_dialog::DialogExt;
+
+/// Event emitted while `run_auto_director` is working.
+const AUTO_DIRECTOR_PROGRESS_EVENT: &str = "auto-director-progress";
+
+/// Event emitted for every export progress report, including the final one.
+const EXPORT_PROGRESS_EVENT: &str = "export-progress";
+
+/// File extension of zipped project bundles.
+const PROJECT_ARCHIVE_EXT: &str = "grabme";
 
 #[derive(Debug, Serialize)]
 struct LoadedProjectBundle {
@@ -12,6 +47,17 @@ struct LoadedProjectBundle {
     height: u32,
     fps: u32,
     screen_path: Option<String>,
+    event_count: usize,
+    events_duration_secs: f64,
+}
+
+/// A window of the event stream, decimated for display.
+#[derive(Debug, Serialize)]
+struct EventsRangeBundle {
+    start_secs: f64,
+    end_secs: f64,
+    /// Events in the window before decimation.
+    total_in_range: usize,
     events: Vec<InputEvent>,
 }
 
@@ -21,6 +67,8 @@ struct TimelineEditorBundle {
     fps: u32,
     duration_secs: f64,
     timeline: Timeline,
+    /// Export overscan padding, for the preview's safe-area guides.
+    overscan: OverscanConfig,
 }
 
 #[derive(Debug, Deserialize)]
@@ -28,6 +76,121 @@ struct SaveTimelinePayload {
     timeline: Timeline,
 }
 
+/// A rectangle drawn on the preview frame, to zoom to.
+#[derive(Debug, Deserialize)]
+struct ZoomToRegionPayload {
+    /// The timeline being edited, possibly unsaved.
+    timeline: Timeline,
+    /// When the zoom starts, in seconds.
+    time_secs: f64,
+    /// The drawn rectangle, normalized to the frame.
+    region: Viewport,
+    easing: EasingFunction,
+    /// How long the zoom takes.
+    duration_secs: f64,
+}
+
+/// A project directory that passed validation and is ready to load.
+#[derive(Debug, Serialize)]
+struct OpenedProject {
+    path: String,
+    name: String,
+    /// Non-fatal problems, e.g. missing source media.
+    issues: Vec<String>,
+}
+
+/// Overrides for the auto-zoom analysis; unset fields use analyzer defaults.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct AutoDirectorOptions {
+    hover_zoom: Option<f64>,
+    scan_zoom: Option<f64>,
+    dwell_radius: Option<f64>,
+    dwell_velocity: Option<f64>,
+    smooth_window: Option<usize>,
+    monitor_count: Option<usize>,
+    focused_monitor: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+struct AutoDirectorSummary {
+    keyframes: usize,
+    events: usize,
+    timeline: Timeline,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct AutoDirectorProgressEvent {
+    project_path: String,
+    stage: &'static str,
+    message: String,
+    /// Fraction of the `analyzing` stage done, while it reports progress.
+    progress: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ExportProgressEvent {
+    project_path: String,
+    stage: &'static str,
+    progress: f64,
+    frames_rendered: u64,
+    total_frames: u64,
+    eta_secs: f64,
+    output_path: Option<String>,
+    error: Option<String>,
+    /// Set on the final event when a GIF export was put on the clipboard.
+    copied_to_clipboard: bool,
+    /// Why a GIF export couldn't be put on the clipboard, on the final event.
+    clipboard_error: Option<String>,
+}
+
+impl ExportProgressEvent {
+    fn from_progress(project_path: &str, progress: &ExportProgress) -> Self {
+        Self {
+            project_path: project_path.to_string(),
+            stage: export_stage_name(progress.stage),
+            progress: progress.progress,
+            frames_rendered: progress.frames_rendered,
+            total_frames: progress.total_frames,
+            eta_secs: progress.eta_secs,
+            output_path: None,
+            error: None,
+            copied_to_clipboard: false,
+            clipboard_error: None,
+        }
+    }
+}
+
+/// Show a native folder picker and validate the chosen project.
+///
+/// Returns `None` when the dialog is cancelled.
+#[tauri::command]
+async fn pick_project_folder(app: AppHandle) -> Result<Option<OpenedProject>, String> {
+    let Some(folder) = app.dialog().file().blocking_pick_folder() else {
+        return Ok(None);
+    };
+    let path = folder
+        .into_path()
+        .map_err(|e| format!("Failed to resolve selected folder: {e}"))?;
+    open_project_at(&path).map(Some)
+}
+
+/// Validate a dropped or associated path (project directory or `.grabme` archive).
+#[tauri::command]
+fn open_project_path(path: String) -> Result<OpenedProject, String> {
+    open_project_at(&resolve_project_path(&path))
+}
+
+/// Project passed on the command line, e.g. when launched from a `.grabme` file.
+#[tauri::command]
+fn launch_project_path() -> Option<String> {
+    std::env::args_os()
+        .skip(1)
+        .map(PathBuf::from)
+        .find(|path| path.exists())
+        .map(|path| path.to_string_lossy().to_string())
+}
+
 #[tauri::command]
 fn load_project_bundle(project_path: String) -> Result<LoadedProjectBundle, String> {
     let root = resolve_project_path(&project_path);
@@ -50,7 +213,65 @@ fn load_project_bundle(project_path: String) -> Result<LoadedProjectBundle, Stri
         height: loaded.project.recording.capture_height,
         fps: loaded.project.recording.fps,
         screen_path,
-        events,
+        event_count: events.len(),
+        events_duration_secs: events.last().map_or(0.0, InputEvent::timestamp_secs),
+    })
+}
+
+/// Parsed events of the last project the timeline asked about, so scrolling
+/// and zooming don't re-read the file. Reloaded when the file changes.
+#[derive(Default)]
+struct EventsCache(Mutex<Option<CachedEvents>>);
+
+struct CachedEvents {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    len: u64,
+    events: Arc<Vec<InputEvent>>,
+}
+
+impl EventsCache {
+    fn events(&self, path: &Path) -> Result<Arc<Vec<InputEvent>>, String> {
+        let metadata = std::fs::metadata(path)
+            .map_err(|e| format!("Failed to read events at {}: {e}", path.display()))?;
+        let (modified, len) = (metadata.modified().ok(), metadata.len());
+
+        let mut cached = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(entry) = cached.as_ref() {
+            if entry.path == path && entry.modified == modified && entry.len == len {
+                return Ok(entry.events.clone());
+            }
+        }
+        let events = Arc::new(read_events(path)?);
+        *cached = Some(CachedEvents {
+            path: path.to_path_buf(),
+            modified,
+            len,
+            events: events.clone(),
+        });
+        Ok(events)
+    }
+}
+
+/// Load events between `start_secs` and `end_secs`, keeping at most
+/// `max_points` pointer samples so long recordings stay cheap to draw.
+#[tauri::command]
+fn load_events_range(
+    cache: State<'_, EventsCache>,
+    project_path: String,
+    start_secs: f64,
+    end_secs: f64,
+    max_points: usize,
+) -> Result<EventsRangeBundle, String> {
+    let root = resolve_project_path(&project_path);
+    let events = cache.events(&root.join("meta").join("events.jsonl"))?;
+    let in_range = events_in_range(&events, start_secs, end_secs);
+
+    Ok(EventsRangeBundle {
+        start_secs,
+        end_secs,
+        total_in_range: in_range.len(),
+        events: decimate_pointer_events(in_range, max_points),
     })
 }
 
@@ -74,9 +295,19 @@ fn load_timeline_bundle(project_path: String) -> Result<TimelineEditorBundle, St
         fps: loaded.project.recording.fps,
         duration_secs,
         timeline: loaded.timeline,
+        overscan: loaded.project.export.overscan,
     })
 }
 
+/// Comments left on `grabme review` pages, in timeline order.
+#[tauri::command]
+fn load_review_comments(project_path: String) -> Result<Vec<ReviewComment>, String> {
+    let root = resolve_project_path(&project_path);
+    ReviewComments::load(&root)
+        .map(|comments| comments.comments)
+        .map_err(|e| format!("Failed to load review comments: {e}"))
+}
+
 #[tauri::command]
 fn save_timeline_bundle(project_path: String, payload: SaveTimelinePayload) -> Result<(), String> {
     let root = resolve_project_path(&project_path);
@@ -89,6 +320,409 @@ fn save_timeline_bundle(project_path: String, payload: SaveTimelinePayload) -> R
         .map_err(|e| format!("Failed to save timeline: {e}"))
 }
 
+#[tauri::command]
+async fn run_auto_director(
+    app: AppHandle,
+    project_path: String,
+    options: Option<AutoDirectorOptions>,
+) -> Result<AutoDirectorSummary, String> {
+    let options = options.unwrap_or_default();
+    let notify = |stage: &'static str, message: String| {
+        let _ = app.emit(
+            AUTO_DIRECTOR_PROGRESS_EVENT,
+            AutoDirectorProgressEvent {
+                project_path: project_path.clone(),
+                stage,
+                message,
+                progress: None,
+            },
+        );
+    };
+
+    notify("loading", "Loading events".to_string());
+    let root = resolve_project_path(&project_path);
+    let mut loaded =
+        LoadedProject::load(&root).map_err(|e| format!("Failed to load project metadata: {e}"))?;
+
+    let events_path = root.join("meta").join("events.jsonl");
+    let events_raw = std::fs::read_to_string(&events_path)
+        .map_err(|e| format!("Failed to read events at {}: {e}", events_path.display()))?;
+    let events = parse_events(&events_raw).map_err(|e| format!("Invalid event line: {e}"))?;
+    let events = project_events_to_capture_space(
+        &events,
+        parse_events_header(&events_raw).as_ref(),
+        &loaded.project.recording,
+    )
+    .events;
+
+    notify("analyzing", format!("Analyzing {} events", events.len()));
+    let defaults = AutoZoomConfig::default();
+    let config = AutoZoomConfig {
+        hover_zoom: options.hover_zoom.unwrap_or(defaults.hover_zoom),
+        scan_zoom: options.scan_zoom.unwrap_or(defaults.scan_zoom),
+        dwell_radius: options.dwell_radius.unwrap_or(defaults.dwell_radius),
+        dwell_velocity_threshold: options
+            .dwell_velocity
+            .unwrap_or(defaults.dwell_velocity_threshold),
+        smoothing_window: options.smooth_window.unwrap_or(defaults.smoothing_window),
+        monitor_count: options.monitor_count.unwrap_or(defaults.monitor_count),
+        focused_monitor_index: options
+            .focused_monitor
+            .unwrap_or(defaults.focused_monitor_index),
+        ..defaults
+    };
+    let progress_app = app.clone();
+    let progress_path = project_path.clone();
+    let event_count = events.len();
+    let timeline = AutoZoomAnalyzer::new(config)
+        .with_progress(Arc::new(move |progress: AnalysisProgress| {
+            let _ = progress_app.emit(
+                AUTO_DIRECTOR_PROGRESS_EVENT,
+                AutoDirectorProgressEvent {
+                    project_path: progress_path.clone(),
+                    stage: "analyzing",
+                    message: format!(
+                        "Analyzing {event_count} events ({:.0}%)",
+                        progress.fraction * 100.0
+                    ),
+                    progress: Some(progress.fraction),
+                },
+            );
+        }))
+        .analyze(&events);
+    loaded.timeline.keyframes = timeline.keyframes;
+
+    notify("saving", "Saving timeline".to_string());
+    loaded
+        .save()
+        .map_err(|e| format!("Failed to save timeline: {e}"))?;
+
+    let keyframes = loaded.timeline.keyframes.len();
+    notify("complete", format!("Generated {keyframes} keyframes"));
+
+    Ok(AutoDirectorSummary {
+        keyframes,
+        events: events.len(),
+        timeline: loaded.timeline,
+    })
+}
+
+/// Webcam thumbnails for the timeline, generated on first request.
+///
+/// Returns an empty list for projects without a webcam track.
+#[tauri::command]
+async fn load_webcam_thumbnails(project_path: String) -> Result<Vec<WebcamThumb>, String> {
+    let root = resolve_project_path(&project_path);
+    let existing = list_webcam_thumbnails(&root)
+        .map_err(|e| format!("Failed to read webcam thumbnails: {e}"))?;
+    if !existing.is_empty() {
+        return Ok(existing);
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        generate_webcam_thumbnails(&root, DEFAULT_WEBCAM_THUMB_INTERVAL_SECS)
+    })
+    .await
+    .map_err(|e| format!("Thumbnail task failed: {e}"))?
+    .map_err(|e| format!("Failed to generate webcam thumbnails: {e}"))
+}
+
+/// JPEG bytes of one webcam thumbnail listed by `load_webcam_thumbnails`.
+#[tauri::command]
+fn read_webcam_thumbnail(project_path: String, file: String) -> Result<Vec<u8>, String> {
+    if file.contains(['/', '\\']) || file.starts_with('.') {
+        return Err(format!("Invalid thumbnail name: {file}"));
+    }
+    let path = webcam_thumbs_dir(&resolve_project_path(&project_path)).join(&file);
+    std::fs::read(&path).map_err(|e| format!("Failed to read {}: {e}", path.display()))
+}
+
+/// Where the recording's black pre-roll ends, in seconds. The editor uses
+/// this to prefill the start offset override.
+#[tauri::command]
+async fn detect_preroll(project_path: String) -> Result<f64, String> {
+    let root = resolve_project_path(&project_path);
+    tauri::async_runtime::spawn_blocking(move || detect_project_preroll(&root))
+        .await
+        .map_err(|e| format!("Pre-roll detection task failed: {e}"))?
+        .map_err(|e| format!("Failed to detect pre-roll: {e}"))
+}
+
+/// Where the recording's idle tail starts, in seconds, or `None` when its
+/// end has nothing to trim. The editor uses this to prefill the end offset
+/// override.
+#[tauri::command]
+async fn detect_tail(project_path: String) -> Result<Option<f64>, String> {
+    let root = resolve_project_path(&project_path);
+    tauri::async_runtime::spawn_blocking(move || detect_project_tail(&root))
+        .await
+        .map_err(|e| format!("Tail detection task failed: {e}"))?
+        .map(|tail| tail.map(|tail| tail.end_secs))
+        .map_err(|e| format!("Failed to detect idle tail: {e}"))
+}
+
+/// Whether the screen recording shows the system cursor, so the editor can
+/// offer to skip the synthetic one. `None` when the probe can't tell.
+#[tauri::command]
+async fn detect_cursor_in_recording(project_path: String) -> Result<Option<bool>, String> {
+    let root = resolve_project_path(&project_path);
+    tauri::async_runtime::spawn_blocking(move || detect_captured_cursor(&root))
+        .await
+        .map_err(|e| format!("Cursor detection task failed: {e}"))?
+        .map(|probe| probe.map(|probe| probe.cursor_visible()))
+        .map_err(|e| format!("Failed to detect the recorded cursor: {e}"))
+}
+
+/// PNG bytes of the screen frame at `time_secs`, for drawing zoom regions on.
+#[tauri::command]
+async fn read_preview_frame(project_path: String, time_secs: f64) -> Result<Vec<u8>, String> {
+    let root = resolve_project_path(&project_path);
+    let path = tauri::async_runtime::spawn_blocking(move || write_preview_frame(&root, time_secs))
+        .await
+        .map_err(|e| format!("Preview frame task failed: {e}"))?
+        .map_err(|e| format!("Failed to extract the preview frame: {e}"))?;
+    std::fs::read(&path).map_err(|e| format!("Failed to read {}: {e}", path.display()))
+}
+
+/// The timeline with a manual zoom onto the drawn region added.
+/// Add a zoom to `payload.region`, framed in the shape of the export.
+#[tauri::command]
+fn zoom_to_region(project_path: String, payload: ZoomToRegionPayload) -> Result<Timeline, String> {
+    let lock = project_aspect_lock(&project_path)?;
+    let mut timeline = payload.timeline;
+    timeline.zoom_to_region(
+        payload.time_secs,
+        payload.region,
+        payload.easing,
+        payload.duration_secs,
+        lock,
+    );
+    Ok(timeline)
+}
+
+/// `payload.timeline` with every keyframe that would be distorted in the
+/// export reshaped to the export aspect.
+#[tauri::command]
+fn lock_timeline_aspect(
+    project_path: String,
+    payload: SaveTimelinePayload,
+) -> Result<Timeline, String> {
+    let lock = project_aspect_lock(&project_path)?;
+    let mut timeline = payload.timeline;
+    timeline.lock_aspect(lock);
+    Ok(timeline)
+}
+
+/// Turn the export's overscan padding on or off and save the project.
+#[tauri::command]
+fn set_overscan(project_path: String, enabled: bool) -> Result<OverscanConfig, String> {
+    let root = resolve_project_path(&project_path);
+    let mut loaded =
+        LoadedProject::load(&root).map_err(|e| format!("Failed to load project metadata: {e}"))?;
+    loaded.project.export.overscan.enabled = enabled;
+    loaded
+        .save()
+        .map_err(|e| format!("Failed to save project: {e}"))?;
+    Ok(loaded.project.export.overscan)
+}
+
+/// Viewport shape for the project's export. Square and vertical exports
+/// reframe the camera themselves, so they keep the capture's shape.
+fn project_aspect_lock(project_path: &str) -> Result<AspectLock, String> {
+    let root = resolve_project_path(project_path);
+    let loaded =
+        LoadedProject::load(&root).map_err(|e| format!("Failed to load project metadata: {e}"))?;
+    Ok(loaded.aspect_lock().unwrap_or(AspectLock::CAPTURE))
+}
+
+/// Start an export in the background and return the output path.
+///
+/// Progress is reported through `export-progress` events; the final event has
+/// stage `complete` or `failed`.
+#[tauri::command]
+fn start_export(
+    app: AppHandle,
+    project_path: String,
+    config: Option<ExportConfig>,
+) -> Result<String, String> {
+    let root = resolve_project_path(&project_path);
+    let loaded =
+        LoadedProject::load(&root).map_err(|e| format!("Failed to load project metadata: {e}"))?;
+
+    let config = config.unwrap_or_else(|| {
+        let mut config = loaded.project.export.clone();
+        (config.width, config.height) = loaded.project.recording.default_export_size(config.format);
+        config
+    });
+    let output_path = if config.format.is_image_sequence() {
+        root.join("exports").join("frames")
+    } else {
+        root.join("exports")
+            .join(format!("output.{}", export_extension(config.format)))
+    };
+    let job = ExportJob {
+        project_dir: root,
+        output_path: output_path.clone(),
+        config,
+        start_secs: None,
+        end_secs: None,
+        trim_preroll: AppConfig::load().recording.trim_preroll,
+        trim_tail: AppConfig::load().recording.trim_tail,
+        debug_overlay: false,
+        resources: ExportResources::background().with_memory(&AppConfig::load().memory.profile()),
+        fast_copy: false,
+        draft: None,
+    };
+
+    let output_display = output_path.to_string_lossy().to_string();
+    let copy_to_clipboard = job.config.format == ExportFormat::Gif;
+    std::thread::spawn(move || {
+        let progress_app = app.clone();
+        let progress_path = project_path.clone();
+        let callback = Box::new(move |progress: ExportProgress| {
+            let _ = progress_app.emit(
+                EXPORT_PROGRESS_EVENT,
+                ExportProgressEvent::from_progress(&progress_path, &progress),
+            );
+        });
+
+        let mut done = ExportProgressEvent::from_progress(
+            &project_path,
+            &ExportProgress {
+                progress: 1.0,
+                frames_rendered: 0,
+                total_frames: 0,
+                eta_secs: 0.0,
+                stage: ExportStage::Complete,
+            },
+        );
+        match tauri::async_runtime::block_on(export_project(job, Some(callback))) {
+            Ok(path) => {
+                if copy_to_clipboard {
+                    match copy_file_to_clipboard(&path) {
+                        Ok(_) => done.copied_to_clipboard = true,
+                        Err(e) => {
+                            done.clipboard_error = Some(format!("Clipboard copy failed: {e}"))
+                        }
+                    }
+                }
+                done.output_path = Some(path.to_string_lossy().to_string());
+            }
+            Err(e) => {
+                done.stage = export_stage_name(ExportStage::Failed);
+                done.progress = 0.0;
+                done.error = Some(format!("Export failed: {e}"));
+            }
+        }
+        let _ = app.emit(EXPORT_PROGRESS_EVENT, done);
+    });
+
+    Ok(output_display)
+}
+
+fn export_stage_name(stage: ExportStage) -> &'static str {
+    match stage {
+        ExportStage::Preparing => "preparing",
+        ExportStage::Rendering => "rendering",
+        ExportStage::Encoding => "encoding",
+        ExportStage::Finalizing => "finalizing",
+        ExportStage::Complete => "complete",
+        ExportStage::Failed => "failed",
+    }
+}
+
+fn export_extension(format: ExportFormat) -> &'static str {
+    match format {
+        ExportFormat::Mp4H264 | ExportFormat::Mp4H265 => "mp4",
+        ExportFormat::Gif => "gif",
+        ExportFormat::Webm => "webm",
+        ExportFormat::PngSequence => "png",
+    }
+}
+
+fn open_project_at(path: &Path) -> Result<OpenedProject, String> {
+    let root = if path.is_file()
+        && path
+            .extension()
+            .is_some_and(|ext| ext == PROJECT_ARCHIVE_EXT)
+    {
+        extract_project_archive(path)?
+    } else if path.is_dir() {
+        path.to_path_buf()
+    } else {
+        return Err(format!(
+            "{} is not a project folder or .{PROJECT_ARCHIVE_EXT} archive",
+            path.display()
+        ));
+    };
+
+    let loaded = LoadedProject::load(&root)
+        .map_err(|e| format!("{} is not a GrabMe project: {e}", root.display()))?;
+
+    Ok(OpenedProject {
+        path: root.to_string_lossy().to_string(),
+        name: loaded.project.name.clone(),
+        issues: loaded
+            .validate_sources()
+            .into_iter()
+            .chain(loaded.validate_timeline())
+            .collect(),
+    })
+}
+
+/// Unpack a `.grabme` archive into a new folder next to itself. An existing
+/// folder of the same name is left alone and a numbered one used instead.
+fn extract_project_archive(archive_path: &Path) -> Result<PathBuf, String> {
+    let target = claim_extraction_dir(&archive_path.with_extension(""))?;
+
+    let file = std::fs::File::open(archive_path)
+        .map_err(|e| format!("Failed to open {}: {e}", archive_path.display()))?;
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|e| format!("Failed to read {}: {e}", archive_path.display()))?;
+    archive
+        .extract(&target)
+        .map_err(|e| format!("Failed to extract {}: {e}", archive_path.display()))?;
+
+    // Archives may wrap the bundle in a single top-level folder.
+    if !target.join("meta").exists() {
+        let nested = std::fs::read_dir(&target)
+            .map_err(|e| format!("Failed to read {}: {e}", target.display()))?
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .find(|path| path.join("meta").join("project.json").exists());
+        if let Some(nested) = nested {
+            return Ok(nested);
+        }
+    }
+
+    Ok(target)
+}
+
+/// Create `base`, or `base-2`, `base-3`, ... when it is taken. Creating the
+/// folder claims it, so two extractions never share one.
+fn claim_extraction_dir(base: &Path) -> Result<PathBuf, String> {
+    let stem = base
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "project".to_string());
+    for n in 1..1000 {
+        let candidate = if n == 1 {
+            base.to_path_buf()
+        } else {
+            base.with_file_name(format!("{stem}-{n}"))
+        };
+        match std::fs::create_dir(&candidate) {
+            Ok(()) => return Ok(candidate),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(format!("Failed to create {}: {e}", candidate.display())),
+        }
+    }
+    Err(format!(
+        "No free folder to extract into next to {}",
+        base.display()
+    ))
+}
+
 fn resolve_project_path(project_path: &str) -> PathBuf {
     let path = PathBuf::from(project_path);
     if path.is_absolute() {
@@ -119,11 +753,30 @@ fn read_events(path: &Path) -> Result<Vec<InputEvent>, String> {
 }
 
 fn main() {
+    let _temp = grabme_common::temp::init_session_temp();
     tauri::Builder::default()
+        .plugin(tauri_plugin_dialog::init())
+        .manage(EventsCache::default())
         .invoke_handler(tauri::generate_handler![
+            pick_project_folder,
+            open_project_path,
+            launch_project_path,
             load_project_bundle,
             load_timeline_bundle,
-            save_timeline_bundle
+            save_timeline_bundle,
+            load_review_comments,
+            load_events_range,
+            run_auto_director,
+            load_webcam_thumbnails,
+            read_webcam_thumbnail,
+            detect_preroll,
+            detect_tail,
+            detect_cursor_in_recording,
+            read_preview_frame,
+            zoom_to_region,
+            lock_timeline_aspect,
+            set_overscan,
+            start_export
         ])
         .run(tauri::generate_context!())
         .expect("error while running GrabMe desktop app");
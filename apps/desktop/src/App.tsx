@@ -1,6 +1,15 @@
-import { useMemo, useState } from "react";
+import { useCallback, useEffect, useMemo, useRef, useState, type PointerEvent } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import { TimelineSegment, TimelineTrack } from "./components/TimelineTrack";
+import { listen } from "@tauri-apps/api/event";
+import { getCurrentWebview } from "@tauri-apps/api/webview";
+import {
+  TimelineComment,
+  TimelineEventOverlay,
+  TimelineSegment,
+  TimelineSpeedMark,
+  TimelineThumbnail,
+  TimelineTrack
+} from "./components/TimelineTrack";
 
 type Viewport = {
   x: number;
@@ -9,13 +18,54 @@ type Viewport = {
   h: number;
 };
 
+type CubicBezier = { x1: number; y1: number; x2: number; y2: number };
+
+type Spring = { stiffness: number; damping: number; mass: number };
+
+/** Named curves serialize as strings, parametric ones as a one-key object. */
+type Easing = string | { cubic_bezier: CubicBezier } | { spring: Spring };
+
 type CameraKeyframe = {
   t: number;
   viewport: Viewport;
-  easing: string;
+  easing: Easing;
   source: string;
+  confidence?: number | null;
 };
 
+const NAMED_EASINGS = [
+  "linear",
+  "ease_in",
+  "ease_out",
+  "ease_in_out",
+  "ease_in_cubic",
+  "ease_out_cubic",
+  "ease_in_out_cubic",
+  "ease_in_sine",
+  "ease_out_sine",
+  "ease_in_out_sine",
+  "ease_in_expo",
+  "ease_out_expo",
+  "ease_in_out_expo",
+  "ease_in_back",
+  "ease_out_back",
+  "ease_in_out_back"
+];
+
+const DEFAULT_BEZIER: CubicBezier = { x1: 0.25, y1: 0.1, x2: 0.25, y2: 1 };
+
+const DEFAULT_SPRING: Spring = { stiffness: 170, damping: 18, mass: 1 };
+
+function easingKind(easing: Easing): string {
+  if (typeof easing === "string") {
+    return easing;
+  }
+  return "cubic_bezier" in easing ? "cubic_bezier" : "spring";
+}
+
+/** Keyframes scored below this are highlighted for review. */
+const LOW_CONFIDENCE_THRESHOLD = 0.5;
+
 type CursorMotionTrailConfig = {
   enabled: boolean;
   ghost_count: number;
@@ -30,14 +80,42 @@ type CursorConfig = {
   custom_asset: string | null;
   show_click_animation: boolean;
   motion_trail: CursorMotionTrailConfig;
+  overlay?: CursorOverlay;
 };
 
+type CursorOverlay = "auto" | "show" | "hide";
+
+type SpeedSegment =
+  | {
+      type: "slow_motion";
+      start_secs: number;
+      end_secs: number;
+      rate: number;
+      audio: "mute" | "stretch";
+      interpolate: boolean;
+    }
+  | { type: "freeze"; at_secs: number; duration_secs: number };
+
+/** Slow-motion rates offered in the editor; the exporter accepts 0.25-0.5. */
+const SLOW_MOTION_RATES = [0.25, 0.33, 0.5];
+const DEFAULT_SLOW_MOTION_SECS = 1;
+const DEFAULT_FREEZE_SECS = 1.5;
+
+/** Default length of a zoom onto a drawn region, in seconds. */
+const DEFAULT_REGION_ZOOM_SECS = 0.6;
+
+/** Drawn regions smaller than this share of the frame are ignored. */
+const MIN_REGION_SIZE = 0.01;
+
 type Timeline = {
   version: string;
   keyframes: CameraKeyframe[];
   effects: unknown[];
   cursor_config: CursorConfig;
   cuts: Array<{ start_secs: number; end_secs: number; reason: string }>;
+  speed_segments: SpeedSegment[];
+  start_offset_secs?: number | null;
+  end_offset_secs?: number | null;
 };
 
 type TimelineEditorBundle = {
@@ -45,6 +123,76 @@ type TimelineEditorBundle = {
   fps: number;
   duration_secs: number;
   timeline: Timeline;
+  overscan: OverscanConfig;
+};
+
+type OverscanConfig = {
+  enabled: boolean;
+  margin_ratio: number;
+};
+
+type OpenedProject = {
+  path: string;
+  name: string;
+  issues: string[];
+};
+
+type InputEvent = {
+  t: number;
+  type: string;
+  x?: number;
+  y?: number;
+  state?: string;
+};
+
+type WebcamThumb = {
+  time_secs: number;
+  file: string;
+};
+
+type EventsRangeBundle = {
+  start_secs: number;
+  end_secs: number;
+  total_in_range: number;
+  events: InputEvent[];
+};
+
+/** Pointer samples requested per timeline pixel at the current zoom. */
+const POINTER_SAMPLES_PER_PIXEL = 0.5;
+
+/** A comment from a `grabme review` page (`meta/comments.json`). */
+type ReviewComment = {
+  time_secs: number;
+  video_secs: number;
+  author: string;
+  text: string;
+  created_at: string;
+};
+
+type AutoDirectorSummary = {
+  keyframes: number;
+  events: number;
+  timeline: Timeline;
+};
+
+type AutoDirectorProgressEvent = {
+  project_path: string;
+  stage: string;
+  message: string;
+  progress: number | null;
+};
+
+type ExportProgressEvent = {
+  project_path: string;
+  stage: string;
+  progress: number;
+  frames_rendered: number;
+  total_frames: number;
+  eta_secs: number;
+  output_path: string | null;
+  error: string | null;
+  copied_to_clipboard: boolean;
+  clipboard_error: string | null;
 };
 
 export default function App(): JSX.Element {
@@ -54,6 +202,227 @@ export default function App(): JSX.Element {
   const [status, setStatus] = useState<string>("Load a project to begin");
   const [dirty, setDirty] = useState(false);
   const [zoom, setZoom] = useState(64);
+  const [analyzing, setAnalyzing] = useState(false);
+  const [dropActive, setDropActive] = useState(false);
+  const [eventOverlay, setEventOverlay] = useState<TimelineEventOverlay | null>(null);
+  const [webcamThumbs, setWebcamThumbs] = useState<TimelineThumbnail[]>([]);
+  const [reviewComments, setReviewComments] = useState<TimelineComment[]>([]);
+  const [exportProgress, setExportProgress] = useState<ExportProgressEvent | null>(null);
+  const [speedAt, setSpeedAt] = useState(0);
+  const [regionAt, setRegionAt] = useState(0);
+  const [regionSecs, setRegionSecs] = useState(DEFAULT_REGION_ZOOM_SECS);
+  const [regionEasing, setRegionEasing] = useState("ease_in_out");
+  const [previewUrl, setPreviewUrl] = useState<string | null>(null);
+  const [region, setRegion] = useState<Viewport | null>(null);
+  const [regionAnchor, setRegionAnchor] = useState<[number, number] | null>(null);
+  const [safeArea, setSafeArea] = useState(false);
+  const [eventView, setEventView] = useState<{ start: number; end: number } | null>(null);
+  const fetchedEventsRef = useRef<{ start: number; end: number; zoom: number } | null>(null);
+
+  const exporting =
+    exportProgress !== null &&
+    exportProgress.stage !== "complete" &&
+    exportProgress.stage !== "failed";
+
+  const loadedName = bundle?.name;
+  const durationSecs = bundle?.duration_secs ?? 0;
+
+  useEffect(() => {
+    fetchedEventsRef.current = null;
+  }, [loadedName]);
+
+  useEffect(() => {
+    if (!loadedName || durationSecs <= 0 || !eventView) {
+      setEventOverlay(null);
+      return;
+    }
+
+    // Keep what is already drawn while the view stays inside the last fetch.
+    const fetched = fetchedEventsRef.current;
+    if (
+      fetched &&
+      fetched.zoom === zoom &&
+      fetched.start <= eventView.start &&
+      fetched.end >= eventView.end
+    ) {
+      return;
+    }
+
+    // Fetch half a view either side so short scrolls don't refetch.
+    const margin = (eventView.end - eventView.start) / 2;
+    const startSecs = Math.max(0, eventView.start - margin);
+    const endSecs = Math.min(durationSecs, eventView.end + margin);
+    const maxPoints = Math.ceil((endSecs - startSecs) * zoom * POINTER_SAMPLES_PER_PIXEL);
+
+    let cancelled = false;
+    invoke<EventsRangeBundle>("load_events_range", {
+      projectPath,
+      startSecs,
+      endSecs,
+      maxPoints
+    })
+      .then((range) => {
+        if (cancelled) {
+          return;
+        }
+        const pointer: Array<[number, number]> = [];
+        const clicks: number[] = [];
+        for (const event of range.events) {
+          const secs = event.t / 1_000_000_000;
+          if (event.type === "pointer" && event.y !== undefined) {
+            pointer.push([secs, event.y]);
+          } else if (event.type === "click" && event.state === "down") {
+            clicks.push(secs);
+          }
+        }
+        fetchedEventsRef.current = { start: startSecs, end: endSecs, zoom };
+        setEventOverlay({ pointer, clicks });
+      })
+      .catch(() => {
+        if (!cancelled) {
+          fetchedEventsRef.current = null;
+          setEventOverlay(null);
+        }
+      });
+
+    return () => {
+      cancelled = true;
+    };
+    // Refetch when the project, zoom level or visible window changes.
+  }, [loadedName, durationSecs, zoom, eventView]);
+
+  useEffect(() => {
+    if (!loadedName) {
+      setWebcamThumbs([]);
+      return;
+    }
+
+    let cancelled = false;
+    const urls: string[] = [];
+    invoke<WebcamThumb[]>("load_webcam_thumbnails", { projectPath })
+      .then((thumbs) =>
+        Promise.all(
+          thumbs.map(async (thumb) => {
+            const bytes = await invoke<number[]>("read_webcam_thumbnail", {
+              projectPath,
+              file: thumb.file
+            });
+            const url = URL.createObjectURL(
+              new Blob([new Uint8Array(bytes)], { type: "image/jpeg" })
+            );
+            urls.push(url);
+            return { time: thumb.time_secs, url };
+          })
+        )
+      )
+      .then((loaded) => {
+        if (!cancelled) {
+          setWebcamThumbs(loaded);
+        }
+      })
+      .catch(() => {
+        if (!cancelled) {
+          setWebcamThumbs([]);
+        }
+      });
+
+    return () => {
+      cancelled = true;
+      urls.forEach((url) => URL.revokeObjectURL(url));
+    };
+    // Thumbnails only change when another project is loaded.
+  }, [loadedName]);
+
+  useEffect(() => {
+    if (!loadedName) {
+      setReviewComments([]);
+      return;
+    }
+
+    let cancelled = false;
+    invoke<ReviewComment[]>("load_review_comments", { projectPath })
+      .then((comments) => {
+        if (!cancelled) {
+          setReviewComments(
+            comments.map((comment) => ({
+              time: comment.time_secs,
+              label: comment.author ? `${comment.author}: ${comment.text}` : comment.text
+            }))
+          );
+        }
+      })
+      .catch(() => {
+        if (!cancelled) {
+          setReviewComments([]);
+        }
+      });
+
+    return () => {
+      cancelled = true;
+    };
+    // Comments are read when a project is (re)opened.
+  }, [loadedName]);
+
+  useEffect(() => {
+    invoke<string | null>("launch_project_path").then((path) => {
+      if (path) {
+        void openProjectPath(path);
+      }
+    });
+
+    const unlistenDrop = getCurrentWebview().onDragDropEvent((event) => {
+      if (event.payload.type === "over") {
+        setDropActive(true);
+      } else if (event.payload.type === "drop") {
+        setDropActive(false);
+        const [path] = event.payload.paths;
+        if (path) {
+          void openProjectPath(path);
+        }
+      } else {
+        setDropActive(false);
+      }
+    });
+
+    return () => {
+      unlistenDrop.then((unlisten) => unlisten());
+    };
+  }, []);
+
+  useEffect(() => {
+    const unlistenAnalyze = listen<AutoDirectorProgressEvent>(
+      "auto-director-progress",
+      (event) => setStatus(`Auto-Director: ${event.payload.message}`)
+    );
+    const unlistenExport = listen<ExportProgressEvent>("export-progress", (event) => {
+      const progress = event.payload;
+      setExportProgress(progress);
+      if (progress.stage === "complete") {
+        setStatus(
+          `Exported ${progress.output_path ?? ""}${
+            progress.copied_to_clipboard ? " · copied to clipboard" : ""
+          }`
+        );
+        if (progress.clipboard_error) {
+          setError(progress.clipboard_error);
+        }
+      } else if (progress.stage === "failed") {
+        setError(progress.error);
+        setStatus("Export failed");
+      } else {
+        setStatus(
+          `Export ${progress.stage} ${Math.round(progress.progress * 100)}% · ETA ${Math.ceil(
+            progress.eta_secs
+          )}s`
+        );
+      }
+    });
+
+    return () => {
+      unlistenAnalyze.then((unlisten) => unlisten());
+      unlistenExport.then((unlisten) => unlisten());
+    };
+  }, []);
 
   const keyframes = useMemo(() => {
     if (!bundle) {
@@ -71,22 +440,61 @@ export default function App(): JSX.Element {
       const nextTime = keyframes[index + 1]?.t ?? bundle.duration_secs;
       const zoomFactor = 1 / Math.max(frame.viewport.w, frame.viewport.h);
       const tint = Math.min(0.85, 0.35 + zoomFactor * 0.15);
+      const confidence = frame.confidence ?? 1;
+      const lowConfidence = confidence < LOW_CONFIDENCE_THRESHOLD;
+      const label = `k${index + 1} ${Math.round(zoomFactor * 100)}%`;
       return {
         id: `segment-${index}`,
-        label: `k${index + 1} ${Math.round(zoomFactor * 100)}%`,
+        label: lowConfidence ? `${label} (${Math.round(confidence * 100)}% sure)` : label,
         start: frame.t,
         end: Math.max(frame.t + 0.2, nextTime),
-        color: `rgba(35, 179, 139, ${tint.toFixed(3)})`
+        color: lowConfidence
+          ? `rgba(230, 162, 60, ${tint.toFixed(3)})`
+          : `rgba(35, 179, 139, ${tint.toFixed(3)})`,
+        flagged: lowConfidence
       };
     });
   }, [bundle, keyframes]);
 
-  async function loadTimelineBundle() {
+  async function openProjectPath(path: string) {
+    setError(null);
+    setStatus("Opening project...");
+    try {
+      const opened = await invoke<OpenedProject>("open_project_path", { path });
+      await applyOpenedProject(opened);
+    } catch (openError) {
+      setError(String(openError));
+      setStatus("Not a GrabMe project");
+    }
+  }
+
+  async function pickProjectFolder() {
+    setError(null);
+    try {
+      const opened = await invoke<OpenedProject | null>("pick_project_folder");
+      if (opened) {
+        await applyOpenedProject(opened);
+      }
+    } catch (pickError) {
+      setError(String(pickError));
+      setStatus("Not a GrabMe project");
+    }
+  }
+
+  async function applyOpenedProject(opened: OpenedProject) {
+    setProjectPath(opened.path);
+    await loadTimelineBundle(opened.path);
+    if (opened.issues.length > 0) {
+      setError(opened.issues.join("; "));
+    }
+  }
+
+  async function loadTimelineBundle(path: string = projectPath) {
     setError(null);
     setStatus("Loading timeline...");
     try {
       const data = await invoke<TimelineEditorBundle>("load_timeline_bundle", {
-        projectPath
+        projectPath: path
       });
       data.timeline.keyframes.sort((a, b) => a.t - b.t);
       setBundle(data);
@@ -117,6 +525,64 @@ export default function App(): JSX.Element {
     }
   }
 
+  async function runAutoDirector() {
+    setError(null);
+    setAnalyzing(true);
+    try {
+      const summary = await invoke<AutoDirectorSummary>("run_auto_director", {
+        projectPath,
+        options: null
+      });
+      summary.timeline.keyframes.sort((a, b) => a.t - b.t);
+      setBundle((prev) => (prev ? { ...prev, timeline: summary.timeline } : prev));
+      setDirty(false);
+      setStatus(`Auto-Director generated ${summary.keyframes} keyframes`);
+    } catch (analyzeError) {
+      setError(String(analyzeError));
+      setStatus("Auto-Director failed");
+    } finally {
+      setAnalyzing(false);
+    }
+  }
+
+  async function startExport() {
+    setError(null);
+    // Progress events can arrive before `start_export` returns, so the
+    // preparing state goes first and never replaces one of them.
+    setExportProgress({
+      project_path: projectPath,
+      stage: "preparing",
+      progress: 0,
+      frames_rendered: 0,
+      total_frames: 0,
+      eta_secs: 0,
+      output_path: null,
+      error: null,
+      copied_to_clipboard: false,
+      clipboard_error: null
+    });
+    setStatus("Preparing export");
+    try {
+      const outputPath = await invoke<string>("start_export", {
+        projectPath,
+        config: null
+      });
+      setExportProgress((prev) =>
+        prev && prev.output_path === null ? { ...prev, output_path: outputPath } : prev
+      );
+    } catch (exportError) {
+      setExportProgress(null);
+      setError(String(exportError));
+      setStatus("Export failed");
+    }
+  }
+
+  const updateEventView = useCallback((start: number, end: number) => {
+    setEventView((prev) =>
+      prev && prev.start === start && prev.end === end ? prev : { start, end }
+    );
+  }, []);
+
   function updateSegment(segmentId: string, start: number, end: number) {
     if (!bundle) {
       return;
@@ -155,6 +621,107 @@ export default function App(): JSX.Element {
     setStatus("Unsaved timeline edits");
   }
 
+  function setStartOffset(secs: number | null) {
+    setBundle((prev) => {
+      if (!prev) {
+        return prev;
+      }
+      const timeline = structuredClone(prev.timeline);
+      timeline.start_offset_secs = secs === null ? null : Math.max(0, secs);
+      return { ...prev, timeline };
+    });
+    setDirty(true);
+  }
+
+  async function detectStartOffset() {
+    setError(null);
+    setStatus("Detecting pre-roll...");
+    try {
+      const secs = await invoke<number>("detect_preroll", { projectPath });
+      setStartOffset(secs);
+      setStatus(`Detected ${secs.toFixed(2)}s of pre-roll`);
+    } catch (detectError) {
+      setError(String(detectError));
+      setStatus("Pre-roll detection failed");
+    }
+  }
+
+  function setEndOffset(secs: number | null) {
+    setBundle((prev) => {
+      if (!prev) {
+        return prev;
+      }
+      const timeline = structuredClone(prev.timeline);
+      timeline.end_offset_secs = secs === null ? null : Math.max(0, secs);
+      return { ...prev, timeline };
+    });
+    setDirty(true);
+  }
+
+  async function detectEndOffset() {
+    setError(null);
+    setStatus("Detecting idle tail...");
+    try {
+      const secs = await invoke<number | null>("detect_tail", { projectPath });
+      if (secs === null) {
+        setEndOffset(bundle?.duration_secs ?? null);
+        setStatus("No idle tail to trim");
+      } else {
+        setEndOffset(secs);
+        setStatus(`Export ends at ${secs.toFixed(2)}s, before the idle tail`);
+      }
+    } catch (detectError) {
+      setError(String(detectError));
+      setStatus("Tail detection failed");
+    }
+  }
+
+  function updateSpeedSegments(update: (segments: SpeedSegment[]) => SpeedSegment[]) {
+    setBundle((prev) => {
+      if (!prev) {
+        return prev;
+      }
+      const timeline = structuredClone(prev.timeline);
+      timeline.speed_segments = update(timeline.speed_segments ?? []).sort(
+        (a, b) => speedSegmentStart(a) - speedSegmentStart(b)
+      );
+      return { ...prev, timeline };
+    });
+    setDirty(true);
+    setStatus("Unsaved timeline edits");
+  }
+
+  function addSpeedSegment(type: SpeedSegment["type"]) {
+    if (!bundle) {
+      return;
+    }
+    const at = clamp(speedAt, 0, bundle.duration_secs);
+    const segment: SpeedSegment =
+      type === "freeze"
+        ? { type, at_secs: at, duration_secs: DEFAULT_FREEZE_SECS }
+        : {
+            type,
+            start_secs: at,
+            end_secs: Math.min(bundle.duration_secs, at + DEFAULT_SLOW_MOTION_SECS),
+            rate: 0.5,
+            audio: "mute",
+            interpolate: false,
+          };
+    updateSpeedSegments((segments) => [...segments, segment]);
+  }
+
+  function editSpeedSegment(index: number, patch: Partial<SpeedSegment>) {
+    updateSpeedSegments((segments) =>
+      segments.map((segment, i) =>
+        i === index ? ({ ...segment, ...patch } as SpeedSegment) : segment
+      )
+    );
+  }
+
+  function removeSpeedSegment(index: number) {
+    updateSpeedSegments((segments) => segments.filter((_, i) => i !== index));
+  }
+
   function setHideMouseJitter(enabled: boolean) {
     if (!bundle) {
       return;
@@ -174,14 +741,213 @@ export default function App(): JSX.Element {
     setDirty(true);
   }
 
+  function setCursorOverlay(overlay: CursorOverlay) {
+    setBundle((prev) => {
+      if (!prev) {
+        return prev;
+      }
+      const timeline = structuredClone(prev.timeline);
+      timeline.cursor_config.overlay = overlay;
+      return { ...prev, timeline };
+    });
+    setDirty(true);
+    setStatus("Unsaved timeline edits");
+  }
+
+  async function detectRecordedCursor() {
+    setError(null);
+    setStatus("Checking the recording for a cursor...");
+    try {
+      const visible = await invoke<boolean | null>("detect_cursor_in_recording", {
+        projectPath,
+      });
+      if (visible === null) {
+        setStatus("The pointer rarely rests; can't tell whether the recording shows a cursor");
+      } else if (visible) {
+        setCursorOverlay("hide");
+        setStatus("The recording already shows the cursor; export won't draw a second one");
+      } else {
+        setStatus("The recording has no cursor; export draws one");
+      }
+    } catch (detectError) {
+      setError(String(detectError));
+      setStatus("Cursor detection failed");
+    }
+  }
+
+  async function loadPreviewFrame() {
+    setError(null);
+    setStatus("Extracting preview frame...");
+    try {
+      const bytes = await invoke<number[]>("read_preview_frame", {
+        projectPath,
+        timeSecs: regionAt
+      });
+      const url = URL.createObjectURL(new Blob([new Uint8Array(bytes)], { type: "image/png" }));
+      setPreviewUrl((previous) => {
+        if (previous) {
+          URL.revokeObjectURL(previous);
+        }
+        return url;
+      });
+      setRegion(null);
+      setStatus(`Preview at ${regionAt.toFixed(2)}s`);
+    } catch (previewError) {
+      setError(String(previewError));
+      setStatus("Preview frame failed");
+    }
+  }
+
+  /** Pointer position as a share of the preview frame. */
+  function previewPoint(event: PointerEvent<HTMLDivElement>): [number, number] {
+    const rect = event.currentTarget.getBoundingClientRect();
+    return [
+      clamp((event.clientX - rect.left) / Math.max(rect.width, 1), 0, 1),
+      clamp((event.clientY - rect.top) / Math.max(rect.height, 1), 0, 1)
+    ];
+  }
+
+  function startRegion(event: PointerEvent<HTMLDivElement>) {
+    event.currentTarget.setPointerCapture(event.pointerId);
+    const point = previewPoint(event);
+    setRegionAnchor(point);
+    setRegion({ x: point[0], y: point[1], w: 0, h: 0 });
+  }
+
+  function dragRegion(event: PointerEvent<HTMLDivElement>) {
+    if (!regionAnchor) {
+      return;
+    }
+    const [x, y] = previewPoint(event);
+    setRegion({
+      x: Math.min(x, regionAnchor[0]),
+      y: Math.min(y, regionAnchor[1]),
+      w: Math.abs(x - regionAnchor[0]),
+      h: Math.abs(y - regionAnchor[1])
+    });
+  }
+
+  async function addRegionZoom() {
+    if (!bundle || !region || Math.max(region.w, region.h) < MIN_REGION_SIZE) {
+      return;
+    }
+    setError(null);
+    try {
+      const timeline = await invoke<Timeline>("zoom_to_region", {
+        projectPath,
+        payload: {
+          timeline: bundle.timeline,
+          time_secs: regionAt,
+          region,
+          easing: regionEasing,
+          duration_secs: regionSecs
+        }
+      });
+      timeline.keyframes.sort((a, b) => a.t - b.t);
+      setBundle((prev) => (prev ? { ...prev, timeline } : prev));
+      setRegion(null);
+      setDirty(true);
+      setStatus("Unsaved timeline edits");
+    } catch (zoomError) {
+      setError(String(zoomError));
+      setStatus("Zoom to region failed");
+    }
+  }
+
+  async function lockKeyframeAspect() {
+    if (!bundle) {
+      return;
+    }
+    setError(null);
+    try {
+      const timeline = await invoke<Timeline>("lock_timeline_aspect", {
+        projectPath,
+        payload: { timeline: bundle.timeline }
+      });
+      timeline.keyframes.sort((a, b) => a.t - b.t);
+      setBundle((prev) => (prev ? { ...prev, timeline } : prev));
+      setDirty(true);
+      setStatus("Keyframes reshaped to the export aspect");
+    } catch (lockError) {
+      setError(String(lockError));
+      setStatus("Aspect correction failed");
+    }
+  }
+
+  async function toggleOverscan(enabled: boolean) {
+    if (!bundle) {
+      return;
+    }
+    setError(null);
+    try {
+      const overscan = await invoke<OverscanConfig>("set_overscan", { projectPath, enabled });
+      setBundle((prev) => (prev ? { ...prev, overscan } : prev));
+      setStatus(enabled ? "Export pads the frame for TV overscan" : "Overscan padding off");
+    } catch (overscanError) {
+      setError(String(overscanError));
+      setStatus("Failed to save overscan padding");
+    }
+  }
+
+  function updateKeyframeEasing(index: number, update: (easing: Easing) => Easing) {
+    setBundle((prev) => {
+      if (!prev) {
+        return prev;
+      }
+      const timeline = structuredClone(prev.timeline);
+      timeline.keyframes.sort((a, b) => a.t - b.t);
+      const frame = timeline.keyframes[index];
+      if (!frame) {
+        return prev;
+      }
+      frame.easing = update(frame.easing);
+      return { ...prev, timeline };
+    });
+    setDirty(true);
+    setStatus("Unsaved timeline edits");
+  }
+
+  function setKeyframeEasingKind(index: number, kind: string) {
+    updateKeyframeEasing(index, (easing) => {
+      if (kind === easingKind(easing)) {
+        return easing;
+      }
+      if (kind === "cubic_bezier") {
+        return { cubic_bezier: { ...DEFAULT_BEZIER } };
+      }
+      if (kind === "spring") {
+        return { spring: { ...DEFAULT_SPRING } };
+      }
+      return kind;
+    });
+  }
+
+  function setKeyframeEasingParam(index: number, param: string, value: number) {
+    if (!Number.isFinite(value)) {
+      return;
+    }
+    updateKeyframeEasing(index, (easing) => {
+      if (typeof easing === "string") {
+        return easing;
+      }
+      if ("cubic_bezier" in easing) {
+        return { cubic_bezier: { ...easing.cubic_bezier, [param]: value } };
+      }
+      return { spring: { ...easing.spring, [param]: value } };
+    });
+  }
+
   const hideMouseJitter = Boolean(
     bundle &&
       bundle.timeline.cursor_config.smoothing !== "none" &&
       bundle.timeline.cursor_config.smoothing_factor > 0
   );
+  const startOffset = bundle?.timeline.start_offset_secs ?? null;
+  const endOffset = bundle?.timeline.end_offset_secs ?? null;
+  const speedSegments = bundle?.timeline.speed_segments ?? [];
 
   return (
-    <main className="editor-root">
+    <main className={dropActive ? "editor-root drop-active" : "editor-root"}>
       <header className="editor-header">
         <div>
           <h1>GrabMe Timeline Prototype</h1>
@@ -212,12 +978,21 @@ export default function App(): JSX.Element {
         </label>
 
         <div className="button-group">
-          <button type="button" onClick={loadTimelineBundle}>
+          <button type="button" onClick={pickProjectFolder}>
+            Open...
+          </button>
+          <button type="button" onClick={() => loadTimelineBundle()}>
             Load Timeline
           </button>
           <button type="button" onClick={saveTimelineBundle} disabled={!bundle || !dirty}>
             Save Timeline
           </button>
+          <button type="button" onClick={runAutoDirector} disabled={!bundle || analyzing || dirty}>
+            {analyzing ? "Analyzing..." : "Auto-Direct"}
+          </button>
+          <button type="button" onClick={startExport} disabled={!bundle || exporting || dirty}>
+            {exporting ? "Exporting..." : "Export"}
+          </button>
         </div>
       </section>
 
@@ -227,7 +1002,12 @@ export default function App(): JSX.Element {
             durationSecs={bundle.duration_secs}
             pixelsPerSecond={zoom}
             segments={segments}
+            events={eventOverlay}
+            thumbnails={webcamThumbs}
+            speedMarks={speedSegments.map(speedMark)}
+            comments={reviewComments}
             onSegmentChange={updateSegment}
+            onViewChange={updateEventView}
           />
 
           <section className="inspector-grid">
@@ -236,6 +1016,60 @@ export default function App(): JSX.Element {
               <p>
                 {bundle.name} · {bundle.fps}fps · {bundle.duration_secs.toFixed(1)}s
               </p>
+              <label className="toggle-row">
+                <input
+                  type="checkbox"
+                  checked={startOffset === null}
+                  onChange={(event) => setStartOffset(event.target.checked ? null : 0)}
+                />
+                Trim pre-roll automatically
+              </label>
+              {startOffset === null ? (
+                <small>Export starts after the black frames at the beginning</small>
+              ) : (
+                <label className="easing-param">
+                  start
+                  <input
+                    type="number"
+                    min={0}
+                    step={0.05}
+                    value={startOffset}
+                    onChange={(event) => setStartOffset(Number(event.target.value))}
+                  />
+                  s
+                  <button type="button" onClick={() => void detectStartOffset()}>
+                    Detect
+                  </button>
+                </label>
+              )}
+              <label className="toggle-row">
+                <input
+                  type="checkbox"
+                  checked={endOffset === null}
+                  onChange={(event) =>
+                    setEndOffset(event.target.checked ? null : bundle.duration_secs)
+                  }
+                />
+                Trim idle tail automatically
+              </label>
+              {endOffset === null ? (
+                <small>Export ends after the last speech or input</small>
+              ) : (
+                <label className="easing-param">
+                  end
+                  <input
+                    type="number"
+                    min={0}
+                    step={0.05}
+                    value={endOffset}
+                    onChange={(event) => setEndOffset(Number(event.target.value))}
+                  />
+                  s
+                  <button type="button" onClick={() => void detectEndOffset()}>
+                    Detect
+                  </button>
+                </label>
+              )}
             </article>
 
             <article className="panel">
@@ -252,16 +1086,278 @@ export default function App(): JSX.Element {
                 smoothing={bundle.timeline.cursor_config.smoothing} · factor=
                 {bundle.timeline.cursor_config.smoothing_factor.toFixed(2)}
               </small>
+              <label className="easing-param">
+                draw cursor
+                <select
+                  value={bundle.timeline.cursor_config.overlay ?? "auto"}
+                  onChange={(event) => setCursorOverlay(event.target.value as CursorOverlay)}
+                >
+                  <option value="auto">unless recorded</option>
+                  <option value="show">always</option>
+                  <option value="hide">never</option>
+                </select>
+                <button type="button" onClick={() => void detectRecordedCursor()}>
+                  Check recording
+                </button>
+              </label>
+            </article>
+
+            <article className="panel">
+              <h2>Speed</h2>
+              <label className="easing-param">
+                at
+                <input
+                  type="number"
+                  min={0}
+                  step={0.1}
+                  value={speedAt}
+                  onChange={(event) => setSpeedAt(Number(event.target.value))}
+                />
+                s
+                <button type="button" onClick={() => addSpeedSegment("slow_motion")}>
+                  Slow-mo
+                </button>
+                <button type="button" onClick={() => addSpeedSegment("freeze")}>
+                  Freeze
+                </button>
+              </label>
+              <ul>
+                {speedSegments.map((segment, index) => (
+                  <li key={`speed-${index}`}>
+                    {segment.type === "freeze" ? (
+                      <>
+                        freeze
+                        <label className="easing-param">
+                          at
+                          <input
+                            type="number"
+                            min={0}
+                            step={0.1}
+                            value={segment.at_secs}
+                            onChange={(event) =>
+                              editSpeedSegment(index, { at_secs: Number(event.target.value) })
+                            }
+                          />
+                        </label>
+                        <label className="easing-param">
+                          hold
+                          <input
+                            type="number"
+                            min={0.1}
+                            step={0.1}
+                            value={segment.duration_secs}
+                            onChange={(event) =>
+                              editSpeedSegment(index, {
+                                duration_secs: Number(event.target.value),
+                              })
+                            }
+                          />
+                        </label>
+                      </>
+                    ) : (
+                      <>
+                        slow
+                        <label className="easing-param">
+                          from
+                          <input
+                            type="number"
+                            min={0}
+                            step={0.1}
+                            value={segment.start_secs}
+                            onChange={(event) =>
+                              editSpeedSegment(index, {
+                                start_secs: Number(event.target.value),
+                              })
+                            }
+                          />
+                        </label>
+                        <label className="easing-param">
+                          to
+                          <input
+                            type="number"
+                            min={0}
+                            step={0.1}
+                            value={segment.end_secs}
+                            onChange={(event) =>
+                              editSpeedSegment(index, { end_secs: Number(event.target.value) })
+                            }
+                          />
+                        </label>
+                        <select
+                          value={segment.rate}
+                          onChange={(event) =>
+                            editSpeedSegment(index, { rate: Number(event.target.value) })
+                          }
+                        >
+                          {SLOW_MOTION_RATES.map((rate) => (
+                            <option key={rate} value={rate}>
+                              {rate}x
+                            </option>
+                          ))}
+                        </select>
+                        <select
+                          value={segment.audio}
+                          onChange={(event) =>
+                            editSpeedSegment(index, {
+                              audio: event.target.value as "mute" | "stretch",
+                            })
+                          }
+                        >
+                          <option value="mute">mute</option>
+                          <option value="stretch">stretch</option>
+                        </select>
+                        <label className="easing-param">
+                          <input
+                            type="checkbox"
+                            checked={segment.interpolate}
+                            onChange={(event) =>
+                              editSpeedSegment(index, { interpolate: event.target.checked })
+                            }
+                          />
+                          blend
+                        </label>
+                      </>
+                    )}
+                    <button type="button" onClick={() => removeSpeedSegment(index)}>
+                      Remove
+                    </button>
+                  </li>
+                ))}
+              </ul>
+            </article>
+
+            <article className="panel">
+              <h2>Zoom to Region</h2>
+              <label className="easing-param">
+                at
+                <input
+                  type="number"
+                  min={0}
+                  step={0.1}
+                  value={regionAt}
+                  onChange={(event) => setRegionAt(Number(event.target.value))}
+                />
+                s
+                <button type="button" onClick={() => void loadPreviewFrame()}>
+                  Show frame
+                </button>
+              </label>
+              {previewUrl ? (
+                <div
+                  className="region-preview"
+                  onPointerDown={startRegion}
+                  onPointerMove={dragRegion}
+                  onPointerUp={() => setRegionAnchor(null)}
+                >
+                  <img src={previewUrl} alt="Screen frame" draggable={false} />
+                  {safeArea && bundle ? (
+                    <div
+                      className="safe-area-guide"
+                      style={{ inset: `${Math.min(bundle.overscan.margin_ratio, 0.1) * 100}%` }}
+                    />
+                  ) : null}
+                  {region ? (
+                    <div
+                      className="region-rect"
+                      style={{
+                        left: `${region.x * 100}%`,
+                        top: `${region.y * 100}%`,
+                        width: `${region.w * 100}%`,
+                        height: `${region.h * 100}%`
+                      }}
+                    />
+                  ) : null}
+                </div>
+              ) : (
+                <small>Show a frame, then drag a rectangle to zoom to</small>
+              )}
+              <label className="easing-param">
+                <input
+                  type="checkbox"
+                  checked={safeArea}
+                  onChange={(event) => setSafeArea(event.target.checked)}
+                />
+                Safe area
+                <input
+                  type="checkbox"
+                  checked={bundle?.overscan.enabled ?? false}
+                  disabled={!bundle}
+                  onChange={(event) => void toggleOverscan(event.target.checked)}
+                />
+                Overscan padding
+              </label>
+              <label className="easing-param">
+                over
+                <input
+                  type="number"
+                  min={0}
+                  step={0.1}
+                  value={regionSecs}
+                  onChange={(event) => setRegionSecs(Number(event.target.value))}
+                />
+                s
+                <select
+                  value={regionEasing}
+                  onChange={(event) => setRegionEasing(event.target.value)}
+                >
+                  {NAMED_EASINGS.map((name) => (
+                    <option key={name} value={name}>
+                      {name}
+                    </option>
+                  ))}
+                </select>
+                <button
+                  type="button"
+                  onClick={() => void addRegionZoom()}
+                  disabled={!region || Math.max(region.w, region.h) < MIN_REGION_SIZE}
+                >
+                  Add zoom
+                </button>
+              </label>
             </article>
 
             <article className="panel">
               <h2>Keyframes</h2>
+              <button type="button" onClick={() => void lockKeyframeAspect()}>
+                Fix aspect
+              </button>
               <ul>
                 {keyframes.map((frame, index) => (
                   <li key={`keyframe-${index}`}>
                     t={frame.t.toFixed(2)}s · vp {frame.viewport.x.toFixed(2)},{" "}
                     {frame.viewport.y.toFixed(2)} {frame.viewport.w.toFixed(2)}x
                     {frame.viewport.h.toFixed(2)}
+                    <select
+                      value={easingKind(frame.easing)}
+                      onChange={(event) => setKeyframeEasingKind(index, event.target.value)}
+                    >
+                      {NAMED_EASINGS.map((name) => (
+                        <option key={name} value={name}>
+                          {name}
+                        </option>
+                      ))}
+                      <option value="cubic_bezier">cubic_bezier</option>
+                      <option value="spring">spring</option>
+                    </select>
+                    {typeof frame.easing === "string"
+                      ? null
+                      : Object.entries(
+                          "cubic_bezier" in frame.easing
+                            ? frame.easing.cubic_bezier
+                            : frame.easing.spring
+                        ).map(([param, value]) => (
+                          <label key={param} className="easing-param">
+                            {param}
+                            <input
+                              type="number"
+                              step={0.05}
+                              value={value}
+                              onChange={(event) =>
+                                setKeyframeEasingParam(index, param, Number(event.target.value))
+                              }
+                            />
+                          </label>
+                        ))}
                   </li>
                 ))}
               </ul>
@@ -270,11 +1366,25 @@ export default function App(): JSX.Element {
         </>
       ) : null}
 
+      {exportProgress ? (
+        <progress className="export-progress" max={1} value={exportProgress.progress} />
+      ) : null}
+
       {error ? <p className="error-text">{error}</p> : null}
     </main>
   );
 }
 
+function speedSegmentStart(segment: SpeedSegment): number {
+  return segment.type === "freeze" ? segment.at_secs : segment.start_secs;
+}
+
+function speedMark(segment: SpeedSegment): TimelineSpeedMark {
+  return segment.type === "freeze"
+    ? { start: segment.at_secs, end: segment.at_secs, label: `freeze ${segment.duration_secs}s` }
+    : { start: segment.start_secs, end: segment.end_secs, label: `${segment.rate}x` };
+}
+
 function clamp(value: number, min: number, max: number): number {
   return Math.min(max, Math.max(min, value));
 }
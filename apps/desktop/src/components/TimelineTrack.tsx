@@ -1,4 +1,4 @@
-import { useEffect, useMemo, useState } from "react";
+import { useEffect, useMemo, useRef, useState } from "react";
 
 export type TimelineSegment = {
   id: string;
@@ -6,6 +6,35 @@ export type TimelineSegment = {
   start: number;
   end: number;
   color: string;
+  /** Highlight the segment for review (low Auto-Director confidence). */
+  flagged?: boolean;
+};
+
+export type TimelineEventOverlay = {
+  /** Decimated pointer samples as [time secs, normalized y]. */
+  pointer: Array<[number, number]>;
+  /** Click times in seconds. */
+  clicks: number[];
+};
+
+export type TimelineThumbnail = {
+  /** Screen-timeline position in seconds. */
+  time: number;
+  /** Object URL of the image. */
+  url: string;
+};
+
+/** A slow-motion span or freeze point, in source seconds. */
+export type TimelineSpeedMark = {
+  start: number;
+  end: number;
+  label: string;
+};
+
+/** A review comment pinned to a timeline time in seconds. */
+export type TimelineComment = {
+  time: number;
+  label: string;
 };
 
 type DragAction =
@@ -28,14 +57,32 @@ type TimelineTrackProps = {
   durationSecs: number;
   pixelsPerSecond: number;
   segments: TimelineSegment[];
+  events?: TimelineEventOverlay | null;
+  thumbnails?: TimelineThumbnail[];
+  speedMarks?: TimelineSpeedMark[];
+  comments?: TimelineComment[];
   onSegmentChange: (segmentId: string, start: number, end: number) => void;
+  /** Called with the visible span in seconds when it scrolls, zooms or resizes. */
+  onViewChange?: (startSecs: number, endSecs: number) => void;
 };
 
 const MIN_SEGMENT_DURATION = 0.2;
+const EVENT_LANE_HEIGHT = 28;
 
 export function TimelineTrack(props: TimelineTrackProps): JSX.Element {
-  const { durationSecs, pixelsPerSecond, segments, onSegmentChange } = props;
+  const {
+    durationSecs,
+    pixelsPerSecond,
+    segments,
+    events,
+    thumbnails,
+    speedMarks,
+    comments,
+    onSegmentChange,
+    onViewChange,
+  } = props;
   const [dragAction, setDragAction] = useState<DragAction | null>(null);
+  const scrollRef = useRef<HTMLDivElement>(null);
 
   const totalWidth = useMemo(
     () => Math.max(680, durationSecs * pixelsPerSecond),
@@ -76,6 +123,27 @@ export function TimelineTrack(props: TimelineTrackProps): JSX.Element {
     };
   }, [dragAction, durationSecs, onSegmentChange, pixelsPerSecond]);
 
+  useEffect(() => {
+    const scroller = scrollRef.current;
+    if (!scroller || !onViewChange || pixelsPerSecond <= 0) {
+      return;
+    }
+
+    const report = () => {
+      const start = scroller.scrollLeft / pixelsPerSecond;
+      const end = (scroller.scrollLeft + scroller.clientWidth) / pixelsPerSecond;
+      onViewChange(start, Math.min(end, durationSecs));
+    };
+
+    report();
+    scroller.addEventListener("scroll", report, { passive: true });
+    window.addEventListener("resize", report);
+    return () => {
+      scroller.removeEventListener("scroll", report);
+      window.removeEventListener("resize", report);
+    };
+  }, [durationSecs, onViewChange, pixelsPerSecond]);
+
   return (
     <section className="timeline-shell">
       <header className="timeline-head">
@@ -83,7 +151,7 @@ export function TimelineTrack(props: TimelineTrackProps): JSX.Element {
         <small>{durationSecs.toFixed(1)}s</small>
       </header>
 
-      <div className="timeline-scroll">
+      <div className="timeline-scroll" ref={scrollRef}>
         <div className="timeline-ruler" style={{ width: `${totalWidth}px` }}>
           {buildRulerTicks(durationSecs).map((tick) => (
             <span key={tick} style={{ left: `${tick * pixelsPerSecond}px` }}>
@@ -92,6 +160,68 @@ export function TimelineTrack(props: TimelineTrackProps): JSX.Element {
           ))}
         </div>
 
+        {thumbnails && thumbnails.length > 0 ? (
+          <div className="timeline-thumbs" style={{ width: `${totalWidth}px` }}>
+            {thumbnails.map((thumb) => (
+              <img
+                key={thumb.url}
+                src={thumb.url}
+                alt={`Webcam at ${thumb.time.toFixed(1)}s`}
+                title={`${thumb.time.toFixed(1)}s`}
+                style={{ left: `${thumb.time * pixelsPerSecond}px` }}
+              />
+            ))}
+          </div>
+        ) : null}
+
+        {events ? (
+          <svg className="timeline-events" width={totalWidth} height={EVENT_LANE_HEIGHT}>
+            <polyline
+              points={events.pointer
+                .map(([t, y]) => `${t * pixelsPerSecond},${y * EVENT_LANE_HEIGHT}`)
+                .join(" ")}
+            />
+            {events.clicks.map((t, index) => (
+              <line
+                key={`click-${index}`}
+                x1={t * pixelsPerSecond}
+                x2={t * pixelsPerSecond}
+                y1={0}
+                y2={EVENT_LANE_HEIGHT}
+              />
+            ))}
+          </svg>
+        ) : null}
+
+        {speedMarks && speedMarks.length > 0 ? (
+          <div className="timeline-speed" style={{ width: `${totalWidth}px` }}>
+            {speedMarks.map((mark, index) => (
+              <span
+                key={`speed-${index}`}
+                title={mark.label}
+                style={{
+                  left: `${mark.start * pixelsPerSecond}px`,
+                  width: `${Math.max(4, (mark.end - mark.start) * pixelsPerSecond)}px`,
+                }}
+              >
+                {mark.label}
+              </span>
+            ))}
+          </div>
+        ) : null}
+
+        {comments && comments.length > 0 ? (
+          <div className="timeline-comments" style={{ width: `${totalWidth}px` }}>
+            {comments.map((comment, index) => (
+              <span
+                key={`comment-${index}`}
+                title={`${comment.time.toFixed(1)}s: ${comment.label}`}
+                style={{ left: `${comment.time * pixelsPerSecond}px` }}
+              />
+            ))}
+          </div>
+        ) : null}
+
         <div className="timeline-track" style={{ width: `${totalWidth}px` }}>
           {segments.map((segment) => {
             const left = segment.start * pixelsPerSecond;
@@ -99,7 +229,7 @@ export function TimelineTrack(props: TimelineTrackProps): JSX.Element {
             return (
               <article
                 key={segment.id}
-                className="timeline-segment"
+                className={segment.flagged ? "timeline-segment flagged" : "timeline-segment"}
                 style={{ left: `${left}px`, width: `${width}px`, background: segment.color }}
                 onMouseDown={(event) => {
                   event.preventDefault();
@@ -3,20 +3,33 @@ use std::sync::mpsc::{self, Receiver};
 use std::time::Instant;
 
 use eframe::egui::{self, Color32, Pos2, Rect, Rounding, Sense, Stroke, Vec2};
+use grabme_capture_engine::suspend::{next_segment_name, SuspendDetector, SUSPEND_WAIT};
 use grabme_capture_engine::{
-    AudioCaptureConfig, CaptureMode, CaptureSession, ScreenCaptureConfig, SessionConfig,
+    split_monitors, AudioCaptureConfig, CaptureMode, CaptureSession, LockResponse,
+    ScreenCaptureConfig, SessionConfig, WebcamFormatRequest,
 };
-use grabme_platform_linux::{detect_monitors, MonitorInfo};
-use grabme_processing_core::auto_zoom::{AutoZoomAnalyzer, AutoZoomConfig};
-use grabme_project_model::event::{
-    parse_events, EventKind, EventStreamHeader, InputEvent, PointerCoordinateSpace,
+use grabme_common::clock::ClockSource;
+use grabme_common::config::{
+    library_index_path, log_file_path, AppConfig, OverlayParking, RecordingDefaults, ResumeAction,
+    ScreenCorner,
 };
-use grabme_project_model::project::{
-    AspectMode, ExportConfig, ExportFormat, LoadedProject, RecordingConfig,
+use grabme_common::error::GrabmeError;
+use grabme_common::logging::session_log_path;
+use grabme_common::memory::MemoryProfile;
+use grabme_common::update::check_for_update;
+use grabme_platform_linux::{detect_monitors, DisplayTarget, MonitorInfo};
+use grabme_processing_core::auto_zoom::{
+    AutoZoomAnalyzer, AutoZoomConfig, DisplayMetrics, DwellUnit,
 };
+use grabme_project_model::event::{parse_events, parse_events_header};
+use grabme_project_model::library::{LibraryEntry, ProjectLibrary};
+use grabme_project_model::project::{AspectMode, ExportConfig, ExportFormat, LoadedProject};
+use grabme_project_model::projection::project_events_to_capture_space;
 use grabme_project_model::timeline::{CameraKeyframe, EasingFunction, KeyframeSource};
 use grabme_project_model::viewport::Viewport;
+use grabme_render_engine::draft::DraftOptions;
 use grabme_render_engine::export::{export_project, ExportJob, ExportProgress};
+use grabme_render_engine::resources::ExportResources;
 
 mod webcam_preview;
 use webcam_preview::WebcamPreview;
@@ -29,12 +42,17 @@ use webcam_preview::WebcamPreview;
 
 const BUBBLE_HEIGHT: f32 = 36.0;
 const BUBBLE_EXPANDED_HEIGHT: f32 = 332.0;
+const BUBBLE_ERROR_HEIGHT: f32 = 200.0;
 const BUBBLE_WIDTH_IDLE: f32 = 320.0;
 const BUBBLE_WIDTH_RECORDING: f32 = 138.0;
-const BUBBLE_WIDTH_POST: f32 = 320.0;
+const BUBBLE_WIDTH_POST: f32 = 400.0;
 const CIRCLE_RADIUS: f32 = 10.0;
 const PADDING: f32 = 6.0;
 const DROPDOWN_MAX_HEIGHT: f32 = 220.0;
+/// Holding Render this long renders a draft preview instead.
+const LONG_PRESS_SECS: f64 = 0.6;
+const HISTORY_ROW_HEIGHT: f32 = 28.0;
+const HISTORY_MAX_ROWS: usize = 9;
 
 const RED_IDLE: Color32 = Color32::from_rgb(200, 52, 52);
 const RED_RECORDING: Color32 = Color32::from_rgb(255, 60, 60);
@@ -99,6 +117,15 @@ impl Stage {
     }
 }
 
+// ── Project history actions ──────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HistoryAction {
+    Open,
+    Analyze,
+    Export,
+}
+
 // ── Render messages (background thread -> UI) ────────────────────────────────
 
 #[derive(Debug)]
@@ -116,9 +143,51 @@ enum RenderMessage {
     },
     Failed {
         error: String,
+        hint: Option<&'static str>,
     },
 }
 
+// ── Errors ───────────────────────────────────────────────────────────────────
+
+/// The action behind an error, so the error panel can retry it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FailedAction {
+    Record,
+    Stop,
+    AutoDirect,
+    Render,
+    DraftRender,
+    QuickExport,
+}
+
+impl FailedAction {
+    fn label(self) -> &'static str {
+        match self {
+            FailedAction::Record => "Recording failed",
+            FailedAction::Stop => "Stop failed",
+            FailedAction::AutoDirect => "Auto-Direct failed",
+            FailedAction::Render => "Render failed",
+            FailedAction::DraftRender => "Draft render failed",
+            FailedAction::QuickExport => "Quick export failed",
+        }
+    }
+
+    /// Stopping hands the session to the stop task, so there is nothing
+    /// left to stop again.
+    fn can_retry(self) -> bool {
+        self != FailedAction::Stop
+    }
+}
+
+/// The last failure, shown in full in the expandable error panel.
+struct OverlayError {
+    action: FailedAction,
+    message: String,
+    hint: Option<&'static str>,
+    /// Capability check results, once the user has run it from the panel.
+    check: Option<Vec<String>>,
+}
+
 // ── Application state ────────────────────────────────────────────────────────
 
 struct OverlayApp {
@@ -126,8 +195,15 @@ struct OverlayApp {
 
     // Recording
     session: Option<CaptureSession>,
-    start_task: Option<tokio::task::JoinHandle<Result<CaptureSession, String>>>,
-    stop_task: Option<tokio::task::JoinHandle<Result<PathBuf, String>>>,
+    start_task: Option<tokio::task::JoinHandle<Result<CaptureSession, GrabmeError>>>,
+    stop_task: Option<tokio::task::JoinHandle<Result<PathBuf, GrabmeError>>>,
+    quick_export_after_stop: bool,
+    /// Set when a suspend stopped the recording; acted on once it's saved.
+    suspended: Option<(LockResponse, SuspendDetector)>,
+    /// Saved before a suspend; waiting for the system to sleep and wake.
+    wake_wait: Option<(Instant, SuspendDetector)>,
+    /// Asking whether to record a new segment after a suspend.
+    resume_prompt: bool,
     stage: Stage,
     prev_stage: Stage, // track transitions to avoid per-frame resizes
 
@@ -139,6 +215,8 @@ struct OverlayApp {
     system_audio: bool,
     webcam: bool,
     webcam_preview_enabled: bool,
+    recording_defaults: RecordingDefaults,
+    memory: MemoryProfile,
 
     // Dropdowns
     countdown_preset: CountdownPreset,
@@ -152,15 +230,28 @@ struct OverlayApp {
     status: String,
     active_project_path: Option<PathBuf>,
     last_export_path: Option<PathBuf>,
+    error: Option<OverlayError>,
+    error_open: bool,
 
     // Render progress
     render_receiver: Option<Receiver<RenderMessage>>,
+    render_action: FailedAction,
     render_percent: f64,
     render_eta_secs: f64,
+    /// When the Render button went down, and whether the hold already
+    /// started a draft.
+    render_press: Option<(Instant, bool)>,
 
     // Optional external live webcam preview process.
     webcam_preview: WebcamPreview,
 
+    // Opt-in update check; yields the newer version, if any.
+    update_receiver: Option<Receiver<String>>,
+
+    // Recent projects (persisted library index)
+    library: ProjectLibrary,
+    history_open: bool,
+
     // Window behavior
     centered_once: bool,
     menus_open: bool,
@@ -169,6 +260,8 @@ struct OverlayApp {
     recording_monitor_index: Option<usize>,
     relocated_for_recording: bool,
     pre_record_outer_pos: Option<Pos2>,
+    parking: Option<OverlayParking>,
+    parked_outer_pos: Option<Pos2>,
 }
 
 impl Default for OverlayApp {
@@ -179,7 +272,11 @@ impl Default for OverlayApp {
             .build()
             .expect("tokio runtime should initialize");
 
-        let monitors = detect_monitors().unwrap_or_default();
+        let config = AppConfig::load();
+        let monitors = split_monitors(
+            &detect_monitors().unwrap_or_default(),
+            &config.recording.monitor_splits,
+        );
         let monitor_count = monitors.len();
 
         Self {
@@ -187,15 +284,21 @@ impl Default for OverlayApp {
             session: None,
             start_task: None,
             stop_task: None,
+            quick_export_after_stop: false,
+            suspended: None,
+            wake_wait: None,
+            resume_prompt: false,
             stage: Stage::Idle,
             prev_stage: Stage::Idle,
             project_name: "recording".to_string(),
-            output_dir: ".".to_string(),
-            fps: 60,
+            output_dir: config.projects_dir.display().to_string(),
+            fps: config.recording.fps,
             mic: true,
             system_audio: true,
             webcam: false,
             webcam_preview_enabled: false,
+            recording_defaults: config.recording.clone(),
+            memory: config.memory.profile(),
             countdown_preset: CountdownPreset::None,
             monitors,
             selected_monitor: 0.min(monitor_count.saturating_sub(1)),
@@ -203,10 +306,17 @@ impl Default for OverlayApp {
             status: String::new(),
             active_project_path: None,
             last_export_path: None,
+            error: None,
+            error_open: false,
             render_receiver: None,
+            render_action: FailedAction::Render,
             render_percent: 0.0,
             render_eta_secs: 0.0,
+            render_press: None,
             webcam_preview: WebcamPreview::new(),
+            update_receiver: spawn_update_check(&config),
+            library: load_project_library(),
+            history_open: false,
             centered_once: false,
             menus_open: false,
             prev_window_size: Vec2::new(BUBBLE_WIDTH_IDLE, BUBBLE_HEIGHT),
@@ -214,12 +324,43 @@ impl Default for OverlayApp {
             recording_monitor_index: None,
             relocated_for_recording: false,
             pre_record_outer_pos: None,
+            parking: config.overlay.parking,
+            parked_outer_pos: None,
         }
     }
 }
 
+/// Look for a newer release in the background when the config opts in.
+fn spawn_update_check(config: &AppConfig) -> Option<Receiver<String>> {
+    if !config.updates.check_on_start || config.network.offline {
+        return None;
+    }
+    let (tx, rx) = mpsc::channel();
+    let config = config.clone();
+    // A failed check is not worth interrupting anyone for.
+    std::thread::spawn(move || {
+        if let Ok(Some(release)) = check_for_update(&config) {
+            let _ = tx.send(release.version);
+        }
+    });
+    Some(rx)
+}
+
 // ── Recording logic ──────────────────────────────────────────────────────────
 
+/// Hotkeys for `recording.reactions`, in order.
+const REACTION_KEYS: [egui::Key; 9] = [
+    egui::Key::Num1,
+    egui::Key::Num2,
+    egui::Key::Num3,
+    egui::Key::Num4,
+    egui::Key::Num5,
+    egui::Key::Num6,
+    egui::Key::Num7,
+    egui::Key::Num8,
+    egui::Key::Num9,
+];
+
 impl OverlayApp {
     fn build_session_config(&self) -> SessionConfig {
         SessionConfig {
@@ -230,6 +371,8 @@ impl OverlayApp {
                     monitor_index: self.selected_monitor,
                 },
                 hide_cursor: true,
+                cursor_mode: None,
+                monitor_splits: self.recording_defaults.monitor_splits.clone(),
             },
             audio: AudioCaptureConfig {
                 mic: self.mic,
@@ -238,12 +381,32 @@ impl OverlayApp {
                 sample_rate: 48_000,
             },
             webcam: self.webcam,
+            webcam_format: WebcamFormatRequest::from_defaults(&self.recording_defaults),
             fps: self.fps,
             pointer_sample_rate_hz: 60,
+            clock_source: ClockSource::default(),
+            display: DisplayTarget {
+                server: AppConfig::load()
+                    .recording
+                    .display_server
+                    .as_deref()
+                    .and_then(|name| name.parse().ok()),
+                x11_display: None,
+            },
+            auto_adjust_fps: false,
+            gamepad: false,
+            pip: None,
+            frame_times: false,
+            system_metrics: false,
+            on_lock: self.recording_defaults.on_lock,
+            virtual_camera: None,
+            spool_dir: self.recording_defaults.spool_dir.clone(),
+            memory: self.memory,
         }
     }
 
     fn initiate_recording(&mut self) {
+        self.clear_error();
         if self.project_name.trim().is_empty() {
             self.status = "Project name required".to_string();
             return;
@@ -263,14 +426,20 @@ impl OverlayApp {
             return;
         }
 
+        let config = self.build_session_config();
+        if let Err(err) = std::fs::create_dir_all(&config.output_dir) {
+            self.status = format!("Output folder unavailable: {err}");
+            self.stage = Stage::Idle;
+            return;
+        }
+
         self.status = "Starting...".to_string();
         self.stage = Stage::Starting;
 
-        let config = self.build_session_config();
         let handle = self.runtime.handle().clone();
         self.start_task = Some(handle.spawn(async move {
             let mut session = CaptureSession::new(config);
-            session.start().await.map_err(|e| e.to_string())?;
+            session.start().await?;
             Ok(session)
         }));
     }
@@ -286,15 +455,37 @@ impl OverlayApp {
             return;
         };
 
+        self.clear_error();
         self.status = "Stopping...".to_string();
         self.stage = Stage::Stopping;
 
         let handle = self.runtime.handle().clone();
-        self.stop_task =
-            Some(handle.spawn(async move { session.stop().await.map_err(|e| e.to_string()) }));
+        self.stop_task = Some(handle.spawn(async move { session.stop().await }));
     }
 
     fn poll_session_tasks(&mut self) {
+        let lock_response = self
+            .session
+            .as_mut()
+            .and_then(CaptureSession::poll_system_events);
+        match lock_response {
+            Some(LockResponse::Stop) => self.stop_recording(),
+            Some(LockResponse::Pause) => self.status = "Paused (screen locked)".to_string(),
+            Some(LockResponse::Resume) => self.status = String::new(),
+            Some(response @ (LockResponse::Suspend | LockResponse::Slept)) => {
+                self.suspended = Some((response, SuspendDetector::new()));
+                self.stop_recording();
+            }
+            None => {}
+        }
+        let woke = self.wake_wait.as_mut().is_some_and(|(since, detector)| {
+            detector.check().is_some() || since.elapsed() >= SUSPEND_WAIT
+        });
+        if woke {
+            self.wake_wait = None;
+            self.after_wake();
+        }
+
         let start_finished = self
             .start_task
             .as_ref()
@@ -326,15 +517,15 @@ impl OverlayApp {
                 }
                 Ok(Err(err)) => {
                     self.webcam_preview.stop();
-                    self.status = format!("Failed: {err}");
                     self.stage = Stage::Idle;
                     self.recording_monitor_index = None;
+                    self.report_failure(FailedAction::Record, err.to_string(), err.hint());
                 }
                 Err(err) => {
                     self.webcam_preview.stop();
-                    self.status = format!("Failed: {err}");
                     self.stage = Stage::Idle;
                     self.recording_monitor_index = None;
+                    self.report_failure(FailedAction::Record, err.to_string(), None);
                 }
             }
         }
@@ -349,39 +540,81 @@ impl OverlayApp {
             match self.runtime.block_on(task) {
                 Ok(Ok(path)) => {
                     self.webcam_preview.stop();
+                    self.remember_project(&path);
                     self.active_project_path = Some(path);
                     self.stage = Stage::PostRecord;
                     self.status = "Stopped".to_string();
                     self.recording_monitor_index = None;
+                    if std::mem::take(&mut self.quick_export_after_stop) {
+                        self.start_quick_export();
+                    }
+                    match self.suspended.take() {
+                        Some((LockResponse::Suspend, detector)) => {
+                            self.status = "Saved before suspend".to_string();
+                            self.wake_wait = Some((Instant::now(), detector));
+                        }
+                        Some(_) => self.after_wake(),
+                        None => {}
+                    }
                 }
                 Ok(Err(err)) => {
                     self.webcam_preview.stop();
-                    self.status = format!("Stop failed: {err}");
+                    self.quick_export_after_stop = false;
+                    self.suspended = None;
                     self.stage = Stage::Idle;
                     self.recording_monitor_index = None;
+                    self.report_failure(FailedAction::Stop, err.to_string(), err.hint());
                 }
                 Err(err) => {
                     self.webcam_preview.stop();
-                    self.status = format!("Stop failed: {err}");
+                    self.quick_export_after_stop = false;
+                    self.suspended = None;
                     self.stage = Stage::Idle;
                     self.recording_monitor_index = None;
+                    self.report_failure(FailedAction::Stop, err.to_string(), None);
                 }
             }
         }
     }
 
+    /// The system is awake after a suspend saved the recording.
+    fn after_wake(&mut self) {
+        match self.recording_defaults.on_resume {
+            ResumeAction::Auto => self.resume_recording(),
+            ResumeAction::Prompt => {
+                self.resume_prompt = true;
+                self.status = "Resume recording?".to_string();
+            }
+            ResumeAction::Off => self.status = "Stopped for suspend".to_string(),
+        }
+    }
+
+    /// Record the next segment after a suspend, under a new project name.
+    fn resume_recording(&mut self) {
+        self.resume_prompt = false;
+        self.project_name = next_segment_name(Path::new(&self.output_dir), &self.project_name);
+        self.start_recording_now();
+    }
+
     fn run_auto_direct(&mut self) {
+        self.clear_error();
         let Some(project_path) = self.active_project_path.as_ref() else {
             self.status = "No project".to_string();
             return;
         };
         match auto_direct_project(project_path) {
             Ok(n) => self.status = format!("{n} keyframes"),
-            Err(err) => self.status = format!("Auto-Direct failed: {err}"),
+            Err(err) => {
+                let hint = failure_hint(&err);
+                self.report_failure(FailedAction::AutoDirect, format!("{err:#}"), hint);
+            }
         }
     }
 
-    fn start_render(&mut self) {
+    /// Auto-Direct, then export. A draft is a fast low-resolution preview
+    /// with a timecode and DRAFT watermark, for review.
+    fn start_render(&mut self, draft: bool) {
+        self.clear_error();
         let Some(project_path) = self.active_project_path.clone() else {
             return;
         };
@@ -389,17 +622,70 @@ impl OverlayApp {
         let auto_keyframes = match auto_direct_project(&project_path) {
             Ok(count) => count,
             Err(err) => {
-                self.status = format!("Render blocked: Auto-Direct failed ({err})");
+                let hint = failure_hint(&err);
+                let message = format!("Auto-Direct failed: {err:#}");
+                let action = if draft {
+                    FailedAction::DraftRender
+                } else {
+                    FailedAction::Render
+                };
+                self.report_failure(action, message, hint);
                 return;
             }
         };
 
+        self.status = if draft {
+            format!("Rendering draft... ({auto_keyframes} keyframes)")
+        } else {
+            format!("Rendering... ({auto_keyframes} keyframes)")
+        };
+        self.spawn_export(project_path, false, draft);
+    }
+
+    /// Export the raw recording immediately: no Auto-Direct, no compositing.
+    fn start_quick_export(&mut self) {
+        self.clear_error();
+        let Some(project_path) = self.active_project_path.clone() else {
+            return;
+        };
+        self.status = "Quick export...".to_string();
+        self.spawn_export(project_path, true, false);
+    }
+
+    /// Stop the running recording and quick-export it as soon as it is saved.
+    fn stop_and_quick_export(&mut self) {
+        if self.session.is_none() {
+            return;
+        }
+        self.quick_export_after_stop = true;
+        self.stop_recording();
+    }
+
+    /// Drop a reaction marker into the running recording.
+    fn add_reaction(&mut self, emoji: &str) {
+        let Some(session) = self.session.as_ref() else {
+            return;
+        };
+        self.status = match session.add_reaction(emoji) {
+            Ok(()) => format!("Reaction {emoji}"),
+            Err(err) => format!("Reaction failed: {err}"),
+        };
+    }
+
+    fn spawn_export(&mut self, project_path: PathBuf, fast_copy: bool, draft: bool) {
         let (tx, rx) = mpsc::channel::<RenderMessage>();
         self.render_receiver = Some(rx);
         self.render_percent = 0.0;
         self.render_eta_secs = 0.0;
+        self.render_action = if fast_copy {
+            FailedAction::QuickExport
+        } else if draft {
+            FailedAction::DraftRender
+        } else {
+            FailedAction::Render
+        };
         self.stage = Stage::Rendering;
-        self.status = format!("Rendering... ({auto_keyframes} keyframes)");
+        let memory = self.memory;
 
         std::thread::spawn(move || {
             let runtime = match tokio::runtime::Builder::new_current_thread()
@@ -410,6 +696,7 @@ impl OverlayApp {
                 Err(err) => {
                     let _ = tx.send(RenderMessage::Failed {
                         error: format!("Runtime failed: {err}"),
+                        hint: None,
                     });
                     return;
                 }
@@ -419,18 +706,39 @@ impl OverlayApp {
                 let loaded = LoadedProject::load(&project_path)
                     .map_err(|e| anyhow::anyhow!("Load failed: {e}"))?;
 
-                let output_path = project_path.join("exports").join("output.mp4");
+                let file_name = if fast_copy {
+                    "clip.mp4"
+                } else if draft {
+                    "draft.mp4"
+                } else {
+                    "output.mp4"
+                };
+                let output_path = project_path.join("exports").join(file_name);
+                let (width, height) = loaded
+                    .project
+                    .recording
+                    .default_export_size(ExportFormat::Mp4H264);
                 let config = ExportConfig {
                     format: ExportFormat::Mp4H264,
-                    width: loaded.project.export.width,
-                    height: loaded.project.export.height,
+                    width,
+                    height,
                     fps: loaded.project.recording.fps,
                     video_bitrate_kbps: loaded.project.export.video_bitrate_kbps,
                     audio_bitrate_kbps: loaded.project.export.audio_bitrate_kbps,
                     aspect_mode: AspectMode::Landscape,
                     burn_subtitles: loaded.project.export.burn_subtitles,
+                    subtitle_style: loaded.project.export.subtitle_style.clone(),
                     webcam: loaded.project.export.webcam.clone(),
                     canvas: loaded.project.export.canvas.clone(),
+                    strokes: loaded.project.export.strokes.clone(),
+                    reactions: loaded.project.export.reactions.clone(),
+                    gamepad: loaded.project.export.gamepad.clone(),
+                    metrics: loaded.project.export.metrics.clone(),
+                    clock: loaded.project.export.clock.clone(),
+                    pip: loaded.project.export.pip.clone(),
+                    waveform: loaded.project.export.waveform.clone(),
+                    metadata: loaded.project.export.metadata.clone(),
+                    overscan: loaded.project.export.overscan.clone(),
                 };
 
                 let tx_progress = tx.clone();
@@ -449,11 +757,22 @@ impl OverlayApp {
                     config,
                     start_secs: None,
                     end_secs: None,
+                    trim_preroll: AppConfig::load().recording.trim_preroll,
+                    trim_tail: AppConfig::load().recording.trim_tail,
+                    debug_overlay: false,
+                    resources: ExportResources::background().with_memory(&memory),
+                    fast_copy,
+                    draft: None,
+                };
+                let job = if draft {
+                    job.into_draft(DraftOptions { watermark: true })
+                } else {
+                    job
                 };
 
                 export_project(job, Some(progress_cb))
                     .await
-                    .map_err(|e| anyhow::anyhow!("Render failed: {e}"))
+                    .map_err(anyhow::Error::from)
             });
 
             match result {
@@ -462,13 +781,68 @@ impl OverlayApp {
                 }
                 Err(err) => {
                     let _ = tx.send(RenderMessage::Failed {
-                        error: err.to_string(),
+                        error: format!("{err:#}"),
+                        hint: failure_hint(&err),
                     });
                 }
             }
         });
     }
 
+    fn remember_project(&mut self, project_path: &Path) {
+        let path = std::fs::canonicalize(project_path).unwrap_or_else(|_| project_path.into());
+        let name = LoadedProject::load(&path)
+            .map(|loaded| loaded.project.name)
+            .unwrap_or_else(|_| {
+                path.file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| path.display().to_string())
+            });
+
+        self.library.touch(name, &path);
+        if let Err(err) = self.library.save(library_index_path()) {
+            self.status = format!("History not saved: {err}");
+        }
+    }
+
+    fn apply_history_action(&mut self, entry: &LibraryEntry, action: HistoryAction) {
+        if !entry.path.join("meta").join("project.json").exists() {
+            self.library.prune_missing();
+            let _ = self.library.save(library_index_path());
+            self.status = format!("{} no longer exists", entry.name);
+            return;
+        }
+
+        self.history_open = false;
+        self.remember_project(&entry.path);
+        self.active_project_path = Some(entry.path.clone());
+        self.last_export_path = None;
+        self.stage = Stage::PostRecord;
+
+        match action {
+            HistoryAction::Open => self.status = format!("Opened {}", entry.name),
+            HistoryAction::Analyze => self.run_auto_direct(),
+            HistoryAction::Export => self.start_render(false),
+        }
+    }
+
+    fn poll_update_check(&mut self) {
+        let Some(receiver) = self.update_receiver.as_ref() else {
+            return;
+        };
+        match receiver.try_recv() {
+            Ok(version) => {
+                self.update_receiver = None;
+                if self.status.is_empty() {
+                    self.status =
+                        format!("GrabMe {version} is available; run `grabme self-update`");
+                }
+            }
+            Err(mpsc::TryRecvError::Empty) => {}
+            Err(mpsc::TryRecvError::Disconnected) => self.update_receiver = None,
+        }
+    }
+
     fn poll_render_messages(&mut self) {
         let Some(receiver) = self.render_receiver.as_ref() else {
             return;
@@ -489,10 +863,10 @@ impl OverlayApp {
                     self.render_receiver = None;
                     break;
                 }
-                Ok(RenderMessage::Failed { error }) => {
+                Ok(RenderMessage::Failed { error, hint }) => {
                     self.stage = Stage::PostRecord;
-                    self.status = error;
                     self.render_receiver = None;
+                    self.report_failure(self.render_action, error, hint);
                     break;
                 }
                 Err(mpsc::TryRecvError::Empty) => break,
@@ -506,6 +880,96 @@ impl OverlayApp {
         }
     }
 
+    /// Record a failure: a one-line status in the bubble, the full message
+    /// and hint in the error panel. The idle bubble has no status line, so
+    /// the panel opens right away there.
+    fn report_failure(
+        &mut self,
+        action: FailedAction,
+        message: String,
+        hint: Option<&'static str>,
+    ) {
+        self.status = format!("{}: {message}", action.label());
+        self.error_open = self.stage == Stage::Idle;
+        self.error = Some(OverlayError {
+            action,
+            message,
+            hint,
+            check: None,
+        });
+    }
+
+    fn clear_error(&mut self) {
+        self.error = None;
+        self.error_open = false;
+    }
+
+    fn error_panel_open(&self) -> bool {
+        self.error_open
+            && self.error.is_some()
+            && matches!(self.stage, Stage::Idle | Stage::PostRecord)
+    }
+
+    fn retry_failed_action(&mut self) {
+        let Some(action) = self.error.as_ref().map(|error| error.action) else {
+            return;
+        };
+        match action {
+            FailedAction::Record => self.initiate_recording(),
+            FailedAction::AutoDirect => self.run_auto_direct(),
+            FailedAction::Render => self.start_render(false),
+            FailedAction::DraftRender => self.start_render(true),
+            FailedAction::QuickExport => self.start_quick_export(),
+            FailedAction::Stop => {}
+        }
+    }
+
+    /// Open the project's session log, or the application log when there
+    /// is no project or the recording never got far enough to write one.
+    fn open_logs(&mut self) {
+        let path = self
+            .active_project_path
+            .as_deref()
+            .map(session_log_path)
+            .filter(|path| path.is_file())
+            .unwrap_or_else(|| AppConfig::load().logging.file.unwrap_or_else(log_file_path));
+        if !path.is_file() {
+            self.status = format!("No log at {}", path.display());
+            return;
+        }
+        if let Err(err) = std::process::Command::new("xdg-open").arg(&path).spawn() {
+            self.status = format!("Could not open {}: {err}", path.display());
+        }
+    }
+
+    /// Run the same capability check as `grabme check` and list what is
+    /// missing in the error panel.
+    fn run_capability_check(&mut self) {
+        let capabilities = grabme_platform_linux::permissions::check_capabilities();
+        let mut lines: Vec<String> = capabilities
+            .iter()
+            .filter(|capability| !capability.available)
+            .map(|capability| {
+                let fix = capability
+                    .fix_instructions
+                    .as_deref()
+                    .unwrap_or(&capability.description);
+                let kind = if capability.required {
+                    "Missing"
+                } else {
+                    "Optional"
+                };
+                format!("{kind}: {} - {fix}", capability.name)
+            })
+            .collect();
+        if lines.is_empty() {
+            lines.push("All capabilities are available.".to_string());
+        }
+        if let Some(error) = self.error.as_mut() {
+            error.check = Some(lines);
+        }
+    }
+
     fn tick_countdown(&mut self) {
         if self.stage != Stage::Countdown {
             return;
@@ -614,18 +1078,22 @@ impl OverlayApp {
             return;
         };
 
-        if self.monitors.len() <= 1 {
-            return;
-        }
-
-        let Some((target_idx, target_monitor)) = self
-            .monitors
-            .iter()
-            .enumerate()
-            .find(|(idx, _)| *idx != recording_monitor)
-        else {
+        // A saved parking spot wins, even on the recorded monitor; otherwise
+        // move off the recorded monitor when there is another one.
+        let parked = self.parking.as_ref().and_then(|parking| {
+            self.monitors
+                .iter()
+                .position(|m| m.name == parking.monitor)
+                .map(|idx| (idx, parking.corner))
+        });
+        let Some((target_idx, corner)) = parked.or_else(|| {
+            (0..self.monitors.len())
+                .find(|idx| *idx != recording_monitor)
+                .map(|idx| (idx, ScreenCorner::TopLeft))
+        }) else {
             return;
         };
+        let target_monitor = &self.monitors[target_idx];
 
         let current_pos = ctx.input(|i| i.viewport().outer_rect.map(|r| r.left_top()));
         if self.pre_record_outer_pos.is_none() {
@@ -634,20 +1102,57 @@ impl OverlayApp {
 
         let target_size = self.target_window_size();
         let margin = 20.0;
+        let min_x = target_monitor.x as f32 + margin;
+        let min_y = target_monitor.y as f32 + margin;
         let max_x =
             (target_monitor.x as f32 + target_monitor.width as f32 - target_size.x - margin)
-                .max(target_monitor.x as f32 + margin);
+                .max(min_x);
         let max_y =
             (target_monitor.y as f32 + target_monitor.height as f32 - target_size.y - margin)
-                .max(target_monitor.y as f32 + margin);
+                .max(min_y);
         let pos = Pos2::new(
-            (target_monitor.x as f32 + margin).min(max_x),
-            (target_monitor.y as f32 + margin).min(max_y),
+            if corner.is_right() { max_x } else { min_x },
+            if corner.is_bottom() { max_y } else { min_y },
         );
         ctx.send_viewport_cmd(egui::ViewportCommand::OuterPosition(pos));
 
         self.last_window_monitor = Some(target_idx);
         self.relocated_for_recording = true;
+        self.parked_outer_pos = Some(pos);
+    }
+
+    /// If the overlay was dragged away from its parking spot during the
+    /// recording, remember the new monitor and nearest corner.
+    fn save_parking_from_drag(&mut self, ctx: &egui::Context) {
+        let Some(parked) = self.parked_outer_pos.take() else {
+            return;
+        };
+        let Some(window_rect) = ctx.input(|i| i.viewport().outer_rect) else {
+            return;
+        };
+        if window_rect.left_top().distance(parked) < 4.0 {
+            return;
+        }
+        let Some(monitor) = self
+            .monitor_index_for_point(window_rect.center())
+            .and_then(|idx| self.monitors.get(idx))
+        else {
+            return;
+        };
+
+        let parking = OverlayParking {
+            monitor: monitor.name.clone(),
+            corner: ScreenCorner::nearest(
+                (window_rect.center().x as f64 - monitor.x as f64) / monitor.width.max(1) as f64,
+                (window_rect.center().y as f64 - monitor.y as f64) / monitor.height.max(1) as f64,
+            ),
+        };
+        let mut config = AppConfig::load();
+        config.overlay.parking = Some(parking.clone());
+        if let Err(err) = config.save() {
+            self.status = format!("Parking not saved: {err}");
+        }
+        self.parking = Some(parking);
     }
 
     fn maybe_restore_overlay_after_recording(&mut self, ctx: &egui::Context) {
@@ -655,6 +1160,8 @@ impl OverlayApp {
             return;
         }
 
+        self.save_parking_from_drag(ctx);
+
         if let Some(pos) = self.pre_record_outer_pos.take() {
             ctx.send_viewport_cmd(egui::ViewportCommand::OuterPosition(pos));
         }
@@ -663,8 +1170,10 @@ impl OverlayApp {
     }
 
     fn target_window_size(&self) -> Vec2 {
-        let height = if self.stage == Stage::Idle && self.menus_open {
+        let height = if self.stage == Stage::Idle && (self.menus_open || self.history_open) {
             BUBBLE_EXPANDED_HEIGHT
+        } else if self.error_panel_open() {
+            BUBBLE_ERROR_HEIGHT
         } else {
             BUBBLE_HEIGHT
         };
@@ -709,11 +1218,12 @@ impl OverlayApp {
             return;
         }
 
-        let only_idle_menu_toggle = self.stage == Stage::Idle
-            && self.prev_stage == Stage::Idle
+        // Menus, history and the error panel grow the window downwards.
+        let only_panel_toggle = self.stage == self.prev_stage
+            && matches!(self.stage, Stage::Idle | Stage::PostRecord)
             && (target_size.x - self.prev_window_size.x).abs() <= 0.1;
 
-        if only_idle_menu_toggle {
+        if only_panel_toggle {
             self.resize_preserving_top_left(ctx, target_size);
         } else {
             self.resize_preserving_center(ctx, target_size);
@@ -736,6 +1246,26 @@ impl eframe::App for OverlayApp {
         self.tick_countdown();
         self.poll_session_tasks();
         self.poll_render_messages();
+        self.poll_update_check();
+
+        // `Q` = instant clip: stop (if recording) and stream-copy export.
+        if ctx.input(|i| i.key_pressed(egui::Key::Q)) {
+            match self.stage {
+                Stage::Recording => self.stop_and_quick_export(),
+                Stage::PostRecord => self.start_quick_export(),
+                _ => {}
+            }
+        }
+        // `1`-`9` drop the matching reaction at the pointer.
+        if self.stage == Stage::Recording {
+            let pressed = ctx.input(|i| REACTION_KEYS.iter().position(|k| i.key_pressed(*k)));
+            if let Some(emoji) = pressed
+                .and_then(|idx| self.recording_defaults.reaction_for_hotkey(idx + 1))
+                .map(str::to_string)
+            {
+                self.add_reaction(&emoji);
+            }
+        }
         let preview_running = self.webcam_preview.is_running();
         if self.stage == Stage::Recording && self.webcam_preview_enabled && !preview_running {
             self.webcam_preview_enabled = false;
@@ -760,7 +1290,9 @@ impl eframe::App for OverlayApp {
         }
 
         // ── Draw ─────────────────────────────────────────────────────────
-        let expanded_background = self.stage == Stage::Idle && self.menus_open;
+        let expanded_background = (self.stage == Stage::Idle
+            && (self.menus_open || self.history_open))
+            || self.error_panel_open();
         self.menus_open = false;
         egui::CentralPanel::default()
             .frame(egui::Frame::none())
@@ -795,13 +1327,29 @@ impl eframe::App for OverlayApp {
                     ctx.send_viewport_cmd(egui::ViewportCommand::StartDrag);
                 }
 
+                let panel_rect = Rect::from_min_max(
+                    Pos2::new(full_rect.left(), bubble_rect.bottom()),
+                    full_rect.max,
+                );
                 match self.stage {
-                    Stage::Idle => self.draw_idle(ui, bubble_rect),
+                    Stage::Idle => {
+                        self.draw_idle(ui, bubble_rect);
+                        if self.history_open && expanded {
+                            self.draw_history(ui, panel_rect);
+                        } else if self.error_panel_open() {
+                            self.draw_error_panel(ui, panel_rect);
+                        }
+                    }
                     Stage::Countdown => self.draw_countdown(ui, bubble_rect),
                     Stage::Starting => self.draw_starting(ui, bubble_rect),
                     Stage::Recording => self.draw_recording(ui, bubble_rect),
                     Stage::Stopping => self.draw_stopping(ui, bubble_rect),
-                    Stage::PostRecord => self.draw_post_record(ui, bubble_rect),
+                    Stage::PostRecord => {
+                        self.draw_post_record(ui, bubble_rect);
+                        if self.error_panel_open() {
+                            self.draw_error_panel(ui, panel_rect);
+                        }
+                    }
                     Stage::Rendering => self.draw_rendering(ui, bubble_rect),
                 }
             });
@@ -842,6 +1390,7 @@ impl OverlayApp {
         let left_anchor = cx + CIRCLE_RADIUS + 10.0;
         let right_anchor = rect.right() - PADDING;
 
+        let history_btn_w = 30.0;
         let cam_btn_w = 30.0;
         let preview_btn_w = 34.0;
         let btn_gap = 4.0;
@@ -855,9 +1404,14 @@ impl OverlayApp {
             Pos2::new(preview_rect.left() - btn_gap, row_bottom - 2.0),
         );
 
+        let history_rect = Rect::from_min_max(
+            Pos2::new(cam_rect.left() - btn_gap - history_btn_w, row_top + 2.0),
+            Pos2::new(cam_rect.left() - btn_gap, row_bottom - 2.0),
+        );
+
         let mut timer_width = 64.0;
         let min_monitor_width = 68.0;
-        let monitor_right = cam_rect.left() - btn_gap;
+        let monitor_right = history_rect.left() - btn_gap;
         let mut monitor_left = left_anchor + timer_width + btn_gap;
         let mut monitor_width = monitor_right - monitor_left;
 
@@ -912,6 +1466,34 @@ impl OverlayApp {
                 }
             });
 
+        let history_resp =
+            ui.interact(history_rect, ui.id().with("history_toggle"), Sense::click());
+        let history_color = if self.history_open {
+            ACCENT.linear_multiply(0.95)
+        } else {
+            Color32::from_rgb(217, 226, 240)
+        };
+        let history_text_color = if self.history_open {
+            Color32::WHITE
+        } else {
+            Color32::from_rgb(74, 92, 116)
+        };
+        ui.painter()
+            .rect_filled(history_rect, Rounding::same(6.0), history_color);
+        ui.painter().text(
+            history_rect.center(),
+            egui::Align2::CENTER_CENTER,
+            "LIB",
+            egui::FontId::proportional(9.0),
+            history_text_color,
+        );
+        if history_resp.clicked() {
+            self.history_open = !self.history_open;
+            if self.history_open {
+                self.library.prune_missing();
+            }
+        }
+
         let cam_resp = ui.interact(cam_rect, ui.id().with("cam_toggle"), Sense::click());
         let cam_color = if self.webcam {
             ACCENT.linear_multiply(0.95)
@@ -964,7 +1546,9 @@ impl OverlayApp {
             egui::FontId::proportional(9.0),
             preview_text_color,
         );
-        if preview_resp.clicked() && self.webcam {
+        if preview_resp.clicked() && self.webcam && !self.memory.live_previews {
+            self.status = "Webcam preview is off in low-memory mode".to_string();
+        } else if preview_resp.clicked() && self.webcam {
             self.webcam_preview_enabled = !self.webcam_preview_enabled;
             if !self.webcam_preview_enabled {
                 self.webcam_preview.stop();
@@ -980,6 +1564,92 @@ impl OverlayApp {
         self.menus_open = timer_open || monitor_open;
     }
 
+    // ── History: recent projects with [Open] [Analyze] [Export] ─────────────
+
+    fn draw_history(&mut self, ui: &mut egui::Ui, rect: Rect) {
+        let left = rect.left() + PADDING + 4.0;
+        let right = rect.right() - PADDING - 2.0;
+
+        ui.painter().text(
+            Pos2::new(left, rect.top() + 10.0),
+            egui::Align2::LEFT_CENTER,
+            "Recent projects",
+            egui::FontId::proportional(11.0),
+            TEXT_COLOR,
+        );
+
+        let entries: Vec<LibraryEntry> = self.library.recent(HISTORY_MAX_ROWS).to_vec();
+        if entries.is_empty() {
+            ui.painter().text(
+                Pos2::new(left, rect.top() + 34.0),
+                egui::Align2::LEFT_CENTER,
+                "No recordings yet",
+                egui::FontId::proportional(10.0),
+                TEXT_DIM,
+            );
+            return;
+        }
+
+        let mut pending: Option<(LibraryEntry, HistoryAction)> = None;
+        let mut y = rect.top() + 22.0;
+        for (idx, entry) in entries.iter().enumerate() {
+            if y + HISTORY_ROW_HEIGHT > rect.bottom() {
+                break;
+            }
+            let row =
+                Rect::from_min_max(Pos2::new(left, y), Pos2::new(right, y + HISTORY_ROW_HEIGHT));
+
+            let mut btn_right = row.right();
+            for (action, label, width) in [
+                (HistoryAction::Export, "Export", 42.0),
+                (HistoryAction::Analyze, "Analyze", 46.0),
+                (HistoryAction::Open, "Open", 34.0),
+            ] {
+                let btn_rect = Rect::from_min_max(
+                    Pos2::new(btn_right - width, row.top() + 4.0),
+                    Pos2::new(btn_right, row.bottom() - 4.0),
+                );
+                let resp = ui.interact(
+                    btn_rect,
+                    ui.id().with(("history_row", idx, label)),
+                    Sense::click(),
+                );
+                let bg = if resp.hovered() {
+                    Color32::from_rgb(205, 218, 236)
+                } else {
+                    Color32::from_rgb(217, 226, 240)
+                };
+                ui.painter().rect_filled(btn_rect, Rounding::same(6.0), bg);
+                ui.painter().text(
+                    btn_rect.center(),
+                    egui::Align2::CENTER_CENTER,
+                    label,
+                    egui::FontId::proportional(9.0),
+                    Color32::from_rgb(74, 92, 116),
+                );
+                if resp.clicked() {
+                    pending = Some((entry.clone(), action));
+                }
+                btn_right = btn_rect.left() - 4.0;
+            }
+
+            let name_chars = (((btn_right - row.left()) / 6.5) as usize).max(6);
+            ui.painter().text(
+                Pos2::new(row.left(), row.center().y),
+                egui::Align2::LEFT_CENTER,
+                ellipsize_label(&entry.name, name_chars),
+                egui::FontId::proportional(11.0),
+                TEXT_COLOR,
+            );
+
+            y += HISTORY_ROW_HEIGHT;
+        }
+
+        if let Some((entry, action)) = pending {
+            self.apply_history_action(&entry, action);
+        }
+    }
+
     // ── Countdown: [pulsing number]  "Starting in Xs" ───────────────────────
 
     fn draw_countdown(&mut self, ui: &mut egui::Ui, rect: Rect) {
@@ -1082,7 +1752,7 @@ impl OverlayApp {
             .circle_filled(Pos2::new(cx, cy), CIRCLE_RADIUS * 0.7, RED_PULSE_DIM);
     }
 
-    // ── PostRecord: [Auto-Direct]  [Render]  [New] ──────────────────────────
+    // ── PostRecord: [Auto-Direct]  [Render]  [Quick export]  [New] ──────────
 
     fn draw_post_record(&mut self, ui: &mut egui::Ui, rect: Rect) {
         let cy = rect.center().y;
@@ -1090,6 +1760,10 @@ impl OverlayApp {
         let btn_y = rect.top() + 6.0;
         let mut x = rect.left() + PADDING + 2.0;
 
+        if self.resume_prompt {
+            x = self.draw_pill_button(ui, x, btn_y, btn_h, "Resume", RED_IDLE, "resume_btn");
+            x += 4.0;
+        }
         x = self.draw_pill_button(ui, x, btn_y, btn_h, "Auto-Direct", ACCENT, "ad_btn");
         x += 4.0;
         x = self.draw_pill_button(
@@ -1102,7 +1776,17 @@ impl OverlayApp {
             "render_btn",
         );
         x += 4.0;
-        self.draw_pill_button(
+        x = self.draw_pill_button(
+            ui,
+            x,
+            btn_y,
+            btn_h,
+            "Quick export",
+            Color32::from_rgb(230, 150, 40),
+            "quick_btn",
+        );
+        x += 4.0;
+        x = self.draw_pill_button(
             ui,
             x,
             btn_y,
@@ -1113,13 +1797,127 @@ impl OverlayApp {
         );
 
         if !self.status.is_empty() {
-            ui.painter().text(
+            // Failures are clickable and expand into the error panel.
+            let max_chars = (((rect.right() - PADDING - x - 8.0) / 5.5) as usize).max(4);
+            let color = if self.error.is_some() {
+                RED_IDLE
+            } else {
+                TEXT_DIM
+            };
+            let status_rect = ui.painter().text(
                 Pos2::new(rect.right() - PADDING, cy),
                 egui::Align2::RIGHT_CENTER,
-                &self.status,
+                ellipsize_label(&self.status, max_chars),
                 egui::FontId::proportional(10.0),
-                TEXT_DIM,
+                color,
             );
+            if self.error.is_some() {
+                let resp = ui.interact(status_rect, ui.id().with("status_error"), Sense::click());
+                if resp.hovered() {
+                    ui.painter().hline(
+                        status_rect.x_range(),
+                        status_rect.bottom(),
+                        Stroke::new(1.0, color),
+                    );
+                }
+                if resp.clicked() {
+                    self.error_open = !self.error_open;
+                }
+            }
+        }
+    }
+
+    // ── Error panel: full message, hint, [Retry] [Open logs] [Check] ────────
+
+    fn draw_error_panel(&mut self, ui: &mut egui::Ui, rect: Rect) {
+        let Some(error) = self.error.as_ref() else {
+            return;
+        };
+        let left = rect.left() + PADDING + 4.0;
+        let right = rect.right() - PADDING - 2.0;
+
+        ui.painter().text(
+            Pos2::new(left, rect.top() + 10.0),
+            egui::Align2::LEFT_CENTER,
+            error.action.label(),
+            egui::FontId::proportional(11.0),
+            RED_IDLE,
+        );
+        let close_rect =
+            Rect::from_center_size(Pos2::new(right - 6.0, rect.top() + 10.0), Vec2::splat(14.0));
+        let close_resp = ui.interact(close_rect, ui.id().with("error_close"), Sense::click());
+        ui.painter().text(
+            close_rect.center(),
+            egui::Align2::CENTER_CENTER,
+            "x",
+            egui::FontId::proportional(11.0),
+            if close_resp.hovered() {
+                TEXT_COLOR
+            } else {
+                TEXT_DIM
+            },
+        );
+
+        let btn_h = 20.0;
+        let btn_y = rect.bottom() - btn_h - 8.0;
+        let body_rect = Rect::from_min_max(
+            Pos2::new(left, rect.top() + 20.0),
+            Pos2::new(right, btn_y - 6.0),
+        );
+        let mut body = ui.child_ui(body_rect, egui::Layout::top_down(egui::Align::Min));
+        egui::ScrollArea::vertical()
+            .max_height(body_rect.height())
+            .auto_shrink([false, false])
+            .show(&mut body, |ui| {
+                ui.spacing_mut().item_spacing.y = 4.0;
+                ui.add(
+                    egui::Label::new(
+                        egui::RichText::new(&error.message)
+                            .size(10.0)
+                            .color(TEXT_COLOR),
+                    )
+                    .wrap(true),
+                );
+                if let Some(hint) = error.hint {
+                    ui.add(
+                        egui::Label::new(egui::RichText::new(hint).size(10.0).color(ACCENT))
+                            .wrap(true),
+                    );
+                }
+                for line in error.check.iter().flatten() {
+                    ui.add(
+                        egui::Label::new(egui::RichText::new(line).size(10.0).color(TEXT_DIM))
+                            .wrap(true),
+                    );
+                }
+            });
+
+        let can_retry = error.action.can_retry();
+        let mut x = left;
+        if can_retry {
+            x = self.draw_pill_button(ui, x, btn_y, btn_h, "Retry", ACCENT, "retry_btn") + 4.0;
+        }
+        x = self.draw_pill_button(
+            ui,
+            x,
+            btn_y,
+            btn_h,
+            "Open logs",
+            Color32::from_rgb(100, 100, 120),
+            "logs_btn",
+        ) + 4.0;
+        self.draw_pill_button(
+            ui,
+            x,
+            btn_y,
+            btn_h,
+            "Check",
+            Color32::from_rgb(100, 100, 120),
+            "check_btn",
+        );
+
+        if close_resp.clicked() {
+            self.error_open = false;
         }
     }
 
@@ -1200,11 +1998,39 @@ impl OverlayApp {
             color,
         );
 
+        // Holding Render (or right-clicking it) renders a draft instead.
+        if id_str == "render_btn" {
+            if resp.is_pointer_button_down_on() {
+                let (pressed_at, fired) = *self.render_press.get_or_insert((Instant::now(), false));
+                if !fired && pressed_at.elapsed().as_secs_f64() >= LONG_PRESS_SECS {
+                    self.render_press = Some((pressed_at, true));
+                    self.start_render(true);
+                } else if !fired {
+                    ui.ctx().request_repaint();
+                }
+            } else if let Some((_, true)) = self.render_press.take() {
+                // The release that ends a long press is not a click.
+                return x + w;
+            }
+            if resp.secondary_clicked() {
+                self.start_render(true);
+                return x + w;
+            }
+        }
+
         if resp.clicked() {
             match id_str {
                 "ad_btn" => self.run_auto_direct(),
-                "render_btn" => self.start_render(),
+                "render_btn" => self.start_render(false),
+                "quick_btn" => self.start_quick_export(),
+                "retry_btn" => self.retry_failed_action(),
+                "logs_btn" => self.open_logs(),
+                "check_btn" => self.run_capability_check(),
+                "resume_btn" => self.resume_recording(),
                 "new_btn" => {
+                    self.clear_error();
+                    self.resume_prompt = false;
+                    self.wake_wait = None;
                     self.webcam_preview.stop();
                     self.stage = Stage::Idle;
                     self.active_project_path = None;
@@ -1248,6 +2074,20 @@ fn ellipsize_label(text: &str, max_chars: usize) -> String {
     format!("{prefix}...")
 }
 
+/// Hint for an error that came from a GrabMe crate, through any context.
+fn failure_hint(err: &anyhow::Error) -> Option<&'static str> {
+    err.downcast_ref::<GrabmeError>()
+        .and_then(GrabmeError::hint)
+}
+
+// ── Project library ──────────────────────────────────────────────────────────
+
+fn load_project_library() -> ProjectLibrary {
+    let mut library = ProjectLibrary::load(library_index_path()).unwrap_or_default();
+    library.prune_missing();
+    library
+}
+
 // ── Auto-Director ────────────────────────────────────────────────────────────
 
 fn auto_direct_project(project_path: &Path) -> anyhow::Result<usize> {
@@ -1260,8 +2100,12 @@ fn auto_direct_project(project_path: &Path) -> anyhow::Result<usize> {
     let events =
         parse_events(&events_raw).map_err(|e| anyhow::anyhow!("Failed to parse events: {e}"))?;
 
-    let prepared_events =
-        remap_events_for_auto_director(&events, &events_raw, &loaded.project.recording);
+    let prepared_events = project_events_to_capture_space(
+        &events,
+        parse_events_header(&events_raw).as_ref(),
+        &loaded.project.recording,
+    )
+    .events;
 
     if prepared_events.is_empty() {
         loaded.timeline.keyframes = vec![CameraKeyframe {
@@ -1269,16 +2113,31 @@ fn auto_direct_project(project_path: &Path) -> anyhow::Result<usize> {
             viewport: Viewport::FULL,
             easing: EasingFunction::EaseInOut,
             source: KeyframeSource::Auto,
+            confidence: None,
         }];
     } else {
+        // Events are remapped onto the recorded monitor, so the dwell
+        // thresholds are resolved against its size rather than the capture.
+        let recording = &loaded.project.recording;
+        let display = if recording.monitor_width > 0 && recording.monitor_height > 0 {
+            Some(DisplayMetrics {
+                width_px: recording.monitor_width,
+                height_px: recording.monitor_height,
+                scale_factor: recording.scale_factor,
+            })
+        } else {
+            DisplayMetrics::from_recording(recording)
+        };
         let config = AutoZoomConfig {
             dwell_threshold_secs: 1.25,
-            dwell_radius: 0.09,
+            dwell_radius: 130.0,
             hover_zoom: 0.90,
             scan_zoom: 1.0,
             smoothing_window: 5,
             min_viewport_size: 0.85,
-            dwell_velocity_threshold: 0.12,
+            dwell_velocity_threshold: 173.0,
+            dwell_unit: DwellUnit::LogicalPx,
+            display,
             monitor_count: 1,
             focused_monitor_index: 0,
             ..Default::default()
@@ -1296,285 +2155,6 @@ fn auto_direct_project(project_path: &Path) -> anyhow::Result<usize> {
     Ok(loaded.timeline.keyframes.len())
 }
 
-fn remap_events_for_auto_director(
-    events: &[InputEvent],
-    raw_events_file: &str,
-    recording: &RecordingConfig,
-) -> Vec<InputEvent> {
-    let header_space = parse_events_header(raw_events_file)
-        .map(|header| header.pointer_coordinate_space)
-        .filter(|space| *space != PointerCoordinateSpace::LegacyUnspecified);
-
-    let preferred_space = header_space.or_else(|| {
-        let space = recording.pointer_coordinate_space;
-        if space == PointerCoordinateSpace::LegacyUnspecified {
-            None
-        } else {
-            Some(space)
-        }
-    });
-
-    let pointer_space = select_pointer_space_for_auto_director(preferred_space, events, recording);
-    remap_pointer_events(events, recording, pointer_space)
-}
-
-fn parse_events_header(raw_events_file: &str) -> Option<EventStreamHeader> {
-    let line = raw_events_file
-        .lines()
-        .map(str::trim)
-        .find(|line| line.starts_with('#'))?;
-    serde_json::from_str::<EventStreamHeader>(line.trim_start_matches('#').trim()).ok()
-}
-
-fn select_pointer_space_for_auto_director(
-    preferred: Option<PointerCoordinateSpace>,
-    events: &[InputEvent],
-    recording: &RecordingConfig,
-) -> PointerCoordinateSpace {
-    const MIN_VALID_RATIO: f64 = 0.55;
-
-    if let Some(space) = preferred {
-        if pointer_space_in_bounds_ratio(space, events, recording) >= MIN_VALID_RATIO {
-            return space;
-        }
-    }
-
-    let mut candidates = vec![PointerCoordinateSpace::CaptureNormalized];
-    if recording.monitor_width > 0
-        && recording.monitor_height > 0
-        && recording.virtual_width > 0
-        && recording.virtual_height > 0
-    {
-        candidates.push(PointerCoordinateSpace::VirtualDesktopNormalized);
-        if recording.virtual_x != 0 || recording.virtual_y != 0 {
-            candidates.push(PointerCoordinateSpace::VirtualDesktopRootOrigin);
-        }
-    }
-
-    let mut best = PointerCoordinateSpace::CaptureNormalized;
-    let mut best_score = f64::NEG_INFINITY;
-    for candidate in candidates {
-        let score = pointer_space_score(candidate, events, recording);
-        if score > best_score {
-            best = candidate;
-            best_score = score;
-        }
-    }
-
-    best
-}
-
-fn pointer_space_score(
-    space: PointerCoordinateSpace,
-    events: &[InputEvent],
-    recording: &RecordingConfig,
-) -> f64 {
-    let mut sampled = 0usize;
-    let mut in_bounds = 0usize;
-    let mut min_x = f64::INFINITY;
-    let mut max_x = f64::NEG_INFINITY;
-    let mut min_y = f64::INFINITY;
-    let mut max_y = f64::NEG_INFINITY;
-
-    let sample_stride = ((events.len() as f64) / 800.0).ceil() as usize;
-    let sample_stride = sample_stride.max(1);
-
-    for (idx, event) in events.iter().enumerate() {
-        if idx % sample_stride != 0 {
-            continue;
-        }
-
-        let Some((x, y)) = event.pointer_position() else {
-            continue;
-        };
-
-        let Some((mx, my)) = map_pointer_to_recorded_monitor(x, y, recording, space) else {
-            continue;
-        };
-
-        sampled += 1;
-        if (0.0..=1.0).contains(&mx) && (0.0..=1.0).contains(&my) {
-            in_bounds += 1;
-            min_x = min_x.min(mx);
-            max_x = max_x.max(mx);
-            min_y = min_y.min(my);
-            max_y = max_y.max(my);
-        }
-    }
-
-    if sampled == 0 {
-        return -1.0;
-    }
-
-    let in_bounds_ratio = in_bounds as f64 / sampled as f64;
-    let span_x = if min_x.is_finite() && max_x.is_finite() {
-        (max_x - min_x).clamp(0.0, 1.0)
-    } else {
-        0.0
-    };
-    let span_y = if min_y.is_finite() && max_y.is_finite() {
-        (max_y - min_y).clamp(0.0, 1.0)
-    } else {
-        0.0
-    };
-
-    in_bounds_ratio * 3.0 + span_x + span_y
-}
-
-fn pointer_space_in_bounds_ratio(
-    space: PointerCoordinateSpace,
-    events: &[InputEvent],
-    recording: &RecordingConfig,
-) -> f64 {
-    let mut sampled = 0usize;
-    let mut in_bounds = 0usize;
-
-    for event in events {
-        let Some((x, y)) = event.pointer_position() else {
-            continue;
-        };
-
-        let Some((mx, my)) = map_pointer_to_recorded_monitor(x, y, recording, space) else {
-            continue;
-        };
-
-        sampled += 1;
-        if (0.0..=1.0).contains(&mx) && (0.0..=1.0).contains(&my) {
-            in_bounds += 1;
-        }
-    }
-
-    if sampled == 0 {
-        0.0
-    } else {
-        in_bounds as f64 / sampled as f64
-    }
-}
-
-fn remap_pointer_events(
-    events: &[InputEvent],
-    recording: &RecordingConfig,
-    pointer_space: PointerCoordinateSpace,
-) -> Vec<InputEvent> {
-    const EDGE_TOLERANCE: f64 = 0.04;
-
-    events
-        .iter()
-        .filter_map(|event| {
-            let remap_point = |x: f64, y: f64| -> Option<(f64, f64)> {
-                let (mx, my) = map_pointer_to_recorded_monitor(x, y, recording, pointer_space)?;
-                if !(-EDGE_TOLERANCE..=1.0 + EDGE_TOLERANCE).contains(&mx)
-                    || !(-EDGE_TOLERANCE..=1.0 + EDGE_TOLERANCE).contains(&my)
-                {
-                    return None;
-                }
-                Some((mx.clamp(0.0, 1.0), my.clamp(0.0, 1.0)))
-            };
-
-            let kind = match &event.kind {
-                EventKind::Pointer { x, y } => {
-                    let (nx, ny) = remap_point(*x, *y)?;
-                    EventKind::Pointer { x: nx, y: ny }
-                }
-                EventKind::Click {
-                    button,
-                    state,
-                    x,
-                    y,
-                } => {
-                    let (nx, ny) = remap_point(*x, *y)?;
-                    EventKind::Click {
-                        button: *button,
-                        state: *state,
-                        x: nx,
-                        y: ny,
-                    }
-                }
-                EventKind::Scroll { dx, dy, x, y } => {
-                    let (nx, ny) = remap_point(*x, *y)?;
-                    EventKind::Scroll {
-                        dx: *dx,
-                        dy: *dy,
-                        x: nx,
-                        y: ny,
-                    }
-                }
-                EventKind::Key { code, state } => EventKind::Key {
-                    code: code.clone(),
-                    state: *state,
-                },
-                EventKind::WindowFocus {
-                    window_title,
-                    app_id,
-                } => EventKind::WindowFocus {
-                    window_title: window_title.clone(),
-                    app_id: app_id.clone(),
-                },
-            };
-
-            Some(InputEvent {
-                timestamp_ns: event.timestamp_ns,
-                kind,
-            })
-        })
-        .collect()
-}
-
-fn map_pointer_to_recorded_monitor(
-    x: f64,
-    y: f64,
-    recording: &RecordingConfig,
-    pointer_space: PointerCoordinateSpace,
-) -> Option<(f64, f64)> {
-    match pointer_space {
-        PointerCoordinateSpace::CaptureNormalized | PointerCoordinateSpace::LegacyUnspecified => {
-            Some((x, y))
-        }
-        PointerCoordinateSpace::VirtualDesktopNormalized => {
-            if recording.monitor_width == 0
-                || recording.monitor_height == 0
-                || recording.virtual_width == 0
-                || recording.virtual_height == 0
-            {
-                return Some((x, y));
-            }
-
-            let monitor_w = recording.monitor_width as f64;
-            let monitor_h = recording.monitor_height as f64;
-            let virtual_w = recording.virtual_width as f64;
-            let virtual_h = recording.virtual_height as f64;
-
-            let pixel_x = recording.virtual_x as f64 + x * virtual_w;
-            let pixel_y = recording.virtual_y as f64 + y * virtual_h;
-            Some((
-                (pixel_x - recording.monitor_x as f64) / monitor_w,
-                (pixel_y - recording.monitor_y as f64) / monitor_h,
-            ))
-        }
-        PointerCoordinateSpace::VirtualDesktopRootOrigin => {
-            if recording.monitor_width == 0
-                || recording.monitor_height == 0
-                || recording.virtual_width == 0
-                || recording.virtual_height == 0
-            {
-                return Some((x, y));
-            }
-
-            let monitor_w = recording.monitor_width as f64;
-            let monitor_h = recording.monitor_height as f64;
-            let virtual_w = recording.virtual_width as f64;
-            let virtual_h = recording.virtual_height as f64;
-
-            let pixel_x = x * virtual_w;
-            let pixel_y = y * virtual_h;
-            Some((
-                (pixel_x - recording.monitor_x as f64) / monitor_w,
-                (pixel_y - recording.monitor_y as f64) / monitor_h,
-            ))
-        }
-    }
-}
-
 fn clamp_timeline_to_visible_bounds(keyframes: &mut [CameraKeyframe], min_viewport: f64) {
     for keyframe in keyframes {
         let width = keyframe.viewport.w.clamp(min_viewport, 1.0);
@@ -1592,6 +2172,8 @@ fn main() -> anyhow::Result<()> {
     // The user can still drag the window; we just don't want the WM
     // doubling our already-compact pixel sizes.
     std::env::set_var("WINIT_X11_SCALE_FACTOR", "1");
+    let _temp = grabme_common::temp::init_session_temp();
+    grabme_common::logging::init_logging(&AppConfig::load().logging);
 
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
@@ -1,20 +1,87 @@
 //! Subtitle generation in SRT and VTT formats.
+//!
+//! Whisper can be confidently wrong over music or typing noise. Segments
+//! whose confidence falls below [`SubtitleOptions::min_confidence`] are
+//! dropped or marked as uncertain: italic and grey in SRT, which burn-in
+//! keeps, and italic with a `low-confidence` class in VTT.
 
 use crate::transcription::TranscriptionSegment;
 use grabme_common::error::GrabmeResult;
+use serde::{Deserialize, Serialize};
+
+/// Colour of uncertain captions in SRT.
+const LOW_CONFIDENCE_COLOR: &str = "#a0a0a0";
+
+/// What happens to a segment below the confidence threshold.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LowConfidenceCaptions {
+    /// Keep it, styled as uncertain.
+    #[default]
+    Style,
+    /// Leave it out.
+    Drop,
+}
+
+/// Options for subtitle generation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct SubtitleOptions {
+    /// Confidence in [0.0, 1.0] below which a segment counts as uncertain.
+    /// `None` writes every segment as is. Segments without a confidence
+    /// are never gated.
+    pub min_confidence: Option<f64>,
+
+    /// What to do with uncertain segments.
+    pub low_confidence: LowConfidenceCaptions,
+}
+
+impl SubtitleOptions {
+    /// Whether `segment` scored below the threshold.
+    fn is_uncertain(&self, segment: &TranscriptionSegment) -> bool {
+        match (self.min_confidence, segment.confidence) {
+            (Some(min), Some(confidence)) => confidence < min,
+            _ => false,
+        }
+    }
+
+    /// Segments to write, each with whether it is uncertain.
+    fn captions<'a>(
+        &'a self,
+        segments: &'a [TranscriptionSegment],
+    ) -> impl Iterator<Item = (&'a TranscriptionSegment, bool)> + 'a {
+        segments
+            .iter()
+            .map(|segment| (segment, self.is_uncertain(segment)))
+            .filter(|(_, uncertain)| {
+                !(*uncertain && self.low_confidence == LowConfidenceCaptions::Drop)
+            })
+    }
+}
 
 /// Generate SRT subtitle content from transcription segments.
 pub fn generate_srt(segments: &[TranscriptionSegment]) -> String {
+    generate_srt_with(segments, &SubtitleOptions::default())
+}
+
+/// Generate SRT subtitle content, gating segments on confidence.
+pub fn generate_srt_with(segments: &[TranscriptionSegment], options: &SubtitleOptions) -> String {
     let mut output = String::new();
 
-    for (i, segment) in segments.iter().enumerate() {
+    for (i, (segment, uncertain)) in options.captions(segments).enumerate() {
         output.push_str(&format!("{}\n", i + 1));
         output.push_str(&format!(
             "{} --> {}\n",
             format_srt_time(segment.start_secs),
             format_srt_time(segment.end_secs),
         ));
-        output.push_str(&segment.text);
+        if uncertain {
+            output.push_str(&format!(
+                "<i><font color=\"{LOW_CONFIDENCE_COLOR}\">{}</font></i>",
+                segment.text
+            ));
+        } else {
+            output.push_str(&segment.text);
+        }
         output.push_str("\n\n");
     }
 
@@ -23,15 +90,24 @@ pub fn generate_srt(segments: &[TranscriptionSegment]) -> String {
 
 /// Generate WebVTT subtitle content from transcription segments.
 pub fn generate_vtt(segments: &[TranscriptionSegment]) -> String {
+    generate_vtt_with(segments, &SubtitleOptions::default())
+}
+
+/// Generate WebVTT subtitle content, gating segments on confidence.
+pub fn generate_vtt_with(segments: &[TranscriptionSegment], options: &SubtitleOptions) -> String {
     let mut output = String::from("WEBVTT\n\n");
 
-    for segment in segments {
+    for (segment, uncertain) in options.captions(segments) {
         output.push_str(&format!(
             "{} --> {}\n",
             format_vtt_time(segment.start_secs),
             format_vtt_time(segment.end_secs),
         ));
-        output.push_str(&segment.text);
+        if uncertain {
+            output.push_str(&format!("<c.low-confidence><i>{}</i></c>", segment.text));
+        } else {
+            output.push_str(&segment.text);
+        }
         output.push_str("\n\n");
     }
 
@@ -62,10 +138,19 @@ fn format_vtt_time(secs: f64) -> String {
 pub fn save_subtitles(
     segments: &[TranscriptionSegment],
     path: &std::path::Path,
+) -> GrabmeResult<()> {
+    save_subtitles_with(segments, path, &SubtitleOptions::default())
+}
+
+/// Save subtitles to a file, gating segments on confidence.
+pub fn save_subtitles_with(
+    segments: &[TranscriptionSegment],
+    path: &std::path::Path,
+    options: &SubtitleOptions,
 ) -> GrabmeResult<()> {
     let content = match path.extension().and_then(|e| e.to_str()) {
-        Some("vtt") => generate_vtt(segments),
-        _ => generate_srt(segments), // default to SRT
+        Some("vtt") => generate_vtt_with(segments, options),
+        _ => generate_srt_with(segments, options), // default to SRT
     };
     std::fs::write(path, content)?;
     Ok(())
@@ -111,6 +196,46 @@ mod tests {
         assert!(vtt.contains("00:01:01.500 --> 00:01:03.000"));
     }
 
+    fn segment(start_secs: f64, text: &str, confidence: Option<f64>) -> TranscriptionSegment {
+        TranscriptionSegment {
+            start_secs,
+            end_secs: start_secs + 1.0,
+            text: text.to_string(),
+            confidence,
+        }
+    }
+
+    #[test]
+    fn test_low_confidence_segments_are_styled_or_dropped() {
+        let segments = vec![
+            segment(0.0, "Clear speech", Some(0.9)),
+            segment(2.0, "clack clack", Some(0.3)),
+            segment(4.0, "No score", None),
+        ];
+        let mut options = SubtitleOptions {
+            min_confidence: Some(0.6),
+            ..SubtitleOptions::default()
+        };
+
+        let srt = generate_srt_with(&segments, &options);
+        assert!(srt.contains("\nClear speech\n"));
+        assert!(srt.contains(
+            "2\n00:00:02,000 --> 00:00:03,000\n<i><font color=\"#a0a0a0\">clack clack</font></i>"
+        ));
+        assert!(srt.contains("\nNo score\n"));
+        let vtt = generate_vtt_with(&segments, &options);
+        assert!(vtt.contains("<c.low-confidence><i>clack clack</i></c>"));
+
+        options.low_confidence = LowConfidenceCaptions::Drop;
+        let srt = generate_srt_with(&segments, &options);
+        assert!(!srt.contains("clack"));
+        // Numbering stays consecutive.
+        assert!(srt.contains("2\n00:00:04,000 --> 00:00:05,000\nNo score"));
+
+        // Without a threshold nothing is gated.
+        assert_eq!(generate_srt(&segments).matches("-->").count(), 3);
+    }
+
     #[test]
     fn test_time_formatting() {
         assert_eq!(format_srt_time(0.0), "00:00:00,000");
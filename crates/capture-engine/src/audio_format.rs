@@ -0,0 +1,223 @@
+//! Audio format negotiation with capture devices.
+//!
+//! Some inputs only run at 44.1 kHz, or only in mono. Instead of converting
+//! everything to one fixed format, capture asks the default device what it
+//! supports and records in the closest format to the requested one. The
+//! track keeps what was used, and export resamples every track to a common
+//! rate.
+
+use gst::prelude::*;
+use gstreamer as gst;
+
+use grabme_project_model::project::AudioFormat;
+
+use crate::pipeline::init_gstreamer;
+
+/// Rates tried in order when a device doesn't support the requested one.
+const FALLBACK_RATES: [u32; 6] = [48_000, 44_100, 96_000, 32_000, 22_050, 16_000];
+/// Channel count used when the device doesn't report one.
+const FALLBACK_CHANNELS: u32 = 2;
+/// Cap for devices that accept any channel count, so a converting source
+/// advertising `[1, 64]` isn't recorded as 64 channels.
+const MAX_CHANNELS: u32 = 8;
+
+/// One set of formats a device accepts: any of `rates` (inclusive ranges;
+/// a fixed rate is a range of one) with a channel count in `channels`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AudioCapsRange {
+    pub rates: Vec<(u32, u32)>,
+    pub channels: (u32, u32),
+}
+
+impl AudioCapsRange {
+    fn supports_rate(&self, rate: u32) -> bool {
+        self.rates
+            .iter()
+            .any(|&(min, max)| (min..=max).contains(&rate))
+    }
+
+    /// Supported rate nearest to `rate`.
+    fn nearest_rate(&self, rate: u32) -> Option<u32> {
+        self.rates
+            .iter()
+            .map(|&(min, max)| rate.clamp(min, max))
+            .min_by_key(|candidate| candidate.abs_diff(rate))
+    }
+}
+
+/// Pick the format to record in: `requested_rate` when the device supports
+/// it, else the closest rate it does, at the device's own channel count.
+/// With nothing known about the device the requested rate is used in
+/// stereo, and GStreamer converts.
+pub fn negotiate_audio_format(requested_rate: u32, supported: &[AudioCapsRange]) -> AudioFormat {
+    let requested = AudioFormat {
+        sample_rate: requested_rate,
+        channels: FALLBACK_CHANNELS,
+    };
+    if supported.is_empty() {
+        return requested;
+    }
+
+    let rate_and_caps = std::iter::once(requested_rate)
+        .chain(FALLBACK_RATES)
+        .find_map(|rate| {
+            supported
+                .iter()
+                .find(|caps| caps.supports_rate(rate))
+                .map(|caps| (rate, caps))
+        })
+        .or_else(|| {
+            supported
+                .iter()
+                .filter_map(|caps| Some((caps.nearest_rate(requested_rate)?, caps)))
+                .min_by_key(|(rate, _)| rate.abs_diff(requested_rate))
+        });
+    let Some((sample_rate, caps)) = rate_and_caps else {
+        return requested;
+    };
+
+    let (min_channels, max_channels) = caps.channels;
+    AudioFormat {
+        sample_rate,
+        channels: max_channels.min(MAX_CHANNELS).max(min_channels).max(1),
+    }
+}
+
+/// Formats the default device of `device_class` (`Audio/Source` for
+/// inputs, `Audio/Sink` for the output whose monitor is recorded) accepts.
+/// Empty when the device can't be probed.
+pub fn probe_default_device_caps(device_class: &str) -> Vec<AudioCapsRange> {
+    if init_gstreamer().is_err() {
+        return Vec::new();
+    }
+    let monitor = gst::DeviceMonitor::new();
+    monitor.add_filter(Some(device_class), None);
+    if monitor.start().is_err() {
+        return Vec::new();
+    }
+    let devices = monitor.devices();
+    monitor.stop();
+
+    let is_default = |device: &gst::Device| {
+        device
+            .properties()
+            .and_then(|props| props.get::<bool>("is-default").ok())
+            .unwrap_or(false)
+    };
+    let Some(device) = devices
+        .iter()
+        .find(|device| is_default(device))
+        .or_else(|| devices.iter().next())
+    else {
+        return Vec::new();
+    };
+    let caps = device
+        .caps()
+        .map(|caps| caps.iter().filter_map(caps_range).collect())
+        .unwrap_or_default();
+    tracing::debug!(device = %device.display_name(), ?caps, "Probed audio device formats");
+    caps
+}
+
+fn caps_range(structure: &gst::StructureRef) -> Option<AudioCapsRange> {
+    if structure.name() != "audio/x-raw" {
+        return None;
+    }
+    let rates = int_field(structure, "rate")?;
+    let channels = int_field(structure, "channels")
+        .and_then(|ranges| {
+            let min = ranges.iter().map(|&(min, _)| min).min()?;
+            let max = ranges.iter().map(|&(_, max)| max).max()?;
+            Some((min, max))
+        })
+        .unwrap_or((FALLBACK_CHANNELS, FALLBACK_CHANNELS));
+    Some(AudioCapsRange { rates, channels })
+}
+
+/// A fixed int, int range, or list of ints, as inclusive ranges.
+fn int_field(structure: &gst::StructureRef, name: &str) -> Option<Vec<(u32, u32)>> {
+    let to_u32 = |value: i32| u32::try_from(value).ok();
+    if let Ok(value) = structure.get::<i32>(name) {
+        let value = to_u32(value)?;
+        return Some(vec![(value, value)]);
+    }
+    if let Ok(range) = structure.get::<gst::IntRange<i32>>(name) {
+        return Some(vec![(to_u32(range.min())?, to_u32(range.max())?)]);
+    }
+    if let Ok(list) = structure.get::<gst::List>(name) {
+        let values: Vec<(u32, u32)> = list
+            .iter()
+            .filter_map(|value| value.get::<i32>().ok().and_then(to_u32))
+            .map(|value| (value, value))
+            .collect();
+        return (!values.is_empty()).then_some(values);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn caps(rates: &[(u32, u32)], channels: (u32, u32)) -> AudioCapsRange {
+        AudioCapsRange {
+            rates: rates.to_vec(),
+            channels,
+        }
+    }
+
+    #[test]
+    fn test_negotiation_keeps_a_supported_request() {
+        let supported = [caps(&[(8_000, 192_000)], (2, 2))];
+        assert_eq!(
+            negotiate_audio_format(48_000, &supported),
+            AudioFormat {
+                sample_rate: 48_000,
+                channels: 2
+            }
+        );
+        assert_eq!(
+            negotiate_audio_format(48_000, &[]),
+            AudioFormat {
+                sample_rate: 48_000,
+                channels: FALLBACK_CHANNELS
+            }
+        );
+    }
+
+    #[test]
+    fn test_negotiation_falls_back_to_what_the_device_supports() {
+        // A 44.1 kHz mono-only headset.
+        let headset = [caps(&[(44_100, 44_100)], (1, 1))];
+        assert_eq!(
+            negotiate_audio_format(48_000, &headset),
+            AudioFormat {
+                sample_rate: 44_100,
+                channels: 1
+            }
+        );
+
+        // No common rate: the nearest supported one.
+        let odd = [caps(&[(11_025, 11_025), (24_000, 24_000)], (2, 2))];
+        assert_eq!(
+            negotiate_audio_format(48_000, &odd),
+            AudioFormat {
+                sample_rate: 24_000,
+                channels: 2
+            }
+        );
+    }
+
+    #[test]
+    fn test_negotiation_keeps_the_device_channel_count() {
+        // A stereo USB mic stays stereo, a mono one mono.
+        for channels in [1, 2, 6] {
+            let device = [caps(&[(48_000, 48_000)], (channels, channels))];
+            assert_eq!(negotiate_audio_format(48_000, &device).channels, channels);
+        }
+
+        // A converting source that takes anything is capped.
+        let any = [caps(&[(8_000, 192_000)], (1, 64))];
+        assert_eq!(negotiate_audio_format(48_000, &any).channels, MAX_CHANNELS);
+    }
+}
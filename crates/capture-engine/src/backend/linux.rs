@@ -1,18 +1,36 @@
+use std::collections::BTreeMap;
 use std::path::Path;
 
 use grabme_common::error::{GrabmeError, GrabmeResult};
-use grabme_platform_core::{virtual_desktop_bounds, MonitorInfo};
+use grabme_input_tracker::{EventSender, PointerWatch};
+use grabme_platform_core::{
+    split_monitors, split_parent, virtual_desktop_bounds, DisplayTarget, MonitorInfo,
+    SystemSessionEvent, WindowGeometry,
+};
+use grabme_platform_linux::appearance::{
+    spawn_appearance_watcher, AppearanceChange, ColorSchemePreference,
+};
+use grabme_platform_linux::logind::spawn_logind_watcher;
 use grabme_platform_linux::portal::{
     close_session, is_portal_available, request_screencast, CursorMode,
 };
-use grabme_platform_linux::{detect_display_server, detect_monitors, DisplayServer, SourceType};
+use grabme_platform_linux::{
+    detect_display_server, detect_monitors, focused_window_geometry, query_window_geometry,
+    set_display_target, x11_display_name, DisplayServer, SourceType,
+};
+use grabme_project_model::event::{ColorScheme, EventKind};
+use grabme_project_model::project::CursorCaptureMode;
 
-use crate::backend::CaptureBackend;
+use crate::backend::{CaptureBackend, WindowGeometryProbe};
 use crate::pipeline::{
-    build_mic_pipeline, build_screen_pipeline, build_system_audio_pipeline, build_webcam_pipeline,
-    build_x11_mic_pipeline, build_x11_screen_pipeline, CapturePipeline,
+    build_live_view_pipeline, build_mic_pipeline, build_screen_pipeline,
+    build_system_audio_pipeline, build_v4l2_pip_pipeline, build_webcam_pipeline,
+    build_x11_mic_pipeline, build_x11_pip_pipeline, build_x11_screen_pipeline,
+    pipewire_live_source, x11_live_source, CapturePipeline,
 };
-use crate::session::{CaptureMode, ScreenCaptureConfig};
+use crate::session::{CaptureMode, PipSource, ScreenCaptureConfig};
+use crate::virtual_camera::{V4l2LoopbackSink, VirtualCameraConfig, VirtualCameraSink};
+use crate::webcam_format::WebcamFormatRequest;
 
 pub struct LinuxBackend {
     display_server: DisplayServer,
@@ -22,6 +40,12 @@ pub struct LinuxBackend {
     cursor_hidden: bool,
     // Store region for X11 pipeline
     capture_region: Option<(i32, i32, u32, u32)>,
+    // Cursor mode the capture was set up with
+    cursor_mode: Option<CursorCaptureMode>,
+    // Edges to trim off the portal stream when recording a split region
+    screen_crop: Option<(u32, u32, u32, u32)>,
+    // Monitor splits the capture was set up with
+    monitor_splits: BTreeMap<String, u32>,
 }
 
 impl LinuxBackend {
@@ -32,6 +56,9 @@ impl LinuxBackend {
             portal_session_handle: None,
             cursor_hidden: true,
             capture_region: None,
+            cursor_mode: None,
+            screen_crop: None,
+            monitor_splits: BTreeMap::new(),
         }
     }
 }
@@ -50,21 +77,32 @@ impl CaptureBackend for LinuxBackend {
         Ok(())
     }
 
+    fn set_display_target(&mut self, target: &DisplayTarget) {
+        set_display_target(target.clone());
+    }
+
     fn detect_monitors(&self) -> GrabmeResult<Vec<MonitorInfo>> {
         detect_monitors()
     }
 
+    fn focused_window_geometry(&self) -> GrabmeResult<Option<WindowGeometry>> {
+        focused_window_geometry()
+    }
+
     async fn prepare_screen_capture(
         &mut self,
         config: &ScreenCaptureConfig,
     ) -> GrabmeResult<(u32, u32)> {
         self.cursor_hidden = config.hide_cursor;
+        self.monitor_splits = config.monitor_splits.clone();
+        self.screen_crop = None;
 
         let monitor_index = match config.mode {
             CaptureMode::FullScreen { monitor_index } => monitor_index,
             _ => 0,
         };
-        let monitors = self.detect_monitors().unwrap_or_default();
+        let detected = self.detect_monitors().unwrap_or_default();
+        let monitors = split_monitors(&detected, &config.monitor_splits);
         if !monitors.is_empty() && monitor_index >= monitors.len() {
             return Err(GrabmeError::capture(format!(
                 "Invalid monitor index {monitor_index}. Available monitors: {}",
@@ -80,19 +118,57 @@ impl CaptureBackend for LinuxBackend {
                     ));
                 }
 
-                let cursor_mode = if config.hide_cursor {
-                    CursorMode::Hidden
-                } else {
-                    CursorMode::Embedded
+                let cursor_mode = match config.requested_cursor_mode() {
+                    CursorCaptureMode::Hidden => CursorMode::Hidden,
+                    CursorCaptureMode::Embedded => CursorMode::Embedded,
+                    CursorCaptureMode::Metadata => CursorMode::Metadata,
                 };
 
-                // Note: For now we only support monitor capture via portal fully.
-                // Window capture support would need to be passed here.
+                // Window streams from the portal follow the window on their own,
+                // so no geometry polling is needed on Wayland.
+                let source_type = match config.mode {
+                    CaptureMode::Window { .. } => SourceType::Window,
+                    _ => SourceType::Monitor,
+                };
                 let portal_session =
-                    request_screencast(SourceType::Monitor, cursor_mode, monitor_index).await?;
+                    request_screencast(source_type, cursor_mode, monitor_index).await?;
 
                 self.pipewire_node_id = Some(portal_session.pipewire_node_id);
                 self.portal_session_handle = Some(portal_session.session_handle);
+                self.cursor_mode = Some(match portal_session.cursor_mode {
+                    CursorMode::Hidden => CursorCaptureMode::Hidden,
+                    CursorMode::Embedded => CursorCaptureMode::Embedded,
+                    CursorMode::Metadata => CursorCaptureMode::Metadata,
+                });
+
+                // The portal streams whole monitors, so a split region is cut
+                // out of its parent's stream. The user has to pick the parent
+                // monitor in the portal dialog.
+                let region = match config.mode {
+                    CaptureMode::FullScreen { .. } => monitors.get(monitor_index),
+                    _ => None,
+                };
+                if let Some((region, parent)) =
+                    region.and_then(|region| Some((region, split_parent(&detected, region)?)))
+                {
+                    let crop = split_region_crop(
+                        region,
+                        parent,
+                        (portal_session.width, portal_session.height),
+                    );
+                    tracing::info!(
+                        region = %region.name,
+                        parent = %parent.name,
+                        ?crop,
+                        "Cropping portal stream to split monitor region"
+                    );
+                    let (left, top, right, bottom) = crop;
+                    self.screen_crop = Some(crop);
+                    return Ok((
+                        portal_session.width.saturating_sub(left + right),
+                        portal_session.height.saturating_sub(top + bottom),
+                    ));
+                }
 
                 Ok((portal_session.width, portal_session.height))
             }
@@ -105,6 +181,12 @@ impl CaptureBackend for LinuxBackend {
                 // monitor following in post processing.
                 let (vx, vy, vw, vh) = virtual_desktop_bounds(&monitors);
                 self.capture_region = Some((vx, vy, vw, vh));
+                // ximagesrc can only draw the pointer or leave it out.
+                self.cursor_mode = Some(if config.hide_cursor {
+                    CursorCaptureMode::Hidden
+                } else {
+                    CursorCaptureMode::Embedded
+                });
 
                 tracing::info!(
                     virtual_x = vx,
@@ -124,6 +206,10 @@ impl CaptureBackend for LinuxBackend {
         }
     }
 
+    fn cursor_mode(&self) -> Option<CursorCaptureMode> {
+        self.cursor_mode
+    }
+
     fn build_screen_pipeline(
         &self,
         output_path: &Path,
@@ -136,11 +222,15 @@ impl CaptureBackend for LinuxBackend {
                         "PipeWire node ID not available. Did you call prepare_screen_capture?",
                     )
                 })?;
-                build_screen_pipeline(node_id, output_path, fps)
-            }
-            DisplayServer::X11 => {
-                build_x11_screen_pipeline(output_path, fps, self.cursor_hidden, self.capture_region)
+                build_screen_pipeline(node_id, output_path, fps, self.screen_crop)
             }
+            DisplayServer::X11 => build_x11_screen_pipeline(
+                output_path,
+                fps,
+                self.cursor_hidden,
+                self.capture_region,
+                x11_display_name().as_deref(),
+            ),
             _ => Err(GrabmeError::platform("Unknown display server")),
         }
     }
@@ -178,8 +268,90 @@ impl CaptureBackend for LinuxBackend {
         &self,
         output_path: &Path,
         fps: u32,
+        request: WebcamFormatRequest,
     ) -> GrabmeResult<Box<dyn CapturePipeline>> {
-        build_webcam_pipeline(output_path, fps)
+        build_webcam_pipeline(output_path, fps, request)
+    }
+
+    fn build_pip_pipeline(
+        &self,
+        source: &PipSource,
+        output_path: &Path,
+        fps: u32,
+    ) -> GrabmeResult<Box<dyn CapturePipeline>> {
+        match source {
+            PipSource::Device(device) => build_v4l2_pip_pipeline(device, output_path, fps),
+            PipSource::Monitor(index) => {
+                // A second monitor stream would need its own portal session
+                // on Wayland; X11 can grab any region of the desktop.
+                if self.display_server != DisplayServer::X11 {
+                    return Err(GrabmeError::unsupported(
+                        "Picture-in-picture monitor capture is only supported on X11; use a capture device instead",
+                    ));
+                }
+                let monitors = split_monitors(&self.detect_monitors()?, &self.monitor_splits);
+                let monitor = monitors.get(*index).ok_or_else(|| {
+                    GrabmeError::capture(format!(
+                        "Invalid picture-in-picture monitor index {index}. Available monitors: {}",
+                        monitor_list_for_error(&monitors)
+                    ))
+                })?;
+                build_x11_pip_pipeline(
+                    output_path,
+                    fps,
+                    (monitor.x, monitor.y, monitor.width, monitor.height),
+                    x11_display_name().as_deref(),
+                )
+            }
+        }
+    }
+
+    fn build_virtual_camera_pipeline(
+        &self,
+        config: &VirtualCameraConfig,
+        monitor: Option<&MonitorInfo>,
+        pointer: PointerWatch,
+    ) -> GrabmeResult<Box<dyn CapturePipeline>> {
+        let source = match self.display_server {
+            DisplayServer::Wayland => {
+                let node_id = self.pipewire_node_id.ok_or_else(|| {
+                    GrabmeError::capture(
+                        "PipeWire node ID not available. Did you call prepare_screen_capture?",
+                    )
+                })?;
+                pipewire_live_source(node_id, self.screen_crop)
+            }
+            DisplayServer::X11 => x11_live_source(
+                monitor
+                    .map(|m| (m.x, m.y, m.width, m.height))
+                    .or(self.capture_region),
+                x11_display_name().as_deref(),
+            )?,
+            _ => return Err(GrabmeError::platform("Unknown display server")),
+        };
+        let sink = V4l2LoopbackSink::open(config.device.as_deref())?;
+        tracing::info!(device = %sink.describe(), zoom = config.zoom, "Mirroring capture to virtual camera");
+        build_live_view_pipeline(&source, &sink, config, pointer)
+    }
+
+    fn window_geometry_probe(&self) -> Option<WindowGeometryProbe> {
+        // X11 records the whole virtual desktop; the window is followed in post.
+        (self.display_server == DisplayServer::X11).then_some(query_window_geometry as _)
+    }
+
+    fn watch_appearance(&self, events: EventSender) -> Option<tokio::task::JoinHandle<()>> {
+        Some(spawn_appearance_watcher(move |change| {
+            events.send(appearance_event(change))
+        }))
+    }
+
+    fn watch_system_session(
+        &self,
+        events: tokio::sync::mpsc::UnboundedSender<SystemSessionEvent>,
+    ) -> Option<tokio::task::JoinHandle<()>> {
+        Some(spawn_logind_watcher(move |event| {
+            events.send(event).is_ok()
+        }))
     }
 
     fn get_display_server(&self) -> grabme_project_model::project::DisplayServer {
@@ -198,6 +370,19 @@ impl CaptureBackend for LinuxBackend {
     }
 }
 
+fn appearance_event(change: AppearanceChange) -> EventKind {
+    match change {
+        AppearanceChange::ColorScheme(scheme) => EventKind::ColorScheme {
+            scheme: match scheme {
+                ColorSchemePreference::Dark => ColorScheme::Dark,
+                ColorSchemePreference::Light => ColorScheme::Light,
+                ColorSchemePreference::NoPreference => ColorScheme::NoPreference,
+            },
+        },
+        AppearanceChange::Brightness(level) => EventKind::Brightness { level },
+    }
+}
+
 fn monitor_list_for_error(monitors: &[MonitorInfo]) -> String {
     monitors
         .iter()
@@ -216,3 +401,25 @@ fn monitor_list_for_error(monitors: &[MonitorInfo]) -> String {
         .collect::<Vec<_>>()
         .join(", ")
 }
+
+/// `(left, top, right, bottom)` pixels to trim off a `stream` of `parent`
+/// so that only `region` is left. The stream can be scaled relative to the
+/// monitor layout, e.g. when the compositor streams logical pixels.
+fn split_region_crop(
+    region: &MonitorInfo,
+    parent: &MonitorInfo,
+    (stream_width, stream_height): (u32, u32),
+) -> (u32, u32, u32, u32) {
+    let scale_x = stream_width as f64 / parent.width.max(1) as f64;
+    let scale_y = stream_height as f64 / parent.height.max(1) as f64;
+    let left = ((region.x - parent.x).max(0) as f64 * scale_x).round() as u32;
+    let top = ((region.y - parent.y).max(0) as f64 * scale_y).round() as u32;
+    let width = (region.width as f64 * scale_x).round() as u32;
+    let height = (region.height as f64 * scale_y).round() as u32;
+    (
+        left.min(stream_width),
+        top.min(stream_height),
+        stream_width.saturating_sub(left + width),
+        stream_height.saturating_sub(top + height),
+    )
+}
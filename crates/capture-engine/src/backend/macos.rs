@@ -1,12 +1,13 @@
 use std::path::Path;
 
 use grabme_common::error::{GrabmeError, GrabmeResult};
-use grabme_platform_core::MonitorInfo;
+use grabme_platform_core::{MonitorInfo, WindowGeometry};
 use grabme_platform_macos as platform_macos;
 
 use crate::backend::CaptureBackend;
 use crate::pipeline::CapturePipeline;
 use crate::session::ScreenCaptureConfig;
+use crate::webcam_format::WebcamFormatRequest;
 
 /// Compile-safe macOS backend skeleton.
 ///
@@ -35,6 +36,10 @@ impl CaptureBackend for MacOSBackend {
         platform_macos::detect_monitors()
     }
 
+    fn focused_window_geometry(&self) -> GrabmeResult<Option<WindowGeometry>> {
+        platform_macos::focused_window_geometry()
+    }
+
     async fn prepare_screen_capture(
         &mut self,
         _config: &ScreenCaptureConfig,
@@ -70,6 +75,7 @@ impl CaptureBackend for MacOSBackend {
         &self,
         _output_path: &Path,
         _fps: u32,
+        _request: WebcamFormatRequest,
     ) -> GrabmeResult<Box<dyn CapturePipeline>> {
         Err(GrabmeError::platform("macOS backend not yet implemented"))
     }
@@ -2,11 +2,18 @@ use std::path::Path;
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 
-use grabme_common::error::GrabmeResult;
-use grabme_platform_core::MonitorInfo;
+use grabme_common::error::{GrabmeError, GrabmeResult};
+use grabme_input_tracker::{EventSender, PointerWatch};
+use grabme_platform_core::{DisplayTarget, MonitorInfo, SystemSessionEvent, WindowGeometry};
+use grabme_project_model::project::CursorCaptureMode;
 
 use crate::pipeline::CapturePipeline;
-use crate::session::ScreenCaptureConfig;
+use crate::session::{PipSource, ScreenCaptureConfig};
+use crate::virtual_camera::VirtualCameraConfig;
+use crate::webcam_format::WebcamFormatRequest;
+
+/// Looks up the current geometry of a window by platform id.
+pub type WindowGeometryProbe = fn(&str) -> GrabmeResult<WindowGeometry>;
 
 /// Abstract interface for platform-specific capture capabilities.
 #[async_trait::async_trait]
@@ -14,9 +21,21 @@ pub trait CaptureBackend: Send + Sync {
     /// Initialize the backend (e.g. check permissions, connect to display server).
     async fn init(&mut self) -> GrabmeResult<()>;
 
+    /// Capture from `target` instead of the display the environment points
+    /// at. Called before [`init`](Self::init); backends with a single
+    /// desktop ignore it.
+    fn set_display_target(&mut self, _target: &DisplayTarget) {}
+
     /// Detect available monitors.
     fn detect_monitors(&self) -> GrabmeResult<Vec<MonitorInfo>>;
 
+    /// Geometry of the currently focused window, in virtual-desktop pixels.
+    ///
+    /// Returns `Ok(None)` when nothing has focus or the platform cannot tell.
+    fn focused_window_geometry(&self) -> GrabmeResult<Option<WindowGeometry>> {
+        Ok(None)
+    }
+
     /// Prepare for screen capture (e.g. Request ScreenCast portal on Linux).
     /// Returns the negotiated width/height.
     async fn prepare_screen_capture(
@@ -24,6 +43,13 @@ pub trait CaptureBackend: Send + Sync {
         config: &ScreenCaptureConfig,
     ) -> GrabmeResult<(u32, u32)>;
 
+    /// How the cursor reaches the screen track, known once
+    /// [`prepare_screen_capture`](Self::prepare_screen_capture) has run.
+    /// `None` where the backend can't tell.
+    fn cursor_mode(&self) -> Option<CursorCaptureMode> {
+        None
+    }
+
     /// Build the screen capture pipeline.
     fn build_screen_pipeline(
         &self,
@@ -50,13 +76,63 @@ pub trait CaptureBackend: Send + Sync {
         &self,
         output_path: &Path,
         fps: u32,
+        request: WebcamFormatRequest,
     ) -> GrabmeResult<Box<dyn CapturePipeline>>;
 
+    /// Build the auxiliary picture-in-picture pipeline for `source`.
+    fn build_pip_pipeline(
+        &self,
+        _source: &PipSource,
+        _output_path: &Path,
+        _fps: u32,
+    ) -> GrabmeResult<Box<dyn CapturePipeline>> {
+        Err(GrabmeError::unsupported(
+            "Picture-in-picture capture is not supported on this platform",
+        ))
+    }
+
+    /// Build a live view of the captured screen that follows `pointer` and
+    /// feeds a virtual camera. `monitor` is the recorded monitor, when a
+    /// whole monitor is recorded.
+    fn build_virtual_camera_pipeline(
+        &self,
+        _config: &VirtualCameraConfig,
+        _monitor: Option<&MonitorInfo>,
+        _pointer: PointerWatch,
+    ) -> GrabmeResult<Box<dyn CapturePipeline>> {
+        Err(GrabmeError::unsupported(
+            "Virtual camera output is not supported on this platform",
+        ))
+    }
+
     /// Get the stop flag for input tracking if the backend manages it.
     fn get_input_stop_flag(&self) -> Option<Arc<AtomicBool>> {
         None
     }
 
+    /// Geometry lookup used to follow a window the capture is snapped to.
+    ///
+    /// Backends that capture the window itself (or don't support window
+    /// capture) return `None` and no geometry track is recorded.
+    fn window_geometry_probe(&self) -> Option<WindowGeometryProbe> {
+        None
+    }
+
+    /// Send desktop theme and brightness changes to `events` until the
+    /// returned task is aborted. `None` where the platform can't report them.
+    fn watch_appearance(&self, _events: EventSender) -> Option<tokio::task::JoinHandle<()>> {
+        None
+    }
+
+    /// Send screen lock and suspend changes to `events` until the returned
+    /// task is aborted. `None` where the platform can't report them.
+    fn watch_system_session(
+        &self,
+        _events: tokio::sync::mpsc::UnboundedSender<SystemSessionEvent>,
+    ) -> Option<tokio::task::JoinHandle<()>> {
+        None
+    }
+
     /// Get the display server type for metadata.
     fn get_display_server(&self) -> grabme_project_model::project::DisplayServer;
 
@@ -1,8 +1,9 @@
 use std::path::Path;
 
 use grabme_common::error::{GrabmeError, GrabmeResult};
-use grabme_platform_core::MonitorInfo;
+use grabme_platform_core::{split_monitors, MonitorInfo, SystemSessionEvent, WindowGeometry};
 use grabme_platform_windows as platform_windows;
+use grabme_project_model::project::CursorCaptureMode;
 
 use crate::backend::CaptureBackend;
 use crate::pipeline::{
@@ -10,6 +11,7 @@ use crate::pipeline::{
     build_windows_webcam_pipeline, CapturePipeline,
 };
 use crate::session::{CaptureMode, ScreenCaptureConfig};
+use crate::webcam_format::WebcamFormatRequest;
 
 pub struct WindowsBackend {
     selected_monitor_index: Option<usize>,
@@ -42,6 +44,10 @@ impl CaptureBackend for WindowsBackend {
         platform_windows::detect_monitors()
     }
 
+    fn focused_window_geometry(&self) -> GrabmeResult<Option<WindowGeometry>> {
+        platform_windows::focused_window_geometry()
+    }
+
     async fn prepare_screen_capture(
         &mut self,
         config: &ScreenCaptureConfig,
@@ -62,6 +68,11 @@ impl CaptureBackend for WindowsBackend {
         };
 
         let monitors = self.detect_monitors()?;
+        if split_monitors(&monitors, &config.monitor_splits).len() != monitors.len() {
+            return Err(GrabmeError::unsupported(
+                "Monitor splits are not implemented on Windows yet",
+            ));
+        }
         if monitor_index >= monitors.len() {
             return Err(GrabmeError::capture(format!(
                 "Invalid monitor index {monitor_index}. Available monitors: {}",
@@ -74,6 +85,14 @@ impl CaptureBackend for WindowsBackend {
         Ok((monitor.width, monitor.height))
     }
 
+    fn cursor_mode(&self) -> Option<CursorCaptureMode> {
+        Some(if self.hide_cursor {
+            CursorCaptureMode::Hidden
+        } else {
+            CursorCaptureMode::Embedded
+        })
+    }
+
     fn build_screen_pipeline(
         &self,
         output_path: &Path,
@@ -108,8 +127,18 @@ impl CaptureBackend for WindowsBackend {
         &self,
         output_path: &Path,
         fps: u32,
+        request: WebcamFormatRequest,
     ) -> GrabmeResult<Box<dyn CapturePipeline>> {
-        build_windows_webcam_pipeline(output_path, fps)
+        build_windows_webcam_pipeline(output_path, fps, request)
+    }
+
+    fn watch_system_session(
+        &self,
+        events: tokio::sync::mpsc::UnboundedSender<SystemSessionEvent>,
+    ) -> Option<tokio::task::JoinHandle<()>> {
+        Some(platform_windows::spawn_session_watcher(move |event| {
+            events.send(event).is_ok()
+        }))
     }
 
     fn get_display_server(&self) -> grabme_project_model::project::DisplayServer {
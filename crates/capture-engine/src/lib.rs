@@ -22,11 +22,26 @@
 //! └─────────────────────────────────────────────┘
 //! ```
 
+pub mod audio_format;
 pub mod backend;
+pub mod metrics;
+pub mod monitor;
+pub mod pacing;
 pub mod pipeline;
+pub mod preset;
 pub mod session;
+pub mod storage;
+pub mod suspend;
+pub mod virtual_camera;
+pub mod webcam_format;
 
+pub use grabme_platform_core::{split_monitors, DisplayTarget};
+pub use pacing::{plan_frame_pacing, PacingPlan};
+pub use preset::RecordingPreset;
 pub use session::*;
+pub use storage::IoHealth;
+pub use virtual_camera::VirtualCameraConfig;
+pub use webcam_format::WebcamFormatRequest;
 
 /// Detect and return all connected monitors using the platform backend.
 /// Returns monitors in enumeration order — the index in this list corresponds
@@ -35,3 +50,15 @@ pub fn list_monitors() -> grabme_common::error::GrabmeResult<Vec<grabme_platform
 {
     backend::get_backend().detect_monitors()
 }
+
+/// Monitor to record when `--monitor` is not given: the one showing the
+/// focused window, else the primary monitor.
+pub fn default_monitor_index(monitors: &[grabme_platform_core::MonitorInfo]) -> usize {
+    monitor::default_monitor_index(backend::get_backend().as_ref(), monitors)
+}
+
+/// Like [`default_monitor_index`], also saying whether the focused window
+/// decided it.
+pub fn default_monitor(monitors: &[grabme_platform_core::MonitorInfo]) -> monitor::DefaultMonitor {
+    monitor::default_monitor(backend::get_backend().as_ref(), monitors)
+}
@@ -0,0 +1,248 @@
+//! System metrics sampling for `meta/metrics.jsonl`.
+//!
+//! CPU and memory come from procfs. GPU utilisation comes from the DRM
+//! `gpu_busy_percent` attribute (amdgpu, some Intel drivers) or, failing
+//! that, `nvidia-smi`. Where none of these exist the GPU is left out of
+//! the samples rather than reported as idle.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use grabme_common::clock::RecordingClock;
+use grabme_common::error::{GrabmeError, GrabmeResult};
+use grabme_project_model::metrics::SystemMetricsSample;
+
+/// Time between samples.
+pub const METRICS_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Aggregate CPU time counters from the first line of `/proc/stat`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct CpuTimes {
+    busy: u64,
+    total: u64,
+}
+
+/// Parse the aggregate `cpu` line of `/proc/stat`. Idle and iowait count
+/// as not busy.
+fn parse_cpu_times(stat: &str) -> Option<CpuTimes> {
+    let line = stat.lines().find(|line| line.starts_with("cpu "))?;
+    let fields: Vec<u64> = line
+        .split_whitespace()
+        .skip(1)
+        .map(|field| field.parse().ok())
+        .collect::<Option<_>>()?;
+    if fields.len() < 4 {
+        return None;
+    }
+    // user nice system idle iowait irq softirq steal; guest time is
+    // already included in user and nice.
+    let total: u64 = fields.iter().take(8).sum();
+    let idle = fields[3] + fields.get(4).copied().unwrap_or(0);
+    Some(CpuTimes {
+        busy: total.saturating_sub(idle),
+        total,
+    })
+}
+
+/// Busy share between two readings, in percent.
+fn cpu_percent(before: CpuTimes, after: CpuTimes) -> f64 {
+    let total = after.total.saturating_sub(before.total);
+    if total == 0 {
+        return 0.0;
+    }
+    let busy = after.busy.saturating_sub(before.busy);
+    (busy as f64 * 100.0 / total as f64).clamp(0.0, 100.0)
+}
+
+/// Memory in use from `/proc/meminfo`, in percent.
+fn parse_ram_percent(meminfo: &str) -> Option<f64> {
+    let field = |name: &str| {
+        meminfo
+            .lines()
+            .find_map(|line| line.strip_prefix(name)?.strip_prefix(':'))
+            .and_then(|rest| rest.split_whitespace().next())
+            .and_then(|kb| kb.parse::<f64>().ok())
+    };
+    let total = field("MemTotal")?;
+    let available = field("MemAvailable")?;
+    (total > 0.0).then(|| ((total - available) * 100.0 / total).clamp(0.0, 100.0))
+}
+
+/// Where GPU utilisation is read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum GpuSource {
+    Drm(PathBuf),
+    NvidiaSmi,
+}
+
+impl GpuSource {
+    /// The first DRM card reporting utilisation, else `nvidia-smi` when it
+    /// answers.
+    fn detect(drm: &Path) -> Option<Self> {
+        let mut cards: Vec<PathBuf> = std::fs::read_dir(drm)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter(|entry| {
+                let name = entry.file_name();
+                let name = name.to_string_lossy();
+                name.starts_with("card") && !name.contains('-')
+            })
+            .map(|entry| entry.path().join("device").join("gpu_busy_percent"))
+            .filter(|path| path.exists())
+            .collect();
+        cards.sort();
+        if let Some(path) = cards.into_iter().next() {
+            return Some(Self::Drm(path));
+        }
+        query_nvidia_smi().map(|_| Self::NvidiaSmi)
+    }
+
+    fn read(&self) -> Option<f64> {
+        match self {
+            Self::Drm(path) => std::fs::read_to_string(path)
+                .ok()?
+                .trim()
+                .parse::<f64>()
+                .ok(),
+            Self::NvidiaSmi => query_nvidia_smi(),
+        }
+        .map(|percent| percent.clamp(0.0, 100.0))
+    }
+}
+
+/// Utilisation of the first NVIDIA GPU.
+fn query_nvidia_smi() -> Option<f64> {
+    let output = Command::new("nvidia-smi")
+        .args([
+            "--query-gpu=utilization.gpu",
+            "--format=csv,noheader,nounits",
+        ])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .next()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// Reads system usage, keeping the CPU counters between samples.
+struct MetricsProbe {
+    cpu: CpuTimes,
+    gpu: Option<GpuSource>,
+}
+
+impl MetricsProbe {
+    fn new() -> GrabmeResult<Self> {
+        let cpu = read_cpu_times()?;
+        let gpu = GpuSource::detect(Path::new("/sys/class/drm"));
+        if gpu.is_none() {
+            tracing::info!("GPU utilisation is not available; metrics will cover CPU and memory");
+        }
+        Ok(Self { cpu, gpu })
+    }
+
+    fn sample(&mut self, t_ns: u64) -> GrabmeResult<SystemMetricsSample> {
+        let cpu = read_cpu_times()?;
+        let cpu_percent = cpu_percent(self.cpu, cpu);
+        self.cpu = cpu;
+        let ram_percent = std::fs::read_to_string("/proc/meminfo")
+            .ok()
+            .and_then(|meminfo| parse_ram_percent(&meminfo))
+            .unwrap_or(0.0);
+        Ok(SystemMetricsSample {
+            t_ns,
+            cpu_percent,
+            ram_percent,
+            gpu_percent: self.gpu.as_ref().and_then(GpuSource::read),
+        })
+    }
+}
+
+fn read_cpu_times() -> GrabmeResult<CpuTimes> {
+    std::fs::read_to_string("/proc/stat")
+        .ok()
+        .as_deref()
+        .and_then(parse_cpu_times)
+        .ok_or_else(|| GrabmeError::unsupported("System metrics need /proc/stat"))
+}
+
+/// Sample system usage into `path` every [`METRICS_INTERVAL`] until `stop`
+/// is set. Lines are written as they are taken so an interrupted recording
+/// keeps what was sampled.
+pub fn spawn_metrics_sampler(
+    path: &Path,
+    clock: RecordingClock,
+    stop: Arc<AtomicBool>,
+) -> GrabmeResult<JoinHandle<()>> {
+    let mut probe = MetricsProbe::new()?;
+    let mut file = std::fs::File::create(path)
+        .map_err(|e| GrabmeError::capture(format!("Failed to create {}: {e}", path.display())))?;
+
+    Ok(std::thread::spawn(move || {
+        while !stop.load(Ordering::SeqCst) {
+            std::thread::sleep(METRICS_INTERVAL);
+            let sample = match probe.sample(clock.elapsed_ns()) {
+                Ok(sample) => sample,
+                Err(e) => {
+                    tracing::warn!(error = %e, "Stopped sampling system metrics");
+                    return;
+                }
+            };
+            let line = serde_json::to_string(&sample).unwrap_or_default();
+            if let Err(e) = writeln!(file, "{line}") {
+                tracing::warn!(error = %e, "Failed to log system metrics");
+                return;
+            }
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cpu_percent_is_busy_share_between_readings() {
+        let before = parse_cpu_times("cpu  100 0 100 700 100 0 0 0 0 0\ncpu0 1 2 3 4\n").unwrap();
+        let after = parse_cpu_times("cpu  250 0 150 900 100 0 0 0 0 0\n").unwrap();
+        assert_eq!(before.total, 1000);
+        assert_eq!(before.busy, 200);
+        assert!((cpu_percent(before, after) - 50.0).abs() < 1e-9);
+        assert_eq!(cpu_percent(after, after), 0.0);
+        assert_eq!(parse_cpu_times("cpu0 1 2 3 4\n"), None);
+    }
+
+    #[test]
+    fn test_ram_percent_uses_available_memory() {
+        let meminfo = "MemTotal:       16000000 kB\nMemFree:         1000000 kB\n\
+                       MemAvailable:    4000000 kB\n";
+        assert!((parse_ram_percent(meminfo).unwrap() - 75.0).abs() < 1e-9);
+        assert_eq!(parse_ram_percent("MemTotal: 100 kB\n"), None);
+    }
+
+    #[test]
+    fn test_gpu_source_prefers_drm_busy_percent() {
+        let dir = std::env::temp_dir().join("grabme_test_drm_metrics");
+        let _ = std::fs::remove_dir_all(&dir);
+        let device = dir.join("card1").join("device");
+        std::fs::create_dir_all(&device).unwrap();
+        std::fs::write(device.join("gpu_busy_percent"), "37\n").unwrap();
+        std::fs::create_dir_all(dir.join("card1-DP-1")).unwrap();
+
+        let source = GpuSource::detect(&dir).unwrap();
+        assert_eq!(source, GpuSource::Drm(device.join("gpu_busy_percent")));
+        assert_eq!(source.read(), Some(37.0));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,127 @@
+//! Default monitor selection.
+//!
+//! When no monitor is requested explicitly, record the one the user is
+//! working on: the monitor showing most of the focused window.
+
+use grabme_platform_core::{MonitorInfo, WindowGeometry};
+
+use crate::backend::CaptureBackend;
+
+/// Index of the monitor overlapping the largest area of `window`.
+///
+/// Falls back to the monitor containing the window centre when the window
+/// overlaps no monitor (e.g. a stale or off-screen geometry).
+pub fn monitor_index_for_window(monitors: &[MonitorInfo], window: WindowGeometry) -> Option<usize> {
+    let overlap = |m: &MonitorInfo| {
+        let left = window.x.max(m.x) as i64;
+        let top = window.y.max(m.y) as i64;
+        let right = (window.x as i64 + window.width as i64).min(m.x as i64 + m.width as i64);
+        let bottom = (window.y as i64 + window.height as i64).min(m.y as i64 + m.height as i64);
+        (right - left).max(0) * (bottom - top).max(0)
+    };
+
+    let best = monitors
+        .iter()
+        .enumerate()
+        .map(|(idx, m)| (idx, overlap(m)))
+        .filter(|(_, area)| *area > 0)
+        .max_by_key(|(_, area)| *area)
+        .map(|(idx, _)| idx);
+    if best.is_some() {
+        return best;
+    }
+
+    let cx = window.x as i64 + window.width as i64 / 2;
+    let cy = window.y as i64 + window.height as i64 / 2;
+    monitors.iter().position(|m| {
+        cx >= m.x as i64
+            && cx < m.x as i64 + m.width as i64
+            && cy >= m.y as i64
+            && cy < m.y as i64 + m.height as i64
+    })
+}
+
+/// Monitor picked when none was requested, and how it was picked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DefaultMonitor {
+    pub index: usize,
+    /// The focused window was found on this monitor. False when the lookup
+    /// failed or matched no monitor and the primary monitor was used.
+    pub from_focus: bool,
+}
+
+/// Monitor to record when none was requested.
+///
+/// Prefers the monitor showing the focused window, then the primary monitor,
+/// then index 0.
+pub fn default_monitor(backend: &dyn CaptureBackend, monitors: &[MonitorInfo]) -> DefaultMonitor {
+    let focused = match backend.focused_window_geometry() {
+        Ok(geometry) => geometry,
+        Err(err) => {
+            tracing::debug!(error = %err, "Focused window query failed; using primary monitor");
+            None
+        }
+    };
+
+    match focused.and_then(|window| monitor_index_for_window(monitors, window)) {
+        Some(index) => DefaultMonitor {
+            index,
+            from_focus: true,
+        },
+        None => DefaultMonitor {
+            index: monitors.iter().position(|m| m.primary).unwrap_or(0),
+            from_focus: false,
+        },
+    }
+}
+
+/// Index of [`default_monitor`].
+pub fn default_monitor_index(backend: &dyn CaptureBackend, monitors: &[MonitorInfo]) -> usize {
+    default_monitor(backend, monitors).index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(name: &str, x: i32, width: u32, primary: bool) -> MonitorInfo {
+        MonitorInfo {
+            name: name.to_string(),
+            width,
+            height: 1080,
+            x,
+            y: 0,
+            scale_factor: 1.0,
+            refresh_rate_hz: 60,
+            primary,
+        }
+    }
+
+    #[test]
+    fn picks_monitor_with_largest_overlap() {
+        let monitors = vec![
+            monitor("left", -1920, 1920, false),
+            monitor("main", 0, 2560, true),
+        ];
+        // Straddles the seam, mostly on the left monitor.
+        let window = WindowGeometry {
+            x: -1500,
+            y: 100,
+            width: 1600,
+            height: 800,
+        };
+        assert_eq!(monitor_index_for_window(&monitors, window), Some(0));
+    }
+
+    #[test]
+    fn off_screen_window_matches_no_monitor() {
+        let monitors = vec![monitor("main", 0, 1920, true)];
+        let window = WindowGeometry {
+            x: 5000,
+            y: 5000,
+            width: 100,
+            height: 100,
+        };
+        assert_eq!(monitor_index_for_window(&monitors, window), None);
+    }
+}
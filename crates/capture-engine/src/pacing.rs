@@ -0,0 +1,165 @@
+//! Frame pacing against monitor refresh rates.
+//!
+//! A capture rate that doesn't divide the panel refresh rate repeats frames
+//! unevenly. On X11 the whole virtual desktop is captured, so a mixed
+//! 144Hz/60Hz layout can judder on one monitor whatever rate is chosen; the
+//! recorded monitor takes priority.
+
+use grabme_platform_core::MonitorInfo;
+use grabme_project_model::project::FramePacing;
+
+/// Lowest frame rate auto-adjustment will pick.
+const MIN_ADJUSTED_FPS: u32 = 24;
+
+/// Outcome of [`plan_frame_pacing`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PacingPlan {
+    /// Frame rate to capture at.
+    pub fps: u32,
+    /// Decision to persist in `recording.frame_pacing`.
+    pub pacing: FramePacing,
+    /// Human-readable problems, empty when pacing is clean.
+    pub warnings: Vec<String>,
+}
+
+/// Check `fps` against the refresh rates of `selected` and the rest of
+/// `monitors`, optionally switching to a rate that divides the selected
+/// monitor's refresh rate.
+pub fn plan_frame_pacing(
+    monitors: &[MonitorInfo],
+    selected: Option<&MonitorInfo>,
+    fps: u32,
+    auto_adjust: bool,
+) -> PacingPlan {
+    let monitor_refresh_hz = selected
+        .or_else(|| monitors.iter().find(|m| m.primary))
+        .or(monitors.first())
+        .map(|m| m.refresh_rate_hz)
+        .unwrap_or(0);
+    let mut desktop_refresh_hz: Vec<u32> = monitors
+        .iter()
+        .map(|m| m.refresh_rate_hz)
+        .filter(|hz| *hz > 0)
+        .collect();
+    desktop_refresh_hz.sort_unstable();
+    desktop_refresh_hz.dedup();
+
+    let mut warnings = Vec::new();
+    let mut chosen = fps;
+    let mut adjusted = false;
+
+    if !paces_evenly(fps, monitor_refresh_hz) {
+        match adjusted_fps(fps, monitor_refresh_hz).filter(|_| auto_adjust) {
+            Some(better) => {
+                warnings.push(format!(
+                    "{fps}fps does not divide the {monitor_refresh_hz}Hz refresh rate; recording at {better}fps instead"
+                ));
+                chosen = better;
+                adjusted = true;
+            }
+            None => warnings.push(format!(
+                "{fps}fps does not divide the {monitor_refresh_hz}Hz refresh rate; expect uneven frame pacing{}",
+                adjusted_fps(fps, monitor_refresh_hz)
+                    .map(|better| format!(" (try --fps {better} or --auto-fps)"))
+                    .unwrap_or_default()
+            )),
+        }
+    }
+
+    let uneven_elsewhere: Vec<u32> = desktop_refresh_hz
+        .iter()
+        .copied()
+        .filter(|hz| *hz != monitor_refresh_hz && !paces_evenly(chosen, *hz))
+        .collect();
+    if !uneven_elsewhere.is_empty() {
+        let rates: Vec<String> = uneven_elsewhere
+            .iter()
+            .map(|hz| format!("{hz}Hz"))
+            .collect();
+        warnings.push(format!(
+            "mixed refresh rates: content on the {} monitor(s) will be unevenly paced at {chosen}fps",
+            rates.join("/")
+        ));
+    }
+
+    PacingPlan {
+        fps: chosen,
+        pacing: FramePacing {
+            requested_fps: fps,
+            monitor_refresh_hz,
+            desktop_refresh_hz,
+            adjusted,
+            uneven: !paces_evenly(chosen, monitor_refresh_hz) || !uneven_elsewhere.is_empty(),
+        },
+        warnings,
+    }
+}
+
+/// Whether every captured frame maps to the same number of refreshes (or
+/// vice versa). Unknown refresh rates are assumed fine.
+fn paces_evenly(fps: u32, refresh_hz: u32) -> bool {
+    fps == 0 || refresh_hz == 0 || refresh_hz % fps == 0 || fps % refresh_hz == 0
+}
+
+/// Divisor of `refresh_hz` closest to `fps`, preferring the higher rate on a
+/// tie, or `None` when nothing at or above [`MIN_ADJUSTED_FPS`] exists.
+fn adjusted_fps(fps: u32, refresh_hz: u32) -> Option<u32> {
+    (1..=refresh_hz)
+        .filter(|divisor| refresh_hz % divisor == 0)
+        .map(|divisor| refresh_hz / divisor)
+        .filter(|rate| *rate >= MIN_ADJUSTED_FPS)
+        .min_by_key(|rate| (rate.abs_diff(fps), u32::MAX - rate))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(name: &str, refresh_rate_hz: u32, primary: bool) -> MonitorInfo {
+        MonitorInfo {
+            name: name.to_string(),
+            width: 1920,
+            height: 1080,
+            x: 0,
+            y: 0,
+            scale_factor: 1.0,
+            refresh_rate_hz,
+            primary,
+        }
+    }
+
+    #[test]
+    fn test_plan_warns_on_mismatch_without_adjusting() {
+        let monitors = vec![monitor("DP-1", 144, true), monitor("HDMI-1", 60, false)];
+        let plan = plan_frame_pacing(&monitors, Some(&monitors[0]), 60, false);
+
+        assert_eq!(plan.fps, 60);
+        assert!(plan.pacing.uneven);
+        assert!(!plan.pacing.adjusted);
+        assert_eq!(plan.pacing.desktop_refresh_hz, vec![60, 144]);
+        assert!(plan.warnings[0].contains("try --fps 72"));
+    }
+
+    #[test]
+    fn test_plan_auto_adjusts_to_refresh_divisor() {
+        let monitors = vec![monitor("DP-1", 144, true), monitor("HDMI-1", 60, false)];
+        let plan = plan_frame_pacing(&monitors, Some(&monitors[0]), 60, true);
+
+        assert_eq!(plan.fps, 72);
+        assert!(plan.pacing.adjusted);
+        assert_eq!(plan.pacing.requested_fps, 60);
+        // 72fps still can't pace the 60Hz secondary evenly.
+        assert!(plan.pacing.uneven);
+        assert!(plan.warnings[1].starts_with("mixed refresh rates: content on the 60Hz"));
+    }
+
+    #[test]
+    fn test_plan_is_clean_for_matching_rates() {
+        let monitors = vec![monitor("DP-1", 120, true), monitor("HDMI-1", 60, false)];
+        let plan = plan_frame_pacing(&monitors, Some(&monitors[0]), 60, true);
+
+        assert_eq!(plan.fps, 60);
+        assert!(!plan.pacing.uneven);
+        assert!(plan.warnings.is_empty());
+    }
+}
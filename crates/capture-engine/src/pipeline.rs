@@ -3,15 +3,52 @@
 //! This module will contain the actual GStreamer pipeline setup.
 //! For now it defines the trait interface that the session uses.
 
+use std::io::Write;
 use std::path::Path;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Arc, OnceLock};
-use std::time::Duration;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{mpsc, Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
 
+use grabme_common::clock::RecordingClock;
 use grabme_common::error::{GrabmeError, GrabmeResult};
+use grabme_input_tracker::PointerWatch;
+use grabme_platform_core::DisplayServer;
+use grabme_project_model::frame_times::FrameTimeSample;
+use grabme_project_model::project::{AudioFormat, ColorRange, VideoFormat};
 use gst::prelude::*;
 use gstreamer as gst;
 
+use crate::audio_format::{negotiate_audio_format, probe_default_device_caps};
+use crate::virtual_camera::{FollowCamera, VirtualCameraConfig, VirtualCameraSink};
+use crate::webcam_format::{negotiate_webcam_mode, probe_v4l2_modes, WebcamFormatRequest};
+
+/// Colorimetry forced on screen capture caps before encoding.
+///
+/// `bt709` is BT.709 primaries/matrix at limited range; pinning it keeps
+/// `videoconvert` from picking a range based on the source format.
+const SCREEN_COLORIMETRY: &str = "bt709";
+
+/// Frames a capture source may queue ahead of the encoder.
+static SOURCE_QUEUE_BUFFERS: AtomicU32 = AtomicU32::new(200);
+
+/// Let capture sources queue at most `buffers` frames ahead of the
+/// encoder in pipelines built from now on. Fewer frames use less memory
+/// but drop sooner when the encoder stalls.
+pub fn set_source_queue_buffers(buffers: u32) {
+    SOURCE_QUEUE_BUFFERS.store(buffers.max(1), Ordering::Relaxed);
+}
+
+/// Leaky queue between a capture source and the rest of its pipeline.
+fn source_queue() -> String {
+    format!(
+        "queue max-size-buffers={} leaky=downstream",
+        SOURCE_QUEUE_BUFFERS.load(Ordering::Relaxed)
+    )
+}
+
+/// Range of the encoded screen track, matching [`SCREEN_COLORIMETRY`].
+pub const SCREEN_COLOR_RANGE: ColorRange = ColorRange::Limited;
+
 /// Trait for a media capture pipeline.
 ///
 /// Implementations will wrap GStreamer or FFmpeg pipelines
@@ -34,6 +71,52 @@ pub trait CapturePipeline: Send {
 
     /// Get pipeline statistics.
     fn stats(&self) -> PipelineStats;
+
+    /// Times (ns of pipeline running time) at which a lost input device was
+    /// replaced by the default device. Only audio pipelines report these.
+    fn device_fallbacks_ns(&self) -> Vec<u64> {
+        Vec::new()
+    }
+
+    /// Format the pipeline records audio in, as negotiated with the device.
+    /// Only audio pipelines report one.
+    fn audio_format(&self) -> Option<AudioFormat> {
+        None
+    }
+
+    /// Frame size and rate the pipeline records video in, as negotiated
+    /// with the device. Only webcam pipelines report one.
+    fn video_format(&self) -> Option<VideoFormat> {
+        None
+    }
+
+    /// Spans (ns since pipeline start) during which the input device was
+    /// missing and the track holds filler frames. Only the webcam reports
+    /// these.
+    fn device_gaps_ns(&self) -> Vec<(u64, u64)> {
+        Vec::new()
+    }
+
+    /// Times (ns since pipeline start) at which the captured display
+    /// changed size, with the new width and height. Only the PipeWire
+    /// screen pipeline reports these.
+    fn resolution_changes_ns(&self) -> Vec<(u64, u32, u32)> {
+        Vec::new()
+    }
+
+    /// Append the PTS and `clock` time of the first captured frame and of
+    /// every `interval_frames`-th one after it to `path`. Only screen
+    /// pipelines support this.
+    fn log_frame_times(
+        &mut self,
+        _path: &Path,
+        _interval_frames: u64,
+        _clock: RecordingClock,
+    ) -> GrabmeResult<()> {
+        Err(GrabmeError::unsupported(
+            "Frame time logging is only supported on screen pipelines",
+        ))
+    }
 }
 
 /// Runtime statistics from a capture pipeline.
@@ -211,12 +294,84 @@ impl CapturePipeline for GstCapturePipeline {
     fn stats(&self) -> PipelineStats {
         self.stats.clone()
     }
+
+    fn log_frame_times(
+        &mut self,
+        path: &Path,
+        interval_frames: u64,
+        clock: RecordingClock,
+    ) -> GrabmeResult<()> {
+        watch_frame_times(&self.pipeline, path, interval_frames, clock)
+    }
 }
 
+/// Picks the source frames logged to `frame-times.jsonl`.
+struct FrameTimesSampler {
+    interval_frames: u64,
+    frames: u64,
+}
+
+impl FrameTimesSampler {
+    fn new(interval_frames: u64) -> Self {
+        Self {
+            interval_frames: interval_frames.max(1),
+            frames: 0,
+        }
+    }
+
+    /// Count a frame, returning its sample when it is due.
+    fn observe(&mut self, pts_ns: u64, wall_ns: u64) -> Option<FrameTimeSample> {
+        self.frames += 1;
+        ((self.frames - 1) % self.interval_frames == 0).then_some(FrameTimeSample {
+            frame: self.frames,
+            pts_ns,
+            wall_ns,
+        })
+    }
+}
+
+/// Log frames leaving the `screen_src` element, before `videorate` evens
+/// out their timing.
+fn watch_frame_times(
+    pipeline: &gst::Pipeline,
+    path: &Path,
+    interval_frames: u64,
+    clock: RecordingClock,
+) -> GrabmeResult<()> {
+    let pad = pipeline
+        .by_name("screen_src")
+        .and_then(|source| source.static_pad("src"))
+        .ok_or_else(|| GrabmeError::capture("Screen pipeline has no screen_src pad to watch"))?;
+    let file = std::fs::File::create(path)
+        .map_err(|e| GrabmeError::capture(format!("Failed to create {}: {e}", path.display())))?;
+
+    let log = Mutex::new((FrameTimesSampler::new(interval_frames), file));
+    pad.add_probe(gst::PadProbeType::BUFFER, move |_pad, info| {
+        let Some(pts) = info.buffer().and_then(|buffer| buffer.pts()) else {
+            return gst::PadProbeReturn::Ok;
+        };
+        let wall_ns = clock.elapsed_ns();
+        let mut log = log.lock().unwrap();
+        let (sampler, file) = &mut *log;
+        if let Some(sample) = sampler.observe(pts.nseconds(), wall_ns) {
+            let line = serde_json::to_string(&sample).unwrap_or_default();
+            if let Err(e) = writeln!(file, "{line}") {
+                tracing::warn!(error = %e, "Failed to log frame time");
+            }
+        }
+        gst::PadProbeReturn::Ok
+    });
+    Ok(())
+}
+
+/// `crop` trims `(left, top, right, bottom)` pixels off the stream before
+/// encoding, for recording one region of a monitor split with
+/// `monitor_splits`.
 pub fn build_screen_pipeline(
     pipewire_node_id: u32,
     output_path: &Path,
     fps: u32,
+    crop: Option<(u32, u32, u32, u32)>,
 ) -> GrabmeResult<Box<dyn CapturePipeline>> {
     let path = escape_path(output_path);
     // `keyframe-interval` = fps * 2 seconds: one keyframe every 2 seconds allows
@@ -224,12 +379,171 @@ pub fn build_screen_pipeline(
     let keyint = fps.saturating_mul(2).max(2);
     // queue elements decouple the capture source from the encoder so that
     // encoder stalls don't cause dropped frames at the source.
+    let caps =
+        format!("video/x-raw,format=I420,colorimetry={SCREEN_COLORIMETRY},framerate={fps}/1");
+    let crop = screen_crop_fragment(crop);
+    // The size filter is pinned to the first negotiated size once the stream
+    // starts; see `watch_screen_size`.
+    let source_queue = source_queue();
     let launch = format!(
-        "pipewiresrc path={pipewire_node_id} do-timestamp=true ! queue max-size-buffers=200 leaky=downstream ! videoconvert ! videorate ! video/x-raw,framerate={fps}/1 ! queue max-size-buffers=8 ! x264enc tune=zerolatency speed-preset=veryfast key-int-max={keyint} ! h264parse ! queue max-size-buffers=8 ! matroskamux ! filesink location=\"{path}\""
+        "pipewiresrc name=screen_src path={pipewire_node_id} do-timestamp=true ! {source_queue} ! videoconvert !{crop} videoscale add-borders=true ! videorate ! capsfilter name=screen_size caps=\"{caps}\" ! queue max-size-buffers=8 ! x264enc tune=zerolatency speed-preset=veryfast key-int-max={keyint} ! h264parse ! queue max-size-buffers=8 ! matroskamux ! filesink location=\"{path}\""
     );
-    Ok(Box::new(GstCapturePipeline::from_launch(
-        "screen", &launch,
-    )?))
+    let inner = GstCapturePipeline::from_launch("screen", &launch)?;
+    let state = Arc::new(ScreenSizeState::default());
+    watch_screen_size(&inner.pipeline, &caps, &state)?;
+    Ok(Box::new(ScreenSizePipeline { inner, state }))
+}
+
+/// PipeWire screen pipeline that records display resolution changes.
+///
+/// A stream renegotiating to a new size (a game switching to fullscreen)
+/// can't be muxed into the same track, so the track keeps the first size
+/// and later frames are scaled into it with borders. Each change is kept
+/// so export can map input coordinates onto the scaled picture.
+pub struct ScreenSizePipeline {
+    inner: GstCapturePipeline,
+    state: Arc<ScreenSizeState>,
+}
+
+#[derive(Default)]
+struct ScreenSizeState {
+    started_at: Mutex<Option<Instant>>,
+    size: Mutex<Option<(u32, u32)>>,
+    /// Changes in ns since pipeline start, with the new size.
+    changes_ns: Mutex<Vec<(u64, u32, u32)>>,
+}
+
+/// How a negotiated size relates to the ones before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SizeObservation {
+    First,
+    Unchanged,
+    Changed,
+}
+
+impl ScreenSizeState {
+    fn elapsed_ns(&self) -> u64 {
+        self.started_at
+            .lock()
+            .unwrap()
+            .map(|started| started.elapsed().as_nanos() as u64)
+            .unwrap_or(0)
+    }
+
+    fn observe(&self, width: u32, height: u32, now_ns: u64) -> SizeObservation {
+        let mut size = self.size.lock().unwrap();
+        let observation = match *size {
+            None => SizeObservation::First,
+            Some(last) if last == (width, height) => return SizeObservation::Unchanged,
+            Some(_) => {
+                self.changes_ns
+                    .lock()
+                    .unwrap()
+                    .push((now_ns, width, height));
+                SizeObservation::Changed
+            }
+        };
+        *size = Some((width, height));
+        observation
+    }
+}
+
+/// Watch the caps leaving the PipeWire source. The first size pins the
+/// `screen_size` filter; later sizes are logged as changes.
+fn watch_screen_size(
+    pipeline: &gst::Pipeline,
+    base_caps: &str,
+    state: &Arc<ScreenSizeState>,
+) -> GrabmeResult<()> {
+    let (Some(source), Some(size_filter)) = (
+        pipeline.by_name("screen_src"),
+        pipeline.by_name("screen_size"),
+    ) else {
+        return Err(GrabmeError::capture(
+            "Screen pipeline is missing its source or size filter",
+        ));
+    };
+    let pad = source
+        .static_pad("src")
+        .ok_or_else(|| GrabmeError::capture("Screen source has no src pad"))?;
+
+    let state = Arc::clone(state);
+    let base_caps = base_caps.to_string();
+    pad.add_probe(gst::PadProbeType::EVENT_DOWNSTREAM, move |_pad, info| {
+        let Some(gst::PadProbeData::Event(event)) = &info.data else {
+            return gst::PadProbeReturn::Ok;
+        };
+        let gst::EventView::Caps(caps) = event.view() else {
+            return gst::PadProbeReturn::Ok;
+        };
+        let Some((width, height)) = caps.caps().structure(0).and_then(|structure| {
+            let width = structure.get::<i32>("width").ok()?;
+            let height = structure.get::<i32>("height").ok()?;
+            (width > 0 && height > 0).then_some((width as u32, height as u32))
+        }) else {
+            return gst::PadProbeReturn::Ok;
+        };
+
+        match state.observe(width, height, state.elapsed_ns()) {
+            SizeObservation::First => {
+                let pinned =
+                    format!("{base_caps},width={width},height={height},pixel-aspect-ratio=1/1");
+                match pinned.parse::<gst::Caps>() {
+                    Ok(pinned) => size_filter.set_property("caps", &pinned),
+                    Err(e) => tracing::warn!(error = %e, "Failed to pin screen track size"),
+                }
+            }
+            SizeObservation::Changed => tracing::warn!(
+                width,
+                height,
+                "Display resolution changed; scaling frames to the recorded size"
+            ),
+            SizeObservation::Unchanged => {}
+        }
+        gst::PadProbeReturn::Ok
+    });
+    Ok(())
+}
+
+impl CapturePipeline for ScreenSizePipeline {
+    fn start(&mut self) -> GrabmeResult<()> {
+        // Set before the pipeline plays so the first caps event sees it.
+        *self.state.started_at.lock().unwrap() = Some(Instant::now());
+        self.inner.start()
+    }
+
+    fn stop(&mut self) -> GrabmeResult<()> {
+        self.inner.stop()
+    }
+
+    fn pause(&mut self) -> GrabmeResult<()> {
+        self.inner.pause()
+    }
+
+    fn resume(&mut self) -> GrabmeResult<()> {
+        self.inner.resume()
+    }
+
+    fn is_running(&self) -> bool {
+        self.inner.is_running()
+    }
+
+    fn stats(&self) -> PipelineStats {
+        self.inner.stats()
+    }
+
+    fn resolution_changes_ns(&self) -> Vec<(u64, u32, u32)> {
+        self.state.changes_ns.lock().unwrap().clone()
+    }
+
+    fn log_frame_times(
+        &mut self,
+        path: &Path,
+        interval_frames: u64,
+        clock: RecordingClock,
+    ) -> GrabmeResult<()> {
+        self.inner.log_frame_times(path, interval_frames, clock)
+    }
 }
 
 pub fn build_x11_screen_pipeline(
@@ -237,22 +551,66 @@ pub fn build_x11_screen_pipeline(
     fps: u32,
     hide_cursor: bool,
     capture_region: Option<(i32, i32, u32, u32)>,
+    display_name: Option<&str>,
+) -> GrabmeResult<Box<dyn CapturePipeline>> {
+    build_x11_region_pipeline(
+        "screen-x11",
+        output_path,
+        fps,
+        hide_cursor,
+        capture_region,
+        display_name,
+    )
+}
+
+/// Record one monitor as the auxiliary picture-in-picture track on X11.
+pub fn build_x11_pip_pipeline(
+    output_path: &Path,
+    fps: u32,
+    monitor_region: (i32, i32, u32, u32),
+    display_name: Option<&str>,
+) -> GrabmeResult<Box<dyn CapturePipeline>> {
+    build_x11_region_pipeline(
+        "pip-x11",
+        output_path,
+        fps,
+        false,
+        Some(monitor_region),
+        display_name,
+    )
+}
+
+fn build_x11_region_pipeline(
+    name: &str,
+    output_path: &Path,
+    fps: u32,
+    hide_cursor: bool,
+    capture_region: Option<(i32, i32, u32, u32)>,
+    display_name: Option<&str>,
 ) -> GrabmeResult<Box<dyn CapturePipeline>> {
     let path = escape_path(output_path);
     let show_pointer = if hide_cursor { "false" } else { "true" };
     let region = x11_capture_region_fragment(capture_region)?;
+    let display = x11_display_fragment(display_name);
     let keyint = fps.saturating_mul(2).max(2);
     // `remote=true` allows ximagesrc to work correctly with certain remote X11
     // setups. `use-damage=false` ensures full frame delivery (no incremental
     // damage updates that can miss regions). queue leaky=downstream prevents
     // buffer build-up when the encoder is momentarily slow.
+    let source_queue = source_queue();
     let launch = format!(
-        "ximagesrc use-damage=false remote=true show-pointer={show_pointer}{region} ! queue max-size-buffers=200 leaky=downstream ! videoconvert ! videorate ! video/x-raw,framerate={fps}/1 ! queue max-size-buffers=8 ! x264enc tune=zerolatency speed-preset=veryfast key-int-max={keyint} ! h264parse ! queue max-size-buffers=8 ! matroskamux ! filesink location=\"{path}\""
+        "ximagesrc name=screen_src use-damage=false remote=true show-pointer={show_pointer}{display}{region} ! {source_queue} ! videoconvert ! videorate ! video/x-raw,format=I420,colorimetry={SCREEN_COLORIMETRY},framerate={fps}/1 ! queue max-size-buffers=8 ! x264enc tune=zerolatency speed-preset=veryfast key-int-max={keyint} ! h264parse ! queue max-size-buffers=8 ! matroskamux ! filesink location=\"{path}\""
     );
-    Ok(Box::new(GstCapturePipeline::from_launch(
-        "screen-x11",
-        &launch,
-    )?))
+    Ok(Box::new(GstCapturePipeline::from_launch(name, &launch)?))
+}
+
+fn screen_crop_fragment(crop: Option<(u32, u32, u32, u32)>) -> String {
+    match crop {
+        Some((left, top, right, bottom)) => {
+            format!(" videocrop left={left} top={top} right={right} bottom={bottom} !")
+        }
+        None => String::new(),
+    }
 }
 
 fn x11_capture_region_fragment(
@@ -283,41 +641,253 @@ fn x11_capture_region_fragment(
     Ok(format!(" startx={x} starty={y} endx={endx} endy={endy}"))
 }
 
+/// `display-name` for ximagesrc; without it the element opens `$DISPLAY`.
+fn x11_display_fragment(display_name: Option<&str>) -> String {
+    display_name
+        .map(|name| format!(" display-name=\"{}\"", name.replace('"', "")))
+        .unwrap_or_default()
+}
+
 pub fn build_mic_pipeline(
     output_path: &Path,
     sample_rate: u32,
 ) -> GrabmeResult<Box<dyn CapturePipeline>> {
-    let path = escape_path(output_path);
-    let launch = format!(
-        "pipewiresrc do-timestamp=true ! audioconvert ! audioresample ! audio/x-raw,rate={sample_rate} ! wavenc ! filesink location=\"{path}\""
-    );
-    Ok(Box::new(GstCapturePipeline::from_launch("mic", &launch)?))
+    build_resilient_audio_pipeline(
+        "mic",
+        "pipewiresrc do-timestamp=true",
+        INPUT_DEVICE_CLASS,
+        output_path,
+        sample_rate,
+    )
 }
 
 pub fn build_x11_mic_pipeline(
     output_path: &Path,
     sample_rate: u32,
 ) -> GrabmeResult<Box<dyn CapturePipeline>> {
-    let path = escape_path(output_path);
-    let launch = format!(
-        "pulsesrc do-timestamp=true ! audioconvert ! audioresample ! audio/x-raw,rate={sample_rate} ! wavenc ! filesink location=\"{path}\""
-    );
-    Ok(Box::new(GstCapturePipeline::from_launch(
-        "mic-x11", &launch,
-    )?))
+    build_resilient_audio_pipeline(
+        "mic-x11",
+        "pulsesrc do-timestamp=true",
+        INPUT_DEVICE_CLASS,
+        output_path,
+        sample_rate,
+    )
 }
 
 pub fn build_system_audio_pipeline(
     output_path: &Path,
     sample_rate: u32,
+) -> GrabmeResult<Box<dyn CapturePipeline>> {
+    build_resilient_audio_pipeline(
+        "system",
+        "pipewiresrc do-timestamp=true stream-properties=props,media.class=Audio/Source",
+        OUTPUT_DEVICE_CLASS,
+        output_path,
+        sample_rate,
+    )
+}
+
+/// Device classes probed for supported formats: microphones, and the
+/// output whose monitor is recorded as system audio.
+const INPUT_DEVICE_CLASS: &str = "Audio/Source";
+const OUTPUT_DEVICE_CLASS: &str = "Audio/Sink";
+/// Upper bound on device swaps per pipeline, so a missing default device
+/// doesn't turn into a tight error/retry loop.
+const MAX_DEVICE_FALLBACKS: usize = 8;
+
+/// Audio capture pipeline that survives input device loss.
+///
+/// The device branch feeds an `audiomixer` next to a live silence source, so
+/// a dead device leaves silence in the file instead of ending it. When the
+/// device branch posts an error it is swapped for a fresh branch on the
+/// default device and the switch time, in pipeline running time, is kept as
+/// a fallback marker so it lines up with the recorded audio.
+pub struct ResilientAudioPipeline {
+    inner: GstCapturePipeline,
+    fallback: Arc<DeviceFallbackState>,
+    format: AudioFormat,
+}
+
+#[derive(Default)]
+struct DeviceFallbackState {
+    device_bin: Mutex<Option<gst::Bin>>,
+    markers_ns: Mutex<Vec<u64>>,
+}
+
+fn build_resilient_audio_pipeline(
+    name: &str,
+    source: &str,
+    device_class: &str,
+    output_path: &Path,
+    sample_rate: u32,
 ) -> GrabmeResult<Box<dyn CapturePipeline>> {
     let path = escape_path(output_path);
+    let format = negotiate_audio_format(sample_rate, &probe_default_device_caps(device_class));
+    if format.sample_rate != sample_rate {
+        tracing::info!(
+            pipeline = name,
+            sample_rate = format.sample_rate,
+            channels = format.channels,
+            "Recording audio in the device's format"
+        );
+    }
+    let caps = format!(
+        "audio/x-raw,rate={},channels={}",
+        format.sample_rate, format.channels
+    );
     let launch = format!(
-        "pipewiresrc do-timestamp=true stream-properties=props,media.class=Audio/Source ! audioconvert ! audioresample ! audio/x-raw,rate={sample_rate} ! wavenc ! filesink location=\"{path}\""
+        "audiomixer name=mix ! audioconvert ! audioresample ! {caps} ! wavenc ! filesink location=\"{path}\" \
+         audiotestsrc is-live=true wave=silence ! audioconvert ! audioresample ! {caps} ! mix."
     );
-    Ok(Box::new(GstCapturePipeline::from_launch(
-        "system", &launch,
-    )?))
+    let device_desc = format!("{source} ! audioconvert ! audioresample ! {caps} ! queue");
+
+    let inner = GstCapturePipeline::from_launch(name, &launch)?;
+    let fallback = Arc::new(DeviceFallbackState::default());
+    attach_device_branch(&inner.pipeline, &device_desc, &fallback.device_bin)?;
+
+    let (tx, rx) = mpsc::channel::<()>();
+    if let Some(bus) = inner.pipeline.bus() {
+        let state = Arc::clone(&fallback);
+        bus.set_sync_handler(move |_bus, msg| {
+            let gst::MessageView::Error(err) = msg.view() else {
+                return gst::BusSyncReply::Pass;
+            };
+            let from_device = match (msg.src(), state.device_bin.lock().unwrap().as_ref()) {
+                (Some(src), Some(bin)) => src.has_as_ancestor(bin),
+                _ => false,
+            };
+            if !from_device {
+                return gst::BusSyncReply::Pass;
+            }
+            tracing::warn!(error = %err.error(), "Audio input device lost; falling back to default device");
+            let _ = tx.send(());
+            // Handled here: keep it off the bus so the pipeline isn't torn down.
+            gst::BusSyncReply::Drop
+        });
+    }
+
+    let pipeline = inner.pipeline.clone();
+    let state = Arc::clone(&fallback);
+    let pipeline_name = name.to_string();
+    std::thread::spawn(move || {
+        while rx.recv().is_ok() {
+            // Running time is what the recorded buffers are stamped with,
+            // and it stops while the pipeline is paused.
+            let elapsed_ns = pipeline
+                .current_running_time()
+                .map(|t| t.nseconds())
+                .unwrap_or(0);
+            let swaps = {
+                let mut markers = state.markers_ns.lock().unwrap();
+                markers.push(elapsed_ns);
+                markers.len()
+            };
+
+            detach_device_branch(&pipeline, &state.device_bin);
+            if swaps > MAX_DEVICE_FALLBACKS {
+                tracing::warn!(pipeline = %pipeline_name, "Too many audio device failures; recording silence");
+                continue;
+            }
+            match attach_device_branch(&pipeline, &device_desc, &state.device_bin) {
+                Ok(()) => {
+                    tracing::info!(pipeline = %pipeline_name, at_ns = elapsed_ns, "Audio capture switched to default device")
+                }
+                Err(err) => {
+                    tracing::warn!(pipeline = %pipeline_name, error = %err, "Default audio device unavailable; recording silence")
+                }
+            }
+        }
+    });
+
+    Ok(Box::new(ResilientAudioPipeline {
+        inner,
+        fallback,
+        format,
+    }))
+}
+
+/// Build a device branch from `desc`, link it into the mixer, and bring it
+/// up to the pipeline's current state.
+fn attach_device_branch(
+    pipeline: &gst::Pipeline,
+    desc: &str,
+    device_bin: &Mutex<Option<gst::Bin>>,
+) -> GrabmeResult<()> {
+    let bin = gst::parse::bin_from_description(desc, true)
+        .map_err(|e| GrabmeError::capture(format!("Failed to build device branch: {e}")))?;
+    let mixer = pipeline
+        .by_name("mix")
+        .ok_or_else(|| GrabmeError::capture("Capture pipeline has no mixer"))?;
+
+    pipeline
+        .add(&bin)
+        .map_err(|e| GrabmeError::capture(format!("Failed to add device branch: {e}")))?;
+    let started = bin
+        .link(&mixer)
+        .map_err(|e| GrabmeError::capture(format!("Failed to link device branch: {e}")))
+        .and_then(|_| {
+            bin.sync_state_with_parent()
+                .map_err(|e| GrabmeError::capture(format!("Failed to start device branch: {e}")))
+        });
+
+    *device_bin.lock().unwrap() = Some(bin);
+    if started.is_err() {
+        // Leave nothing half-linked behind for the next attempt.
+        detach_device_branch(pipeline, device_bin);
+    }
+    started
+}
+
+/// Shut down and remove the current device branch, releasing its mixer pad.
+fn detach_device_branch(pipeline: &gst::Pipeline, device_bin: &Mutex<Option<gst::Bin>>) {
+    let Some(bin) = device_bin.lock().unwrap().take() else {
+        return;
+    };
+    let mixer_pad = bin.static_pad("src").and_then(|pad| pad.peer());
+    let _ = bin.set_state(gst::State::Null);
+    if let (Some(pad), Some(mixer)) = (mixer_pad, pipeline.by_name("mix")) {
+        mixer.release_request_pad(&pad);
+    }
+    let _ = pipeline.remove(&bin);
+}
+
+impl CapturePipeline for ResilientAudioPipeline {
+    fn start(&mut self) -> GrabmeResult<()> {
+        self.inner.start()
+    }
+
+    fn stop(&mut self) -> GrabmeResult<()> {
+        // Stop reacting to device errors before draining; dropping the
+        // handler also ends the fallback worker thread.
+        if let Some(bus) = self.inner.pipeline.bus() {
+            bus.unset_sync_handler();
+        }
+        self.inner.stop()
+    }
+
+    fn pause(&mut self) -> GrabmeResult<()> {
+        self.inner.pause()
+    }
+
+    fn resume(&mut self) -> GrabmeResult<()> {
+        self.inner.resume()
+    }
+
+    fn is_running(&self) -> bool {
+        self.inner.is_running()
+    }
+
+    fn stats(&self) -> PipelineStats {
+        self.inner.stats()
+    }
+
+    fn device_fallbacks_ns(&self) -> Vec<u64> {
+        self.fallback.markers_ns.lock().unwrap().clone()
+    }
+
+    fn audio_format(&self) -> Option<AudioFormat> {
+        Some(self.format)
+    }
 }
 
 pub fn build_windows_screen_pipeline(
@@ -329,8 +899,9 @@ pub fn build_windows_screen_pipeline(
     let path = escape_path(output_path);
     let show_cursor = if hide_cursor { "false" } else { "true" };
     let keyint = fps.saturating_mul(2).max(2);
+    let source_queue = source_queue();
     let launch = format!(
-        "d3d11screencapturesrc monitor-index={monitor_index} show-cursor={show_cursor} ! queue max-size-buffers=200 leaky=downstream ! videoconvert ! videorate ! video/x-raw,framerate={fps}/1 ! queue max-size-buffers=8 ! x264enc tune=zerolatency speed-preset=veryfast key-int-max={keyint} ! h264parse ! queue max-size-buffers=8 ! matroskamux ! filesink location=\"{path}\""
+        "d3d11screencapturesrc name=screen_src monitor-index={monitor_index} show-cursor={show_cursor} ! {source_queue} ! videoconvert ! videorate ! video/x-raw,format=I420,colorimetry={SCREEN_COLORIMETRY},framerate={fps}/1 ! queue max-size-buffers=8 ! x264enc tune=zerolatency speed-preset=veryfast key-int-max={keyint} ! h264parse ! queue max-size-buffers=8 ! matroskamux ! filesink location=\"{path}\""
     );
     Ok(Box::new(GstCapturePipeline::from_launch(
         "screen-windows",
@@ -342,33 +913,177 @@ pub fn build_windows_mic_pipeline(
     output_path: &Path,
     sample_rate: u32,
 ) -> GrabmeResult<Box<dyn CapturePipeline>> {
-    let path = escape_path(output_path);
-    let launch = format!(
-        "wasapisrc low-latency=true do-timestamp=true ! audioconvert ! audioresample ! audio/x-raw,rate={sample_rate} ! wavenc ! filesink location=\"{path}\""
-    );
-    Ok(Box::new(GstCapturePipeline::from_launch(
+    build_resilient_audio_pipeline(
         "mic-windows",
-        &launch,
-    )?))
+        "wasapisrc low-latency=true do-timestamp=true",
+        INPUT_DEVICE_CLASS,
+        output_path,
+        sample_rate,
+    )
 }
 
 pub fn build_windows_system_audio_pipeline(
     output_path: &Path,
     sample_rate: u32,
 ) -> GrabmeResult<Box<dyn CapturePipeline>> {
-    let path = escape_path(output_path);
+    build_resilient_audio_pipeline(
+        "system-windows",
+        "wasapisrc loopback=true low-latency=true do-timestamp=true",
+        OUTPUT_DEVICE_CLASS,
+        output_path,
+        sample_rate,
+    )
+}
+
+/// How often the live view's crop follows the pointer.
+const LIVE_VIEW_FOLLOW_INTERVAL: Duration = Duration::from_millis(33);
+
+/// Build a live view of the screen that follows `pointer` and ends in
+/// `sink`. `source` is a launch fragment producing raw screen frames.
+pub fn build_live_view_pipeline(
+    source: &str,
+    sink: &dyn VirtualCameraSink,
+    config: &VirtualCameraConfig,
+    pointer: PointerWatch,
+) -> GrabmeResult<Box<dyn CapturePipeline>> {
+    let (width, height, fps) = (config.width.max(2), config.height.max(2), config.fps.max(1));
+    // The crop changes size as the view moves; `videoscale` renegotiates
+    // and borders keep the screen's aspect in the call's frame.
     let launch = format!(
-        "wasapisrc loopback=true low-latency=true do-timestamp=true ! audioconvert ! audioresample ! audio/x-raw,rate={sample_rate} ! wavenc ! filesink location=\"{path}\""
+        "{source} ! queue max-size-buffers=4 leaky=downstream ! videoconvert ! videocrop name=live_crop ! videoscale add-borders=true ! videorate ! video/x-raw,width={width},height={height},framerate={fps}/1 ! queue max-size-buffers=4 leaky=downstream ! {}",
+        sink.launch_fragment()
     );
-    Ok(Box::new(GstCapturePipeline::from_launch(
-        "system-windows",
-        &launch,
-    )?))
+    Ok(Box::new(LiveViewPipeline {
+        inner: GstCapturePipeline::from_launch("virtual-camera", &launch)?,
+        zoom: config.zoom,
+        pointer,
+        follow_stop: Arc::new(AtomicBool::new(false)),
+        follow_thread: None,
+    }))
+}
+
+/// Live view source reading the PipeWire screen stream a second time.
+pub fn pipewire_live_source(pipewire_node_id: u32, crop: Option<(u32, u32, u32, u32)>) -> String {
+    let crop = screen_crop_fragment(crop);
+    format!("pipewiresrc path={pipewire_node_id} do-timestamp=true ! videoconvert !{crop} identity")
+}
+
+/// Live view source grabbing `region` of an X11 display, pointer included
+/// so the call sees it.
+pub fn x11_live_source(
+    region: Option<(i32, i32, u32, u32)>,
+    display_name: Option<&str>,
+) -> GrabmeResult<String> {
+    let region = x11_capture_region_fragment(region)?;
+    let display = x11_display_fragment(display_name);
+    Ok(format!(
+        "ximagesrc use-damage=false remote=true show-pointer=true{display}{region}"
+    ))
+}
+
+/// Live view pipeline with a thread moving its crop after the pointer.
+pub struct LiveViewPipeline {
+    inner: GstCapturePipeline,
+    zoom: f64,
+    pointer: PointerWatch,
+    follow_stop: Arc<AtomicBool>,
+    follow_thread: Option<std::thread::JoinHandle<()>>,
+}
+
+impl LiveViewPipeline {
+    fn spawn_follower(&mut self) -> GrabmeResult<()> {
+        let crop = self
+            .inner
+            .pipeline
+            .by_name("live_crop")
+            .ok_or_else(|| GrabmeError::capture("Live view pipeline has no crop element"))?;
+        let pad = crop
+            .static_pad("sink")
+            .ok_or_else(|| GrabmeError::capture("Live view crop has no sink pad"))?;
+        let pointer = self.pointer.clone();
+        let stop = self.follow_stop.clone();
+        let mut camera = FollowCamera::new(self.zoom);
+        stop.store(false, Ordering::SeqCst);
+        self.follow_thread = Some(std::thread::spawn(move || {
+            while !stop.load(Ordering::Relaxed) {
+                std::thread::sleep(LIVE_VIEW_FOLLOW_INTERVAL);
+                let Some((width, height)) = pad.current_caps().and_then(|caps| {
+                    let structure = caps.structure(0)?;
+                    Some((
+                        structure.get::<i32>("width").ok()?,
+                        structure.get::<i32>("height").ok()?,
+                    ))
+                }) else {
+                    continue;
+                };
+                let (left, top, right, bottom) =
+                    camera.step(pointer.latest(), width.max(0) as u32, height.max(0) as u32);
+                crop.set_property("left", left as i32);
+                crop.set_property("top", top as i32);
+                crop.set_property("right", right as i32);
+                crop.set_property("bottom", bottom as i32);
+            }
+        }));
+        Ok(())
+    }
+
+    fn stop_follower(&mut self) {
+        self.follow_stop.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.follow_thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl CapturePipeline for LiveViewPipeline {
+    fn start(&mut self) -> GrabmeResult<()> {
+        self.inner.start()?;
+        self.spawn_follower()
+    }
+
+    fn stop(&mut self) -> GrabmeResult<()> {
+        self.stop_follower();
+        self.inner.stop()
+    }
+
+    fn pause(&mut self) -> GrabmeResult<()> {
+        self.inner.pause()
+    }
+
+    fn resume(&mut self) -> GrabmeResult<()> {
+        self.inner.resume()
+    }
+
+    fn is_running(&self) -> bool {
+        self.inner.is_running()
+    }
+
+    fn stats(&self) -> PipelineStats {
+        self.inner.stats()
+    }
+}
+
+impl Drop for LiveViewPipeline {
+    fn drop(&mut self) {
+        self.stop_follower();
+    }
+}
+
+/// How often a lost webcam is looked for again.
+const WEBCAM_RECONNECT_INTERVAL: Duration = Duration::from_secs(1);
+/// Frame size used when the webcam's current format can't be read.
+const DEFAULT_WEBCAM_SIZE: (u32, u32) = (1280, 720);
+
+/// Frame rate to ask a webcam for: the requested one, else the recording
+/// rate capped at 30.
+fn requested_webcam_fps(fps: u32, request: WebcamFormatRequest) -> u32 {
+    request.fps.unwrap_or(fps.min(30)).clamp(1, 60)
 }
 
 pub fn build_webcam_pipeline(
     output_path: &Path,
     fps: u32,
+    request: WebcamFormatRequest,
 ) -> GrabmeResult<Box<dyn CapturePipeline>> {
     let device = detect_default_webcam_device().ok_or_else(|| {
         GrabmeError::capture(
@@ -376,25 +1091,274 @@ pub fn build_webcam_pipeline(
         )
     })?;
     let path = escape_path(output_path);
-    let webcam_fps = fps.clamp(1, 30);
+    let (wanted_width, wanted_height) = request
+        .size
+        .or_else(|| v4l2_frame_size(&device))
+        .unwrap_or(DEFAULT_WEBCAM_SIZE);
+    let wanted = VideoFormat {
+        width: wanted_width,
+        height: wanted_height,
+        fps: requested_webcam_fps(fps, request),
+    };
+    // Record what the camera delivers rather than scaling it to the request;
+    // without a mode list, the request is scaled to.
+    let mode = negotiate_webcam_mode(wanted, &probe_v4l2_modes(&device));
+    let format = match mode {
+        Some(mode) => VideoFormat {
+            width: mode.width,
+            height: mode.height,
+            fps: wanted.fps.min(mode.fps()),
+        },
+        None => wanted,
+    };
+    tracing::info!(device = %device, ?wanted, ?mode, ?format, "Negotiated webcam format");
+    let source_caps = mode
+        .map(|mode| format!(" ! {}", mode.source_caps()))
+        .unwrap_or_default();
+    let VideoFormat {
+        width,
+        height,
+        fps: webcam_fps,
+    } = format;
     let keyint = (webcam_fps.saturating_mul(2)).max(2);
+    let caps = format!("video/x-raw,width={width},height={height},framerate={webcam_fps}/1");
     let launch = format!(
-        "v4l2src device=\"{device}\" do-timestamp=true ! videoconvert ! videoscale ! videorate ! video/x-raw,framerate={webcam_fps}/1 ! x264enc tune=zerolatency speed-preset=veryfast bitrate=2500 key-int-max={keyint} ! h264parse ! matroskamux ! filesink location=\"{path}\""
+        "compositor name=mix background=black ! {caps} ! videoconvert ! x264enc tune=zerolatency speed-preset=veryfast bitrate=2500 key-int-max={keyint} ! h264parse ! matroskamux ! filesink location=\"{path}\" \
+         videotestsrc is-live=true pattern=black ! {caps} ! mix."
     );
-    Ok(Box::new(GstCapturePipeline::from_launch(
-        "webcam", &launch,
-    )?))
+    let device_desc = format!(
+        "v4l2src device=\"{device}\" do-timestamp=true{source_caps} ! videoconvert ! videoscale ! videorate ! {caps},pixel-aspect-ratio=1/1 ! queue"
+    );
+
+    let inner = GstCapturePipeline::from_launch("webcam", &launch)?;
+    let state = Arc::new(WebcamReconnectState::default());
+    attach_device_branch(&inner.pipeline, &device_desc, &state.device_bin)?;
+
+    let (tx, rx) = mpsc::channel::<()>();
+    if let Some(bus) = inner.pipeline.bus() {
+        let state = Arc::clone(&state);
+        bus.set_sync_handler(move |_bus, msg| {
+            let gst::MessageView::Error(err) = msg.view() else {
+                return gst::BusSyncReply::Pass;
+            };
+            let from_device = match (msg.src(), state.device_bin.lock().unwrap().as_ref()) {
+                (Some(src), Some(bin)) => src.has_as_ancestor(bin),
+                _ => false,
+            };
+            if !from_device {
+                return gst::BusSyncReply::Pass;
+            }
+            tracing::warn!(error = %err.error(), "Webcam lost; recording black until it reconnects");
+            let _ = tx.send(());
+            gst::BusSyncReply::Drop
+        });
+    }
+
+    let pipeline = inner.pipeline.clone();
+    let worker_state = Arc::clone(&state);
+    std::thread::spawn(move || {
+        let state = worker_state;
+        loop {
+            match rx.recv_timeout(WEBCAM_RECONNECT_INTERVAL) {
+                Ok(()) => {
+                    state.open_gap();
+                    detach_device_branch(&pipeline, &state.device_bin);
+                }
+                Err(mpsc::RecvTimeoutError::Timeout) => {
+                    if !state.gap_open() || !Path::new(&device).exists() {
+                        continue;
+                    }
+                    match attach_device_branch(&pipeline, &device_desc, &state.device_bin) {
+                        Ok(()) => {
+                            let gap = state.close_gap();
+                            tracing::info!(device = %device, ?gap, "Webcam reconnected");
+                        }
+                        Err(err) => {
+                            tracing::debug!(device = %device, error = %err, "Webcam not back yet")
+                        }
+                    }
+                }
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            }
+        }
+    });
+
+    Ok(Box::new(ResilientWebcamPipeline {
+        inner,
+        state,
+        format,
+    }))
 }
 
+/// Webcam capture pipeline that survives the camera being unplugged.
+///
+/// Like [`ResilientAudioPipeline`], the camera branch feeds a `compositor`
+/// over a live black source, so the file keeps its length and timing while
+/// the camera is gone. A lost camera is retried every second and each
+/// outage is kept as a gap, so export can cover it instead of showing black.
+pub struct ResilientWebcamPipeline {
+    inner: GstCapturePipeline,
+    state: Arc<WebcamReconnectState>,
+    format: VideoFormat,
+}
+
+#[derive(Default)]
+struct WebcamReconnectState {
+    device_bin: Mutex<Option<gst::Bin>>,
+    started_at: Mutex<Option<Instant>>,
+    /// Outages in ns since pipeline start. The end is `None` while the
+    /// camera is still missing.
+    gaps_ns: Mutex<Vec<(u64, Option<u64>)>>,
+}
+
+impl WebcamReconnectState {
+    fn elapsed_ns(&self) -> u64 {
+        self.started_at
+            .lock()
+            .unwrap()
+            .map(|started| started.elapsed().as_nanos() as u64)
+            .unwrap_or(0)
+    }
+
+    fn gap_open(&self) -> bool {
+        matches!(self.gaps_ns.lock().unwrap().last(), Some((_, None)))
+    }
+
+    fn open_gap(&self) {
+        if !self.gap_open() {
+            let now = self.elapsed_ns();
+            self.gaps_ns.lock().unwrap().push((now, None));
+        }
+    }
+
+    fn close_gap(&self) -> Option<(u64, u64)> {
+        let now = self.elapsed_ns();
+        let mut gaps = self.gaps_ns.lock().unwrap();
+        let (start, end) = gaps.last_mut()?;
+        if end.is_some() {
+            return None;
+        }
+        *end = Some(now);
+        Some((*start, now))
+    }
+
+    /// All gaps, with a still-open one ending now.
+    fn closed_gaps(&self) -> Vec<(u64, u64)> {
+        let now = self.elapsed_ns();
+        self.gaps_ns
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(start, end)| (*start, end.unwrap_or(now)))
+            .collect()
+    }
+}
+
+impl CapturePipeline for ResilientWebcamPipeline {
+    fn start(&mut self) -> GrabmeResult<()> {
+        self.inner.start()?;
+        *self.state.started_at.lock().unwrap() = Some(Instant::now());
+        Ok(())
+    }
+
+    fn stop(&mut self) -> GrabmeResult<()> {
+        // Dropping the handler also ends the reconnect worker thread.
+        if let Some(bus) = self.inner.pipeline.bus() {
+            bus.unset_sync_handler();
+        }
+        self.inner.stop()
+    }
+
+    fn pause(&mut self) -> GrabmeResult<()> {
+        self.inner.pause()
+    }
+
+    fn resume(&mut self) -> GrabmeResult<()> {
+        self.inner.resume()
+    }
+
+    fn is_running(&self) -> bool {
+        self.inner.is_running()
+    }
+
+    fn stats(&self) -> PipelineStats {
+        self.inner.stats()
+    }
+
+    fn device_gaps_ns(&self) -> Vec<(u64, u64)> {
+        self.state.closed_gaps()
+    }
+
+    fn video_format(&self) -> Option<VideoFormat> {
+        Some(self.format)
+    }
+}
+
+/// Current capture frame size of a V4L2 device, via `v4l2-ctl`.
+fn v4l2_frame_size(dev_path: &str) -> Option<(u32, u32)> {
+    let output = std::process::Command::new("v4l2-ctl")
+        .args(["--device", dev_path, "--get-fmt-video"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_v4l2_frame_size(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Parse the `Width/Height : 1280/720` line of `v4l2-ctl --get-fmt-video`.
+fn parse_v4l2_frame_size(stdout: &str) -> Option<(u32, u32)> {
+    let line = stdout
+        .lines()
+        .find(|line| line.trim_start().starts_with("Width/Height"))?;
+    let (_, value) = line.split_once(':')?;
+    let (width, height) = value.trim().split_once('/')?;
+    let size = (width.trim().parse().ok()?, height.trim().parse().ok()?);
+    (size.0 > 0 && size.1 > 0).then_some(size)
+}
+
+/// Record a V4L2 capture device (for example an HDMI capture card showing
+/// a phone) as the auxiliary picture-in-picture track.
+///
+/// Unlike the webcam, the device's own frame size is kept and the bitrate is
+/// higher, since these sources usually carry UI text.
+pub fn build_v4l2_pip_pipeline(
+    device: &str,
+    output_path: &Path,
+    fps: u32,
+) -> GrabmeResult<Box<dyn CapturePipeline>> {
+    if !Path::new(device).exists() {
+        return Err(GrabmeError::capture(format!(
+            "Picture-in-picture device not found: {device}"
+        )));
+    }
+    let path = escape_path(output_path);
+    let device = device.replace('"', "");
+    let pip_fps = fps.clamp(1, 60);
+    let keyint = pip_fps.saturating_mul(2).max(2);
+    let source_queue = source_queue();
+    let launch = format!(
+        "v4l2src device=\"{device}\" do-timestamp=true ! {source_queue} ! videoconvert ! videorate ! video/x-raw,format=I420,framerate={pip_fps}/1 ! x264enc tune=zerolatency speed-preset=veryfast bitrate=6000 key-int-max={keyint} ! h264parse ! matroskamux ! filesink location=\"{path}\""
+    );
+    Ok(Box::new(GstCapturePipeline::from_launch("pip", &launch)?))
+}
+
+/// Windows webcam capture. Modes aren't listed here, so a requested size is
+/// scaled to and the track's format is left unrecorded.
 pub fn build_windows_webcam_pipeline(
     output_path: &Path,
     fps: u32,
+    request: WebcamFormatRequest,
 ) -> GrabmeResult<Box<dyn CapturePipeline>> {
     let path = escape_path(output_path);
-    let webcam_fps = fps.clamp(1, 30);
+    let webcam_fps = requested_webcam_fps(fps, request);
     let keyint = (webcam_fps.saturating_mul(2)).max(2);
+    let size = request
+        .size
+        .map(|(width, height)| format!(",width={width},height={height}"))
+        .unwrap_or_default();
     let launch = format!(
-        "ksvideosrc device-index=0 do-stats=true ! videoconvert ! videoscale ! videorate ! video/x-raw,framerate={webcam_fps}/1 ! x264enc tune=zerolatency speed-preset=veryfast bitrate=2500 key-int-max={keyint} ! h264parse ! matroskamux ! filesink location=\"{path}\""
+        "ksvideosrc device-index=0 do-stats=true ! videoconvert ! videoscale ! videorate ! video/x-raw{size},framerate={webcam_fps}/1 ! x264enc tune=zerolatency speed-preset=veryfast bitrate=2500 key-int-max={keyint} ! h264parse ! matroskamux ! filesink location=\"{path}\""
     );
     Ok(Box::new(GstCapturePipeline::from_launch(
         "webcam-windows",
@@ -402,7 +1366,56 @@ pub fn build_windows_webcam_pipeline(
     )?))
 }
 
-fn init_gstreamer() -> GrabmeResult<()> {
+/// GStreamer elements the screen and microphone pipelines use on `server`.
+pub fn capture_elements(server: DisplayServer) -> &'static [&'static str] {
+    match server {
+        DisplayServer::X11 => &[
+            "ximagesrc",
+            "pulsesrc",
+            "videoconvert",
+            "videorate",
+            "x264enc",
+            "h264parse",
+            "matroskamux",
+            "wavenc",
+            "filesink",
+        ],
+        DisplayServer::Wayland => &[
+            "pipewiresrc",
+            "videoconvert",
+            "videorate",
+            "x264enc",
+            "h264parse",
+            "matroskamux",
+            "wavenc",
+            "filesink",
+        ],
+        DisplayServer::Windows => &[
+            "d3d11screencapturesrc",
+            "wasapisrc",
+            "videoconvert",
+            "videorate",
+            "x264enc",
+            "h264parse",
+            "matroskamux",
+            "wavenc",
+            "filesink",
+        ],
+        DisplayServer::MacOS | DisplayServer::Unknown => &[],
+    }
+}
+
+/// The subset of `elements` that this GStreamer installation lacks.
+pub fn missing_gst_elements(elements: &[&str]) -> GrabmeResult<Vec<String>> {
+    init_gstreamer()?;
+    Ok(elements
+        .iter()
+        .filter(|name| gst::ElementFactory::find(name).is_none())
+        .map(|name| name.to_string())
+        .collect())
+}
+
+pub(crate) fn init_gstreamer() -> GrabmeResult<()> {
     static GST_INIT: OnceLock<Result<(), String>> = OnceLock::new();
     let init_res = GST_INIT.get_or_init(|| gst::init().map_err(|e| e.to_string()));
     match init_res {
@@ -558,7 +1571,61 @@ fn escape_path(path: &Path) -> String {
 
 #[cfg(test)]
 mod tests {
-    use super::x11_capture_region_fragment;
+    use super::{
+        parse_v4l2_frame_size, screen_crop_fragment, x11_capture_region_fragment,
+        x11_display_fragment, FrameTimesSampler, ScreenSizeState, SizeObservation,
+        WebcamReconnectState,
+    };
+
+    #[test]
+    fn frame_times_sample_the_first_frame_and_each_interval() {
+        let mut sampler = FrameTimesSampler::new(3);
+        let frames: Vec<u64> = (0..7)
+            .filter_map(|i| sampler.observe(i * 10, i * 11))
+            .map(|sample| sample.frame)
+            .collect();
+        assert_eq!(frames, vec![1, 4, 7]);
+        assert_eq!(FrameTimesSampler::new(0).observe(5, 6).unwrap().pts_ns, 5);
+    }
+
+    #[test]
+    fn screen_size_changes_are_logged_after_the_first_size() {
+        let state = ScreenSizeState::default();
+        assert_eq!(state.observe(1920, 1080, 0), SizeObservation::First);
+        assert_eq!(state.observe(1920, 1080, 5), SizeObservation::Unchanged);
+        assert_eq!(state.observe(1280, 720, 10), SizeObservation::Changed);
+        assert_eq!(state.observe(1280, 720, 15), SizeObservation::Unchanged);
+        assert_eq!(state.observe(1920, 1080, 20), SizeObservation::Changed);
+        assert_eq!(
+            *state.changes_ns.lock().unwrap(),
+            vec![(10, 1280, 720), (20, 1920, 1080)]
+        );
+    }
+
+    #[test]
+    fn webcam_gaps_open_once_and_close_on_reconnect() {
+        let state = WebcamReconnectState::default();
+        assert_eq!(state.close_gap(), None);
+
+        state.open_gap();
+        // A second error while the camera is still gone keeps the same gap.
+        state.open_gap();
+        assert!(state.gap_open());
+        assert_eq!(state.closed_gaps().len(), 1);
+
+        assert!(state.close_gap().is_some());
+        assert!(!state.gap_open());
+        assert_eq!(state.close_gap(), None);
+        assert_eq!(state.closed_gaps().len(), 1);
+    }
+
+    #[test]
+    fn v4l2_frame_size_reads_width_height_line() {
+        let stdout = "Format Video Capture:\n\tWidth/Height      : 640/480\n\tPixel Format      : 'YUYV' (YUYV 4:2:2)\n";
+        assert_eq!(parse_v4l2_frame_size(stdout), Some((640, 480)));
+        assert_eq!(parse_v4l2_frame_size("Width/Height : 0/0"), None);
+        assert_eq!(parse_v4l2_frame_size(""), None);
+    }
 
     #[test]
     fn x11_region_fragment_uses_inclusive_end_coordinates() {
@@ -574,4 +1641,19 @@ mod tests {
         let err = x11_capture_region_fragment(Some((0, 0, 0, 1080))).unwrap_err();
         assert!(err.to_string().contains("Invalid X11 capture region"));
     }
+
+    #[test]
+    fn screen_crop_fragment_trims_each_edge() {
+        assert_eq!(
+            screen_crop_fragment(Some((2560, 0, 0, 0))),
+            " videocrop left=2560 top=0 right=0 bottom=0 !"
+        );
+        assert_eq!(screen_crop_fragment(None), "");
+    }
+
+    #[test]
+    fn x11_display_fragment_names_explicit_display() {
+        assert_eq!(x11_display_fragment(Some(":99")), " display-name=\":99\"");
+        assert_eq!(x11_display_fragment(None), "");
+    }
 }
@@ -0,0 +1,175 @@
+//! Recording settings taken from an existing project.
+//!
+//! Follow-up takes should match the original: same monitor, frame rate,
+//! and inputs. Monitor indices shift when displays are plugged in or
+//! rearranged, so the recorded monitor is looked up again in the current
+//! layout by name, then by geometry.
+
+use grabme_platform_core::MonitorInfo;
+use grabme_project_model::project::{Project, RecordingConfig, VideoFormat};
+
+/// Capture settings of an earlier recording, resolved against the current
+/// monitor layout.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordingPreset {
+    /// Index of the recorded monitor in the current layout. `None` when it
+    /// is no longer connected.
+    pub monitor_index: Option<usize>,
+    /// Name of the monitor the project recorded.
+    pub monitor_name: String,
+    /// Frame rate that was asked for, before any pacing adjustment.
+    pub fps: u32,
+    /// Whether the frame rate was adjusted to the refresh rate.
+    pub auto_fps: bool,
+    pub mic: bool,
+    pub system_audio: bool,
+    pub webcam: bool,
+    /// Format the webcam was recorded in, when known.
+    pub webcam_format: Option<VideoFormat>,
+    /// The project followed a window. Window ids don't survive restarts, so
+    /// the window has to be chosen again.
+    pub window: bool,
+}
+
+impl RecordingPreset {
+    /// Settings `project` was recorded with, with its monitor looked up in
+    /// `monitors`.
+    pub fn from_project(project: &Project, monitors: &[MonitorInfo]) -> Self {
+        let recording = &project.recording;
+        let pacing = &recording.frame_pacing;
+        Self {
+            monitor_index: resolve_recorded_monitor(monitors, recording),
+            monitor_name: recording.monitor_name.clone(),
+            fps: if pacing.requested_fps > 0 {
+                pacing.requested_fps
+            } else {
+                recording.fps
+            },
+            auto_fps: pacing.adjusted,
+            mic: project.tracks.mic.is_some(),
+            system_audio: project.tracks.system_audio.is_some(),
+            webcam: project.tracks.webcam.is_some(),
+            webcam_format: project.tracks.webcam.as_ref().and_then(|track| track.video),
+            window: recording.window_track.is_some(),
+        }
+    }
+}
+
+/// Index in `monitors` of the monitor `recording` captured: the one with
+/// the same name, else the one at the same position and size, else the
+/// only one of that size. Projects from before monitor names were stored
+/// fall back to the recorded index.
+pub fn resolve_recorded_monitor(
+    monitors: &[MonitorInfo],
+    recording: &RecordingConfig,
+) -> Option<usize> {
+    if !recording.monitor_name.is_empty() {
+        if let Some(idx) = monitors
+            .iter()
+            .position(|m| m.name == recording.monitor_name)
+        {
+            return Some(idx);
+        }
+    }
+
+    let (width, height) = (recording.monitor_width, recording.monitor_height);
+    if width == 0 || height == 0 {
+        return (recording.monitor_index < monitors.len()).then_some(recording.monitor_index);
+    }
+    if let Some(idx) = monitors.iter().position(|m| {
+        m.x == recording.monitor_x
+            && m.y == recording.monitor_y
+            && m.width == width
+            && m.height == height
+    }) {
+        return Some(idx);
+    }
+
+    let mut same_size = monitors
+        .iter()
+        .enumerate()
+        .filter(|(_, m)| m.width == width && m.height == height);
+    match (same_size.next(), same_size.next()) {
+        (Some((idx, _)), None) => Some(idx),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use grabme_project_model::project::TrackRef;
+
+    use super::*;
+
+    fn monitor(name: &str, x: i32, width: u32) -> MonitorInfo {
+        MonitorInfo {
+            name: name.to_string(),
+            width,
+            height: 1080,
+            x,
+            y: 0,
+            scale_factor: 1.0,
+            refresh_rate_hz: 60,
+            primary: x == 0,
+        }
+    }
+
+    fn recorded(name: &str, x: i32, width: u32) -> RecordingConfig {
+        let mut project = Project::new("take", 1920, 1080, 60);
+        project.recording.monitor_name = name.to_string();
+        project.recording.monitor_x = x;
+        project.recording.monitor_width = width;
+        project.recording.monitor_height = 1080;
+        project.recording
+    }
+
+    #[test]
+    fn test_monitor_is_found_by_name_after_reordering() {
+        let monitors = vec![monitor("HDMI-1", 0, 1920), monitor("DP-1", 1920, 2560)];
+        let recording = recorded("DP-1", 0, 2560);
+        assert_eq!(resolve_recorded_monitor(&monitors, &recording), Some(1));
+    }
+
+    #[test]
+    fn test_renamed_monitor_is_found_by_geometry() {
+        let monitors = vec![monitor("DP-3", 0, 1920), monitor("DP-4", 1920, 1920)];
+        assert_eq!(
+            resolve_recorded_monitor(&monitors, &recorded("DP-1", 1920, 1920)),
+            Some(1)
+        );
+        // Two monitors of the recorded size and neither in place: ambiguous.
+        assert_eq!(
+            resolve_recorded_monitor(&monitors, &recorded("DP-1", -1920, 1920)),
+            None
+        );
+        assert_eq!(
+            resolve_recorded_monitor(&monitors, &recorded("DP-1", -2560, 2560)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_preset_uses_requested_fps_and_recorded_tracks() {
+        let mut project = Project::new("take", 1920, 1080, 60);
+        project.recording.monitor_name = "DP-1".to_string();
+        project.recording.fps = 48;
+        project.recording.frame_pacing.requested_fps = 60;
+        project.recording.frame_pacing.adjusted = true;
+        project.tracks.mic = Some(TrackRef {
+            path: "sources/mic.wav".to_string(),
+            duration_secs: 1.0,
+            codec: "pcm_s16le".to_string(),
+            offset_ns: 0,
+            audio: None,
+            video: None,
+        });
+
+        let preset = RecordingPreset::from_project(&project, &[monitor("DP-1", 0, 1920)]);
+        assert_eq!(preset.monitor_index, Some(0));
+        assert_eq!(preset.fps, 60);
+        assert!(preset.auto_fps);
+        assert!(preset.mic);
+        assert!(!preset.webcam);
+        assert!(!preset.window);
+    }
+}
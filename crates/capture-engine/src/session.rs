@@ -1,21 +1,41 @@
 //! Recording session management.
 
+use std::collections::BTreeMap;
 use std::path::PathBuf;
-use std::process::Command;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
 
-use grabme_common::clock::{DriftMeasurement, RecordingClock};
+use grabme_common::clock::{ClockSource, DriftMeasurement, RecordingClock};
+use grabme_common::config::LockAction;
 use grabme_common::error::{GrabmeError, GrabmeResult};
-use grabme_input_tracker::backends::detect_best_backend;
-use grabme_input_tracker::InputTracker;
-use grabme_platform_core::{virtual_desktop_bounds, MonitorInfo};
+use grabme_common::logging::{attach_session_log, detach_session_log, session_log_path};
+use grabme_common::media::probe_media;
+use grabme_common::memory::MemoryProfile;
+use grabme_input_tracker::backends::detect_backend_with;
+use grabme_input_tracker::{InputOptions, InputStats, InputTracker, ReactionSender};
+use grabme_platform_core::{
+    split_monitors, virtual_desktop_bounds, DisplayTarget, MonitorInfo, SystemSessionEvent,
+    WindowGeometry,
+};
 use grabme_project_model::event::PointerCoordinateSpace;
-use grabme_project_model::project::RecordedMonitor;
+use grabme_project_model::frame_times::frame_times_path;
+use grabme_project_model::metrics::metrics_path;
+use grabme_project_model::project::{
+    AudioDeviceFallback, CursorCaptureMode, PipelineStarts, RecordedMonitor, RecordingClockInfo,
+    ResolutionChange, WebcamGap, WindowGeometrySample, WindowTrack,
+};
 use grabme_project_model::{LoadedProject, TrackRef};
 
-use crate::backend::{get_backend, CaptureBackend};
-use crate::pipeline::CapturePipeline;
+use crate::backend::{get_backend, CaptureBackend, WindowGeometryProbe};
+use crate::metrics::spawn_metrics_sampler;
+use crate::pacing::plan_frame_pacing;
+use crate::pipeline::{set_source_queue_buffers, CapturePipeline, SCREEN_COLOR_RANGE};
+use crate::storage::{move_project, spawn_io_monitor, volume_kind, IoHealth, VolumeKind};
+use crate::suspend::{next_segment_name, SuspendDetector};
+use crate::virtual_camera::VirtualCameraConfig;
+use crate::webcam_format::WebcamFormatRequest;
 
 /// Configuration for starting a new recording session.
 #[derive(Debug, Clone)]
@@ -35,11 +55,63 @@ pub struct SessionConfig {
     /// Whether to capture webcam.
     pub webcam: bool,
 
+    /// Webcam size and rate to ask the camera for.
+    pub webcam_format: WebcamFormatRequest,
+
     /// Target FPS for screen capture.
     pub fps: u32,
 
     /// Pointer sampling rate in Hz.
     pub pointer_sample_rate_hz: u32,
+
+    /// System clock event and track timestamps are measured against.
+    pub clock_source: ClockSource,
+
+    /// Display to capture from instead of the one the environment points at.
+    pub display: DisplayTarget,
+
+    /// Switch `fps` to a divisor of the recorded monitor's refresh rate when
+    /// it would otherwise pace unevenly.
+    pub auto_adjust_fps: bool,
+
+    /// Record gamepad buttons and sticks alongside mouse and keyboard.
+    pub gamepad: bool,
+
+    /// Auxiliary video source recorded as a picture-in-picture track.
+    pub pip: Option<PipSource>,
+
+    /// Log screen capture timestamps to `meta/frame-times.jsonl` so export
+    /// can correct drift and uneven pacing.
+    pub frame_times: bool,
+
+    /// Sample CPU, memory and GPU usage to `meta/metrics.jsonl` for an
+    /// on-screen usage graph at export.
+    pub system_metrics: bool,
+
+    /// What to do when the screen locks or the system suspends. Applied by
+    /// [`CaptureSession::poll_system_events`].
+    pub on_lock: LockAction,
+
+    /// Mirror a cursor-following view of the screen to a virtual webcam
+    /// while recording.
+    pub virtual_camera: Option<VirtualCameraConfig>,
+
+    /// Record into this local directory and move the project to
+    /// `output_dir` on stop, for destinations on network or removable
+    /// drives that can't be trusted to keep up.
+    pub spool_dir: Option<PathBuf>,
+
+    /// Queue sizes and live previews; see [`MemoryProfile`].
+    pub memory: MemoryProfile,
+}
+
+/// Second video source recorded alongside the screen.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PipSource {
+    /// Another monitor, by index in the detected monitor list.
+    Monitor(usize),
+    /// A capture device node such as `/dev/video2`.
+    Device(String),
 }
 
 /// Screen capture configuration.
@@ -50,6 +122,25 @@ pub struct ScreenCaptureConfig {
 
     /// Whether to hide the system cursor from the capture.
     pub hide_cursor: bool,
+
+    /// Cursor mode to ask the Wayland portal for. `None` asks for metadata
+    /// when `hide_cursor` is set and embedded otherwise.
+    pub cursor_mode: Option<CursorCaptureMode>,
+
+    /// Monitors to split into side-by-side regions, by name. Region indices
+    /// take the place of the monitor in `FullScreen { monitor_index }`.
+    pub monitor_splits: BTreeMap<String, u32>,
+}
+
+impl ScreenCaptureConfig {
+    /// The cursor mode to request from the platform.
+    pub fn requested_cursor_mode(&self) -> CursorCaptureMode {
+        self.cursor_mode.unwrap_or(if self.hide_cursor {
+            CursorCaptureMode::Metadata
+        } else {
+            CursorCaptureMode::Embedded
+        })
+    }
 }
 
 /// What region of the screen to capture.
@@ -57,7 +148,8 @@ pub struct ScreenCaptureConfig {
 pub enum CaptureMode {
     /// Entire screen / monitor.
     FullScreen { monitor_index: usize },
-    /// A specific window.
+    /// A specific window. On X11 the window is followed as it moves or
+    /// resizes; on Wayland the portal stream tracks it directly.
     Window { window_id: String },
     /// A rectangular region.
     Region {
@@ -99,6 +191,24 @@ pub enum SessionState {
     Error,
 }
 
+/// What [`CaptureSession::poll_system_events`] did about a screen lock or
+/// suspend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockResponse {
+    /// The recording was paused.
+    Pause,
+    /// The recording was resumed after the screen was unlocked.
+    Resume,
+    /// The recording should be stopped; the caller owns [`CaptureSession::stop`].
+    Stop,
+    /// The system is about to suspend. The recording should be stopped to
+    /// save it before the pipelines die; a new segment can follow on wake.
+    Suspend,
+    /// The system slept without warning and the pipelines are dead. The
+    /// recording should be stopped to save what came before the suspend.
+    Slept,
+}
+
 /// A recording session that coordinates all capture streams.
 pub struct CaptureSession {
     config: SessionConfig,
@@ -109,17 +219,35 @@ pub struct CaptureSession {
     backend: Box<dyn CaptureBackend>,
     screen_pipeline: Option<Box<dyn CapturePipeline>>,
     webcam_pipeline: Option<Box<dyn CapturePipeline>>,
+    pip_pipeline: Option<Box<dyn CapturePipeline>>,
     mic_pipeline: Option<Box<dyn CapturePipeline>>,
     system_pipeline: Option<Box<dyn CapturePipeline>>,
+    virtual_camera_pipeline: Option<Box<dyn CapturePipeline>>,
     input_stop_flag: Option<Arc<AtomicBool>>,
-    input_task: Option<tokio::task::JoinHandle<GrabmeResult<u64>>>,
+    reactions: Option<ReactionSender>,
+    input_task: Option<tokio::task::JoinHandle<GrabmeResult<InputStats>>>,
+    input_stats: Option<InputStats>,
+    appearance_task: Option<tokio::task::JoinHandle<()>>,
+    system_task: Option<tokio::task::JoinHandle<()>>,
+    system_events: Option<tokio::sync::mpsc::UnboundedReceiver<SystemSessionEvent>>,
+    screen_locked: bool,
+    paused_for_lock: bool,
+    suspend: SuspendDetector,
+    window_task: Option<JoinHandle<Vec<WindowGeometrySample>>>,
+    metrics_task: Option<JoinHandle<()>>,
+    io_task: Option<JoinHandle<IoHealth>>,
+    io_health: Option<IoHealth>,
     stream_offsets_ns: StreamOffsets,
 }
 
+/// How often a followed window's geometry is polled.
+const WINDOW_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
 #[derive(Debug, Default, Clone, Copy)]
 struct StreamOffsets {
     screen_ns: i64,
     webcam_ns: i64,
+    pip_ns: i64,
     mic_ns: i64,
     system_ns: i64,
     events_ns: i64,
@@ -137,10 +265,24 @@ impl CaptureSession {
             backend: get_backend(),
             screen_pipeline: None,
             webcam_pipeline: None,
+            pip_pipeline: None,
             mic_pipeline: None,
             system_pipeline: None,
+            virtual_camera_pipeline: None,
             input_stop_flag: None,
+            reactions: None,
             input_task: None,
+            input_stats: None,
+            appearance_task: None,
+            system_task: None,
+            system_events: None,
+            screen_locked: false,
+            paused_for_lock: false,
+            suspend: SuspendDetector::new(),
+            window_task: None,
+            metrics_task: None,
+            io_task: None,
+            io_health: None,
             stream_offsets_ns: StreamOffsets::default(),
         }
     }
@@ -161,20 +303,76 @@ impl CaptureSession {
 
         tracing::info!(name = %self.config.name, "Starting capture session");
 
+        set_source_queue_buffers(self.config.memory.capture_queue_buffers);
+
         // Initialize backend (detect display server, permissions)
+        self.backend.set_display_target(&self.config.display);
         self.backend.init().await?;
 
-        let monitors = self.backend.detect_monitors().unwrap_or_default();
+        let monitors = split_monitors(
+            &self.backend.detect_monitors().unwrap_or_default(),
+            &self.config.screen.monitor_splits,
+        );
         let selected_monitor = self.resolve_selected_monitor(&monitors)?;
 
+        let pacing_plan = plan_frame_pacing(
+            &monitors,
+            selected_monitor.as_ref(),
+            self.config.fps,
+            self.config.auto_adjust_fps,
+        );
+        for warning in &pacing_plan.warnings {
+            tracing::warn!("Frame pacing: {warning}");
+        }
+        self.config.fps = pacing_plan.fps;
+
         // Prepare screen capture (negotiate portals, etc.)
         let (capture_width, capture_height) = self
             .backend
             .prepare_screen_capture(&self.config.screen)
             .await?;
 
+        // Resolve the followed window up front so a bad id fails before any
+        // files are created.
+        let followed_window = match (
+            &self.config.screen.mode,
+            self.backend.window_geometry_probe(),
+        ) {
+            (CaptureMode::Window { window_id }, Some(probe)) => {
+                let geometry = probe(window_id)?;
+                tracing::info!(window_id, ?geometry, "Following window geometry");
+                Some((window_id.clone(), probe, geometry))
+            }
+            _ => None,
+        };
+
         // Create project on disk
-        let project_dir = self.config.output_dir.join(&self.config.name);
+        let destination = self.config.output_dir.join(&self.config.name);
+        let project_dir = match self.config.spool_dir.as_ref() {
+            Some(spool_dir) => {
+                // Fail now rather than after the recording is made.
+                if destination.exists() {
+                    return Err(GrabmeError::capture(format!(
+                        "Project already exists: {}",
+                        destination.display()
+                    )));
+                }
+                tracing::info!(spool = %spool_dir.display(), "Recording to spool directory");
+                spool_dir.join(&self.config.name)
+            }
+            None => {
+                let kind = volume_kind(&self.config.output_dir);
+                if kind != VolumeKind::Local {
+                    tracing::warn!(
+                        output_dir = %self.config.output_dir.display(),
+                        volume = ?kind,
+                        "Recording directly to a network or removable drive; \
+                         a local spool directory avoids stalls on slow writes"
+                    );
+                }
+                destination
+            }
+        };
         let mut project = LoadedProject::create(
             &project_dir,
             &self.config.name,
@@ -199,6 +397,11 @@ impl CaptureSession {
             project.project.recording.monitor_width = capture_width;
             project.project.recording.monitor_height = capture_height;
         }
+        // Export at the monitor's resolution rather than the capture's.
+        (project.project.export.width, project.project.export.height) = project
+            .project
+            .recording
+            .default_export_size(project.project.export.format);
 
         if !monitors.is_empty() {
             let (vx, vy, vw, vh) = virtual_desktop_bounds(&monitors);
@@ -226,6 +429,17 @@ impl CaptureSession {
         }
 
         project.project.recording.display_server = self.backend.get_display_server();
+        project.project.recording.color_range = SCREEN_COLOR_RANGE;
+        project.project.recording.cursor_hidden = self.config.screen.hide_cursor;
+        project.project.recording.cursor_mode = self.backend.cursor_mode();
+        project.project.recording.frame_pacing = pacing_plan.pacing;
+        project.project.recording.window_track =
+            followed_window
+                .as_ref()
+                .map(|(window_id, _, geometry)| WindowTrack {
+                    window_id: window_id.clone(),
+                    samples: vec![window_sample(0, *geometry)],
+                });
 
         // Persist a placeholder screen track immediately so abrupt termination
         // (for example, SIGINT before graceful stop) still leaves discoverable
@@ -235,19 +449,28 @@ impl CaptureSession {
             duration_secs: 0.0,
             codec: "h264".to_string(),
             offset_ns: 0,
+            audio: None,
+            video: None,
         });
         project
             .save()
             .map_err(|e| GrabmeError::capture(format!("Failed to save project metadata: {e}")))?;
 
         // Start the recording clock
-        let clock = RecordingClock::start();
+        let clock = RecordingClock::start_with(self.config.clock_source);
 
         tracing::info!(
             epoch_wall = %clock.epoch_wall(),
+            source = clock.source().as_str(),
+            epoch_monotonic_ns = clock.epoch_monotonic_ns(),
             "Recording clock started"
         );
 
+        let session_log = session_log_path(&project.root);
+        if let Err(e) = attach_session_log(&session_log) {
+            tracing::warn!(path = %session_log.display(), error = %e, "Failed to open session log");
+        }
+
         let sources_dir = project.root.join("sources");
 
         // Build all pipelines first so startup is near-simultaneous.
@@ -255,17 +478,40 @@ impl CaptureSession {
         let mut screen_pipeline = self
             .backend
             .build_screen_pipeline(&screen_path, self.config.fps)?;
+        if self.config.frame_times {
+            // One sample per keyframe interval: two seconds of frames.
+            let interval_frames = u64::from(self.config.fps.saturating_mul(2).max(2));
+            if let Err(e) = screen_pipeline.log_frame_times(
+                &frame_times_path(&project.root),
+                interval_frames,
+                clock.clone(),
+            ) {
+                tracing::warn!(error = %e, "Frame times will not be logged");
+            }
+        }
 
         let mut webcam_pipeline = if self.config.webcam {
             let webcam_path = sources_dir.join("webcam.mkv");
-            Some(
-                self.backend
-                    .build_webcam_pipeline(&webcam_path, self.config.fps)?,
-            )
+            Some(self.backend.build_webcam_pipeline(
+                &webcam_path,
+                self.config.fps,
+                self.config.webcam_format,
+            )?)
         } else {
             None
         };
 
+        let mut pip_pipeline = match &self.config.pip {
+            Some(source) => {
+                let pip_path = sources_dir.join("pip.mkv");
+                Some(
+                    self.backend
+                        .build_pip_pipeline(source, &pip_path, self.config.fps)?,
+                )
+            }
+            None => None,
+        };
+
         let mut mic_pipeline = if self.config.audio.mic {
             let mic_path = sources_dir.join("mic.wav");
             Some(
@@ -295,6 +541,7 @@ impl CaptureSession {
         tracing::info!(
             screen = true,
             webcam = webcam_pipeline.is_some(),
+            pip = pip_pipeline.is_some(),
             mic = mic_pipeline.is_some(),
             system = system_pipeline.is_some(),
             "Starting capture pipelines"
@@ -310,6 +557,12 @@ impl CaptureSession {
             self.webcam_pipeline = Some(webcam_pipeline);
         }
 
+        if let Some(mut pip_pipeline) = pip_pipeline.take() {
+            pip_pipeline.start()?;
+            self.stream_offsets_ns.pip_ns = clock.elapsed_ns() as i64;
+            self.pip_pipeline = Some(pip_pipeline);
+        }
+
         if let Some(mut mic_pipeline) = mic_pipeline.take() {
             mic_pipeline.start()?;
             self.stream_offsets_ns.mic_ns = clock.elapsed_ns() as i64;
@@ -329,7 +582,9 @@ impl CaptureSession {
         // Current input tracker detects backend.
 
         let mut tracker = InputTracker::new(
-            detect_best_backend(),
+            detect_backend_with(InputOptions {
+                gamepad: self.config.gamepad,
+            }),
             events_path,
             clock.clone(),
             capture_width,
@@ -340,18 +595,84 @@ impl CaptureSession {
                 .unwrap_or(1.0),
             self.config.pointer_sample_rate_hz,
         )?;
+        // Only a whole monitor is captured in the recorded monitor's frame.
+        if matches!(self.config.screen.mode, CaptureMode::FullScreen { .. }) {
+            tracker.set_monitor_layout(&project.project.recording);
+        }
+        tracker.set_queue_capacity(self.config.memory.event_queue_capacity);
         self.stream_offsets_ns.events_ns = clock.elapsed_ns() as i64;
 
+        // Persist the epoch now, before offsets are corrected at stop, so an
+        // interrupted recording can still be correlated with the system clock.
+        project.project.recording.clock = clock_info(&clock, self.stream_offsets_ns);
+        project
+            .save()
+            .map_err(|e| GrabmeError::capture(format!("Failed to save project metadata: {e}")))?;
+
         let stop_flag = if let Some(flag) = self.backend.get_input_stop_flag() {
             flag
         } else {
             tracker.stop_flag()
         };
 
+        // The live view is a convenience; the recording goes on without it.
+        let virtual_camera = self.config.virtual_camera.as_ref().filter(|_| {
+            if !self.config.memory.live_previews {
+                tracing::warn!("Virtual camera output is off in the low-memory profile");
+            }
+            self.config.memory.live_previews
+        });
+        if let Some(virtual_camera) = virtual_camera {
+            let monitor = matches!(self.config.screen.mode, CaptureMode::FullScreen { .. })
+                .then_some(selected_monitor.as_ref())
+                .flatten();
+            match self
+                .backend
+                .build_virtual_camera_pipeline(virtual_camera, monitor, tracker.pointer_watch())
+                .and_then(|mut pipeline| pipeline.start().map(|()| pipeline))
+            {
+                Ok(pipeline) => self.virtual_camera_pipeline = Some(pipeline),
+                Err(e) => tracing::warn!(error = %e, "Virtual camera output is unavailable"),
+            }
+        }
+
         self.input_stop_flag = Some(stop_flag);
+        self.reactions = Some(tracker.reaction_sender());
+        self.appearance_task = self.backend.watch_appearance(tracker.event_sender());
+        if self.config.on_lock != LockAction::Ignore {
+            let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+            self.system_task = self.backend.watch_system_session(tx);
+            self.system_events = self.system_task.is_some().then_some(rx);
+        }
+        self.suspend = SuspendDetector::new();
         self.input_task = Some(tokio::spawn(async move { tracker.run().await }));
         tracing::info!("Input tracker task started");
 
+        if let Some((window_id, probe, geometry)) = followed_window {
+            self.window_task = Some(spawn_window_follower(
+                window_id,
+                probe,
+                geometry,
+                clock.clone(),
+                self.stop_flag.clone(),
+            ));
+        }
+
+        if self.config.system_metrics {
+            match spawn_metrics_sampler(
+                &metrics_path(&project.root),
+                clock.clone(),
+                self.stop_flag.clone(),
+            ) {
+                Ok(handle) => self.metrics_task = Some(handle),
+                Err(e) => tracing::warn!(error = %e, "System metrics will not be recorded"),
+            }
+        }
+        self.io_task = Some(spawn_io_monitor(
+            project.root.join("sources"),
+            self.stop_flag.clone(),
+        ));
+
         self.clock = Some(clock);
         self.project = Some(project);
         self.state = SessionState::Recording;
@@ -374,27 +695,79 @@ impl CaptureSession {
             stop.store(true, Ordering::SeqCst);
         }
 
+        if let Some(mut pipeline) = self.virtual_camera_pipeline.take() {
+            if let Err(e) = pipeline.stop() {
+                tracing::warn!(error = %e, "Failed to stop virtual camera output");
+            }
+        }
+        let mut resolution_changes = Vec::new();
         if let Some(mut pipeline) = self.screen_pipeline.take() {
+            resolution_changes = resolution_change_markers(pipeline.as_ref());
             pipeline.stop()?;
         }
+        let mut webcam_gaps = Vec::new();
+        let mut webcam_format = None;
         if let Some(mut pipeline) = self.webcam_pipeline.take() {
+            webcam_gaps = webcam_gap_markers(pipeline.as_ref());
+            webcam_format = pipeline.video_format();
+            pipeline.stop()?;
+        }
+        if let Some(mut pipeline) = self.pip_pipeline.take() {
             pipeline.stop()?;
         }
+        let mut audio_device_fallbacks = Vec::new();
+        let mut mic_format = None;
+        let mut system_format = None;
         if let Some(mut pipeline) = self.mic_pipeline.take() {
+            audio_device_fallbacks.extend(device_fallback_markers("mic", pipeline.as_ref()));
+            mic_format = pipeline.audio_format();
             pipeline.stop()?;
         }
         if let Some(mut pipeline) = self.system_pipeline.take() {
+            audio_device_fallbacks
+                .extend(device_fallback_markers("system_audio", pipeline.as_ref()));
+            system_format = pipeline.audio_format();
             pipeline.stop()?;
         }
 
+        if let Some(handle) = self.appearance_task.take() {
+            handle.abort();
+        }
+        if let Some(handle) = self.system_task.take() {
+            handle.abort();
+        }
+        self.system_events = None;
         if let Some(handle) = self.input_task.take() {
             match handle.await {
-                Ok(Ok(events)) => tracing::info!(events, "Input tracker flushed"),
+                Ok(Ok(stats)) => {
+                    tracing::info!(events = stats.events_written, "Input tracker flushed");
+                    self.input_stats = Some(stats);
+                }
                 Ok(Err(e)) => tracing::warn!(error = %e, "Input tracker exited with error"),
                 Err(e) => tracing::warn!(error = %e, "Input tracker join failed"),
             }
         }
 
+        let window_samples = self
+            .window_task
+            .take()
+            .and_then(|handle| handle.join().ok());
+        if let Some(handle) = self.metrics_task.take() {
+            let _ = handle.join();
+        }
+        if let Some(health) = self.io_task.take().and_then(|handle| handle.join().ok()) {
+            if health.is_degraded() {
+                tracing::warn!(
+                    slow_samples = health.slow_samples,
+                    samples = health.samples,
+                    worst_sync_ms = health.worst_sync_ms,
+                    write_mb_per_sec = health.write_mb_per_sec,
+                    "Recording volume fell behind during the session"
+                );
+            }
+            self.io_health = Some(health);
+        }
+
         // Cleanup backend resources (e.g. close portal session)
         self.backend.shutdown().await?;
 
@@ -415,6 +788,16 @@ impl CaptureSession {
                     );
                 }
 
+                if self.stream_offsets_ns.pip_ns != 0 {
+                    let pip_duration_ns = probe_media_duration_ns(&sources.join("pip.mkv"));
+                    self.stream_offsets_ns.pip_ns = corrected_track_offset_ns(
+                        self.stream_offsets_ns.screen_ns,
+                        self.stream_offsets_ns.pip_ns,
+                        screen_duration_ns,
+                        pip_duration_ns,
+                    );
+                }
+
                 if self.stream_offsets_ns.mic_ns != 0 {
                     let mic_duration_ns = probe_media_duration_ns(&sources.join("mic.wav"));
                     self.stream_offsets_ns.mic_ns = corrected_track_offset_ns(
@@ -446,9 +829,18 @@ impl CaptureSession {
                 CaptureMode::FullScreen { monitor_index } => monitor_index,
                 _ => 0,
             };
+            if let (Some(track), Some(samples)) = (
+                project.project.recording.window_track.as_mut(),
+                window_samples,
+            ) {
+                track.samples = samples;
+            }
 
             let screen_path = project.root.join("sources").join("screen.mkv");
-            if let Some((captured_w, captured_h)) = probe_video_dimensions(&screen_path) {
+            if let Some((captured_w, captured_h)) = probe_media(&screen_path)
+                .ok()
+                .and_then(|info| info.video_size())
+            {
                 let expected_w = project.project.recording.monitor_width;
                 let expected_h = project.project.recording.monitor_height;
                 if expected_w > 0
@@ -466,11 +858,16 @@ impl CaptureSession {
                 }
             }
 
+            project.project.recording.audio_device_fallbacks = audio_device_fallbacks;
+            project.project.recording.webcam_gaps = webcam_gaps;
+            project.project.recording.resolution_changes = resolution_changes;
             project.project.tracks.screen = Some(TrackRef {
                 path: "sources/screen.mkv".to_string(),
                 duration_secs: elapsed,
                 codec: "h264".to_string(),
                 offset_ns: self.stream_offsets_ns.screen_ns,
+                audio: None,
+                video: None,
             });
 
             if self.config.webcam && self.stream_offsets_ns.webcam_ns != 0 {
@@ -479,15 +876,33 @@ impl CaptureSession {
                     duration_secs: elapsed,
                     codec: "h264".to_string(),
                     offset_ns: self.stream_offsets_ns.webcam_ns,
+                    audio: None,
+                    video: webcam_format,
                 });
             }
 
+            if self.config.pip.is_some() && self.stream_offsets_ns.pip_ns != 0 {
+                project.project.tracks.pip = Some(TrackRef {
+                    path: "sources/pip.mkv".to_string(),
+                    duration_secs: elapsed,
+                    codec: "h264".to_string(),
+                    offset_ns: self.stream_offsets_ns.pip_ns,
+                    audio: None,
+                    video: None,
+                });
+            }
+
+            if let Some(format) = mic_format.or(system_format) {
+                project.project.recording.audio_sample_rate = format.sample_rate;
+            }
             if self.config.audio.mic {
                 project.project.tracks.mic = Some(TrackRef {
                     path: "sources/mic.wav".to_string(),
                     duration_secs: elapsed,
                     codec: "pcm".to_string(),
                     offset_ns: self.stream_offsets_ns.mic_ns,
+                    audio: mic_format,
+                    video: None,
                 });
             }
 
@@ -497,6 +912,8 @@ impl CaptureSession {
                     duration_secs: elapsed,
                     codec: "pcm".to_string(),
                     offset_ns: self.stream_offsets_ns.system_ns,
+                    audio: system_format,
+                    video: None,
                 });
             }
 
@@ -508,6 +925,23 @@ impl CaptureSession {
         self.state = SessionState::Stopped;
 
         self.log_clock_drift_check();
+        detach_session_log();
+
+        if let Some(project) = self
+            .project
+            .as_mut()
+            .filter(|_| self.config.spool_dir.is_some())
+        {
+            let destination = self.config.output_dir.join(&self.config.name);
+            tracing::info!(to = %destination.display(), "Moving recording from spool");
+            move_project(&project.root, &destination).map_err(|e| {
+                GrabmeError::capture(format!(
+                    "{e}; the recording is still in {}",
+                    project.root.display()
+                ))
+            })?;
+            project.root = destination;
+        }
 
         Ok(self
             .project
@@ -527,6 +961,9 @@ impl CaptureSession {
         if let Some(ref mut pipeline) = self.webcam_pipeline {
             pipeline.pause()?;
         }
+        if let Some(ref mut pipeline) = self.pip_pipeline {
+            pipeline.pause()?;
+        }
         if let Some(ref mut pipeline) = self.mic_pipeline {
             pipeline.pause()?;
         }
@@ -549,6 +986,9 @@ impl CaptureSession {
         if let Some(ref mut pipeline) = self.webcam_pipeline {
             pipeline.resume()?;
         }
+        if let Some(ref mut pipeline) = self.pip_pipeline {
+            pipeline.resume()?;
+        }
         if let Some(ref mut pipeline) = self.mic_pipeline {
             pipeline.resume()?;
         }
@@ -560,11 +1000,97 @@ impl CaptureSession {
         Ok(())
     }
 
+    /// Apply screen lock and suspend changes reported since the last call,
+    /// as `on_lock` says. Pausing and resuming happen here; a
+    /// [`LockResponse::Stop`], [`Suspend`](LockResponse::Suspend) or
+    /// [`Slept`](LockResponse::Slept) is left to the caller, which owns the
+    /// async [`stop`](Self::stop). Call it regularly while recording; a
+    /// suspend logind didn't report shows up as a gap between calls.
+    pub fn poll_system_events(&mut self) -> Option<LockResponse> {
+        if let Some(slept) = self.suspend.check() {
+            let active = matches!(self.state, SessionState::Recording | SessionState::Paused);
+            if active && self.config.on_lock != LockAction::Ignore {
+                tracing::warn!(
+                    slept_secs = slept.as_secs(),
+                    "System slept while recording; stopping"
+                );
+                return Some(LockResponse::Slept);
+            }
+        }
+        let mut applied = None;
+        while let Some(event) = self
+            .system_events
+            .as_mut()
+            .and_then(|events| events.try_recv().ok())
+        {
+            match event {
+                SystemSessionEvent::Locked => self.screen_locked = true,
+                SystemSessionEvent::Unlocked => self.screen_locked = false,
+                _ => {}
+            }
+            let response = lock_response(
+                self.config.on_lock,
+                event,
+                self.state,
+                self.screen_locked,
+                self.paused_for_lock,
+            );
+            match response {
+                Some(response @ (LockResponse::Stop | LockResponse::Suspend)) => {
+                    tracing::info!(?event, "Stopping recording");
+                    return Some(response);
+                }
+                Some(LockResponse::Pause) => match self.pause() {
+                    Ok(()) => {
+                        self.paused_for_lock = true;
+                        applied = response;
+                    }
+                    Err(e) => tracing::warn!(?event, error = %e, "Failed to pause recording"),
+                },
+                Some(LockResponse::Resume) => match self.resume() {
+                    Ok(()) => {
+                        self.paused_for_lock = false;
+                        applied = response;
+                    }
+                    Err(e) => tracing::warn!(?event, error = %e, "Failed to resume recording"),
+                },
+                Some(LockResponse::Slept) | None => {}
+            }
+        }
+        applied
+    }
+
+    /// Drop a reaction marker at the current pointer position.
+    pub fn add_reaction(&self, emoji: &str) -> GrabmeResult<()> {
+        if self.state != SessionState::Recording {
+            return Err(GrabmeError::capture("Not recording"));
+        }
+        let sent = self
+            .reactions
+            .as_ref()
+            .is_some_and(|reactions| reactions.send(emoji));
+        if !sent {
+            return Err(GrabmeError::input_tracking("Input tracker is not running"));
+        }
+        tracing::info!(emoji, "Reaction added");
+        Ok(())
+    }
+
     /// Get a clone of the stop flag for use in worker threads.
     pub fn stop_flag(&self) -> Arc<AtomicBool> {
         self.stop_flag.clone()
     }
 
+    /// How the input event stream kept up, once the session has stopped.
+    pub fn input_stats(&self) -> Option<InputStats> {
+        self.input_stats
+    }
+
+    /// How the recording volume kept up, once the session has stopped.
+    pub fn io_health(&self) -> Option<IoHealth> {
+        self.io_health
+    }
+
     /// Recording duration so far.
     pub fn elapsed_secs(&self) -> f64 {
         self.clock.as_ref().map(|c| c.elapsed_secs()).unwrap_or(0.0)
@@ -613,6 +1139,7 @@ impl CaptureSession {
         for (label, offset) in [
             ("events", self.stream_offsets_ns.events_ns),
             ("webcam", self.stream_offsets_ns.webcam_ns),
+            ("pip", self.stream_offsets_ns.pip_ns),
             ("mic", self.stream_offsets_ns.mic_ns),
             ("system", self.stream_offsets_ns.system_ns),
         ] {
@@ -634,6 +1161,139 @@ impl CaptureSession {
     }
 }
 
+/// What `action` calls for when `event` arrives in `state`. `locked` is the
+/// lock state after the event. A suspend stops even a paused recording,
+/// since the pipelines don't survive it; only recordings paused for a lock
+/// are resumed, and only into an unlocked screen.
+fn lock_response(
+    action: LockAction,
+    event: SystemSessionEvent,
+    state: SessionState,
+    locked: bool,
+    paused_for_lock: bool,
+) -> Option<LockResponse> {
+    if action == LockAction::Ignore {
+        return None;
+    }
+    match event {
+        SystemSessionEvent::Suspending => {
+            matches!(state, SessionState::Recording | SessionState::Paused)
+                .then_some(LockResponse::Suspend)
+        }
+        SystemSessionEvent::Locked => {
+            if state != SessionState::Recording {
+                return None;
+            }
+            Some(match action {
+                LockAction::Pause => LockResponse::Pause,
+                _ => LockResponse::Stop,
+            })
+        }
+        SystemSessionEvent::Unlocked | SystemSessionEvent::Resumed => {
+            (state == SessionState::Paused && paused_for_lock && !locked)
+                .then_some(LockResponse::Resume)
+        }
+    }
+}
+
+/// Poll a window's geometry until `stop` is set, keeping only changes.
+fn spawn_window_follower(
+    window_id: String,
+    probe: WindowGeometryProbe,
+    initial: WindowGeometry,
+    clock: RecordingClock,
+    stop: Arc<AtomicBool>,
+) -> JoinHandle<Vec<WindowGeometrySample>> {
+    std::thread::spawn(move || {
+        let mut samples = vec![window_sample(0, initial)];
+        let mut last = initial;
+        let mut probe_failed = false;
+
+        while !stop.load(Ordering::SeqCst) {
+            std::thread::sleep(WINDOW_POLL_INTERVAL);
+            match probe(&window_id) {
+                Ok(geometry) if geometry != last => {
+                    tracing::info!(window_id, ?geometry, "Followed window moved or resized");
+                    samples.push(window_sample(clock.elapsed_ns(), geometry));
+                    last = geometry;
+                    probe_failed = false;
+                }
+                Ok(_) => probe_failed = false,
+                Err(e) => {
+                    if !probe_failed {
+                        tracing::warn!(window_id, error = %e, "Lost track of followed window");
+                    }
+                    probe_failed = true;
+                }
+            }
+        }
+
+        samples
+    })
+}
+
+fn window_sample(t_ns: u64, geometry: WindowGeometry) -> WindowGeometrySample {
+    WindowGeometrySample {
+        t_ns,
+        x: geometry.x,
+        y: geometry.y,
+        width: geometry.width,
+        height: geometry.height,
+    }
+}
+
+fn webcam_gap_markers(pipeline: &dyn CapturePipeline) -> Vec<WebcamGap> {
+    let gaps = pipeline.device_gaps_ns();
+    if !gaps.is_empty() {
+        tracing::warn!(
+            gaps = gaps.len(),
+            "Webcam was disconnected during recording; gaps were filled with black"
+        );
+    }
+    gaps.into_iter()
+        .map(|(start_ns, end_ns)| WebcamGap { start_ns, end_ns })
+        .collect()
+}
+
+fn resolution_change_markers(pipeline: &dyn CapturePipeline) -> Vec<ResolutionChange> {
+    let changes = pipeline.resolution_changes_ns();
+    if !changes.is_empty() {
+        tracing::warn!(
+            changes = changes.len(),
+            "Display resolution changed during recording; later frames were scaled to fit"
+        );
+    }
+    changes
+        .into_iter()
+        .map(|(t_ns, width, height)| ResolutionChange {
+            t_ns,
+            width,
+            height,
+        })
+        .collect()
+}
+
+fn device_fallback_markers(
+    track: &str,
+    pipeline: &dyn CapturePipeline,
+) -> Vec<AudioDeviceFallback> {
+    let markers = pipeline.device_fallbacks_ns();
+    if !markers.is_empty() {
+        tracing::warn!(
+            track,
+            fallbacks = markers.len(),
+            "Audio device was lost during recording; gaps were filled with silence"
+        );
+    }
+    markers
+        .into_iter()
+        .map(|t_ns| AudioDeviceFallback {
+            track: track.to_string(),
+            t_ns,
+        })
+        .collect()
+}
+
 fn format_monitor_list(monitors: &[MonitorInfo]) -> String {
     monitors
         .iter()
@@ -653,6 +1313,23 @@ fn format_monitor_list(monitors: &[MonitorInfo]) -> String {
         .join(", ")
 }
 
+fn clock_info(clock: &RecordingClock, starts: StreamOffsets) -> RecordingClockInfo {
+    let started = |ns: i64| (ns != 0).then_some(ns);
+    RecordingClockInfo {
+        source: clock.source(),
+        epoch_monotonic_ns: clock.epoch_monotonic_ns(),
+        epoch_wall: clock.epoch_wall().to_string(),
+        pipeline_starts_ns: PipelineStarts {
+            screen: started(starts.screen_ns),
+            webcam: started(starts.webcam_ns),
+            pip: started(starts.pip_ns),
+            mic: started(starts.mic_ns),
+            system_audio: started(starts.system_ns),
+            events: started(starts.events_ns),
+        },
+    }
+}
+
 fn corrected_track_offset_ns(
     screen_offset_ns: i64,
     measured_track_offset_ns: i64,
@@ -676,62 +1353,19 @@ fn corrected_track_offset_ns(
 }
 
 fn probe_media_duration_ns(path: &std::path::Path) -> Option<i64> {
-    let output = Command::new("ffprobe")
-        .args([
-            "-v",
-            "error",
-            "-show_entries",
-            "format=duration",
-            "-of",
-            "default=noprint_wrappers=1:nokey=1",
-        ])
-        .arg(path)
-        .output()
-        .ok()?;
-
-    if !output.status.success() {
-        return None;
-    }
-
-    let raw = String::from_utf8(output.stdout).ok()?;
-    let secs = raw.lines().next()?.trim().parse::<f64>().ok()?;
-    if !secs.is_finite() || secs <= 0.0 {
-        return None;
-    }
-
+    let secs = probe_media(path).ok()?.duration_secs?;
     Some((secs * 1_000_000_000.0).round() as i64)
 }
 
-fn probe_video_dimensions(path: &std::path::Path) -> Option<(u32, u32)> {
-    let output = Command::new("ffprobe")
-        .args([
-            "-v",
-            "error",
-            "-select_streams",
-            "v:0",
-            "-show_entries",
-            "stream=width,height",
-            "-of",
-            "csv=p=0:s=x",
-        ])
-        .arg(path)
-        .output()
-        .ok()?;
-
-    if !output.status.success() {
-        return None;
-    }
-
-    let raw = String::from_utf8(output.stdout).ok()?;
-    let line = raw.lines().next()?.trim();
-    let (w, h) = line.split_once('x')?;
-    let width = w.parse::<u32>().ok()?;
-    let height = h.parse::<u32>().ok()?;
-    if width == 0 || height == 0 {
-        return None;
+impl SessionConfig {
+    /// The same recording settings for the segment after a suspend, under
+    /// the next free segment name.
+    pub fn next_segment(&self) -> Self {
+        Self {
+            name: next_segment_name(&self.output_dir, &self.name),
+            ..self.clone()
+        }
     }
-
-    Some((width, height))
 }
 
 impl Default for SessionConfig {
@@ -742,6 +1376,8 @@ impl Default for SessionConfig {
             screen: ScreenCaptureConfig {
                 mode: CaptureMode::FullScreen { monitor_index: 0 },
                 hide_cursor: true,
+                cursor_mode: None,
+                monitor_splits: BTreeMap::new(),
             },
             audio: AudioCaptureConfig {
                 mic: true,
@@ -750,8 +1386,20 @@ impl Default for SessionConfig {
                 sample_rate: 48000,
             },
             webcam: false,
+            webcam_format: WebcamFormatRequest::default(),
             fps: 60,
             pointer_sample_rate_hz: 60,
+            clock_source: ClockSource::default(),
+            display: DisplayTarget::default(),
+            auto_adjust_fps: false,
+            gamepad: false,
+            pip: None,
+            frame_times: false,
+            system_metrics: false,
+            on_lock: LockAction::default(),
+            virtual_camera: None,
+            spool_dir: None,
+            memory: MemoryProfile::default(),
         }
     }
 }
@@ -797,4 +1445,47 @@ mod tests {
         // blended delta should be +80ms, absolute offset = 90ms.
         assert_eq!(corrected, 90_000_000);
     }
+
+    #[test]
+    fn lock_response_stops_or_pauses_and_resumes_only_its_own_pause() {
+        use SystemSessionEvent::*;
+
+        let recording = SessionState::Recording;
+        let paused = SessionState::Paused;
+        assert_eq!(
+            lock_response(LockAction::Stop, Locked, recording, true, false),
+            Some(LockResponse::Stop)
+        );
+        assert_eq!(
+            lock_response(LockAction::Ignore, Suspending, recording, false, false),
+            None
+        );
+        assert_eq!(
+            lock_response(LockAction::Pause, Locked, recording, true, false),
+            Some(LockResponse::Pause)
+        );
+        // Pipelines die in a suspend, so even a paused recording is saved.
+        assert_eq!(
+            lock_response(LockAction::Pause, Suspending, paused, true, true),
+            Some(LockResponse::Suspend)
+        );
+        assert_eq!(
+            lock_response(LockAction::Stop, Suspending, recording, false, false),
+            Some(LockResponse::Suspend)
+        );
+        // Waking up still locked waits for the unlock.
+        assert_eq!(
+            lock_response(LockAction::Pause, Resumed, paused, true, true),
+            None
+        );
+        assert_eq!(
+            lock_response(LockAction::Pause, Unlocked, paused, false, true),
+            Some(LockResponse::Resume)
+        );
+        // A pause the user asked for is left alone.
+        assert_eq!(
+            lock_response(LockAction::Pause, Unlocked, paused, false, false),
+            None
+        );
+    }
 }
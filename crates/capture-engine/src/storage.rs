@@ -0,0 +1,269 @@
+//! Recording to network shares and removable drives.
+//!
+//! NFS/SMB mounts and thumb drives can take seconds to sync, long enough to
+//! back up the capture pipelines. A session can record into a local spool
+//! directory and move the finished project to its destination on stop. The
+//! volume being written is also watched while recording: a small synced
+//! write is timed every few seconds, so a volume that can't keep up is
+//! reported while recording instead of showing up as dropped frames.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use grabme_common::error::{GrabmeError, GrabmeResult};
+
+/// How often the recording volume is probed.
+pub const IO_MONITOR_INTERVAL: Duration = Duration::from_secs(2);
+
+/// A synced probe write slower than this means the volume is falling
+/// behind the recording.
+pub const SLOW_SYNC: Duration = Duration::from_millis(500);
+
+/// Name of the probe file, removed when monitoring ends.
+const PROBE_FILE: &str = ".grabme-io-probe";
+
+/// Bytes written per probe.
+const PROBE_BYTES: usize = 4096;
+
+/// Where a path is stored, as far as write latency goes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VolumeKind {
+    Local,
+    /// NFS, SMB and other network file systems.
+    Network,
+    /// File systems typical of thumb drives and SD cards.
+    Removable,
+}
+
+/// Kind of the volume holding `path`, from the longest matching mount
+/// point in `mounts` (the format of `/proc/mounts`).
+pub fn parse_volume_kind(mounts: &str, path: &Path) -> VolumeKind {
+    let fs_type = mounts
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let _device = fields.next()?;
+            // Spaces in mount points are written as `\040`.
+            let mount_point = fields.next()?.replace("\\040", " ");
+            let fs_type = fields.next()?;
+            path.starts_with(&mount_point)
+                .then_some((mount_point.len(), fs_type))
+        })
+        .max_by_key(|(len, _)| *len)
+        .map(|(_, fs_type)| fs_type);
+
+    match fs_type {
+        Some("nfs" | "nfs4" | "cifs" | "smb3" | "smbfs" | "fuse.sshfs" | "9p" | "afs") => {
+            VolumeKind::Network
+        }
+        Some("vfat" | "exfat" | "msdos" | "fuseblk" | "ntfs" | "ntfs3") => VolumeKind::Removable,
+        _ => VolumeKind::Local,
+    }
+}
+
+/// Kind of the volume holding `path`. Paths that don't exist yet are
+/// resolved through their nearest existing parent; `Local` when the mount
+/// table can't be read.
+pub fn volume_kind(path: &Path) -> VolumeKind {
+    let Ok(mounts) = std::fs::read_to_string("/proc/mounts") else {
+        return VolumeKind::Local;
+    };
+    let resolved = path
+        .ancestors()
+        .find_map(|ancestor| ancestor.canonicalize().ok())
+        .unwrap_or_else(|| path.to_path_buf());
+    parse_volume_kind(&mounts, &resolved)
+}
+
+/// How the recording volume kept up.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct IoHealth {
+    /// Probes taken.
+    pub samples: u32,
+
+    /// Probes slower than [`SLOW_SYNC`].
+    pub slow_samples: u32,
+
+    /// Slowest synced probe write, in milliseconds.
+    pub worst_sync_ms: u64,
+
+    /// Average rate the recording was written at, in MB/s.
+    pub write_mb_per_sec: f64,
+}
+
+impl IoHealth {
+    /// Whether the volume fell behind at any point.
+    pub fn is_degraded(&self) -> bool {
+        self.slow_samples > 0
+    }
+}
+
+/// Probe `dir` every [`IO_MONITOR_INTERVAL`] until `stop` is set, warning
+/// when a synced write takes longer than [`SLOW_SYNC`].
+pub fn spawn_io_monitor(dir: PathBuf, stop: Arc<AtomicBool>) -> JoinHandle<IoHealth> {
+    std::thread::spawn(move || {
+        let probe_path = dir.join(PROBE_FILE);
+        let started = Instant::now();
+        let start_bytes = dir_size(&dir);
+        let mut last_bytes = start_bytes;
+        let mut health = IoHealth::default();
+        while !stop.load(Ordering::SeqCst) {
+            std::thread::sleep(IO_MONITOR_INTERVAL);
+            let sync = match time_synced_write(&probe_path) {
+                Ok(sync) => sync,
+                Err(e) => {
+                    tracing::warn!(error = %e, "Stopped monitoring recording volume");
+                    break;
+                }
+            };
+            let bytes = dir_size(&dir);
+            let mb_per_sec = bytes.saturating_sub(last_bytes) as f64
+                / (1024.0 * 1024.0)
+                / IO_MONITOR_INTERVAL.as_secs_f64();
+            last_bytes = bytes;
+
+            health.samples += 1;
+            health.worst_sync_ms = health.worst_sync_ms.max(sync.as_millis() as u64);
+            if sync > SLOW_SYNC {
+                health.slow_samples += 1;
+                // Once, then quietly: the summary at stop has the totals.
+                if health.slow_samples == 1 {
+                    tracing::warn!(
+                        dir = %dir.display(),
+                        sync_ms = sync.as_millis() as u64,
+                        mb_per_sec,
+                        "Recording volume is slow to sync and may not keep up; \
+                         record to a local spool directory instead"
+                    );
+                } else {
+                    tracing::debug!(sync_ms = sync.as_millis() as u64, mb_per_sec, "Slow sync");
+                }
+            }
+        }
+        let _ = std::fs::remove_file(&probe_path);
+        health.write_mb_per_sec = dir_size(&dir).saturating_sub(start_bytes) as f64
+            / (1024.0 * 1024.0)
+            / started.elapsed().as_secs_f64().max(1e-6);
+        health
+    })
+}
+
+/// Write and sync a small block to `path`, returning how long it took.
+fn time_synced_write(path: &Path) -> std::io::Result<Duration> {
+    let started = Instant::now();
+    let mut file = std::fs::File::create(path)?;
+    file.write_all(&[0u8; PROBE_BYTES])?;
+    file.sync_data()?;
+    Ok(started.elapsed())
+}
+
+/// Total size of the files under `dir`.
+fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(kind) if kind.is_dir() => dir_size(&entry.path()),
+            Ok(_) => entry.metadata().map(|meta| meta.len()).unwrap_or(0),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+/// Move a finished project from the spool to `to`. A rename when both are
+/// on one volume; otherwise the files are copied and synced before the
+/// spooled copy is removed, so an interrupted move never loses the only
+/// copy.
+pub fn move_project(from: &Path, to: &Path) -> GrabmeResult<()> {
+    if to.exists() {
+        return Err(GrabmeError::capture(format!(
+            "Cannot move recording to {}: it already exists",
+            to.display()
+        )));
+    }
+    if let Some(parent) = to.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    if std::fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+
+    tracing::info!(from = %from.display(), to = %to.display(), "Copying spooled recording");
+    if let Err(e) = copy_dir_synced(from, to) {
+        let _ = std::fs::remove_dir_all(to);
+        return Err(GrabmeError::capture(format!(
+            "Failed to copy recording to {}: {e}",
+            to.display()
+        )));
+    }
+    std::fs::remove_dir_all(from)?;
+    Ok(())
+}
+
+/// Copy `from` into a new directory `to`, syncing every file.
+fn copy_dir_synced(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::fs::create_dir(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir_synced(&entry.path(), &target)?;
+        } else {
+            std::fs::copy(entry.path(), &target)?;
+            std::fs::File::open(&target)?.sync_all()?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_volume_kind_uses_the_longest_mount_point() {
+        let mounts = "/dev/nvme0n1p2 / ext4 rw,relatime 0 0\n\
+                      nas:/export /mnt/nas nfs4 rw 0 0\n\
+                      //host/share /mnt/nas/smb cifs rw 0 0\n\
+                      /dev/sdb1 /media/me/USB\\040STICK vfat rw 0 0\n";
+        let kind = |path: &str| parse_volume_kind(mounts, Path::new(path));
+        assert_eq!(kind("/home/me/Videos"), VolumeKind::Local);
+        assert_eq!(kind("/mnt/nas/GrabMe"), VolumeKind::Network);
+        assert_eq!(kind("/mnt/nas/smb/GrabMe"), VolumeKind::Network);
+        assert_eq!(kind("/media/me/USB STICK/take"), VolumeKind::Removable);
+        // A mount point prefix that isn't a parent directory.
+        assert_eq!(kind("/mnt/nasty"), VolumeKind::Local);
+    }
+
+    #[test]
+    fn test_move_project_copies_across_and_refuses_to_overwrite() {
+        let root = std::env::temp_dir().join(format!("grabme_test_spool_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let spooled = root.join("spool").join("take");
+        std::fs::create_dir_all(spooled.join("sources")).unwrap();
+        std::fs::write(spooled.join("project.json"), "{}").unwrap();
+        std::fs::write(spooled.join("sources").join("screen.mkv"), [1u8; 64]).unwrap();
+
+        let copied = root.join("copy");
+        copy_dir_synced(&spooled, &copied).unwrap();
+        assert_eq!(dir_size(&copied), dir_size(&spooled));
+
+        let dest = root.join("dest").join("take");
+        move_project(&spooled, &dest).unwrap();
+        assert!(!spooled.exists());
+        assert_eq!(
+            std::fs::read(dest.join("sources").join("screen.mkv")).unwrap(),
+            [1u8; 64]
+        );
+
+        let err = move_project(&copied, &dest).unwrap_err();
+        assert!(err.to_string().contains("already exists"));
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,101 @@
+//! Recording across a laptop suspend.
+//!
+//! Capture pipelines don't survive a suspend: on wake they are dead while
+//! the session still thinks it is recording. logind warns before sleeping
+//! when it is reachable, but without it (or when the warning is missed) the
+//! only trace is in the clocks: `Instant` stops while the system sleeps and
+//! the wall clock doesn't. A session that slept is stopped to save what was
+//! recorded before, and the caller may carry on in a new segment, a new
+//! project named after the first.
+
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime};
+
+/// The wall clock running ahead of `Instant` by more than this between two
+/// checks means the system slept. NTP steps stay well below it.
+pub const MIN_SUSPEND_GAP: Duration = Duration::from_secs(5);
+
+/// How long to wait for the system to sleep after it said it would. logind
+/// gives up on delay inhibitors after `InhibitDelayMaxSec` (5s by default),
+/// so a suspend that hasn't happened by then was cancelled.
+pub const SUSPEND_WAIT: Duration = Duration::from_secs(30);
+
+/// Notices, after the fact, that the system slept.
+#[derive(Debug, Clone, Copy)]
+pub struct SuspendDetector {
+    last_instant: Instant,
+    last_wall: SystemTime,
+}
+
+impl SuspendDetector {
+    pub fn new() -> Self {
+        Self {
+            last_instant: Instant::now(),
+            last_wall: SystemTime::now(),
+        }
+    }
+
+    /// How long the system slept since the last check, if it did.
+    pub fn check(&mut self) -> Option<Duration> {
+        let now_instant = Instant::now();
+        let now_wall = SystemTime::now();
+        let monotonic = now_instant.duration_since(self.last_instant);
+        // A wall clock stepped backwards is no sign of sleep.
+        let wall = now_wall.duration_since(self.last_wall).unwrap_or_default();
+        self.last_instant = now_instant;
+        self.last_wall = now_wall;
+        suspend_gap(monotonic, wall)
+    }
+}
+
+impl Default for SuspendDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn suspend_gap(monotonic: Duration, wall: Duration) -> Option<Duration> {
+    wall.checked_sub(monotonic)
+        .filter(|gap| *gap > MIN_SUSPEND_GAP)
+}
+
+/// Name for the segment recorded after `name` in `output_dir`: `demo`
+/// continues as `demo-2`, `demo-2` as `demo-3`, skipping names that are
+/// already taken.
+pub fn next_segment_name(output_dir: &Path, name: &str) -> String {
+    let (base, number) = match name.rsplit_once('-') {
+        Some((base, number)) if !base.is_empty() => match number.parse::<u32>() {
+            Ok(number) => (base, number),
+            Err(_) => (name, 1),
+        },
+        _ => (name, 1),
+    };
+    (number.saturating_add(1)..)
+        .map(|number| format!("{base}-{number}"))
+        .find(|candidate| !output_dir.join(candidate).exists())
+        .expect("segment numbers run out only after u32::MAX projects")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suspend_gap_and_segment_names() {
+        let secs = Duration::from_secs;
+        assert_eq!(suspend_gap(secs(1), secs(1)), None);
+        assert_eq!(suspend_gap(secs(1), secs(4)), None);
+        assert_eq!(suspend_gap(secs(1), secs(61)), Some(secs(60)));
+        assert_eq!(suspend_gap(secs(10), secs(1)), None);
+
+        let dir = std::env::temp_dir().join(format!("grabme_test_segments_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        assert_eq!(next_segment_name(&dir, "demo"), "demo-2");
+        assert_eq!(next_segment_name(&dir, "demo-2"), "demo-3");
+        assert_eq!(next_segment_name(&dir, "take-two"), "take-two-2");
+        std::fs::create_dir(dir.join("demo-2")).unwrap();
+        assert_eq!(next_segment_name(&dir, "demo"), "demo-3");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,219 @@
+//! Live zoom-follow view sent to a virtual webcam.
+//!
+//! While recording, the screen can also be mirrored to a virtual camera so
+//! the same cursor-following view can be presented in a video call. The
+//! view is a crop of the live screen that eases toward the pointer, scaled
+//! to a webcam-sized frame. Where the frames go is a [`VirtualCameraSink`]:
+//! v4l2loopback on Linux, with other platforms to follow behind the same
+//! trait.
+
+use std::path::{Path, PathBuf};
+
+use grabme_common::error::{GrabmeError, GrabmeResult};
+
+/// Default magnification of the followed view.
+pub const DEFAULT_VIRTUAL_CAMERA_ZOOM: f64 = 1.5;
+
+/// How far the view moves toward the pointer per update [0.0, 1.0].
+const FOLLOW_SMOOTHING: f64 = 0.15;
+
+/// Settings for the live virtual camera view.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VirtualCameraConfig {
+    /// Device to write to, such as `/dev/video10`. Found automatically when
+    /// `None`.
+    pub device: Option<String>,
+    /// Frame size presented to the call.
+    pub width: u32,
+    pub height: u32,
+    /// Frame rate presented to the call.
+    pub fps: u32,
+    /// Magnification of the followed view; 1.0 shows the whole screen.
+    pub zoom: f64,
+}
+
+impl Default for VirtualCameraConfig {
+    fn default() -> Self {
+        Self {
+            device: None,
+            width: 1280,
+            height: 720,
+            fps: 30,
+            zoom: DEFAULT_VIRTUAL_CAMERA_ZOOM,
+        }
+    }
+}
+
+/// Output end of the live view: something that takes raw video and
+/// presents it to other applications as a camera.
+pub trait VirtualCameraSink: Send + Sync {
+    /// Device or endpoint shown to the user.
+    fn describe(&self) -> String;
+
+    /// GStreamer elements ending the live view branch. They receive raw
+    /// video at the configured size and frame rate.
+    fn launch_fragment(&self) -> String;
+}
+
+/// A v4l2loopback device node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct V4l2LoopbackSink {
+    device: PathBuf,
+}
+
+impl V4l2LoopbackSink {
+    /// Use `device`, or the first v4l2loopback device when `None`.
+    pub fn open(device: Option<&str>) -> GrabmeResult<Self> {
+        let device = match device {
+            Some(device) => PathBuf::from(device),
+            None => find_loopback_device(Path::new("/sys/class/video4linux")).ok_or_else(|| {
+                GrabmeError::unsupported(
+                    "No v4l2loopback device found. Load the module with \
+                     `sudo modprobe v4l2loopback exclusive_caps=1 card_label=GrabMe`",
+                )
+            })?,
+        };
+        if !device.exists() {
+            return Err(GrabmeError::capture(format!(
+                "Virtual camera device {} does not exist",
+                device.display()
+            )));
+        }
+        Ok(Self { device })
+    }
+}
+
+impl VirtualCameraSink for V4l2LoopbackSink {
+    fn describe(&self) -> String {
+        self.device.display().to_string()
+    }
+
+    fn launch_fragment(&self) -> String {
+        // Browsers and most call apps only accept YUY2 from loopback
+        // devices.
+        format!(
+            "videoconvert ! video/x-raw,format=YUY2 ! v4l2sink device=\"{}\" sync=false",
+            self.device.display().to_string().replace('"', "")
+        )
+    }
+}
+
+/// First `/dev/videoN` whose sysfs card name is a v4l2loopback one.
+fn find_loopback_device(sysfs: &Path) -> Option<PathBuf> {
+    let mut nodes: Vec<(u32, PathBuf)> = std::fs::read_dir(sysfs)
+        .ok()?
+        .flatten()
+        .filter_map(|entry| {
+            let node = entry.file_name().to_string_lossy().into_owned();
+            let index = node.strip_prefix("video")?.parse::<u32>().ok()?;
+            let name = std::fs::read_to_string(entry.path().join("name")).ok()?;
+            is_loopback_card(&name).then(|| (index, PathBuf::from("/dev").join(node)))
+        })
+        .collect();
+    nodes.sort();
+    nodes.into_iter().next().map(|(_, device)| device)
+}
+
+/// Whether a V4L2 card name belongs to v4l2loopback. Its default label is
+/// "Dummy video device (0x0000)"; custom labels usually say loopback or
+/// name the app.
+fn is_loopback_card(name: &str) -> bool {
+    let name = name.trim().to_ascii_lowercase();
+    name.starts_with("dummy video device") || name.contains("loopback") || name.contains("grabme")
+}
+
+/// Eases a crop of the screen toward the pointer.
+#[derive(Debug, Clone)]
+pub struct FollowCamera {
+    zoom: f64,
+    center: Option<(f64, f64)>,
+}
+
+impl FollowCamera {
+    pub fn new(zoom: f64) -> Self {
+        Self {
+            zoom: if zoom.is_finite() { zoom.max(1.0) } else { 1.0 },
+            center: None,
+        }
+    }
+
+    /// Move toward `pointer` (normalized, `None` when unknown) and return
+    /// the crop of a `width`x`height` frame as `(left, top, right, bottom)`
+    /// pixels to cut away.
+    pub fn step(
+        &mut self,
+        pointer: Option<(f64, f64)>,
+        width: u32,
+        height: u32,
+    ) -> (u32, u32, u32, u32) {
+        let view = 1.0 / self.zoom;
+        let half = view / 2.0;
+        let target = pointer
+            .map(|(x, y)| (x.clamp(half, 1.0 - half), y.clamp(half, 1.0 - half)))
+            .or(self.center)
+            .unwrap_or((0.5, 0.5));
+        let center = match self.center {
+            Some((cx, cy)) => (
+                cx + (target.0 - cx) * FOLLOW_SMOOTHING,
+                cy + (target.1 - cy) * FOLLOW_SMOOTHING,
+            ),
+            None => target,
+        };
+        self.center = Some(center);
+
+        // Even sizes and offsets keep chroma-subsampled frames aligned.
+        let even = |v: f64| ((v / 2.0).round() as u32) * 2;
+        let crop_w = even(width as f64 * view).clamp(2, width.max(2));
+        let crop_h = even(height as f64 * view).clamp(2, height.max(2));
+        let left = even((center.0 - half) * width as f64).min(width.saturating_sub(crop_w));
+        let top = even((center.1 - half) * height as f64).min(height.saturating_sub(crop_h));
+        (
+            left,
+            top,
+            width.saturating_sub(left + crop_w),
+            height.saturating_sub(top + crop_h),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_follow_camera_stays_inside_the_frame() {
+        let mut camera = FollowCamera::new(2.0);
+        assert_eq!(
+            camera.step(Some((0.5, 0.5)), 1920, 1080),
+            (480, 270, 480, 270)
+        );
+
+        let mut camera = FollowCamera::new(2.0);
+        assert_eq!(camera.step(Some((0.0, 1.0)), 1920, 1080), (0, 540, 960, 0));
+    }
+
+    #[test]
+    fn test_follow_camera_eases_toward_the_pointer() {
+        let mut camera = FollowCamera::new(2.0);
+        camera.step(Some((0.25, 0.5)), 1920, 1080);
+        let (left, ..) = camera.step(Some((0.75, 0.5)), 1920, 1080);
+        assert!(left > 0 && left < 960, "left = {left}");
+        // Without a pointer the view holds still.
+        let held = camera.step(None, 1920, 1080);
+        assert_eq!(camera.step(None, 1920, 1080), held);
+    }
+
+    #[test]
+    fn test_zoom_one_shows_the_whole_screen() {
+        let mut camera = FollowCamera::new(1.0);
+        assert_eq!(camera.step(Some((0.9, 0.1)), 1920, 1080), (0, 0, 0, 0));
+    }
+
+    #[test]
+    fn test_loopback_cards_are_recognized() {
+        assert!(is_loopback_card("Dummy video device (0x0000)\n"));
+        assert!(is_loopback_card("GrabMe"));
+        assert!(is_loopback_card("OBS Virtual Camera loopback"));
+        assert!(!is_loopback_card("Integrated Camera: Integrated C"));
+    }
+}
@@ -0,0 +1,250 @@
+//! Webcam format negotiation.
+//!
+//! Cameras offer a fixed list of frame sizes, each at a few frame rates,
+//! and often only reach 30 fps at larger sizes as Motion-JPEG. Capture
+//! picks the listed mode closest to the configured size and rate, keeping
+//! the camera's aspect ratio, and the webcam track records the format that
+//! was used so export can size the overlay to it.
+
+use grabme_common::config::RecordingDefaults;
+use grabme_project_model::project::VideoFormat;
+
+/// Webcam size and rate to ask for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WebcamFormatRequest {
+    /// Frame size to prefer. `None` keeps the camera's current size.
+    pub size: Option<(u32, u32)>,
+
+    /// Frame rate to prefer. `None` uses the recording frame rate, capped
+    /// at 30.
+    pub fps: Option<u32>,
+}
+
+impl WebcamFormatRequest {
+    /// The webcam settings in the recording defaults. A size needs both
+    /// `webcam_width` and `webcam_height`.
+    pub fn from_defaults(defaults: &RecordingDefaults) -> Self {
+        Self {
+            size: defaults.webcam_width.zip(defaults.webcam_height),
+            fps: defaults.webcam_fps,
+        }
+    }
+}
+
+/// One capture mode a camera lists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WebcamMode {
+    pub width: u32,
+    pub height: u32,
+    /// Frame rate in thousandths, so 29.97 fps stays distinct from 30.
+    pub fps_milli: u32,
+    /// Frames come as Motion-JPEG and need decoding.
+    pub mjpeg: bool,
+}
+
+impl WebcamMode {
+    /// Whole frames per second, at least 1.
+    pub fn fps(&self) -> u32 {
+        ((self.fps_milli + 500) / 1000).max(1)
+    }
+
+    /// Caps selecting this mode on `v4l2src`, plus the decoder it needs.
+    pub fn source_caps(&self) -> String {
+        let (width, height) = (self.width, self.height);
+        // A narrow range matches the camera's exact fraction, e.g. 30000/1001.
+        let rate = format!(
+            "[{}/1000,{}/1000]",
+            self.fps_milli.saturating_sub(1),
+            self.fps_milli + 1
+        );
+        if self.mjpeg {
+            format!("image/jpeg,width={width},height={height},framerate={rate} ! jpegdec")
+        } else {
+            format!("video/x-raw,width={width},height={height},framerate={rate}")
+        }
+    }
+}
+
+/// The listed mode to record `wanted` with: the closest frame size,
+/// preferring the wanted aspect ratio, then the lowest rate that reaches
+/// `wanted.fps` (or the fastest one), then uncompressed frames. `None` when
+/// the camera listed nothing.
+pub fn negotiate_webcam_mode(wanted: VideoFormat, modes: &[WebcamMode]) -> Option<WebcamMode> {
+    let wanted_aspect = wanted.width as f64 / wanted.height.max(1) as f64;
+    let wanted_pixels = wanted.width as u64 * wanted.height as u64;
+    let size = modes
+        .iter()
+        .min_by_key(|mode| {
+            let aspect = mode.width as f64 / mode.height.max(1) as f64;
+            let pixels = mode.width as u64 * mode.height as u64;
+            (
+                (aspect - wanted_aspect).abs() > 0.01,
+                pixels.abs_diff(wanted_pixels),
+            )
+        })
+        .map(|mode| (mode.width, mode.height))?;
+
+    let wanted_milli = wanted.fps.saturating_mul(1000);
+    modes
+        .iter()
+        .filter(|mode| (mode.width, mode.height) == size)
+        .min_by_key(|mode| {
+            // 29.97 counts as reaching 30.
+            let too_slow = mode.fps_milli + 50 < wanted_milli;
+            (
+                too_slow,
+                if too_slow {
+                    u32::MAX - mode.fps_milli
+                } else {
+                    mode.fps_milli - wanted_milli.min(mode.fps_milli)
+                },
+                mode.mjpeg,
+            )
+        })
+        .copied()
+}
+
+/// Modes listed by `v4l2-ctl --list-formats-ext`. Only discrete sizes and
+/// intervals of raw or Motion-JPEG formats are kept; other compressed
+/// formats such as H.264 would need their own decoder.
+pub fn parse_v4l2_formats(stdout: &str) -> Vec<WebcamMode> {
+    let mut modes = Vec::new();
+    let mut usable = false;
+    let mut mjpeg = false;
+    let mut size = None;
+    for line in stdout.lines().map(str::trim) {
+        if line.starts_with('[') {
+            // `[0]: 'MJPG' (Motion-JPEG, compressed)`
+            let fourcc = line.split('\'').nth(1).unwrap_or_default();
+            mjpeg = fourcc == "MJPG";
+            usable = mjpeg || !line.contains("compressed");
+            size = None;
+        } else if let Some(rest) = line.strip_prefix("Size: Discrete ") {
+            size = rest.split_once('x').and_then(|(width, height)| {
+                Some((width.trim().parse().ok()?, height.trim().parse().ok()?))
+            });
+        } else if line.starts_with("Size:") {
+            size = None;
+        } else if let Some(rest) = line.strip_prefix("Interval: Discrete ") {
+            // `Interval: Discrete 0.033s (30.000 fps)`
+            let fps = rest
+                .split_once('(')
+                .and_then(|(_, fps)| fps.strip_suffix(" fps)"))
+                .and_then(|fps| fps.trim().parse::<f64>().ok());
+            if let (true, Some((width, height)), Some(fps)) = (usable, size, fps) {
+                let mode = WebcamMode {
+                    width,
+                    height,
+                    fps_milli: (fps * 1000.0).round() as u32,
+                    mjpeg,
+                };
+                if width > 0 && height > 0 && mode.fps_milli > 0 && !modes.contains(&mode) {
+                    modes.push(mode);
+                }
+            }
+        }
+    }
+    modes
+}
+
+/// Modes the V4L2 device at `dev_path` lists. Empty when `v4l2-ctl` is
+/// missing or fails.
+pub fn probe_v4l2_modes(dev_path: &str) -> Vec<WebcamMode> {
+    let Ok(output) = std::process::Command::new("v4l2-ctl")
+        .args(["--device", dev_path, "--list-formats-ext"])
+        .output()
+    else {
+        return Vec::new();
+    };
+    if !output.status.success() {
+        return Vec::new();
+    }
+    parse_v4l2_formats(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIST_FORMATS: &str = "ioctl: VIDIOC_ENUM_FMT
+\tType: Video Capture
+
+\t[0]: 'YUYV' (YUYV 4:2:2)
+\t\tSize: Discrete 640x480
+\t\t\tInterval: Discrete 0.033s (30.000 fps)
+\t\tSize: Discrete 1280x720
+\t\t\tInterval: Discrete 0.100s (10.000 fps)
+\t[1]: 'MJPG' (Motion-JPEG, compressed)
+\t\tSize: Discrete 1280x720
+\t\t\tInterval: Discrete 0.033s (29.970 fps)
+\t\t\tInterval: Discrete 0.067s (15.000 fps)
+\t\tSize: Discrete 640x480
+\t\t\tInterval: Discrete 0.033s (30.000 fps)
+\t[2]: 'H264' (H.264, compressed)
+\t\tSize: Discrete 1920x1080
+\t\t\tInterval: Discrete 0.033s (30.000 fps)
+";
+
+    fn mode(width: u32, height: u32, fps_milli: u32, mjpeg: bool) -> WebcamMode {
+        WebcamMode {
+            width,
+            height,
+            fps_milli,
+            mjpeg,
+        }
+    }
+
+    fn wanted(width: u32, height: u32, fps: u32) -> VideoFormat {
+        VideoFormat { width, height, fps }
+    }
+
+    #[test]
+    fn test_parse_v4l2_formats_keeps_raw_and_mjpeg_modes() {
+        assert_eq!(
+            parse_v4l2_formats(LIST_FORMATS),
+            vec![
+                mode(640, 480, 30_000, false),
+                mode(1280, 720, 10_000, false),
+                mode(1280, 720, 29_970, true),
+                mode(1280, 720, 15_000, true),
+                mode(640, 480, 30_000, true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_negotiation_picks_size_then_rate_then_raw() {
+        let modes = parse_v4l2_formats(LIST_FORMATS);
+        // 720p only reaches 30 fps as MJPEG.
+        let hd = negotiate_webcam_mode(wanted(1280, 720, 30), &modes).unwrap();
+        assert_eq!(hd, mode(1280, 720, 29_970, true));
+        assert_eq!(hd.fps(), 30);
+        assert_eq!(
+            hd.source_caps(),
+            "image/jpeg,width=1280,height=720,framerate=[29969/1000,29971/1000] ! jpegdec"
+        );
+        // Same rate in both formats: raw wins.
+        assert_eq!(
+            negotiate_webcam_mode(wanted(640, 480, 30), &modes),
+            Some(mode(640, 480, 30_000, false))
+        );
+        // A 1080p request falls back to the largest 16:9 mode.
+        assert_eq!(
+            negotiate_webcam_mode(wanted(1920, 1080, 15), &modes),
+            Some(mode(1280, 720, 15_000, true))
+        );
+        assert_eq!(negotiate_webcam_mode(wanted(1280, 720, 30), &[]), None);
+    }
+
+    #[test]
+    fn test_negotiation_takes_the_fastest_rate_when_none_is_fast_enough() {
+        let modes = [
+            mode(1080, 1920, 15_000, false),
+            mode(1080, 1920, 24_000, false),
+        ];
+        assert_eq!(
+            negotiate_webcam_mode(wanted(1280, 720, 30), &modes),
+            Some(mode(1080, 1920, 24_000, false))
+        );
+    }
+}
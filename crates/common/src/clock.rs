@@ -6,8 +6,83 @@
 //! - Converting between monotonic and wall-clock time
 //! - Calculating stream drift
 
+use std::sync::OnceLock;
 use std::time::Instant;
 
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// System clock the recording epoch and event timestamps are read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ClockSource {
+    /// `CLOCK_MONOTONIC`: stops while the machine is suspended.
+    #[default]
+    Monotonic,
+    /// `CLOCK_BOOTTIME`: keeps counting through suspend. Linux only; other
+    /// platforms read the monotonic clock instead.
+    Boottime,
+}
+
+impl ClockSource {
+    /// Current reading of this clock in nanoseconds.
+    ///
+    /// The value is only meaningful relative to other readings of the same
+    /// source on the same boot.
+    pub fn now_ns(self) -> u64 {
+        #[cfg(unix)]
+        {
+            #[cfg(target_os = "linux")]
+            let id = match self {
+                ClockSource::Monotonic => libc::CLOCK_MONOTONIC,
+                ClockSource::Boottime => libc::CLOCK_BOOTTIME,
+            };
+            #[cfg(not(target_os = "linux"))]
+            let id = libc::CLOCK_MONOTONIC;
+
+            let mut ts = libc::timespec {
+                tv_sec: 0,
+                tv_nsec: 0,
+            };
+            // SAFETY: `ts` is a valid, writable timespec for the call.
+            if unsafe { libc::clock_gettime(id, &mut ts) } == 0 {
+                return ts.tv_sec as u64 * 1_000_000_000 + ts.tv_nsec as u64;
+            }
+        }
+
+        process_monotonic_ns()
+    }
+
+    /// Short name as used on the command line and in `project.json`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ClockSource::Monotonic => "monotonic",
+            ClockSource::Boottime => "boottime",
+        }
+    }
+}
+
+impl std::str::FromStr for ClockSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "monotonic" => Ok(ClockSource::Monotonic),
+            "boottime" => Ok(ClockSource::Boottime),
+            other => Err(format!(
+                "unknown clock source '{other}' (expected monotonic or boottime)"
+            )),
+        }
+    }
+}
+
+/// Monotonic fallback when the OS clock cannot be read directly: time since
+/// the first call in this process.
+fn process_monotonic_ns() -> u64 {
+    static ANCHOR: OnceLock<Instant> = OnceLock::new();
+    ANCHOR.get_or_init(Instant::now).elapsed().as_nanos() as u64
+}
+
 /// A recording clock that provides monotonic timestamps relative to
 /// a fixed epoch (the moment recording started).
 #[derive(Debug, Clone)]
@@ -17,33 +92,68 @@ pub struct RecordingClock {
 
     /// Wall-clock time at epoch (ISO 8601 string).
     epoch_wall: String,
+
+    /// Clock the epoch was read from.
+    source: ClockSource,
+
+    /// Raw `source` reading at epoch.
+    epoch_monotonic_ns: u64,
 }
 
 impl RecordingClock {
-    /// Create a new recording clock anchored to now.
+    /// Create a new recording clock on the default source, anchored to now.
     pub fn start() -> Self {
+        Self::start_with(ClockSource::default())
+    }
+
+    /// Create a new recording clock on `source`, anchored to now.
+    pub fn start_with(source: ClockSource) -> Self {
         Self {
+            epoch_monotonic_ns: source.now_ns(),
             epoch: Instant::now(),
             epoch_wall: chrono::Utc::now().to_rfc3339(),
+            source,
         }
     }
 
     /// Create a clock from a known epoch (for loading saved projects).
     pub fn from_epoch(epoch: Instant, wall: String) -> Self {
+        let source = ClockSource::default();
+        let epoch_monotonic_ns = source
+            .now_ns()
+            .saturating_sub(epoch.elapsed().as_nanos() as u64);
         Self {
             epoch,
             epoch_wall: wall,
+            source,
+            epoch_monotonic_ns,
         }
     }
 
     /// Get nanoseconds elapsed since recording start.
+    ///
+    /// Boottime clocks include time spent suspended; monotonic clocks do not.
     pub fn elapsed_ns(&self) -> u64 {
-        self.epoch.elapsed().as_nanos() as u64
+        match self.source {
+            ClockSource::Monotonic => self.epoch.elapsed().as_nanos() as u64,
+            ClockSource::Boottime => self.source.now_ns().saturating_sub(self.epoch_monotonic_ns),
+        }
     }
 
     /// Get seconds elapsed since recording start.
     pub fn elapsed_secs(&self) -> f64 {
-        self.epoch.elapsed().as_secs_f64()
+        Self::ns_to_secs(self.elapsed_ns())
+    }
+
+    /// Clock the epoch was read from.
+    pub fn source(&self) -> ClockSource {
+        self.source
+    }
+
+    /// Raw clock reading at recording start, for `events.jsonl` headers and
+    /// correlating with other processes on the same machine.
+    pub fn epoch_monotonic_ns(&self) -> u64 {
+        self.epoch_monotonic_ns
     }
 
     /// Wall-clock time at recording start.
@@ -143,6 +253,19 @@ mod tests {
         assert!(clock.elapsed_ns() < 1_000_000_000); // less than 1 second
     }
 
+    #[test]
+    fn test_clock_epoch_tracks_source() {
+        for source in [ClockSource::Monotonic, ClockSource::Boottime] {
+            let clock = RecordingClock::start_with(source);
+            assert_eq!(clock.source(), source);
+            assert!(clock.epoch_monotonic_ns() > 0);
+            assert!(source.now_ns() >= clock.epoch_monotonic_ns());
+            assert!(clock.elapsed_ns() < 1_000_000_000);
+        }
+        assert_eq!("BootTime".parse(), Ok(ClockSource::Boottime));
+        assert!("realtime".parse::<ClockSource>().is_err());
+    }
+
     #[test]
     fn test_ns_to_secs_conversion() {
         assert!((RecordingClock::ns_to_secs(1_500_000_000) - 1.5).abs() < 1e-9);
@@ -1,12 +1,19 @@
 //! Application configuration.
 
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::memory::MemoryMode;
 
 /// Global application configuration.
+///
+/// Missing fields in `config.json` fall back to defaults, so a file with only
+/// `projects_dir` is enough to override where recordings go.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AppConfig {
-    /// Directory where projects are stored.
+    /// Directory where projects are stored (`~/Videos/GrabMe` by default).
     pub projects_dir: PathBuf,
 
     /// Default recording settings.
@@ -14,6 +21,145 @@ pub struct AppConfig {
 
     /// Logging configuration.
     pub logging: LoggingConfig,
+
+    /// Recording overlay behavior.
+    pub overlay: OverlayConfig,
+
+    /// Memory profile: `auto` (the default) switches to the low-memory
+    /// profile when little memory is available.
+    pub memory: MemoryMode,
+
+    /// Network access.
+    pub network: NetworkConfig,
+
+    /// Update checks and self-update.
+    pub updates: UpdateConfig,
+}
+
+/// Network access.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkConfig {
+    /// Never touch the network, for air-gapped machines. Update checks
+    /// and `grabme self-update` fail instead, and `grabme review` only
+    /// listens on loopback.
+    pub offline: bool,
+}
+
+/// Update checks and self-update.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UpdateConfig {
+    /// Let the overlay look for a newer release when it starts.
+    pub check_on_start: bool,
+}
+
+/// Recording overlay behavior.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OverlayConfig {
+    /// Where the overlay moves while recording. `None` moves it to the first
+    /// monitor that isn't being recorded.
+    pub parking: Option<OverlayParking>,
+}
+
+/// A user-chosen spot for the overlay during recording.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OverlayParking {
+    /// Monitor name as reported by monitor detection (e.g. `HDMI-1`).
+    pub monitor: String,
+    /// Corner of that monitor.
+    pub corner: ScreenCorner,
+}
+
+/// A corner of a monitor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScreenCorner {
+    #[default]
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl ScreenCorner {
+    /// Corner nearest a point given as fractions of the monitor size.
+    pub fn nearest(x_frac: f64, y_frac: f64) -> Self {
+        match (x_frac >= 0.5, y_frac >= 0.5) {
+            (false, false) => ScreenCorner::TopLeft,
+            (true, false) => ScreenCorner::TopRight,
+            (false, true) => ScreenCorner::BottomLeft,
+            (true, true) => ScreenCorner::BottomRight,
+        }
+    }
+
+    /// Whether the corner is on the right edge.
+    pub fn is_right(self) -> bool {
+        matches!(self, ScreenCorner::TopRight | ScreenCorner::BottomRight)
+    }
+
+    /// Whether the corner is on the bottom edge.
+    pub fn is_bottom(self) -> bool {
+        matches!(self, ScreenCorner::BottomLeft | ScreenCorner::BottomRight)
+    }
+}
+
+/// What a running recording does when the screen locks or the system
+/// suspends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LockAction {
+    /// Stop and save the recording.
+    #[default]
+    Stop,
+    /// Pause until the screen is unlocked again.
+    Pause,
+    /// Keep recording.
+    Ignore,
+}
+
+impl std::str::FromStr for LockAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "stop" => Ok(LockAction::Stop),
+            "pause" => Ok(LockAction::Pause),
+            "ignore" | "none" => Ok(LockAction::Ignore),
+            other => Err(format!(
+                "Unknown lock action: {other}. Use: stop, pause, ignore"
+            )),
+        }
+    }
+}
+
+/// What to do once the system wakes from a suspend that ended a recording.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResumeAction {
+    /// Ask whether to record a new segment.
+    #[default]
+    Prompt,
+    /// Record a new segment right away.
+    Auto,
+    /// Leave it stopped.
+    Off,
+}
+
+impl std::str::FromStr for ResumeAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "prompt" | "ask" => Ok(ResumeAction::Prompt),
+            "auto" => Ok(ResumeAction::Auto),
+            "off" | "none" => Ok(ResumeAction::Off),
+            other => Err(format!(
+                "Unknown resume action: {other}. Use: prompt, auto, off"
+            )),
+        }
+    }
 }
 
 /// Default recording parameters.
@@ -33,27 +179,125 @@ pub struct RecordingDefaults {
 
     /// Whether to hide cursor during capture by default.
     pub hide_cursor: bool,
+
+    /// Capture backend (`x11` or `wayland`) to use instead of detecting
+    /// it. `grabme doctor` sets this when only one backend works.
+    #[serde(default)]
+    pub display_server: Option<String>,
+
+    /// Start exports after the black frames that open most recordings
+    /// while the capture is negotiated. The editor can override the start
+    /// per project.
+    #[serde(default = "default_trim_preroll")]
+    pub trim_preroll: bool,
+
+    /// End exports before the idle tail that closes most recordings while
+    /// the stop button is reached for. The editor can override the end per
+    /// project.
+    #[serde(default)]
+    pub trim_tail: bool,
+
+    /// Reactions dropped at the pointer while recording, bound in order to
+    /// the `1`-`9` hotkeys.
+    #[serde(default = "default_reactions")]
+    pub reactions: Vec<String>,
+
+    /// Monitors to split into side-by-side regions that are listed and
+    /// recorded like separate monitors, e.g. `{"DP-1": 2}` for the two
+    /// halves of an ultrawide.
+    #[serde(default)]
+    pub monitor_splits: BTreeMap<String, u32>,
+
+    /// What to do when the screen locks or the system suspends while
+    /// recording.
+    #[serde(default)]
+    pub on_lock: LockAction,
+
+    /// What to do after waking from a suspend that stopped a recording.
+    #[serde(default)]
+    pub on_resume: ResumeAction,
+
+    /// Webcam frame size to ask the camera for; both must be set. The
+    /// camera's current size is kept otherwise.
+    #[serde(default)]
+    pub webcam_width: Option<u32>,
+    #[serde(default)]
+    pub webcam_height: Option<u32>,
+
+    /// Webcam frame rate to ask the camera for [default: the recording
+    /// frame rate, at most 30].
+    #[serde(default)]
+    pub webcam_fps: Option<u32>,
+
+    /// Local directory to record into before moving the project to its
+    /// destination when recording stops. Useful when projects live on a
+    /// network share or removable drive.
+    #[serde(default)]
+    pub spool_dir: Option<PathBuf>,
+}
+
+impl RecordingDefaults {
+    /// Reaction bound to hotkey `n` (`1`-`9`).
+    pub fn reaction_for_hotkey(&self, n: usize) -> Option<&str> {
+        if !(1..=9).contains(&n) {
+            return None;
+        }
+        self.reactions.get(n - 1).map(String::as_str)
+    }
+}
+
+fn default_trim_preroll() -> bool {
+    true
+}
+
+fn default_reactions() -> Vec<String> {
+    ["🎉", "👉", "✅", "🔥"].map(String::from).to_vec()
 }
 
 /// Logging configuration.
+///
+/// `RUST_LOG`, when set, replaces `level` and `filters` entirely.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct LoggingConfig {
     /// Log level filter (e.g., "info", "debug", "grabme=debug,warn").
     pub level: String,
 
-    /// Whether to output structured JSON logs.
+    /// Per-crate levels layered over `level`, e.g.
+    /// `{"grabme_capture_engine": "debug"}`.
+    pub filters: BTreeMap<String, String>,
+
+    /// Whether to output structured JSON logs on the console.
     pub json: bool,
 
-    /// Optional log file path.
+    /// Log file path; defaults to [`log_file_path`]. The file is always
+    /// JSON lines so `grabme logs` can filter it.
     pub file: Option<PathBuf>,
 }
 
+impl LoggingConfig {
+    /// `EnvFilter` directives: the base level followed by per-crate levels.
+    pub fn filter_directives(&self) -> String {
+        let mut directives = vec![self.level.clone()];
+        directives.extend(
+            self.filters
+                .iter()
+                .map(|(target, level)| format!("{target}={level}")),
+        );
+        directives.join(",")
+    }
+}
+
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
             projects_dir: dirs_default_projects(),
             recording: RecordingDefaults::default(),
             logging: LoggingConfig::default(),
+            overlay: OverlayConfig::default(),
+            memory: MemoryMode::default(),
+            network: NetworkConfig::default(),
+            updates: UpdateConfig::default(),
         }
     }
 }
@@ -66,6 +310,17 @@ impl Default for RecordingDefaults {
             video_codec: "h264".to_string(),
             audio_sample_rate: 48000,
             hide_cursor: true,
+            display_server: None,
+            trim_preroll: true,
+            trim_tail: false,
+            reactions: default_reactions(),
+            monitor_splits: BTreeMap::new(),
+            on_lock: LockAction::Stop,
+            on_resume: ResumeAction::Prompt,
+            webcam_width: None,
+            webcam_height: None,
+            webcam_fps: None,
+            spool_dir: None,
         }
     }
 }
@@ -74,6 +329,7 @@ impl Default for LoggingConfig {
     fn default() -> Self {
         Self {
             level: "info".to_string(),
+            filters: BTreeMap::new(),
             json: false,
             file: None,
         }
@@ -100,6 +356,12 @@ impl AppConfig {
         Self::default()
     }
 
+    /// Return the projects directory, creating it on first use.
+    pub fn ensure_projects_dir(&self) -> Result<PathBuf, std::io::Error> {
+        std::fs::create_dir_all(&self.projects_dir)?;
+        Ok(self.projects_dir.clone())
+    }
+
     /// Save config to the standard location.
     pub fn save(&self) -> Result<(), std::io::Error> {
         let config_path = config_file_path();
@@ -122,13 +384,125 @@ fn config_file_path() -> PathBuf {
     base.join("grabme").join("config.json")
 }
 
-/// Default projects directory.
+/// Standard location of the recent-project library index.
+pub fn library_index_path() -> PathBuf {
+    data_dir().join("library.json")
+}
+
+/// Standard location of the application log.
+pub fn log_file_path() -> PathBuf {
+    data_dir().join("logs").join("grabme.log")
+}
+
+/// Default projects directory inside the user's videos folder.
 fn dirs_default_projects() -> PathBuf {
+    videos_dir().join("GrabMe")
+}
+
+/// Platform videos folder: `%USERPROFILE%\Videos` on Windows, `~/Movies` on
+/// macOS, and `XDG_VIDEOS_DIR` (or `~/Videos`) elsewhere.
+fn videos_dir() -> PathBuf {
+    if cfg!(windows) {
+        return std::env::var("USERPROFILE")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| std::env::temp_dir())
+            .join("Videos");
+    }
+
+    let home = PathBuf::from(std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string()));
+    if cfg!(target_os = "macos") {
+        return home.join("Movies");
+    }
+
+    if let Ok(dir) = std::env::var("XDG_VIDEOS_DIR") {
+        return PathBuf::from(dir);
+    }
+    let user_dirs = config_file_path()
+        .parent()
+        .and_then(|grabme| grabme.parent())
+        .map(|config| config.join("user-dirs.dirs"));
+    user_dirs
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|content| parse_user_dirs_videos(&content, &home))
+        .unwrap_or_else(|| home.join("Videos"))
+}
+
+/// Extract `XDG_VIDEOS_DIR` from an xdg-user-dirs `user-dirs.dirs` file.
+fn parse_user_dirs_videos(content: &str, home: &Path) -> Option<PathBuf> {
+    let value = content
+        .lines()
+        .map(str::trim)
+        .find_map(|line| line.strip_prefix("XDG_VIDEOS_DIR="))?
+        .trim_matches('"');
+
+    let path = match value.strip_prefix("$HOME") {
+        Some(rest) => home.join(rest.trim_start_matches('/')),
+        None => PathBuf::from(value),
+    };
+    // xdg-user-dirs sets a folder to $HOME itself to mean "disabled".
+    (path.is_absolute() && path != home).then_some(path)
+}
+
+/// Per-user GrabMe data directory.
+fn data_dir() -> PathBuf {
     let base = std::env::var("XDG_DATA_HOME")
         .map(PathBuf::from)
         .unwrap_or_else(|_| {
             let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
             PathBuf::from(home).join(".local").join("share")
         });
-    base.join("grabme").join("projects")
+    base.join("grabme")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_user_dirs_videos_expands_home() {
+        let content =
+            "# comment\nXDG_DESKTOP_DIR=\"$HOME/Desktop\"\nXDG_VIDEOS_DIR=\"$HOME/Filme\"\n";
+        let parsed = parse_user_dirs_videos(content, Path::new("/home/me"));
+        assert_eq!(parsed, Some(PathBuf::from("/home/me/Filme")));
+    }
+
+    #[test]
+    fn test_parse_user_dirs_videos_ignores_disabled_entry() {
+        let content = "XDG_VIDEOS_DIR=\"$HOME/\"\n";
+        assert_eq!(parse_user_dirs_videos(content, Path::new("/home/me")), None);
+    }
+
+    #[test]
+    fn test_partial_config_uses_defaults() {
+        let config: AppConfig = serde_json::from_str(r#"{"projects_dir":"/data/rec"}"#).unwrap();
+        assert_eq!(config.projects_dir, PathBuf::from("/data/rec"));
+        assert_eq!(config.recording.fps, 60);
+        assert!(config.overlay.parking.is_none());
+    }
+
+    #[test]
+    fn test_logging_filters_become_directives() {
+        let config: AppConfig = serde_json::from_str(
+            r#"{"logging":{"level":"warn","filters":{"grabme_capture_engine":"debug"}}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.logging.filter_directives(),
+            "warn,grabme_capture_engine=debug"
+        );
+        assert!(!config.logging.json);
+    }
+
+    #[test]
+    fn test_overlay_parking_round_trips() {
+        let config: AppConfig = serde_json::from_str(
+            r#"{"overlay":{"parking":{"monitor":"HDMI-1","corner":"bottom_right"}}}"#,
+        )
+        .unwrap();
+        let parking = config.overlay.parking.unwrap();
+        assert_eq!(parking.monitor, "HDMI-1");
+        assert_eq!(parking.corner, ScreenCorner::BottomRight);
+        assert_eq!(ScreenCorner::nearest(0.9, 0.1), ScreenCorner::TopRight);
+        assert_eq!(ScreenCorner::nearest(0.2, 0.8), ScreenCorner::BottomLeft);
+    }
 }
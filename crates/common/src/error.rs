@@ -87,4 +87,71 @@ impl GrabmeError {
             message: msg.into(),
         }
     }
+
+    /// What the user can try next, for errors where there is a likely fix.
+    ///
+    /// Shown next to the message in the overlay's error panel.
+    pub fn hint(&self) -> Option<&'static str> {
+        match self {
+            Self::Capture { .. } => Some(
+                "Run `grabme check` to confirm screen capture works, or `grabme doctor` \
+                 for a test recording.",
+            ),
+            Self::InputTracking { .. } => Some(
+                "Pointer tracking needs read access to /dev/input; add your user to the \
+                 `input` group and log in again.",
+            ),
+            Self::Audio { .. } => Some(
+                "Check that PipeWire or PulseAudio is running and no other app holds the \
+                 microphone, or record without audio.",
+            ),
+            Self::Render { .. } => Some(
+                "Check that ffmpeg is installed and the disk has free space; the session log \
+                 has the full ffmpeg output.",
+            ),
+            Self::Project { .. } | Self::FileNotFound { .. } => Some(
+                "The project may have been moved or deleted. `grabme validate` lists what is \
+                 missing.",
+            ),
+            Self::Json(_) => {
+                Some("A project file is damaged. `grabme validate` reports which one.")
+            }
+            Self::Config { .. } => {
+                Some("Fix the setting in the GrabMe config file, or remove it to use the default.")
+            }
+            Self::PermissionDenied { .. } => {
+                Some("Grant the missing permission, then run `grabme check`.")
+            }
+            Self::Platform { .. } | Self::Unsupported { .. } => {
+                Some("Run `grabme check` to see what this desktop session supports.")
+            }
+            Self::Io(err) => match err.kind() {
+                std::io::ErrorKind::PermissionDenied => {
+                    Some("Choose an output folder you can write to.")
+                }
+                std::io::ErrorKind::NotFound => Some("A file or folder GrabMe needs is missing."),
+                _ => Some("Check that the output folder exists and the disk has free space."),
+            },
+            Self::Processing { .. } | Self::Other(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hint_points_at_a_fix() {
+        assert!(GrabmeError::capture("no portal")
+            .hint()
+            .unwrap()
+            .contains("grabme check"));
+        let denied = std::io::Error::from(std::io::ErrorKind::PermissionDenied);
+        assert_eq!(
+            GrabmeError::from(denied).hint(),
+            Some("Choose an output folder you can write to.")
+        );
+        assert_eq!(GrabmeError::processing("bad events").hint(), None);
+    }
 }
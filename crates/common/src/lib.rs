@@ -5,11 +5,19 @@
 //! - Clock and timing utilities for stream synchronization
 //! - Tracing/logging initialization
 //! - Configuration loading
+//! - Media probing with ffprobe
+//! - Memory profiles for constrained machines
+//! - Per-session temporary directories
+//! - Release checks and self-update
 
 pub mod clock;
 pub mod config;
 pub mod error;
 pub mod logging;
+pub mod media;
+pub mod memory;
+pub mod temp;
+pub mod update;
 
 pub use clock::*;
 pub use config::*;
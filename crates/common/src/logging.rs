@@ -1,33 +1,230 @@
 //! Logging and tracing initialization.
+//!
+//! Logs go to stderr and, as JSON lines, to the application log file. While
+//! a recording runs they are also copied to the project's session log so a
+//! bug report can carry exactly the lines for that recording.
 
-use crate::config::LoggingConfig;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use tracing::Level;
+use tracing_subscriber::fmt::MakeWriter;
+
+use crate::config::{log_file_path, LoggingConfig};
+
+/// The application log is rotated to `<name>.1` when it grows past this.
+const MAX_LOG_FILE_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Session log file, when a recording is running.
+static SESSION_LOG: OnceLock<Mutex<Option<File>>> = OnceLock::new();
+
+fn session_log() -> &'static Mutex<Option<File>> {
+    SESSION_LOG.get_or_init(|| Mutex::new(None))
+}
 
 /// Initialize the tracing subscriber with the given configuration.
 pub fn init_logging(config: &LoggingConfig) {
-    use tracing_subscriber::{fmt, EnvFilter};
+    use tracing_subscriber::layer::SubscriberExt;
+    use tracing_subscriber::{fmt, EnvFilter, Layer};
 
-    let env_filter =
-        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(&config.level));
+    let env_filter = EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| EnvFilter::new(config.filter_directives()));
 
-    if config.json {
-        let subscriber = fmt::Subscriber::builder()
-            .with_env_filter(env_filter)
-            .json()
-            .finish();
-        tracing::subscriber::set_global_default(subscriber).ok();
+    let console = if config.json {
+        fmt::layer().json().with_writer(io::stderr).boxed()
     } else {
-        let subscriber = fmt::Subscriber::builder()
-            .with_env_filter(env_filter)
+        fmt::layer()
             .with_target(true)
             .with_thread_ids(false)
             .with_file(false)
             .with_line_number(false)
-            .finish();
-        tracing::subscriber::set_global_default(subscriber).ok();
-    }
+            .with_writer(io::stderr)
+            .boxed()
+    };
+
+    let path = config.file.clone().unwrap_or_else(log_file_path);
+    let file = open_log_file(&path)
+        .map_err(|e| eprintln!("Failed to open log file {}: {e}", path.display()))
+        .ok();
+    let files = fmt::layer().json().with_ansi(false).with_writer(LogFiles {
+        main: file.map(|file| Arc::new(Mutex::new(file))),
+    });
+
+    let subscriber = tracing_subscriber::registry()
+        .with(env_filter)
+        .with(console)
+        .with(files);
+    tracing::subscriber::set_global_default(subscriber).ok();
 }
 
 /// Initialize logging with defaults (useful for tests and quick scripts).
 pub fn init_default_logging() {
     init_logging(&LoggingConfig::default());
 }
+
+/// Copy log lines to `path` (usually `<project>/meta/session.log`) until
+/// [`detach_session_log`] is called.
+pub fn attach_session_log(path: &Path) -> io::Result<()> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    *session_log().lock().unwrap_or_else(|e| e.into_inner()) = Some(file);
+    Ok(())
+}
+
+/// Stop copying log lines to the session log.
+pub fn detach_session_log() {
+    *session_log().lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Session log path inside a project directory.
+pub fn session_log_path(project_root: &Path) -> PathBuf {
+    project_root.join("meta").join("session.log")
+}
+
+fn open_log_file(path: &Path) -> io::Result<File> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    if std::fs::metadata(path).is_ok_and(|meta| meta.len() > MAX_LOG_FILE_BYTES) {
+        let mut rotated = path.as_os_str().to_owned();
+        rotated.push(".1");
+        std::fs::rename(path, rotated)?;
+    }
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+/// Writes each formatted event to the application log and the session log.
+struct LogFiles {
+    main: Option<Arc<Mutex<File>>>,
+}
+
+impl<'a> MakeWriter<'a> for LogFiles {
+    type Writer = LogFilesWriter;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        LogFilesWriter {
+            main: self.main.clone(),
+        }
+    }
+}
+
+struct LogFilesWriter {
+    main: Option<Arc<Mutex<File>>>,
+}
+
+impl Write for LogFilesWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if let Some(main) = &self.main {
+            let _ = main
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .write_all(buf);
+        }
+        if let Some(session) = session_log()
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_mut()
+        {
+            let _ = session.write_all(buf);
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// One line of a JSON log file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogRecord {
+    pub timestamp: String,
+    pub level: Level,
+    pub target: String,
+    pub message: String,
+    /// Remaining event fields, sorted by name.
+    pub fields: Vec<(String, String)>,
+}
+
+impl LogRecord {
+    /// Parse a line written by the JSON file layer. Returns `None` for lines
+    /// in any other format.
+    pub fn parse(line: &str) -> Option<Self> {
+        let value: serde_json::Value = serde_json::from_str(line).ok()?;
+        let level = Level::from_str(value.get("level")?.as_str()?).ok()?;
+        let mut message = String::new();
+        let mut fields = Vec::new();
+        if let Some(map) = value.get("fields").and_then(|f| f.as_object()) {
+            for (key, field) in map {
+                let text = match field {
+                    serde_json::Value::String(text) => text.clone(),
+                    other => other.to_string(),
+                };
+                if key == "message" {
+                    message = text;
+                } else {
+                    fields.push((key.clone(), text));
+                }
+            }
+        }
+        Some(Self {
+            timestamp: value
+                .get("timestamp")
+                .and_then(|t| t.as_str())
+                .unwrap_or_default()
+                .to_string(),
+            level,
+            target: value
+                .get("target")
+                .and_then(|t| t.as_str())
+                .unwrap_or_default()
+                .to_string(),
+            message,
+            fields,
+        })
+    }
+
+    /// Whether the record is at least as severe as `min`.
+    pub fn at_least(&self, min: Level) -> bool {
+        // `Level` orders more verbose levels as greater.
+        self.level <= min
+    }
+
+    /// Single-line human-readable form.
+    pub fn pretty(&self) -> String {
+        let mut line = format!(
+            "{} {:>5} {}: {}",
+            self.timestamp, self.level, self.target, self.message
+        );
+        for (key, value) in &self.fields {
+            line.push_str(&format!(" {key}={value}"));
+        }
+        line
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_record_parses_json_layer_output() {
+        let line = r#"{"timestamp":"2026-01-02T03:04:05.000006Z","level":"WARN","fields":{"message":"Clock drift exceeds 100ms","stream":"mic","drift_ms":120.5},"target":"grabme_capture_engine::session"}"#;
+        let record = LogRecord::parse(line).unwrap();
+        assert_eq!(record.level, Level::WARN);
+        assert_eq!(record.message, "Clock drift exceeds 100ms");
+        assert!(record.at_least(Level::INFO));
+        assert!(!record.at_least(Level::ERROR));
+        assert_eq!(
+            record.pretty(),
+            "2026-01-02T03:04:05.000006Z  WARN grabme_capture_engine::session: Clock drift exceeds 100ms drift_ms=120.5 stream=mic"
+        );
+    }
+
+    #[test]
+    fn test_log_record_rejects_plain_text() {
+        assert!(LogRecord::parse("2026-01-02 INFO something happened").is_none());
+    }
+}
@@ -0,0 +1,195 @@
+//! ffprobe-based media inspection.
+//!
+//! One ffprobe call and parser shared by capture (correcting track offsets
+//! on stop) and render (durations, frame sizes, stream checks).
+
+use std::path::Path;
+use std::process::Command;
+
+use serde::Deserialize;
+
+use crate::error::{GrabmeError, GrabmeResult};
+
+/// Probed properties of a media file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MediaInfo {
+    /// Container duration in seconds.
+    pub duration_secs: Option<f64>,
+    /// Overall bitrate in kbps.
+    pub bit_rate_kbps: Option<u32>,
+    /// First video stream, if any.
+    pub video: Option<VideoStreamInfo>,
+    /// First audio stream, if any.
+    pub audio: Option<AudioStreamInfo>,
+}
+
+/// Probed video stream properties.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VideoStreamInfo {
+    pub codec: String,
+    pub width: u32,
+    pub height: u32,
+    /// Average frame rate, when ffprobe reports one.
+    pub fps: Option<f64>,
+}
+
+/// Probed audio stream properties.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioStreamInfo {
+    pub codec: String,
+    pub channels: u32,
+    pub sample_rate: u32,
+}
+
+impl MediaInfo {
+    /// Width and height of the first video stream, when both are known.
+    pub fn video_size(&self) -> Option<(u32, u32)> {
+        self.video
+            .as_ref()
+            .map(|video| (video.width, video.height))
+            .filter(|&(width, height)| width > 0 && height > 0)
+    }
+}
+
+/// Probe `path` with ffprobe.
+pub fn probe_media(path: &Path) -> GrabmeResult<MediaInfo> {
+    let output = Command::new("ffprobe")
+        .args([
+            "-v",
+            "error",
+            "-print_format",
+            "json",
+            "-show_format",
+            "-show_streams",
+        ])
+        .arg(path)
+        .output()
+        .map_err(|e| GrabmeError::render(format!("Failed to launch ffprobe: {e}")))?;
+
+    if !output.status.success() {
+        return Err(GrabmeError::render(format!(
+            "ffprobe failed on {}: {}",
+            path.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    parse_ffprobe_json(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[derive(Debug, Deserialize)]
+struct FfprobeOutput {
+    #[serde(default)]
+    streams: Vec<FfprobeStream>,
+    #[serde(default)]
+    format: Option<FfprobeFormat>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FfprobeStream {
+    #[serde(default)]
+    codec_type: String,
+    #[serde(default)]
+    codec_name: String,
+    width: Option<u32>,
+    height: Option<u32>,
+    avg_frame_rate: Option<String>,
+    channels: Option<u32>,
+    sample_rate: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FfprobeFormat {
+    duration: Option<String>,
+    bit_rate: Option<String>,
+}
+
+/// Parse `ffprobe -print_format json -show_format -show_streams` output.
+pub fn parse_ffprobe_json(json: &str) -> GrabmeResult<MediaInfo> {
+    let parsed: FfprobeOutput = serde_json::from_str(json)
+        .map_err(|e| GrabmeError::render(format!("Unreadable ffprobe output: {e}")))?;
+
+    let video = parsed
+        .streams
+        .iter()
+        .find(|s| s.codec_type == "video")
+        .map(|s| VideoStreamInfo {
+            codec: s.codec_name.clone(),
+            width: s.width.unwrap_or(0),
+            height: s.height.unwrap_or(0),
+            fps: s.avg_frame_rate.as_deref().and_then(parse_frame_rate),
+        });
+    let audio = parsed
+        .streams
+        .iter()
+        .find(|s| s.codec_type == "audio")
+        .map(|s| AudioStreamInfo {
+            codec: s.codec_name.clone(),
+            channels: s.channels.unwrap_or(0),
+            sample_rate: s
+                .sample_rate
+                .as_deref()
+                .and_then(|rate| rate.parse().ok())
+                .unwrap_or(0),
+        });
+    let format = parsed.format.as_ref();
+
+    Ok(MediaInfo {
+        duration_secs: format
+            .and_then(|f| f.duration.as_deref())
+            .and_then(|d| d.parse::<f64>().ok())
+            .filter(|d| d.is_finite() && *d > 0.0),
+        bit_rate_kbps: format
+            .and_then(|f| f.bit_rate.as_deref())
+            .and_then(|b| b.parse::<u64>().ok())
+            .map(|bps| (bps / 1000) as u32),
+        video,
+        audio,
+    })
+}
+
+/// Parse an ffprobe rational like `60/1`; `0/0` means unknown.
+fn parse_frame_rate(raw: &str) -> Option<f64> {
+    let (num, den) = raw.split_once('/')?;
+    let num: f64 = num.parse().ok()?;
+    let den: f64 = den.parse().ok()?;
+    (den > 0.0 && num > 0.0).then(|| num / den)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCREEN_JSON: &str = r#"{
+        "streams": [
+            {"codec_type": "video", "codec_name": "h264", "width": 2560, "height": 1440, "avg_frame_rate": "30/1"},
+            {"codec_type": "audio", "codec_name": "aac", "channels": 2, "sample_rate": "48000"}
+        ],
+        "format": {"duration": "12.500000", "bit_rate": "8123456"}
+    }"#;
+
+    #[test]
+    fn test_parse_ffprobe_json_reads_streams_and_format() {
+        let info = parse_ffprobe_json(SCREEN_JSON).unwrap();
+        assert_eq!(info.duration_secs, Some(12.5));
+        assert_eq!(info.bit_rate_kbps, Some(8123));
+        let video = info.video.unwrap();
+        assert_eq!((video.width, video.height), (2560, 1440));
+        assert_eq!(video.fps, Some(30.0));
+        let audio = info.audio.unwrap();
+        assert_eq!((audio.channels, audio.sample_rate), (2, 48000));
+    }
+
+    #[test]
+    fn test_video_size_needs_both_dimensions() {
+        let info = parse_ffprobe_json(
+            r#"{"streams":[{"codec_type":"video","codec_name":"h264","width":1280}]}"#,
+        )
+        .unwrap();
+        assert_eq!(info.video_size(), None);
+        assert_eq!(
+            parse_ffprobe_json(SCREEN_JSON).unwrap().video_size(),
+            Some((2560, 1440))
+        );
+    }
+}
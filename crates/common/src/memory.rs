@@ -0,0 +1,139 @@
+//! Low-memory profile for constrained machines.
+//!
+//! Capture, input tracking and live previews together can run a 4 GB
+//! laptop out of memory. The low-memory profile shrinks the capture and
+//! event queues, turns live previews off, thins pointer samples for
+//! analysis, and reads `events.jsonl` a line at a time instead of loading
+//! it whole. Config `memory` picks `normal`, `low`, or `auto`, which turns
+//! the profile on when little memory is available.
+
+use serde::{Deserialize, Serialize};
+
+/// Available memory, in MiB, below which `auto` picks the low-memory profile.
+pub const LOW_MEMORY_AVAILABLE_MIB: u64 = 2048;
+
+/// Which memory profile to use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MemoryMode {
+    /// Low-memory when less than [`LOW_MEMORY_AVAILABLE_MIB`] is available.
+    #[default]
+    Auto,
+    /// Full buffers and previews.
+    Normal,
+    /// Always the low-memory profile.
+    Low,
+}
+
+impl std::str::FromStr for MemoryMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "auto" => Ok(MemoryMode::Auto),
+            "normal" => Ok(MemoryMode::Normal),
+            "low" => Ok(MemoryMode::Low),
+            other => Err(format!(
+                "Unknown memory mode: {other}. Use: auto, normal, low"
+            )),
+        }
+    }
+}
+
+impl MemoryMode {
+    /// The profile this mode stands for on this machine right now.
+    pub fn profile(self) -> MemoryProfile {
+        match self {
+            MemoryMode::Normal => MemoryProfile::NORMAL,
+            MemoryMode::Low => MemoryProfile::LOW,
+            MemoryMode::Auto => match available_memory_mib() {
+                Some(mib) if mib < LOW_MEMORY_AVAILABLE_MIB => {
+                    tracing::info!(
+                        available_mib = mib,
+                        "Little memory available; using the low-memory profile"
+                    );
+                    MemoryProfile::LOW
+                }
+                _ => MemoryProfile::NORMAL,
+            },
+        }
+    }
+}
+
+/// Buffer sizes and features that trade memory for smoothness.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MemoryProfile {
+    /// Frames the screen source may queue ahead of the encoder.
+    pub capture_queue_buffers: u32,
+    /// Input events the event writer may fall behind by.
+    pub event_queue_capacity: usize,
+    /// Whether live previews (the overlay's webcam preview and the virtual
+    /// camera) may run.
+    pub live_previews: bool,
+    /// Pointer samples per second kept for analysis; `None` keeps all.
+    pub analysis_pointer_hz: Option<f64>,
+    /// Read `events.jsonl` a line at a time instead of loading it whole.
+    pub stream_events: bool,
+}
+
+impl MemoryProfile {
+    pub const NORMAL: Self = Self {
+        capture_queue_buffers: 200,
+        event_queue_capacity: 4096,
+        live_previews: true,
+        analysis_pointer_hz: None,
+        stream_events: false,
+    };
+
+    pub const LOW: Self = Self {
+        capture_queue_buffers: 24,
+        event_queue_capacity: 512,
+        live_previews: false,
+        analysis_pointer_hz: Some(20.0),
+        stream_events: true,
+    };
+
+    /// Whether this is the low-memory profile.
+    pub fn is_low(&self) -> bool {
+        *self == Self::LOW
+    }
+}
+
+impl Default for MemoryProfile {
+    fn default() -> Self {
+        Self::NORMAL
+    }
+}
+
+/// Memory available for new work, in MiB, from `/proc/meminfo`. `None` on
+/// other platforms.
+pub fn available_memory_mib() -> Option<u64> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    parse_mem_available_mib(&meminfo)
+}
+
+fn parse_mem_available_mib(meminfo: &str) -> Option<u64> {
+    let line = meminfo
+        .lines()
+        .find_map(|line| line.strip_prefix("MemAvailable:"))?;
+    let kib: u64 = line.trim().trim_end_matches("kB").trim().parse().ok()?;
+    Some(kib / 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_mode_profiles_and_meminfo() {
+        assert_eq!(MemoryMode::Low.profile(), MemoryProfile::LOW);
+        assert_eq!(MemoryMode::Normal.profile(), MemoryProfile::NORMAL);
+        assert!(MemoryProfile::LOW.is_low());
+        assert_eq!("LOW".parse::<MemoryMode>(), Ok(MemoryMode::Low));
+        assert!("tiny".parse::<MemoryMode>().is_err());
+
+        let meminfo = "MemTotal:        3884512 kB\nMemFree:          201344 kB\nMemAvailable:    1536000 kB\n";
+        assert_eq!(parse_mem_available_mib(meminfo), Some(1500));
+        assert_eq!(parse_mem_available_mib("MemTotal: 1 kB\n"), None);
+    }
+}
@@ -0,0 +1,258 @@
+//! Per-session temporary directories.
+//!
+//! Scratch files (cursor icons, clipboard exports, doctor test recordings)
+//! go in a directory owned by the running process instead of fixed names in
+//! the global temp dir, so two GrabMe processes never overwrite each other's
+//! files. Directories are named `grabme-session-<pid>-<n>` and removed when
+//! dropped; directories left behind by a crashed process are removed by
+//! [`cleanup_orphaned_temp_dirs`] on the next startup.
+//!
+//! Self-updates stage their download next to the executable instead, so the
+//! final rename stays on one volume. Those directories are named
+//! `grabme-update-staging-<pid>-<n>` and are cleaned up at startup too.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use crate::error::{GrabmeError, GrabmeResult};
+
+/// Prefix of every session temp directory name.
+const DIR_PREFIX: &str = "grabme-session-";
+
+/// Prefix of self-update staging directories in the install directory.
+const UPDATE_STAGING_PREFIX: &str = "grabme-update-staging-";
+
+/// Where process liveness can't be checked, orphans are recognized by age.
+#[cfg_attr(unix, allow(dead_code))]
+const ORPHAN_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+static NEXT_DIR_INDEX: AtomicU32 = AtomicU32::new(0);
+
+/// The process-wide directory behind [`session_temp_dir`].
+static SESSION_DIR: Mutex<Option<SessionTempDir>> = Mutex::new(None);
+
+/// A temporary directory removed, with its contents, on drop.
+#[derive(Debug)]
+pub struct SessionTempDir {
+    path: PathBuf,
+}
+
+impl SessionTempDir {
+    /// Create a new directory under the system temp dir.
+    pub fn new() -> GrabmeResult<Self> {
+        Self::new_in(&std::env::temp_dir())
+    }
+
+    /// Create a new directory under `root`.
+    pub fn new_in(root: &Path) -> GrabmeResult<Self> {
+        Self::with_prefix_in(root, DIR_PREFIX)
+    }
+
+    /// Create a directory to stage a self-update in under `root`, the
+    /// directory of the executable being replaced.
+    pub fn update_staging_in(root: &Path) -> GrabmeResult<Self> {
+        Self::with_prefix_in(root, UPDATE_STAGING_PREFIX)
+    }
+
+    fn with_prefix_in(root: &Path, prefix: &str) -> GrabmeResult<Self> {
+        let index = NEXT_DIR_INDEX.fetch_add(1, Ordering::Relaxed);
+        let path = root.join(format!("{prefix}{}-{index}", std::process::id()));
+        // A leftover from an earlier process with the same pid.
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).map_err(|e| {
+            GrabmeError::platform(format!(
+                "Failed to create temp directory {}: {e}",
+                path.display()
+            ))
+        })?;
+        Ok(Self { path })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Path of `name` inside the directory.
+    pub fn join(&self, name: impl AsRef<Path>) -> PathBuf {
+        self.path.join(name)
+    }
+}
+
+impl Drop for SessionTempDir {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_dir_all(&self.path) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                tracing::debug!(path = %self.path.display(), error = %e, "Failed to remove temp directory");
+            }
+        }
+    }
+}
+
+/// The process-wide temp directory, created on first use. Files here live
+/// until the [`SessionTempGuard`] from [`init_session_temp`] is dropped.
+pub fn session_temp_dir() -> GrabmeResult<PathBuf> {
+    let mut dir = SESSION_DIR.lock().unwrap_or_else(|e| e.into_inner());
+    if dir.is_none() {
+        *dir = Some(SessionTempDir::new()?);
+    }
+    Ok(dir.as_ref().map(|d| d.path.clone()).unwrap_or_default())
+}
+
+/// Removes the process-wide temp directory when dropped.
+#[must_use = "the session temp directory is removed when the guard is dropped"]
+#[derive(Debug)]
+pub struct SessionTempGuard(());
+
+impl Drop for SessionTempGuard {
+    fn drop(&mut self) {
+        let dir = SESSION_DIR.lock().unwrap_or_else(|e| e.into_inner()).take();
+        drop(dir);
+    }
+}
+
+/// Clean up after crashed sessions and return a guard that removes this
+/// session's temp directory on exit. Call once at the top of `main`.
+pub fn init_session_temp() -> SessionTempGuard {
+    let mut removed = cleanup_orphaned_temp_dirs();
+    if let Some(install_dir) = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf))
+    {
+        removed += cleanup_orphaned_dirs_in(&install_dir, UPDATE_STAGING_PREFIX);
+    }
+    if removed > 0 {
+        tracing::info!(removed, "Removed temp directories left by earlier sessions");
+    }
+    SessionTempGuard(())
+}
+
+/// Remove session temp directories whose process is gone. Returns how many
+/// were removed.
+pub fn cleanup_orphaned_temp_dirs() -> usize {
+    cleanup_orphaned_dirs_in(&std::env::temp_dir(), DIR_PREFIX)
+}
+
+/// Remove directories in `root` named `<prefix><pid>-<n>` whose process is
+/// gone.
+fn cleanup_orphaned_dirs_in(root: &Path, prefix: &str) -> usize {
+    let Ok(entries) = std::fs::read_dir(root) else {
+        return 0;
+    };
+    let own_pid = std::process::id();
+
+    let mut removed = 0;
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let Some(pid) = name.to_str().and_then(|name| owner_pid(name, prefix)) else {
+            continue;
+        };
+        if pid == own_pid || !entry.file_type().is_ok_and(|t| t.is_dir()) {
+            continue;
+        }
+        let modified = entry.metadata().and_then(|m| m.modified()).ok();
+        if is_orphaned(pid, modified) && std::fs::remove_dir_all(entry.path()).is_ok() {
+            removed += 1;
+        }
+    }
+    removed
+}
+
+/// Pid encoded in a directory name made with `prefix`.
+fn owner_pid(name: &str, prefix: &str) -> Option<u32> {
+    let rest = name.strip_prefix(prefix)?;
+    let (pid, index) = rest.split_once('-')?;
+    index.parse::<u32>().ok()?;
+    pid.parse().ok()
+}
+
+#[cfg(unix)]
+fn is_orphaned(pid: u32, _modified: Option<SystemTime>) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    // Signal 0 checks for existence; EPERM means it exists under another user.
+    let alive = unsafe { libc::kill(pid, 0) } == 0
+        || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM);
+    !alive
+}
+
+#[cfg(not(unix))]
+fn is_orphaned(_pid: u32, modified: Option<SystemTime>) -> bool {
+    modified
+        .and_then(|m| m.elapsed().ok())
+        .is_some_and(|age| age > ORPHAN_MAX_AGE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_root(name: &str) -> PathBuf {
+        let root =
+            std::env::temp_dir().join(format!("grabme-temp-test-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    #[test]
+    fn test_dirs_are_unique_and_removed_on_drop() {
+        let root = scratch_root("drop");
+        let a = SessionTempDir::new_in(&root).unwrap();
+        let b = SessionTempDir::new_in(&root).unwrap();
+        assert_ne!(a.path(), b.path());
+
+        std::fs::write(a.join("icon.svg"), "<svg/>").unwrap();
+        let a_path = a.path().to_path_buf();
+        drop(a);
+        assert!(!a_path.exists());
+        assert!(b.path().is_dir());
+
+        drop(b);
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_owner_pid_parses_session_dir_names() {
+        assert_eq!(owner_pid("grabme-session-4242-0", DIR_PREFIX), Some(4242));
+        assert_eq!(owner_pid("grabme-session-4242", DIR_PREFIX), None);
+        assert_eq!(owner_pid("grabme-doctor-4242-x11", DIR_PREFIX), None);
+        assert_eq!(owner_pid("grabme-session-abc-1", DIR_PREFIX), None);
+        assert_eq!(
+            owner_pid("grabme-update-staging-4242-0", UPDATE_STAGING_PREFIX),
+            Some(4242)
+        );
+        assert_eq!(owner_pid("grabme-update-staging-4242-0", DIR_PREFIX), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_cleanup_removes_dead_sessions_only() {
+        let root = scratch_root("orphans");
+        let live = SessionTempDir::new_in(&root).unwrap();
+        // Pids are capped well below this on Linux and macOS.
+        let dead = root.join(format!("{DIR_PREFIX}{}-0", i32::MAX as u32 - 1));
+        std::fs::create_dir_all(&dead).unwrap();
+        let unrelated = root.join("grabme-cursor-pointer-lucide.svg");
+        std::fs::write(&unrelated, "").unwrap();
+        let live_staging = SessionTempDir::update_staging_in(&root).unwrap();
+        let dead_staging = root.join(format!("{UPDATE_STAGING_PREFIX}{}-0", i32::MAX as u32 - 1));
+        std::fs::create_dir_all(&dead_staging).unwrap();
+
+        assert_eq!(cleanup_orphaned_dirs_in(&root, DIR_PREFIX), 1);
+        assert!(!dead.exists());
+        assert!(live.path().is_dir());
+        assert!(unrelated.exists());
+        assert!(dead_staging.exists());
+
+        assert_eq!(cleanup_orphaned_dirs_in(&root, UPDATE_STAGING_PREFIX), 1);
+        assert!(!dead_staging.exists());
+        assert!(live_staging.path().is_dir());
+
+        drop(live);
+        drop(live_staging);
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
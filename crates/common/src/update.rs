@@ -0,0 +1,417 @@
+//! Release checks and in-place self-update.
+//!
+//! Releases are read from the GitHub releases API of the repository GrabMe
+//! is built from. Each release carries one archive per target,
+//! `grabme-<version>-<target>.tar.gz` (see `.github/workflows/dist.yml`),
+//! with a `sha256sum` listing of it in `<archive>.sha256`. A downloaded
+//! archive is unpacked and its binary replaces the running one only if the
+//! checksum matches and `<archive>.minisig` verifies with `minisign` against
+//! the release key built into this binary. The checksum file comes from the
+//! same release as the archive, so the signature is what ties a download to
+//! the project.
+//!
+//! Requests go through `curl` and archives are unpacked with `tar`. None are
+//! made when `network.offline` is set.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::Command;
+
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::config::AppConfig;
+use crate::error::{GrabmeError, GrabmeResult};
+use crate::temp::SessionTempDir;
+
+/// Version of the running build.
+pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Repository releases are published in.
+const REPOSITORY: &str = env!("CARGO_PKG_REPOSITORY");
+
+/// minisign public key releases are signed with, pinned at build time from
+/// `GRABME_RELEASE_PUBLIC_KEY` (see `.github/workflows/dist.yml`). Builds
+/// without one can check for updates but not install them.
+pub const RELEASE_PUBLIC_KEY: Option<&str> = option_env!("GRABME_RELEASE_PUBLIC_KEY");
+
+/// A published release.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Release {
+    /// Version without the tag's `v` prefix.
+    pub version: String,
+    /// Release page, for release notes.
+    pub page_url: String,
+    pub assets: Vec<ReleaseAsset>,
+}
+
+/// A file attached to a release.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ReleaseAsset {
+    pub name: String,
+    #[serde(rename = "browser_download_url")]
+    pub download_url: String,
+}
+
+#[derive(Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    html_url: String,
+    #[serde(default)]
+    assets: Vec<ReleaseAsset>,
+}
+
+impl Release {
+    fn asset(&self, name: &str) -> Option<&ReleaseAsset> {
+        self.assets.iter().find(|asset| asset.name == name)
+    }
+}
+
+/// The latest published release.
+pub fn latest_release(config: &AppConfig) -> GrabmeResult<Release> {
+    let api = releases_api_url()?;
+    let body = fetch(config, &format!("{api}/latest"), None)?;
+    let release: GithubRelease = serde_json::from_slice(&body).map_err(|e| {
+        GrabmeError::platform(format!("Unexpected reply from the releases API: {e}"))
+    })?;
+    Ok(Release {
+        version: release.tag_name.trim_start_matches('v').to_string(),
+        page_url: release.html_url,
+        assets: release.assets,
+    })
+}
+
+/// The latest release if it is newer than this build.
+pub fn check_for_update(config: &AppConfig) -> GrabmeResult<Option<Release>> {
+    let release = latest_release(config)?;
+    Ok(is_newer(&release.version, CURRENT_VERSION).then_some(release))
+}
+
+/// Whether version `candidate` is newer than `current`. Numeric parts are
+/// compared in order; a pre-release (`1.2.0-rc.1`) is older than its
+/// release.
+pub fn is_newer(candidate: &str, current: &str) -> bool {
+    fn parse(version: &str) -> (Vec<u64>, bool) {
+        let version = version.trim().trim_start_matches('v');
+        let (numbers, pre) = match version.split_once('-') {
+            Some((numbers, _)) => (numbers, true),
+            None => (version, false),
+        };
+        let numbers = numbers
+            .split('.')
+            .map(|part| part.parse().unwrap_or(0))
+            .collect();
+        (numbers, pre)
+    }
+    let (candidate, candidate_pre) = parse(candidate);
+    let (current, current_pre) = parse(current);
+    let len = candidate.len().max(current.len());
+    let pad = |parts: Vec<u64>| {
+        let mut parts = parts;
+        parts.resize(len, 0);
+        parts
+    };
+    match pad(candidate).cmp(&pad(current)) {
+        std::cmp::Ordering::Greater => true,
+        std::cmp::Ordering::Less => false,
+        std::cmp::Ordering::Equal => current_pre && !candidate_pre,
+    }
+}
+
+/// Target triple releases are built for on this platform, if any.
+pub fn release_target() -> Option<String> {
+    cfg!(all(target_os = "linux", target_env = "gnu"))
+        .then(|| format!("{}-unknown-linux-gnu", std::env::consts::ARCH))
+}
+
+/// Name of the release archive of `version` for `target`.
+pub fn archive_name(version: &str, target: &str) -> String {
+    format!("grabme-{version}-{target}.tar.gz")
+}
+
+/// Replace the executable at `exe` with its build from `release`, after
+/// checking the archive against its checksum and its signature.
+pub fn install(config: &AppConfig, release: &Release, exe: &Path) -> GrabmeResult<()> {
+    let public_key = release_public_key()?;
+    let program = exe
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| GrabmeError::platform(format!("Unusable program path {}", exe.display())))?;
+    let target = release_target().ok_or_else(|| {
+        GrabmeError::unsupported(format!(
+            "Releases are not built for {}-{}",
+            std::env::consts::ARCH,
+            std::env::consts::OS
+        ))
+    })?;
+    let name = archive_name(&release.version, &target);
+    let archive = release.asset(&name).ok_or_else(|| {
+        GrabmeError::unsupported(format!(
+            "Release {} has no build for this platform ({name})",
+            release.version
+        ))
+    })?;
+    let checksum_name = format!("{name}.sha256");
+    let checksum = release.asset(&checksum_name).ok_or_else(|| {
+        GrabmeError::platform(format!(
+            "Release {} has no {checksum_name}; refusing to install an unverified build",
+            release.version
+        ))
+    })?;
+    let signature_name = format!("{name}.minisig");
+    let signature = release.asset(&signature_name).ok_or_else(|| {
+        GrabmeError::platform(format!(
+            "Release {} is not signed ({signature_name} is missing); refusing to install it",
+            release.version
+        ))
+    })?;
+
+    // Stage next to the executable so the final rename stays on one volume.
+    // Staging left by a crash is removed at the next startup.
+    let exe_dir = exe.parent().unwrap_or(Path::new("."));
+    let staging =
+        SessionTempDir::update_staging_in(exe_dir).map_err(|e| GrabmeError::PermissionDenied {
+            message: format!("Can't write to {}: {e}", exe_dir.display()),
+        })?;
+
+    let listing = fetch(config, &checksum.download_url, None)?;
+    let expected = parse_checksums(&String::from_utf8_lossy(&listing))
+        .remove(&name)
+        .ok_or_else(|| GrabmeError::platform(format!("{checksum_name} does not list {name}")))?;
+    let download = staging.join(&name);
+    fetch(config, &archive.download_url, Some(&download))?;
+    let actual = sha256_hex(&std::fs::read(&download)?);
+    if !actual.eq_ignore_ascii_case(&expected) {
+        return Err(GrabmeError::platform(format!(
+            "Checksum mismatch for {name}: expected {expected}, got {actual}"
+        )));
+    }
+
+    let signature_path = staging.join(&signature_name);
+    fetch(config, &signature.download_url, Some(&signature_path))?;
+    verify_signature(&download, &signature_path, public_key)?;
+
+    let unpacked = staging.join("unpacked");
+    std::fs::create_dir_all(&unpacked)?;
+    unpack(&download, &unpacked)?;
+    let binary = unpacked.join(program);
+    if !binary.is_file() {
+        return Err(GrabmeError::platform(format!(
+            "{name} does not contain {program}"
+        )));
+    }
+
+    std::fs::set_permissions(&binary, std::fs::metadata(exe)?.permissions())?;
+    replace_executable(&binary, exe)
+}
+
+/// Parse a `sha256sum` listing into file name to lowercase hex digest.
+pub fn parse_checksums(text: &str) -> BTreeMap<String, String> {
+    text.lines()
+        .filter_map(|line| {
+            let (digest, name) = line.trim().split_once(char::is_whitespace)?;
+            let name = name.trim_start().trim_start_matches('*');
+            (digest.len() == 64 && !name.is_empty())
+                .then(|| (name.to_string(), digest.to_ascii_lowercase()))
+        })
+        .collect()
+}
+
+/// Lowercase hex SHA-256 of `bytes`.
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// The pinned release key, or an error for builds made without one.
+fn release_public_key() -> GrabmeResult<&'static str> {
+    RELEASE_PUBLIC_KEY
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+        .ok_or_else(|| {
+            GrabmeError::unsupported(
+                "This build has no release signing key, so it can't verify updates; \
+                 install a release build or update by hand",
+            )
+        })
+}
+
+fn releases_api_url() -> GrabmeResult<String> {
+    let path = REPOSITORY
+        .trim_end_matches('/')
+        .strip_prefix("https://github.com/")
+        .ok_or_else(|| {
+            GrabmeError::unsupported(format!(
+                "Releases are only looked up on GitHub, not {REPOSITORY}"
+            ))
+        })?;
+    Ok(format!("https://api.github.com/repos/{path}/releases"))
+}
+
+/// GET `url` into `dest`, or into memory when `dest` is `None`.
+fn fetch(config: &AppConfig, url: &str, dest: Option<&Path>) -> GrabmeResult<Vec<u8>> {
+    if config.network.offline {
+        return Err(GrabmeError::Config {
+            message: "Network access is disabled (network.offline in the config)".to_string(),
+        });
+    }
+    let mut command = Command::new("curl");
+    command.args([
+        "--fail",
+        "--silent",
+        "--show-error",
+        "--location",
+        "--max-time",
+        "300",
+        "--user-agent",
+        &format!("grabme/{CURRENT_VERSION}"),
+        "--header",
+        "Accept: application/vnd.github+json",
+    ]);
+    if let Some(dest) = dest {
+        command.arg("--output").arg(dest);
+    }
+    let output = command.arg(url).output().map_err(|e| {
+        GrabmeError::platform(format!("Failed to run curl (is it installed?): {e}"))
+    })?;
+    if !output.status.success() {
+        return Err(GrabmeError::platform(format!(
+            "Download of {url} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(output.stdout)
+}
+
+fn unpack(archive: &Path, dest: &Path) -> GrabmeResult<()> {
+    let output = Command::new("tar")
+        .arg("-xzf")
+        .arg(archive)
+        .arg("-C")
+        .arg(dest)
+        .output()
+        .map_err(|e| GrabmeError::platform(format!("Failed to run tar: {e}")))?;
+    if !output.status.success() {
+        return Err(GrabmeError::platform(format!(
+            "Failed to unpack {}: {}",
+            archive.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+fn verify_signature(file: &Path, signature: &Path, public_key: &str) -> GrabmeResult<()> {
+    let output = Command::new("minisign")
+        .arg("-V")
+        .arg("-m")
+        .arg(file)
+        .arg("-x")
+        .arg(signature)
+        .arg("-P")
+        .arg(public_key)
+        .output()
+        .map_err(|e| {
+            GrabmeError::platform(format!(
+                "Verifying the release signature needs minisign: {e}"
+            ))
+        })?;
+    if !output.status.success() {
+        return Err(GrabmeError::platform(format!(
+            "Release signature does not verify: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+/// Move `new` over `exe`. A running executable can be renamed but not
+/// overwritten on Windows, so the old one is moved aside there first.
+fn replace_executable(new: &Path, exe: &Path) -> GrabmeResult<()> {
+    let denied = |e: std::io::Error| match e.kind() {
+        std::io::ErrorKind::PermissionDenied => GrabmeError::PermissionDenied {
+            message: format!(
+                "Can't replace {}; run the update as the user that installed it",
+                exe.display()
+            ),
+        },
+        _ => GrabmeError::Io(e),
+    };
+    if !cfg!(windows) {
+        return std::fs::rename(new, exe).map_err(denied);
+    }
+    let old = exe.with_extension("old.exe");
+    let _ = std::fs::remove_file(&old);
+    std::fs::rename(exe, &old).map_err(denied)?;
+    std::fs::rename(new, exe).map_err(|e| {
+        // Put the old build back rather than leave nothing behind.
+        let _ = std::fs::rename(&old, exe);
+        denied(e)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_newer_compares_numeric_parts() {
+        assert!(is_newer("0.2.0", "0.1.9"));
+        assert!(is_newer("v0.10.0", "0.9.0"));
+        assert!(is_newer("1.0", "0.9.9"));
+        assert!(is_newer("1.2.0", "1.2.0-rc.1"));
+        assert!(!is_newer("1.2.0-rc.1", "1.2.0"));
+        assert!(!is_newer("0.1.0", "0.1.0"));
+        assert!(!is_newer("0.1.0", "0.1.1"));
+    }
+
+    #[test]
+    fn test_checksums_and_archive_names() {
+        let digest = sha256_hex(b"grabme");
+        assert_eq!(digest.len(), 64);
+        let name = archive_name("0.2.0", "x86_64-unknown-linux-gnu");
+        assert_eq!(name, "grabme-0.2.0-x86_64-unknown-linux-gnu.tar.gz");
+        let listing = format!(
+            "{digest}  {name}\n{}  *other.tar.gz\nnot a checksum line\n",
+            digest.to_ascii_uppercase()
+        );
+        let checksums = parse_checksums(&listing);
+        assert_eq!(checksums.len(), 2);
+        assert_eq!(checksums[&name], digest);
+        assert_eq!(checksums["other.tar.gz"], digest);
+
+        assert_eq!(
+            releases_api_url().unwrap(),
+            "https://api.github.com/repos/velocitatem/grabme/releases"
+        );
+    }
+
+    #[test]
+    fn test_install_needs_a_pinned_release_key() {
+        if RELEASE_PUBLIC_KEY.is_some() {
+            return;
+        }
+        let release = Release {
+            version: "99.0.0".to_string(),
+            page_url: String::new(),
+            assets: Vec::new(),
+        };
+        let err = install(
+            &AppConfig::default(),
+            &release,
+            Path::new("/nonexistent/grabme"),
+        )
+        .unwrap_err();
+        assert!(matches!(err, GrabmeError::Unsupported { .. }), "{err:?}");
+    }
+
+    #[test]
+    fn test_offline_config_blocks_requests() {
+        let mut config = AppConfig::default();
+        config.network.offline = true;
+        let err = latest_release(&config).unwrap_err();
+        assert!(matches!(err, GrabmeError::Config { .. }));
+    }
+}
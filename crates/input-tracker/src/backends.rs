@@ -2,6 +2,8 @@
 
 #[cfg(target_os = "linux")]
 mod linux;
+#[cfg(target_os = "linux")]
+mod linux_direct;
 #[cfg(not(target_os = "linux"))]
 mod non_linux;
 
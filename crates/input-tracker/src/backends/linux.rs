@@ -2,26 +2,64 @@
 
 use std::collections::VecDeque;
 use std::fs::OpenOptions;
-use std::io::Read;
+use std::io::{Read, Write};
 use std::os::unix::fs::MetadataExt;
 use std::os::unix::fs::OpenOptionsExt;
-use std::process::Command;
 use std::time::{Duration, Instant};
 
 use grabme_common::error::GrabmeResult;
 use grabme_platform_core::virtual_desktop_bounds;
+use grabme_platform_linux::x11_command;
 use grabme_project_model::event::InputEvent;
-use grabme_project_model::event::{ButtonState, MouseButton, PointerCoordinateSpace};
+use grabme_project_model::event::{ButtonState, MouseButton, PointerCoordinateSpace, ScrollSource};
+
+use super::linux_direct::DirectInputDevices;
+use crate::{InputBackend, InputOptions};
+
+/// Button bits in the first packet byte, then the two explorer-mode side
+/// buttons, in `button_state` order.
+const BUTTONS: [MouseButton; 5] = [
+    MouseButton::Left,
+    MouseButton::Right,
+    MouseButton::Middle,
+    MouseButton::Back,
+    MouseButton::Forward,
+];
+const POINTER_RESYNC_INTERVAL: Duration = Duration::from_millis(75);
 
-use crate::InputBackend;
+/// PS/2 protocol spoken by `/dev/input/mice`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MouseProtocol {
+    /// 3-byte packets: motion and three buttons.
+    Ps2,
+    /// 4-byte packets with an 8-bit vertical wheel.
+    IntelliMouse,
+    /// 4-byte packets with a 4-bit vertical wheel and the side buttons.
+    Explorer,
+}
 
-const LEFT_BUTTON: usize = 0;
-const RIGHT_BUTTON: usize = 1;
-const MIDDLE_BUTTON: usize = 2;
-const POINTER_RESYNC_INTERVAL: Duration = Duration::from_millis(75);
+impl MouseProtocol {
+    fn packet_len(self) -> usize {
+        match self {
+            Self::Ps2 => 3,
+            Self::IntelliMouse | Self::Explorer => 4,
+        }
+    }
+}
+
+/// One decoded `/dev/input/mice` packet.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct MousePacket {
+    dx: f64,
+    dy: f64,
+    /// Wheel steps, positive downwards.
+    wheel: f64,
+    buttons: [bool; 5],
+}
 
 pub struct EvdevBackend {
     device: std::fs::File,
+    protocol: MouseProtocol,
     pending: VecDeque<InputEvent>,
     x: f64,
     y: f64,
@@ -30,26 +68,34 @@ pub struct EvdevBackend {
     width: f64,
     height: f64,
     last_resync: Instant,
-    button_state: [bool; 3],
+    button_state: [bool; 5],
 }
 
 impl EvdevBackend {
     pub fn new() -> GrabmeResult<Self> {
-        let device = OpenOptions::new()
-            .read(true)
-            .custom_flags(libc::O_NONBLOCK)
-            .open("/dev/input/mice")
-            .map_err(|e| {
-                grabme_common::error::GrabmeError::input_tracking(format!(
-                    "Failed to open /dev/input/mice: {e}"
-                ))
-            })?;
+        // Write access lets us switch the device to explorer mode for the
+        // wheel and side buttons; without it we still get plain PS/2.
+        let open = |write: bool| {
+            OpenOptions::new()
+                .read(true)
+                .write(write)
+                .custom_flags(libc::O_NONBLOCK)
+                .open("/dev/input/mice")
+        };
+        let mut device = open(true).or_else(|_| open(false)).map_err(|e| {
+            grabme_common::error::GrabmeError::input_tracking(format!(
+                "Failed to open /dev/input/mice: {e}"
+            ))
+        })?;
+        let protocol = negotiate_protocol(&mut device);
+        tracing::debug!(?protocol, "Negotiated /dev/input/mice protocol");
 
         let (origin_x, origin_y, width, height) = desktop_geometry();
         let (x, y) = initial_pointer_position(origin_x, origin_y, width, height);
 
         Ok(Self {
             device,
+            protocol,
             pending: VecDeque::new(),
             x,
             y,
@@ -58,7 +104,7 @@ impl EvdevBackend {
             width,
             height,
             last_resync: Instant::now(),
-            button_state: [false, false, false],
+            button_state: [false; 5],
         })
     }
 
@@ -70,10 +116,12 @@ impl EvdevBackend {
     }
 
     fn ingest_packets(&mut self) -> GrabmeResult<()> {
+        let len = self.protocol.packet_len();
         loop {
-            let mut packet = [0u8; 3];
-            match self.device.read(&mut packet) {
-                Ok(3) => {
+            let mut packet = [0u8; 4];
+            match self.device.read(&mut packet[..len]) {
+                Ok(n) if n == len => {
+                    let packet = decode_packet(&packet[..len], self.protocol);
                     self.process_packet(packet);
                 }
                 Ok(0) => break,
@@ -89,12 +137,9 @@ impl EvdevBackend {
         Ok(())
     }
 
-    fn process_packet(&mut self, packet: [u8; 3]) {
-        let dx = packet[1] as i8 as f64;
-        let dy = packet[2] as i8 as f64;
-
-        self.x = (self.x + dx / self.width).clamp(0.0, 1.0);
-        self.y = (self.y - dy / self.height).clamp(0.0, 1.0);
+    fn process_packet(&mut self, packet: MousePacket) {
+        self.x = (self.x + packet.dx / self.width).clamp(0.0, 1.0);
+        self.y = (self.y - packet.dy / self.height).clamp(0.0, 1.0);
 
         if self.last_resync.elapsed() >= POINTER_RESYNC_INTERVAL {
             if let Some((rx, ry)) =
@@ -109,16 +154,25 @@ impl EvdevBackend {
         self.pending
             .push_back(InputEvent::pointer(0, self.x, self.y));
 
-        let left = packet[0] & 0b001 != 0;
-        let right = packet[0] & 0b010 != 0;
-        let middle = packet[0] & 0b100 != 0;
+        for (idx, pressed) in packet.buttons.into_iter().enumerate() {
+            self.push_button_transition(idx, pressed);
+        }
 
-        self.push_button_transition(LEFT_BUTTON, left, MouseButton::Left);
-        self.push_button_transition(RIGHT_BUTTON, right, MouseButton::Right);
-        self.push_button_transition(MIDDLE_BUTTON, middle, MouseButton::Middle);
+        // mousedev only forwards the vertical wheel; horizontal scrolling
+        // never reaches this device.
+        if packet.wheel != 0.0 {
+            self.pending.push_back(InputEvent::scroll(
+                0,
+                0.0,
+                packet.wheel,
+                ScrollSource::Wheel,
+                self.x,
+                self.y,
+            ));
+        }
     }
 
-    fn push_button_transition(&mut self, idx: usize, now: bool, button: MouseButton) {
+    fn push_button_transition(&mut self, idx: usize, now: bool) {
         let previous = self.button_state[idx];
         if previous == now {
             return;
@@ -131,7 +185,57 @@ impl EvdevBackend {
             ButtonState::Up
         };
         self.pending
-            .push_back(InputEvent::click(0, button, state, self.x, self.y));
+            .push_back(InputEvent::click(0, BUTTONS[idx], state, self.x, self.y));
+    }
+}
+
+/// Switch `/dev/input/mice` to the richest protocol it will accept, using the
+/// standard PS/2 sample-rate knock sequences, and return the one in effect.
+fn negotiate_protocol(device: &mut std::fs::File) -> MouseProtocol {
+    const INTELLIMOUSE_KNOCK: [u8; 6] = [0xF3, 200, 0xF3, 100, 0xF3, 80];
+    const EXPLORER_KNOCK: [u8; 6] = [0xF3, 200, 0xF3, 200, 0xF3, 80];
+    const GET_DEVICE_ID: u8 = 0xF2;
+
+    let mut knock = |sequence: &[u8]| -> Option<u8> {
+        device.write_all(sequence).ok()?;
+        device.write_all(&[GET_DEVICE_ID]).ok()?;
+        // mousedev answers synchronously: one ACK per byte, then the id.
+        let mut reply = [0u8; 16];
+        let n = device.read(&mut reply).ok()?;
+        reply[..n].last().copied()
+    };
+
+    if knock(&INTELLIMOUSE_KNOCK) != Some(3) {
+        return MouseProtocol::Ps2;
+    }
+    match knock(&EXPLORER_KNOCK) {
+        Some(4) => MouseProtocol::Explorer,
+        _ => MouseProtocol::IntelliMouse,
+    }
+}
+
+fn decode_packet(packet: &[u8], protocol: MouseProtocol) -> MousePacket {
+    let mut buttons = [false; 5];
+    for (idx, pressed) in buttons.iter_mut().take(3).enumerate() {
+        *pressed = packet[0] & (1 << idx) != 0;
+    }
+
+    let wheel = match protocol {
+        MouseProtocol::Ps2 => 0,
+        MouseProtocol::IntelliMouse => packet[3] as i8,
+        MouseProtocol::Explorer => {
+            buttons[3] = packet[3] & 0x10 != 0;
+            buttons[4] = packet[3] & 0x20 != 0;
+            // Sign-extend the low nibble.
+            ((packet[3] << 4) as i8) >> 4
+        }
+    };
+
+    MousePacket {
+        dx: packet[1] as i8 as f64,
+        dy: packet[2] as i8 as f64,
+        wheel: wheel as f64,
+        buttons,
     }
 }
 
@@ -227,7 +331,7 @@ impl X11PollingBackend {
     }
 
     pub fn is_supported() -> bool {
-        Command::new("xdotool").arg("--version").output().is_ok()
+        x11_command("xdotool").arg("--version").output().is_ok()
     }
 }
 
@@ -264,7 +368,77 @@ impl InputBackend for X11PollingBackend {
     }
 }
 
+/// A pointer backend plus touchscreen, stylus, and gamepad devices.
+pub struct WithDirectInput {
+    pointer: Box<dyn InputBackend>,
+    direct: DirectInputDevices,
+    name: String,
+}
+
+impl WithDirectInput {
+    pub fn new(pointer: Box<dyn InputBackend>, direct: DirectInputDevices) -> Self {
+        let name = format!("{}+direct", pointer.name());
+        Self {
+            pointer,
+            direct,
+            name,
+        }
+    }
+}
+
+impl InputBackend for WithDirectInput {
+    fn poll(&mut self) -> GrabmeResult<Option<InputEvent>> {
+        if let Some(event) = self.direct.poll()? {
+            return Ok(Some(event));
+        }
+        self.pointer.poll()
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn is_available(&self) -> bool {
+        self.pointer.is_available()
+    }
+
+    fn pointer_coordinate_space(&self) -> PointerCoordinateSpace {
+        self.pointer.pointer_coordinate_space()
+    }
+}
+
 pub fn detect_best_backend() -> Box<dyn InputBackend> {
+    detect_backend_with(InputOptions::default())
+}
+
+/// Like [`detect_best_backend`], also opening the optional sources in `options`.
+pub fn detect_backend_with(options: InputOptions) -> Box<dyn InputBackend> {
+    let pointer = detect_pointer_backend();
+
+    // Direct devices belong to the local seat, like `/dev/input/mice`, not
+    // to an explicitly chosen display.
+    if grabme_platform_linux::display_target()
+        .x11_display
+        .is_some()
+    {
+        return pointer;
+    }
+    // Touch positions are desktop-normalized, which only lines up with a
+    // pointer backend using the same space.
+    let touch =
+        pointer.pointer_coordinate_space() == PointerCoordinateSpace::VirtualDesktopNormalized;
+    let direct = DirectInputDevices::open(touch, options.gamepad);
+    if direct.is_empty() {
+        if options.gamepad {
+            tracing::warn!("Gamepad capture requested but no readable gamepad was found");
+        }
+        return pointer;
+    }
+    tracing::info!(devices = ?direct.device_names(), "Recording direct input devices");
+    Box::new(WithDirectInput::new(pointer, direct))
+}
+
+fn detect_pointer_backend() -> Box<dyn InputBackend> {
     if let Ok(forced) = std::env::var("GRABME_FORCE_INPUT_BACKEND") {
         match forced.as_str() {
             "x11" | "x11-polling" => {
@@ -293,6 +467,24 @@ pub fn detect_best_backend() -> Box<dyn InputBackend> {
         }
     }
 
+    // Kernel input devices belong to the local seat, not to an explicitly
+    // chosen (often virtual) X display, so poll that display instead.
+    if grabme_platform_linux::display_target()
+        .x11_display
+        .is_some()
+        && X11PollingBackend::is_supported()
+    {
+        match X11PollingBackend::new() {
+            Ok(backend) => {
+                tracing::info!("Using X11 polling backend for explicit display");
+                return Box::new(backend);
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to initialize X11 polling backend");
+            }
+        }
+    }
+
     if EvdevBackend::is_supported() {
         match EvdevBackend::new() {
             Ok(backend) => {
@@ -358,7 +550,7 @@ fn desktop_geometry() -> (f64, f64, f64, f64) {
             )
         }
         _ => {
-            if let Ok(output) = Command::new("xdotool").arg("getdisplaygeometry").output() {
+            if let Ok(output) = x11_command("xdotool").arg("getdisplaygeometry").output() {
                 if output.status.success() {
                     let s = String::from_utf8_lossy(&output.stdout);
                     let parts: Vec<&str> = s.split_whitespace().collect();
@@ -389,7 +581,7 @@ fn query_pointer_position(
         return None;
     }
 
-    let output = Command::new("xdotool")
+    let output = x11_command("xdotool")
         .args(["getmouselocation", "--shell"])
         .output();
 
@@ -435,6 +627,33 @@ fn normalize_virtual_point(
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_decode_ps2_packet() {
+        let packet = decode_packet(&[0b101, 3, 0xFE], MouseProtocol::Ps2);
+        assert_eq!(packet.dx, 3.0);
+        assert_eq!(packet.dy, -2.0);
+        assert_eq!(packet.wheel, 0.0);
+        assert_eq!(packet.buttons, [true, false, true, false, false]);
+    }
+
+    #[test]
+    fn test_decode_explorer_packet_wheel_and_side_buttons() {
+        // Wheel one step up (-1 in the low nibble), back button held.
+        let packet = decode_packet(&[0, 0, 0, 0x1F], MouseProtocol::Explorer);
+        assert_eq!(packet.wheel, -1.0);
+        assert_eq!(packet.buttons, [false, false, false, true, false]);
+
+        let packet = decode_packet(&[0, 0, 0, 0x22], MouseProtocol::Explorer);
+        assert_eq!(packet.wheel, 2.0);
+        assert_eq!(packet.buttons, [false, false, false, false, true]);
+    }
+
+    #[test]
+    fn test_decode_intellimouse_packet_wheel() {
+        let packet = decode_packet(&[0, 0, 0, 0xFD], MouseProtocol::IntelliMouse);
+        assert_eq!(packet.wheel, -3.0);
+    }
+
     #[test]
     fn test_normalize_virtual_point_with_zero_origin() {
         let (x, y) = normalize_virtual_point(960.0, 540.0, 0.0, 0.0, 1920.0, 1080.0);
@@ -0,0 +1,920 @@
+//! Touchscreen, stylus, and gamepad capture from `/dev/input/event*`.
+//!
+//! `/dev/input/mice` only carries relative pointer motion, so other devices
+//! are opened with the `evdev` crate and read separately: direct-touch
+//! panels with multitouch slots, anything with a pen tool, and anything with
+//! gamepad buttons. Touchpads are left alone; they already move the pointer.
+//!
+//! Touch and pen positions are stored as virtual-desktop coordinates. A
+//! direct device (a touchscreen, or a pen on one) covers a single output,
+//! which is picked the way compositors map them by default: built-in panels
+//! to the built-in display, external ones to the primary external display.
+//! Pen tablets without a screen cover the whole desktop.
+//!
+//! There is no portal path. No desktop portal reports touch or pen input to
+//! an observer; the InputCapture portal takes input away from the desktop
+//! while it captures, which is no use for a recording.
+
+use std::collections::VecDeque;
+use std::os::unix::io::AsRawFd;
+
+use evdev::{
+    AbsoluteAxisType, AttributeSetRef, BusType, Device, InputEventKind, Key, PropType,
+    Synchronization,
+};
+use grabme_common::error::{GrabmeError, GrabmeResult};
+use grabme_platform_core::{virtual_desktop_bounds, MonitorInfo};
+use grabme_project_model::event::{
+    ButtonState, GamepadAxis, GamepadButton, InputEvent, StylusTool, TouchPhase,
+};
+
+/// Stick values inside this radius are recorded as centred.
+const STICK_DEADZONE: f64 = 0.12;
+/// Smallest axis change worth an event.
+const AXIS_STEP: f64 = 0.05;
+
+/// Connector prefixes of built-in panels.
+const BUILTIN_CONNECTORS: [&str; 3] = ["eDP", "LVDS", "DSI"];
+
+/// Range of one absolute axis.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct AxisRange {
+    min: i32,
+    max: i32,
+}
+
+impl AxisRange {
+    fn normalize(self, value: i32) -> f64 {
+        let span = (self.max - self.min).max(1) as f64;
+        ((value - self.min) as f64 / span).clamp(0.0, 1.0)
+    }
+}
+
+/// The part of the virtual desktop a touch or pen device covers, normalized
+/// to the desktop.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct DesktopArea {
+    x: f64,
+    y: f64,
+    w: f64,
+    h: f64,
+}
+
+impl DesktopArea {
+    const FULL: DesktopArea = DesktopArea {
+        x: 0.0,
+        y: 0.0,
+        w: 1.0,
+        h: 1.0,
+    };
+
+    /// `monitor`'s rectangle within the desktop spanned by `monitors`.
+    fn of_monitor(monitor: &MonitorInfo, monitors: &[MonitorInfo]) -> Self {
+        let (origin_x, origin_y, width, height) = virtual_desktop_bounds(monitors);
+        let (width, height) = (width.max(1) as f64, height.max(1) as f64);
+        Self {
+            x: (monitor.x - origin_x) as f64 / width,
+            y: (monitor.y - origin_y) as f64 / height,
+            w: monitor.width as f64 / width,
+            h: monitor.height as f64 / height,
+        }
+    }
+
+    /// Desktop position of a point normalized to the device's axes.
+    fn map(self, x: f64, y: f64) -> (f64, f64) {
+        (self.x + x * self.w, self.y + y * self.h)
+    }
+}
+
+/// The area a device covers: an output for direct devices, the desktop for
+/// tablets without a screen.
+fn device_area(direct: bool, builtin: bool, monitors: &[MonitorInfo]) -> DesktopArea {
+    if !direct || monitors.len() < 2 {
+        return DesktopArea::FULL;
+    }
+    let is_builtin = |m: &&MonitorInfo| {
+        BUILTIN_CONNECTORS
+            .iter()
+            .any(|prefix| m.name.starts_with(prefix))
+    };
+    let external = || {
+        let mut external = monitors.iter().filter(|m| !is_builtin(m));
+        let first = external.clone().next();
+        external.find(|m| m.primary).or(first)
+    };
+    let output = if builtin {
+        monitors.iter().find(is_builtin)
+    } else {
+        external()
+    }
+    .or_else(|| monitors.iter().find(|m| m.primary))
+    .unwrap_or(&monitors[0]);
+    DesktopArea::of_monitor(output, monitors)
+}
+
+/// What kind of absolute device an evdev node is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DirectKind {
+    Touchscreen,
+    Stylus,
+    Gamepad,
+}
+
+/// Classify a device by its capabilities, or `None` for devices not read
+/// here.
+fn classify(
+    props: &AttributeSetRef<PropType>,
+    keys: Option<&AttributeSetRef<Key>>,
+    abs: Option<&AttributeSetRef<AbsoluteAxisType>>,
+) -> Option<DirectKind> {
+    let has_key = |key| keys.is_some_and(|keys| keys.contains(key));
+    let has_abs = |axis| abs.is_some_and(|abs| abs.contains(axis));
+    if has_key(Key::BTN_TOOL_PEN) && has_abs(AbsoluteAxisType::ABS_X) {
+        Some(DirectKind::Stylus)
+    } else if props.contains(PropType::DIRECT)
+        && has_abs(AbsoluteAxisType::ABS_MT_SLOT)
+        && has_abs(AbsoluteAxisType::ABS_MT_POSITION_X)
+    {
+        Some(DirectKind::Touchscreen)
+    } else if has_key(Key::BTN_SOUTH) {
+        Some(DirectKind::Gamepad)
+    } else {
+        None
+    }
+}
+
+/// Per-slot multitouch state (protocol B).
+#[derive(Debug, Clone, Copy, Default)]
+struct TouchSlot {
+    tracking_id: Option<i32>,
+    x: i32,
+    y: i32,
+    pressure: Option<i32>,
+    began: bool,
+    ended: bool,
+    moved: bool,
+}
+
+/// Turns multitouch evdev frames into `Touch` events.
+#[derive(Debug)]
+struct TouchTracker {
+    x_range: AxisRange,
+    y_range: AxisRange,
+    pressure_range: Option<AxisRange>,
+    area: DesktopArea,
+    slots: Vec<TouchSlot>,
+    current_slot: usize,
+}
+
+impl TouchTracker {
+    fn new(
+        x_range: AxisRange,
+        y_range: AxisRange,
+        pressure_range: Option<AxisRange>,
+        area: DesktopArea,
+    ) -> Self {
+        Self {
+            x_range,
+            y_range,
+            pressure_range,
+            area,
+            slots: vec![TouchSlot::default()],
+            current_slot: 0,
+        }
+    }
+
+    fn slot(&mut self) -> &mut TouchSlot {
+        if self.current_slot >= self.slots.len() {
+            self.slots
+                .resize(self.current_slot + 1, TouchSlot::default());
+        }
+        &mut self.slots[self.current_slot]
+    }
+
+    fn handle(&mut self, event: &evdev::InputEvent, out: &mut VecDeque<InputEvent>) {
+        let value = event.value();
+        match event.kind() {
+            InputEventKind::AbsAxis(AbsoluteAxisType::ABS_MT_SLOT) => {
+                self.current_slot = value.max(0) as usize
+            }
+            InputEventKind::AbsAxis(AbsoluteAxisType::ABS_MT_TRACKING_ID) => {
+                let slot = self.slot();
+                if value < 0 {
+                    slot.ended = slot.tracking_id.is_some();
+                } else {
+                    slot.tracking_id = Some(value);
+                    slot.began = true;
+                    slot.ended = false;
+                }
+            }
+            InputEventKind::AbsAxis(AbsoluteAxisType::ABS_MT_POSITION_X) => {
+                let slot = self.slot();
+                slot.x = value;
+                slot.moved = true;
+            }
+            InputEventKind::AbsAxis(AbsoluteAxisType::ABS_MT_POSITION_Y) => {
+                let slot = self.slot();
+                slot.y = value;
+                slot.moved = true;
+            }
+            InputEventKind::AbsAxis(AbsoluteAxisType::ABS_MT_PRESSURE) => {
+                let slot = self.slot();
+                slot.pressure = Some(value);
+                slot.moved = true;
+            }
+            InputEventKind::Synchronization(Synchronization::SYN_REPORT) => self.flush(out),
+            _ => {}
+        }
+    }
+
+    fn flush(&mut self, out: &mut VecDeque<InputEvent>) {
+        let (x_range, y_range, pressure_range) = (self.x_range, self.y_range, self.pressure_range);
+        for slot in &mut self.slots {
+            let Some(id) = slot.tracking_id else {
+                continue;
+            };
+            let phase = if slot.ended {
+                TouchPhase::Up
+            } else if slot.began {
+                TouchPhase::Down
+            } else if slot.moved {
+                TouchPhase::Move
+            } else {
+                continue;
+            };
+            let pressure = pressure_range
+                .zip(slot.pressure)
+                .map(|(range, value)| range.normalize(value));
+            let (x, y) = self
+                .area
+                .map(x_range.normalize(slot.x), y_range.normalize(slot.y));
+            out.push_back(InputEvent::touch(0, id as u32, phase, x, y, pressure));
+
+            slot.began = false;
+            slot.moved = false;
+            if slot.ended {
+                *slot = TouchSlot::default();
+            }
+        }
+    }
+}
+
+/// Turns pen tablet evdev frames into `Stylus` events.
+#[derive(Debug)]
+struct StylusTracker {
+    x_range: AxisRange,
+    y_range: AxisRange,
+    pressure_range: Option<AxisRange>,
+    area: DesktopArea,
+    tool: StylusTool,
+    x: i32,
+    y: i32,
+    pressure: i32,
+    in_contact: bool,
+    contact_changed: bool,
+    moved: bool,
+}
+
+impl StylusTracker {
+    fn new(
+        x_range: AxisRange,
+        y_range: AxisRange,
+        pressure_range: Option<AxisRange>,
+        area: DesktopArea,
+    ) -> Self {
+        Self {
+            x_range,
+            y_range,
+            pressure_range,
+            area,
+            tool: StylusTool::Pen,
+            x: 0,
+            y: 0,
+            pressure: 0,
+            in_contact: false,
+            contact_changed: false,
+            moved: false,
+        }
+    }
+
+    fn handle(&mut self, event: &evdev::InputEvent, out: &mut VecDeque<InputEvent>) {
+        let value = event.value();
+        match event.kind() {
+            InputEventKind::Key(Key::BTN_TOOL_PEN) if value != 0 => self.tool = StylusTool::Pen,
+            InputEventKind::Key(Key::BTN_TOOL_RUBBER) if value != 0 => {
+                self.tool = StylusTool::Eraser
+            }
+            InputEventKind::Key(Key::BTN_TOUCH) => {
+                let now = value != 0;
+                self.contact_changed |= now != self.in_contact;
+                self.in_contact = now;
+            }
+            InputEventKind::AbsAxis(AbsoluteAxisType::ABS_X) => {
+                self.x = value;
+                self.moved = true;
+            }
+            InputEventKind::AbsAxis(AbsoluteAxisType::ABS_Y) => {
+                self.y = value;
+                self.moved = true;
+            }
+            InputEventKind::AbsAxis(AbsoluteAxisType::ABS_PRESSURE) => {
+                self.pressure = value;
+                self.moved = true;
+            }
+            InputEventKind::Synchronization(Synchronization::SYN_REPORT) => self.flush(out),
+            _ => {}
+        }
+    }
+
+    fn flush(&mut self, out: &mut VecDeque<InputEvent>) {
+        // Hovering only moves the cursor, which the pointer backend records.
+        let phase = match (self.contact_changed, self.in_contact) {
+            (true, true) => Some(TouchPhase::Down),
+            (true, false) => Some(TouchPhase::Up),
+            (false, true) if self.moved => Some(TouchPhase::Move),
+            _ => None,
+        };
+        if let Some(phase) = phase {
+            let pressure = match self.pressure_range {
+                Some(range) => range.normalize(self.pressure),
+                None if self.in_contact => 1.0,
+                None => 0.0,
+            };
+            let (x, y) = self.area.map(
+                self.x_range.normalize(self.x),
+                self.y_range.normalize(self.y),
+            );
+            out.push_back(InputEvent::stylus(0, self.tool, phase, x, y, pressure));
+        }
+        self.contact_changed = false;
+        self.moved = false;
+    }
+}
+
+/// Map an evdev key to a gamepad button.
+fn gamepad_button(key: Key) -> GamepadButton {
+    match key {
+        Key::BTN_SOUTH => GamepadButton::South,
+        Key::BTN_EAST => GamepadButton::East,
+        Key::BTN_NORTH => GamepadButton::North,
+        Key::BTN_WEST => GamepadButton::West,
+        Key::BTN_TL => GamepadButton::LeftBumper,
+        Key::BTN_TR => GamepadButton::RightBumper,
+        Key::BTN_TL2 => GamepadButton::LeftTrigger,
+        Key::BTN_TR2 => GamepadButton::RightTrigger,
+        Key::BTN_SELECT => GamepadButton::Select,
+        Key::BTN_START => GamepadButton::Start,
+        Key::BTN_MODE => GamepadButton::Mode,
+        Key::BTN_THUMBL => GamepadButton::LeftStick,
+        Key::BTN_THUMBR => GamepadButton::RightStick,
+        Key::BTN_DPAD_UP => GamepadButton::DpadUp,
+        Key::BTN_DPAD_DOWN => GamepadButton::DpadDown,
+        Key::BTN_DPAD_LEFT => GamepadButton::DpadLeft,
+        Key::BTN_DPAD_RIGHT => GamepadButton::DpadRight,
+        other => GamepadButton::Other(other.code()),
+    }
+}
+
+/// Turns gamepad evdev events into `GamepadButton`/`GamepadAxis` events.
+/// The hat switch most pads use for the d-pad is reported as d-pad buttons.
+#[derive(Debug)]
+struct GamepadTracker {
+    pad: u32,
+    axes: Vec<(AbsoluteAxisType, GamepadAxis, AxisRange)>,
+    last_values: Vec<f64>,
+    hat: [i32; 2],
+}
+
+impl GamepadTracker {
+    fn new(pad: u32, axes: Vec<(AbsoluteAxisType, GamepadAxis, AxisRange)>) -> Self {
+        let last_values = vec![0.0; axes.len()];
+        Self {
+            pad,
+            axes,
+            last_values,
+            hat: [0, 0],
+        }
+    }
+
+    fn handle(&mut self, event: &evdev::InputEvent, out: &mut VecDeque<InputEvent>) {
+        let value = event.value();
+        match event.kind() {
+            // Autorepeat (2) carries no new state.
+            InputEventKind::Key(key) if value != 2 => {
+                let state = if value != 0 {
+                    ButtonState::Down
+                } else {
+                    ButtonState::Up
+                };
+                out.push_back(InputEvent::gamepad_button(
+                    0,
+                    self.pad,
+                    gamepad_button(key),
+                    state,
+                ));
+            }
+            InputEventKind::AbsAxis(
+                axis @ (AbsoluteAxisType::ABS_HAT0X | AbsoluteAxisType::ABS_HAT0Y),
+            ) => {
+                let (idx, negative, positive) = if axis == AbsoluteAxisType::ABS_HAT0X {
+                    (0, GamepadButton::DpadLeft, GamepadButton::DpadRight)
+                } else {
+                    (1, GamepadButton::DpadUp, GamepadButton::DpadDown)
+                };
+                let previous = std::mem::replace(&mut self.hat[idx], value.signum());
+                if previous == value.signum() {
+                    return;
+                }
+                let button_for = |dir: i32| if dir < 0 { negative } else { positive };
+                if previous != 0 {
+                    out.push_back(InputEvent::gamepad_button(
+                        0,
+                        self.pad,
+                        button_for(previous),
+                        ButtonState::Up,
+                    ));
+                }
+                if value != 0 {
+                    out.push_back(InputEvent::gamepad_button(
+                        0,
+                        self.pad,
+                        button_for(value),
+                        ButtonState::Down,
+                    ));
+                }
+            }
+            InputEventKind::AbsAxis(code) => {
+                let Some(idx) = self
+                    .axes
+                    .iter()
+                    .position(|(axis_code, _, _)| *axis_code == code)
+                else {
+                    return;
+                };
+                let (_, axis, range) = self.axes[idx];
+                let value = normalize_gamepad_axis(axis, range, value);
+                let last = self.last_values[idx];
+                let recentred = value == 0.0 && last != 0.0;
+                if (value - last).abs() >= AXIS_STEP || recentred {
+                    self.last_values[idx] = value;
+                    out.push_back(InputEvent::gamepad_axis(0, self.pad, axis, value));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn normalize_gamepad_axis(axis: GamepadAxis, range: AxisRange, value: i32) -> f64 {
+    let unit = range.normalize(value);
+    match axis {
+        GamepadAxis::LeftTrigger | GamepadAxis::RightTrigger => unit,
+        _ => {
+            let centred = unit * 2.0 - 1.0;
+            if centred.abs() < STICK_DEADZONE {
+                0.0
+            } else {
+                centred
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Tracker {
+    Touch(TouchTracker),
+    Stylus(StylusTracker),
+    Gamepad(GamepadTracker),
+}
+
+impl Tracker {
+    fn handle(&mut self, event: &evdev::InputEvent, out: &mut VecDeque<InputEvent>) {
+        match self {
+            Tracker::Touch(tracker) => tracker.handle(event, out),
+            Tracker::Stylus(tracker) => tracker.handle(event, out),
+            Tracker::Gamepad(tracker) => tracker.handle(event, out),
+        }
+    }
+}
+
+struct DirectDevice {
+    name: String,
+    device: Device,
+    tracker: Tracker,
+}
+
+/// All touchscreens, pen devices, and gamepads the process can read.
+pub struct DirectInputDevices {
+    devices: Vec<DirectDevice>,
+    pending: VecDeque<InputEvent>,
+}
+
+impl DirectInputDevices {
+    /// Open every readable touchscreen and stylus device (with `touch`) and
+    /// gamepad (with `gamepads`). Devices that can't be opened (usually
+    /// permissions) are not listed by evdev and so are skipped.
+    pub fn open(touch: bool, gamepads: bool) -> Self {
+        let monitors = if touch {
+            grabme_platform_linux::detect_monitors().unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+        let mut next_pad = 0u32;
+        let mut devices = Vec::new();
+        for (path, device) in evdev::enumerate() {
+            let Some(kind) = classify(
+                device.properties(),
+                device.supported_keys(),
+                device.supported_absolute_axes(),
+            ) else {
+                continue;
+            };
+            let wanted = match kind {
+                DirectKind::Touchscreen | DirectKind::Stylus => touch,
+                DirectKind::Gamepad => gamepads,
+            };
+            if !wanted {
+                continue;
+            }
+            let name = device.name().unwrap_or("unnamed device").to_string();
+            match open_device(name, device, kind, next_pad, &monitors) {
+                Ok(device) => {
+                    if kind == DirectKind::Gamepad {
+                        next_pad += 1;
+                    }
+                    tracing::info!(name = %device.name, node = %path.display(), ?kind, "Capturing direct input device");
+                    devices.push(device);
+                }
+                Err(e) => {
+                    tracing::debug!(node = %path.display(), error = %e, "Skipping direct input device");
+                }
+            }
+        }
+
+        Self {
+            devices,
+            pending: VecDeque::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.devices.is_empty()
+    }
+
+    /// Names of the devices being read.
+    pub fn device_names(&self) -> Vec<&str> {
+        self.devices.iter().map(|d| d.name.as_str()).collect()
+    }
+
+    pub fn poll(&mut self) -> GrabmeResult<Option<InputEvent>> {
+        if self.pending.is_empty() {
+            for device in &mut self.devices {
+                read_device(device, &mut self.pending)?;
+            }
+        }
+        Ok(self.pending.pop_front())
+    }
+}
+
+fn open_device(
+    name: String,
+    device: Device,
+    kind: DirectKind,
+    pad: u32,
+    monitors: &[MonitorInfo],
+) -> std::io::Result<DirectDevice> {
+    // Polled alongside the pointer backend, so reads must not block.
+    // SAFETY: plain fcntl calls on a descriptor the device owns.
+    unsafe {
+        let fd = device.as_raw_fd();
+        let flags = libc::fcntl(fd, libc::F_GETFL);
+        if flags < 0 || libc::fcntl(fd, libc::F_SETFL, flags | libc::O_NONBLOCK) < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+    }
+
+    let state = device.get_abs_state()?;
+    let supported = device.supported_absolute_axes();
+    let range = |axis: AbsoluteAxisType| {
+        let info = &state[axis.0 as usize];
+        supported
+            .is_some_and(|axes| axes.contains(axis))
+            .then_some(AxisRange {
+                min: info.minimum,
+                max: info.maximum,
+            })
+            .filter(|r| r.max > r.min)
+    };
+    let missing = |axis: AbsoluteAxisType| {
+        std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            format!("{name} reports no range for {axis:?}"),
+        )
+    };
+    let direct = device.properties().contains(PropType::DIRECT);
+    let builtin = matches!(
+        device.input_id().bus_type(),
+        BusType::BUS_I2C | BusType::BUS_HOST | BusType::BUS_SPI
+    );
+    let area = device_area(direct, builtin, monitors);
+
+    let tracker = match kind {
+        DirectKind::Touchscreen => Tracker::Touch(TouchTracker::new(
+            range(AbsoluteAxisType::ABS_MT_POSITION_X)
+                .ok_or_else(|| missing(AbsoluteAxisType::ABS_MT_POSITION_X))?,
+            range(AbsoluteAxisType::ABS_MT_POSITION_Y)
+                .ok_or_else(|| missing(AbsoluteAxisType::ABS_MT_POSITION_Y))?,
+            range(AbsoluteAxisType::ABS_MT_PRESSURE),
+            area,
+        )),
+        DirectKind::Stylus => Tracker::Stylus(StylusTracker::new(
+            range(AbsoluteAxisType::ABS_X).ok_or_else(|| missing(AbsoluteAxisType::ABS_X))?,
+            range(AbsoluteAxisType::ABS_Y).ok_or_else(|| missing(AbsoluteAxisType::ABS_Y))?,
+            range(AbsoluteAxisType::ABS_PRESSURE),
+            area,
+        )),
+        DirectKind::Gamepad => {
+            let axes = [
+                (AbsoluteAxisType::ABS_X, GamepadAxis::LeftStickX),
+                (AbsoluteAxisType::ABS_Y, GamepadAxis::LeftStickY),
+                (AbsoluteAxisType::ABS_RX, GamepadAxis::RightStickX),
+                (AbsoluteAxisType::ABS_RY, GamepadAxis::RightStickY),
+                (AbsoluteAxisType::ABS_Z, GamepadAxis::LeftTrigger),
+                (AbsoluteAxisType::ABS_RZ, GamepadAxis::RightTrigger),
+            ]
+            .into_iter()
+            .filter_map(|(code, axis)| Some((code, axis, range(code)?)))
+            .collect();
+            Tracker::Gamepad(GamepadTracker::new(pad, axes))
+        }
+    };
+
+    Ok(DirectDevice {
+        name,
+        device,
+        tracker,
+    })
+}
+
+fn read_device(device: &mut DirectDevice, out: &mut VecDeque<InputEvent>) -> GrabmeResult<()> {
+    loop {
+        let events = match device.device.fetch_events() {
+            Ok(events) => events,
+            Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => return Ok(()),
+            Err(err) => {
+                return Err(GrabmeError::input_tracking(format!(
+                    "Failed reading {}: {err}",
+                    device.name
+                )));
+            }
+        };
+        let mut any = false;
+        for event in events {
+            any = true;
+            device.tracker.handle(&event, out);
+        }
+        if !any {
+            return Ok(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use evdev::{AttributeSet, EventType};
+    use grabme_project_model::event::EventKind;
+
+    const RANGE: AxisRange = AxisRange { min: 0, max: 1000 };
+
+    fn abs(axis: AbsoluteAxisType, value: i32) -> evdev::InputEvent {
+        evdev::InputEvent::new(EventType::ABSOLUTE, axis.0, value)
+    }
+
+    fn key(key: Key, value: i32) -> evdev::InputEvent {
+        evdev::InputEvent::new(EventType::KEY, key.code(), value)
+    }
+
+    fn syn() -> evdev::InputEvent {
+        evdev::InputEvent::new(EventType::SYNCHRONIZATION, Synchronization::SYN_REPORT.0, 0)
+    }
+
+    fn monitor(name: &str, x: i32, primary: bool) -> MonitorInfo {
+        MonitorInfo {
+            name: name.to_string(),
+            width: 1920,
+            height: 1080,
+            x,
+            y: 0,
+            scale_factor: 1.0,
+            refresh_rate_hz: 60,
+            primary,
+        }
+    }
+
+    #[test]
+    fn test_classify_finds_touchscreen_pen_and_gamepad() {
+        let direct: AttributeSet<PropType> = [PropType::DIRECT].into_iter().collect();
+        let pointer: AttributeSet<PropType> = [PropType::POINTER].into_iter().collect();
+        let mt: AttributeSet<AbsoluteAxisType> = [
+            AbsoluteAxisType::ABS_X,
+            AbsoluteAxisType::ABS_Y,
+            AbsoluteAxisType::ABS_MT_SLOT,
+            AbsoluteAxisType::ABS_MT_POSITION_X,
+            AbsoluteAxisType::ABS_MT_POSITION_Y,
+        ]
+        .into_iter()
+        .collect();
+        let xy: AttributeSet<AbsoluteAxisType> = [AbsoluteAxisType::ABS_X, AbsoluteAxisType::ABS_Y]
+            .into_iter()
+            .collect();
+        let touch: AttributeSet<Key> = [Key::BTN_TOUCH].into_iter().collect();
+        let pen: AttributeSet<Key> = [Key::BTN_TOOL_PEN, Key::BTN_TOUCH].into_iter().collect();
+        let pad: AttributeSet<Key> = [Key::BTN_SOUTH, Key::BTN_EAST].into_iter().collect();
+
+        assert_eq!(
+            classify(&direct, Some(&touch), Some(&mt)),
+            Some(DirectKind::Touchscreen)
+        );
+        assert_eq!(
+            classify(&direct, Some(&pen), Some(&xy)),
+            Some(DirectKind::Stylus)
+        );
+        assert_eq!(
+            classify(&pointer, Some(&pad), Some(&xy)),
+            Some(DirectKind::Gamepad)
+        );
+        // A touchpad has slots but isn't a direct device.
+        assert_eq!(classify(&pointer, Some(&touch), Some(&mt)), None);
+    }
+
+    #[test]
+    fn test_device_area_maps_direct_devices_to_one_output() {
+        let monitors = [monitor("HDMI-1", 0, true), monitor("eDP-1", 1920, false)];
+        let half = |x| DesktopArea {
+            x,
+            y: 0.0,
+            w: 0.5,
+            h: 1.0,
+        };
+
+        assert_eq!(device_area(true, true, &monitors), half(0.5));
+        assert_eq!(device_area(true, false, &monitors), half(0.0));
+        // Tablets without a screen, and single-monitor setups, span the desktop.
+        assert_eq!(device_area(false, false, &monitors), DesktopArea::FULL);
+        assert_eq!(device_area(true, true, &monitors[..1]), DesktopArea::FULL);
+    }
+
+    #[test]
+    fn test_touch_tracker_emits_down_move_up() {
+        let mut tracker = TouchTracker::new(RANGE, RANGE, Some(RANGE), DesktopArea::FULL);
+        let mut out = VecDeque::new();
+        for event in [
+            abs(AbsoluteAxisType::ABS_MT_SLOT, 0),
+            abs(AbsoluteAxisType::ABS_MT_TRACKING_ID, 42),
+            abs(AbsoluteAxisType::ABS_MT_POSITION_X, 250),
+            abs(AbsoluteAxisType::ABS_MT_POSITION_Y, 500),
+            abs(AbsoluteAxisType::ABS_MT_PRESSURE, 800),
+            syn(),
+            abs(AbsoluteAxisType::ABS_MT_POSITION_X, 300),
+            syn(),
+            abs(AbsoluteAxisType::ABS_MT_TRACKING_ID, -1),
+            syn(),
+        ] {
+            tracker.handle(&event, &mut out);
+        }
+
+        let phases: Vec<_> = out
+            .iter()
+            .map(|event| match event.kind {
+                EventKind::Touch { id, phase, .. } => (id, phase),
+                _ => panic!("expected touch"),
+            })
+            .collect();
+        assert_eq!(
+            phases,
+            vec![
+                (42, TouchPhase::Down),
+                (42, TouchPhase::Move),
+                (42, TouchPhase::Up)
+            ]
+        );
+        assert_eq!(
+            out[1].kind,
+            EventKind::Touch {
+                id: 42,
+                phase: TouchPhase::Move,
+                x: 0.3,
+                y: 0.5,
+                pressure: Some(0.8),
+            }
+        );
+    }
+
+    #[test]
+    fn test_touch_on_the_right_output_lands_on_the_right_half() {
+        let area = DesktopArea {
+            x: 0.5,
+            y: 0.0,
+            w: 0.5,
+            h: 1.0,
+        };
+        let mut tracker = TouchTracker::new(RANGE, RANGE, None, area);
+        let mut out = VecDeque::new();
+        for event in [
+            abs(AbsoluteAxisType::ABS_MT_TRACKING_ID, 1),
+            abs(AbsoluteAxisType::ABS_MT_POSITION_X, 500),
+            abs(AbsoluteAxisType::ABS_MT_POSITION_Y, 250),
+            syn(),
+        ] {
+            tracker.handle(&event, &mut out);
+        }
+
+        assert!(matches!(
+            out[0].kind,
+            EventKind::Touch { x, y, .. } if x == 0.75 && y == 0.25
+        ));
+    }
+
+    #[test]
+    fn test_gamepad_tracker_buttons_hat_and_axes() {
+        let stick = AxisRange {
+            min: -32768,
+            max: 32767,
+        };
+        let mut tracker = GamepadTracker::new(
+            0,
+            vec![(AbsoluteAxisType::ABS_X, GamepadAxis::LeftStickX, stick)],
+        );
+        let mut out = VecDeque::new();
+        for event in [
+            key(Key::BTN_SOUTH, 1),
+            key(Key::BTN_SOUTH, 0),
+            abs(AbsoluteAxisType::ABS_HAT0X, 1),
+            abs(AbsoluteAxisType::ABS_HAT0X, -1),
+            abs(AbsoluteAxisType::ABS_HAT0X, 0),
+            // Inside the deadzone: no event.
+            abs(AbsoluteAxisType::ABS_X, 1000),
+            abs(AbsoluteAxisType::ABS_X, 32767),
+            abs(AbsoluteAxisType::ABS_X, 0),
+        ] {
+            tracker.handle(&event, &mut out);
+        }
+
+        let kinds: Vec<_> = out.iter().map(|event| event.kind.clone()).collect();
+        let button = |button, state| EventKind::GamepadButton {
+            pad: 0,
+            button,
+            state,
+        };
+        assert_eq!(
+            kinds[..6],
+            [
+                button(GamepadButton::South, ButtonState::Down),
+                button(GamepadButton::South, ButtonState::Up),
+                button(GamepadButton::DpadRight, ButtonState::Down),
+                button(GamepadButton::DpadRight, ButtonState::Up),
+                button(GamepadButton::DpadLeft, ButtonState::Down),
+                button(GamepadButton::DpadLeft, ButtonState::Up),
+            ]
+        );
+        assert_eq!(kinds.len(), 8);
+        assert!(matches!(kinds[6], EventKind::GamepadAxis { value, .. } if value == 1.0));
+        assert!(matches!(kinds[7], EventKind::GamepadAxis { value, .. } if value == 0.0));
+    }
+
+    #[test]
+    fn test_stylus_tracker_ignores_hover_and_tracks_eraser() {
+        let mut tracker = StylusTracker::new(RANGE, RANGE, Some(RANGE), DesktopArea::FULL);
+        let mut out = VecDeque::new();
+        for event in [
+            key(Key::BTN_TOOL_RUBBER, 1),
+            abs(AbsoluteAxisType::ABS_X, 100),
+            syn(),
+            key(Key::BTN_TOUCH, 1),
+            abs(AbsoluteAxisType::ABS_PRESSURE, 500),
+            syn(),
+            key(Key::BTN_TOUCH, 0),
+            syn(),
+        ] {
+            tracker.handle(&event, &mut out);
+        }
+
+        assert_eq!(out.len(), 2);
+        assert!(matches!(
+            out[0].kind,
+            EventKind::Stylus {
+                tool: StylusTool::Eraser,
+                phase: TouchPhase::Down,
+                ..
+            }
+        ));
+        assert!(matches!(
+            out[1].kind,
+            EventKind::Stylus {
+                phase: TouchPhase::Up,
+                ..
+            }
+        ));
+    }
+}
@@ -3,7 +3,7 @@
 use grabme_common::error::GrabmeResult;
 use grabme_project_model::event::{InputEvent, PointerCoordinateSpace};
 
-use crate::InputBackend;
+use crate::{InputBackend, InputOptions};
 
 pub struct StubBackend {
     events: Vec<InputEvent>,
@@ -48,6 +48,13 @@ impl InputBackend for StubBackend {
 }
 
 pub fn detect_best_backend() -> Box<dyn InputBackend> {
+    detect_backend_with(InputOptions::default())
+}
+
+pub fn detect_backend_with(options: InputOptions) -> Box<dyn InputBackend> {
+    if options.gamepad {
+        tracing::warn!("Gamepad capture is only implemented on Linux");
+    }
     tracing::warn!(
         "Input capture backends for this platform are not implemented yet; using stub backend"
     );
@@ -0,0 +1,323 @@
+//! Synthetic input for replaying scripts.
+//!
+//! An [`InputInjector`] moves the pointer, presses buttons and types on
+//! the live desktop. X11 goes through `xdotool`; Wayland compositors don't
+//! let clients inject input, so `ydotool` writes to a uinput device through
+//! its `ydotoold` daemon instead. Other backends only need to implement the
+//! trait.
+
+use std::process::Command;
+
+use grabme_common::error::{GrabmeError, GrabmeResult};
+use grabme_platform_core::coords::PhysicalPx;
+use grabme_project_model::event::{ButtonState, MouseButton};
+
+/// Sends synthetic input to the desktop.
+pub trait InputInjector: Send {
+    /// Backend name for logging.
+    fn name(&self) -> &str;
+
+    /// Move the pointer to a virtual-desktop position.
+    fn move_to(&mut self, point: PhysicalPx) -> GrabmeResult<()>;
+
+    /// Press or release a mouse button.
+    fn button(&mut self, button: MouseButton, state: ButtonState) -> GrabmeResult<()>;
+
+    /// Scroll by wheel steps, positive right and down.
+    fn scroll(&mut self, dx: i32, dy: i32) -> GrabmeResult<()>;
+
+    /// Press and release a key combination such as `ctrl+s` or `Return`.
+    fn key(&mut self, combo: &str) -> GrabmeResult<()>;
+
+    /// Type `text`, waiting `delay_ms` between characters.
+    fn type_text(&mut self, text: &str, delay_ms: u32) -> GrabmeResult<()>;
+}
+
+/// The injector for the current display: `xdotool` on X11 (or an
+/// overridden X11 display), `ydotool` on Wayland.
+#[cfg(target_os = "linux")]
+pub fn detect_injector() -> GrabmeResult<Box<dyn InputInjector>> {
+    use grabme_platform_linux::{detect_display_server, DisplayServer};
+
+    if detect_display_server() == DisplayServer::Wayland {
+        if Command::new("ydotool").arg("help").output().is_err() {
+            return Err(GrabmeError::unsupported(
+                "Replaying input on Wayland needs ydotool with ydotoold running",
+            ));
+        }
+        Ok(Box::new(YdotoolInjector))
+    } else {
+        if grabme_platform_linux::x11_command("xdotool")
+            .arg("--version")
+            .output()
+            .is_err()
+        {
+            return Err(GrabmeError::unsupported(
+                "Replaying input on X11 needs xdotool. Install with: sudo apt install xdotool",
+            ));
+        }
+        Ok(Box::new(XdotoolInjector))
+    }
+}
+
+/// Input injection is only implemented for Linux.
+#[cfg(not(target_os = "linux"))]
+pub fn detect_injector() -> GrabmeResult<Box<dyn InputInjector>> {
+    Err(GrabmeError::unsupported(
+        "Replaying input scripts is only supported on Linux",
+    ))
+}
+
+/// Run `command`, failing with its stderr.
+fn run(mut command: Command) -> GrabmeResult<()> {
+    let program = command.get_program().to_string_lossy().to_string();
+    let output = command
+        .output()
+        .map_err(|e| GrabmeError::input_tracking(format!("Failed to run {program}: {e}")))?;
+    if !output.status.success() {
+        return Err(GrabmeError::input_tracking(format!(
+            "{program} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+/// X11 button number for `button`.
+fn x11_button(button: MouseButton) -> u16 {
+    match button {
+        MouseButton::Left => 1,
+        MouseButton::Middle => 2,
+        MouseButton::Right => 3,
+        MouseButton::Back => 8,
+        MouseButton::Forward => 9,
+        MouseButton::Other(code) => code,
+    }
+}
+
+/// Injects input with `xdotool`.
+#[cfg(target_os = "linux")]
+pub struct XdotoolInjector;
+
+#[cfg(target_os = "linux")]
+impl XdotoolInjector {
+    fn command(args: &[&str]) -> Command {
+        let mut command = grabme_platform_linux::x11_command("xdotool");
+        command.args(args);
+        command
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl InputInjector for XdotoolInjector {
+    fn name(&self) -> &str {
+        "xdotool"
+    }
+
+    fn move_to(&mut self, point: PhysicalPx) -> GrabmeResult<()> {
+        let (x, y) = (point.x.round().to_string(), point.y.round().to_string());
+        run(Self::command(&["mousemove", &x, &y]))
+    }
+
+    fn button(&mut self, button: MouseButton, state: ButtonState) -> GrabmeResult<()> {
+        let action = match state {
+            ButtonState::Down => "mousedown",
+            ButtonState::Up => "mouseup",
+        };
+        run(Self::command(&[action, &x11_button(button).to_string()]))
+    }
+
+    fn scroll(&mut self, dx: i32, dy: i32) -> GrabmeResult<()> {
+        // Buttons 4-7 are wheel up, down, left and right.
+        for (steps, negative, positive) in [(dy, "4", "5"), (dx, "6", "7")] {
+            if steps != 0 {
+                let button = if steps < 0 { negative } else { positive };
+                let repeat = steps.unsigned_abs().to_string();
+                run(Self::command(&["click", "--repeat", &repeat, button]))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn key(&mut self, combo: &str) -> GrabmeResult<()> {
+        run(Self::command(&["key", "--clearmodifiers", combo]))
+    }
+
+    fn type_text(&mut self, text: &str, delay_ms: u32) -> GrabmeResult<()> {
+        run(Self::command(&[
+            "type",
+            "--delay",
+            &delay_ms.to_string(),
+            "--",
+            text,
+        ]))
+    }
+}
+
+/// Injects input with `ydotool` (1.0 or later), which needs `ydotoold`
+/// running and access to `/dev/uinput`.
+pub struct YdotoolInjector;
+
+impl YdotoolInjector {
+    fn command(args: &[&str]) -> Command {
+        let mut command = Command::new("ydotool");
+        command.args(args);
+        command
+    }
+}
+
+impl InputInjector for YdotoolInjector {
+    fn name(&self) -> &str {
+        "ydotool"
+    }
+
+    fn move_to(&mut self, point: PhysicalPx) -> GrabmeResult<()> {
+        let (x, y) = (point.x.round().to_string(), point.y.round().to_string());
+        run(Self::command(&[
+            "mousemove",
+            "--absolute",
+            "-x",
+            &x,
+            "-y",
+            &y,
+        ]))
+    }
+
+    fn button(&mut self, button: MouseButton, state: ButtonState) -> GrabmeResult<()> {
+        let code: u16 = match button {
+            MouseButton::Left => 0x00,
+            MouseButton::Right => 0x01,
+            MouseButton::Middle => 0x02,
+            MouseButton::Back => 0x03,
+            MouseButton::Forward => 0x04,
+            MouseButton::Other(_) => {
+                return Err(GrabmeError::unsupported(format!(
+                    "ydotool can't press mouse button {button:?}"
+                )))
+            }
+        };
+        let flag = match state {
+            ButtonState::Down => 0x40,
+            ButtonState::Up => 0x80,
+        };
+        run(Self::command(&["click", &format!("{:#04x}", code | flag)]))
+    }
+
+    fn scroll(&mut self, dx: i32, dy: i32) -> GrabmeResult<()> {
+        // The wheel axis counts up as positive.
+        run(Self::command(&[
+            "mousemove",
+            "--wheel",
+            "-x",
+            &dx.to_string(),
+            "-y",
+            &(-dy).to_string(),
+        ]))
+    }
+
+    fn key(&mut self, combo: &str) -> GrabmeResult<()> {
+        let codes = combo
+            .split('+')
+            .map(|key| {
+                linux_keycode(key).ok_or_else(|| {
+                    GrabmeError::unsupported(format!("ydotool: unknown key {key:?} in {combo:?}"))
+                })
+            })
+            .collect::<GrabmeResult<Vec<u16>>>()?;
+        // Press in order, release in reverse.
+        let events: Vec<String> = codes
+            .iter()
+            .map(|code| format!("{code}:1"))
+            .chain(codes.iter().rev().map(|code| format!("{code}:0")))
+            .collect();
+        let mut command = Self::command(&["key"]);
+        command.args(&events);
+        run(command)
+    }
+
+    fn type_text(&mut self, text: &str, delay_ms: u32) -> GrabmeResult<()> {
+        run(Self::command(&[
+            "type",
+            "--key-delay",
+            &delay_ms.to_string(),
+            "--",
+            text,
+        ]))
+    }
+}
+
+/// Linux input keycode for an xdotool-style key name.
+fn linux_keycode(key: &str) -> Option<u16> {
+    const LETTERS: &[u8] = b"qwertyuiopasdfghjklzxcvbnm";
+    const LETTER_CODES: [u16; 26] = [
+        16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 30, 31, 32, 33, 34, 35, 36, 37, 38, 44, 45, 46, 47,
+        48, 49, 50,
+    ];
+
+    let lower = key.to_ascii_lowercase();
+    if let [c] = lower.as_bytes() {
+        if let Some(i) = LETTERS.iter().position(|l| l == c) {
+            return Some(LETTER_CODES[i]);
+        }
+        if c.is_ascii_digit() {
+            // 1-9 are codes 2-10, 0 is 11.
+            return Some(if *c == b'0' {
+                11
+            } else {
+                (c - b'0') as u16 + 1
+            });
+        }
+    }
+    if let Some(n) = lower.strip_prefix('f').and_then(|n| n.parse::<u16>().ok()) {
+        return match n {
+            1..=10 => Some(58 + n),
+            11 => Some(87),
+            12 => Some(88),
+            _ => None,
+        };
+    }
+    Some(match lower.as_str() {
+        "ctrl" | "control" | "control_l" => 29,
+        "shift" | "shift_l" => 42,
+        "alt" | "alt_l" => 56,
+        "super" | "super_l" | "meta" => 125,
+        "return" | "enter" => 28,
+        "escape" | "esc" => 1,
+        "tab" => 15,
+        "backspace" => 14,
+        "space" => 57,
+        "delete" => 111,
+        "home" => 102,
+        "end" => 107,
+        "page_up" | "prior" => 104,
+        "page_down" | "next" => 109,
+        "left" => 105,
+        "right" => 106,
+        "up" => 103,
+        "down" => 108,
+        "minus" => 12,
+        "equal" => 13,
+        "comma" => 51,
+        "period" => 52,
+        "slash" => 53,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_linux_keycodes_for_common_keys() {
+        assert_eq!(linux_keycode("ctrl"), Some(29));
+        assert_eq!(linux_keycode("S"), Some(31));
+        assert_eq!(linux_keycode("1"), Some(2));
+        assert_eq!(linux_keycode("0"), Some(11));
+        assert_eq!(linux_keycode("F5"), Some(63));
+        assert_eq!(linux_keycode("F12"), Some(88));
+        assert_eq!(linux_keycode("Return"), Some(28));
+        assert_eq!(linux_keycode("XF86AudioMute"), None);
+        assert_eq!(x11_button(MouseButton::Forward), 9);
+    }
+}
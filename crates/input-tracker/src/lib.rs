@@ -8,18 +8,40 @@
 //! - **Evdev:** Direct device access (requires privileges)
 //! - **X11:** XInput2 (legacy)
 //!
-//! Events are written in append-only JSONL format for crash safety.
+//! Events are written in append-only JSONL format for crash safety, on a
+//! writer thread fed through a bounded queue (see [`queue`]).
+//!
+//! The reverse direction lives here too: [`script`] replays scripted input
+//! through an [`inject::InputInjector`] for reproducible demos.
 
 pub mod backends;
+pub mod inject;
+pub mod queue;
+pub mod script;
 pub mod writer;
 
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
 
 use grabme_common::clock::RecordingClock;
-use grabme_common::error::GrabmeResult;
-use grabme_project_model::event::{EventStreamHeader, InputEvent, PointerCoordinateSpace};
+use grabme_common::error::{GrabmeError, GrabmeResult};
+use grabme_project_model::event::{
+    EventKind, EventStreamHeader, InputEvent, PointerCoordinateSpace, PointerLayout,
+    EVENT_SCHEMA_VERSION,
+};
+use grabme_project_model::project::RecordingConfig;
+
+use queue::EventQueue;
+pub use queue::{InputStats, EVENT_QUEUE_CAPACITY};
+
+/// Optional input sources, off unless asked for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InputOptions {
+    /// Record gamepad buttons and sticks.
+    pub gamepad: bool,
+}
 
 /// Trait for input tracking backends.
 pub trait InputBackend: Send {
@@ -38,14 +60,70 @@ pub trait InputBackend: Send {
     }
 }
 
+/// Drops reaction markers into a running tracker's event stream.
+#[derive(Debug, Clone)]
+pub struct ReactionSender(Sender<String>);
+
+impl ReactionSender {
+    /// Queue a reaction at the current pointer position. Returns `false`
+    /// once the tracker has stopped.
+    pub fn send(&self, emoji: impl Into<String>) -> bool {
+        self.0.send(emoji.into()).is_ok()
+    }
+}
+
+/// Adds events from outside the input backend, such as desktop appearance
+/// changes, to a running tracker's event stream. Events are timestamped
+/// when the tracker writes them.
+#[derive(Debug, Clone)]
+pub struct EventSender(Sender<EventKind>);
+
+impl EventSender {
+    /// Queue an event. Returns `false` once the tracker has stopped.
+    pub fn send(&self, kind: EventKind) -> bool {
+        self.0.send(kind).is_ok()
+    }
+}
+
+/// Latest pointer position of a running tracker, for live consumers such
+/// as a virtual camera following the cursor. Normalized against the
+/// captured region when the monitor layout is known.
+#[derive(Debug, Clone, Default)]
+pub struct PointerWatch(Arc<Mutex<Option<(f64, f64)>>>);
+
+impl PointerWatch {
+    /// Last position seen, `None` before the pointer first moves.
+    pub fn latest(&self) -> Option<(f64, f64)> {
+        *self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn set(&self, position: (f64, f64)) {
+        *self.0.lock().unwrap_or_else(|e| e.into_inner()) = Some(position);
+    }
+}
+
 /// The input tracker that coordinates a backend with event writing.
 pub struct InputTracker {
     backend: Box<dyn InputBackend>,
-    writer: writer::EventWriter,
+    /// Handed to the writer thread when tracking starts.
+    writer: Option<writer::EventWriter>,
     #[allow(dead_code)] // Used for future drift detection
     clock: RecordingClock,
     stop_flag: Arc<AtomicBool>,
-    events_logged: u64,
+    stats: InputStats,
+    reactions_tx: Sender<String>,
+    reactions_rx: Receiver<String>,
+    external_tx: Sender<EventKind>,
+    external_rx: Receiver<EventKind>,
+    /// Last pointer position seen, where reactions are placed.
+    last_pointer: (f64, f64),
+    /// Space the backend reports pointer values in.
+    pointer_coordinate_space: PointerCoordinateSpace,
+    /// Monitor layout pointer events are resolved against, if known.
+    pointer_layout: Option<PointerLayout>,
+    pointer_watch: PointerWatch,
+    /// Events the writer may fall behind by.
+    queue_capacity: usize,
 }
 
 impl InputTracker {
@@ -61,8 +139,8 @@ impl InputTracker {
     ) -> GrabmeResult<Self> {
         let pointer_coordinate_space = backend.pointer_coordinate_space();
         let header = EventStreamHeader {
-            schema_version: "1.0".to_string(),
-            epoch_monotonic_ns: 0,
+            schema_version: EVENT_SCHEMA_VERSION.to_string(),
+            epoch_monotonic_ns: clock.epoch_monotonic_ns(),
             epoch_wall: clock.epoch_wall().to_string(),
             capture_width,
             capture_height,
@@ -72,27 +150,121 @@ impl InputTracker {
         };
 
         let writer = writer::EventWriter::new(output_path, header)?;
+        let (reactions_tx, reactions_rx) = mpsc::channel();
+        let (external_tx, external_rx) = mpsc::channel();
 
         Ok(Self {
             backend,
-            writer,
+            writer: Some(writer),
             clock,
             stop_flag: Arc::new(AtomicBool::new(false)),
-            events_logged: 0,
+            stats: InputStats::default(),
+            reactions_tx,
+            reactions_rx,
+            external_tx,
+            external_rx,
+            last_pointer: (0.5, 0.5),
+            pointer_coordinate_space,
+            pointer_layout: None,
+            pointer_watch: PointerWatch::default(),
+            queue_capacity: EVENT_QUEUE_CAPACITY,
         })
     }
 
+    /// Resolve pointer events against the monitor layout of `recording`,
+    /// tagging each with the monitor under it and its capture-normalized
+    /// position. Only valid when the recorded monitor is what is captured.
+    pub fn set_monitor_layout(&mut self, recording: &RecordingConfig) {
+        self.pointer_layout = recording.pointer_layout(self.pointer_coordinate_space);
+        if self.pointer_layout.is_none() {
+            tracing::debug!("No usable monitor layout; pointer events carry no hits");
+        }
+    }
+
+    /// Let the writer fall behind by at most `capacity` events instead of
+    /// [`EVENT_QUEUE_CAPACITY`], to bound memory.
+    pub fn set_queue_capacity(&mut self, capacity: usize) {
+        self.queue_capacity = capacity.max(1);
+    }
+
+    /// Queue `event` for writing, resolving its pointer hit first.
+    async fn write(&mut self, queue: &mut EventQueue, mut event: InputEvent) -> GrabmeResult<()> {
+        if let (Some(layout), Some((x, y))) = (&self.pointer_layout, event.hit_position()) {
+            event.hit = layout.hit(x, y);
+        }
+        if let Some(position) = event.pointer_position() {
+            self.pointer_watch.set(
+                event
+                    .hit
+                    .map(|hit| (hit.capture_x, hit.capture_y))
+                    .unwrap_or(position),
+            );
+        }
+        queue.push(event).await
+    }
+
     /// Run the tracking loop until the stop flag is set.
-    pub async fn run(&mut self) -> GrabmeResult<u64> {
+    pub async fn run(&mut self) -> GrabmeResult<InputStats> {
+        let writer = self
+            .writer
+            .take()
+            .ok_or_else(|| GrabmeError::capture("Input tracker already ran"))?;
+        let (mut queue, rx) = EventQueue::new(self.queue_capacity);
+        let writer_task = queue::spawn_writer(writer, rx);
         tracing::info!(backend = %self.backend.name(), "Input tracker started");
 
+        let polled = self.poll_loop(&mut queue).await;
+        // Close the queue even after an error so the writer flushes what it has.
+        let queued = match polled {
+            Ok(()) => queue.finish().await,
+            Err(e) => {
+                drop(queue);
+                Err(e)
+            }
+        };
+        let written = writer_task
+            .await
+            .map_err(|e| GrabmeError::capture(format!("Event writer panicked: {e}")))??;
+        let mut stats = queued?;
+        stats.events_written = written;
+        self.stats = stats;
+
+        if stats.had_backpressure() {
+            tracing::warn!(
+                coalesced = stats.pointer_coalesced,
+                waits = stats.backpressure_waits,
+                peak_queue_depth = stats.peak_queue_depth,
+                "Event writer fell behind input"
+            );
+        }
+        tracing::info!(events = stats.events_written, "Input tracker stopped");
+        Ok(stats)
+    }
+
+    async fn poll_loop(&mut self, queue: &mut EventQueue) -> GrabmeResult<()> {
         while !self.stop_flag.load(Ordering::Relaxed) {
+            while let Ok(emoji) = self.reactions_rx.try_recv() {
+                let (x, y) = self.last_pointer;
+                let event = InputEvent::reaction(self.clock.elapsed_ns(), emoji, x, y);
+                self.write(queue, event).await?;
+            }
+            while let Ok(kind) = self.external_rx.try_recv() {
+                let event = InputEvent {
+                    timestamp_ns: self.clock.elapsed_ns(),
+                    kind,
+                    hit: None,
+                };
+                self.write(queue, event).await?;
+            }
+
             match self.backend.poll() {
                 Ok(Some(event)) => {
                     let mut event = event;
                     event.timestamp_ns = self.clock.elapsed_ns();
-                    self.writer.write_event(&event)?;
-                    self.events_logged += 1;
+                    if let Some(position) = event.pointer_position() {
+                        self.last_pointer = position;
+                    }
+                    self.write(queue, event).await?;
                 }
                 Ok(None) => {
                     // No event available, yield briefly
@@ -103,10 +275,7 @@ impl InputTracker {
                 }
             }
         }
-
-        self.writer.flush()?;
-        tracing::info!(events = self.events_logged, "Input tracker stopped");
-        Ok(self.events_logged)
+        Ok(())
     }
 
     /// Set the stop flag.
@@ -119,8 +288,29 @@ impl InputTracker {
         self.stop_flag.clone()
     }
 
-    /// Number of events logged so far.
+    /// Sender for reaction markers, usable while the tracker runs.
+    pub fn reaction_sender(&self) -> ReactionSender {
+        ReactionSender(self.reactions_tx.clone())
+    }
+
+    /// Sender for events from outside the backend, usable while the
+    /// tracker runs.
+    pub fn event_sender(&self) -> EventSender {
+        EventSender(self.external_tx.clone())
+    }
+
+    /// Watch on the latest pointer position, usable while the tracker runs.
+    pub fn pointer_watch(&self) -> PointerWatch {
+        self.pointer_watch.clone()
+    }
+
+    /// Number of events written, once [`InputTracker::run`] returns.
     pub fn events_logged(&self) -> u64 {
-        self.events_logged
+        self.stats.events_written
+    }
+
+    /// How the event stream kept up, once [`InputTracker::run`] returns.
+    pub fn stats(&self) -> InputStats {
+        self.stats
     }
 }
@@ -0,0 +1,195 @@
+//! Bounded hand-off between the backend poll loop and the event writer.
+//!
+//! A 1000 Hz mouse plus key repeat can produce events faster than the
+//! writer serialises and syncs them. Events go through a bounded channel
+//! to a writer thread so disk stalls don't hold up polling. When the
+//! channel is full, pointer moves are coalesced: only the newest waiting
+//! position is kept. Every other event, clicks and keys included, waits for
+//! room instead of being dropped.
+
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::mpsc::{self, Receiver, Sender};
+
+use grabme_common::error::{GrabmeError, GrabmeResult};
+use grabme_project_model::event::{EventKind, InputEvent};
+
+use crate::writer::EventWriter;
+
+/// Events the writer can fall behind by before backpressure kicks in.
+pub const EVENT_QUEUE_CAPACITY: usize = 4096;
+
+/// How the event stream kept up during a recording.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InputStats {
+    /// Events written to `events.jsonl`.
+    pub events_written: u64,
+
+    /// Pointer moves replaced by a newer position while the writer was
+    /// behind.
+    pub pointer_coalesced: u64,
+
+    /// Times the poll loop waited for room to queue an event.
+    pub backpressure_waits: u64,
+
+    /// Most events waiting for the writer at once.
+    pub peak_queue_depth: usize,
+}
+
+impl InputStats {
+    /// Whether the writer ever fell behind.
+    pub fn had_backpressure(&self) -> bool {
+        self.pointer_coalesced > 0 || self.backpressure_waits > 0
+    }
+}
+
+/// Producer side of the queue, owned by the poll loop.
+pub(crate) struct EventQueue {
+    tx: Sender<InputEvent>,
+    capacity: usize,
+    /// Newest pointer move that didn't fit, sent once there is room.
+    pending_pointer: Option<InputEvent>,
+    stats: InputStats,
+}
+
+impl EventQueue {
+    pub(crate) fn new(capacity: usize) -> (Self, Receiver<InputEvent>) {
+        let (tx, rx) = mpsc::channel(capacity);
+        let queue = Self {
+            tx,
+            capacity,
+            pending_pointer: None,
+            stats: InputStats::default(),
+        };
+        (queue, rx)
+    }
+
+    /// Queue `event`, coalescing pointer moves while the writer is behind
+    /// and waiting for room for anything else.
+    pub(crate) async fn push(&mut self, event: InputEvent) -> GrabmeResult<()> {
+        if let Some(pending) = self.pending_pointer.take() {
+            self.pending_pointer = self.try_send(pending)?;
+        }
+
+        if matches!(event.kind, EventKind::Pointer { .. }) {
+            if self.pending_pointer.is_some() {
+                self.pending_pointer = Some(event);
+                self.stats.pointer_coalesced += 1;
+            } else {
+                self.pending_pointer = self.try_send(event)?;
+            }
+        } else {
+            // Keep the stream in order: the waiting move happened first.
+            if let Some(pending) = self.pending_pointer.take() {
+                self.send(pending).await?;
+            }
+            if let Some(event) = self.try_send(event)? {
+                self.send(event).await?;
+            }
+        }
+
+        let depth = self.capacity - self.tx.capacity();
+        self.stats.peak_queue_depth = self.stats.peak_queue_depth.max(depth);
+        Ok(())
+    }
+
+    /// Send any waiting pointer move and close the queue, returning the
+    /// producer-side counters.
+    pub(crate) async fn finish(mut self) -> GrabmeResult<InputStats> {
+        if let Some(pending) = self.pending_pointer.take() {
+            self.send(pending).await?;
+        }
+        Ok(self.stats)
+    }
+
+    /// Queue without waiting; gives the event back when the queue is full.
+    fn try_send(&self, event: InputEvent) -> GrabmeResult<Option<InputEvent>> {
+        match self.tx.try_send(event) {
+            Ok(()) => Ok(None),
+            Err(TrySendError::Full(event)) => Ok(Some(event)),
+            Err(TrySendError::Closed(_)) => Err(writer_stopped()),
+        }
+    }
+
+    async fn send(&mut self, event: InputEvent) -> GrabmeResult<()> {
+        self.stats.backpressure_waits += 1;
+        self.tx.send(event).await.map_err(|_| writer_stopped())
+    }
+}
+
+fn writer_stopped() -> GrabmeError {
+    GrabmeError::capture("Event writer stopped")
+}
+
+/// Write queued events on a blocking thread until the queue closes.
+/// Returns how many events were written.
+pub(crate) fn spawn_writer(
+    mut writer: EventWriter,
+    mut rx: Receiver<InputEvent>,
+) -> tokio::task::JoinHandle<GrabmeResult<u64>> {
+    tokio::task::spawn_blocking(move || {
+        while let Some(event) = rx.blocking_recv() {
+            writer.write_event(&event)?;
+        }
+        writer.flush()?;
+        Ok(writer.events_written())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use grabme_project_model::event::{ButtonState, MouseButton};
+
+    fn drain(rx: &mut Receiver<InputEvent>) -> Vec<InputEvent> {
+        let mut events = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            events.push(event);
+        }
+        events
+    }
+
+    #[tokio::test]
+    async fn test_full_queue_coalesces_pointer_moves() {
+        let (mut queue, mut rx) = EventQueue::new(2);
+        for t in 0..5 {
+            queue
+                .push(InputEvent::pointer(t, 0.1 * t as f64, 0.5))
+                .await
+                .unwrap();
+        }
+        // Two fit, the third waits and is replaced by the fourth and fifth.
+        assert_eq!(queue.stats.pointer_coalesced, 2);
+        assert_eq!(queue.stats.peak_queue_depth, 2);
+
+        assert_eq!(drain(&mut rx).len(), 2);
+        let stats = queue.finish().await.unwrap();
+        let last = drain(&mut rx);
+        assert_eq!(last, vec![InputEvent::pointer(4, 0.4, 0.5)]);
+        assert_eq!(stats.backpressure_waits, 1);
+    }
+
+    #[tokio::test]
+    async fn test_clicks_wait_for_room_after_the_pending_move() {
+        let (mut queue, mut rx) = EventQueue::new(1);
+        queue.push(InputEvent::pointer(0, 0.1, 0.1)).await.unwrap();
+        queue.push(InputEvent::pointer(1, 0.2, 0.2)).await.unwrap();
+
+        let reader = tokio::spawn(async move {
+            let mut events = Vec::new();
+            while let Some(event) = rx.recv().await {
+                events.push(event);
+            }
+            events
+        });
+        let click = InputEvent::click(2, MouseButton::Left, ButtonState::Down, 0.2, 0.2);
+        queue.push(click.clone()).await.unwrap();
+        let stats = queue.finish().await.unwrap();
+
+        let events = reader.await.unwrap();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[1], InputEvent::pointer(1, 0.2, 0.2));
+        assert_eq!(events[2], click);
+        assert_eq!(stats.pointer_coalesced, 0);
+        assert!(stats.had_backpressure());
+    }
+}
@@ -0,0 +1,989 @@
+//! Input scripts for reproducible demos.
+//!
+//! A script is a YAML list of steps run one after another: pointer moves,
+//! clicks, drags, scrolls, key combinations, typed text and waits.
+//! Positions are pixels on the recorded monitor, so a script replays the
+//! same on any monitor of that size and is scaled onto one of another
+//! size. [`InputScript::from_events`] turns a recorded take into a script
+//! that can be edited and replayed.
+//!
+//! ```yaml
+//! screen: { width: 1920, height: 1080 }
+//! steps:
+//!   - { action: move, x: 640, y: 360, duration_secs: 0.6 }
+//!   - { action: click }
+//!   - { action: type, text: hello world }
+//!   - { action: key, keys: ctrl+s }
+//!   - { action: wait, secs: 1.5 }
+//! ```
+
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use grabme_common::error::{GrabmeError, GrabmeResult};
+use grabme_platform_core::coords::{CaptureNorm, PhysicalPx, PhysicalRect};
+use grabme_project_model::event::{
+    ButtonState, EventKind, InputEvent, MouseButton, PointerLayout, ScrollSource,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::inject::InputInjector;
+
+/// Script format version written by this build.
+pub const SCRIPT_VERSION: u32 = 1;
+
+/// Delay between typed characters when a step doesn't set one.
+pub const DEFAULT_TYPE_DELAY_MS: u32 = 40;
+
+/// Interval between pointer positions sent during a gliding move.
+const MOVE_INTERVAL: Duration = Duration::from_millis(16);
+
+/// Longest sleep between checks of the stop flag.
+const STOP_POLL: Duration = Duration::from_millis(50);
+
+/// Pauses shorter than this are folded into the next step when converting.
+const MIN_WAIT_SECS: f64 = 0.05;
+
+/// A pointer still for this long ends a move when converting.
+const PAUSE_SECS: f64 = 0.3;
+
+/// Pointer path points closer than this to the simplified path are dropped.
+const PATH_TOLERANCE_PX: f64 = 4.0;
+
+/// A press and release within this time and distance convert to a click.
+const CLICK_MAX_SECS: f64 = 0.3;
+const CLICK_MAX_TRAVEL_PX: i32 = 4;
+
+/// A second click this soon after the first, in place, makes a double click.
+const MULTI_CLICK_SECS: f64 = 0.4;
+
+/// Scrolls this close together convert to one step.
+const SCROLL_MERGE_SECS: f64 = 0.2;
+
+/// Pixels of precise (touchpad) scrolling per wheel step.
+const PRECISE_SCROLL_PX_PER_STEP: f64 = 50.0;
+
+/// Keys typed this close together convert to one `type` step.
+const TYPE_MERGE_SECS: f64 = 1.0;
+
+/// A sequence of input steps to replay.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InputScript {
+    /// Format version, for forward compatibility.
+    #[serde(default = "default_version")]
+    pub version: u32,
+
+    /// Size of the screen positions are given on, in pixels.
+    pub screen: ScriptScreen,
+
+    /// Pause after recording starts, before the first step.
+    #[serde(default = "default_pause_secs")]
+    pub lead_in_secs: f64,
+
+    /// Pause after the last step, before recording stops.
+    #[serde(default = "default_pause_secs")]
+    pub tail_secs: f64,
+
+    pub steps: Vec<ScriptStep>,
+}
+
+/// Screen size script positions are relative to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScriptScreen {
+    pub width: u32,
+    pub height: u32,
+}
+
+/// One scripted action.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum ScriptStep {
+    /// Do nothing for `secs`.
+    Wait { secs: f64 },
+
+    /// Move the pointer to `x`, `y`, gliding there over `duration_secs`.
+    Move {
+        x: i32,
+        y: i32,
+        #[serde(default, skip_serializing_if = "is_zero")]
+        duration_secs: f64,
+    },
+
+    /// Click `count` times, at `x`, `y` when given.
+    Click {
+        #[serde(default = "left_button", skip_serializing_if = "is_left")]
+        button: MouseButton,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        x: Option<i32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        y: Option<i32>,
+        #[serde(default = "one", skip_serializing_if = "is_one")]
+        count: u32,
+    },
+
+    /// Hold a button down, e.g. to start a drag.
+    Press {
+        #[serde(default = "left_button", skip_serializing_if = "is_left")]
+        button: MouseButton,
+    },
+
+    /// Let go of a held button.
+    Release {
+        #[serde(default = "left_button", skip_serializing_if = "is_left")]
+        button: MouseButton,
+    },
+
+    /// Scroll by wheel steps, positive right and down.
+    Scroll {
+        #[serde(default, skip_serializing_if = "is_zero_steps")]
+        dx: i32,
+        #[serde(default, skip_serializing_if = "is_zero_steps")]
+        dy: i32,
+    },
+
+    /// Press and release a key combination in xdotool syntax, e.g. `ctrl+s`.
+    Key { keys: String },
+
+    /// Type `text`, waiting `delay_ms` between characters.
+    Type {
+        text: String,
+        #[serde(default = "default_type_delay")]
+        delay_ms: u32,
+    },
+}
+
+fn default_version() -> u32 {
+    SCRIPT_VERSION
+}
+
+fn default_pause_secs() -> f64 {
+    1.0
+}
+
+fn default_type_delay() -> u32 {
+    DEFAULT_TYPE_DELAY_MS
+}
+
+fn left_button() -> MouseButton {
+    MouseButton::Left
+}
+
+fn one() -> u32 {
+    1
+}
+
+fn is_zero(value: &f64) -> bool {
+    *value == 0.0
+}
+
+fn is_zero_steps(value: &i32) -> bool {
+    *value == 0
+}
+
+fn is_left(button: &MouseButton) -> bool {
+    *button == MouseButton::Left
+}
+
+fn is_one(value: &u32) -> bool {
+    *value == 1
+}
+
+impl InputScript {
+    /// Parse a YAML script.
+    pub fn from_yaml(yaml: &str) -> GrabmeResult<Self> {
+        let script: Self = serde_yaml::from_str(yaml)
+            .map_err(|e| GrabmeError::input_tracking(format!("Invalid input script: {e}")))?;
+        if script.version > SCRIPT_VERSION {
+            return Err(GrabmeError::unsupported(format!(
+                "Input script version {} is newer than this build supports ({SCRIPT_VERSION})",
+                script.version
+            )));
+        }
+        if script.screen.width == 0 || script.screen.height == 0 {
+            return Err(GrabmeError::input_tracking(
+                "Input script screen size must not be zero",
+            ));
+        }
+        Ok(script)
+    }
+
+    /// Read a YAML script from `path`.
+    pub fn load(path: &Path) -> GrabmeResult<Self> {
+        let yaml = std::fs::read_to_string(path).map_err(|e| {
+            GrabmeError::input_tracking(format!("Failed to read {}: {e}", path.display()))
+        })?;
+        Self::from_yaml(&yaml)
+    }
+
+    /// The script as YAML.
+    pub fn to_yaml(&self) -> GrabmeResult<String> {
+        serde_yaml::to_string(self)
+            .map_err(|e| GrabmeError::input_tracking(format!("Failed to write input script: {e}")))
+    }
+
+    /// Roughly how long a replay takes, counting typing at its delay.
+    pub fn duration_secs(&self) -> f64 {
+        let steps: f64 = self
+            .steps
+            .iter()
+            .map(|step| match step {
+                ScriptStep::Wait { secs } => secs.max(0.0),
+                ScriptStep::Move { duration_secs, .. } => duration_secs.max(0.0),
+                ScriptStep::Type { text, delay_ms } => {
+                    text.chars().count() as f64 * *delay_ms as f64 / 1000.0
+                }
+                _ => 0.0,
+            })
+            .sum();
+        self.lead_in_secs.max(0.0) + steps + self.tail_secs.max(0.0)
+    }
+
+    /// Convert a recorded take into a script on a `screen`-sized capture.
+    /// Pointer paths are simplified into a few gliding moves, quick presses
+    /// become clicks, and unmodified letters, digits and spaces become typed
+    /// text. Positions come from each event's resolved hit, else from
+    /// `layout`, else are taken as capture-normalized.
+    pub fn from_events(
+        events: &[InputEvent],
+        layout: Option<&PointerLayout>,
+        screen: ScriptScreen,
+    ) -> Self {
+        let mut converter = Converter {
+            steps: Vec::new(),
+            clock: events.first().map_or(0.0, InputEvent::timestamp_secs),
+            pos: None,
+            path: Vec::new(),
+            text: String::new(),
+            modifiers: Vec::new(),
+            consumed: vec![false; events.len()],
+        };
+        let to_px = |event: &InputEvent, x: f64, y: f64| {
+            let capture = event
+                .hit
+                .map(|hit| hit.capture())
+                .or_else(|| {
+                    layout
+                        .and_then(|layout| layout.hit(x, y))
+                        .map(|hit| hit.capture())
+                })
+                .unwrap_or(CaptureNorm::new(x, y));
+            (
+                (capture.x * screen.width as f64).round() as i32,
+                (capture.y * screen.height as f64).round() as i32,
+            )
+        };
+
+        for (idx, event) in events.iter().enumerate() {
+            let t = event.timestamp_secs();
+            match &event.kind {
+                EventKind::Pointer { x, y } => converter.pointer(t, to_px(event, *x, *y)),
+                EventKind::Click {
+                    button,
+                    state: ButtonState::Down,
+                    x,
+                    y,
+                } => {
+                    let at = to_px(event, *x, *y);
+                    let release = events[idx + 1..]
+                        .iter()
+                        .position(|later| {
+                            matches!(
+                                &later.kind,
+                                EventKind::Click { button: b, state: ButtonState::Up, .. } if b == button
+                            )
+                        })
+                        .map(|offset| idx + 1 + offset);
+                    let quick = release.filter(|&up| {
+                        let (ux, uy) = match &events[up].kind {
+                            EventKind::Click { x, y, .. } => to_px(&events[up], *x, *y),
+                            _ => at,
+                        };
+                        events[up].timestamp_secs() - t <= CLICK_MAX_SECS
+                            && (ux - at.0).abs().max((uy - at.1).abs()) <= CLICK_MAX_TRAVEL_PX
+                    });
+                    match quick {
+                        Some(up) => {
+                            converter.consumed[up] = true;
+                            converter.click(t, events[up].timestamp_secs(), *button, at);
+                        }
+                        None => {
+                            converter.begin(t);
+                            converter.place(at);
+                            converter.steps.push(ScriptStep::Press { button: *button });
+                        }
+                    }
+                }
+                EventKind::Click {
+                    button,
+                    state: ButtonState::Up,
+                    x,
+                    y,
+                } if !converter.consumed[idx] => {
+                    converter.begin(t);
+                    converter.place(to_px(event, *x, *y));
+                    converter
+                        .steps
+                        .push(ScriptStep::Release { button: *button });
+                }
+                EventKind::Scroll { dx, dy, source, .. } => {
+                    let scale = if source.is_precise() {
+                        1.0 / PRECISE_SCROLL_PX_PER_STEP
+                    } else {
+                        1.0
+                    };
+                    converter.scroll(t, dx * scale, dy * scale, *source);
+                }
+                EventKind::Key { code, state } => converter.key(t, code, *state),
+                _ => {}
+            }
+        }
+        converter.flush_path();
+        converter.flush_text();
+
+        Self {
+            version: SCRIPT_VERSION,
+            screen,
+            lead_in_secs: default_pause_secs(),
+            tail_secs: default_pause_secs(),
+            steps: converter.steps,
+        }
+    }
+
+    /// Replay the script through `injector`, with positions mapped onto
+    /// `target` (the recorded monitor, in virtual-desktop pixels). Stops
+    /// early, releasing held buttons, once `stop` is set.
+    pub fn play(
+        &self,
+        injector: &mut dyn InputInjector,
+        target: PhysicalRect,
+        stop: &AtomicBool,
+    ) -> GrabmeResult<()> {
+        let mut player = Player {
+            injector,
+            target,
+            screen: self.screen,
+            stop,
+            due: Instant::now(),
+            pos: None,
+            held: Vec::new(),
+        };
+        let result = player.run(self);
+        for button in std::mem::take(&mut player.held) {
+            let _ = player.injector.button(button, ButtonState::Up);
+        }
+        result
+    }
+}
+
+/// Builds script steps from a stream of recorded events.
+struct Converter {
+    steps: Vec<ScriptStep>,
+    /// Recording time the steps so far take the replay to.
+    clock: f64,
+    /// Pointer position after the steps so far.
+    pos: Option<(i32, i32)>,
+    /// Pointer samples not yet turned into moves.
+    path: Vec<(f64, (i32, i32))>,
+    /// Characters not yet turned into a `type` step.
+    text: String,
+    modifiers: Vec<&'static str>,
+    /// Releases already converted as part of a click.
+    consumed: Vec<bool>,
+}
+
+impl Converter {
+    /// Start a step at `t`: pending moves and text first, then a wait.
+    fn begin(&mut self, t: f64) {
+        self.flush_path();
+        self.flush_text();
+        self.wait_until(t);
+    }
+
+    fn wait_until(&mut self, t: f64) {
+        let gap = t - self.clock;
+        if gap >= MIN_WAIT_SECS {
+            self.steps.push(ScriptStep::Wait {
+                secs: round_secs(gap),
+            });
+        }
+        self.clock = self.clock.max(t);
+    }
+
+    /// Jump to `at` if the pointer isn't already there.
+    fn place(&mut self, at: (i32, i32)) {
+        if self.pos != Some(at) {
+            self.steps.push(ScriptStep::Move {
+                x: at.0,
+                y: at.1,
+                duration_secs: 0.0,
+            });
+            self.pos = Some(at);
+        }
+    }
+
+    fn pointer(&mut self, t: f64, at: (i32, i32)) {
+        self.flush_text();
+        if let Some(&(last_t, last)) = self.path.last() {
+            if last == at {
+                return;
+            }
+            if t - last_t > PAUSE_SECS {
+                self.flush_path();
+            }
+        } else if self.pos == Some(at) {
+            return;
+        }
+        self.path.push((t, at));
+    }
+
+    /// Turn the pending pointer samples into gliding moves.
+    fn flush_path(&mut self) {
+        let path = std::mem::take(&mut self.path);
+        let Some(&(start_t, start)) = path.first() else {
+            return;
+        };
+        let kept = simplify_path(&path);
+        self.wait_until(start_t);
+        self.place(start);
+        let mut prev_t = start_t;
+        for &(t, (x, y)) in kept.iter().skip(1) {
+            self.steps.push(ScriptStep::Move {
+                x,
+                y,
+                duration_secs: round_secs(t - prev_t),
+            });
+            prev_t = t;
+        }
+        self.clock = self.clock.max(prev_t);
+        self.pos = path.last().map(|&(_, at)| at);
+    }
+
+    fn flush_text(&mut self) {
+        if !self.text.is_empty() {
+            self.steps.push(ScriptStep::Type {
+                text: std::mem::take(&mut self.text),
+                delay_ms: DEFAULT_TYPE_DELAY_MS,
+            });
+        }
+    }
+
+    fn click(&mut self, t: f64, release_t: f64, button: MouseButton, at: (i32, i32)) {
+        self.flush_path();
+        self.flush_text();
+        // A second click in place soon after the first adds to it.
+        if self.pos == Some(at) && t - self.clock <= MULTI_CLICK_SECS {
+            if let Some(ScriptStep::Click {
+                button: previous,
+                count,
+                ..
+            }) = self.steps.last_mut()
+            {
+                if *previous == button {
+                    *count += 1;
+                    self.clock = release_t;
+                    return;
+                }
+            }
+        }
+        self.wait_until(t);
+        let moved = self.pos != Some(at);
+        self.steps.push(ScriptStep::Click {
+            button,
+            x: moved.then_some(at.0),
+            y: moved.then_some(at.1),
+            count: 1,
+        });
+        self.pos = Some(at);
+        self.clock = release_t;
+    }
+
+    fn scroll(&mut self, t: f64, dx: f64, dy: f64, source: ScrollSource) {
+        self.flush_path();
+        self.flush_text();
+        let (dx, dy) = if source.is_precise() {
+            (dx, dy)
+        } else {
+            (dx.signum() * dx.abs().ceil(), dy.signum() * dy.abs().ceil())
+        };
+        if t - self.clock <= SCROLL_MERGE_SECS {
+            if let Some(ScriptStep::Scroll { dx: sx, dy: sy }) = self.steps.last_mut() {
+                *sx += dx.round() as i32;
+                *sy += dy.round() as i32;
+                self.clock = t;
+                return;
+            }
+        }
+        let (dx, dy) = (dx.round() as i32, dy.round() as i32);
+        if dx == 0 && dy == 0 {
+            return;
+        }
+        self.wait_until(t);
+        self.steps.push(ScriptStep::Scroll { dx, dy });
+    }
+
+    fn key(&mut self, t: f64, code: &str, state: ButtonState) {
+        if let Some(modifier) = modifier_name(code) {
+            match state {
+                ButtonState::Down if !self.modifiers.contains(&modifier) => {
+                    self.modifiers.push(modifier)
+                }
+                ButtonState::Up => self.modifiers.retain(|held| *held != modifier),
+                _ => {}
+            }
+            return;
+        }
+        if state == ButtonState::Up {
+            return;
+        }
+
+        let shift_only = self.modifiers.iter().all(|held| *held == "shift");
+        let typed = typed_char(code, self.modifiers.contains(&"shift")).filter(|_| shift_only);
+        match typed {
+            Some(c) if !self.text.is_empty() && t - self.clock <= TYPE_MERGE_SECS => {
+                self.text.push(c);
+                self.clock = t;
+            }
+            Some(c) => {
+                self.begin(t);
+                self.text.push(c);
+            }
+            None => {
+                self.begin(t);
+                let key = key_name(code);
+                let keys: Vec<&str> = self
+                    .modifiers
+                    .iter()
+                    .copied()
+                    .chain([key.as_str()])
+                    .collect();
+                self.steps.push(ScriptStep::Key {
+                    keys: keys.join("+"),
+                });
+            }
+        }
+    }
+}
+
+/// Ramer-Douglas-Peucker simplification of a pointer path, keeping the
+/// ends and every point further than [`PATH_TOLERANCE_PX`] from the line
+/// through its neighbours.
+fn simplify_path(path: &[(f64, (i32, i32))]) -> Vec<(f64, (i32, i32))> {
+    if path.len() <= 2 {
+        return path.to_vec();
+    }
+    let (first, last) = (path[0].1, path[path.len() - 1].1);
+    let (fx, fy) = (first.0 as f64, first.1 as f64);
+    let (dx, dy) = (last.0 as f64 - fx, last.1 as f64 - fy);
+    let length = dx.hypot(dy);
+    let distance = |&(_, (x, y)): &(f64, (i32, i32))| {
+        let (px, py) = (x as f64 - fx, y as f64 - fy);
+        if length == 0.0 {
+            px.hypot(py)
+        } else {
+            (px * dy - py * dx).abs() / length
+        }
+    };
+    let (split, farthest) = path[1..path.len() - 1]
+        .iter()
+        .enumerate()
+        .map(|(i, point)| (i + 1, distance(point)))
+        .fold((0, 0.0), |best, candidate| {
+            if candidate.1 > best.1 {
+                candidate
+            } else {
+                best
+            }
+        });
+    if farthest <= PATH_TOLERANCE_PX {
+        return vec![path[0], path[path.len() - 1]];
+    }
+    let mut kept = simplify_path(&path[..=split]);
+    kept.pop();
+    kept.extend(simplify_path(&path[split..]));
+    kept
+}
+
+fn round_secs(secs: f64) -> f64 {
+    (secs.max(0.0) * 100.0).round() / 100.0
+}
+
+/// xdotool name of a modifier key code (`ControlLeft`, `ShiftRight`, ...).
+fn modifier_name(code: &str) -> Option<&'static str> {
+    match code.trim_end_matches("Left").trim_end_matches("Right") {
+        "Control" => Some("ctrl"),
+        "Shift" => Some("shift"),
+        "Alt" => Some("alt"),
+        "Meta" | "Super" | "OS" => Some("super"),
+        _ => None,
+    }
+}
+
+/// Character a key code types on its own, or with shift for letters.
+fn typed_char(code: &str, shift: bool) -> Option<char> {
+    if let Some(letter) = code.strip_prefix("Key").filter(|rest| rest.len() == 1) {
+        let c = letter.chars().next()?.to_ascii_lowercase();
+        return Some(if shift { c.to_ascii_uppercase() } else { c });
+    }
+    if shift {
+        return None;
+    }
+    match code {
+        "Space" => Some(' '),
+        "Minus" => Some('-'),
+        "Equal" => Some('='),
+        "Comma" => Some(','),
+        "Period" => Some('.'),
+        "Slash" => Some('/'),
+        _ => code
+            .strip_prefix("Digit")
+            .filter(|rest| rest.len() == 1)
+            .and_then(|digit| digit.chars().next()),
+    }
+}
+
+/// xdotool key name for a recorded key code.
+fn key_name(code: &str) -> String {
+    if let Some(rest) = code
+        .strip_prefix("Key")
+        .or_else(|| code.strip_prefix("Digit"))
+        .filter(|rest| rest.len() == 1)
+    {
+        // Uppercase letters would add shift.
+        return rest.to_ascii_lowercase();
+    }
+    match code {
+        "Enter" => "Return",
+        "Backspace" => "BackSpace",
+        "Space" => "space",
+        "ArrowLeft" => "Left",
+        "ArrowRight" => "Right",
+        "ArrowUp" => "Up",
+        "ArrowDown" => "Down",
+        "PageUp" => "Page_Up",
+        "PageDown" => "Page_Down",
+        "Minus" => "minus",
+        "Equal" => "equal",
+        "Comma" => "comma",
+        "Period" => "period",
+        "Slash" => "slash",
+        other => other,
+    }
+    .to_string()
+}
+
+/// Replays steps on a schedule, so slow injector calls don't add up.
+struct Player<'a> {
+    injector: &'a mut dyn InputInjector,
+    target: PhysicalRect,
+    screen: ScriptScreen,
+    stop: &'a AtomicBool,
+    /// When the next action is due.
+    due: Instant,
+    /// Pointer position after the last move, in desktop pixels.
+    pos: Option<PhysicalPx>,
+    held: Vec<MouseButton>,
+}
+
+impl Player<'_> {
+    fn run(&mut self, script: &InputScript) -> GrabmeResult<()> {
+        tracing::info!(
+            injector = self.injector.name(),
+            steps = script.steps.len(),
+            "Replaying input script"
+        );
+        if !self.wait(script.lead_in_secs) {
+            return Ok(());
+        }
+        for step in &script.steps {
+            if self.stop.load(Ordering::SeqCst) {
+                return Ok(());
+            }
+            match step {
+                ScriptStep::Wait { secs } => {
+                    if !self.wait(*secs) {
+                        return Ok(());
+                    }
+                }
+                ScriptStep::Move {
+                    x,
+                    y,
+                    duration_secs,
+                } => {
+                    if !self.glide(self.to_desktop(*x, *y), *duration_secs)? {
+                        return Ok(());
+                    }
+                }
+                ScriptStep::Click {
+                    button,
+                    x,
+                    y,
+                    count,
+                } => {
+                    if let (Some(x), Some(y)) = (x, y) {
+                        let point = self.to_desktop(*x, *y);
+                        self.injector.move_to(point)?;
+                        self.pos = Some(point);
+                    }
+                    for _ in 0..(*count).max(1) {
+                        self.injector.button(*button, ButtonState::Down)?;
+                        self.injector.button(*button, ButtonState::Up)?;
+                    }
+                }
+                ScriptStep::Press { button } => {
+                    self.injector.button(*button, ButtonState::Down)?;
+                    self.held.push(*button);
+                }
+                ScriptStep::Release { button } => {
+                    self.injector.button(*button, ButtonState::Up)?;
+                    self.held.retain(|held| held != button);
+                }
+                ScriptStep::Scroll { dx, dy } => self.injector.scroll(*dx, *dy)?,
+                ScriptStep::Key { keys } => self.injector.key(keys)?,
+                ScriptStep::Type { text, delay_ms } => {
+                    self.injector.type_text(text, *delay_ms)?;
+                    // Typing blocks until done; carry on from now.
+                    self.due = self.due.max(Instant::now());
+                }
+            }
+        }
+        self.wait(script.tail_secs);
+        Ok(())
+    }
+
+    /// Script pixels to desktop pixels on the target monitor.
+    fn to_desktop(&self, x: i32, y: i32) -> PhysicalPx {
+        PhysicalPx::new(
+            self.target.x as f64
+                + x as f64 * self.target.width as f64 / self.screen.width.max(1) as f64,
+            self.target.y as f64
+                + y as f64 * self.target.height as f64 / self.screen.height.max(1) as f64,
+        )
+    }
+
+    /// Move to `to` over `secs`. `false` when stopped on the way.
+    fn glide(&mut self, to: PhysicalPx, secs: f64) -> GrabmeResult<bool> {
+        let secs = secs.max(0.0);
+        if let Some(from) = self.pos.filter(|_| secs > 0.0) {
+            let start = self.due;
+            let steps = (secs / MOVE_INTERVAL.as_secs_f64()).ceil().max(1.0) as u32;
+            for i in 1..steps {
+                let progress = i as f64 / steps as f64;
+                self.due = start + Duration::from_secs_f64(secs * progress);
+                if !self.sleep_until_due() {
+                    return Ok(false);
+                }
+                self.injector.move_to(PhysicalPx::new(
+                    from.x + (to.x - from.x) * progress,
+                    from.y + (to.y - from.y) * progress,
+                ))?;
+            }
+            self.due = start + Duration::from_secs_f64(secs);
+            if !self.sleep_until_due() {
+                return Ok(false);
+            }
+        }
+        self.injector.move_to(to)?;
+        self.pos = Some(to);
+        Ok(true)
+    }
+
+    /// Pause for `secs` after the last due time. `false` when stopped.
+    fn wait(&mut self, secs: f64) -> bool {
+        self.due += Duration::from_secs_f64(secs.max(0.0));
+        self.sleep_until_due()
+    }
+
+    fn sleep_until_due(&self) -> bool {
+        loop {
+            if self.stop.load(Ordering::SeqCst) {
+                return false;
+            }
+            let now = Instant::now();
+            if now >= self.due {
+                return true;
+            }
+            std::thread::sleep((self.due - now).min(STOP_POLL));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCREEN: ScriptScreen = ScriptScreen {
+        width: 1000,
+        height: 500,
+    };
+
+    fn ms(ms: u64) -> u64 {
+        ms * 1_000_000
+    }
+
+    #[test]
+    fn test_script_yaml_round_trip_and_defaults() {
+        let yaml = "screen: { width: 1920, height: 1080 }
+steps:
+  - { action: move, x: 640, y: 360, duration_secs: 0.5 }
+  - { action: click, count: 2 }
+  - { action: press, button: right }
+  - { action: scroll, dy: -3 }
+  - { action: type, text: hello }
+  - { action: key, keys: ctrl+s }
+  - { action: wait, secs: 1.5 }
+";
+        let script = InputScript::from_yaml(yaml).unwrap();
+        assert_eq!(script.version, SCRIPT_VERSION);
+        assert_eq!(script.lead_in_secs, 1.0);
+        assert_eq!(
+            script.steps[1],
+            ScriptStep::Click {
+                button: MouseButton::Left,
+                x: None,
+                y: None,
+                count: 2,
+            }
+        );
+        assert_eq!(
+            script.steps[4],
+            ScriptStep::Type {
+                text: "hello".to_string(),
+                delay_ms: DEFAULT_TYPE_DELAY_MS,
+            }
+        );
+        assert!((script.duration_secs() - (1.0 + 0.5 + 0.2 + 1.5 + 1.0)).abs() < 1e-9);
+        assert_eq!(
+            InputScript::from_yaml(&script.to_yaml().unwrap()).unwrap(),
+            script
+        );
+
+        assert!(InputScript::from_yaml("screen: { width: 0, height: 1 }\nsteps: []").is_err());
+        assert!(
+            InputScript::from_yaml("version: 99\nscreen: { width: 1, height: 1 }\nsteps: []")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_from_events_converts_moves_clicks_drags_and_typing() {
+        let mut events = Vec::new();
+        // A straight glide from (100, 100) to (500, 100) over 0.4 s.
+        for i in 0..=8 {
+            events.push(InputEvent::pointer(
+                ms(1000 + i * 50),
+                0.1 + 0.05 * i as f64,
+                0.2,
+            ));
+        }
+        events.push(InputEvent::click(
+            ms(1500),
+            MouseButton::Left,
+            ButtonState::Down,
+            0.5,
+            0.2,
+        ));
+        events.push(InputEvent::click(
+            ms(1580),
+            MouseButton::Left,
+            ButtonState::Up,
+            0.5,
+            0.2,
+        ));
+        events.push(InputEvent::click(
+            ms(1700),
+            MouseButton::Left,
+            ButtonState::Down,
+            0.5,
+            0.2,
+        ));
+        events.push(InputEvent::click(
+            ms(1760),
+            MouseButton::Left,
+            ButtonState::Up,
+            0.5,
+            0.2,
+        ));
+        // Typing "Hi" then ctrl+s.
+        events.push(InputEvent::key(ms(3000), "ShiftLeft", ButtonState::Down));
+        events.push(InputEvent::key(ms(3010), "KeyH", ButtonState::Down));
+        events.push(InputEvent::key(ms(3050), "ShiftLeft", ButtonState::Up));
+        events.push(InputEvent::key(ms(3100), "KeyI", ButtonState::Down));
+        events.push(InputEvent::key(ms(3500), "ControlLeft", ButtonState::Down));
+        events.push(InputEvent::key(ms(3550), "KeyS", ButtonState::Down));
+        events.push(InputEvent::key(ms(3600), "ControlLeft", ButtonState::Up));
+        // A drag.
+        events.push(InputEvent::click(
+            ms(4000),
+            MouseButton::Left,
+            ButtonState::Down,
+            0.5,
+            0.2,
+        ));
+        events.push(InputEvent::pointer(ms(4300), 0.6, 0.4));
+        events.push(InputEvent::click(
+            ms(4600),
+            MouseButton::Left,
+            ButtonState::Up,
+            0.6,
+            0.4,
+        ));
+
+        let script = InputScript::from_events(&events, None, SCREEN);
+        assert_eq!(
+            script.steps,
+            vec![
+                ScriptStep::Move {
+                    x: 100,
+                    y: 100,
+                    duration_secs: 0.0,
+                },
+                ScriptStep::Move {
+                    x: 500,
+                    y: 100,
+                    duration_secs: 0.4,
+                },
+                ScriptStep::Wait { secs: 0.1 },
+                ScriptStep::Click {
+                    button: MouseButton::Left,
+                    x: None,
+                    y: None,
+                    count: 2,
+                },
+                ScriptStep::Wait { secs: 1.25 },
+                ScriptStep::Type {
+                    text: "Hi".to_string(),
+                    delay_ms: DEFAULT_TYPE_DELAY_MS,
+                },
+                ScriptStep::Wait { secs: 0.45 },
+                ScriptStep::Key {
+                    keys: "ctrl+s".to_string(),
+                },
+                ScriptStep::Wait { secs: 0.45 },
+                ScriptStep::Press {
+                    button: MouseButton::Left,
+                },
+                ScriptStep::Wait { secs: 0.3 },
+                ScriptStep::Move {
+                    x: 600,
+                    y: 200,
+                    duration_secs: 0.0,
+                },
+                ScriptStep::Wait { secs: 0.3 },
+                ScriptStep::Release {
+                    button: MouseButton::Left,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_simplify_path_keeps_corners() {
+        let path: Vec<(f64, (i32, i32))> = (0..=10)
+            .map(|i| (i as f64, (i * 10, 0)))
+            .chain((1..=10).map(|i| (10.0 + i as f64, (100, i * 10))))
+            .collect();
+        let kept: Vec<(i32, i32)> = simplify_path(&path).iter().map(|p| p.1).collect();
+        assert_eq!(kept, vec![(0, 0), (100, 0), (100, 100)]);
+    }
+}
@@ -5,6 +5,7 @@ use std::io::{BufWriter, Write};
 use std::path::PathBuf;
 
 use grabme_common::error::{GrabmeError, GrabmeResult};
+use grabme_project_model::atomic::write_atomic;
 use grabme_project_model::event::{EventStreamHeader, InputEvent};
 
 /// Writes events to a JSONL file in append-only mode.
@@ -21,19 +22,15 @@ impl EventWriter {
             std::fs::create_dir_all(parent)?;
         }
 
-        let file = OpenOptions::new()
-            .create(true)
-            .write(true)
-            .truncate(true)
-            .open(&path)?;
-
-        let mut writer = BufWriter::new(file);
-
-        // Write header as a comment line (prefixed with #)
+        // The header goes in atomically so a crash during startup never
+        // leaves a stream without one; events are then appended.
         let header_json = serde_json::to_string(&header)?;
-        writeln!(writer, "# {header_json}")
+        write_atomic(&path, format!("# {header_json}\n").as_bytes())
             .map_err(|e| GrabmeError::capture(format!("Failed to write header: {e}")))?;
 
+        let file = OpenOptions::new().append(true).open(&path)?;
+        let writer = BufWriter::new(file);
+
         Ok(Self {
             writer,
             path,
@@ -56,10 +53,11 @@ impl EventWriter {
         Ok(())
     }
 
-    /// Flush buffered writes to disk.
+    /// Flush buffered writes and sync them to disk.
     pub fn flush(&mut self) -> GrabmeResult<()> {
         self.writer
             .flush()
+            .and_then(|_| self.writer.get_ref().sync_data())
             .map_err(|e| GrabmeError::capture(format!("Failed to flush events: {e}")))?;
         Ok(())
     }
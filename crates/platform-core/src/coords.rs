@@ -0,0 +1,257 @@
+//! Strongly typed screen coordinates.
+//!
+//! Positions pass through several spaces between the OS and the export:
+//!
+//! - [`PhysicalPx`]: physical pixels on the virtual desktop, where monitor
+//!   geometry lives.
+//! - [`LogicalPx`]: physical pixels divided by the monitor scale factor, as
+//!   reported by scaled Wayland and macOS APIs.
+//! - [`VirtualNorm`]: `[0.0, 1.0]` across the bounds of the whole virtual
+//!   desktop.
+//! - [`CaptureNorm`]: `[0.0, 1.0]` across the captured region. Event streams,
+//!   viewports, and the cursor overlay use this space.
+//!
+//! Mixing them up compiles fine as bare `f64` pairs and shows up as a cursor
+//! drawn on the wrong monitor. Each space has its own type here, and moving
+//! between them goes through a conversion that names the rectangle it is
+//! relative to.
+
+use serde::{Deserialize, Serialize};
+
+/// A point in physical virtual-desktop pixels.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct PhysicalPx {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// A point in logical (scale-independent) pixels.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct LogicalPx {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// A point normalized against the virtual desktop bounds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct VirtualNorm {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// A point normalized against the captured region. Outside `[0.0, 1.0]`
+/// when it lies off the captured region.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct CaptureNorm {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// Rectangle in physical virtual-desktop pixels.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PhysicalRect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl PhysicalPx {
+    pub fn new(x: f64, y: f64) -> Self {
+        Self { x, y }
+    }
+
+    /// Logical position on a monitor with `scale_factor`.
+    pub fn to_logical(self, scale_factor: f64) -> LogicalPx {
+        let scale = valid_scale(scale_factor);
+        LogicalPx::new(self.x / scale, self.y / scale)
+    }
+}
+
+impl LogicalPx {
+    pub fn new(x: f64, y: f64) -> Self {
+        Self { x, y }
+    }
+
+    /// Physical position on a monitor with `scale_factor`.
+    pub fn to_physical(self, scale_factor: f64) -> PhysicalPx {
+        let scale = valid_scale(scale_factor);
+        PhysicalPx::new(self.x * scale, self.y * scale)
+    }
+}
+
+impl VirtualNorm {
+    pub fn new(x: f64, y: f64) -> Self {
+        Self { x, y }
+    }
+
+    /// Normalize `point` against the virtual desktop `bounds`.
+    pub fn from_physical(point: PhysicalPx, bounds: PhysicalRect) -> Self {
+        let (x, y) = bounds.normalize(point);
+        Self::new(x, y)
+    }
+
+    /// Physical position on the virtual desktop `bounds`.
+    pub fn to_physical(self, bounds: PhysicalRect) -> PhysicalPx {
+        bounds.denormalize(self.x, self.y)
+    }
+
+    /// Position in the captured region `capture` of the virtual desktop
+    /// `bounds`.
+    pub fn to_capture(self, bounds: PhysicalRect, capture: PhysicalRect) -> CaptureNorm {
+        CaptureNorm::from_physical(self.to_physical(bounds), capture)
+    }
+}
+
+impl CaptureNorm {
+    pub fn new(x: f64, y: f64) -> Self {
+        Self { x, y }
+    }
+
+    /// Normalize `point` against the captured region `capture`.
+    pub fn from_physical(point: PhysicalPx, capture: PhysicalRect) -> Self {
+        let (x, y) = capture.normalize(point);
+        Self::new(x, y)
+    }
+
+    /// Physical position on the virtual desktop.
+    pub fn to_physical(self, capture: PhysicalRect) -> PhysicalPx {
+        capture.denormalize(self.x, self.y)
+    }
+
+    /// Position in the virtual desktop `bounds` containing `capture`.
+    pub fn to_virtual(self, capture: PhysicalRect, bounds: PhysicalRect) -> VirtualNorm {
+        VirtualNorm::from_physical(self.to_physical(capture), bounds)
+    }
+
+    /// This point clamped onto the captured region.
+    pub fn clamped(self) -> Self {
+        Self::new(self.x.clamp(0.0, 1.0), self.y.clamp(0.0, 1.0))
+    }
+
+    /// Whether the point lies on the captured region.
+    pub fn is_inside(self) -> bool {
+        (0.0..=1.0).contains(&self.x) && (0.0..=1.0).contains(&self.y)
+    }
+
+    /// Euclidean distance to another point.
+    pub fn distance_to(&self, other: &CaptureNorm) -> f64 {
+        ((self.x - other.x).powi(2) + (self.y - other.y).powi(2)).sqrt()
+    }
+
+    /// Linear interpolation between two points.
+    pub fn lerp(a: &CaptureNorm, b: &CaptureNorm, t: f64) -> CaptureNorm {
+        let t = t.clamp(0.0, 1.0);
+        CaptureNorm {
+            x: a.x + (b.x - a.x) * t,
+            y: a.y + (b.y - a.y) * t,
+        }
+    }
+}
+
+impl PhysicalRect {
+    pub fn new(x: i32, y: i32, width: u32, height: u32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Whether the rectangle has no area.
+    pub fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    /// Whether `point` lies inside. The right and bottom edges are
+    /// exclusive so adjacent monitors don't both claim a point.
+    pub fn contains(&self, point: PhysicalPx) -> bool {
+        let left = self.x as f64;
+        let top = self.y as f64;
+        point.x >= left
+            && point.x < left + self.width as f64
+            && point.y >= top
+            && point.y < top + self.height as f64
+    }
+
+    fn normalize(&self, point: PhysicalPx) -> (f64, f64) {
+        (
+            (point.x - self.x as f64) / self.width.max(1) as f64,
+            (point.y - self.y as f64) / self.height.max(1) as f64,
+        )
+    }
+
+    fn denormalize(&self, x: f64, y: f64) -> PhysicalPx {
+        PhysicalPx::new(
+            self.x as f64 + x * self.width as f64,
+            self.y as f64 + y * self.height as f64,
+        )
+    }
+}
+
+fn valid_scale(scale_factor: f64) -> f64 {
+    if scale_factor.is_finite() && scale_factor > 0.0 {
+        scale_factor
+    } else {
+        1.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DESKTOP: PhysicalRect = PhysicalRect {
+        x: -1920,
+        y: 0,
+        width: 4480,
+        height: 1440,
+    };
+    const RIGHT_MONITOR: PhysicalRect = PhysicalRect {
+        x: 0,
+        y: 0,
+        width: 2560,
+        height: 1440,
+    };
+
+    #[test]
+    fn virtual_norm_maps_into_the_captured_monitor() {
+        let origin = VirtualNorm::new(1920.0 / 4480.0, 0.0).to_capture(DESKTOP, RIGHT_MONITOR);
+        assert!(origin.x.abs() < 1e-9 && origin.y.abs() < 1e-9);
+
+        // A point on the left monitor is outside the capture.
+        let left = VirtualNorm::new(0.1, 0.5).to_capture(DESKTOP, RIGHT_MONITOR);
+        assert!(left.x < 0.0);
+        assert!(!left.is_inside());
+        assert_eq!(left.clamped().x, 0.0);
+    }
+
+    #[test]
+    fn capture_and_virtual_round_trip() {
+        let point = CaptureNorm::new(0.25, 0.75);
+        let back = point
+            .to_virtual(RIGHT_MONITOR, DESKTOP)
+            .to_capture(DESKTOP, RIGHT_MONITOR);
+        assert!(point.distance_to(&back) < 1e-12);
+        assert_eq!(
+            point.to_physical(RIGHT_MONITOR),
+            PhysicalPx::new(640.0, 1080.0)
+        );
+    }
+
+    #[test]
+    fn logical_pixels_scale_with_the_monitor() {
+        let physical = PhysicalPx::new(3000.0, 1500.0);
+        assert_eq!(physical.to_logical(2.0), LogicalPx::new(1500.0, 750.0));
+        assert_eq!(physical.to_logical(2.0).to_physical(2.0), physical);
+        assert_eq!(physical.to_logical(0.0), LogicalPx::new(3000.0, 1500.0));
+    }
+
+    #[test]
+    fn rect_contains_excludes_far_edges() {
+        assert!(RIGHT_MONITOR.contains(PhysicalPx::new(0.0, 0.0)));
+        assert!(!RIGHT_MONITOR.contains(PhysicalPx::new(2560.0, 10.0)));
+        assert!(!RIGHT_MONITOR.contains(PhysicalPx::new(-0.5, 10.0)));
+    }
+}
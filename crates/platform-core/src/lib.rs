@@ -3,8 +3,14 @@
 //! This crate contains cross-platform display/capture data structures used
 //! by capture/input/render crates without coupling to a concrete OS backend.
 
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
+pub mod coords;
+
+pub use coords::{CaptureNorm, LogicalPx, PhysicalPx, PhysicalRect, VirtualNorm};
+
 /// Information about a connected monitor.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct MonitorInfo {
@@ -34,6 +40,33 @@ impl MonitorInfo {
     pub fn logical_height(&self) -> u32 {
         (self.height as f64 / self.scale_factor) as u32
     }
+
+    /// Position and size on the virtual desktop.
+    pub fn rect(&self) -> PhysicalRect {
+        PhysicalRect::new(self.x, self.y, self.width, self.height)
+    }
+}
+
+/// Position and size of a top-level window in virtual-desktop pixels.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WindowGeometry {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// A change in the desktop session that affects a running recording.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SystemSessionEvent {
+    /// The screen was locked.
+    Locked,
+    /// The screen was unlocked.
+    Unlocked,
+    /// The system is about to suspend or hibernate.
+    Suspending,
+    /// The system woke up from suspend.
+    Resumed,
 }
 
 /// Display server / platform family used for capture.
@@ -48,6 +81,45 @@ pub enum DisplayServer {
     Unknown,
 }
 
+impl std::str::FromStr for DisplayServer {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "wayland" => Ok(DisplayServer::Wayland),
+            "x11" => Ok(DisplayServer::X11),
+            "windows" => Ok(DisplayServer::Windows),
+            "macos" => Ok(DisplayServer::MacOS),
+            other => Err(format!("unknown display server '{other}'")),
+        }
+    }
+}
+
+/// Display to capture from when it should not be inferred from the
+/// environment (for example a headless `Xvfb :99`).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DisplayTarget {
+    /// X11 display name such as `:99`. Implies X11 unless `server` is set.
+    pub x11_display: Option<String>,
+    /// Display server to use instead of detecting one.
+    pub server: Option<DisplayServer>,
+}
+
+impl DisplayTarget {
+    /// Target a specific X11 display.
+    pub fn x11(display: impl Into<String>) -> Self {
+        Self {
+            x11_display: Some(display.into()),
+            server: Some(DisplayServer::X11),
+        }
+    }
+
+    /// Whether nothing is overridden.
+    pub fn is_default(&self) -> bool {
+        self.x11_display.is_none() && self.server.is_none()
+    }
+}
+
 /// Compute virtual desktop bounds that include all connected monitors.
 /// Returns `(min_x, min_y, width, height)` in physical pixels.
 pub fn virtual_desktop_bounds(monitors: &[MonitorInfo]) -> (i32, i32, u32, u32) {
@@ -73,18 +145,72 @@ pub fn virtual_desktop_bounds(monitors: &[MonitorInfo]) -> (i32, i32, u32, u32)
     (min_x, min_y, width, height)
 }
 
+/// `monitors` with each monitor named in `splits` replaced by that many
+/// side-by-side regions of equal width, named `<name>:1`, `<name>:2`, ...
+/// from left to right. Ultrawide users often treat the halves as separate
+/// screens; the regions can be selected and recorded like monitors.
+pub fn split_monitors(
+    monitors: &[MonitorInfo],
+    splits: &BTreeMap<String, u32>,
+) -> Vec<MonitorInfo> {
+    monitors
+        .iter()
+        .flat_map(|monitor| {
+            let parts = splits
+                .get(&monitor.name)
+                .copied()
+                .unwrap_or(1)
+                .clamp(1, monitor.width.max(1));
+            let width = monitor.width / parts;
+            (0..parts).map(move |part| {
+                if parts == 1 {
+                    return monitor.clone();
+                }
+                let offset = width * part;
+                MonitorInfo {
+                    name: format!("{}:{}", monitor.name, part + 1),
+                    // The last region takes the pixels left over by the division.
+                    width: if part + 1 == parts {
+                        monitor.width - offset
+                    } else {
+                        width
+                    },
+                    x: monitor.x + offset as i32,
+                    primary: monitor.primary && part == 0,
+                    ..monitor.clone()
+                }
+            })
+        })
+        .collect()
+}
+
+/// The monitor in `monitors` that `region`, a split region from
+/// [`split_monitors`], was cut from. `None` when `region` is a whole monitor.
+pub fn split_parent<'a>(
+    monitors: &'a [MonitorInfo],
+    region: &MonitorInfo,
+) -> Option<&'a MonitorInfo> {
+    monitors.iter().find(|monitor| {
+        (monitor.width, monitor.height) != (region.width, region.height)
+            && region.x >= monitor.x
+            && region.y >= monitor.y
+            && region.x + region.width as i32 <= monitor.x + monitor.width as i32
+            && region.y + region.height as i32 <= monitor.y + monitor.height as i32
+    })
+}
+
 /// Normalize absolute pixel coordinates to `[0.0, 1.0]` for a monitor.
 pub fn normalize_coords(pixel_x: i32, pixel_y: i32, monitor: &MonitorInfo) -> (f64, f64) {
-    let x = (pixel_x - monitor.x) as f64 / monitor.width.max(1) as f64;
-    let y = (pixel_y - monitor.y) as f64 / monitor.height.max(1) as f64;
-    (x.clamp(0.0, 1.0), y.clamp(0.0, 1.0))
+    let point = PhysicalPx::new(pixel_x as f64, pixel_y as f64);
+    let norm = CaptureNorm::from_physical(point, monitor.rect()).clamped();
+    (norm.x, norm.y)
 }
 
 /// Denormalize `[0.0, 1.0]` coordinates back to absolute pixels.
 pub fn denormalize_coords(norm_x: f64, norm_y: f64, width: u32, height: u32) -> (i32, i32) {
-    let x = (norm_x.clamp(0.0, 1.0) * width.max(1) as f64) as i32;
-    let y = (norm_y.clamp(0.0, 1.0) * height.max(1) as f64) as i32;
-    (x, y)
+    let rect = PhysicalRect::new(0, 0, width.max(1), height.max(1));
+    let point = CaptureNorm::new(norm_x, norm_y).clamped().to_physical(rect);
+    (point.x as i32, point.y as i32)
 }
 
 #[cfg(test)]
@@ -122,4 +248,48 @@ mod tests {
         assert_eq!(w, 4480);
         assert_eq!(h, 1440);
     }
+
+    #[test]
+    fn split_monitors_cut_named_monitors_into_regions() {
+        let ultrawide = MonitorInfo {
+            name: "DP-1".to_string(),
+            width: 5121,
+            height: 1440,
+            x: 1920,
+            y: 0,
+            scale_factor: 1.0,
+            refresh_rate_hz: 120,
+            primary: true,
+        };
+        let side = MonitorInfo {
+            name: "HDMI-A-1".to_string(),
+            x: 0,
+            width: 1920,
+            height: 1080,
+            primary: false,
+            ..ultrawide.clone()
+        };
+        let monitors = vec![side.clone(), ultrawide.clone()];
+        let splits = BTreeMap::from([("DP-1".to_string(), 2), ("HDMI-A-1".to_string(), 0)]);
+
+        let split = split_monitors(&monitors, &splits);
+        assert_eq!(split.len(), 3);
+        assert_eq!(split[0], side);
+        assert_eq!(split[1].name, "DP-1:1");
+        assert_eq!(
+            (split[1].x, split[1].width, split[1].height),
+            (1920, 2560, 1440)
+        );
+        assert!(split[1].primary);
+        assert_eq!(split[2].name, "DP-1:2");
+        assert_eq!((split[2].x, split[2].width), (4480, 2561));
+        assert!(!split[2].primary);
+        assert_eq!(
+            virtual_desktop_bounds(&split),
+            virtual_desktop_bounds(&monitors)
+        );
+
+        assert_eq!(split_parent(&monitors, &split[2]), Some(&ultrawide));
+        assert_eq!(split_parent(&monitors, &side), None);
+    }
 }
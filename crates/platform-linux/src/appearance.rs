@@ -0,0 +1,189 @@
+//! Desktop appearance changes: dark/light theme and display brightness.
+//!
+//! Switching theme or dimming the screen mid-recording changes what the
+//! whole frame looks like. The color scheme comes from the XDG Settings
+//! portal's change signal; brightness is polled from the first backlight
+//! under `/sys/class/backlight`, since there is no portal for it. Displays
+//! without a backlight (most external monitors) report no brightness.
+
+use std::path::Path;
+use std::pin::Pin;
+use std::time::Duration;
+
+use ashpd::desktop::settings::{ColorScheme as AshColorScheme, Settings};
+use futures_util::{Stream, StreamExt};
+
+const BACKLIGHT_DIR: &str = "/sys/class/backlight";
+
+/// How often the backlight is read.
+const BRIGHTNESS_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Smallest brightness step reported, so sensor noise and single steps of
+/// fine-grained backlights don't flood the event stream.
+const BRIGHTNESS_MIN_STEP: f64 = 0.02;
+
+/// Desktop color scheme preference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorSchemePreference {
+    Dark,
+    Light,
+    NoPreference,
+}
+
+impl From<AshColorScheme> for ColorSchemePreference {
+    fn from(scheme: AshColorScheme) -> Self {
+        match scheme {
+            AshColorScheme::PreferDark => Self::Dark,
+            AshColorScheme::PreferLight => Self::Light,
+            AshColorScheme::NoPreference => Self::NoPreference,
+        }
+    }
+}
+
+/// A change in how the desktop looks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AppearanceChange {
+    ColorScheme(ColorSchemePreference),
+    /// Backlight level `[0.0, 1.0]`.
+    Brightness(f64),
+}
+
+type SchemeStream<'a> = Pin<Box<dyn Stream<Item = AshColorScheme> + Send + 'a>>;
+
+/// Report appearance changes to `on_change` until the task is aborted or
+/// `on_change` returns `false`.
+///
+/// Only changes are reported, not the state at startup. Without a Settings
+/// portal only brightness is watched, and vice versa.
+pub fn spawn_appearance_watcher<F>(mut on_change: F) -> tokio::task::JoinHandle<()>
+where
+    F: FnMut(AppearanceChange) -> bool + Send + 'static,
+{
+    tokio::spawn(async move {
+        let settings = match Settings::new().await {
+            Ok(settings) => Some(settings),
+            Err(e) => {
+                tracing::debug!(error = %e, "Settings portal unavailable; not watching color scheme");
+                None
+            }
+        };
+        let mut last_scheme = None;
+        let mut schemes: Option<SchemeStream<'_>> = None;
+        if let Some(settings) = settings.as_ref() {
+            last_scheme = settings.color_scheme().await.ok();
+            match settings.receive_color_scheme_changed().await {
+                Ok(stream) => schemes = Some(Box::pin(stream)),
+                Err(e) => {
+                    tracing::debug!(error = %e, "Failed to subscribe to color scheme changes")
+                }
+            }
+        }
+
+        let mut last_brightness = read_backlight_level(Path::new(BACKLIGHT_DIR));
+        let mut poll = tokio::time::interval(BRIGHTNESS_POLL_INTERVAL);
+        if schemes.is_none() && last_brightness.is_none() {
+            tracing::debug!("No appearance signals available");
+            return;
+        }
+
+        loop {
+            tokio::select! {
+                scheme = next_scheme(&mut schemes) => match scheme {
+                    Some(scheme) if last_scheme != Some(scheme) => {
+                        tracing::info!(?scheme, "Color scheme changed");
+                        last_scheme = Some(scheme);
+                        if !on_change(AppearanceChange::ColorScheme(scheme.into())) {
+                            return;
+                        }
+                    }
+                    Some(_) => {}
+                    None => schemes = None,
+                },
+                _ = poll.tick(), if last_brightness.is_some() => {
+                    let level = read_backlight_level(Path::new(BACKLIGHT_DIR));
+                    if let Some(level) = brightness_change(last_brightness, level) {
+                        tracing::debug!(level, "Brightness changed");
+                        last_brightness = Some(level);
+                        if !on_change(AppearanceChange::Brightness(level)) {
+                            return;
+                        }
+                    }
+                }
+            }
+        }
+    })
+}
+
+async fn next_scheme(schemes: &mut Option<SchemeStream<'_>>) -> Option<AshColorScheme> {
+    match schemes {
+        Some(stream) => stream.next().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// `level` if it differs enough from `last` to be reported.
+fn brightness_change(last: Option<f64>, level: Option<f64>) -> Option<f64> {
+    let level = level?;
+    match last {
+        Some(last) if (level - last).abs() < BRIGHTNESS_MIN_STEP => None,
+        _ => Some(level),
+    }
+}
+
+/// Brightness of the first backlight under `dir`, as a share of its maximum.
+fn read_backlight_level(dir: &Path) -> Option<f64> {
+    let mut devices: Vec<_> = std::fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .collect();
+    devices.sort();
+
+    devices.iter().find_map(|device| {
+        let read = |name: &str| -> Option<u64> {
+            std::fs::read_to_string(device.join(name))
+                .ok()?
+                .trim()
+                .parse()
+                .ok()
+        };
+        // `actual_brightness` is what the hardware reports; `brightness` is
+        // only the last requested value.
+        let level = read("actual_brightness").or_else(|| read("brightness"))?;
+        let max = read("max_brightness").filter(|max| *max > 0)?;
+        Some((level as f64 / max as f64).clamp(0.0, 1.0))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_brightness_change_ignores_small_steps() {
+        assert_eq!(brightness_change(Some(0.5), Some(0.51)), None);
+        assert_eq!(brightness_change(Some(0.5), Some(0.3)), Some(0.3));
+        assert_eq!(brightness_change(None, Some(0.3)), Some(0.3));
+        assert_eq!(brightness_change(Some(0.5), None), None);
+    }
+
+    #[test]
+    fn test_read_backlight_level_prefers_actual_brightness() {
+        let dir =
+            std::env::temp_dir().join(format!("grabme-backlight-test-{}", std::process::id()));
+        let device = dir.join("intel_backlight");
+        std::fs::create_dir_all(&device).unwrap();
+        std::fs::write(device.join("max_brightness"), "1000\n").unwrap();
+        std::fs::write(device.join("brightness"), "800\n").unwrap();
+        assert_eq!(read_backlight_level(&dir), Some(0.8));
+
+        std::fs::write(device.join("actual_brightness"), "250\n").unwrap();
+        assert_eq!(read_backlight_level(&dir), Some(0.25));
+
+        std::fs::write(device.join("max_brightness"), "0\n").unwrap();
+        assert_eq!(read_backlight_level(&dir), None);
+
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(read_backlight_level(&dir), None);
+    }
+}
@@ -3,9 +3,53 @@
 use grabme_common::error::GrabmeResult;
 use grabme_platform_core::{
     denormalize_coords as core_denormalize_coords, normalize_coords as core_normalize_coords,
-    virtual_desktop_bounds as core_virtual_desktop_bounds, DisplayServer, MonitorInfo,
+    virtual_desktop_bounds as core_virtual_desktop_bounds, DisplayServer, DisplayTarget,
+    MonitorInfo,
 };
 use std::process::Command;
+use std::sync::RwLock;
+
+/// Process-wide display override. Monitor detection, window queries, and
+/// input polling all shell out to X11 tools, so the target is applied where
+/// those commands are built rather than threaded through every call.
+static DISPLAY_TARGET: RwLock<Option<DisplayTarget>> = RwLock::new(None);
+
+/// Direct all later display queries and capture at `target`.
+pub fn set_display_target(target: DisplayTarget) {
+    if !target.is_default() {
+        tracing::info!(?target, "Using explicit display target");
+    }
+    let mut current = DISPLAY_TARGET.write().unwrap_or_else(|e| e.into_inner());
+    *current = (!target.is_default()).then_some(target);
+}
+
+/// The display override in effect, if any.
+pub fn display_target() -> DisplayTarget {
+    DISPLAY_TARGET
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .unwrap_or_default()
+}
+
+/// X11 display that capture and X11 tools talk to: the override, else
+/// `$DISPLAY`.
+pub fn x11_display_name() -> Option<String> {
+    display_target()
+        .x11_display
+        .or_else(|| std::env::var("DISPLAY").ok())
+        .filter(|name| !name.is_empty())
+}
+
+/// Command for an X11 client tool (`xrandr`, `xdotool`, ...) pointed at the
+/// overridden display, if one is set.
+pub fn x11_command(program: &str) -> Command {
+    let mut command = Command::new(program);
+    if let Some(display) = display_target().x11_display {
+        command.env("DISPLAY", display);
+    }
+    command
+}
 
 /// Detect connected monitors.
 pub fn detect_monitors() -> GrabmeResult<Vec<MonitorInfo>> {
@@ -42,7 +86,8 @@ pub fn denormalize_coords(norm_x: f64, norm_y: f64, width: u32, height: u32) ->
 
 /// Detect the current display server.
 ///
-/// Detection priority:
+/// An explicit [`set_display_target`] wins: its `server`, or X11 when only a
+/// display name is given. Otherwise detection priority is:
 /// 1. `XDG_SESSION_TYPE` — the canonical session type set by the login manager.
 ///    This is the most reliable indicator.
 /// 2. `WAYLAND_DISPLAY` — set when a Wayland compositor is running.
@@ -52,6 +97,14 @@ pub fn denormalize_coords(norm_x: f64, norm_y: f64, width: u32, height: u32) ->
 /// session via XWayland) may have both `WAYLAND_DISPLAY` and `DISPLAY` set.
 /// `XDG_SESSION_TYPE` disambiguates this correctly.
 pub fn detect_display_server() -> DisplayServer {
+    let target = display_target();
+    if let Some(server) = target.server {
+        return server;
+    }
+    if target.x11_display.is_some() {
+        return DisplayServer::X11;
+    }
+
     // XDG_SESSION_TYPE is the most authoritative source
     match std::env::var("XDG_SESSION_TYPE")
         .as_deref()
@@ -75,7 +128,7 @@ pub fn detect_display_server() -> DisplayServer {
 }
 
 fn parse_xrandr_output() -> Option<Vec<MonitorInfo>> {
-    let output = Command::new("xrandr").arg("--query").output().ok()?;
+    let output = x11_command("xrandr").arg("--query").output().ok()?;
     if !output.status.success() {
         return None;
     }
@@ -475,6 +528,20 @@ eDP-1 connected primary 1920x1080+0+0 (normal left inverted right x axis y axis)
         assert!(names.contains(&"eDP-1"), "active monitor must be present");
     }
 
+    #[test]
+    fn display_target_overrides_detection_and_tool_env() {
+        set_display_target(DisplayTarget::x11(":99"));
+        assert_eq!(detect_display_server(), DisplayServer::X11);
+        assert_eq!(x11_display_name().as_deref(), Some(":99"));
+        let command = x11_command("xrandr");
+        assert!(command
+            .get_envs()
+            .any(|(key, value)| key == "DISPLAY" && value == Some(std::ffi::OsStr::new(":99"))));
+
+        set_display_target(DisplayTarget::default());
+        assert_eq!(x11_command("xrandr").get_envs().count(), 0);
+    }
+
     #[test]
     fn test_normalize_coords() {
         let monitor = MonitorInfo {
@@ -5,11 +5,18 @@
 //! - **PipeWire:** Audio/video stream management
 //! - **Display Detection:** Monitor enumeration and DPI handling
 //! - **Permissions:** Capability detection and user guidance
+//! - **Windows:** X11 window picking and geometry polling
+//! - **Appearance:** Theme and brightness change notifications
+//! - **logind:** Screen lock and suspend notifications
 
+pub mod appearance;
 pub mod display;
+pub mod logind;
 pub mod permissions;
 pub mod portal;
+pub mod window;
 
 pub use display::*;
-pub use grabme_platform_core::{DisplayServer, MonitorInfo};
+pub use grabme_platform_core::{DisplayServer, DisplayTarget, MonitorInfo, WindowGeometry};
 pub use portal::*;
+pub use window::*;
@@ -0,0 +1,181 @@
+//! Screen lock and suspend signals from systemd-logind.
+//!
+//! Locking the screen or suspending mid-recording would otherwise leave the
+//! capture running over a black or locked screen. logind reports both on
+//! the system bus: `Lock`/`Unlock` and the `LockedHint` property on the
+//! user's session, and `PrepareForSleep` on the manager. A delay inhibitor
+//! is held while watching so the recording gets a moment to stop or pause
+//! before the system sleeps.
+
+use std::pin::Pin;
+use std::time::Duration;
+
+use ashpd::zbus::zvariant::{OwnedFd, OwnedObjectPath};
+use ashpd::zbus::{Connection, Proxy};
+use futures_util::future::ready;
+use futures_util::stream::{select_all, Stream, StreamExt};
+use grabme_platform_core::SystemSessionEvent;
+
+const LOGIND: &str = "org.freedesktop.login1";
+const MANAGER_PATH: &str = "/org/freedesktop/login1";
+const MANAGER_INTERFACE: &str = "org.freedesktop.login1.Manager";
+const SESSION_INTERFACE: &str = "org.freedesktop.login1.Session";
+const USER_SELF_PATH: &str = "/org/freedesktop/login1/user/self";
+const USER_INTERFACE: &str = "org.freedesktop.login1.User";
+
+/// How long the sleep inhibitor is held after `Suspending` is reported.
+/// logind stops waiting after `InhibitDelayMaxSec` (5s by default) anyway.
+const SLEEP_GRACE: Duration = Duration::from_secs(2);
+
+type EventStream = Pin<Box<dyn Stream<Item = SystemSessionEvent> + Send>>;
+
+/// Report lock and suspend changes to `on_change` until the task is
+/// aborted or `on_change` returns `false`.
+///
+/// Without a system bus or logind (containers, non-systemd distributions)
+/// the task ends right away and nothing is reported.
+pub fn spawn_logind_watcher<F>(mut on_change: F) -> tokio::task::JoinHandle<()>
+where
+    F: FnMut(SystemSessionEvent) -> bool + Send + 'static,
+{
+    tokio::spawn(async move {
+        let connection = match Connection::system().await {
+            Ok(connection) => connection,
+            Err(e) => {
+                tracing::debug!(error = %e, "System bus unavailable; not watching lock or suspend");
+                return;
+            }
+        };
+        let manager = match Proxy::new(&connection, LOGIND, MANAGER_PATH, MANAGER_INTERFACE).await {
+            Ok(manager) => manager,
+            Err(e) => {
+                tracing::debug!(error = %e, "logind unavailable; not watching lock or suspend");
+                return;
+            }
+        };
+
+        let mut streams: Vec<EventStream> = Vec::new();
+        match manager.receive_signal("PrepareForSleep").await {
+            Ok(signals) => streams.push(Box::pin(signals.filter_map(|message| {
+                let start = message.body().deserialize::<bool>().ok();
+                ready(start.map(|start| {
+                    if start {
+                        SystemSessionEvent::Suspending
+                    } else {
+                        SystemSessionEvent::Resumed
+                    }
+                }))
+            }))),
+            Err(e) => tracing::debug!(error = %e, "Failed to subscribe to suspend signals"),
+        }
+
+        match session_proxy(&connection, &manager).await {
+            Some(session) => {
+                for (signal, event) in [
+                    ("Lock", SystemSessionEvent::Locked),
+                    ("Unlock", SystemSessionEvent::Unlocked),
+                ] {
+                    match session.receive_signal(signal).await {
+                        Ok(signals) => {
+                            streams.push(Box::pin(signals.map(move |_| event)));
+                        }
+                        Err(e) => {
+                            tracing::debug!(signal, error = %e, "Failed to subscribe to lock signal")
+                        }
+                    }
+                }
+                // Desktops that lock on their own (GNOME, KDE) only update
+                // the hint; `loginctl lock-session` sends the signals.
+                let hints = session.receive_property_changed::<bool>("LockedHint").await;
+                streams.push(Box::pin(hints.filter_map(|change| async move {
+                    let locked = change.get().await.ok()?;
+                    Some(if locked {
+                        SystemSessionEvent::Locked
+                    } else {
+                        SystemSessionEvent::Unlocked
+                    })
+                })));
+            }
+            None => tracing::debug!("No logind session for this process; not watching locks"),
+        }
+
+        if streams.is_empty() {
+            return;
+        }
+
+        let mut inhibitor = take_sleep_inhibitor(&manager).await;
+        let mut events = select_all(streams);
+        let mut locked = false;
+        while let Some(event) = events.next().await {
+            // The signals and the hint both report a lock; pass on one.
+            match event {
+                SystemSessionEvent::Locked if locked => continue,
+                SystemSessionEvent::Unlocked if !locked => continue,
+                SystemSessionEvent::Locked => locked = true,
+                SystemSessionEvent::Unlocked => locked = false,
+                _ => {}
+            }
+            tracing::info!(?event, "Session state changed");
+            if !on_change(event) {
+                return;
+            }
+            match event {
+                SystemSessionEvent::Suspending => {
+                    tokio::time::sleep(SLEEP_GRACE).await;
+                    inhibitor = None;
+                }
+                SystemSessionEvent::Resumed if inhibitor.is_none() => {
+                    inhibitor = take_sleep_inhibitor(&manager).await;
+                }
+                _ => {}
+            }
+        }
+        drop(inhibitor);
+    })
+}
+
+/// The logind session this process runs in, or the user's graphical
+/// session when it was started outside one (e.g. from a user service).
+async fn session_proxy(connection: &Connection, manager: &Proxy<'_>) -> Option<Proxy<'static>> {
+    let path = match manager
+        .call::<_, _, OwnedObjectPath>("GetSessionByPID", &(std::process::id(),))
+        .await
+    {
+        Ok(path) => path,
+        Err(_) => {
+            let user = Proxy::new(connection, LOGIND, USER_SELF_PATH, USER_INTERFACE)
+                .await
+                .ok()?;
+            let (_, path) = user
+                .get_property::<(String, OwnedObjectPath)>("Display")
+                .await
+                .ok()?;
+            path
+        }
+    };
+    Proxy::new(connection, LOGIND, path.into_inner(), SESSION_INTERFACE)
+        .await
+        .ok()
+}
+
+/// Ask logind to delay sleep until the returned descriptor is closed.
+async fn take_sleep_inhibitor(manager: &Proxy<'_>) -> Option<OwnedFd> {
+    match manager
+        .call::<_, _, OwnedFd>(
+            "Inhibit",
+            &(
+                "sleep",
+                "GrabMe",
+                "Stop or pause the recording before sleeping",
+                "delay",
+            ),
+        )
+        .await
+    {
+        Ok(fd) => Some(fd),
+        Err(e) => {
+            tracing::debug!(error = %e, "Could not take a sleep inhibitor");
+            None
+        }
+    }
+}
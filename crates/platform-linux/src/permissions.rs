@@ -26,7 +26,8 @@ pub fn check_capabilities() -> Vec<Capability> {
 
 /// Check if XDG Desktop Portal is accessible.
 fn check_portal_access() -> Capability {
-    let available = std::env::var("WAYLAND_DISPLAY").is_ok() || std::env::var("DISPLAY").is_ok();
+    let available =
+        std::env::var("WAYLAND_DISPLAY").is_ok() || crate::display::x11_display_name().is_some();
 
     Capability {
         name: "Screen Capture Portal".to_string(),
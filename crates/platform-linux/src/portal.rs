@@ -8,7 +8,8 @@
 //!
 //! 1. Connect to `org.freedesktop.portal.ScreenCast` via DBus
 //! 2. Create a session
-//! 3. Select sources (screen/window) with `cursor_mode = hidden`
+//! 3. Select sources (screen/window) with the closest cursor mode the portal
+//!    offers to the one asked for
 //! 4. Start the stream → receive a PipeWire node ID
 //! 5. Connect to PipeWire and receive video frames
 
@@ -39,6 +40,39 @@ impl CursorMode {
             CursorMode::Metadata => 4, // CURSOR_MODE_METADATA
         }
     }
+
+    /// Modes to try, in order, when `self` is asked for. Hidden and
+    /// metadata both keep the cursor out of the frames, so each stands in
+    /// for the other before falling back to embedded.
+    fn preference(self) -> [CursorMode; 3] {
+        match self {
+            CursorMode::Hidden => [
+                CursorMode::Hidden,
+                CursorMode::Metadata,
+                CursorMode::Embedded,
+            ],
+            CursorMode::Metadata => [
+                CursorMode::Metadata,
+                CursorMode::Hidden,
+                CursorMode::Embedded,
+            ],
+            CursorMode::Embedded => [
+                CursorMode::Embedded,
+                CursorMode::Metadata,
+                CursorMode::Hidden,
+            ],
+        }
+    }
+}
+
+/// The mode to request when `requested` was asked for and the portal
+/// offers `available`. Portals that don't list their modes get `requested`.
+fn pick_cursor_mode(requested: CursorMode, available: &[CursorMode]) -> CursorMode {
+    requested
+        .preference()
+        .into_iter()
+        .find(|mode| available.contains(mode))
+        .unwrap_or(requested)
 }
 
 /// Source type for screen capture.
@@ -69,6 +103,9 @@ pub struct PortalSession {
 
     /// Portal session handle.
     pub session_handle: String,
+
+    /// Cursor mode the session was started with.
+    pub cursor_mode: CursorMode,
 }
 
 /// Request a screen capture session through the XDG Desktop Portal.
@@ -94,6 +131,24 @@ pub async fn request_screencast(
         GrabmeError::platform(format!("Failed to connect to XDG ScreenCast portal: {e}"))
     })?;
 
+    let available: Vec<CursorMode> = match proxy.available_cursor_modes().await {
+        Ok(modes) => modes.iter().map(from_ash_cursor_mode).collect(),
+        Err(e) => {
+            tracing::debug!(error = %e, "Portal does not list its cursor modes");
+            Vec::new()
+        }
+    };
+    let requested = cursor_mode;
+    let cursor_mode = pick_cursor_mode(requested, &available);
+    if cursor_mode != requested {
+        tracing::info!(
+            ?requested,
+            used = ?cursor_mode,
+            ?available,
+            "Portal does not offer the requested cursor mode"
+        );
+    }
+
     let session = proxy
         .create_session()
         .await
@@ -159,6 +214,7 @@ pub async fn request_screencast(
         width,
         height,
         session_handle: format!("{session:?}"),
+        cursor_mode,
     })
 }
 
@@ -189,9 +245,50 @@ fn map_cursor_mode(mode: CursorMode) -> AshCursorMode {
     }
 }
 
+fn from_ash_cursor_mode(mode: AshCursorMode) -> CursorMode {
+    match mode {
+        AshCursorMode::Hidden => CursorMode::Hidden,
+        AshCursorMode::Embedded => CursorMode::Embedded,
+        AshCursorMode::Metadata => CursorMode::Metadata,
+    }
+}
+
 fn map_source_type(source_type: SourceType) -> AshSourceType {
     match source_type {
         SourceType::Monitor => AshSourceType::Monitor,
         SourceType::Window => AshSourceType::Window,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pick_cursor_mode_keeps_the_cursor_out_of_frames_when_possible() {
+        let all = [
+            CursorMode::Hidden,
+            CursorMode::Embedded,
+            CursorMode::Metadata,
+        ];
+        assert_eq!(
+            pick_cursor_mode(CursorMode::Metadata, &all),
+            CursorMode::Metadata
+        );
+        assert_eq!(
+            pick_cursor_mode(
+                CursorMode::Metadata,
+                &[CursorMode::Hidden, CursorMode::Embedded]
+            ),
+            CursorMode::Hidden
+        );
+        assert_eq!(
+            pick_cursor_mode(CursorMode::Hidden, &[CursorMode::Embedded]),
+            CursorMode::Embedded
+        );
+        assert_eq!(
+            pick_cursor_mode(CursorMode::Embedded, &[]),
+            CursorMode::Embedded
+        );
+    }
+}
@@ -0,0 +1,162 @@
+//! X11 window lookup, focus, and geometry polling.
+//!
+//! Uses `xwininfo`, which ships with the standard X11 client utilities, so no
+//! extra library dependency is needed for window-snapped recording.
+
+use grabme_common::error::{GrabmeError, GrabmeResult};
+use grabme_platform_core::{DisplayServer, WindowGeometry};
+
+use crate::display::{detect_display_server, x11_command};
+
+/// Let the user click a window and return its id and geometry.
+pub fn pick_window() -> GrabmeResult<(String, WindowGeometry)> {
+    run_xwininfo(&[])
+}
+
+/// Current geometry of the window with the given X11 id (e.g. `0x3a00007`).
+pub fn query_window_geometry(window_id: &str) -> GrabmeResult<WindowGeometry> {
+    run_xwininfo(&["-id", window_id]).map(|(_, geometry)| geometry)
+}
+
+/// Geometry of the currently focused window.
+///
+/// Reads `_NET_ACTIVE_WINDOW` from the root window with `xprop`. Wayland has
+/// no portable focused-window query, so this returns `Ok(None)` there.
+pub fn focused_window_geometry() -> GrabmeResult<Option<WindowGeometry>> {
+    if detect_display_server() != DisplayServer::X11 {
+        return Ok(None);
+    }
+
+    let output = x11_command("xprop")
+        .args(["-root", "_NET_ACTIVE_WINDOW"])
+        .output()
+        .map_err(|e| GrabmeError::platform(format!("Failed to run xprop: {e}")))?;
+    if !output.status.success() {
+        return Err(GrabmeError::platform(format!(
+            "xprop failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    match parse_active_window_id(&String::from_utf8_lossy(&output.stdout)) {
+        Some(window_id) => query_window_geometry(&window_id).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Extract the window id from `xprop -root _NET_ACTIVE_WINDOW` output.
+/// A zero id means nothing has focus.
+fn parse_active_window_id(stdout: &str) -> Option<String> {
+    let (_, rest) = stdout.split_once('#')?;
+    let id = rest
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .find(|part| !part.is_empty())?;
+    let value = u64::from_str_radix(id.trim_start_matches("0x"), 16).ok()?;
+    (value != 0).then(|| id.to_string())
+}
+
+fn run_xwininfo(args: &[&str]) -> GrabmeResult<(String, WindowGeometry)> {
+    let output = x11_command("xwininfo")
+        .args(args)
+        .output()
+        .map_err(|e| GrabmeError::platform(format!("Failed to run xwininfo: {e}")))?;
+    if !output.status.success() {
+        return Err(GrabmeError::platform(format!(
+            "xwininfo failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    parse_xwininfo(&String::from_utf8_lossy(&output.stdout))
+        .ok_or_else(|| GrabmeError::platform("Could not parse xwininfo output"))
+}
+
+fn parse_xwininfo(stdout: &str) -> Option<(String, WindowGeometry)> {
+    let mut window_id = None;
+    let mut x = None;
+    let mut y = None;
+    let mut width = None;
+    let mut height = None;
+
+    for line in stdout.lines().map(str::trim) {
+        if let Some(rest) = line
+            .strip_prefix("xwininfo: Window id:")
+            .or_else(|| line.strip_prefix("Window id:"))
+        {
+            window_id = rest.split_whitespace().next().map(str::to_string);
+        } else if let Some(value) = line.strip_prefix("Absolute upper-left X:") {
+            x = value.trim().parse().ok();
+        } else if let Some(value) = line.strip_prefix("Absolute upper-left Y:") {
+            y = value.trim().parse().ok();
+        } else if let Some(value) = line.strip_prefix("Width:") {
+            width = value.trim().parse().ok();
+        } else if let Some(value) = line.strip_prefix("Height:") {
+            height = value.trim().parse().ok();
+        }
+    }
+
+    Some((
+        window_id?,
+        WindowGeometry {
+            x: x?,
+            y: y?,
+            width: width?,
+            height: height?,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XWININFO_FIXTURE: &str = "
+xwininfo: Window id: 0x3a00007 \"Terminal\"
+
+  Absolute upper-left X:  -1800
+  Absolute upper-left Y:  64
+  Relative upper-left X:  0
+  Relative upper-left Y:  0
+  Width: 1280
+  Height: 720
+  Depth: 24
+  Visual: 0x21
+";
+
+    #[test]
+    fn xwininfo_parses_id_and_absolute_geometry() {
+        let (id, geometry) = parse_xwininfo(XWININFO_FIXTURE).expect("should parse");
+        assert_eq!(id, "0x3a00007");
+        assert_eq!(
+            geometry,
+            WindowGeometry {
+                x: -1800,
+                y: 64,
+                width: 1280,
+                height: 720,
+            }
+        );
+    }
+
+    #[test]
+    fn active_window_id_parses_xprop_output() {
+        assert_eq!(
+            parse_active_window_id("_NET_ACTIVE_WINDOW(WINDOW): window id # 0x3a00007\n")
+                .as_deref(),
+            Some("0x3a00007")
+        );
+        assert_eq!(
+            parse_active_window_id("_NET_ACTIVE_WINDOW(WINDOW): window id # 0x0, 0x0\n"),
+            None
+        );
+        assert_eq!(
+            parse_active_window_id("_NET_ACTIVE_WINDOW:  not found.\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn xwininfo_missing_fields_is_none() {
+        assert!(parse_xwininfo("xwininfo: Window id: 0x1 \"x\"\n  Width: 10\n").is_none());
+    }
+}
@@ -4,7 +4,7 @@
 //! Quartz input integrations planned for later milestones.
 
 use grabme_common::error::{GrabmeError, GrabmeResult};
-use grabme_platform_core::MonitorInfo;
+use grabme_platform_core::{MonitorInfo, WindowGeometry};
 
 /// Detect monitors on macOS.
 ///
@@ -15,6 +15,13 @@ pub fn detect_monitors() -> GrabmeResult<Vec<MonitorInfo>> {
     ))
 }
 
+/// Geometry of the focused window on macOS.
+///
+/// TODO(platform/macos): query the frontmost window via CGWindowListCopyWindowInfo.
+pub fn focused_window_geometry() -> GrabmeResult<Option<WindowGeometry>> {
+    Ok(None)
+}
+
 /// Placeholder for future ScreenCaptureKit support details.
 #[derive(Debug, Clone, Copy, Default)]
 pub struct ScreenCaptureKitSupport {
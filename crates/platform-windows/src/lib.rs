@@ -3,9 +3,13 @@
 use std::process::Command;
 
 use grabme_common::error::{GrabmeError, GrabmeResult};
-use grabme_platform_core::MonitorInfo;
+use grabme_platform_core::{MonitorInfo, WindowGeometry};
 use serde::Deserialize;
 
+pub mod session;
+
+pub use session::spawn_session_watcher;
+
 #[derive(Debug, Deserialize)]
 struct RawScreen {
     #[serde(rename = "Name")]
@@ -76,6 +80,54 @@ pub fn detect_monitors() -> GrabmeResult<Vec<MonitorInfo>> {
     Ok(monitors)
 }
 
+#[derive(Debug, Deserialize)]
+struct RawWindowRect {
+    #[serde(rename = "X")]
+    x: i32,
+    #[serde(rename = "Y")]
+    y: i32,
+    #[serde(rename = "Width")]
+    width: i32,
+    #[serde(rename = "Height")]
+    height: i32,
+}
+
+/// Geometry of the foreground window, or `None` if nothing has focus.
+pub fn focused_window_geometry() -> GrabmeResult<Option<WindowGeometry>> {
+    let script = r#"Add-Type @"
+using System;
+using System.Runtime.InteropServices;
+public struct GrabmeRect { public int Left; public int Top; public int Right; public int Bottom; }
+public static class GrabmeForeground {
+    [DllImport("user32.dll")] public static extern IntPtr GetForegroundWindow();
+    [DllImport("user32.dll")] public static extern bool GetWindowRect(IntPtr hWnd, out GrabmeRect rect);
+}
+"@
+$rect = New-Object GrabmeRect
+if ([GrabmeForeground]::GetWindowRect([GrabmeForeground]::GetForegroundWindow(), [ref]$rect)) {
+    [PSCustomObject]@{ X = $rect.Left; Y = $rect.Top; Width = $rect.Right - $rect.Left; Height = $rect.Bottom - $rect.Top } | ConvertTo-Json -Compress
+}"#;
+
+    let stdout = run_powershell(script).map_err(|e| {
+        GrabmeError::platform(format!(
+            "Failed to query foreground window via PowerShell: {e}"
+        ))
+    })?;
+    let rect: RawWindowRect = serde_json::from_str(&stdout).map_err(|e| {
+        GrabmeError::platform(format!("Failed to parse foreground window rect: {e}"))
+    })?;
+
+    if rect.width <= 0 || rect.height <= 0 {
+        return Ok(None);
+    }
+    Ok(Some(WindowGeometry {
+        x: rect.x,
+        y: rect.y,
+        width: rect.width as u32,
+        height: rect.height as u32,
+    }))
+}
+
 /// Runtime support hint for Windows Graphics Capture flow.
 #[derive(Debug, Clone, Copy, Default)]
 pub struct GraphicsCaptureSupport {
@@ -88,8 +140,10 @@ pub fn probe_graphics_capture_support() -> GraphicsCaptureSupport {
 }
 
 fn run_powershell_monitor_query() -> Result<String, String> {
-    let script = "Add-Type -AssemblyName System.Windows.Forms; [System.Windows.Forms.Screen]::AllScreens | ForEach-Object { [PSCustomObject]@{ Name = $_.DeviceName; Width = $_.Bounds.Width; Height = $_.Bounds.Height; X = $_.Bounds.X; Y = $_.Bounds.Y; Primary = $_.Primary } } | ConvertTo-Json -Compress";
+    run_powershell("Add-Type -AssemblyName System.Windows.Forms; [System.Windows.Forms.Screen]::AllScreens | ForEach-Object { [PSCustomObject]@{ Name = $_.DeviceName; Width = $_.Bounds.Width; Height = $_.Bounds.Height; X = $_.Bounds.X; Y = $_.Bounds.Y; Primary = $_.Primary } } | ConvertTo-Json -Compress")
+}
 
+fn run_powershell(script: &str) -> Result<String, String> {
     let shells = ["pwsh", "powershell"];
     let mut last_err = String::new();
 
@@ -104,7 +158,7 @@ fn run_powershell_monitor_query() -> Result<String, String> {
                 if !stdout.is_empty() {
                     return Ok(stdout);
                 }
-                last_err = format!("{shell} returned empty monitor list");
+                last_err = format!("{shell} returned no output");
             }
             Ok(output) => {
                 last_err = format!(
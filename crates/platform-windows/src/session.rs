@@ -0,0 +1,135 @@
+//! Screen lock and suspend notifications.
+//!
+//! Windows reports locks with `WM_WTSSESSION_CHANGE` to windows registered
+//! through `WTSRegisterSessionNotification`, and suspend and resume with
+//! `WM_POWERBROADCAST` to every top-level window. Both need a window and a
+//! message loop, so a Windows PowerShell helper keeps a hidden one and
+//! prints a line per change, which is read here.
+
+use std::process::Stdio;
+
+use grabme_platform_core::SystemSessionEvent;
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+/// The helper window. Windows PowerShell ships with every Windows and builds
+/// against the .NET Framework's WinForms, so no other runtime is needed.
+const SESSION_WATCHER_SCRIPT: &str = r#"Add-Type -ReferencedAssemblies System.Windows.Forms, System.Drawing @"
+using System;
+using System.Runtime.InteropServices;
+using System.Windows.Forms;
+public class GrabmeSessionWindow : Form {
+    [DllImport("wtsapi32.dll")] static extern bool WTSRegisterSessionNotification(IntPtr hWnd, int dwFlags);
+    const int WM_WTSSESSION_CHANGE = 0x02B1;
+    const int WM_POWERBROADCAST = 0x0218;
+    const int WTS_SESSION_LOCK = 0x7;
+    const int WTS_SESSION_UNLOCK = 0x8;
+    const int PBT_APMSUSPEND = 0x4;
+    const int PBT_APMRESUMESUSPEND = 0x7;
+    const int PBT_APMRESUMEAUTOMATIC = 0x12;
+    public GrabmeSessionWindow() {
+        ShowInTaskbar = false;
+        WTSRegisterSessionNotification(Handle, 0);
+    }
+    protected override void WndProc(ref Message m) {
+        if (m.Msg == WM_WTSSESSION_CHANGE) {
+            switch (m.WParam.ToInt32()) {
+                case WTS_SESSION_LOCK: Console.WriteLine("locked"); break;
+                case WTS_SESSION_UNLOCK: Console.WriteLine("unlocked"); break;
+            }
+        } else if (m.Msg == WM_POWERBROADCAST) {
+            switch (m.WParam.ToInt32()) {
+                case PBT_APMSUSPEND: Console.WriteLine("suspending"); break;
+                case PBT_APMRESUMESUSPEND:
+                case PBT_APMRESUMEAUTOMATIC: Console.WriteLine("resumed"); break;
+            }
+        }
+        base.WndProc(ref m);
+    }
+}
+"@
+$window = New-Object GrabmeSessionWindow
+[System.Windows.Forms.Application]::Run()"#;
+
+/// Report lock and suspend changes to `on_change` until the task is
+/// aborted or `on_change` returns `false`.
+///
+/// Without PowerShell the task ends right away and nothing is reported.
+pub fn spawn_session_watcher<F>(mut on_change: F) -> tokio::task::JoinHandle<()>
+where
+    F: FnMut(SystemSessionEvent) -> bool + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut child = match tokio::process::Command::new("powershell")
+            .args([
+                "-NoProfile",
+                "-NonInteractive",
+                "-STA",
+                "-Command",
+                SESSION_WATCHER_SCRIPT,
+            ])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(e) => {
+                tracing::debug!(error = %e, "PowerShell unavailable; not watching lock or suspend");
+                return;
+            }
+        };
+        let Some(stdout) = child.stdout.take() else {
+            return;
+        };
+
+        let mut lines = BufReader::new(stdout).lines();
+        let mut suspended = false;
+        while let Ok(Some(line)) = lines.next_line().await {
+            let Some(event) = parse_session_event(&line) else {
+                continue;
+            };
+            // Windows sends both resume broadcasts after a user wakes the
+            // system; pass on one.
+            match event {
+                SystemSessionEvent::Resumed if !suspended => continue,
+                SystemSessionEvent::Suspending => suspended = true,
+                SystemSessionEvent::Resumed => suspended = false,
+                _ => {}
+            }
+            tracing::info!(?event, "Session state changed");
+            if !on_change(event) {
+                break;
+            }
+        }
+        let _ = child.kill().await;
+    })
+}
+
+fn parse_session_event(line: &str) -> Option<SystemSessionEvent> {
+    match line.trim() {
+        "locked" => Some(SystemSessionEvent::Locked),
+        "unlocked" => Some(SystemSessionEvent::Unlocked),
+        "suspending" => Some(SystemSessionEvent::Suspending),
+        "resumed" => Some(SystemSessionEvent::Resumed),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_session_event_reads_helper_lines() {
+        assert_eq!(
+            parse_session_event("locked\r"),
+            Some(SystemSessionEvent::Locked)
+        );
+        assert_eq!(
+            parse_session_event("resumed"),
+            Some(SystemSessionEvent::Resumed)
+        );
+        assert_eq!(parse_session_event("WARNING: something"), None);
+    }
+}
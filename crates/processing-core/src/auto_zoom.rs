@@ -10,11 +10,25 @@
 //! 3. **Velocity** analysis: classify chunks as "hover" (zoom in) or "scan" (zoom out).
 //! 4. **Keyframe** generation: create viewport keyframes from centroid + velocity data.
 //! 5. **Smoothing** pass: apply moving average to prevent jerky camera motion.
+//! 6. **Wide shots**: pull out to the full screen for a moment when the
+//!    theme or brightness changes, so the viewer sees the whole switch.
+//!
+//! Dwell thresholds are normalized by default, which makes the same radius
+//! a much larger physical distance on a 32" 4K display than on a laptop.
+//! With [`DwellUnit::LogicalPx`] or [`DwellUnit::Millimeters`] they are
+//! resolved against the recorded display's [`DisplayMetrics`] instead.
+
+use std::str::FromStr;
 
+use grabme_platform_core::coords::CaptureNorm;
 use grabme_project_model::event::InputEvent;
+use grabme_project_model::project::RecordingConfig;
 use grabme_project_model::timeline::{CameraKeyframe, EasingFunction, KeyframeSource, Timeline};
 use grabme_project_model::viewport::Viewport;
 
+use crate::pass::{PassError, PassStage, TimelinePass};
+use crate::progress::{AnalysisProgressCallback, AnalysisStage, ProgressTicker};
+
 /// Configuration for the auto-zoom analyzer.
 #[derive(Debug, Clone)]
 pub struct AutoZoomConfig {
@@ -24,7 +38,7 @@ pub struct AutoZoomConfig {
     /// Minimum dwell time to trigger a zoom-in (seconds).
     pub dwell_threshold_secs: f64,
 
-    /// Maximum pointer spread (normalized) to consider "hovering".
+    /// Maximum pointer spread to consider "hovering", in `dwell_unit`.
     /// If the pointer stays within this radius, it's a dwell.
     pub dwell_radius: f64,
 
@@ -40,15 +54,26 @@ pub struct AutoZoomConfig {
     /// Minimum zoom level (viewport size). Prevents extreme zoom-in.
     pub min_viewport_size: f64,
 
-    /// Maximum velocity considered "dwell" (normalized units per second).
+    /// Maximum velocity considered "dwell" (`dwell_unit` per second).
     pub dwell_velocity_threshold: f64,
 
+    /// Unit of `dwell_radius` and `dwell_velocity_threshold`.
+    pub dwell_unit: DwellUnit,
+
+    /// Display the events were recorded on. Needed to resolve physical
+    /// dwell units; ignored for [`DwellUnit::Normalized`].
+    pub display: Option<DisplayMetrics>,
+
     /// Expected monitor count for ultra-wide or multi-monitor captures.
     /// Used with `focused_monitor_index` to keep analysis on one monitor region.
     pub monitor_count: usize,
 
     /// Zero-based monitor index to focus.
     pub focused_monitor_index: usize,
+
+    /// How long to hold a full-screen shot after a theme or brightness
+    /// change (seconds). `0.0` keeps the camera on its planned path.
+    pub appearance_wide_shot_secs: f64,
 }
 
 impl Default for AutoZoomConfig {
@@ -62,12 +87,137 @@ impl Default for AutoZoomConfig {
             smoothing_window: 3,
             min_viewport_size: 0.40,
             dwell_velocity_threshold: 0.18,
+            dwell_unit: DwellUnit::Normalized,
+            display: None,
             monitor_count: 1,
             focused_monitor_index: 0,
+            appearance_wide_shot_secs: 1.5,
+        }
+    }
+}
+
+/// Unit of the dwell radius and velocity thresholds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DwellUnit {
+    /// Fractions of the recorded frame.
+    #[default]
+    Normalized,
+    /// Logical pixels: physical pixels divided by the display scale factor.
+    LogicalPx,
+    /// Millimetres on screen, taking a logical pixel as 1/96 inch (the
+    /// density desktop scale factors aim for).
+    Millimeters,
+}
+
+impl DwellUnit {
+    /// Default `(radius, velocity)` thresholds in this unit. The physical
+    /// defaults match the normalized ones on a 1080p display at scale 1.
+    pub fn default_thresholds(self) -> (f64, f64) {
+        match self {
+            DwellUnit::Normalized => (0.15, 0.18),
+            DwellUnit::LogicalPx => (216.0, 260.0),
+            DwellUnit::Millimeters => (57.0, 69.0),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DwellUnit::Normalized => "normalized",
+            DwellUnit::LogicalPx => "px",
+            DwellUnit::Millimeters => "mm",
+        }
+    }
+}
+
+impl FromStr for DwellUnit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "normalized" | "norm" => Ok(DwellUnit::Normalized),
+            "px" | "logical-px" | "logical" => Ok(DwellUnit::LogicalPx),
+            "mm" | "millimeters" | "millimetres" => Ok(DwellUnit::Millimeters),
+            other => Err(format!(
+                "Unknown dwell unit: {other}. Use: normalized, px, mm"
+            )),
+        }
+    }
+}
+
+/// Logical pixels per millimetre at 96 logical pixels per inch.
+const LOGICAL_PX_PER_MM: f64 = 96.0 / 25.4;
+
+/// Size and scale of the frame pointer positions are normalized against.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DisplayMetrics {
+    /// Frame size in physical pixels.
+    pub width_px: u32,
+    pub height_px: u32,
+    /// Desktop scale factor (physical pixels per logical pixel).
+    pub scale_factor: f64,
+}
+
+impl DisplayMetrics {
+    /// Metrics of the screen track of a recording. `None` when the capture
+    /// size is unknown.
+    pub fn from_recording(recording: &RecordingConfig) -> Option<Self> {
+        (recording.capture_width > 0 && recording.capture_height > 0).then_some(Self {
+            width_px: recording.capture_width,
+            height_px: recording.capture_height,
+            scale_factor: recording.scale_factor,
+        })
+    }
+
+    /// Convert a distance in `unit` to normalized frame units.
+    ///
+    /// Normalized x and y have different pixel lengths on a non-square
+    /// frame, so pixel distances are measured against the geometric mean
+    /// of width and height.
+    pub fn normalize(&self, value: f64, unit: DwellUnit) -> f64 {
+        let scale = if self.scale_factor > 0.0 {
+            self.scale_factor
+        } else {
+            1.0
+        };
+        let physical_px = match unit {
+            DwellUnit::Normalized => return value,
+            DwellUnit::LogicalPx => value * scale,
+            DwellUnit::Millimeters => value * LOGICAL_PX_PER_MM * scale,
+        };
+        let frame_px = (self.width_px as f64 * self.height_px as f64).sqrt();
+        if frame_px > 0.0 {
+            physical_px / frame_px
+        } else {
+            value
         }
     }
 }
 
+/// As a pass, auto-zoom replaces the camera path and keeps everything else.
+impl TimelinePass for AutoZoomAnalyzer {
+    fn name(&self) -> &str {
+        "auto-zoom"
+    }
+
+    fn stage(&self) -> PassStage {
+        PassStage::Camera
+    }
+
+    fn run(&self, events: &[InputEvent], mut timeline: Timeline) -> Result<Timeline, PassError> {
+        timeline.keyframes = self.analyze(events).keyframes;
+        Ok(timeline)
+    }
+}
+
+/// Time the camera takes to pull out before an appearance change.
+const WIDE_SHOT_LEAD_SECS: f64 = 0.3;
+
+/// Time the camera takes to return to its planned path after a wide shot.
+const WIDE_SHOT_RETURN_SECS: f64 = 0.6;
+
+/// Pointer sample rate at which event density no longer lowers confidence.
+const FULL_CONFIDENCE_SAMPLES_PER_SEC: f64 = 20.0;
+
 /// Analysis result for a single time chunk.
 #[derive(Debug, Clone)]
 pub struct ChunkAnalysis {
@@ -76,7 +226,7 @@ pub struct ChunkAnalysis {
     /// End time of the chunk (seconds).
     pub end_secs: f64,
     /// Average pointer position (centroid).
-    pub centroid: (f64, f64),
+    pub centroid: CaptureNorm,
     /// Maximum distance from centroid (spread).
     pub spread: f64,
     /// Average pointer velocity (normalized units per second).
@@ -101,12 +251,45 @@ pub enum ActivityType {
 /// The auto-zoom analyzer.
 pub struct AutoZoomAnalyzer {
     config: AutoZoomConfig,
+    /// Dwell radius in normalized units.
+    dwell_radius: f64,
+    /// Dwell velocity threshold in normalized units per second.
+    dwell_velocity: f64,
+    progress: Option<AnalysisProgressCallback>,
 }
 
 impl AutoZoomAnalyzer {
     /// Create a new analyzer with the given configuration.
+    ///
+    /// Physical dwell thresholds are resolved against `config.display`
+    /// here. Without a display the normalized defaults are used.
     pub fn new(config: AutoZoomConfig) -> Self {
-        Self { config }
+        let (dwell_radius, dwell_velocity) = match (config.dwell_unit, config.display) {
+            (DwellUnit::Normalized, _) => (config.dwell_radius, config.dwell_velocity_threshold),
+            (unit, Some(display)) => (
+                display.normalize(config.dwell_radius, unit),
+                display.normalize(config.dwell_velocity_threshold, unit),
+            ),
+            (unit, None) => {
+                tracing::warn!(
+                    unit = unit.as_str(),
+                    "Display size unknown; using normalized dwell thresholds"
+                );
+                DwellUnit::Normalized.default_thresholds()
+            }
+        };
+        Self {
+            config,
+            dwell_radius,
+            dwell_velocity,
+            progress: None,
+        }
+    }
+
+    /// Report progress through event chunks to `callback`.
+    pub fn with_progress(mut self, callback: AnalysisProgressCallback) -> Self {
+        self.progress = Some(callback);
+        self
     }
 
     /// Create an analyzer with default configuration.
@@ -127,7 +310,7 @@ impl AutoZoomAnalyzer {
         let smoothed = self.smooth_keyframes(&raw_keyframes);
 
         let mut timeline = Timeline::new();
-        timeline.keyframes = smoothed;
+        timeline.keyframes = self.insert_appearance_wide_shots(smoothed, events);
 
         (timeline, chunks)
     }
@@ -149,15 +332,22 @@ impl AutoZoomAnalyzer {
 
         let mut chunks = vec![];
         let mut chunk_start = start_ns;
+        let total_chunks = (end_ns - start_ns).div_ceil(chunk_ns.max(1)) as usize;
+        let mut ticker = ProgressTicker::start(
+            self.progress.as_ref(),
+            AnalysisStage::AutoZoom,
+            total_chunks,
+        );
 
         while chunk_start < end_ns {
             let chunk_end = chunk_start + chunk_ns;
 
             // Collect pointer positions in this chunk
-            let positions: Vec<(f64, f64)> = focused_events
+            let positions: Vec<CaptureNorm> = focused_events
                 .iter()
                 .filter(|e| e.timestamp_ns >= chunk_start && e.timestamp_ns < chunk_end)
                 .filter_map(|e| e.pointer_position())
+                .map(|(x, y)| CaptureNorm::new(x, y))
                 .collect();
 
             let start_secs = (chunk_start - start_ns) as f64 / 1e9;
@@ -167,7 +357,7 @@ impl AutoZoomAnalyzer {
                 chunks.push(ChunkAnalysis {
                     start_secs,
                     end_secs,
-                    centroid: (0.5, 0.5),
+                    centroid: CaptureNorm::new(0.5, 0.5),
                     spread: 0.0,
                     velocity: 0.0,
                     sample_count: 0,
@@ -178,9 +368,7 @@ impl AutoZoomAnalyzer {
                 let spread = Self::compute_spread(&positions, centroid);
                 let velocity = Self::compute_velocity(&positions, self.config.chunk_duration_secs);
 
-                let activity = if spread <= self.config.dwell_radius
-                    && velocity <= self.config.dwell_velocity_threshold
-                {
+                let activity = if spread <= self.dwell_radius && velocity <= self.dwell_velocity {
                     ActivityType::Dwell
                 } else {
                     ActivityType::Scan
@@ -198,7 +386,9 @@ impl AutoZoomAnalyzer {
             }
 
             chunk_start = chunk_end;
+            ticker.tick(chunks.len());
         }
+        ticker.finish();
 
         chunks
     }
@@ -236,8 +426,8 @@ impl AutoZoomAnalyzer {
                 .clamp(self.config.min_viewport_size, 1.0);
 
             let viewport = Viewport::centered(
-                chunk.centroid.0,
-                chunk.centroid.1,
+                chunk.centroid.x,
+                chunk.centroid.y,
                 viewport_size,
                 viewport_size,
             );
@@ -247,6 +437,7 @@ impl AutoZoomAnalyzer {
                 viewport,
                 easing: EasingFunction::EaseInOut,
                 source: KeyframeSource::Auto,
+                confidence: Some(self.chunk_confidence(chunk)),
             };
 
             if let Some(existing) = keyframes.last_mut() {
@@ -269,9 +460,11 @@ impl AutoZoomAnalyzer {
                 viewport: Viewport::FULL,
                 easing: EasingFunction::EaseInOut,
                 source: KeyframeSource::Auto,
+                confidence: None,
             });
         } else if keyframes[0].time_secs > 0.0 {
             let first_viewport = keyframes[0].viewport;
+            let first_confidence = keyframes[0].confidence;
             keyframes.insert(
                 0,
                 CameraKeyframe {
@@ -279,6 +472,7 @@ impl AutoZoomAnalyzer {
                     viewport: first_viewport,
                     easing: EasingFunction::EaseInOut,
                     source: KeyframeSource::Auto,
+                    confidence: first_confidence,
                 },
             );
         }
@@ -286,6 +480,35 @@ impl AutoZoomAnalyzer {
         keyframes
     }
 
+    /// Score how trustworthy the framing generated from `chunk` is.
+    ///
+    /// Combines how clearly the chunk cleared the dwell/scan thresholds
+    /// (a chunk sitting right on the boundary could have gone either way)
+    /// with how many pointer samples back the decision.
+    pub fn chunk_confidence(&self, chunk: &ChunkAnalysis) -> f64 {
+        let radius = self.dwell_radius.max(1e-6);
+        let velocity = self.dwell_velocity.max(1e-6);
+        let strength = match chunk.activity {
+            ActivityType::Dwell => {
+                let spread_margin = 1.0 - chunk.spread / radius;
+                let velocity_margin = 1.0 - chunk.velocity / velocity;
+                (spread_margin.clamp(0.0, 1.0) + velocity_margin.clamp(0.0, 1.0)) / 2.0
+            }
+            ActivityType::Scan => {
+                let spread_margin = chunk.spread / radius - 1.0;
+                let velocity_margin = chunk.velocity / velocity - 1.0;
+                spread_margin.max(velocity_margin).clamp(0.0, 1.0)
+            }
+            ActivityType::Idle => 0.0,
+        };
+
+        let chunk_secs = (chunk.end_secs - chunk.start_secs).max(1e-6);
+        let density =
+            (chunk.sample_count as f64 / (chunk_secs * FULL_CONFIDENCE_SAMPLES_PER_SEC)).min(1.0);
+
+        (strength * (0.5 + 0.5 * density)).clamp(0.0, 1.0)
+    }
+
     /// Smooth keyframes using a moving average on viewport parameters.
     fn smooth_keyframes(&self, keyframes: &[CameraKeyframe]) -> Vec<CameraKeyframe> {
         if keyframes.len() <= 2 || self.config.smoothing_window <= 1 {
@@ -325,6 +548,7 @@ impl AutoZoomAnalyzer {
                 viewport,
                 easing: keyframes[i].easing,
                 source: KeyframeSource::Auto,
+                confidence: keyframes[i].confidence,
             });
         }
 
@@ -336,32 +560,91 @@ impl AutoZoomAnalyzer {
         smoothed
     }
 
+    /// Replace the camera path around theme and brightness changes with a
+    /// full-screen shot held for `appearance_wide_shot_secs`.
+    fn insert_appearance_wide_shots(
+        &self,
+        keyframes: Vec<CameraKeyframe>,
+        events: &[InputEvent],
+    ) -> Vec<CameraKeyframe> {
+        let hold = self.config.appearance_wide_shot_secs;
+        if hold <= 0.0 {
+            return keyframes;
+        }
+
+        // Changes close enough for their transitions to overlap share a shot.
+        let mut shots: Vec<(f64, f64)> = Vec::new();
+        for event in events.iter().filter(|e| e.is_appearance_change()) {
+            let t = event.timestamp_secs();
+            match shots.last_mut() {
+                Some((_, end)) if t - WIDE_SHOT_LEAD_SECS <= *end + WIDE_SHOT_RETURN_SECS => {
+                    *end = end.max(t + hold);
+                }
+                _ => shots.push((t, t + hold)),
+            }
+        }
+        if shots.is_empty() {
+            return keyframes;
+        }
+
+        let mut planned = Timeline::new();
+        planned.keyframes = keyframes;
+        let keyframe = |time_secs: f64, viewport: Viewport| CameraKeyframe {
+            time_secs,
+            viewport,
+            easing: EasingFunction::EaseInOut,
+            source: KeyframeSource::Auto,
+            confidence: None,
+        };
+
+        let mut keyframes: Vec<CameraKeyframe> = planned
+            .keyframes
+            .iter()
+            .filter(|kf| {
+                !shots.iter().any(|(start, end)| {
+                    kf.time_secs >= start - WIDE_SHOT_LEAD_SECS
+                        && kf.time_secs <= end + WIDE_SHOT_RETURN_SECS
+                })
+            })
+            .cloned()
+            .collect();
+        for &(start, end) in &shots {
+            let lead = (start - WIDE_SHOT_LEAD_SECS).max(0.0);
+            if lead < start {
+                keyframes.push(keyframe(lead, planned.viewport_at(lead)));
+            }
+            keyframes.push(keyframe(start, Viewport::FULL));
+            keyframes.push(keyframe(end, Viewport::FULL));
+            let back = end + WIDE_SHOT_RETURN_SECS;
+            keyframes.push(keyframe(back, planned.viewport_at(back)));
+        }
+        keyframes.sort_by(|a, b| a.time_secs.total_cmp(&b.time_secs));
+        keyframes
+    }
+
     /// Compute the centroid (average position) of a set of points.
-    fn compute_centroid(positions: &[(f64, f64)]) -> (f64, f64) {
+    fn compute_centroid(positions: &[CaptureNorm]) -> CaptureNorm {
         let n = positions.len() as f64;
-        let sum_x: f64 = positions.iter().map(|(x, _)| x).sum();
-        let sum_y: f64 = positions.iter().map(|(_, y)| y).sum();
-        (sum_x / n, sum_y / n)
+        let sum_x: f64 = positions.iter().map(|p| p.x).sum();
+        let sum_y: f64 = positions.iter().map(|p| p.y).sum();
+        CaptureNorm::new(sum_x / n, sum_y / n)
     }
 
     /// Compute the maximum spread (distance from centroid).
-    fn compute_spread(positions: &[(f64, f64)], centroid: (f64, f64)) -> f64 {
+    fn compute_spread(positions: &[CaptureNorm], centroid: CaptureNorm) -> f64 {
         positions
             .iter()
-            .map(|(x, y)| ((x - centroid.0).powi(2) + (y - centroid.1).powi(2)).sqrt())
+            .map(|p| p.distance_to(&centroid))
             .fold(0.0_f64, f64::max)
     }
 
     /// Compute average velocity (normalized units per second).
-    fn compute_velocity(positions: &[(f64, f64)], duration_secs: f64) -> f64 {
+    fn compute_velocity(positions: &[CaptureNorm], duration_secs: f64) -> f64 {
         if positions.len() < 2 || duration_secs <= 0.0 {
             return 0.0;
         }
 
-        let total_distance: f64 = positions
-            .windows(2)
-            .map(|w| ((w[1].0 - w[0].0).powi(2) + (w[1].1 - w[0].1).powi(2)).sqrt())
-            .sum();
+        let total_distance: f64 = positions.windows(2).map(|w| w[1].distance_to(&w[0])).sum();
 
         total_distance / duration_secs
     }
@@ -394,7 +677,7 @@ impl AutoZoomAnalyzer {
     }
 }
 
-fn ensure_anchor_visible(viewport: Viewport, anchor: (f64, f64), padding_ratio: f64) -> Viewport {
+fn ensure_anchor_visible(viewport: Viewport, anchor: CaptureNorm, padding_ratio: f64) -> Viewport {
     let mut x = viewport.x;
     let mut y = viewport.y;
     let w = viewport.w;
@@ -403,18 +686,18 @@ fn ensure_anchor_visible(viewport: Viewport, anchor: (f64, f64), padding_ratio:
     let pad = padding_ratio.clamp(0.0, 0.45);
     let inner_left = x + w * pad;
     let inner_right = x + w * (1.0 - pad);
-    if anchor.0 < inner_left {
-        x -= inner_left - anchor.0;
-    } else if anchor.0 > inner_right {
-        x += anchor.0 - inner_right;
+    if anchor.x < inner_left {
+        x -= inner_left - anchor.x;
+    } else if anchor.x > inner_right {
+        x += anchor.x - inner_right;
     }
 
     let inner_top = y + h * pad;
     let inner_bottom = y + h * (1.0 - pad);
-    if anchor.1 < inner_top {
-        y -= inner_top - anchor.1;
-    } else if anchor.1 > inner_bottom {
-        y += anchor.1 - inner_bottom;
+    if anchor.y < inner_top {
+        y -= inner_top - anchor.y;
+    } else if anchor.y > inner_bottom {
+        y += anchor.y - inner_bottom;
     }
 
     Viewport::new(x, y, w, h)
@@ -476,12 +759,60 @@ mod tests {
         assert_eq!(chunks[0].activity, ActivityType::Scan);
     }
 
+    #[test]
+    fn test_chunking_reports_progress() {
+        let events: Vec<InputEvent> = (0..=40)
+            .map(|i| InputEvent::pointer(i * 250_000_000, 0.2, 0.2))
+            .collect();
+        let seen = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let analyzer = AutoZoomAnalyzer::with_defaults().with_progress({
+            let seen = std::sync::Arc::clone(&seen);
+            std::sync::Arc::new(move |progress| seen.lock().unwrap().push(progress))
+        });
+        let chunks = analyzer.chunk_events(&events);
+
+        let seen = seen.lock().unwrap();
+        // Start, one report per chunk but the last, and the end.
+        assert_eq!(seen.len(), chunks.len() + 1);
+        assert!(seen.iter().all(|p| p.stage == AnalysisStage::AutoZoom));
+        assert_eq!(seen.first().map(|p| p.fraction), Some(0.0));
+        assert_eq!(seen.last().map(|p| p.fraction), Some(1.0));
+    }
+
+    #[test]
+    fn test_appearance_change_inserts_wide_shot() {
+        let mut events: Vec<InputEvent> = (0..=40)
+            .map(|i| InputEvent::pointer(i * 250_000_000, 0.2, 0.2))
+            .collect();
+        events.push(InputEvent::color_scheme(
+            5_000_000_000,
+            grabme_project_model::event::ColorScheme::Dark,
+        ));
+        events.push(InputEvent::brightness(5_500_000_000, 0.4));
+        events.sort_by_key(|e| e.timestamp_ns);
+
+        let analyzer = AutoZoomAnalyzer::with_defaults();
+        let timeline = analyzer.analyze(&events);
+        assert!(timeline.viewport_at(4.0).w < 1.0);
+        assert_eq!(timeline.viewport_at(5.0), Viewport::FULL);
+        // The second change extends the shot instead of starting another.
+        assert_eq!(timeline.viewport_at(6.9), Viewport::FULL);
+        assert_eq!(timeline.viewport_at(7.6), timeline.viewport_at(9.0));
+        assert!(timeline.viewport_at(7.6).w < 1.0);
+
+        let analyzer = AutoZoomAnalyzer::new(AutoZoomConfig {
+            appearance_wide_shot_secs: 0.0,
+            ..Default::default()
+        });
+        assert!(analyzer.analyze(&events).viewport_at(5.0).w < 1.0);
+    }
+
     #[test]
     fn test_centroid_calculation() {
-        let positions = vec![(0.0, 0.0), (1.0, 1.0)];
+        let positions = vec![CaptureNorm::new(0.0, 0.0), CaptureNorm::new(1.0, 1.0)];
         let centroid = AutoZoomAnalyzer::compute_centroid(&positions);
-        assert!((centroid.0 - 0.5).abs() < 1e-9);
-        assert!((centroid.1 - 0.5).abs() < 1e-9);
+        assert!((centroid.x - 0.5).abs() < 1e-9);
+        assert!((centroid.y - 0.5).abs() < 1e-9);
     }
 
     #[test]
@@ -515,24 +846,28 @@ mod tests {
                 viewport: Viewport::FULL,
                 easing: EasingFunction::Linear,
                 source: KeyframeSource::Auto,
+                confidence: None,
             },
             CameraKeyframe {
                 time_secs: 2.0,
                 viewport: Viewport::new(0.1, 0.1, 0.5, 0.5),
                 easing: EasingFunction::Linear,
                 source: KeyframeSource::Auto,
+                confidence: None,
             },
             CameraKeyframe {
                 time_secs: 4.0,
                 viewport: Viewport::new(0.3, 0.3, 0.4, 0.4),
                 easing: EasingFunction::Linear,
                 source: KeyframeSource::Auto,
+                confidence: None,
             },
             CameraKeyframe {
                 time_secs: 6.0,
                 viewport: Viewport::new(0.5, 0.5, 0.5, 0.5),
                 easing: EasingFunction::Linear,
                 source: KeyframeSource::Auto,
+                confidence: None,
             },
         ];
 
@@ -561,7 +896,7 @@ mod tests {
 
         let chunks = analyzer.chunk_events(&events);
         assert!(!chunks.is_empty());
-        assert!(chunks.iter().all(|chunk| chunk.centroid.0 <= 0.5));
+        assert!(chunks.iter().all(|chunk| chunk.centroid.x <= 0.5));
     }
 
     #[test]
@@ -592,7 +927,7 @@ mod tests {
         let chunks = vec![ChunkAnalysis {
             start_secs: 0.0,
             end_secs: 2.0,
-            centroid: (0.5, 0.5),
+            centroid: CaptureNorm::new(0.5, 0.5),
             spread: 0.3,
             velocity: 0.4,
             sample_count: 30,
@@ -616,23 +951,116 @@ mod tests {
                 viewport: Viewport::new(0.0, 0.0, 0.4, 0.4),
                 easing: EasingFunction::Linear,
                 source: KeyframeSource::Auto,
+                confidence: None,
             },
             CameraKeyframe {
                 time_secs: 2.0,
                 viewport: Viewport::new(0.8, 0.0, 0.4, 0.4),
                 easing: EasingFunction::Linear,
                 source: KeyframeSource::Auto,
+                confidence: None,
             },
             CameraKeyframe {
                 time_secs: 4.0,
                 viewport: Viewport::new(0.0, 0.0, 0.4, 0.4),
                 easing: EasingFunction::Linear,
                 source: KeyframeSource::Auto,
+                confidence: None,
             },
         ];
 
         let smoothed = analyzer.smooth_keyframes(&keyframes);
         let anchor = keyframes[1].viewport.center();
-        assert!(smoothed[1].viewport.contains(anchor.0, anchor.1));
+        assert!(smoothed[1].viewport.contains(anchor));
+    }
+
+    #[test]
+    fn test_physical_dwell_thresholds_follow_display() {
+        let laptop = DisplayMetrics {
+            width_px: 1920,
+            height_px: 1080,
+            scale_factor: 1.0,
+        };
+        let hidpi = DisplayMetrics {
+            scale_factor: 2.0,
+            width_px: 3840,
+            height_px: 2160,
+        };
+        let large = DisplayMetrics {
+            scale_factor: 1.0,
+            ..hidpi
+        };
+        assert!((laptop.normalize(216.0, DwellUnit::LogicalPx) - 0.15).abs() < 1e-9);
+        assert!((hidpi.normalize(216.0, DwellUnit::LogicalPx) - 0.15).abs() < 1e-9);
+        assert!((large.normalize(216.0, DwellUnit::LogicalPx) - 0.075).abs() < 1e-9);
+        assert!((laptop.normalize(25.4, DwellUnit::Millimeters) - 96.0 / 1440.0).abs() < 1e-9);
+        assert_eq!(large.normalize(0.2, DwellUnit::Normalized), 0.2);
+
+        // The same 0.1 spread is a dwell with normalized thresholds, but
+        // twice the physical distance of the radius on a large 4K display.
+        let events = make_pointer_events(&[
+            (0, 0.5, 0.5),
+            (1_000_000_000, 0.65, 0.5),
+            (1_900_000_000, 0.65, 0.5),
+        ]);
+        let normalized = AutoZoomAnalyzer::with_defaults();
+        assert_eq!(
+            normalized.chunk_events(&events)[0].activity,
+            ActivityType::Dwell
+        );
+        let (radius, velocity) = DwellUnit::LogicalPx.default_thresholds();
+        let physical = AutoZoomAnalyzer::new(AutoZoomConfig {
+            dwell_radius: radius,
+            dwell_velocity_threshold: velocity,
+            dwell_unit: DwellUnit::LogicalPx,
+            display: Some(large),
+            ..Default::default()
+        });
+        assert_eq!(
+            physical.chunk_events(&events)[0].activity,
+            ActivityType::Scan
+        );
+    }
+
+    #[test]
+    fn test_chunk_confidence_rewards_clear_dense_dwell() {
+        let analyzer = AutoZoomAnalyzer::with_defaults();
+        let chunk = |spread: f64, velocity: f64, sample_count: usize, activity| ChunkAnalysis {
+            start_secs: 0.0,
+            end_secs: 2.0,
+            centroid: CaptureNorm::new(0.5, 0.5),
+            spread,
+            velocity,
+            sample_count,
+            activity,
+        };
+
+        let clear = analyzer.chunk_confidence(&chunk(0.0, 0.0, 40, ActivityType::Dwell));
+        let borderline = analyzer.chunk_confidence(&chunk(0.14, 0.17, 40, ActivityType::Dwell));
+        let sparse = analyzer.chunk_confidence(&chunk(0.0, 0.0, 4, ActivityType::Dwell));
+        let clear_scan = analyzer.chunk_confidence(&chunk(0.6, 1.0, 40, ActivityType::Scan));
+
+        assert!((clear - 1.0).abs() < 1e-9);
+        assert!(borderline < 0.1);
+        assert!((sparse - 0.55).abs() < 1e-9);
+        assert!((clear_scan - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_generated_keyframes_carry_confidence() {
+        let events = make_pointer_events(&[
+            (0, 0.1, 0.1),
+            (500_000_000, 0.1, 0.1),
+            (1_000_000_000, 0.1, 0.1),
+            (2_000_000_000, 0.1, 0.1),
+            (3_000_000_000, 0.9, 0.9),
+            (4_000_000_000, 0.9, 0.9),
+        ]);
+
+        let timeline = AutoZoomAnalyzer::with_defaults().analyze(&events);
+        assert!(timeline
+            .keyframes
+            .iter()
+            .all(|kf| kf.confidence.is_some_and(|c| (0.0..=1.0).contains(&c))));
     }
 }
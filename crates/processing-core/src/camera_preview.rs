@@ -74,12 +74,14 @@ mod tests {
                 viewport: Viewport::FULL,
                 easing: EasingFunction::Linear,
                 source: KeyframeSource::Auto,
+                confidence: None,
             },
             CameraKeyframe {
                 time_secs: 2.0,
                 viewport: Viewport::new(0.25, 0.25, 0.5, 0.5),
                 easing: EasingFunction::Linear,
                 source: KeyframeSource::Auto,
+                confidence: None,
             },
         ];
 
@@ -0,0 +1,151 @@
+//! Click-burst aggregation.
+//!
+//! Double and triple clicks (or a quick run of clicks on the same control)
+//! should read as one emphasis on screen, not a pulse per click. Clicks are
+//! merged into a burst while each follows the previous one within the burst
+//! window and stays near where the burst started; the camera then makes a
+//! single move that holds for the whole burst.
+
+use grabme_project_model::event::{ButtonState, EventKind, InputEvent, MouseButton};
+
+/// Configuration for click-burst aggregation.
+#[derive(Debug, Clone)]
+pub struct ClickBurstConfig {
+    /// Maximum gap between consecutive clicks of one burst (seconds).
+    pub window_secs: f64,
+
+    /// Maximum distance (normalized) from the burst's first click for a
+    /// click to join it. Clicks further away start a new burst.
+    pub max_radius: f64,
+
+    /// Mouse button whose presses are aggregated.
+    pub button: MouseButton,
+}
+
+impl Default for ClickBurstConfig {
+    fn default() -> Self {
+        Self {
+            window_secs: 0.5,
+            max_radius: 0.05,
+            button: MouseButton::Left,
+        }
+    }
+}
+
+/// A run of clicks merged into one emphasis.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClickBurst {
+    /// Timestamp of the first press (nanoseconds).
+    pub start_ns: u64,
+    /// Timestamp of the last press (nanoseconds).
+    pub end_ns: u64,
+    /// Mean position of the presses.
+    pub x: f64,
+    pub y: f64,
+    /// Number of presses in the burst.
+    pub click_count: usize,
+}
+
+impl ClickBurst {
+    /// Time from the first to the last press, in seconds. A camera hold
+    /// should last at least this long so later clicks stay in frame.
+    pub fn duration_secs(&self) -> f64 {
+        self.end_ns.saturating_sub(self.start_ns) as f64 / 1e9
+    }
+}
+
+/// Group button presses in `events` into bursts, in time order.
+pub fn aggregate_click_bursts(events: &[InputEvent], config: &ClickBurstConfig) -> Vec<ClickBurst> {
+    let window_ns = (config.window_secs.max(0.0) * 1e9) as u64;
+    let mut bursts: Vec<ClickBurst> = Vec::new();
+    // Position of the first press of the open burst.
+    let mut anchor = (0.0, 0.0);
+
+    for event in events {
+        let EventKind::Click {
+            button,
+            state: ButtonState::Down,
+            x,
+            y,
+        } = event.kind
+        else {
+            continue;
+        };
+        if button != config.button {
+            continue;
+        }
+
+        if let Some(burst) = bursts.last_mut() {
+            let gap = event.timestamp_ns.saturating_sub(burst.end_ns);
+            let distance = ((x - anchor.0).powi(2) + (y - anchor.1).powi(2)).sqrt();
+            if gap <= window_ns && distance <= config.max_radius {
+                let n = burst.click_count as f64;
+                burst.x = (burst.x * n + x) / (n + 1.0);
+                burst.y = (burst.y * n + y) / (n + 1.0);
+                burst.end_ns = event.timestamp_ns;
+                burst.click_count += 1;
+                continue;
+            }
+        }
+
+        anchor = (x, y);
+        bursts.push(ClickBurst {
+            start_ns: event.timestamp_ns,
+            end_ns: event.timestamp_ns,
+            x,
+            y,
+            click_count: 1,
+        });
+    }
+
+    bursts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn down(ms: u64, x: f64, y: f64) -> InputEvent {
+        InputEvent::click(ms * 1_000_000, MouseButton::Left, ButtonState::Down, x, y)
+    }
+
+    #[test]
+    fn test_double_and_triple_clicks_merge() {
+        let events = vec![
+            down(1000, 0.50, 0.50),
+            InputEvent::click(1_040_000_000, MouseButton::Left, ButtonState::Up, 0.5, 0.5),
+            down(1200, 0.51, 0.50),
+            down(1400, 0.50, 0.51),
+            down(3000, 0.50, 0.50),
+        ];
+        let bursts = aggregate_click_bursts(&events, &ClickBurstConfig::default());
+
+        assert_eq!(bursts.len(), 2);
+        assert_eq!(bursts[0].click_count, 3);
+        assert!((bursts[0].duration_secs() - 0.4).abs() < 1e-9);
+        assert!((bursts[0].x - 0.503333).abs() < 1e-5);
+        assert_eq!(bursts[1].click_count, 1);
+        assert_eq!(bursts[1].duration_secs(), 0.0);
+    }
+
+    #[test]
+    fn test_distant_or_other_button_clicks_do_not_merge() {
+        let events = vec![
+            down(1000, 0.2, 0.2),
+            down(1100, 0.8, 0.8),
+            InputEvent::click(
+                1_150_000_000,
+                MouseButton::Right,
+                ButtonState::Down,
+                0.8,
+                0.8,
+            ),
+            down(1200, 0.8, 0.8),
+        ];
+        let bursts = aggregate_click_bursts(&events, &ClickBurstConfig::default());
+
+        assert_eq!(bursts.len(), 2);
+        assert_eq!(bursts[0].click_count, 1);
+        assert_eq!(bursts[1].click_count, 2);
+    }
+}
@@ -7,9 +7,12 @@ use grabme_project_model::event::InputEvent;
 use grabme_project_model::timeline::CursorConfig;
 use grabme_project_model::viewport::Point2D;
 
+use crate::progress::{AnalysisProgressCallback, AnalysisStage, ProgressTicker};
+
 /// Cursor smoothing engine.
 pub struct CursorSmoother {
     algorithm: SmoothingAlgorithm,
+    progress: Option<AnalysisProgressCallback>,
 }
 
 /// Available smoothing algorithms.
@@ -40,7 +43,16 @@ pub enum SmoothingAlgorithm {
 impl CursorSmoother {
     /// Create a smoother with the given algorithm.
     pub fn new(algorithm: SmoothingAlgorithm) -> Self {
-        Self { algorithm }
+        Self {
+            algorithm,
+            progress: None,
+        }
+    }
+
+    /// Report progress through pointer samples to `callback`.
+    pub fn with_progress(mut self, callback: AnalysisProgressCallback) -> Self {
+        self.progress = Some(callback);
+        self
     }
 
     /// Create a smoother with sensible defaults (EMA, strength=0.3).
@@ -74,15 +86,23 @@ impl CursorSmoother {
             .filter_map(|e| e.pointer_position().map(|(x, y)| (e.timestamp_ns, x, y)))
             .collect();
 
-        match self.algorithm {
-            SmoothingAlgorithm::Ema { strength } => self.smooth_ema(&raw, strength),
-            SmoothingAlgorithm::Bezier { strength } => self.smooth_bezier(&raw, strength),
-            SmoothingAlgorithm::Kalman { strength } => self.smooth_kalman(&raw, strength),
+        let mut ticker =
+            ProgressTicker::start(self.progress.as_ref(), AnalysisStage::Smoothing, raw.len());
+        let smoothed = match self.algorithm {
+            SmoothingAlgorithm::Ema { strength } => self.smooth_ema(&raw, strength, &mut ticker),
+            SmoothingAlgorithm::Bezier { strength } => {
+                self.smooth_bezier(&raw, strength, &mut ticker)
+            }
+            SmoothingAlgorithm::Kalman { strength } => {
+                self.smooth_kalman(&raw, strength, &mut ticker)
+            }
             SmoothingAlgorithm::MovingAverage { window } => {
-                self.smooth_moving_average(&raw, window)
+                self.smooth_moving_average(&raw, window, &mut ticker)
             }
             SmoothingAlgorithm::None => raw,
-        }
+        };
+        ticker.finish();
+        smoothed
     }
 
     /// Get a smoothed position at a specific time using interpolation.
@@ -126,10 +146,76 @@ impl CursorSmoother {
         Some(Point2D::new(x0 + (x1 - x0) * t, y0 + (y1 - y0) * t))
     }
 
+    /// Like [`position_at`](Self::position_at), but follows a Catmull-Rom
+    /// curve through the neighbouring samples instead of straight segments,
+    /// so positions between samples keep the cursor's velocity.
+    ///
+    /// Segments longer than `max_gap_ns` stay straight, and their samples
+    /// don't shape the curve next to them: a pointer that sat still must not
+    /// swing out before it moves.
+    pub fn position_at_catmull_rom(
+        smoothed_data: &[(u64, f64, f64)],
+        timestamp_ns: u64,
+        max_gap_ns: u64,
+    ) -> Option<Point2D> {
+        let first = smoothed_data.first()?;
+        let last = smoothed_data.last()?;
+        if timestamp_ns <= first.0 || timestamp_ns >= last.0 {
+            return Self::position_at(smoothed_data, timestamp_ns);
+        }
+
+        let idx = smoothed_data
+            .partition_point(|(t, _, _)| *t <= timestamp_ns)
+            .saturating_sub(1);
+        let (t1, x1, y1) = smoothed_data[idx];
+        let Some(&(t2, x2, y2)) = smoothed_data.get(idx + 1) else {
+            return Some(Point2D::new(x1, y1));
+        };
+        let span = t2.saturating_sub(t1);
+        if span == 0 || span > max_gap_ns {
+            return Self::position_at(smoothed_data, timestamp_ns);
+        }
+
+        let close = |a: u64, b: u64| b > a && b - a <= max_gap_ns;
+        let before = idx
+            .checked_sub(1)
+            .map(|i| smoothed_data[i])
+            .filter(|p| close(p.0, t1));
+        let after = smoothed_data
+            .get(idx + 2)
+            .copied()
+            .filter(|p| close(t2, p.0));
+
+        // Tangents per nanosecond, one-sided where a neighbour is missing.
+        let slope = |a: (u64, f64, f64), b: (u64, f64, f64)| {
+            let dt = (b.0 - a.0) as f64;
+            ((b.1 - a.1) / dt, (b.2 - a.2) / dt)
+        };
+        let m1 = slope(before.unwrap_or((t1, x1, y1)), (t2, x2, y2));
+        let m2 = slope((t1, x1, y1), after.unwrap_or((t2, x2, y2)));
+
+        let h = span as f64;
+        let s = (timestamp_ns - t1) as f64 / h;
+        let (s2, s3) = (s * s, s * s * s);
+        let h00 = 2.0 * s3 - 3.0 * s2 + 1.0;
+        let h10 = s3 - 2.0 * s2 + s;
+        let h01 = -2.0 * s3 + 3.0 * s2;
+        let h11 = s3 - s2;
+        Some(Point2D::new(
+            h00 * x1 + h10 * h * m1.0 + h01 * x2 + h11 * h * m2.0,
+            h00 * y1 + h10 * h * m1.1 + h01 * y2 + h11 * h * m2.1,
+        ))
+    }
+
     /// EMA smoothing using preview-compatible strength semantics.
     ///
     /// `alpha = 1 - strength`, then `smoothed = alpha * current + (1 - alpha) * previous`.
-    fn smooth_ema(&self, raw: &[(u64, f64, f64)], strength: f64) -> Vec<(u64, f64, f64)> {
+    fn smooth_ema(
+        &self,
+        raw: &[(u64, f64, f64)],
+        strength: f64,
+        ticker: &mut ProgressTicker,
+    ) -> Vec<(u64, f64, f64)> {
         if raw.is_empty() {
             return vec![];
         }
@@ -145,13 +231,19 @@ impl CursorSmoother {
             prev_x = alpha * x + (1.0 - alpha) * prev_x;
             prev_y = alpha * y + (1.0 - alpha) * prev_y;
             result.push((t, prev_x, prev_y));
+            ticker.tick(result.len());
         }
 
         result
     }
 
     /// Neighbor midpoint pull smoothing compatible with preview "bezier" mode.
-    fn smooth_bezier(&self, raw: &[(u64, f64, f64)], strength: f64) -> Vec<(u64, f64, f64)> {
+    fn smooth_bezier(
+        &self,
+        raw: &[(u64, f64, f64)],
+        strength: f64,
+        ticker: &mut ProgressTicker,
+    ) -> Vec<(u64, f64, f64)> {
         if raw.len() < 3 {
             return raw.to_vec();
         }
@@ -170,6 +262,7 @@ impl CursorSmoother {
             let x = curr.1 * (1.0 - pull) + cx * pull;
             let y = curr.2 * (1.0 - pull) + cy * pull;
             result.push((curr.0, x, y));
+            ticker.tick(i);
         }
 
         result.push(*raw.last().unwrap());
@@ -177,7 +270,12 @@ impl CursorSmoother {
     }
 
     /// Kalman smoothing compatible with preview "kalman" mode.
-    fn smooth_kalman(&self, raw: &[(u64, f64, f64)], strength: f64) -> Vec<(u64, f64, f64)> {
+    fn smooth_kalman(
+        &self,
+        raw: &[(u64, f64, f64)],
+        strength: f64,
+        ticker: &mut ProgressTicker,
+    ) -> Vec<(u64, f64, f64)> {
         if raw.is_empty() {
             return vec![];
         }
@@ -206,6 +304,7 @@ impl CursorSmoother {
             py *= 1.0 - ky;
 
             result.push((t, x, y));
+            ticker.tick(result.len());
         }
 
         result
@@ -216,6 +315,7 @@ impl CursorSmoother {
         &self,
         raw: &[(u64, f64, f64)],
         window: usize,
+        ticker: &mut ProgressTicker,
     ) -> Vec<(u64, f64, f64)> {
         if raw.is_empty() || window == 0 {
             return raw.to_vec();
@@ -232,6 +332,7 @@ impl CursorSmoother {
             let sum_y: f64 = raw[start..end].iter().map(|(_, _, y)| y).sum();
 
             result.push((raw[i].0, sum_x / count, sum_y / count));
+            ticker.tick(i);
         }
 
         result
@@ -335,6 +436,42 @@ mod tests {
         assert!((mid.y - 0.5).abs() < 1e-9);
     }
 
+    #[test]
+    fn test_catmull_rom_follows_curve_through_samples() {
+        // Samples on y = x^2 every 16ms.
+        let data: Vec<(u64, f64, f64)> = (0..6u64)
+            .map(|i| {
+                let x = i as f64 * 0.1;
+                (i * 16_000_000, x, x * x)
+            })
+            .collect();
+
+        let at_sample =
+            CursorSmoother::position_at_catmull_rom(&data, 32_000_000, 100_000_000).unwrap();
+        assert!((at_sample.x - 0.2).abs() < 1e-9);
+        assert!((at_sample.y - 0.04).abs() < 1e-9);
+
+        // Halfway between x=0.2 and x=0.3 the curve is much closer to 0.0625
+        // than the straight segment (0.065).
+        let mid = CursorSmoother::position_at_catmull_rom(&data, 40_000_000, 100_000_000).unwrap();
+        let linear = CursorSmoother::position_at(&data, 40_000_000).unwrap();
+        assert!((mid.x - 0.25).abs() < 1e-9);
+        assert!((mid.y - 0.0625).abs() < (linear.y - 0.0625).abs());
+    }
+
+    #[test]
+    fn test_catmull_rom_keeps_long_gaps_straight() {
+        let data = vec![
+            (0u64, 0.0, 0.0),
+            (16_000_000, 0.5, 0.0),
+            (2_000_000_000, 0.5, 0.0),
+            (2_016_000_000, 0.5, 0.5),
+        ];
+        let still =
+            CursorSmoother::position_at_catmull_rom(&data, 1_000_000_000, 100_000_000).unwrap();
+        assert_eq!((still.x, still.y), (0.5, 0.0));
+    }
+
     #[test]
     fn test_cursor_loop() {
         let smoothed = vec![
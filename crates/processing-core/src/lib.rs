@@ -2,18 +2,27 @@
 //!
 //! Analyzes input event streams to generate automated editing decisions:
 //! - **Auto-Zoom:** Detect activity regions and generate camera keyframes
+//! - **Click Bursts:** Merge rapid repeated clicks into one emphasis
 //! - **Cursor Smoothing:** Apply motion smoothing algorithms to pointer data
+//! - **Pacing:** Measure narration speed to time click emphasis
 //! - **Vertical Framing:** Generate 9:16 viewport that follows cursor
+//! - **Passes:** Chain built-in and third-party timeline passes
+//! - **Progress:** Report how far long analyses have got
 //!
 //! This crate is pure computation — no I/O, no platform dependencies.
 //! All inputs are data; all outputs are data.
 
 pub mod auto_zoom;
 pub mod camera_preview;
+pub mod click_burst;
 pub mod cursor_smooth;
 pub mod heatmap;
+pub mod pacing;
+pub mod pass;
+pub mod progress;
 pub mod vertical;
 
 pub use auto_zoom::AutoZoomAnalyzer;
 pub use camera_preview::simulate_camera_motion;
 pub use cursor_smooth::CursorSmoother;
+pub use pass::{PassRegistry, PassStage, TimelinePass};
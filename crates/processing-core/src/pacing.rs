@@ -0,0 +1,151 @@
+//! Narration pace.
+//!
+//! Fast narration in a rapid demo wants quick cuts; a slow tutorial wants
+//! the camera to linger. Speech rate around a moment is measured from the
+//! transcript and turned into click-emphasis timing, scaled against a
+//! typical speaking rate.
+
+/// Speaking rate the default click timing is tuned for (words per minute).
+pub const REFERENCE_WORDS_PER_MINUTE: f64 = 150.0;
+
+/// Seconds of narration on each side of a moment used to measure the pace.
+pub const DEFAULT_PACE_WINDOW_SECS: f64 = 15.0;
+
+/// A transcribed stretch of speech.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpeechSpan {
+    pub start_secs: f64,
+    pub end_secs: f64,
+    pub words: usize,
+}
+
+/// Speech rate over a recording, from its transcript segments.
+#[derive(Debug, Clone, Default)]
+pub struct NarrationPace {
+    spans: Vec<SpeechSpan>,
+}
+
+impl NarrationPace {
+    /// Build from `(start_secs, end_secs, text)` transcript segments.
+    /// Segments without words or with no duration are ignored.
+    pub fn from_segments<'a>(segments: impl IntoIterator<Item = (f64, f64, &'a str)>) -> Self {
+        let mut spans: Vec<SpeechSpan> = segments
+            .into_iter()
+            .filter(|(start, end, _)| end > start)
+            .map(|(start_secs, end_secs, text)| SpeechSpan {
+                start_secs,
+                end_secs,
+                words: text.split_whitespace().count(),
+            })
+            .filter(|span| span.words > 0)
+            .collect();
+        spans.sort_by(|a, b| a.start_secs.total_cmp(&b.start_secs));
+        Self { spans }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    /// Words per minute of the speech within `window_secs` of `time_secs`.
+    /// Only time spent speaking counts, so pauses between sentences don't
+    /// read as slow narration. `None` when nobody speaks in the window.
+    pub fn words_per_minute_around(&self, time_secs: f64, window_secs: f64) -> Option<f64> {
+        let from = time_secs - window_secs.max(0.0);
+        let to = time_secs + window_secs.max(0.0);
+        let mut words = 0.0;
+        let mut speaking_secs = 0.0;
+        for span in &self.spans {
+            let overlap = span.end_secs.min(to) - span.start_secs.max(from);
+            if overlap <= 0.0 {
+                continue;
+            }
+            let duration = span.end_secs - span.start_secs;
+            words += span.words as f64 * overlap / duration;
+            speaking_secs += overlap;
+        }
+        (speaking_secs > 0.0).then(|| words * 60.0 / speaking_secs)
+    }
+
+    /// Click timing for the narration around `time_secs`, or `default`
+    /// when there is no speech nearby.
+    pub fn click_pacing_at(&self, time_secs: f64, default: ClickPacing) -> ClickPacing {
+        self.words_per_minute_around(time_secs, DEFAULT_PACE_WINDOW_SECS)
+            .map(|wpm| default.scaled_to(wpm))
+            .unwrap_or(default)
+    }
+}
+
+/// Timing of click emphasis.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClickPacing {
+    /// Minimum time between two click zooms (seconds).
+    pub cooldown_secs: f64,
+    /// How long the zoom holds after the last click of a burst (seconds).
+    pub hold_secs: f64,
+}
+
+impl ClickPacing {
+    /// This timing adjusted for narration at `words_per_minute`. Timing
+    /// scales inversely with the rate relative to
+    /// [`REFERENCE_WORDS_PER_MINUTE`], bounded to between half and double.
+    pub fn scaled_to(self, words_per_minute: f64) -> Self {
+        if !words_per_minute.is_finite() || words_per_minute <= 0.0 {
+            return self;
+        }
+        let factor = (REFERENCE_WORDS_PER_MINUTE / words_per_minute).clamp(0.5, 2.0);
+        Self {
+            cooldown_secs: self.cooldown_secs * factor,
+            hold_secs: self.hold_secs * factor,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEFAULT: ClickPacing = ClickPacing {
+        cooldown_secs: 3.0,
+        hold_secs: 0.08,
+    };
+
+    fn words(n: usize) -> String {
+        vec!["word"; n].join(" ")
+    }
+
+    #[test]
+    fn test_words_per_minute_ignores_pauses() {
+        let ten = words(10);
+        // 10 words in 4s, a long pause, then 10 more in 4s: 150 wpm.
+        let pace = NarrationPace::from_segments([(0.0, 4.0, ten.as_str()), (20.0, 24.0, &ten)]);
+        let wpm = pace.words_per_minute_around(12.0, 15.0).unwrap();
+        assert!((wpm - 150.0).abs() < 1e-9, "wpm = {wpm}");
+        assert_eq!(pace.words_per_minute_around(100.0, 15.0), None);
+    }
+
+    #[test]
+    fn test_partial_segments_count_proportionally() {
+        let pace = NarrationPace::from_segments([(0.0, 10.0, words(20).as_str())]);
+        // Half the segment falls in the window: 10 words over 5s.
+        let wpm = pace.words_per_minute_around(0.0, 5.0).unwrap();
+        assert!((wpm - 120.0).abs() < 1e-9, "wpm = {wpm}");
+    }
+
+    #[test]
+    fn test_fast_narration_tightens_click_timing() {
+        let fast = DEFAULT.scaled_to(300.0);
+        assert!((fast.cooldown_secs - 1.5).abs() < 1e-9);
+        let slow = DEFAULT.scaled_to(50.0);
+        assert!((slow.cooldown_secs - 6.0).abs() < 1e-9);
+        assert!((slow.hold_secs - 0.16).abs() < 1e-9);
+        assert_eq!(DEFAULT.scaled_to(0.0), DEFAULT);
+    }
+
+    #[test]
+    fn test_no_speech_keeps_default_timing() {
+        let pace = NarrationPace::from_segments([(0.0, 2.0, "  "), (5.0, 5.0, "hello")]);
+        assert!(pace.is_empty());
+        assert_eq!(pace.click_pacing_at(1.0, DEFAULT), DEFAULT);
+    }
+}
@@ -0,0 +1,443 @@
+//! Timeline passes: the Auto-Director's extension point.
+//!
+//! A [`TimelinePass`] takes the input event stream and the timeline built so
+//! far and returns a modified timeline. The built-in camera styles (auto-zoom,
+//! vertical, production) are passes, and third parties can add their own —
+//! in Rust, or as WebAssembly modules with the `wasm-plugins` feature (see
+//! [`wasm`]).
+//!
+//! Passes run in [`PassStage`] order; passes in the same stage run in the
+//! order they were registered.
+
+use grabme_project_model::event::InputEvent;
+use grabme_project_model::timeline::Timeline;
+
+/// When a pass runs relative to the others.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PassStage {
+    /// Builds the camera path. Camera passes usually replace the keyframes.
+    Camera,
+    /// Adjusts an existing camera path (extra emphasis, cleanups).
+    Refine,
+    /// Last-word passes: validation, clamping, metadata.
+    Finalize,
+}
+
+impl PassStage {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PassStage::Camera => "camera",
+            PassStage::Refine => "refine",
+            PassStage::Finalize => "finalize",
+        }
+    }
+}
+
+/// Errors raised while running a pass.
+#[derive(Debug, thiserror::Error)]
+pub enum PassError {
+    #[error("pass '{pass}' failed: {message}")]
+    Failed { pass: String, message: String },
+
+    #[error("plugin '{pass}' could not be loaded: {message}")]
+    Plugin { pass: String, message: String },
+}
+
+/// A step of the Auto-Director pipeline.
+pub trait TimelinePass {
+    /// Short identifier shown in logs and errors.
+    fn name(&self) -> &str;
+
+    /// Stage the pass belongs to.
+    fn stage(&self) -> PassStage {
+        PassStage::Refine
+    }
+
+    /// Produce the next timeline from the events and the current timeline.
+    fn run(&self, events: &[InputEvent], timeline: Timeline) -> Result<Timeline, PassError>;
+}
+
+/// Ordered set of passes.
+#[derive(Default)]
+pub struct PassRegistry<'a> {
+    passes: Vec<Box<dyn TimelinePass + 'a>>,
+}
+
+impl<'a> PassRegistry<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a pass. It runs after every pass of an earlier stage and after
+    /// passes of its own stage registered before it.
+    pub fn register(&mut self, pass: impl TimelinePass + 'a) -> &mut Self {
+        self.register_boxed(Box::new(pass))
+    }
+
+    pub fn register_boxed(&mut self, pass: Box<dyn TimelinePass + 'a>) -> &mut Self {
+        let stage = pass.stage();
+        let index = self
+            .passes
+            .iter()
+            .position(|existing| existing.stage() > stage)
+            .unwrap_or(self.passes.len());
+        self.passes.insert(index, pass);
+        self
+    }
+
+    /// Pass names in execution order.
+    pub fn names(&self) -> Vec<&str> {
+        self.passes.iter().map(|pass| pass.name()).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.passes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.passes.is_empty()
+    }
+
+    /// Run every pass in order, threading the timeline through them.
+    pub fn run(
+        &self,
+        events: &[InputEvent],
+        mut timeline: Timeline,
+    ) -> Result<Timeline, PassError> {
+        for pass in &self.passes {
+            tracing::debug!(
+                pass = pass.name(),
+                stage = pass.stage().as_str(),
+                "Running pass"
+            );
+            timeline = pass.run(events, timeline)?;
+        }
+        Ok(timeline)
+    }
+}
+
+#[cfg(feature = "wasm-plugins")]
+pub mod wasm {
+    //! Timeline passes loaded from WebAssembly modules.
+    //!
+    //! A plugin module must export:
+    //! - `memory`: its linear memory;
+    //! - `alloc(len: i32) -> i32`: reserve `len` bytes and return the offset;
+    //! - `run(events_ptr: i32, events_len: i32, timeline_ptr: i32, timeline_len: i32) -> i64`.
+    //!
+    //! The host copies the events (a JSON array of [`InputEvent`]) and the
+    //! current timeline (JSON) into plugin memory and calls `run`, which
+    //! returns the output timeline JSON as `(ptr << 32) | len`. Plugins get
+    //! no imports, so they can't touch the filesystem or network. A fuel
+    //! budget stops modules that never return, and a memory cap stops
+    //! modules that grow without bound.
+
+    use super::*;
+
+    use wasmi::{
+        Config, Engine, Linker, Memory, Module, Store, StoreLimits, StoreLimitsBuilder, TypedFunc,
+    };
+
+    /// Instructions a plugin may execute per run.
+    pub const DEFAULT_FUEL: u64 = 5_000_000_000;
+
+    /// Bytes of linear memory a plugin may use per run.
+    pub const DEFAULT_MEMORY_LIMIT: usize = 256 * 1024 * 1024;
+
+    /// A pass backed by a WebAssembly module.
+    pub struct WasmPass {
+        name: String,
+        stage: PassStage,
+        engine: Engine,
+        module: Module,
+        fuel: u64,
+        memory_limit: usize,
+    }
+
+    impl WasmPass {
+        /// Compile a plugin from its `.wasm` bytes.
+        pub fn from_bytes(name: impl Into<String>, wasm: &[u8]) -> Result<Self, PassError> {
+            let name = name.into();
+            let mut config = Config::default();
+            config.consume_fuel(true);
+            let engine = Engine::new(&config);
+            let module = Module::new(&engine, wasm).map_err(|e| PassError::Plugin {
+                pass: name.clone(),
+                message: e.to_string(),
+            })?;
+            Ok(Self {
+                name,
+                stage: PassStage::Refine,
+                engine,
+                module,
+                fuel: DEFAULT_FUEL,
+                memory_limit: DEFAULT_MEMORY_LIMIT,
+            })
+        }
+
+        pub fn with_stage(mut self, stage: PassStage) -> Self {
+            self.stage = stage;
+            self
+        }
+
+        pub fn with_fuel(mut self, fuel: u64) -> Self {
+            self.fuel = fuel;
+            self
+        }
+
+        pub fn with_memory_limit(mut self, bytes: usize) -> Self {
+            self.memory_limit = bytes;
+            self
+        }
+
+        fn fail(&self, message: impl ToString) -> PassError {
+            PassError::Failed {
+                pass: self.name.clone(),
+                message: message.to_string(),
+            }
+        }
+
+        fn copy_in(
+            &self,
+            store: &mut Store<StoreLimits>,
+            memory: Memory,
+            alloc: &TypedFunc<i32, i32>,
+            bytes: &[u8],
+        ) -> Result<(i32, i32), PassError> {
+            let len = i32::try_from(bytes.len()).map_err(|_| self.fail("input too large"))?;
+            let ptr = alloc
+                .call(&mut *store, len)
+                .map_err(|e| self.fail(format!("alloc trapped: {e}")))?;
+            memory
+                .write(&mut *store, ptr as u32 as usize, bytes)
+                .map_err(|e| self.fail(format!("alloc returned an invalid offset: {e}")))?;
+            Ok((ptr, len))
+        }
+    }
+
+    impl TimelinePass for WasmPass {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        fn stage(&self) -> PassStage {
+            self.stage
+        }
+
+        fn run(&self, events: &[InputEvent], timeline: Timeline) -> Result<Timeline, PassError> {
+            let events_json = serde_json::to_vec(events).map_err(|e| self.fail(e))?;
+            let timeline_json = serde_json::to_vec(&timeline).map_err(|e| self.fail(e))?;
+
+            let limits = StoreLimitsBuilder::new()
+                .memory_size(self.memory_limit)
+                .build();
+            let mut store = Store::new(&self.engine, limits);
+            store.limiter(|limits| limits);
+            store.set_fuel(self.fuel).map_err(|e| self.fail(e))?;
+            let instance = Linker::<StoreLimits>::new(&self.engine)
+                .instantiate(&mut store, &self.module)
+                .and_then(|pre| pre.start(&mut store))
+                .map_err(|e| self.fail(format!("instantiation failed: {e}")))?;
+
+            let memory = instance
+                .get_memory(&store, "memory")
+                .ok_or_else(|| self.fail("module does not export 'memory'"))?;
+            let alloc = instance
+                .get_typed_func::<i32, i32>(&store, "alloc")
+                .map_err(|e| self.fail(format!("missing 'alloc' export: {e}")))?;
+            let run = instance
+                .get_typed_func::<(i32, i32, i32, i32), i64>(&store, "run")
+                .map_err(|e| self.fail(format!("missing 'run' export: {e}")))?;
+
+            let (events_ptr, events_len) =
+                self.copy_in(&mut store, memory, &alloc, &events_json)?;
+            let (timeline_ptr, timeline_len) =
+                self.copy_in(&mut store, memory, &alloc, &timeline_json)?;
+
+            let packed =
+                run.call(
+                    &mut store,
+                    (events_ptr, events_len, timeline_ptr, timeline_len),
+                )
+                .map_err(|e| self.fail(format!("run trapped: {e}")))? as u64;
+            let out_ptr = (packed >> 32) as usize;
+            let out_len = (packed & 0xffff_ffff) as usize;
+
+            // Check the range before touching it: the length is the plugin's
+            // word and can claim up to 4 GiB.
+            let data = memory.data(&store);
+            let output = out_ptr
+                .checked_add(out_len)
+                .filter(|&end| end <= data.len())
+                .map(|end| &data[out_ptr..end])
+                .ok_or_else(|| {
+                    self.fail(format!(
+                        "run returned {out_len} bytes at {out_ptr}, outside its {} byte memory",
+                        data.len()
+                    ))
+                })?;
+            serde_json::from_slice(output)
+                .map_err(|e| self.fail(format!("run returned an invalid timeline: {e}")))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use grabme_project_model::timeline::{CameraKeyframe, EasingFunction, KeyframeSource};
+    use grabme_project_model::viewport::Viewport;
+
+    struct Push {
+        name: &'static str,
+        stage: PassStage,
+    }
+
+    impl TimelinePass for Push {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn stage(&self) -> PassStage {
+            self.stage
+        }
+
+        fn run(
+            &self,
+            _events: &[InputEvent],
+            mut timeline: Timeline,
+        ) -> Result<Timeline, PassError> {
+            timeline.keyframes.push(CameraKeyframe {
+                time_secs: timeline.keyframes.len() as f64,
+                viewport: Viewport::FULL,
+                easing: EasingFunction::EaseInOut,
+                source: KeyframeSource::Auto,
+                confidence: None,
+            });
+            Ok(timeline)
+        }
+    }
+
+    #[test]
+    fn test_passes_run_in_stage_then_registration_order() {
+        let mut registry = PassRegistry::new();
+        registry
+            .register(Push {
+                name: "finish",
+                stage: PassStage::Finalize,
+            })
+            .register(Push {
+                name: "refine-a",
+                stage: PassStage::Refine,
+            })
+            .register(Push {
+                name: "camera",
+                stage: PassStage::Camera,
+            })
+            .register(Push {
+                name: "refine-b",
+                stage: PassStage::Refine,
+            });
+
+        assert_eq!(
+            registry.names(),
+            vec!["camera", "refine-a", "refine-b", "finish"]
+        );
+        let seeded = Timeline::new().keyframes.len();
+        let timeline = registry.run(&[], Timeline::new()).unwrap();
+        assert_eq!(timeline.keyframes.len(), seeded + 4);
+    }
+
+    #[test]
+    fn test_auto_zoom_pass_replaces_keyframes() {
+        let events: Vec<InputEvent> = (0..40)
+            .map(|i| InputEvent::pointer(i * 100_000_000, 0.3, 0.3))
+            .collect();
+        let mut registry = PassRegistry::new();
+        registry.register(crate::AutoZoomAnalyzer::with_defaults());
+
+        let mut seeded = Timeline::new();
+        seeded.keyframes.push(CameraKeyframe {
+            time_secs: 99.0,
+            viewport: Viewport::FULL,
+            easing: EasingFunction::Linear,
+            source: KeyframeSource::Manual,
+            confidence: None,
+        });
+        let timeline = registry.run(&events, seeded).unwrap();
+        let expected = crate::AutoZoomAnalyzer::with_defaults().analyze(&events);
+        assert_eq!(timeline.keyframes, expected.keyframes);
+    }
+
+    #[cfg(feature = "wasm-plugins")]
+    #[test]
+    fn test_wasm_pass_round_trips_timeline() {
+        // Bump allocator; `run` hands back the timeline it was given.
+        let wasm = wat::parse_str(
+            r#"(module
+                (memory (export "memory") 4)
+                (global $next (mut i32) (i32.const 16))
+                (func (export "alloc") (param $len i32) (result i32)
+                    (local $ptr i32)
+                    (local.set $ptr (global.get $next))
+                    (global.set $next (i32.add (global.get $next) (local.get $len)))
+                    (local.get $ptr))
+                (func (export "run") (param i32 i32 i32 i32) (result i64)
+                    (i64.or
+                        (i64.shl (i64.extend_i32_u (local.get 2)) (i64.const 32))
+                        (i64.extend_i32_u (local.get 3)))))"#,
+        )
+        .unwrap();
+        let pass = wasm::WasmPass::from_bytes("echo", &wasm).unwrap();
+
+        let mut timeline = Timeline::new();
+        timeline.keyframes.push(CameraKeyframe {
+            time_secs: 1.5,
+            viewport: Viewport::new(0.1, 0.1, 0.5, 0.5),
+            easing: EasingFunction::EaseOut,
+            source: KeyframeSource::Auto,
+            confidence: Some(0.75),
+        });
+        let out = pass
+            .run(&[InputEvent::pointer(0, 0.5, 0.5)], timeline.clone())
+            .unwrap();
+        assert_eq!(out.keyframes, timeline.keyframes);
+    }
+
+    #[cfg(feature = "wasm-plugins")]
+    #[test]
+    fn test_wasm_pass_output_must_lie_in_its_memory() {
+        // `run` claims 4 GiB - 1 bytes of output at offset 16.
+        let wasm = wat::parse_str(
+            r#"(module
+                (memory (export "memory") 1)
+                (func (export "alloc") (param i32) (result i32) (i32.const 16))
+                (func (export "run") (param i32 i32 i32 i32) (result i64)
+                    (i64.const 0x10ffffffff)))"#,
+        )
+        .unwrap();
+        let pass = wasm::WasmPass::from_bytes("liar", &wasm).unwrap();
+        let err = pass.run(&[], Timeline::new()).unwrap_err();
+        assert!(err.to_string().contains("outside"), "{err}");
+    }
+
+    #[cfg(feature = "wasm-plugins")]
+    #[test]
+    fn test_wasm_pass_memory_is_capped() {
+        let wasm = wat::parse_str(r#"(module (memory (export "memory") 2))"#).unwrap();
+        let pass = wasm::WasmPass::from_bytes("greedy", &wasm)
+            .unwrap()
+            .with_memory_limit(64 * 1024);
+        let err = pass.run(&[], Timeline::new()).unwrap_err();
+        assert!(err.to_string().contains("instantiation failed"), "{err}");
+    }
+
+    #[cfg(feature = "wasm-plugins")]
+    #[test]
+    fn test_wasm_pass_without_exports_fails() {
+        let wasm = wat::parse_str("(module)").unwrap();
+        let pass = wasm::WasmPass::from_bytes("empty", &wasm).unwrap();
+        let err = pass.run(&[], Timeline::new()).unwrap_err();
+        assert!(err.to_string().contains("memory"), "{err}");
+    }
+}
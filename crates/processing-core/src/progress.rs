@@ -0,0 +1,115 @@
+//! Progress reports from long analyses.
+//!
+//! Smoothing and auto-zoom over a multi-minute event stream take long enough
+//! that callers want to show they are still moving. Both take an optional
+//! callback and report the fraction of their stage done, at most once per
+//! percent.
+
+use std::sync::Arc;
+
+/// Progress callback for analyses.
+pub type AnalysisProgressCallback = Arc<dyn Fn(AnalysisProgress) + Send + Sync>;
+
+/// Analysis progress report.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AnalysisProgress {
+    /// Stage the report is for.
+    pub stage: AnalysisStage,
+    /// Fraction of the stage done [0.0, 1.0].
+    pub fraction: f64,
+}
+
+/// Stages of an analysis that report progress.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnalysisStage {
+    /// Smoothing pointer positions.
+    Smoothing,
+    /// Classifying event chunks for auto-zoom.
+    AutoZoom,
+}
+
+impl AnalysisStage {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AnalysisStage::Smoothing => "smoothing",
+            AnalysisStage::AutoZoom => "auto_zoom",
+        }
+    }
+}
+
+/// Calls the callback as work goes by: once at the start, on every percent,
+/// and once at the end.
+pub(crate) struct ProgressTicker<'a> {
+    callback: Option<&'a AnalysisProgressCallback>,
+    stage: AnalysisStage,
+    total: usize,
+    step: usize,
+    next: usize,
+}
+
+impl<'a> ProgressTicker<'a> {
+    pub(crate) fn start(
+        callback: Option<&'a AnalysisProgressCallback>,
+        stage: AnalysisStage,
+        total: usize,
+    ) -> Self {
+        let step = (total / 100).max(1);
+        let ticker = Self {
+            callback,
+            stage,
+            total,
+            step,
+            next: step,
+        };
+        ticker.report(0.0);
+        ticker
+    }
+
+    /// Record that `done` of the total items are finished.
+    pub(crate) fn tick(&mut self, done: usize) {
+        if done >= self.next && done < self.total {
+            self.next = done + self.step;
+            self.report(done as f64 / self.total as f64);
+        }
+    }
+
+    pub(crate) fn finish(self) {
+        self.report(1.0);
+    }
+
+    fn report(&self, fraction: f64) {
+        if let Some(callback) = self.callback {
+            callback(AnalysisProgress {
+                stage: self.stage,
+                fraction,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_ticker_reports_start_each_percent_and_end() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let callback: AnalysisProgressCallback = {
+            let seen = Arc::clone(&seen);
+            Arc::new(move |progress| seen.lock().unwrap().push(progress.fraction))
+        };
+        let mut ticker = ProgressTicker::start(Some(&callback), AnalysisStage::Smoothing, 1000);
+        for done in 0..1000 {
+            ticker.tick(done);
+        }
+        ticker.finish();
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 101);
+        assert_eq!(seen[0], 0.0);
+        assert_eq!(seen[1], 0.01);
+        assert_eq!(*seen.last().unwrap(), 1.0);
+        assert!(seen.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+}
@@ -4,9 +4,11 @@
 //! keeping it centered while maintaining stable framing.
 
 use grabme_project_model::event::InputEvent;
-use grabme_project_model::timeline::{CameraKeyframe, EasingFunction, KeyframeSource};
+use grabme_project_model::timeline::{CameraKeyframe, EasingFunction, KeyframeSource, Timeline};
 use grabme_project_model::viewport::Viewport;
 
+use crate::pass::{PassError, PassStage, TimelinePass};
+
 /// Configuration for vertical mode processing.
 #[derive(Debug, Clone)]
 pub struct VerticalConfig {
@@ -50,6 +52,7 @@ pub fn generate_vertical_timeline(
             viewport: Viewport::vertical_centered(0.5, 0.5, config.viewport_height),
             easing: EasingFunction::EaseInOut,
             source: KeyframeSource::Auto,
+            confidence: None,
         }];
     }
 
@@ -79,12 +82,34 @@ pub fn generate_vertical_timeline(
             viewport,
             easing: EasingFunction::EaseInOut,
             source: KeyframeSource::Auto,
+            confidence: None,
         });
     }
 
     keyframes
 }
 
+/// Vertical framing as a camera pass.
+#[derive(Debug, Clone, Default)]
+pub struct VerticalPass {
+    pub config: VerticalConfig,
+}
+
+impl TimelinePass for VerticalPass {
+    fn name(&self) -> &str {
+        "vertical"
+    }
+
+    fn stage(&self) -> PassStage {
+        PassStage::Camera
+    }
+
+    fn run(&self, events: &[InputEvent], mut timeline: Timeline) -> Result<Timeline, PassError> {
+        timeline.keyframes = generate_vertical_timeline(events, &self.config);
+        Ok(timeline)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
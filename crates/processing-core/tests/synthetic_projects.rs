@@ -0,0 +1,86 @@
+use grabme_processing_core::auto_zoom::AutoZoomAnalyzer;
+use grabme_processing_core::cursor_smooth::CursorSmoother;
+use grabme_project_model::event::{ButtonState, EventKind, EventStream, InputEvent};
+use grabme_testkit::{EventPattern, ProjectFixture, TestDir};
+
+#[test]
+fn auto_zoom_follows_clicks_read_from_a_bundle() {
+    let dir = TestDir::new("auto-zoom-clicks");
+    let project = ProjectFixture::new("clicks")
+        .with_duration_secs(12.0)
+        .with_events(EventPattern::Clicks { every_secs: 3.0 })
+        .build(&dir.path().join("clicks"))
+        .unwrap();
+    let events: Vec<InputEvent> =
+        EventStream::open(&project.root.join("meta").join("events.jsonl"))
+            .unwrap()
+            .map(Result::unwrap)
+            .collect();
+
+    let timeline = AutoZoomAnalyzer::with_defaults().analyze(&events);
+
+    assert!(!timeline.keyframes.is_empty());
+    assert!(timeline.keyframes.iter().all(|kf| kf.viewport.w < 1.0));
+    // The camera heads for each click before it lands.
+    for event in &events {
+        if let EventKind::Click {
+            state: ButtonState::Down,
+            x,
+            y,
+            ..
+        } = event.kind
+        {
+            let t = event.timestamp_ns as f64 / 1e9;
+            let keyframe = timeline
+                .keyframes
+                .iter()
+                .rev()
+                .find(|kf| kf.time_secs < t)
+                .unwrap();
+            let v = keyframe.viewport;
+            assert!(
+                (v.x..=v.x + v.w).contains(&x) && (v.y..=v.y + v.h).contains(&y),
+                "click at {t}s ({x}, {y}) is outside {v:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn auto_zoom_stays_wide_for_a_full_screen_sweep() {
+    let events = ProjectFixture::new("sweep")
+        .with_duration_secs(12.0)
+        .with_events(EventPattern::Sweep { period_secs: 4.0 })
+        .capture_events();
+
+    let timeline = AutoZoomAnalyzer::with_defaults().analyze(&events);
+
+    assert!(!timeline.keyframes.is_empty());
+    assert!(timeline
+        .keyframes
+        .iter()
+        .all(|kf| (kf.viewport.w - 1.0).abs() < 1e-9));
+}
+
+#[test]
+fn cursor_smoothing_keeps_a_sweep_on_its_track() {
+    let events = ProjectFixture::new("sweep")
+        .with_duration_secs(4.0)
+        .with_pointer_hz(120)
+        .with_events(EventPattern::Sweep { period_secs: 2.0 })
+        .capture_events();
+
+    let smoothed = CursorSmoother::default_ema().smooth(&events);
+
+    assert_eq!(smoothed.len(), events.len());
+    assert!(smoothed
+        .iter()
+        .all(|&(_, x, y)| (0.05..=0.95).contains(&x) && (y - 0.5).abs() < 1e-9));
+    // Smoothing lags but still travels most of the way across.
+    let (min, max) = smoothed
+        .iter()
+        .fold((f64::MAX, f64::MIN), |(min, max), &(_, x, _)| {
+            (min.min(x), max.max(x))
+        });
+    assert!(max - min > 0.7, "sweep covered only {min}..{max}");
+}
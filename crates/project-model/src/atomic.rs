@@ -0,0 +1,189 @@
+//! Crash-safe metadata writes.
+//!
+//! Files are written to a temporary sibling, synced, and renamed over the
+//! original, so a crash leaves either the old or the new contents. JSON
+//! metadata also keeps the previous good version as `<name>.bak`, which
+//! loading falls back to when the primary is unreadable.
+
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::project::ProjectError;
+
+/// Replace `path` with `contents` atomically.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let tmp = sibling(path, "tmp");
+    {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&tmp)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+    }
+    if let Err(e) = std::fs::rename(&tmp, path) {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(e);
+    }
+    sync_parent_dir(path);
+    Ok(())
+}
+
+/// Serialize `value` to `path` atomically, first copying the current file to
+/// the backup slot if it still parses as `T`.
+pub fn write_json_with_backup<T: Serialize + DeserializeOwned>(
+    path: &Path,
+    value: &T,
+) -> Result<(), ProjectError> {
+    let json = serde_json::to_string_pretty(value).map_err(|e| ProjectError::ParseError {
+        path: path.to_path_buf(),
+        source: e,
+    })?;
+
+    let current_is_good = std::fs::read_to_string(path)
+        .ok()
+        .is_some_and(|current| serde_json::from_str::<T>(&current).is_ok());
+    if current_is_good {
+        let backup = backup_path(path);
+        std::fs::copy(path, sibling(&backup, "tmp"))
+            .and_then(|_| std::fs::rename(sibling(&backup, "tmp"), &backup))
+            .map_err(|e| ProjectError::IoError {
+                path: backup,
+                source: e,
+            })?;
+    }
+
+    write_atomic(path, json.as_bytes()).map_err(|e| ProjectError::IoError {
+        path: path.to_path_buf(),
+        source: e,
+    })
+}
+
+/// Read `path` as `T`, falling back to its backup when it is missing or
+/// corrupt. A recovered backup is written back over the primary and the
+/// unreadable file is kept as `<name>.corrupt`.
+///
+/// Returns `Ok(None)` when neither file exists.
+pub fn read_json_with_backup<T: Serialize + DeserializeOwned>(
+    path: &Path,
+) -> Result<Option<T>, ProjectError> {
+    let primary_err = match read_json(path) {
+        Ok(value) => return Ok(Some(value)),
+        Err(ProjectError::IoError { source, .. })
+            if source.kind() == io::ErrorKind::NotFound && !backup_path(path).exists() =>
+        {
+            return Ok(None);
+        }
+        Err(e) => e,
+    };
+
+    let backup = backup_path(path);
+    let Ok(value) = read_json::<T>(&backup) else {
+        return Err(primary_err);
+    };
+    tracing::warn!(
+        path = %path.display(),
+        error = %primary_err,
+        "Metadata unreadable; recovered the last good backup"
+    );
+    if path.exists() {
+        let _ = std::fs::rename(path, sibling(path, "corrupt"));
+    }
+    if let Ok(json) = serde_json::to_string_pretty(&value) {
+        let _ = write_atomic(path, json.as_bytes());
+    }
+    Ok(Some(value))
+}
+
+/// Backup location for `path`: `project.json` -> `project.json.bak`.
+pub fn backup_path(path: &Path) -> PathBuf {
+    sibling(path, "bak")
+}
+
+fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T, ProjectError> {
+    let json = std::fs::read_to_string(path).map_err(|e| ProjectError::IoError {
+        path: path.to_path_buf(),
+        source: e,
+    })?;
+    serde_json::from_str(&json).map_err(|e| ProjectError::ParseError {
+        path: path.to_path_buf(),
+        source: e,
+    })
+}
+
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// Persist the rename itself. Directories can't be opened for syncing on
+/// every platform, so failures are ignored.
+fn sync_parent_dir(path: &Path) {
+    #[cfg(unix)]
+    if let Some(parent) = path.parent() {
+        if let Ok(dir) = std::fs::File::open(parent) {
+            let _ = dir.sync_all();
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(name);
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_write_with_backup_keeps_previous_good_version() {
+        let dir = temp_dir("grabme_test_atomic_backup");
+        let path = dir.join("data.json");
+
+        write_json_with_backup(&path, &vec![1u32]).unwrap();
+        assert!(!backup_path(&path).exists());
+        write_json_with_backup(&path, &vec![2u32]).unwrap();
+
+        assert_eq!(read_json::<Vec<u32>>(&path).unwrap(), vec![2]);
+        assert_eq!(read_json::<Vec<u32>>(&backup_path(&path)).unwrap(), vec![1]);
+        assert!(!sibling(&path, "tmp").exists());
+    }
+
+    #[test]
+    fn test_read_recovers_backup_when_primary_is_corrupt() {
+        let dir = temp_dir("grabme_test_atomic_recover");
+        let path = dir.join("data.json");
+        write_json_with_backup(&path, &vec![1u32]).unwrap();
+        write_json_with_backup(&path, &vec![2u32]).unwrap();
+        std::fs::write(&path, "[2, 3").unwrap();
+
+        let value: Vec<u32> = read_json_with_backup(&path).unwrap().unwrap();
+        assert_eq!(value, vec![1]);
+        assert_eq!(read_json::<Vec<u32>>(&path).unwrap(), vec![1]);
+        assert!(sibling(&path, "corrupt").exists());
+
+        // A corrupt primary is not promoted to the backup slot.
+        std::fs::write(&path, "{").unwrap();
+        write_json_with_backup(&path, &vec![4u32]).unwrap();
+        assert_eq!(read_json::<Vec<u32>>(&backup_path(&path)).unwrap(), vec![1]);
+    }
+
+    #[test]
+    fn test_read_missing_without_backup_is_none() {
+        let dir = temp_dir("grabme_test_atomic_missing");
+        let value: Option<Vec<u32>> = read_json_with_backup(&dir.join("nope.json")).unwrap();
+        assert!(value.is_none());
+    }
+}
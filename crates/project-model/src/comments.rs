@@ -0,0 +1,121 @@
+//! Review comments (`meta/comments.json`).
+//!
+//! `grabme review` shares a draft export with a page where reviewers leave
+//! comments at moments of the video. Each comment keeps both the position
+//! in the reviewed draft and the timeline time it maps to, so the editor
+//! can show it as a marker even after the draft is re-exported.
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::atomic::{read_json_with_backup, write_json_with_backup};
+use crate::project::ProjectError;
+
+/// File name of the comments inside `meta/`.
+pub const COMMENTS_FILE: &str = "comments.json";
+
+/// One reviewer comment.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReviewComment {
+    /// Timeline (source) time the comment refers to, in seconds.
+    pub time_secs: f64,
+
+    /// Position in the reviewed draft, in seconds.
+    pub video_secs: f64,
+
+    /// Name the reviewer gave; may be empty.
+    #[serde(default)]
+    pub author: String,
+
+    pub text: String,
+
+    pub created_at: DateTime<Utc>,
+}
+
+/// All review comments of a project, ordered by timeline time.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReviewComments {
+    #[serde(default)]
+    pub comments: Vec<ReviewComment>,
+}
+
+/// Path of the comments file in the project at `project_root`.
+pub fn comments_path(project_root: &Path) -> PathBuf {
+    project_root.join("meta").join(COMMENTS_FILE)
+}
+
+impl ReviewComment {
+    /// A comment made now.
+    pub fn new(
+        time_secs: f64,
+        video_secs: f64,
+        author: impl Into<String>,
+        text: impl Into<String>,
+    ) -> Self {
+        Self {
+            time_secs,
+            video_secs,
+            author: author.into(),
+            text: text.into(),
+            created_at: Utc::now(),
+        }
+    }
+}
+
+impl ReviewComments {
+    /// Load the comments of the project at `project_root`; empty when
+    /// nobody has commented yet.
+    pub fn load(project_root: &Path) -> Result<Self, ProjectError> {
+        let mut comments: Self =
+            read_json_with_backup(&comments_path(project_root))?.unwrap_or_default();
+        comments.sort();
+        Ok(comments)
+    }
+
+    /// Write the comments to the project at `project_root`.
+    pub fn save(&self, project_root: &Path) -> Result<(), ProjectError> {
+        write_json_with_backup(&comments_path(project_root), self)
+    }
+
+    /// Add `comment`, keeping the list in timeline order.
+    pub fn add(&mut self, comment: ReviewComment) {
+        self.comments.push(comment);
+        self.sort();
+    }
+
+    fn sort(&mut self) {
+        self.comments.sort_by(|a, b| {
+            a.time_secs
+                .total_cmp(&b.time_secs)
+                .then(a.created_at.cmp(&b.created_at))
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_comments_round_trip_in_timeline_order() {
+        let dir = std::env::temp_dir().join("grabme_test_comments");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("meta")).unwrap();
+        assert_eq!(
+            ReviewComments::load(&dir).unwrap(),
+            ReviewComments::default()
+        );
+
+        let mut comments = ReviewComments::default();
+        comments.add(ReviewComment::new(12.5, 12.5, "Sam", "Zoom in here"));
+        comments.add(ReviewComment::new(3.0, 3.0, "Sam", "Too fast"));
+        comments.save(&dir).unwrap();
+
+        let loaded = ReviewComments::load(&dir).unwrap();
+        assert_eq!(loaded, comments);
+        assert_eq!(loaded.comments[0].text, "Too fast");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
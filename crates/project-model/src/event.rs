@@ -4,13 +4,29 @@
 //! All pointer coordinates are normalized to `[0.0, 1.0]` relative to
 //! the capture region dimensions.
 
+use grabme_platform_core::coords::{CaptureNorm, PhysicalPx, PhysicalRect, VirtualNorm};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// Monotonic timestamp in nanoseconds since recording start.
 pub type TimestampNs = u64;
 
+/// Schema version written to new event stream headers.
+///
+/// - `1.0`: initial format.
+/// - `1.1`: scroll events carry `source`; buttons beyond back/forward are
+///   recorded as `{"other": n}`.
+/// - `1.2`: `touch` and `stylus` events.
+/// - `1.3`: `gamepad_button` and `gamepad_axis` events.
+/// - `1.4`: `reaction` markers.
+/// - `1.5`: `color_scheme` and `brightness` changes.
+/// - `1.6`: pointer, click, scroll, and reaction events carry `hit`.
+///
+/// Readers accept all of them; fields added later have defaults.
+pub const EVENT_SCHEMA_VERSION: &str = "1.6";
+
 /// Coordinate space used by recorded pointer values.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum PointerCoordinateSpace {
     /// Coordinates are normalized directly against the captured region.
@@ -25,7 +41,7 @@ pub enum PointerCoordinateSpace {
 }
 
 /// A single recorded input event with timestamp.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct InputEvent {
     /// Monotonic nanoseconds since recording start.
     #[serde(rename = "t")]
@@ -34,10 +50,93 @@ pub struct InputEvent {
     /// The event payload.
     #[serde(flatten)]
     pub kind: EventKind,
+
+    /// Where the pointer was on the recorded monitor layout, resolved at
+    /// capture time. Only on pointer-positioned events, and only when the
+    /// layout was known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hit: Option<PointerHit>,
+}
+
+/// A pointer position resolved against the monitor layout at capture time.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PointerHit {
+    /// Index into the recording's `monitors` of the monitor under the
+    /// pointer; `None` in gaps between monitors.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub monitor: Option<usize>,
+
+    /// Position normalized against the captured region. Outside `[0.0, 1.0]`
+    /// while the pointer is on another monitor.
+    pub capture_x: f64,
+    pub capture_y: f64,
+}
+
+impl PointerHit {
+    /// The resolved position.
+    pub fn capture(&self) -> CaptureNorm {
+        CaptureNorm::new(self.capture_x, self.capture_y)
+    }
+}
+
+/// Monitor layout pointer samples are resolved against while recording.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PointerLayout {
+    /// Space the recorded pointer values are in.
+    pub space: PointerCoordinateSpace,
+    /// Bounds of the whole virtual desktop.
+    pub virtual_desktop: PhysicalRect,
+    /// The captured region.
+    pub capture: PhysicalRect,
+    /// Monitors, in the order recorded in the project.
+    pub monitors: Vec<PhysicalRect>,
+}
+
+impl PointerLayout {
+    /// Resolve a pointer value in [`Self::space`]. `None` for legacy
+    /// coordinates or an empty capture region.
+    pub fn hit(&self, x: f64, y: f64) -> Option<PointerHit> {
+        if self.capture.is_empty() {
+            return None;
+        }
+        let point = self.to_physical(x, y)?;
+        let capture = CaptureNorm::from_physical(point, self.capture);
+        Some(PointerHit {
+            monitor: self
+                .monitors
+                .iter()
+                .position(|monitor| monitor.contains(point)),
+            capture_x: capture.x,
+            capture_y: capture.y,
+        })
+    }
+
+    /// Physical desktop position of a pointer value in [`Self::space`].
+    fn to_physical(&self, x: f64, y: f64) -> Option<PhysicalPx> {
+        match self.space {
+            PointerCoordinateSpace::CaptureNormalized => {
+                Some(CaptureNorm::new(x, y).to_physical(self.capture))
+            }
+            PointerCoordinateSpace::VirtualDesktopNormalized => {
+                Some(VirtualNorm::new(x, y).to_physical(self.virtual_desktop))
+            }
+            PointerCoordinateSpace::VirtualDesktopRootOrigin => {
+                // Normalized against the desktop size, but from the root
+                // window origin rather than the bounds' top-left.
+                let root = PhysicalRect {
+                    x: 0,
+                    y: 0,
+                    ..self.virtual_desktop
+                };
+                Some(VirtualNorm::new(x, y).to_physical(root))
+            }
+            PointerCoordinateSpace::LegacyUnspecified => None,
+        }
+    }
 }
 
 /// Discriminated union of event types.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum EventKind {
     /// Mouse/touchpad pointer position update.
@@ -69,15 +168,86 @@ pub enum EventKind {
 
     /// Scroll wheel event.
     Scroll {
-        /// Horizontal scroll delta (normalized).
+        /// Horizontal scroll delta, positive to the right. Wheel steps for
+        /// discrete sources, pixels for precise ones.
         dx: f64,
-        /// Vertical scroll delta (normalized).
+        /// Vertical scroll delta, positive downwards. Same units as `dx`.
         dy: f64,
+        /// Device that produced the scroll.
+        #[serde(default)]
+        source: ScrollSource,
         /// Pointer position at scroll time.
         x: f64,
         y: f64,
     },
 
+    /// Finger contact on a touchscreen.
+    Touch {
+        /// Contact id, stable from `down` to `up`. Ids are reused afterwards.
+        id: u32,
+        /// Contact phase.
+        phase: TouchPhase,
+        /// Normalized contact position.
+        x: f64,
+        y: f64,
+        /// Normalized pressure `[0.0, 1.0]`, if the panel reports it.
+        #[serde(default)]
+        pressure: Option<f64>,
+    },
+
+    /// Pen or eraser contact on a drawing tablet or pen display.
+    Stylus {
+        /// Which end of the pen is in use.
+        tool: StylusTool,
+        /// Contact phase.
+        phase: TouchPhase,
+        /// Normalized tip position.
+        x: f64,
+        y: f64,
+        /// Normalized pressure `[0.0, 1.0]`.
+        pressure: f64,
+    },
+
+    /// Gamepad button press or release.
+    GamepadButton {
+        /// Gamepad index, in the order pads were opened.
+        pad: u32,
+        button: GamepadButton,
+        state: ButtonState,
+    },
+
+    /// Gamepad stick or trigger movement.
+    GamepadAxis {
+        /// Gamepad index, in the order pads were opened.
+        pad: u32,
+        axis: GamepadAxis,
+        /// `[-1.0, 1.0]` for sticks (positive right/down), `[0.0, 1.0]`
+        /// for triggers.
+        value: f64,
+    },
+
+    /// Reaction dropped with a hotkey during recording, drawn at export as
+    /// a short animation at the pointer.
+    Reaction {
+        /// The emoji to show.
+        emoji: String,
+        /// Pointer position when the hotkey was pressed.
+        x: f64,
+        y: f64,
+    },
+
+    /// Desktop switched between dark and light appearance.
+    ColorScheme {
+        /// The scheme now preferred.
+        scheme: ColorScheme,
+    },
+
+    /// Display backlight brightness changed.
+    Brightness {
+        /// New brightness `[0.0, 1.0]` of the backlight's maximum.
+        level: f64,
+    },
+
     /// Window focus change.
     WindowFocus {
         /// Window title or identifier that gained focus.
@@ -87,8 +257,18 @@ pub enum EventKind {
     },
 }
 
+/// Desktop color scheme preference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorScheme {
+    Dark,
+    Light,
+    /// The desktop leaves it to each application.
+    NoPreference,
+}
+
 /// Mouse button identifier.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum MouseButton {
     Left,
@@ -96,10 +276,90 @@ pub enum MouseButton {
     Middle,
     Back,
     Forward,
+    /// Any further button, by its 1-based index as reported by the device
+    /// (X11 numbering: 8 and 9 are back/forward, so this starts at 10).
+    Other(u16),
+}
+
+/// Phase of a touch or stylus contact.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TouchPhase {
+    Down,
+    Move,
+    Up,
+}
+
+/// Stylus tool in contact.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StylusTool {
+    Pen,
+    Eraser,
+}
+
+/// Gamepad button, named by position (Xbox A is `south`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GamepadButton {
+    South,
+    East,
+    North,
+    West,
+    LeftBumper,
+    RightBumper,
+    /// Digital trigger click; analog triggers are `GamepadAxis` events.
+    LeftTrigger,
+    RightTrigger,
+    Select,
+    Start,
+    Mode,
+    LeftStick,
+    RightStick,
+    DpadUp,
+    DpadDown,
+    DpadLeft,
+    DpadRight,
+    /// Any other button, by evdev key code.
+    Other(u16),
+}
+
+/// Gamepad analog axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GamepadAxis {
+    LeftStickX,
+    LeftStickY,
+    RightStickX,
+    RightStickY,
+    LeftTrigger,
+    RightTrigger,
+}
+
+/// Where a scroll event came from, which also says how to read its deltas.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ScrollSource {
+    /// Files written before the source was recorded.
+    #[default]
+    Unknown,
+    /// Notched wheel or tilt wheel; deltas are whole steps.
+    Wheel,
+    /// Touchpad or touchscreen finger scrolling; deltas are pixels.
+    Finger,
+    /// Smooth scrolling from a free-spinning wheel or trackball; deltas are pixels.
+    Continuous,
+}
+
+impl ScrollSource {
+    /// Whether deltas are pixel-precise rather than whole wheel steps.
+    pub fn is_precise(self) -> bool {
+        matches!(self, Self::Finger | Self::Continuous)
+    }
 }
 
 /// Button/key state.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ButtonState {
     Down,
@@ -107,12 +367,14 @@ pub enum ButtonState {
 }
 
 /// Stream of events with recording metadata.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct EventStreamHeader {
     /// Schema version for forward compatibility.
     pub schema_version: String,
 
-    /// Monotonic clock epoch: system monotonic time (ns) at recording start.
+    /// Monotonic clock epoch: raw reading (ns) of the recording's clock
+    /// source at recording start. Zero in files written before it was
+    /// persisted.
     pub epoch_monotonic_ns: u64,
 
     /// Wall-clock time at recording start (ISO 8601).
@@ -139,6 +401,7 @@ impl InputEvent {
         Self {
             timestamp_ns,
             kind: EventKind::Pointer { x, y },
+            hit: None,
         }
     }
 
@@ -158,6 +421,7 @@ impl InputEvent {
                 x,
                 y,
             },
+            hit: None,
         }
     }
 
@@ -169,6 +433,132 @@ impl InputEvent {
                 code: code.into(),
                 state,
             },
+            hit: None,
+        }
+    }
+
+    /// Create a scroll event.
+    pub fn scroll(
+        timestamp_ns: TimestampNs,
+        dx: f64,
+        dy: f64,
+        source: ScrollSource,
+        x: f64,
+        y: f64,
+    ) -> Self {
+        Self {
+            timestamp_ns,
+            kind: EventKind::Scroll {
+                dx,
+                dy,
+                source,
+                x,
+                y,
+            },
+            hit: None,
+        }
+    }
+
+    /// Create a touch event.
+    pub fn touch(
+        timestamp_ns: TimestampNs,
+        id: u32,
+        phase: TouchPhase,
+        x: f64,
+        y: f64,
+        pressure: Option<f64>,
+    ) -> Self {
+        Self {
+            timestamp_ns,
+            kind: EventKind::Touch {
+                id,
+                phase,
+                x,
+                y,
+                pressure,
+            },
+            hit: None,
+        }
+    }
+
+    /// Create a stylus event.
+    pub fn stylus(
+        timestamp_ns: TimestampNs,
+        tool: StylusTool,
+        phase: TouchPhase,
+        x: f64,
+        y: f64,
+        pressure: f64,
+    ) -> Self {
+        Self {
+            timestamp_ns,
+            kind: EventKind::Stylus {
+                tool,
+                phase,
+                x,
+                y,
+                pressure,
+            },
+            hit: None,
+        }
+    }
+
+    /// Create a gamepad button event.
+    pub fn gamepad_button(
+        timestamp_ns: TimestampNs,
+        pad: u32,
+        button: GamepadButton,
+        state: ButtonState,
+    ) -> Self {
+        Self {
+            timestamp_ns,
+            kind: EventKind::GamepadButton { pad, button, state },
+            hit: None,
+        }
+    }
+
+    /// Create a gamepad axis event.
+    pub fn gamepad_axis(
+        timestamp_ns: TimestampNs,
+        pad: u32,
+        axis: GamepadAxis,
+        value: f64,
+    ) -> Self {
+        Self {
+            timestamp_ns,
+            kind: EventKind::GamepadAxis { pad, axis, value },
+            hit: None,
+        }
+    }
+
+    /// Create a reaction marker.
+    pub fn reaction(timestamp_ns: TimestampNs, emoji: impl Into<String>, x: f64, y: f64) -> Self {
+        Self {
+            timestamp_ns,
+            kind: EventKind::Reaction {
+                emoji: emoji.into(),
+                x,
+                y,
+            },
+            hit: None,
+        }
+    }
+
+    /// Create a color scheme change.
+    pub fn color_scheme(timestamp_ns: TimestampNs, scheme: ColorScheme) -> Self {
+        Self {
+            timestamp_ns,
+            kind: EventKind::ColorScheme { scheme },
+            hit: None,
+        }
+    }
+
+    /// Create a brightness change.
+    pub fn brightness(timestamp_ns: TimestampNs, level: f64) -> Self {
+        Self {
+            timestamp_ns,
+            kind: EventKind::Brightness { level },
+            hit: None,
         }
     }
 
@@ -186,6 +576,38 @@ impl InputEvent {
             _ => None,
         }
     }
+
+    /// Mutable screen position of events that have one: pointer, click,
+    /// scroll, touch, stylus, and reaction events.
+    pub fn position_mut(&mut self) -> Option<(&mut f64, &mut f64)> {
+        match &mut self.kind {
+            EventKind::Pointer { x, y }
+            | EventKind::Click { x, y, .. }
+            | EventKind::Scroll { x, y, .. }
+            | EventKind::Touch { x, y, .. }
+            | EventKind::Stylus { x, y, .. }
+            | EventKind::Reaction { x, y, .. } => Some((x, y)),
+            _ => None,
+        }
+    }
+
+    /// Position of events placed with the mouse pointer: pointer, click,
+    /// scroll, and reaction events. These are the ones that carry a `hit`.
+    pub fn hit_position(&self) -> Option<(f64, f64)> {
+        match &self.kind {
+            EventKind::Reaction { x, y, .. } => Some((*x, *y)),
+            _ => self.pointer_position(),
+        }
+    }
+
+    /// Whether this is a theme or brightness change, after which the
+    /// whole screen can look different.
+    pub fn is_appearance_change(&self) -> bool {
+        matches!(
+            self.kind,
+            EventKind::ColorScheme { .. } | EventKind::Brightness { .. }
+        )
+    }
 }
 
 /// Parse events from JSONL content (one JSON object per line).
@@ -198,6 +620,184 @@ pub fn parse_events(jsonl: &str) -> Result<Vec<InputEvent>, serde_json::Error> {
         .collect()
 }
 
+/// The header comment of JSONL event content, when it has a readable one.
+pub fn parse_events_header(jsonl: &str) -> Option<EventStreamHeader> {
+    let line = jsonl
+        .lines()
+        .map(str::trim)
+        .find(|line| line.starts_with('#'))?;
+    serde_json::from_str(line.trim_start_matches('#').trim()).ok()
+}
+
+/// Events read from a JSONL stream a line at a time, so large recordings
+/// never sit in memory as text. The header comment, if the stream opens
+/// with one, is parsed up front; later comments and blank lines are
+/// skipped.
+pub struct EventStream<R> {
+    lines: std::io::Lines<R>,
+    first: Option<String>,
+    header: Option<EventStreamHeader>,
+}
+
+impl EventStream<std::io::BufReader<std::fs::File>> {
+    /// Stream the events of the file at `path`.
+    pub fn open(path: &std::path::Path) -> std::io::Result<Self> {
+        Self::new(std::io::BufReader::new(std::fs::File::open(path)?))
+    }
+}
+
+impl<R: std::io::BufRead> EventStream<R> {
+    pub fn new(reader: R) -> std::io::Result<Self> {
+        let mut lines = reader.lines();
+        let mut first = None;
+        let mut header = None;
+        for line in lines.by_ref() {
+            let line = line?;
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+            match trimmed.strip_prefix('#') {
+                Some(json) => header = serde_json::from_str(json.trim()).ok(),
+                None => first = Some(line),
+            }
+            break;
+        }
+        Ok(Self {
+            lines,
+            first,
+            header,
+        })
+    }
+
+    /// The stream's header, when it has a readable one.
+    pub fn header(&self) -> Option<&EventStreamHeader> {
+        self.header.as_ref()
+    }
+}
+
+impl<R: std::io::BufRead> Iterator for EventStream<R> {
+    type Item = std::io::Result<InputEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line = match self.first.take() {
+                Some(line) => line,
+                None => match self.lines.next()? {
+                    Ok(line) => line,
+                    Err(e) => return Some(Err(e)),
+                },
+            };
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            return Some(serde_json::from_str(line).map_err(std::io::Error::from));
+        }
+    }
+}
+
+/// Drops pointer moves that follow the last kept one by less than
+/// `1 / max_hz`, to thin a stream as it is read. Every other event is kept.
+#[derive(Debug, Clone)]
+pub struct PointerThinner {
+    min_gap_ns: TimestampNs,
+    last_kept_ns: Option<TimestampNs>,
+}
+
+impl PointerThinner {
+    pub fn new(max_hz: f64) -> Self {
+        Self {
+            min_gap_ns: (1e9 / max_hz.max(f64::MIN_POSITIVE)) as TimestampNs,
+            last_kept_ns: None,
+        }
+    }
+
+    /// Whether to keep `event`.
+    pub fn keep(&mut self, event: &InputEvent) -> bool {
+        if !matches!(event.kind, EventKind::Pointer { .. }) {
+            return true;
+        }
+        let due = self.last_kept_ns.map_or(true, |last| {
+            event.timestamp_ns.saturating_sub(last) >= self.min_gap_ns
+        });
+        if due {
+            self.last_kept_ns = Some(event.timestamp_ns);
+        }
+        due
+    }
+}
+
+/// Move pointer-positioned events to their capture-time hits.
+///
+/// Only applies when every such event has a hit, as recordings made since
+/// schema 1.6 with a known layout do; their positions are then
+/// capture-normalized and need no projection guessing. Returns whether the
+/// events were moved.
+pub fn apply_pointer_hits(events: &mut [InputEvent]) -> bool {
+    let mut positioned = events
+        .iter()
+        .filter(|event| event.hit_position().is_some())
+        .peekable();
+    if positioned.peek().is_none() || !positioned.all(|event| event.hit.is_some()) {
+        return false;
+    }
+    for event in events.iter_mut() {
+        let Some(hit) = event.hit else {
+            continue;
+        };
+        if let Some((x, y)) = event.position_mut() {
+            (*x, *y) = (hit.capture_x, hit.capture_y);
+        }
+    }
+    true
+}
+
+/// Slice of a time-ordered event stream within `[start_secs, end_secs]`.
+pub fn events_in_range(events: &[InputEvent], start_secs: f64, end_secs: f64) -> &[InputEvent] {
+    let start_ns = (start_secs.max(0.0) * 1_000_000_000.0) as TimestampNs;
+    let end_ns = (end_secs.max(0.0) * 1_000_000_000.0) as TimestampNs;
+    let start = events.partition_point(|event| event.timestamp_ns < start_ns);
+    let end = events.partition_point(|event| event.timestamp_ns <= end_ns);
+    &events[start..end.max(start)]
+}
+
+/// Reduce pointer samples to about `max_points`, evenly spaced over the slice.
+/// The final pointer sample is always kept so paths end where the cursor did.
+///
+/// Clicks, keys, scrolls, and focus changes are always kept so overlays
+/// never lose discrete interactions; only `Pointer` moves are thinned.
+pub fn decimate_pointer_events(events: &[InputEvent], max_points: usize) -> Vec<InputEvent> {
+    let pointer_count = events
+        .iter()
+        .filter(|event| matches!(event.kind, EventKind::Pointer { .. }))
+        .count();
+    if pointer_count <= max_points {
+        return events.to_vec();
+    }
+
+    let mut kept = Vec::with_capacity(max_points + (events.len() - pointer_count));
+    let mut pointer_index = 0usize;
+    let mut next_bucket = 0usize;
+    for event in events {
+        if !matches!(event.kind, EventKind::Pointer { .. }) {
+            kept.push(event.clone());
+            continue;
+        }
+
+        // Keep the first pointer sample of each bucket, plus the final one.
+        let bucket = pointer_index * max_points / pointer_count;
+        let is_last = pointer_index + 1 == pointer_count;
+        if max_points > 0 && (bucket >= next_bucket || is_last) {
+            kept.push(event.clone());
+            next_bucket = bucket + 1;
+        }
+        pointer_index += 1;
+    }
+
+    kept
+}
+
 /// Serialize events to JSONL format.
 pub fn serialize_events(events: &[InputEvent]) -> Result<String, serde_json::Error> {
     let mut output = String::new();
@@ -242,6 +842,166 @@ mod tests {
         assert_eq!(event, parsed);
     }
 
+    #[test]
+    fn test_reaction_event_roundtrip() {
+        let event = InputEvent::reaction(2_500_000_000, "🎉", 0.3, 0.7);
+        let json = serde_json::to_string(&event).unwrap();
+        assert!(json.contains("\"type\":\"reaction\""));
+        let parsed: InputEvent = serde_json::from_str(&json).unwrap();
+        assert_eq!(event, parsed);
+        assert_eq!(parsed.pointer_position(), None);
+    }
+
+    #[test]
+    fn test_pointer_layout_resolves_hits_across_monitors() {
+        // Capturing the right-hand monitor of two side by side.
+        let left = PhysicalRect {
+            x: 0,
+            y: 0,
+            width: 1920,
+            height: 1080,
+        };
+        let right = PhysicalRect {
+            x: 1920,
+            y: 0,
+            width: 1280,
+            height: 1024,
+        };
+        let layout = PointerLayout {
+            space: PointerCoordinateSpace::VirtualDesktopNormalized,
+            virtual_desktop: PhysicalRect {
+                x: 0,
+                y: 0,
+                width: 3200,
+                height: 1080,
+            },
+            capture: right,
+            monitors: vec![left, right],
+        };
+
+        let hit = layout.hit(0.8, 0.5).unwrap();
+        assert_eq!(hit.monitor, Some(1));
+        assert!((hit.capture_x - 0.5).abs() < 1e-9);
+        assert!((hit.capture_y - 540.0 / 1024.0).abs() < 1e-9);
+
+        let hit = layout.hit(0.3, 0.5).unwrap();
+        assert_eq!(hit.monitor, Some(0));
+        assert!(hit.capture_x < 0.0);
+
+        // Below the shorter monitor is in no monitor at all.
+        assert_eq!(layout.hit(0.8, 0.99).unwrap().monitor, None);
+
+        let legacy = PointerLayout {
+            space: PointerCoordinateSpace::LegacyUnspecified,
+            ..layout
+        };
+        assert_eq!(legacy.hit(0.5, 0.5), None);
+    }
+
+    #[test]
+    fn test_apply_pointer_hits_needs_every_positioned_event() {
+        let hit = PointerHit {
+            monitor: Some(0),
+            capture_x: 0.25,
+            capture_y: 0.75,
+        };
+        let mut pointer = InputEvent::pointer(1_000, 0.1, 0.2);
+        pointer.hit = Some(hit);
+        let json = serde_json::to_string(&pointer).unwrap();
+        assert!(json.contains(r#""hit":{"monitor":0,"capture_x":0.25,"capture_y":0.75}"#));
+        assert_eq!(serde_json::from_str::<InputEvent>(&json).unwrap(), pointer);
+
+        let key = InputEvent::key(2_000, "KeyA", ButtonState::Down);
+        let mut events = vec![
+            pointer.clone(),
+            key.clone(),
+            InputEvent::pointer(3_000, 0.4, 0.4),
+        ];
+        assert!(!apply_pointer_hits(&mut events));
+        assert_eq!(events[0].pointer_position(), Some((0.1, 0.2)));
+
+        let mut events = vec![pointer, key];
+        assert!(apply_pointer_hits(&mut events));
+        assert_eq!(events[0].pointer_position(), Some((0.25, 0.75)));
+        assert!(!apply_pointer_hits(&mut [InputEvent::key(
+            0,
+            "KeyB",
+            ButtonState::Up
+        )]));
+    }
+
+    #[test]
+    fn test_appearance_events_roundtrip() {
+        let events = vec![
+            InputEvent::color_scheme(1_000_000_000, ColorScheme::Dark),
+            InputEvent::brightness(2_000_000_000, 0.35),
+        ];
+        let json = serde_json::to_string(&events[0]).unwrap();
+        assert_eq!(
+            json,
+            r#"{"t":1000000000,"type":"color_scheme","scheme":"dark"}"#
+        );
+        for event in events {
+            assert!(event.is_appearance_change());
+            let json = serde_json::to_string(&event).unwrap();
+            let parsed: InputEvent = serde_json::from_str(&json).unwrap();
+            assert_eq!(event, parsed);
+        }
+        assert!(!InputEvent::pointer(0, 0.5, 0.5).is_appearance_change());
+    }
+
+    #[test]
+    fn test_scroll_and_extra_button_roundtrip() {
+        let events = vec![
+            InputEvent::scroll(0, -1.5, 0.0, ScrollSource::Finger, 0.4, 0.6),
+            InputEvent::click(1, MouseButton::Other(12), ButtonState::Down, 0.4, 0.6),
+        ];
+        let jsonl = serialize_events(&events).unwrap();
+        assert!(jsonl.contains("\"source\":\"finger\""));
+        assert!(jsonl.contains("\"button\":{\"other\":12}"));
+        assert_eq!(parse_events(&jsonl).unwrap(), events);
+    }
+
+    #[test]
+    fn test_touch_and_stylus_roundtrip() {
+        let events = vec![
+            InputEvent::touch(0, 3, TouchPhase::Down, 0.2, 0.3, None),
+            InputEvent::stylus(1, StylusTool::Pen, TouchPhase::Move, 0.4, 0.5, 0.75),
+        ];
+        let jsonl = serialize_events(&events).unwrap();
+        assert!(jsonl.contains("\"type\":\"touch\""));
+        assert!(jsonl.contains("\"tool\":\"pen\""));
+        assert_eq!(parse_events(&jsonl).unwrap(), events);
+        // Touch contacts are not the pointer.
+        assert_eq!(events[0].pointer_position(), None);
+    }
+
+    #[test]
+    fn test_gamepad_events_roundtrip() {
+        let events = vec![
+            InputEvent::gamepad_button(0, 0, GamepadButton::South, ButtonState::Down),
+            InputEvent::gamepad_axis(1, 1, GamepadAxis::LeftStickX, -0.5),
+        ];
+        let jsonl = serialize_events(&events).unwrap();
+        assert!(jsonl.contains("\"type\":\"gamepad_button\""));
+        assert!(jsonl.contains("\"axis\":\"left_stick_x\""));
+        assert_eq!(parse_events(&jsonl).unwrap(), events);
+    }
+
+    #[test]
+    fn test_legacy_scroll_defaults_to_unknown_source() {
+        let jsonl = "{\"t\":0,\"type\":\"scroll\",\"dx\":0.0,\"dy\":1.0,\"x\":0.5,\"y\":0.5}\n";
+        let parsed = parse_events(jsonl).unwrap();
+        assert!(matches!(
+            parsed[0].kind,
+            EventKind::Scroll {
+                source: ScrollSource::Unknown,
+                ..
+            }
+        ));
+        assert!(!ScrollSource::Unknown.is_precise());
+    }
+
     #[test]
     fn test_jsonl_roundtrip() {
         let events = vec![
@@ -263,6 +1023,39 @@ mod tests {
         assert_eq!(parsed[0].timestamp_ns, 0);
     }
 
+    #[test]
+    fn test_event_stream_reads_header_and_thins_pointer_moves() {
+        let jsonl = [
+            "",
+            r#"# {"schema_version":"1.6","epoch_monotonic_ns":0,"epoch_wall":"","capture_width":1920,"capture_height":1080,"scale_factor":1.0,"pointer_sample_rate_hz":60,"pointer_coordinate_space":"capture_normalized"}"#,
+            r#"{"t":0,"type":"pointer","x":0.1,"y":0.1}"#,
+            r#"{"t":10000000,"type":"pointer","x":0.2,"y":0.2}"#,
+            "",
+            r#"{"t":20000000,"type":"click","button":"left","state":"down","x":0.2,"y":0.2}"#,
+            r#"{"t":60000000,"type":"pointer","x":0.3,"y":0.3}"#,
+        ]
+        .join("\n");
+        let stream = EventStream::new(jsonl.as_bytes()).unwrap();
+        assert_eq!(
+            stream.header().map(|h| h.pointer_coordinate_space),
+            Some(PointerCoordinateSpace::CaptureNormalized)
+        );
+        let mut thinner = PointerThinner::new(20.0);
+        let kept: Vec<TimestampNs> = stream
+            .map(Result::unwrap)
+            .filter(|event| thinner.keep(event))
+            .map(|event| event.timestamp_ns)
+            .collect();
+        assert_eq!(kept, [0, 20_000_000, 60_000_000]);
+
+        let headless = concat!(r#"{"t":5,"type":"pointer","x":0,"y":0}"#, "\nnot json\n");
+        let mut headless = EventStream::new(headless.as_bytes()).unwrap();
+        assert!(headless.header().is_none());
+        assert_eq!(headless.next().unwrap().unwrap().timestamp_ns, 5);
+        assert!(headless.next().unwrap().is_err());
+        assert!(headless.next().is_none());
+    }
+
     #[test]
     fn test_pointer_position_extraction() {
         let ptr = InputEvent::pointer(0, 0.3, 0.7);
@@ -275,6 +1068,42 @@ mod tests {
         assert_eq!(key.pointer_position(), None);
     }
 
+    #[test]
+    fn test_events_in_range_is_inclusive() {
+        let events: Vec<_> = (0..10)
+            .map(|i| InputEvent::pointer(i * 500_000_000, 0.5, 0.5))
+            .collect();
+        let range = events_in_range(&events, 1.0, 2.0);
+        assert_eq!(range.len(), 3);
+        assert_eq!(range[0].timestamp_ns, 1_000_000_000);
+        assert!(events_in_range(&events, 3.0, 2.0).is_empty());
+    }
+
+    #[test]
+    fn test_decimate_pointer_events_keeps_clicks() {
+        let mut events: Vec<_> = (0..1000)
+            .map(|i| InputEvent::pointer(i * 1_000_000, 0.5, 0.5))
+            .collect();
+        events.insert(
+            500,
+            InputEvent::click(500_000_000, MouseButton::Left, ButtonState::Down, 0.5, 0.5),
+        );
+
+        let decimated = decimate_pointer_events(&events, 100);
+        let pointers = decimated
+            .iter()
+            .filter(|event| matches!(event.kind, EventKind::Pointer { .. }))
+            .count();
+        assert!(pointers <= 101);
+        assert!(decimated
+            .iter()
+            .any(|event| matches!(event.kind, EventKind::Click { .. })));
+        assert_eq!(decimated.last().unwrap().timestamp_ns, 999_000_000);
+        assert!(decimated
+            .windows(2)
+            .all(|pair| pair[0].timestamp_ns <= pair[1].timestamp_ns));
+    }
+
     #[test]
     fn test_timestamp_secs() {
         let event = InputEvent::pointer(1_500_000_000, 0.0, 0.0);
@@ -0,0 +1,176 @@
+//! Screen capture timestamps (`meta/frame-times.jsonl`).
+//!
+//! When enabled, the screen pipeline logs one sample per keyframe interval:
+//! how many frames the capture source had delivered, the presentation
+//! timestamp (PTS) of the latest one, and the recording-clock time it
+//! arrived. The track is muxed by PTS while input events are stamped with
+//! the recording clock, so the difference between the two shows how far
+//! the video drifted from the events and how evenly frames were paced.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::project::ProjectError;
+
+/// File name of the sidecar inside `meta/`.
+pub const FRAME_TIMES_FILE: &str = "frame-times.jsonl";
+
+/// One line of `frame-times.jsonl`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FrameTimeSample {
+    /// Frames delivered by the capture source so far, counting this one.
+    pub frame: u64,
+
+    /// Presentation timestamp the source gave the frame.
+    pub pts_ns: u64,
+
+    /// Recording-clock time the frame reached the pipeline, on the same
+    /// clock as event timestamps.
+    pub wall_ns: u64,
+}
+
+/// Path of the sidecar in the project at `project_root`.
+pub fn frame_times_path(project_root: &Path) -> PathBuf {
+    project_root.join("meta").join(FRAME_TIMES_FILE)
+}
+
+/// Frame time samples of a recording, ordered by wall time.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FrameTimes {
+    samples: Vec<FrameTimeSample>,
+}
+
+impl FrameTimes {
+    /// Parse JSONL samples. Blank lines are skipped.
+    pub fn parse(jsonl: &str) -> Result<Self, serde_json::Error> {
+        let mut samples = jsonl
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(serde_json::from_str)
+            .collect::<Result<Vec<FrameTimeSample>, _>>()?;
+        samples.sort_by_key(|sample| sample.wall_ns);
+        Ok(Self { samples })
+    }
+
+    /// Load the sidecar of the project at `project_root`. `Ok(None)` when
+    /// the recording didn't log frame times.
+    pub fn load(project_root: &Path) -> Result<Option<Self>, ProjectError> {
+        let path = frame_times_path(project_root);
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(source) => return Err(ProjectError::IoError { path, source }),
+        };
+        Self::parse(&content)
+            .map(Some)
+            .map_err(|source| ProjectError::ParseError { path, source })
+    }
+
+    pub fn samples(&self) -> &[FrameTimeSample] {
+        &self.samples
+    }
+
+    /// Whether there are enough samples to measure anything.
+    pub fn is_usable(&self) -> bool {
+        self.samples.len() >= 2
+    }
+
+    /// How far the track's PTS has run ahead of the recording clock at
+    /// `wall_ns`, relative to the first sample. Interpolated between
+    /// samples and held at the last one's drift past the end.
+    pub fn drift_ns_at(&self, wall_ns: u64) -> i64 {
+        let Some(first) = self.samples.first() else {
+            return 0;
+        };
+        let drift = |sample: &FrameTimeSample| {
+            (sample.pts_ns as i64 - first.pts_ns as i64)
+                - (sample.wall_ns as i64 - first.wall_ns as i64)
+        };
+        let next = self
+            .samples
+            .partition_point(|sample| sample.wall_ns <= wall_ns);
+        if next == 0 {
+            return 0;
+        }
+        let before = &self.samples[next - 1];
+        let Some(after) = self.samples.get(next) else {
+            return drift(before);
+        };
+        let span = (after.wall_ns - before.wall_ns) as f64;
+        let t = if span > 0.0 {
+            (wall_ns - before.wall_ns) as f64 / span
+        } else {
+            0.0
+        };
+        drift(before) + ((drift(after) - drift(before)) as f64 * t).round() as i64
+    }
+
+    /// Largest drift, by magnitude, at any sample.
+    pub fn max_drift_ns(&self) -> i64 {
+        self.samples
+            .iter()
+            .map(|sample| self.drift_ns_at(sample.wall_ns))
+            .max_by_key(|drift| drift.abs())
+            .unwrap_or(0)
+    }
+
+    /// Frames per second the source delivered between consecutive samples,
+    /// measured on the PTS clock.
+    pub fn interval_fps(&self) -> Vec<f64> {
+        self.samples
+            .windows(2)
+            .filter_map(|pair| {
+                let frames = pair[1].frame.checked_sub(pair[0].frame)?;
+                let secs = pair[1].pts_ns.checked_sub(pair[0].pts_ns)? as f64 / 1_000_000_000.0;
+                (secs > 0.0).then(|| frames as f64 / secs)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(frame: u64, pts_ms: u64, wall_ms: u64) -> FrameTimeSample {
+        FrameTimeSample {
+            frame,
+            pts_ns: pts_ms * 1_000_000,
+            wall_ns: wall_ms * 1_000_000,
+        }
+    }
+
+    #[test]
+    fn test_drift_is_interpolated_from_the_first_sample() {
+        let times = FrameTimes {
+            samples: vec![
+                sample(1, 40, 100),
+                sample(61, 2_040, 2_100),
+                sample(121, 4_080, 4_100),
+            ],
+        };
+
+        assert_eq!(times.drift_ns_at(0), 0);
+        assert_eq!(times.drift_ns_at(2_100_000_000), 0);
+        assert_eq!(times.drift_ns_at(3_100_000_000), 20_000_000);
+        assert_eq!(times.drift_ns_at(9_000_000_000), 40_000_000);
+        assert_eq!(times.max_drift_ns(), 40_000_000);
+
+        let fps = times.interval_fps();
+        assert_eq!(fps.len(), 2);
+        assert!((fps[0] - 30.0).abs() < 1e-9);
+        assert!((fps[1] - 60.0 / 2.04).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_parse_sorts_samples_and_skips_blank_lines() {
+        let jsonl = "{\"frame\":61,\"pts_ns\":2000,\"wall_ns\":2000}\n\n\
+                     {\"frame\":1,\"pts_ns\":0,\"wall_ns\":0}\n";
+        let times = FrameTimes::parse(jsonl).unwrap();
+        assert!(times.is_usable());
+        assert_eq!(times.samples()[0].frame, 1);
+        assert!(FrameTimes::parse("{\"frame\":1}").is_err());
+    }
+}
@@ -4,16 +4,35 @@
 //! - **Events:** Timestamped input events (pointer, click, key, window focus)
 //! - **Timeline:** Editing decisions (zoom keyframes, camera regions, effects)
 //! - **Project:** Top-level metadata, tracks, and export configuration
+//! - **Library:** Index of recently recorded/opened projects
+//! - **Comments:** Timestamped reviewer comments on a shared draft
+//! - **Frame times:** Capture timestamps of the screen track, for drift checks
+//! - **Metrics:** CPU, memory and GPU usage sampled during capture
+//! - **Projection:** Recorded pointer positions mapped onto the capture
+//! - **Schema:** JSON Schema of the event stream, project and timeline files
 //!
 //! All coordinates are normalized to `[0.0, 1.0]` range relative to the
 //! capture region to survive DPI/scaling changes across sessions.
 
+pub mod atomic;
+pub mod comments;
 pub mod event;
+pub mod frame_times;
+pub mod library;
+pub mod metrics;
 pub mod project;
+pub mod projection;
+pub mod schema;
 pub mod timeline;
 pub mod viewport;
 
+pub use comments::*;
 pub use event::*;
+pub use frame_times::*;
+pub use library::*;
+pub use metrics::*;
 pub use project::*;
+pub use projection::*;
+pub use schema::*;
 pub use timeline::*;
 pub use viewport::*;
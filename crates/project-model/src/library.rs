@@ -0,0 +1,178 @@
+//! Recent-project library index.
+//!
+//! The library is a small JSON index (`library.json`) of projects the user
+//! has recorded or opened, most recent first. It only stores pointers to
+//! project directories; the bundles themselves stay where they were saved.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::atomic::write_atomic;
+use crate::project::ProjectError;
+
+/// Index of recently used projects.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectLibrary {
+    /// Schema version for forward compatibility.
+    pub version: String,
+
+    /// Known projects, most recently used first.
+    #[serde(default)]
+    pub entries: Vec<LibraryEntry>,
+}
+
+/// A single project reference in the library.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LibraryEntry {
+    /// Human-readable project name.
+    pub name: String,
+
+    /// Absolute path to the project directory.
+    pub path: PathBuf,
+
+    /// Last time the project was recorded or opened (ISO 8601).
+    pub last_opened_at: String,
+}
+
+impl ProjectLibrary {
+    /// Maximum number of entries kept in the index.
+    pub const MAX_ENTRIES: usize = 25;
+
+    /// Create an empty library.
+    pub fn new() -> Self {
+        Self {
+            version: "1.0".to_string(),
+            entries: vec![],
+        }
+    }
+
+    /// Load the library index, returning an empty library if the file does not exist.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ProjectError> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::new());
+        }
+
+        let json = std::fs::read_to_string(path).map_err(|e| ProjectError::IoError {
+            path: path.to_path_buf(),
+            source: e,
+        })?;
+        serde_json::from_str(&json).map_err(|e| ProjectError::ParseError {
+            path: path.to_path_buf(),
+            source: e,
+        })
+    }
+
+    /// Save the library index, creating parent directories as needed.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ProjectError> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| ProjectError::IoError {
+                path: parent.to_path_buf(),
+                source: e,
+            })?;
+        }
+
+        let json = serde_json::to_string_pretty(self).map_err(|e| ProjectError::ParseError {
+            path: path.to_path_buf(),
+            source: e,
+        })?;
+        write_atomic(path, json.as_bytes()).map_err(|e| ProjectError::IoError {
+            path: path.to_path_buf(),
+            source: e,
+        })
+    }
+
+    /// Record that a project was used, moving it to the front of the list.
+    pub fn touch(&mut self, name: impl Into<String>, path: impl AsRef<Path>) {
+        let path = path.as_ref().to_path_buf();
+        self.entries.retain(|entry| entry.path != path);
+        self.entries.insert(
+            0,
+            LibraryEntry {
+                name: name.into(),
+                path,
+                last_opened_at: chrono::Utc::now().to_rfc3339(),
+            },
+        );
+        self.entries.truncate(Self::MAX_ENTRIES);
+    }
+
+    /// Drop entries whose project directory no longer exists.
+    /// Returns the number of removed entries.
+    pub fn prune_missing(&mut self) -> usize {
+        let before = self.entries.len();
+        self.entries
+            .retain(|entry| entry.path.join("meta").join("project.json").exists());
+        before - self.entries.len()
+    }
+
+    /// The `n` most recently used projects.
+    pub fn recent(&self, n: usize) -> &[LibraryEntry] {
+        &self.entries[..n.min(self.entries.len())]
+    }
+}
+
+impl Default for ProjectLibrary {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_touch_moves_existing_entry_to_front() {
+        let mut library = ProjectLibrary::new();
+        library.touch("a", "/tmp/a");
+        library.touch("b", "/tmp/b");
+        library.touch("a", "/tmp/a");
+
+        assert_eq!(library.entries.len(), 2);
+        assert_eq!(library.entries[0].name, "a");
+        assert_eq!(library.entries[1].name, "b");
+    }
+
+    #[test]
+    fn test_touch_truncates_to_max_entries() {
+        let mut library = ProjectLibrary::new();
+        for i in 0..(ProjectLibrary::MAX_ENTRIES + 5) {
+            library.touch(format!("p{i}"), format!("/tmp/p{i}"));
+        }
+        assert_eq!(library.entries.len(), ProjectLibrary::MAX_ENTRIES);
+        assert_eq!(library.recent(3).len(), 3);
+    }
+
+    #[test]
+    fn test_load_missing_file_returns_empty_library() {
+        let path = std::env::temp_dir().join("grabme_test_library_missing.json");
+        let _ = std::fs::remove_file(&path);
+        let library = ProjectLibrary::load(&path).unwrap();
+        assert!(library.entries.is_empty());
+    }
+
+    #[test]
+    fn test_library_save_load_and_prune() {
+        let dir = std::env::temp_dir().join("grabme_test_library");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let project_dir = dir.join("take-1");
+        crate::project::LoadedProject::create(&project_dir, "take-1", 1920, 1080, 60).unwrap();
+
+        let index_path = dir.join("library.json");
+        let mut library = ProjectLibrary::new();
+        library.touch("take-1", &project_dir);
+        library.touch("gone", dir.join("gone"));
+        library.save(&index_path).unwrap();
+
+        let mut loaded = ProjectLibrary::load(&index_path).unwrap();
+        assert_eq!(loaded.entries.len(), 2);
+        assert_eq!(loaded.prune_missing(), 1);
+        assert_eq!(loaded.entries[0].path, project_dir);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,112 @@
+//! System resource usage during capture (`meta/metrics.jsonl`).
+//!
+//! Performance demos are more convincing with the machine's load on screen.
+//! When enabled, capture samples CPU, memory, and (where the driver reports
+//! it) GPU utilisation a few times a second, stamped with the recording
+//! clock like input events. Export can draw the samples as a small graph.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::project::ProjectError;
+
+/// File name of the sidecar inside `meta/`.
+pub const METRICS_FILE: &str = "metrics.jsonl";
+
+/// One line of `metrics.jsonl`. Utilisation is in percent, `[0.0, 100.0]`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SystemMetricsSample {
+    /// Recording-clock time of the sample, on the same clock as event
+    /// timestamps.
+    pub t_ns: u64,
+
+    /// Busy share of all CPU cores since the previous sample.
+    pub cpu_percent: f64,
+
+    /// Share of physical memory in use.
+    pub ram_percent: f64,
+
+    /// GPU utilisation, when the driver exposes it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gpu_percent: Option<f64>,
+}
+
+impl SystemMetricsSample {
+    pub fn timestamp_secs(&self) -> f64 {
+        self.t_ns as f64 / 1_000_000_000.0
+    }
+}
+
+/// Path of the sidecar in the project at `project_root`.
+pub fn metrics_path(project_root: &Path) -> PathBuf {
+    project_root.join("meta").join(METRICS_FILE)
+}
+
+/// System metrics of a recording, ordered by time.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SystemMetrics {
+    samples: Vec<SystemMetricsSample>,
+}
+
+impl SystemMetrics {
+    /// Parse JSONL samples. Blank lines are skipped.
+    pub fn parse(jsonl: &str) -> Result<Self, serde_json::Error> {
+        let mut samples = jsonl
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(serde_json::from_str)
+            .collect::<Result<Vec<SystemMetricsSample>, _>>()?;
+        samples.sort_by_key(|sample| sample.t_ns);
+        Ok(Self { samples })
+    }
+
+    /// Load the sidecar of the project at `project_root`. `Ok(None)` when
+    /// the recording didn't sample system metrics.
+    pub fn load(project_root: &Path) -> Result<Option<Self>, ProjectError> {
+        let path = metrics_path(project_root);
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(source) => return Err(ProjectError::IoError { path, source }),
+        };
+        Self::parse(&content)
+            .map(Some)
+            .map_err(|source| ProjectError::ParseError { path, source })
+    }
+
+    pub fn samples(&self) -> &[SystemMetricsSample] {
+        &self.samples
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Whether any sample reports GPU utilisation.
+    pub fn has_gpu(&self) -> bool {
+        self.samples
+            .iter()
+            .any(|sample| sample.gpu_percent.is_some())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sorts_samples_and_keeps_missing_gpu() {
+        let jsonl = concat!(
+            "{\"t_ns\":500,\"cpu_percent\":40.0,\"ram_percent\":61.5,\"gpu_percent\":12.0}\n\n",
+            "{\"t_ns\":0,\"cpu_percent\":10.0,\"ram_percent\":60.0}\n",
+        );
+        let metrics = SystemMetrics::parse(jsonl).unwrap();
+        assert_eq!(metrics.samples().len(), 2);
+        assert_eq!(metrics.samples()[0].gpu_percent, None);
+        assert_eq!(metrics.samples()[1].gpu_percent, Some(12.0));
+        assert!(metrics.has_gpu());
+        assert!(SystemMetrics::parse("{\"t_ns\":1}").is_err());
+    }
+}
@@ -5,13 +5,19 @@
 
 use std::path::{Path, PathBuf};
 
+use chrono::{DateTime, Utc};
+use grabme_common::clock::ClockSource;
+use grabme_platform_core::coords::{CaptureNorm, PhysicalRect};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::event::PointerCoordinateSpace;
+use crate::atomic::{read_json_with_backup, write_json_with_backup};
+use crate::event::{PointerCoordinateSpace, PointerLayout};
 use crate::timeline::Timeline;
+use crate::viewport::{AspectLock, Viewport};
 
 /// Top-level project file (`project.json`).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Project {
     /// Schema version.
     pub version: String,
@@ -36,10 +42,93 @@ pub struct Project {
 
     /// Export configuration.
     pub export: ExportConfig,
+
+    /// Most recent successful export, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_export: Option<ExportRecord>,
+
+    /// Raw sources re-encoded or deleted by `grabme slim`, oldest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub slimmed_sources: Vec<SlimmedSource>,
+}
+
+/// Where and when a project was last exported.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ExportRecord {
+    /// Output path, relative to the project root when it lies inside it.
+    pub path: PathBuf,
+
+    /// Completion timestamp (ISO 8601).
+    pub exported_at: String,
+
+    /// Format that was written.
+    pub format: ExportFormat,
+}
+
+/// A raw source file that was shrunk or removed to save disk space.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct SlimmedSource {
+    /// Source path before slimming, relative to the project root.
+    pub path: String,
+
+    /// What was done to it.
+    pub action: SlimAction,
+
+    /// Size of the original file.
+    pub original_bytes: u64,
+
+    /// Size after slimming; zero when deleted.
+    pub slimmed_bytes: u64,
+
+    /// Path the re-encoded source was written to, when it changed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub new_path: Option<String>,
+
+    /// When it was slimmed (ISO 8601).
+    pub slimmed_at: String,
+}
+
+/// How a raw source was slimmed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SlimAction {
+    /// Re-encoded to a smaller file; the track still points at it.
+    Reencoded,
+    /// Deleted; the track can no longer be rendered.
+    Deleted,
+}
+
+impl Project {
+    /// Whether the source at `path` was deleted by `grabme slim`.
+    pub fn source_deleted(&self, path: &str) -> bool {
+        self.slimmed_sources
+            .iter()
+            .any(|slimmed| slimmed.action == SlimAction::Deleted && slimmed.path == path)
+    }
+}
+
+impl ExportRecord {
+    /// Record an export to `output` that just finished.
+    pub fn now(project_root: &Path, output: &Path, format: ExportFormat) -> Self {
+        let path = output
+            .strip_prefix(project_root)
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|_| output.to_path_buf());
+        Self {
+            path,
+            exported_at: chrono::Utc::now().to_rfc3339(),
+            format,
+        }
+    }
+
+    /// Absolute output path for a project at `project_root`.
+    pub fn resolve(&self, project_root: &Path) -> PathBuf {
+        project_root.join(&self.path)
+    }
 }
 
 /// Configuration used during recording.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct RecordingConfig {
     /// Capture resolution (physical pixels).
     pub capture_width: u32,
@@ -57,6 +146,11 @@ pub struct RecordingConfig {
     /// Whether the system cursor was hidden during capture.
     pub cursor_hidden: bool,
 
+    /// How the cursor reached the screen track. Absent in older projects,
+    /// where `cursor_hidden` decides.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor_mode: Option<CursorCaptureMode>,
+
     /// Selected monitor index when recording full screen.
     #[serde(default)]
     pub monitor_index: usize,
@@ -97,12 +191,342 @@ pub struct RecordingConfig {
     #[serde(default)]
     pub pointer_coordinate_space: PointerCoordinateSpace,
 
+    /// Window the recording was snapped to, if any.
+    #[serde(default)]
+    pub window_track: Option<WindowTrack>,
+
     /// Audio sample rate.
     pub audio_sample_rate: u32,
+
+    /// YUV quantization range of the encoded screen track.
+    #[serde(default)]
+    pub color_range: ColorRange,
+
+    /// Points where an audio input device was lost and capture fell back to
+    /// the default device. The gap before each switch is silence.
+    #[serde(default)]
+    pub audio_device_fallbacks: Vec<AudioDeviceFallback>,
+
+    /// Spans where the webcam was disconnected. The webcam track holds black
+    /// frames there; export covers them as `export.webcam.gap_mode` says.
+    #[serde(default)]
+    pub webcam_gaps: Vec<WebcamGap>,
+
+    /// Points where the captured display changed resolution. The screen
+    /// track keeps its first size and later frames are scaled into it with
+    /// borders, so export maps input coordinates per segment.
+    #[serde(default)]
+    pub resolution_changes: Vec<ResolutionChange>,
+
+    /// Clock that event and track timestamps were measured against.
+    #[serde(default)]
+    pub clock: RecordingClockInfo,
+
+    /// How the capture frame rate relates to the monitors' refresh rates.
+    #[serde(default)]
+    pub frame_pacing: FramePacing,
+}
+
+/// Frame-pacing decision made at recording start.
+///
+/// Capturing at a rate that does not divide the monitor refresh rate (60fps
+/// on a 144Hz panel) repeats frames irregularly, which reads as judder.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct FramePacing {
+    /// Frame rate that was asked for. Differs from `fps` when adjusted.
+    pub requested_fps: u32,
+
+    /// Refresh rate of the recorded monitor in Hz (0 when unknown).
+    pub monitor_refresh_hz: u32,
+
+    /// Distinct refresh rates across the captured desktop, ascending.
+    pub desktop_refresh_hz: Vec<u32>,
+
+    /// Whether `fps` was changed to divide the monitor refresh rate.
+    pub adjusted: bool,
+
+    /// Whether captured frames are expected to be unevenly paced.
+    pub uneven: bool,
+}
+
+/// Recording clock epoch and pipeline start times.
+///
+/// Event timestamps are nanoseconds since the epoch; a track's media PTS 0
+/// sits at its [`TrackRef::offset_ns`]. This ties both to the system clock
+/// and to wall time.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct RecordingClockInfo {
+    /// System clock the epoch was read from.
+    pub source: ClockSource,
+
+    /// Raw `source` reading at the epoch. Zero for recordings made before
+    /// the epoch was persisted.
+    pub epoch_monotonic_ns: u64,
+
+    /// Wall-clock time at the epoch (RFC 3339).
+    pub epoch_wall: String,
+
+    /// When each capture pipeline reached PLAYING, in nanoseconds since the
+    /// epoch, before any duration-based offset correction.
+    pub pipeline_starts_ns: PipelineStarts,
+}
+
+/// Per-pipeline start times in nanoseconds since the recording epoch.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct PipelineStarts {
+    pub screen: Option<i64>,
+    pub webcam: Option<i64>,
+    pub pip: Option<i64>,
+    pub mic: Option<i64>,
+    pub system_audio: Option<i64>,
+    pub events: Option<i64>,
+}
+
+impl RecordingClockInfo {
+    /// Whether the raw clock epoch was recorded.
+    pub fn has_epoch(&self) -> bool {
+        self.epoch_monotonic_ns > 0
+    }
+
+    /// Raw clock reading for an event timestamp.
+    pub fn event_to_clock_ns(&self, event_ns: u64) -> Option<u64> {
+        self.has_epoch()
+            .then(|| self.epoch_monotonic_ns.saturating_add(event_ns))
+    }
+
+    /// Event timestamp for a raw clock reading taken during the recording.
+    /// Readings before the epoch clamp to zero.
+    pub fn clock_to_event_ns(&self, clock_ns: u64) -> Option<u64> {
+        self.has_epoch()
+            .then(|| clock_ns.saturating_sub(self.epoch_monotonic_ns))
+    }
+
+    /// Wall-clock time of an event timestamp.
+    pub fn event_to_wall(&self, event_ns: u64) -> Option<DateTime<Utc>> {
+        let epoch = DateTime::parse_from_rfc3339(&self.epoch_wall).ok()?;
+        Some(epoch.with_timezone(&Utc) + chrono::Duration::nanoseconds(event_ns as i64))
+    }
+
+    /// Event timestamp of a wall-clock time. Times before the epoch clamp to
+    /// zero.
+    pub fn wall_to_event_ns(&self, wall: DateTime<Utc>) -> Option<u64> {
+        let epoch = DateTime::parse_from_rfc3339(&self.epoch_wall).ok()?;
+        let delta = wall.signed_duration_since(epoch.with_timezone(&Utc));
+        Some(delta.num_nanoseconds().unwrap_or(i64::MAX).max(0) as u64)
+    }
+}
+
+/// Marker for an audio input device swap during recording.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct AudioDeviceFallback {
+    /// Track that lost its device (`mic` or `system_audio`).
+    pub track: String,
+
+    /// Time of the swap, in nanoseconds from the start of that track.
+    pub t_ns: u64,
+}
+
+/// A span where the webcam was disconnected during recording.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct WebcamGap {
+    /// Start of the outage, in nanoseconds from the start of the webcam track.
+    pub start_ns: u64,
+
+    /// When the webcam was back, or the end of the recording.
+    pub end_ns: u64,
+}
+
+/// A display resolution change during recording.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ResolutionChange {
+    /// Time of the change, in nanoseconds from the start of the screen track.
+    pub t_ns: u64,
+
+    /// Display size from then on (physical pixels).
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ResolutionChange {
+    /// Map a normalized input position into a `track_width`x`track_height`
+    /// screen track holding this display size scaled to fit, centred.
+    ///
+    /// Input positions stay normalized against the display size at
+    /// recording start, so a pixel keeps its normalized value and only the
+    /// fit scale and border offset apply.
+    pub fn map_position(
+        &self,
+        position: CaptureNorm,
+        track_width: u32,
+        track_height: u32,
+    ) -> CaptureNorm {
+        if self.width == 0 || self.height == 0 || track_width == 0 || track_height == 0 {
+            return position;
+        }
+        let (track_w, track_h) = (track_width as f64, track_height as f64);
+        let scale = (track_w / self.width as f64).min(track_h / self.height as f64);
+        let offset_x = (1.0 - self.width as f64 * scale / track_w) / 2.0;
+        let offset_y = (1.0 - self.height as f64 * scale / track_h) / 2.0;
+        CaptureNorm::new(offset_x + position.x * scale, offset_y + position.y * scale)
+    }
+}
+
+impl RecordingConfig {
+    /// Export size for this recording when none is asked for: the recorded
+    /// monitor's size, fitted into `format`'s [default size
+    /// limit](ExportFormat::default_size_limit). Window and region captures
+    /// whose shape differs from the monitor's keep their captured size.
+    pub fn default_export_size(&self, format: ExportFormat) -> (u32, u32) {
+        let (width, height) = self.export_shape();
+        format.fit_default_size(width, height)
+    }
+
+    /// Export size for a requested `width` and `height`, either of which may
+    /// be missing. A missing side keeps the recorded shape, rounded to even
+    /// for the encoders; with neither, the
+    /// [default size](Self::default_export_size) is used.
+    pub fn export_size(
+        &self,
+        format: ExportFormat,
+        width: Option<u32>,
+        height: Option<u32>,
+    ) -> (u32, u32) {
+        let (shape_w, shape_h) = self.export_shape();
+        let (shape_w, shape_h) = (shape_w.max(1) as f64, shape_h.max(1) as f64);
+        let even = |value: f64| ((value / 2.0).round() as u32 * 2).max(2);
+        match (width, height) {
+            (Some(width), Some(height)) => (width, height),
+            (Some(width), None) => (width, even(width as f64 * shape_h / shape_w)),
+            (None, Some(height)) => (even(height as f64 * shape_w / shape_h), height),
+            (None, None) => self.default_export_size(format),
+        }
+    }
+
+    /// The recorded monitor's size, or the captured size for window and
+    /// region captures whose shape differs from the monitor's.
+    fn export_shape(&self) -> (u32, u32) {
+        let monitor = (self.monitor_width, self.monitor_height);
+        let capture = (self.capture_width, self.capture_height);
+        let same_shape = monitor.0 > 0
+            && monitor.1 > 0
+            && (monitor.0 as u64 * capture.1 as u64).abs_diff(monitor.1 as u64 * capture.0 as u64)
+                <= monitor.0.max(monitor.1) as u64;
+        if same_shape {
+            monitor
+        } else {
+            capture
+        }
+    }
+
+    /// The resolution change in effect `track_ns` into the screen track.
+    pub fn resolution_change_at(&self, track_ns: u64) -> Option<&ResolutionChange> {
+        self.resolution_changes
+            .iter()
+            .rev()
+            .find(|change| change.t_ns <= track_ns)
+    }
+
+    /// Whether the screen track already shows the system cursor, so export
+    /// must not draw a second one.
+    pub fn cursor_in_frames(&self) -> bool {
+        match self.cursor_mode {
+            Some(mode) => mode == CursorCaptureMode::Embedded,
+            None => !self.cursor_hidden,
+        }
+    }
+
+    /// The recorded monitor on the virtual desktop.
+    pub fn monitor_rect(&self) -> PhysicalRect {
+        PhysicalRect::new(
+            self.monitor_x,
+            self.monitor_y,
+            self.monitor_width,
+            self.monitor_height,
+        )
+    }
+
+    /// Bounds of the virtual desktop at recording start.
+    pub fn virtual_desktop_rect(&self) -> PhysicalRect {
+        PhysicalRect::new(
+            self.virtual_x,
+            self.virtual_y,
+            self.virtual_width,
+            self.virtual_height,
+        )
+    }
+
+    /// Layout for resolving pointer values in `space` to the recorded
+    /// monitor. `None` when the monitor or desktop geometry is unknown, or
+    /// when a window rather than the monitor was captured.
+    pub fn pointer_layout(&self, space: PointerCoordinateSpace) -> Option<PointerLayout> {
+        if self.window_track.is_some()
+            || self.monitor_width == 0
+            || self.monitor_height == 0
+            || self.virtual_width == 0
+            || self.virtual_height == 0
+        {
+            return None;
+        }
+        Some(PointerLayout {
+            space,
+            virtual_desktop: self.virtual_desktop_rect(),
+            capture: self.monitor_rect(),
+            monitors: self
+                .monitors
+                .iter()
+                .map(|monitor| PhysicalRect {
+                    x: monitor.x,
+                    y: monitor.y,
+                    width: monitor.width,
+                    height: monitor.height,
+                })
+                .collect(),
+        })
+    }
+}
+
+/// How the system cursor was captured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CursorCaptureMode {
+    /// Left out of the capture.
+    Hidden,
+    /// Drawn into the frames.
+    Embedded,
+    /// Left out of the frames and sent alongside them as stream metadata.
+    Metadata,
+}
+
+/// YUV quantization range of a video stream.
+///
+/// Mismatched range flags are the usual cause of washed-out or crushed
+/// exports, so capture records the range it encoded and export converts
+/// explicitly instead of relying on encoder defaults.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorRange {
+    /// Broadcast/TV range (16-235 luma). Default for YUV encoders.
+    #[default]
+    Limited,
+    /// Full/PC range (0-255 luma).
+    Full,
+}
+
+impl ColorRange {
+    /// Range name understood by FFmpeg (`tv` or `pc`).
+    pub fn ffmpeg_name(self) -> &'static str {
+        match self {
+            Self::Limited => "tv",
+            Self::Full => "pc",
+        }
+    }
 }
 
 /// A monitor geometry snapshot stored in recording metadata.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct RecordedMonitor {
     pub name: String,
     pub x: i32,
@@ -112,8 +536,61 @@ pub struct RecordedMonitor {
     pub primary: bool,
 }
 
+/// Geometry history of the window a recording was snapped to.
+///
+/// On X11 the full virtual desktop is captured and the window is followed in
+/// post, so moves and resizes are logged here rather than baked into the video.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct WindowTrack {
+    /// Platform window identifier (e.g. X11 `0x3a00007`).
+    pub window_id: String,
+
+    /// Geometry samples, recorded only when the window moved or resized.
+    #[serde(default)]
+    pub samples: Vec<WindowGeometrySample>,
+}
+
+/// Window rectangle at a point in the recording.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct WindowGeometrySample {
+    /// Nanoseconds since recording start (same clock as input events).
+    pub t_ns: u64,
+
+    /// Window rectangle in virtual-desktop pixels.
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl WindowTrack {
+    /// Window rectangles as normalized viewports of the captured virtual
+    /// desktop, paired with their time in seconds.
+    pub fn viewports(&self, recording: &RecordingConfig) -> Vec<(f64, Viewport)> {
+        let virtual_w = recording.virtual_width as f64;
+        let virtual_h = recording.virtual_height as f64;
+        if virtual_w <= 0.0 || virtual_h <= 0.0 {
+            return vec![];
+        }
+
+        self.samples
+            .iter()
+            .map(|sample| {
+                let w = (sample.width as f64 / virtual_w).clamp(0.01, 1.0);
+                let h = (sample.height as f64 / virtual_h).clamp(0.01, 1.0);
+                let x = ((sample.x - recording.virtual_x) as f64 / virtual_w).clamp(0.0, 1.0 - w);
+                let y = ((sample.y - recording.virtual_y) as f64 / virtual_h).clamp(0.0, 1.0 - h);
+                (
+                    sample.t_ns as f64 / 1_000_000_000.0,
+                    Viewport::new(x, y, w, h),
+                )
+            })
+            .collect()
+    }
+}
+
 /// Display server type.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum DisplayServer {
     Wayland,
@@ -123,7 +600,7 @@ pub enum DisplayServer {
 }
 
 /// References to source media files (relative to project root).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Tracks {
     /// Screen capture video.
     pub screen: Option<TrackRef>,
@@ -131,6 +608,11 @@ pub struct Tracks {
     /// Webcam video.
     pub webcam: Option<TrackRef>,
 
+    /// Auxiliary video (a second monitor or capture card) shown as
+    /// picture-in-picture.
+    #[serde(default)]
+    pub pip: Option<TrackRef>,
+
     /// Microphone audio.
     pub mic: Option<TrackRef>,
 
@@ -140,10 +622,14 @@ pub struct Tracks {
     /// Per-application audio tracks.
     #[serde(default)]
     pub app_audio: Vec<AppAudioTrack>,
+
+    /// Background music bed mixed in at export time.
+    #[serde(default)]
+    pub music: Option<MusicTrack>,
 }
 
 /// Reference to a media file with metadata.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TrackRef {
     /// Relative path from project root to the media file.
     pub path: String,
@@ -158,10 +644,49 @@ pub struct TrackRef {
     /// Used to synchronize tracks that may have started at different times.
     #[serde(default)]
     pub offset_ns: i64,
+
+    /// Format an audio track was captured in, as negotiated with the
+    /// device. Absent for video tracks and older recordings.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audio: Option<AudioFormat>,
+
+    /// Format a webcam track was captured in, as negotiated with the
+    /// camera. Absent for other tracks and older recordings.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub video: Option<VideoFormat>,
+}
+
+/// Sample rate and channel count of captured audio.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct AudioFormat {
+    pub sample_rate: u32,
+    pub channels: u32,
+}
+
+/// Frame size and rate of captured video.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct VideoFormat {
+    pub width: u32,
+    pub height: u32,
+    pub fps: u32,
+}
+
+impl TrackRef {
+    /// Media PTS (seconds) of an event timestamp. Negative when the event
+    /// happened before this track started.
+    pub fn event_to_media_secs(&self, event_ns: u64) -> f64 {
+        (event_ns as i64 - self.offset_ns) as f64 / 1_000_000_000.0
+    }
+
+    /// Event timestamp of a media PTS. PTS before the epoch clamp to zero.
+    pub fn media_secs_to_event_ns(&self, media_secs: f64) -> u64 {
+        let ns = (media_secs * 1_000_000_000.0).round() as i64 + self.offset_ns;
+        ns.max(0) as u64
+    }
 }
 
 /// Per-application audio track.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct AppAudioTrack {
     /// Application name or PID.
     pub app_name: String,
@@ -170,8 +695,60 @@ pub struct AppAudioTrack {
     pub track: TrackRef,
 }
 
+/// Background music mixed under the recording during export.
+///
+/// The file is looped or trimmed to the export duration, faded in and out,
+/// and optionally ducked while the microphone carries narration.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct MusicTrack {
+    /// Relative path from project root to the music file.
+    pub path: String,
+
+    /// Music gain in dB applied before mixing (typically -24 to -12).
+    pub gain_db: f64,
+
+    /// Repeat the music when it is shorter than the export.
+    pub looped: bool,
+
+    /// Fade-in duration at the start of the export, in seconds.
+    pub fade_in_secs: f64,
+
+    /// Fade-out duration at the end of the export, in seconds.
+    pub fade_out_secs: f64,
+
+    /// Lower the music while the microphone track is active.
+    pub ducking: bool,
+}
+
+impl MusicTrack {
+    /// Default music gain used when none is specified.
+    pub const DEFAULT_GAIN_DB: f64 = -18.0;
+
+    /// Create a music track with default mix settings.
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            ..Self::default()
+        }
+    }
+}
+
+impl Default for MusicTrack {
+    fn default() -> Self {
+        Self {
+            path: String::new(),
+            gain_db: Self::DEFAULT_GAIN_DB,
+            looped: true,
+            fade_in_secs: 1.5,
+            fade_out_secs: 2.0,
+            ducking: true,
+        }
+    }
+}
+
 /// Export configuration.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ExportConfig {
     /// Output format.
     pub format: ExportFormat,
@@ -196,6 +773,10 @@ pub struct ExportConfig {
     #[serde(default)]
     pub burn_subtitles: bool,
 
+    /// Styling for burned-in subtitles.
+    #[serde(default)]
+    pub subtitle_style: SubtitleStyle,
+
     /// Webcam overlay configuration for export.
     #[serde(default)]
     pub webcam: WebcamConfig,
@@ -203,10 +784,46 @@ pub struct ExportConfig {
     /// Canvas framing style controls for export rendering.
     #[serde(default)]
     pub canvas: CanvasStyleConfig,
+
+    /// Pen and touch stroke overlay.
+    #[serde(default)]
+    pub strokes: StrokeOverlayConfig,
+
+    /// Reactions dropped with hotkeys during recording.
+    #[serde(default)]
+    pub reactions: ReactionOverlayConfig,
+
+    /// Gamepad diagram overlay.
+    #[serde(default)]
+    pub gamepad: GamepadOverlayConfig,
+
+    /// Graph of CPU, memory and GPU usage sampled during capture.
+    #[serde(default)]
+    pub metrics: MetricsOverlayConfig,
+
+    /// Time-of-day clock burned into the frame.
+    #[serde(default)]
+    pub clock: ClockOverlayConfig,
+
+    /// Picture-in-picture layer for the auxiliary video track.
+    #[serde(default)]
+    pub pip: PipConfig,
+
+    /// Audio visualisation drawn instead of the screen.
+    #[serde(default)]
+    pub waveform: WaveformConfig,
+
+    /// Title, author and chapters written into the exported file.
+    #[serde(default)]
+    pub metadata: ExportMetadata,
+
+    /// Padding that keeps the picture clear of TV overscan.
+    #[serde(default)]
+    pub overscan: OverscanConfig,
 }
 
 /// Output video format.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum ExportFormat {
     #[serde(rename = "mp4-h264")]
@@ -215,10 +832,46 @@ pub enum ExportFormat {
     Mp4H265,
     Gif,
     Webm,
+    /// Numbered PNG frames (`frame_000000.png`, ...). The export output
+    /// path is the target directory.
+    #[serde(rename = "png-sequence")]
+    PngSequence,
+}
+
+impl ExportFormat {
+    /// Whether this format writes a directory of still frames instead of a file.
+    pub fn is_image_sequence(self) -> bool {
+        matches!(self, Self::PngSequence)
+    }
+
+    /// Largest default output, as long edge by short edge. GIFs grow with
+    /// every pixel, so they stop at 720p; everything else at 4K UHD.
+    pub fn default_size_limit(self) -> (u32, u32) {
+        match self {
+            Self::Gif => (1280, 720),
+            _ => (3840, 2160),
+        }
+    }
+
+    /// `width` x `height` scaled down, keeping its shape, to fit the
+    /// [default size limit](Self::default_size_limit) in either orientation.
+    /// Dimensions are even for the encoders.
+    pub fn fit_default_size(self, width: u32, height: u32) -> (u32, u32) {
+        let (long, short) = self.default_size_limit();
+        let (width, height) = (width.max(2) as f64, height.max(2) as f64);
+        let (max_w, max_h) = if width >= height {
+            (long, short)
+        } else {
+            (short, long)
+        };
+        let scale = (max_w as f64 / width).min(max_h as f64 / height).min(1.0);
+        let even = |value: f64| ((value * scale / 2.0).floor() as u32 * 2).max(2);
+        (even(width), even(height))
+    }
 }
 
 /// Aspect ratio / framing mode.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum AspectMode {
     /// Standard 16:9 widescreen.
@@ -231,8 +884,60 @@ pub enum AspectMode {
     Custom,
 }
 
+impl AspectMode {
+    /// Name used on the command line and in variant file names.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AspectMode::Landscape => "landscape",
+            AspectMode::Portrait => "vertical",
+            AspectMode::Square => "square",
+            AspectMode::Custom => "custom",
+        }
+    }
+
+    /// Width and height of the frame, or `None` for custom framing.
+    pub fn ratio(self) -> Option<(u32, u32)> {
+        match self {
+            AspectMode::Landscape => Some((16, 9)),
+            AspectMode::Portrait => Some((9, 16)),
+            AspectMode::Square => Some((1, 1)),
+            AspectMode::Custom => None,
+        }
+    }
+
+    /// Output size in this aspect with the short edge of `width`x`height`,
+    /// rounded to even pixels. Custom framing keeps the size.
+    pub fn dimensions(self, width: u32, height: u32) -> (u32, u32) {
+        let Some((w, h)) = self.ratio() else {
+            return (width, height);
+        };
+        let short = width.min(height).max(2);
+        let long = |ratio: f64| ((short as f64 * ratio / 2.0).round() as u32 * 2).max(2);
+        if w >= h {
+            (long(w as f64 / h as f64), short & !1)
+        } else {
+            (short & !1, long(h as f64 / w as f64))
+        }
+    }
+}
+
+impl std::str::FromStr for AspectMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "landscape" | "16:9" => Ok(AspectMode::Landscape),
+            "vertical" | "portrait" | "9:16" => Ok(AspectMode::Portrait),
+            "square" | "1:1" => Ok(AspectMode::Square),
+            other => Err(format!(
+                "Unknown aspect: {other}. Use: landscape, square, vertical"
+            )),
+        }
+    }
+}
+
 /// Webcam overlay settings.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct WebcamConfig {
     /// Whether webcam overlay is enabled during export.
@@ -251,10 +956,33 @@ pub struct WebcamConfig {
 
     /// Overlay opacity [0.0, 1.0].
     pub opacity: f64,
+
+    /// How spans where the webcam was disconnected are covered.
+    #[serde(default)]
+    pub gap_mode: WebcamGapMode,
+
+    /// Slide the overlay to the corner farthest from the pointer when the
+    /// pointer comes under it, such as after a zoom. `corner` is where it
+    /// starts.
+    pub auto_corner: bool,
+
+    /// How long the slide to another corner takes, in seconds.
+    pub corner_slide_secs: f64,
+}
+
+/// What the webcam overlay shows while the webcam was disconnected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WebcamGapMode {
+    /// Hold the last frame before the disconnect.
+    #[default]
+    Freeze,
+    /// Hide the overlay until the webcam is back.
+    Hide,
 }
 
 /// Canvas/background styling controls used by the export renderer.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct CanvasStyleConfig {
     /// Background color as hex string (for example `#1a1a1a`).
@@ -278,6 +1006,322 @@ impl Default for CanvasStyleConfig {
     }
 }
 
+/// Draws recorded pen and touch strokes over the screen at export.
+///
+/// Strokes are drawn on the captured screen before zoom, so they follow the
+/// camera. Eraser strokes are never drawn.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct StrokeOverlayConfig {
+    /// Whether strokes are drawn.
+    pub enabled: bool,
+    /// Stroke color as hex string.
+    pub color: String,
+    /// Line width in source pixels at full pressure.
+    pub width: f64,
+    /// How long a finished stroke stays visible, in seconds.
+    pub linger_secs: f64,
+}
+
+impl Default for StrokeOverlayConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            color: "#ff3b30".to_string(),
+            width: 6.0,
+            linger_secs: 1.5,
+        }
+    }
+}
+
+/// Animates reaction markers at the pointer position at export.
+///
+/// Like strokes, reactions are drawn on the captured screen before zoom.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct ReactionOverlayConfig {
+    /// Whether reactions are drawn.
+    pub enabled: bool,
+    /// Emoji size as a fraction of the source height.
+    pub size_ratio: f64,
+    /// How long each reaction stays on screen, in seconds.
+    pub duration_secs: f64,
+}
+
+impl Default for ReactionOverlayConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            size_ratio: 0.08,
+            duration_secs: 1.2,
+        }
+    }
+}
+
+/// Controller diagram showing held gamepad buttons, for game tutorials.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct GamepadOverlayConfig {
+    /// Whether the diagram is drawn. It only appears while a gamepad is in use.
+    pub enabled: bool,
+    /// Corner placement for the diagram.
+    pub corner: WebcamCorner,
+    /// Diagram height relative to output height.
+    pub size_ratio: f64,
+    /// Margin from edges as ratio of output height.
+    pub margin_ratio: f64,
+    /// Fill color for held buttons, as hex string.
+    pub highlight_color: String,
+}
+
+impl Default for GamepadOverlayConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            corner: WebcamCorner::BottomLeft,
+            size_ratio: 0.22,
+            margin_ratio: 0.03,
+            highlight_color: "#f5c518".to_string(),
+        }
+    }
+}
+
+/// Corner graph of system usage from `meta/metrics.jsonl`, for
+/// performance demos.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct MetricsOverlayConfig {
+    /// Whether the graph is drawn. Needs a recording made with metrics.
+    pub enabled: bool,
+    /// Corner placement for the graph.
+    pub corner: WebcamCorner,
+    /// Graph height relative to output height.
+    pub size_ratio: f64,
+    /// Margin from edges as ratio of output height.
+    pub margin_ratio: f64,
+    /// Seconds of history across the width of the graph.
+    pub history_secs: f64,
+}
+
+impl Default for MetricsOverlayConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            corner: WebcamCorner::TopRight,
+            size_ratio: 0.16,
+            margin_ratio: 0.03,
+            history_secs: 30.0,
+        }
+    }
+}
+
+/// Wall clock showing when each frame was recorded, from the recording
+/// epoch plus media time, for compliance recordings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct ClockOverlayConfig {
+    /// Whether the clock is drawn. Needs a recording with a wall-clock epoch.
+    pub enabled: bool,
+    /// `strftime` format of the clock.
+    pub format: String,
+    /// Show UTC instead of the exporting machine's local time.
+    pub utc: bool,
+    /// Corner placement for the clock.
+    pub corner: WebcamCorner,
+    /// Text height relative to output height.
+    pub size_ratio: f64,
+    /// Margin from edges as ratio of output height.
+    pub margin_ratio: f64,
+}
+
+impl Default for ClockOverlayConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            format: "%Y-%m-%d %H:%M:%S".to_string(),
+            utc: false,
+            corner: WebcamCorner::BottomLeft,
+            size_ratio: 0.035,
+            margin_ratio: 0.02,
+        }
+    }
+}
+
+/// Container metadata written into exported files. Image sequences and
+/// GIFs carry none.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct ExportMetadata {
+    /// Title [default: the project name].
+    pub title: Option<String>,
+    /// Author, written as the `artist` tag.
+    pub author: Option<String>,
+    /// Free-form comment.
+    pub comment: Option<String>,
+    /// Creation time (RFC 3339) [default: the recording's wall-clock
+    /// epoch].
+    pub creation_time: Option<String>,
+    /// Chapter markers, in recording time.
+    pub chapters: Vec<ChapterMarker>,
+}
+
+/// Start of a chapter in exported files.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ChapterMarker {
+    /// Seconds from recording start. Speed segments and partial exports
+    /// move it in the output.
+    pub time_secs: f64,
+    pub title: String,
+}
+
+/// Layout of the auxiliary video track (`tracks.pip`) at export.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct PipConfig {
+    /// Whether the auxiliary track is composited when present.
+    pub enabled: bool,
+    /// Corner placement for the layer.
+    pub corner: WebcamCorner,
+    /// Layer bounding-box size relative to output dimensions.
+    pub size_ratio: f64,
+    /// Margin from edges as ratio of output dimensions.
+    pub margin_ratio: f64,
+    /// Layer opacity [0.0, 1.0].
+    pub opacity: f64,
+}
+
+impl Default for PipConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            corner: WebcamCorner::TopRight,
+            size_ratio: 0.30,
+            margin_ratio: 0.03,
+            opacity: 1.0,
+        }
+    }
+}
+
+/// Shrinks the finished frame and pads it with the canvas background, so
+/// TVs that crop the picture edges don't cut anything off.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct OverscanConfig {
+    /// Whether the frame is padded.
+    pub enabled: bool,
+    /// Padding on each side as a ratio of the output size. `0.05` keeps the
+    /// picture inside the 90% title-safe area. At most `0.10`.
+    pub margin_ratio: f64,
+}
+
+impl Default for OverscanConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            margin_ratio: 0.05,
+        }
+    }
+}
+
+/// Animated audio visualisation that takes the place of the screen layer,
+/// for audio-first recordings where the screen content doesn't matter.
+/// The canvas background and webcam are composited as usual.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct WaveformConfig {
+    /// Whether the export shows the audio instead of the screen.
+    pub enabled: bool,
+    /// How the audio is drawn.
+    pub style: WaveformStyle,
+    /// Waveform color as hex string. The spectrum uses its own palette.
+    pub color: String,
+    /// Height of the visualisation relative to output height.
+    pub height_ratio: f64,
+}
+
+impl Default for WaveformConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            style: WaveformStyle::Waves,
+            color: "#ffffff".to_string(),
+            height_ratio: 0.4,
+        }
+    }
+}
+
+/// Audio visualisation style.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WaveformStyle {
+    /// Waveform of the audio playing at each frame (ffmpeg `showwaves`).
+    #[default]
+    Waves,
+    /// Scrolling frequency spectrum (ffmpeg `showspectrum`).
+    Spectrum,
+}
+
+impl std::str::FromStr for WaveformStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "waves" | "waveform" => Ok(WaveformStyle::Waves),
+            "spectrum" | "spectrogram" => Ok(WaveformStyle::Spectrum),
+            other => Err(format!(
+                "Unknown waveform style: {other}. Use: waves, spectrum"
+            )),
+        }
+    }
+}
+
+/// Styling for burned-in subtitles.
+///
+/// Sizes are in output pixels. Subtitles are kept inside the safe area,
+/// which is inset from every edge by `safe_area_ratio` of the output size.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct SubtitleStyle {
+    /// Font family name, resolved through fontconfig.
+    pub font: String,
+    /// Font size in output pixels.
+    pub font_size: u32,
+    /// Text color as hex string (for example `#ffffff`).
+    pub color: String,
+    /// Outline color as hex string.
+    pub outline_color: String,
+    /// Outline width in output pixels (0 disables the outline).
+    pub outline_width: f64,
+    /// Vertical placement inside the safe area.
+    pub position: SubtitlePosition,
+    /// Safe-area inset as a ratio of output dimensions, in `[0.0, 0.25]`.
+    pub safe_area_ratio: f64,
+}
+
+impl Default for SubtitleStyle {
+    fn default() -> Self {
+        Self {
+            font: "Sans".to_string(),
+            font_size: 48,
+            color: "#ffffff".to_string(),
+            outline_color: "#000000".to_string(),
+            outline_width: 3.0,
+            position: SubtitlePosition::Bottom,
+            safe_area_ratio: 0.05,
+        }
+    }
+}
+
+/// Vertical placement for burned-in subtitles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SubtitlePosition {
+    Top,
+    Middle,
+    #[default]
+    Bottom,
+}
+
 impl Default for WebcamConfig {
     fn default() -> Self {
         Self {
@@ -286,12 +1330,15 @@ impl Default for WebcamConfig {
             corner: WebcamCorner::BottomRight,
             margin_ratio: 0.03,
             opacity: 1.0,
+            gap_mode: WebcamGapMode::default(),
+            auto_corner: false,
+            corner_slide_secs: 0.4,
         }
     }
 }
 
 /// Corner placement for webcam overlay.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum WebcamCorner {
     TopLeft,
@@ -318,6 +1365,7 @@ impl Project {
     /// Create a new project with defaults.
     pub fn new(name: impl Into<String>, width: u32, height: u32, fps: u32) -> Self {
         let now = chrono::Utc::now().to_rfc3339();
+        let (export_width, export_height) = ExportFormat::Mp4H264.fit_default_size(width, height);
         Self {
             version: "1.0".to_string(),
             name: name.into(),
@@ -331,6 +1379,7 @@ impl Project {
                 scale_factor: 1.0,
                 display_server: DisplayServer::Wayland,
                 cursor_hidden: true,
+                cursor_mode: None,
                 monitor_index: 0,
                 monitor_name: String::new(),
                 monitor_x: 0,
@@ -343,27 +1392,48 @@ impl Project {
                 virtual_height: height,
                 monitors: vec![],
                 pointer_coordinate_space: PointerCoordinateSpace::LegacyUnspecified,
+                window_track: None,
                 audio_sample_rate: 48000,
+                color_range: ColorRange::Limited,
+                audio_device_fallbacks: vec![],
+                webcam_gaps: vec![],
+                resolution_changes: vec![],
+                clock: RecordingClockInfo::default(),
+                frame_pacing: FramePacing::default(),
             },
             tracks: Tracks {
                 screen: None,
                 webcam: None,
+                pip: None,
                 mic: None,
                 system_audio: None,
                 app_audio: vec![],
+                music: None,
             },
             export: ExportConfig {
                 format: ExportFormat::Mp4H264,
-                width,
-                height,
+                width: export_width,
+                height: export_height,
                 fps,
                 video_bitrate_kbps: 8000,
                 audio_bitrate_kbps: 192,
                 aspect_mode: AspectMode::Landscape,
                 burn_subtitles: false,
+                subtitle_style: SubtitleStyle::default(),
                 webcam: WebcamConfig::default(),
                 canvas: CanvasStyleConfig::default(),
+                strokes: StrokeOverlayConfig::default(),
+                reactions: ReactionOverlayConfig::default(),
+                gamepad: GamepadOverlayConfig::default(),
+                metrics: MetricsOverlayConfig::default(),
+                clock: ClockOverlayConfig::default(),
+                pip: PipConfig::default(),
+                waveform: WaveformConfig::default(),
+                metadata: ExportMetadata::default(),
+                overscan: OverscanConfig::default(),
             },
+            last_export: None,
+            slimmed_sources: vec![],
         }
     }
 }
@@ -376,31 +1446,12 @@ impl LoadedProject {
         let project_path = root.join("meta").join("project.json");
         let timeline_path = root.join("meta").join("timeline.json");
 
-        let project_json =
-            std::fs::read_to_string(&project_path).map_err(|e| ProjectError::IoError {
-                path: project_path.clone(),
-                source: e,
-            })?;
-
         let project: Project =
-            serde_json::from_str(&project_json).map_err(|e| ProjectError::ParseError {
-                path: project_path,
-                source: e,
+            read_json_with_backup(&project_path)?.ok_or_else(|| ProjectError::IoError {
+                path: project_path.clone(),
+                source: std::io::Error::from(std::io::ErrorKind::NotFound),
             })?;
-
-        let timeline = if timeline_path.exists() {
-            let timeline_json =
-                std::fs::read_to_string(&timeline_path).map_err(|e| ProjectError::IoError {
-                    path: timeline_path.clone(),
-                    source: e,
-                })?;
-            serde_json::from_str(&timeline_json).map_err(|e| ProjectError::ParseError {
-                path: timeline_path,
-                source: e,
-            })?
-        } else {
-            Timeline::new()
-        };
+        let timeline = read_json_with_backup(&timeline_path)?.unwrap_or_else(Timeline::new);
 
         Ok(Self {
             root,
@@ -417,27 +1468,8 @@ impl LoadedProject {
             source: e,
         })?;
 
-        let project_path = meta_dir.join("project.json");
-        let project_json =
-            serde_json::to_string_pretty(&self.project).map_err(|e| ProjectError::ParseError {
-                path: project_path.clone(),
-                source: e,
-            })?;
-        std::fs::write(&project_path, project_json).map_err(|e| ProjectError::IoError {
-            path: project_path,
-            source: e,
-        })?;
-
-        let timeline_path = meta_dir.join("timeline.json");
-        let timeline_json =
-            serde_json::to_string_pretty(&self.timeline).map_err(|e| ProjectError::ParseError {
-                path: timeline_path.clone(),
-                source: e,
-            })?;
-        std::fs::write(&timeline_path, timeline_json).map_err(|e| ProjectError::IoError {
-            path: timeline_path,
-            source: e,
-        })?;
+        write_json_with_backup(&meta_dir.join("project.json"), &self.project)?;
+        write_json_with_backup(&meta_dir.join("timeline.json"), &self.timeline)?;
 
         Ok(())
     }
@@ -476,7 +1508,12 @@ impl LoadedProject {
         let check_track = |track: &Option<TrackRef>, label: &str, errors: &mut Vec<String>| {
             if let Some(t) = track {
                 let path = self.root.join(&t.path);
-                if !path.exists() {
+                if path.exists() {
+                    return;
+                }
+                if self.project.source_deleted(&t.path) {
+                    errors.push(format!("{label} source removed by grabme slim: {}", t.path));
+                } else {
                     errors.push(format!("{label} source missing: {}", t.path));
                 }
             }
@@ -490,6 +1527,11 @@ impl LoadedProject {
             "System audio",
             &mut errors,
         );
+        if let Some(music) = &self.project.tracks.music {
+            if !self.root.join(&music.path).exists() {
+                errors.push(format!("Music source missing: {}", music.path));
+            }
+        }
 
         // Check events file
         let events_path = self.root.join("meta").join("events.jsonl");
@@ -499,6 +1541,50 @@ impl LoadedProject {
 
         errors
     }
+
+    /// Shape timeline viewports need for the configured export. `None` for
+    /// square and vertical exports, which reframe the camera themselves.
+    pub fn aspect_lock(&self) -> Option<AspectLock> {
+        if matches!(
+            self.project.export.aspect_mode,
+            AspectMode::Square | AspectMode::Portrait
+        ) {
+            return None;
+        }
+        let recording = &self.project.recording;
+        let export = &self.project.export;
+        Some(AspectLock::new(
+            recording.capture_width,
+            recording.capture_height,
+            export.width,
+            export.height,
+        ))
+    }
+
+    /// Timeline keyframes that would be squashed or stretched in the
+    /// export (see [`Self::aspect_lock`]).
+    pub fn validate_timeline(&self) -> Vec<String> {
+        let Some(lock) = self.aspect_lock() else {
+            return vec![];
+        };
+        let capture_aspect = self.project.recording.capture_width.max(1) as f64
+            / self.project.recording.capture_height.max(1) as f64;
+        let export_aspect =
+            self.project.export.width.max(1) as f64 / self.project.export.height.max(1) as f64;
+        self.timeline
+            .aspect_mismatches(lock)
+            .into_iter()
+            .map(|idx| {
+                let keyframe = &self.timeline.keyframes[idx];
+                format!(
+                    "Keyframe at {:.2}s is {:.2}:1 but the export is {:.2}:1, so it will be distorted",
+                    keyframe.time_secs,
+                    keyframe.viewport.pixel_aspect(capture_aspect),
+                    export_aspect
+                )
+            })
+            .collect()
+    }
 }
 
 /// Errors that can occur when working with projects.
@@ -549,6 +1635,111 @@ mod tests {
         assert_eq!(project.export.fps, 60);
     }
 
+    #[test]
+    fn test_export_record_keeps_paths_relative_to_project() {
+        let root = Path::new("/videos/demo");
+        let inside = ExportRecord::now(
+            root,
+            &root.join("exports").join("output.mp4"),
+            ExportFormat::Mp4H264,
+        );
+        assert_eq!(inside.path, PathBuf::from("exports/output.mp4"));
+        assert_eq!(
+            inside.resolve(root),
+            root.join("exports").join("output.mp4")
+        );
+
+        let outside = ExportRecord::now(root, Path::new("/tmp/clip.gif"), ExportFormat::Gif);
+        assert_eq!(outside.resolve(root), PathBuf::from("/tmp/clip.gif"));
+
+        let json = serde_json::to_string(&Project::new("Test", 1920, 1080, 60)).unwrap();
+        assert!(!json.contains("last_export"));
+    }
+
+    #[test]
+    fn test_cursor_in_frames_prefers_recorded_mode() {
+        let mut recording = Project::new("Test", 1920, 1080, 60).recording;
+        assert!(!recording.cursor_in_frames());
+        recording.cursor_hidden = false;
+        assert!(recording.cursor_in_frames());
+
+        recording.cursor_mode = Some(CursorCaptureMode::Metadata);
+        assert!(!recording.cursor_in_frames());
+        recording.cursor_mode = Some(CursorCaptureMode::Embedded);
+        assert!(recording.cursor_in_frames());
+    }
+
+    #[test]
+    fn test_resolution_change_maps_positions_into_letterbox() {
+        let mut project = Project::new("Test Recording", 1920, 1080, 60);
+        project.recording.resolution_changes = vec![
+            ResolutionChange {
+                t_ns: 2_000_000_000,
+                width: 1280,
+                height: 1024,
+            },
+            ResolutionChange {
+                t_ns: 5_000_000_000,
+                width: 1920,
+                height: 1080,
+            },
+        ];
+        let recording = &project.recording;
+        assert_eq!(recording.resolution_change_at(1_000_000_000), None);
+        assert_eq!(
+            recording.resolution_change_at(5_000_000_000).unwrap().width,
+            1920
+        );
+
+        // 1280x1024 fits 1080 tall: scaled by 1080/1024, pillarboxed.
+        let change = recording.resolution_change_at(3_000_000_000).unwrap();
+        let scale = 1080.0 / 1024.0;
+        let offset_x = (1.0 - 1280.0 * scale / 1920.0) / 2.0;
+        let mapped = change.map_position(CaptureNorm::new(0.0, 0.0), 1920, 1080);
+        assert!((mapped.x - offset_x).abs() < 1e-9 && mapped.y.abs() < 1e-9);
+        // The new display's bottom-right corner lands on the picture's edge.
+        let corner = CaptureNorm::new(1280.0 / 1920.0, 1024.0 / 1080.0);
+        let mapped = change.map_position(corner, 1920, 1080);
+        assert!((mapped.x - (1.0 - offset_x)).abs() < 1e-9);
+        assert!((mapped.y - 1.0).abs() < 1e-9);
+
+        let same = recording.resolution_change_at(6_000_000_000).unwrap();
+        let point = CaptureNorm::new(0.3, 0.7);
+        assert_eq!(same.map_position(point, 1920, 1080), point);
+    }
+
+    #[test]
+    fn test_clock_conversions_between_event_media_and_wall_time() {
+        let clock = RecordingClockInfo {
+            source: ClockSource::Monotonic,
+            epoch_monotonic_ns: 5_000_000_000,
+            epoch_wall: "2026-01-02T03:04:05Z".to_string(),
+            pipeline_starts_ns: PipelineStarts::default(),
+        };
+        assert_eq!(clock.event_to_clock_ns(250), Some(5_000_000_250));
+        assert_eq!(clock.clock_to_event_ns(4_000_000_000), Some(0));
+
+        let wall = clock.event_to_wall(1_500_000_000).unwrap();
+        assert_eq!(wall.to_rfc3339(), "2026-01-02T03:04:06.500+00:00");
+        assert_eq!(clock.wall_to_event_ns(wall), Some(1_500_000_000));
+
+        let track = TrackRef {
+            path: "sources/webcam.mkv".to_string(),
+            duration_secs: 10.0,
+            codec: "h264".to_string(),
+            offset_ns: 200_000_000,
+            audio: None,
+            video: None,
+        };
+        assert!((track.event_to_media_secs(1_200_000_000) - 1.0).abs() < 1e-9);
+        assert_eq!(track.media_secs_to_event_ns(1.0), 1_200_000_000);
+        assert!(track.event_to_media_secs(0) < 0.0);
+
+        let legacy: RecordingClockInfo = serde_json::from_str("{}").unwrap();
+        assert!(!legacy.has_epoch());
+        assert_eq!(legacy.event_to_clock_ns(1), None);
+    }
+
     #[test]
     fn test_project_serialization() {
         let project = Project::new("Test", 1920, 1080, 30);
@@ -558,12 +1749,59 @@ mod tests {
         assert_eq!(parsed.version, "1.0");
     }
 
+    #[test]
+    fn test_window_track_viewports_normalize_against_virtual_desktop() {
+        let mut recording = Project::new("Test", 3840, 1080, 30).recording;
+        recording.virtual_x = -1920;
+        recording.virtual_width = 3840;
+        recording.virtual_height = 1080;
+
+        let track = WindowTrack {
+            window_id: "0x3a00007".to_string(),
+            samples: vec![
+                WindowGeometrySample {
+                    t_ns: 0,
+                    x: -1920,
+                    y: 0,
+                    width: 960,
+                    height: 540,
+                },
+                WindowGeometrySample {
+                    t_ns: 2_000_000_000,
+                    x: 1800,
+                    y: 900,
+                    width: 960,
+                    height: 540,
+                },
+            ],
+        };
+
+        let viewports = track.viewports(&recording);
+        assert_eq!(viewports.len(), 2);
+        assert_eq!(viewports[0].1, Viewport::new(0.0, 0.0, 0.25, 0.5));
+        // A window hanging off the desktop edge is clamped back inside.
+        assert!((viewports[1].0 - 2.0).abs() < 1e-9);
+        assert!((viewports[1].1.x - 0.75).abs() < 1e-9);
+        assert!((viewports[1].1.y - 0.5).abs() < 1e-9);
+    }
+
     #[test]
     fn test_webcam_default_opacity_is_opaque() {
         let project = Project::new("Test", 1920, 1080, 30);
         assert!((project.export.webcam.opacity - 1.0).abs() < 1e-9);
     }
 
+    #[test]
+    fn test_music_track_partial_json_uses_mix_defaults() {
+        let music: MusicTrack =
+            serde_json::from_str(r#"{"path":"sources/music.mp3","gain_db":-20.0}"#).unwrap();
+        assert_eq!(music.path, "sources/music.mp3");
+        assert!((music.gain_db + 20.0).abs() < 1e-9);
+        assert!(music.looped);
+        assert!(music.ducking);
+        assert!(music.fade_out_secs > 0.0);
+    }
+
     #[test]
     fn test_loaded_project_create_and_load() {
         let dir = std::env::temp_dir().join("grabme_test_project");
@@ -590,12 +1828,47 @@ mod tests {
             duration_secs: 60.0,
             codec: "h264".to_string(),
             offset_ns: 0,
+            audio: None,
+            video: None,
         });
 
         let errors = loaded.validate_sources();
         assert!(!errors.is_empty());
         assert!(errors.iter().any(|e| e.contains("Screen source missing")));
 
+        loaded.project.slimmed_sources.push(SlimmedSource {
+            path: "sources/screen.mkv".to_string(),
+            action: SlimAction::Deleted,
+            original_bytes: 1_000_000,
+            slimmed_bytes: 0,
+            new_path: None,
+            slimmed_at: "2026-01-01T00:00:00Z".to_string(),
+        });
+        let errors = loaded.validate_sources();
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("Screen source removed by grabme slim")));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_validate_timeline_reports_distorted_keyframes() {
+        let dir = std::env::temp_dir().join("grabme_test_validate_timeline");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let mut loaded = LoadedProject::create(&dir, "Aspect Test", 1920, 1080, 60).unwrap();
+        assert!(loaded.validate_timeline().is_empty());
+
+        loaded.timeline.keyframes[0].viewport = Viewport::new(0.0, 0.0, 0.5, 0.25);
+        let issues = loaded.validate_timeline();
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].contains("3.56:1 but the export is 1.78:1"));
+
+        // Square exports reframe the camera and aren't checked.
+        loaded.project.export.aspect_mode = AspectMode::Square;
+        assert!(loaded.validate_timeline().is_empty());
+
         std::fs::remove_dir_all(&dir).ok();
     }
 
@@ -643,4 +1916,83 @@ mod tests {
             PointerCoordinateSpace::LegacyUnspecified
         );
     }
+
+    #[test]
+    fn test_aspect_mode_dimensions_keep_short_edge() {
+        assert_eq!(AspectMode::Landscape.dimensions(1920, 1080), (1920, 1080));
+        assert_eq!(AspectMode::Square.dimensions(1920, 1080), (1080, 1080));
+        assert_eq!(AspectMode::Portrait.dimensions(1920, 1080), (1080, 1920));
+        assert_eq!(AspectMode::Portrait.dimensions(1280, 720), (720, 1280));
+        assert_eq!(AspectMode::Custom.dimensions(1000, 500), (1000, 500));
+        assert_eq!("9:16".parse::<AspectMode>(), Ok(AspectMode::Portrait));
+        assert_eq!(" Square".parse::<AspectMode>(), Ok(AspectMode::Square));
+        assert!("custom".parse::<AspectMode>().is_err());
+    }
+
+    #[test]
+    fn test_default_export_size_follows_the_recorded_monitor() {
+        let mut recording = Project::new("Test", 2560, 1440, 60).recording;
+        assert_eq!(
+            recording.default_export_size(ExportFormat::Mp4H264),
+            (2560, 1440)
+        );
+        assert_eq!(
+            recording.default_export_size(ExportFormat::Gif),
+            (1280, 720)
+        );
+
+        // A downscaled capture of the same monitor still exports at its size.
+        recording.capture_width = 1920;
+        recording.capture_height = 1080;
+        assert_eq!(
+            recording.default_export_size(ExportFormat::Webm),
+            (2560, 1440)
+        );
+
+        // Beyond 4K, and portrait, fit the limit in their own orientation.
+        recording.monitor_width = 5120;
+        recording.monitor_height = 2880;
+        recording.capture_width = 5120;
+        recording.capture_height = 2880;
+        assert_eq!(
+            recording.default_export_size(ExportFormat::Mp4H264),
+            (3840, 2160)
+        );
+        assert_eq!(
+            ExportFormat::Mp4H265.fit_default_size(2160, 3840),
+            (2160, 3840)
+        );
+        assert_eq!(ExportFormat::Gif.fit_default_size(1080, 1920), (720, 1280));
+
+        // A region of another shape keeps its own size.
+        recording.capture_width = 801;
+        recording.capture_height = 600;
+        assert_eq!(
+            recording.default_export_size(ExportFormat::Mp4H264),
+            (800, 600)
+        );
+        assert_eq!(Project::new("Big", 7680, 4320, 30).export.width, 3840);
+    }
+
+    #[test]
+    fn test_export_size_derives_a_missing_side_from_the_recorded_shape() {
+        let mut recording = Project::new("Test", 2560, 1440, 60).recording;
+        let format = ExportFormat::Mp4H264;
+        assert_eq!(recording.export_size(format, Some(1280), None), (1280, 720));
+        assert_eq!(
+            recording.export_size(format, None, Some(1080)),
+            (1920, 1080)
+        );
+        assert_eq!(recording.export_size(format, Some(1001), None), (1001, 564));
+        assert_eq!(
+            recording.export_size(format, Some(1280), Some(1280)),
+            (1280, 1280)
+        );
+        assert_eq!(recording.export_size(format, None, None), (2560, 1440));
+
+        // A region keeps its own shape.
+        recording.capture_width = 800;
+        recording.capture_height = 600;
+        assert_eq!(recording.export_size(format, Some(400), None), (400, 300));
+    }
 }
@@ -0,0 +1,456 @@
+//! Pointer positions projected onto the captured region.
+//!
+//! Events store pointer positions in the space named by the stream header
+//! (or, for older recordings, the project), and legacy streams name none.
+//! Analysis and previews want them normalized to the recorded monitor.
+//! Capture-time hits are used when the recording has them; otherwise the
+//! named space is checked against the samples, and the best-fitting mapping
+//! wins when it doesn't fit.
+
+use crate::event::{apply_pointer_hits, EventStreamHeader, InputEvent, PointerCoordinateSpace};
+use crate::project::RecordingConfig;
+
+/// How recorded pointer positions were mapped onto the captured region.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PointerProjectionModel {
+    /// Positions resolved against the monitor layout while recording.
+    CaptureHits,
+    CaptureNormalized,
+    VirtualDesktopNormalized,
+    VirtualDesktopRootOrigin,
+}
+
+impl PointerProjectionModel {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PointerProjectionModel::CaptureHits => "capture-time monitor hits",
+            PointerProjectionModel::CaptureNormalized => "capture_normalized",
+            PointerProjectionModel::VirtualDesktopNormalized => {
+                "virtual_desktop_normalized -> capture_normalized"
+            }
+            PointerProjectionModel::VirtualDesktopRootOrigin => {
+                "virtual_desktop_root_origin -> capture_normalized"
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ProjectionCandidate {
+    model: PointerProjectionModel,
+    transform: PointerTransform,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct PointerTransform {
+    scale_x: f64,
+    scale_y: f64,
+    tx: f64,
+    ty: f64,
+}
+
+impl PointerTransform {
+    fn identity() -> Self {
+        Self {
+            scale_x: 1.0,
+            scale_y: 1.0,
+            tx: 0.0,
+            ty: 0.0,
+        }
+    }
+
+    fn from_affine(scale_x: f64, scale_y: f64, tx: f64, ty: f64) -> Self {
+        Self {
+            scale_x,
+            scale_y,
+            tx,
+            ty,
+        }
+    }
+
+    fn project(self, x: f64, y: f64) -> (f64, f64) {
+        (self.scale_x * x + self.tx, self.scale_y * y + self.ty)
+    }
+}
+
+/// Events with their positions normalized to the captured region.
+#[derive(Debug, Clone)]
+pub struct PointerProjection {
+    pub events: Vec<InputEvent>,
+    /// The mapping that was used.
+    pub model: PointerProjectionModel,
+    /// How well the mapping fits the samples: 4.0 per unit of in-bounds
+    /// ratio plus the spread of the samples, less a penalty for hugging the
+    /// border. Higher is better.
+    pub score: f64,
+}
+
+impl PointerProjection {
+    /// The score normalized to `[0.0, 1.0]`: a mapping that keeps every
+    /// sample on screen has full confidence.
+    pub fn confidence(&self) -> f64 {
+        (self.score / 4.0).clamp(0.0, 1.0)
+    }
+}
+
+/// Map the positions of `events` onto the captured region of `recording`.
+/// Projected positions are clamped to `[0.0, 1.0]`.
+pub fn project_events_to_capture_space(
+    events: &[InputEvent],
+    events_header: Option<&EventStreamHeader>,
+    recording: &RecordingConfig,
+) -> PointerProjection {
+    const EXPLICIT_PROJECTION_FALLBACK_DELTA: f64 = 0.35;
+
+    if events.is_empty() {
+        return PointerProjection {
+            events: Vec::new(),
+            model: PointerProjectionModel::CaptureNormalized,
+            score: 0.0,
+        };
+    }
+
+    // Recordings with capture-time hits need no projection guessing.
+    let mut resolved = events.to_vec();
+    if apply_pointer_hits(&mut resolved) {
+        let hits = ProjectionCandidate {
+            model: PointerProjectionModel::CaptureHits,
+            transform: PointerTransform::identity(),
+        };
+        let projected_events = resolved
+            .iter()
+            .map(|event| project_event(event, hits.transform))
+            .collect();
+        return PointerProjection {
+            events: projected_events,
+            model: hits.model,
+            score: score_projection_candidate(hits, &resolved),
+        };
+    }
+
+    let explicit_space = events_header
+        .map(|header| header.pointer_coordinate_space)
+        .filter(|space| *space != PointerCoordinateSpace::LegacyUnspecified)
+        .or_else(|| {
+            let space = recording.pointer_coordinate_space;
+            if space == PointerCoordinateSpace::LegacyUnspecified {
+                None
+            } else {
+                Some(space)
+            }
+        });
+
+    let best_fit = select_best_projection_candidate(events, recording);
+
+    let selected = if let Some(space) = explicit_space {
+        if let Some(explicit) = projection_candidate_for_space(space, recording) {
+            let explicit_score = score_projection_candidate(explicit, events);
+            let best_fit_score = score_projection_candidate(best_fit, events);
+            if best_fit.model == PointerProjectionModel::CaptureNormalized
+                && best_fit_score > explicit_score + EXPLICIT_PROJECTION_FALLBACK_DELTA
+            {
+                best_fit
+            } else {
+                explicit
+            }
+        } else {
+            best_fit
+        }
+    } else {
+        best_fit
+    };
+
+    let projected_events = events
+        .iter()
+        .map(|event| project_event(event, selected.transform))
+        .collect();
+
+    PointerProjection {
+        events: projected_events,
+        model: selected.model,
+        score: score_projection_candidate(selected, events),
+    }
+}
+
+fn projection_candidate_for_space(
+    space: PointerCoordinateSpace,
+    recording: &RecordingConfig,
+) -> Option<ProjectionCandidate> {
+    match space {
+        PointerCoordinateSpace::CaptureNormalized => Some(ProjectionCandidate {
+            model: PointerProjectionModel::CaptureNormalized,
+            transform: PointerTransform::identity(),
+        }),
+        PointerCoordinateSpace::VirtualDesktopNormalized => {
+            virtual_desktop_projection_candidates(recording)
+                .into_iter()
+                .find(|candidate| {
+                    candidate.model == PointerProjectionModel::VirtualDesktopNormalized
+                })
+        }
+        PointerCoordinateSpace::VirtualDesktopRootOrigin => {
+            virtual_desktop_projection_candidates(recording)
+                .into_iter()
+                .find(|candidate| {
+                    candidate.model == PointerProjectionModel::VirtualDesktopRootOrigin
+                })
+        }
+        PointerCoordinateSpace::LegacyUnspecified => None,
+    }
+}
+
+fn select_best_projection_candidate(
+    events: &[InputEvent],
+    recording: &RecordingConfig,
+) -> ProjectionCandidate {
+    let capture_candidate = ProjectionCandidate {
+        model: PointerProjectionModel::CaptureNormalized,
+        transform: PointerTransform::identity(),
+    };
+
+    let mut candidates = vec![capture_candidate];
+    candidates.extend(virtual_desktop_projection_candidates(recording));
+
+    let mut best = capture_candidate;
+    let mut best_score = score_projection_candidate(capture_candidate, events);
+    for candidate in candidates.into_iter().skip(1) {
+        let score = score_projection_candidate(candidate, events);
+        if score > best_score {
+            best = candidate;
+            best_score = score;
+        }
+    }
+
+    best
+}
+
+fn virtual_desktop_projection_candidates(recording: &RecordingConfig) -> Vec<ProjectionCandidate> {
+    let monitor_w = recording.monitor_width as f64;
+    let monitor_h = recording.monitor_height as f64;
+    let virtual_w = recording.virtual_width as f64;
+    let virtual_h = recording.virtual_height as f64;
+    if monitor_w <= 0.0 || monitor_h <= 0.0 || virtual_w <= 0.0 || virtual_h <= 0.0 {
+        return vec![];
+    }
+
+    let scale_x = virtual_w / monitor_w;
+    let scale_y = virtual_h / monitor_h;
+    let tx_bounds = (recording.virtual_x as f64 - recording.monitor_x as f64) / monitor_w;
+    let ty_bounds = (recording.virtual_y as f64 - recording.monitor_y as f64) / monitor_h;
+    let tx_root = -(recording.monitor_x as f64) / monitor_w;
+    let ty_root = -(recording.monitor_y as f64) / monitor_h;
+
+    let bounds_candidate = ProjectionCandidate {
+        model: PointerProjectionModel::VirtualDesktopNormalized,
+        transform: PointerTransform::from_affine(scale_x, scale_y, tx_bounds, ty_bounds),
+    };
+
+    if (tx_bounds - tx_root).abs() < 1e-9 && (ty_bounds - ty_root).abs() < 1e-9 {
+        return vec![bounds_candidate];
+    }
+
+    vec![
+        bounds_candidate,
+        ProjectionCandidate {
+            model: PointerProjectionModel::VirtualDesktopRootOrigin,
+            transform: PointerTransform::from_affine(scale_x, scale_y, tx_root, ty_root),
+        },
+    ]
+}
+
+fn score_projection_candidate(candidate: ProjectionCandidate, events: &[InputEvent]) -> f64 {
+    let sample_stride = ((events.len() as f64) / 1024.0).ceil() as usize;
+    let sample_stride = sample_stride.max(1);
+
+    let mut sampled = 0usize;
+    let mut in_bounds = 0usize;
+    let mut near_border = 0usize;
+    let mut min_x = f64::INFINITY;
+    let mut max_x = f64::NEG_INFINITY;
+    let mut min_y = f64::INFINITY;
+    let mut max_y = f64::NEG_INFINITY;
+
+    for (idx, event) in events.iter().enumerate() {
+        if idx % sample_stride != 0 {
+            continue;
+        }
+        let Some((x, y)) = event.pointer_position() else {
+            continue;
+        };
+
+        let (px, py) = candidate.transform.project(x, y);
+        if !px.is_finite() || !py.is_finite() {
+            continue;
+        }
+
+        sampled += 1;
+        if (0.0..=1.0).contains(&px) && (0.0..=1.0).contains(&py) {
+            in_bounds += 1;
+            min_x = min_x.min(px);
+            max_x = max_x.max(px);
+            min_y = min_y.min(py);
+            max_y = max_y.max(py);
+            if px <= 0.01 || px >= 0.99 || py <= 0.01 || py >= 0.99 {
+                near_border += 1;
+            }
+        }
+    }
+
+    if sampled == 0 {
+        return -1.0;
+    }
+
+    let in_bounds_ratio = in_bounds as f64 / sampled as f64;
+    let span_x = if min_x.is_finite() && max_x.is_finite() {
+        (max_x - min_x).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let span_y = if min_y.is_finite() && max_y.is_finite() {
+        (max_y - min_y).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let span_score = (span_x + span_y).clamp(0.0, 1.5);
+    let border_ratio = if in_bounds > 0 {
+        near_border as f64 / in_bounds as f64
+    } else {
+        1.0
+    };
+
+    in_bounds_ratio * 4.0 + span_score - border_ratio * 0.75
+}
+
+fn project_event(event: &InputEvent, transform: PointerTransform) -> InputEvent {
+    let mut event = event.clone();
+    if let Some((x, y)) = event.position_mut() {
+        let (px, py) = transform.project(*x, *y);
+        (*x, *y) = (px.clamp(0.0, 1.0), py.clamp(0.0, 1.0));
+    }
+    event
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::PointerHit;
+    use crate::project::Project;
+
+    #[test]
+    fn test_projection_uses_virtual_bounds_mapping() {
+        let mut project = Project::new("test", 1920, 1080, 60);
+        project.recording.monitor_x = 0;
+        project.recording.monitor_y = 0;
+        project.recording.monitor_width = 1920;
+        project.recording.monitor_height = 1080;
+        project.recording.virtual_x = 0;
+        project.recording.virtual_y = 0;
+        project.recording.virtual_width = 4480;
+        project.recording.virtual_height = 1440;
+        project.recording.pointer_coordinate_space =
+            PointerCoordinateSpace::VirtualDesktopNormalized;
+
+        let events = vec![InputEvent::pointer(0, 0.25, 0.5)];
+        let projected = project_events_to_capture_space(&events, None, &project.recording);
+
+        assert_eq!(
+            projected.model,
+            PointerProjectionModel::VirtualDesktopNormalized
+        );
+        let (x, y) = projected.events[0].pointer_position().unwrap();
+        assert!((x - 0.583333).abs() < 1e-5);
+        assert!((y - 0.666666).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_projection_honors_explicit_root_origin_header() {
+        let mut project = Project::new("test", 2560, 1440, 60);
+        project.recording.monitor_x = -1920;
+        project.recording.monitor_y = 0;
+        project.recording.monitor_width = 2560;
+        project.recording.monitor_height = 1440;
+        project.recording.virtual_x = -1920;
+        project.recording.virtual_y = 0;
+        project.recording.virtual_width = 4480;
+        project.recording.virtual_height = 1440;
+        project.recording.pointer_coordinate_space = PointerCoordinateSpace::LegacyUnspecified;
+
+        let events = vec![
+            InputEvent::pointer(0, 0.1429, 0.3000),
+            InputEvent::pointer(16_000_000, 0.2143, 0.3200),
+            InputEvent::pointer(32_000_000, 0.2679, 0.3500),
+        ];
+
+        let header = EventStreamHeader {
+            schema_version: "1.0".to_string(),
+            epoch_monotonic_ns: 0,
+            epoch_wall: "2026-01-01T00:00:00Z".to_string(),
+            capture_width: 2560,
+            capture_height: 1440,
+            scale_factor: 1.0,
+            pointer_sample_rate_hz: 60,
+            pointer_coordinate_space: PointerCoordinateSpace::VirtualDesktopRootOrigin,
+        };
+
+        let projected = project_events_to_capture_space(&events, Some(&header), &project.recording);
+        assert_eq!(
+            projected.model,
+            PointerProjectionModel::VirtualDesktopRootOrigin
+        );
+    }
+
+    #[test]
+    fn test_projection_falls_back_when_explicit_mapping_is_inconsistent() {
+        let mut project = Project::new("test", 1920, 1080, 60);
+        project.recording.monitor_x = 0;
+        project.recording.monitor_y = 0;
+        project.recording.monitor_width = 1920;
+        project.recording.monitor_height = 1080;
+        project.recording.virtual_x = 0;
+        project.recording.virtual_y = 0;
+        project.recording.virtual_width = 4480;
+        project.recording.virtual_height = 1440;
+        project.recording.pointer_coordinate_space =
+            PointerCoordinateSpace::VirtualDesktopNormalized;
+
+        // These coordinates resemble a stream normalized against a narrower width,
+        // where forcing virtual_desktop projection would push many points out of bounds.
+        let events = vec![
+            InputEvent::pointer(0, 0.38, 0.45),
+            InputEvent::pointer(16_000_000, 0.44, 0.47),
+            InputEvent::pointer(32_000_000, 0.52, 0.50),
+        ];
+
+        let projected = project_events_to_capture_space(&events, None, &project.recording);
+        assert_eq!(projected.model, PointerProjectionModel::CaptureNormalized);
+    }
+
+    #[test]
+    fn test_projection_prefers_capture_time_hits() {
+        let mut project = Project::new("test", 1920, 1080, 60);
+        project.recording.virtual_width = 4480;
+        project.recording.virtual_height = 1440;
+        project.recording.pointer_coordinate_space =
+            PointerCoordinateSpace::VirtualDesktopNormalized;
+
+        let mut events = vec![
+            InputEvent::pointer(0, 0.25, 0.5),
+            InputEvent::pointer(16_000_000, 0.9, 0.5),
+        ];
+        events[0].hit = Some(PointerHit {
+            monitor: Some(0),
+            capture_x: 0.3,
+            capture_y: 0.6,
+        });
+        events[1].hit = Some(PointerHit {
+            monitor: Some(1),
+            capture_x: 1.8,
+            capture_y: 0.6,
+        });
+
+        let projected = project_events_to_capture_space(&events, None, &project.recording);
+        assert_eq!(projected.model, PointerProjectionModel::CaptureHits);
+        assert_eq!(projected.events[0].pointer_position(), Some((0.3, 0.6)));
+        assert_eq!(projected.events[1].pointer_position(), Some((1.0, 0.6)));
+    }
+}
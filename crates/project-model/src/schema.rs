@@ -0,0 +1,306 @@
+//! JSON Schema for the files downstream tools parse.
+//!
+//! Schemas are generated from the serde models, so they always describe what
+//! GrabMe writes. Published copies are kept per schema version in
+//! `docs/schemas/<EVENT_SCHEMA_VERSION>/`; a test keeps the current version's
+//! copies current and fails when any version changes in a way that would
+//! break readers of the one before it (a field removed, retyped or made
+//! optional, a variant dropped).
+
+use schemars::gen::SchemaSettings;
+use schemars::schema::RootSchema;
+use serde_json::Value;
+
+use crate::event::{EventStreamHeader, InputEvent, EVENT_SCHEMA_VERSION};
+use crate::project::Project;
+use crate::timeline::Timeline;
+
+/// A data contract with a published schema.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaKind {
+    /// One event line of `events.jsonl`.
+    Events,
+    /// `meta/project.json`.
+    Project,
+    /// `meta/timeline.json`.
+    Timeline,
+}
+
+impl SchemaKind {
+    pub const ALL: [SchemaKind; 3] = [
+        SchemaKind::Events,
+        SchemaKind::Project,
+        SchemaKind::Timeline,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SchemaKind::Events => "events",
+            SchemaKind::Project => "project",
+            SchemaKind::Timeline => "timeline",
+        }
+    }
+
+    /// File name of the published schema in `docs/schemas/<version>/`.
+    pub fn file_name(self) -> String {
+        format!("{}.schema.json", self.as_str())
+    }
+}
+
+impl std::str::FromStr for SchemaKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "events" | "event" => Ok(SchemaKind::Events),
+            "project" => Ok(SchemaKind::Project),
+            "timeline" => Ok(SchemaKind::Timeline),
+            other => Err(format!(
+                "Unknown schema: {other}. Use: events, project, timeline"
+            )),
+        }
+    }
+}
+
+/// JSON Schema (draft 7) for `kind`.
+///
+/// The events schema describes one event line. The header on the first
+/// line, written as `# {...}`, is in its definitions as `EventStreamHeader`.
+pub fn json_schema(kind: SchemaKind) -> Value {
+    let mut gen = SchemaSettings::draft07().into_generator();
+    let mut root: RootSchema = match kind {
+        SchemaKind::Events => {
+            gen.subschema_for::<EventStreamHeader>();
+            gen.into_root_schema_for::<InputEvent>()
+        }
+        SchemaKind::Project => gen.into_root_schema_for::<Project>(),
+        SchemaKind::Timeline => gen.into_root_schema_for::<Timeline>(),
+    };
+    let metadata = root.schema.metadata();
+    if kind == SchemaKind::Events {
+        metadata.description = Some(format!(
+            "One line of events.jsonl (schema {EVENT_SCHEMA_VERSION}). The first line is a \
+             `# `-prefixed EventStreamHeader; blank and `#` lines are skipped."
+        ));
+    }
+    serde_json::to_value(root).expect("schemas serialize to JSON")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Ways `new` breaks readers of documents valid under `old`.
+    fn breaking_changes(old: &Value, new: &Value) -> Vec<String> {
+        let mut changes = Vec::new();
+        compare(old, new, "#", &mut changes);
+        let (old_defs, new_defs) = (&old["definitions"], &new["definitions"]);
+        for (name, old_def) in old_defs.as_object().into_iter().flatten() {
+            match new_defs.get(name) {
+                Some(new_def) => compare(old_def, new_def, name, &mut changes),
+                None => changes.push(format!("{name}: definition removed")),
+            }
+        }
+        changes
+    }
+
+    fn compare(old: &Value, new: &Value, path: &str, changes: &mut Vec<String>) {
+        if old.get("$ref") != new.get("$ref") {
+            changes.push(format!("{path}: now refers to {}", new["$ref"]));
+            return;
+        }
+        let types = |schema: &Value| -> Vec<Value> {
+            match schema.get("type") {
+                Some(Value::Array(types)) => types.clone(),
+                Some(single) => vec![single.clone()],
+                None => Vec::new(),
+            }
+        };
+        let (old_types, new_types) = (types(old), types(new));
+        if !old_types.is_empty() && new_types.iter().any(|t| !old_types.contains(t)) {
+            changes.push(format!("{path}: type widened to {}", new["type"]));
+        }
+        for value in old["enum"].as_array().into_iter().flatten() {
+            if !new["enum"]
+                .as_array()
+                .is_some_and(|values| values.contains(value))
+            {
+                changes.push(format!("{path}: value {value} removed"));
+            }
+        }
+        for field in old["required"].as_array().into_iter().flatten() {
+            if !new["required"]
+                .as_array()
+                .is_some_and(|fields| fields.contains(field))
+            {
+                changes.push(format!("{path}: {field} no longer required"));
+            }
+        }
+        for (name, old_prop) in old["properties"].as_object().into_iter().flatten() {
+            match new["properties"].get(name) {
+                Some(new_prop) => compare(old_prop, new_prop, &format!("{path}.{name}"), changes),
+                None => changes.push(format!("{path}.{name}: removed")),
+            }
+        }
+        if let Some(old_items) = old.get("items") {
+            compare(old_items, &new["items"], &format!("{path}[]"), changes);
+        }
+        for key in ["oneOf", "anyOf", "allOf"] {
+            for (idx, old_variant) in old[key].as_array().into_iter().flatten().enumerate() {
+                let kept = new[key]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .any(|new_variant| {
+                        let mut variant_changes = Vec::new();
+                        compare(old_variant, new_variant, path, &mut variant_changes);
+                        variant_changes.is_empty()
+                    });
+                if !kept {
+                    changes.push(format!("{path}: {key} variant {idx} removed or changed"));
+                }
+            }
+        }
+    }
+
+    /// Numeric parts of a schema version, for ordering (`1.10` after `1.9`).
+    fn version_key(version: &str) -> Vec<u64> {
+        version
+            .split('.')
+            .map(|part| part.parse().unwrap_or(0))
+            .collect()
+    }
+
+    /// Schema versions with published snapshots, oldest first.
+    fn published_versions(dir: &std::path::Path) -> Vec<String> {
+        let mut versions: Vec<String> = std::fs::read_dir(dir)
+            .unwrap()
+            .filter_map(Result::ok)
+            .filter(|entry| entry.path().is_dir())
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .collect();
+        versions.sort_by_key(|version| version_key(version));
+        versions
+    }
+
+    fn read_schema(path: &std::path::Path) -> Option<Value> {
+        let text = std::fs::read_to_string(path).ok()?;
+        Some(serde_json::from_str(&text).unwrap())
+    }
+
+    #[test]
+    fn test_published_schemas_are_current_and_additive() {
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../../docs/schemas");
+        let current_dir = dir.join(EVENT_SCHEMA_VERSION);
+        if std::env::var_os("GRABME_BLESS_SCHEMAS").is_some() {
+            std::fs::create_dir_all(&current_dir).unwrap();
+            for kind in SchemaKind::ALL {
+                let text = serde_json::to_string_pretty(&json_schema(kind)).unwrap() + "\n";
+                std::fs::write(current_dir.join(kind.file_name()), text).unwrap();
+            }
+        }
+
+        let versions = published_versions(&dir);
+        assert_eq!(
+            versions.last().map(String::as_str),
+            Some(EVENT_SCHEMA_VERSION),
+            "docs/schemas has no snapshot for schema {EVENT_SCHEMA_VERSION}, or one for a newer \
+             version; rerun with GRABME_BLESS_SCHEMAS=1"
+        );
+
+        for kind in SchemaKind::ALL {
+            // Each version may only add to the one before it.
+            let mut previous: Option<(&str, Value)> = None;
+            for version in &versions {
+                let Some(schema) = read_schema(&dir.join(version).join(kind.file_name())) else {
+                    continue;
+                };
+                if let Some((previous_version, previous_schema)) = &previous {
+                    let changes = breaking_changes(previous_schema, &schema);
+                    assert!(
+                        changes.is_empty(),
+                        "{} changed incompatibly from {previous_version} to {version}:\n{}",
+                        kind.file_name(),
+                        changes.join("\n")
+                    );
+                }
+                previous = Some((version, schema));
+            }
+
+            let published = read_schema(&current_dir.join(kind.file_name()))
+                .unwrap_or_else(|| panic!("{} is missing", kind.file_name()));
+            let current = json_schema(kind);
+            let changes = breaking_changes(&published, &current);
+            assert!(
+                changes.is_empty(),
+                "{} changed incompatibly:\n{}",
+                kind.file_name(),
+                changes.join("\n")
+            );
+            assert_eq!(
+                published,
+                current,
+                "{} for schema {EVENT_SCHEMA_VERSION} is out of date; rerun with \
+                 GRABME_BLESS_SCHEMAS=1",
+                kind.file_name()
+            );
+        }
+    }
+
+    #[test]
+    fn test_version_key_orders_numerically() {
+        let mut versions = vec!["1.10", "1.6", "1.9"];
+        versions.sort_by_key(|version| version_key(version));
+        assert_eq!(versions, ["1.6", "1.9", "1.10"]);
+    }
+
+    #[test]
+    fn test_breaking_changes_allow_additions_only() {
+        let old = json!({
+            "type": "object",
+            "required": ["t"],
+            "properties": {
+                "t": { "type": "integer" },
+                "kind": { "type": "string", "enum": ["a", "b"] }
+            }
+        });
+        let added = json!({
+            "type": "object",
+            "required": ["t", "x"],
+            "properties": {
+                "t": { "type": "integer" },
+                "x": { "type": "number" },
+                "kind": { "type": "string", "enum": ["a", "b", "c"] }
+            }
+        });
+        assert!(breaking_changes(&old, &added).is_empty());
+
+        let broken = json!({
+            "type": "object",
+            "properties": {
+                "t": { "type": ["integer", "null"] },
+                "kind": { "type": "string", "enum": ["a"] }
+            }
+        });
+        assert_eq!(
+            breaking_changes(&old, &broken),
+            vec![
+                "#: \"t\" no longer required",
+                "#.kind: value \"b\" removed",
+                "#.t: type widened to [\"integer\",\"null\"]",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_events_schema_describes_event_lines_and_header() {
+        let schema = json_schema(SchemaKind::Events);
+        assert_eq!(schema["title"], "InputEvent");
+        assert!(schema["definitions"]["EventStreamHeader"].is_object());
+        assert!(schema["description"]
+            .as_str()
+            .unwrap()
+            .contains(EVENT_SCHEMA_VERSION));
+    }
+}
@@ -4,12 +4,13 @@
 //! transform raw source material into the final output. Timelines
 //! are generated by the Auto-Director and can be manually adjusted.
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::viewport::Viewport;
+use crate::viewport::{AspectLock, Viewport};
 
 /// The complete editing timeline for a project.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Timeline {
     /// Schema version for forward compatibility.
     pub version: String,
@@ -27,10 +28,25 @@ pub struct Timeline {
     /// Segments to trim/cut from the recording.
     #[serde(default)]
     pub cuts: Vec<CutSegment>,
+
+    /// Slow-motion and freeze-frame segments, in source time.
+    #[serde(default)]
+    pub speed_segments: Vec<SpeedSegment>,
+
+    /// Where the export starts, in seconds, set in the editor. `None`
+    /// detects the end of the recording's black pre-roll at export time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_offset_secs: Option<f64>,
+
+    /// Where the export ends, in seconds, set in the editor. `None` ends at
+    /// the end of the recording, or before its idle tail when tail trimming
+    /// is on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_offset_secs: Option<f64>,
 }
 
 /// A single camera keyframe at a specific time.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct CameraKeyframe {
     /// Time in seconds from recording start.
     #[serde(rename = "t")]
@@ -46,10 +62,33 @@ pub struct CameraKeyframe {
     /// Whether this keyframe was auto-generated or manually placed.
     #[serde(default)]
     pub source: KeyframeSource,
+
+    /// Auto-Director confidence in `[0.0, 1.0]` that this framing is right.
+    /// `None` for manual keyframes and timelines analyzed before scoring.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f64>,
+}
+
+/// Keyframes scored below this are flagged for review.
+pub const LOW_CONFIDENCE_THRESHOLD: f64 = 0.5;
+
+/// A span of the timeline governed by one low-confidence keyframe.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConfidenceSegment {
+    /// Keyframe time in seconds.
+    pub start_secs: f64,
+    /// Time of the next keyframe (or the timeline end).
+    pub end_secs: f64,
+    /// Confidence of the keyframe.
+    pub confidence: f64,
 }
 
 /// Easing function for transitions between keyframes.
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+///
+/// Named curves serialize as plain strings (`"ease_out_cubic"`); the
+/// parametric ones as a single-key object, e.g.
+/// `{"cubic_bezier": {"x1": 0.25, "y1": 0.1, "x2": 0.25, "y2": 1.0}}`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum EasingFunction {
     Linear,
@@ -57,13 +96,63 @@ pub enum EasingFunction {
     EaseOut,
     #[default]
     EaseInOut,
+    EaseInCubic,
+    EaseOutCubic,
+    EaseInOutCubic,
+    EaseInSine,
+    EaseOutSine,
+    EaseInOutSine,
+    EaseInExpo,
+    EaseOutExpo,
+    EaseInOutExpo,
+    /// Pulls back slightly before moving.
+    EaseInBack,
+    /// Overshoots the target slightly before settling.
+    EaseOutBack,
+    EaseInOutBack,
+    /// CSS-style cubic Bézier through `(0, 0)`, `(x1, y1)`, `(x2, y2)`,
+    /// `(1, 1)`. `x1`/`x2` are clamped to [0, 1]; `y` values outside that
+    /// range overshoot.
+    CubicBezier {
+        x1: f64,
+        y1: f64,
+        x2: f64,
+        y2: f64,
+    },
+    /// Damped spring released from the start viewport toward the end one.
+    /// The transition spans the time the spring takes to settle, so the
+    /// parameters shape the motion rather than its duration. A damping
+    /// ratio below 1 (`damping < 2 * sqrt(stiffness * mass)`) bounces.
+    Spring {
+        stiffness: f64,
+        damping: f64,
+        mass: f64,
+    },
 }
 
+/// Overshoot constant of the back curves (about 10%).
+const BACK_OVERSHOOT: f64 = 1.70158;
+
+/// Residual spring displacement treated as settled.
+const SPRING_SETTLE_EPSILON: f64 = 1e-3;
+
 impl EasingFunction {
+    /// Spring with a light bounce, a reasonable starting point for tuning.
+    pub const SPRING: EasingFunction = EasingFunction::Spring {
+        stiffness: 170.0,
+        damping: 18.0,
+        mass: 1.0,
+    };
+
     /// Apply the easing function to a linear `t` in [0, 1].
+    ///
+    /// The result is 0 at `t = 0` and 1 at `t = 1`; back, Bézier and spring
+    /// curves may leave [0, 1] in between.
     pub fn apply(&self, t: f64) -> f64 {
+        use std::f64::consts::PI;
+
         let t = t.clamp(0.0, 1.0);
-        match self {
+        match *self {
             EasingFunction::Linear => t,
             EasingFunction::EaseIn => t * t,
             EasingFunction::EaseOut => 1.0 - (1.0 - t).powi(2),
@@ -74,12 +163,179 @@ impl EasingFunction {
                     1.0 - (-2.0 * t + 2.0).powi(2) / 2.0
                 }
             }
+            EasingFunction::EaseInCubic => t.powi(3),
+            EasingFunction::EaseOutCubic => 1.0 - (1.0 - t).powi(3),
+            EasingFunction::EaseInOutCubic => {
+                if t < 0.5 {
+                    4.0 * t.powi(3)
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+            EasingFunction::EaseInSine => 1.0 - (t * PI / 2.0).cos(),
+            EasingFunction::EaseOutSine => (t * PI / 2.0).sin(),
+            EasingFunction::EaseInOutSine => -((PI * t).cos() - 1.0) / 2.0,
+            EasingFunction::EaseInExpo => {
+                if t <= 0.0 {
+                    0.0
+                } else {
+                    2f64.powf(10.0 * t - 10.0)
+                }
+            }
+            EasingFunction::EaseOutExpo => {
+                if t >= 1.0 {
+                    1.0
+                } else {
+                    1.0 - 2f64.powf(-10.0 * t)
+                }
+            }
+            EasingFunction::EaseInOutExpo => {
+                if t <= 0.0 || t >= 1.0 {
+                    t
+                } else if t < 0.5 {
+                    2f64.powf(20.0 * t - 10.0) / 2.0
+                } else {
+                    (2.0 - 2f64.powf(-20.0 * t + 10.0)) / 2.0
+                }
+            }
+            EasingFunction::EaseInBack => {
+                let c = BACK_OVERSHOOT;
+                (c + 1.0) * t.powi(3) - c * t * t
+            }
+            EasingFunction::EaseOutBack => {
+                let c = BACK_OVERSHOOT;
+                1.0 + (c + 1.0) * (t - 1.0).powi(3) + c * (t - 1.0).powi(2)
+            }
+            EasingFunction::EaseInOutBack => {
+                let c = BACK_OVERSHOOT * 1.525;
+                if t < 0.5 {
+                    (2.0 * t).powi(2) * ((c + 1.0) * 2.0 * t - c) / 2.0
+                } else {
+                    ((2.0 * t - 2.0).powi(2) * ((c + 1.0) * (t * 2.0 - 2.0) + c) + 2.0) / 2.0
+                }
+            }
+            EasingFunction::CubicBezier { x1, y1, x2, y2 } => cubic_bezier(x1, y1, x2, y2, t),
+            EasingFunction::Spring {
+                stiffness,
+                damping,
+                mass,
+            } => spring(stiffness, damping, mass, t),
+        }
+    }
+
+    /// Whether the curve can leave [0, 1], moving the camera past either
+    /// keyframe.
+    pub fn overshoots(&self) -> bool {
+        match *self {
+            EasingFunction::EaseInBack
+            | EasingFunction::EaseOutBack
+            | EasingFunction::EaseInOutBack => true,
+            EasingFunction::CubicBezier { y1, y2, .. } => {
+                !(0.0..=1.0).contains(&y1) || !(0.0..=1.0).contains(&y2)
+            }
+            EasingFunction::Spring {
+                stiffness,
+                damping,
+                mass,
+            } => damping_ratio(stiffness, damping, mass) < 1.0,
+            _ => false,
         }
     }
 }
 
+/// Evaluate a unit cubic Bézier's `y` at the parameter where its `x`
+/// equals `t`.
+fn cubic_bezier(x1: f64, y1: f64, x2: f64, y2: f64, t: f64) -> f64 {
+    // Both coordinates of B(s) = 3(1-s)²s·p1 + 3(1-s)s²·p2 + s³.
+    fn coord(p1: f64, p2: f64, s: f64) -> f64 {
+        let inv = 1.0 - s;
+        3.0 * inv * inv * s * p1 + 3.0 * inv * s * s * p2 + s * s * s
+    }
+    fn slope(p1: f64, p2: f64, s: f64) -> f64 {
+        let inv = 1.0 - s;
+        3.0 * inv * inv * p1 + 6.0 * inv * s * (p2 - p1) + 3.0 * s * s * (1.0 - p2)
+    }
+
+    let (x1, x2) = (x1.clamp(0.0, 1.0), x2.clamp(0.0, 1.0));
+    if t <= 0.0 || t >= 1.0 {
+        return t;
+    }
+
+    // Newton's method converges in a few steps for most curves; fall back
+    // to bisection (x(s) is monotonic for x1, x2 in [0, 1]) when the slope
+    // flattens out.
+    let mut s = t;
+    for _ in 0..8 {
+        let err = coord(x1, x2, s) - t;
+        if err.abs() < 1e-7 {
+            return coord(y1, y2, s);
+        }
+        let d = slope(x1, x2, s);
+        if d.abs() < 1e-6 {
+            break;
+        }
+        s = (s - err / d).clamp(0.0, 1.0);
+    }
+
+    let (mut lo, mut hi) = (0.0, 1.0);
+    s = t;
+    for _ in 0..50 {
+        let x = coord(x1, x2, s);
+        if (x - t).abs() < 1e-7 {
+            break;
+        }
+        if x < t {
+            lo = s;
+        } else {
+            hi = s;
+        }
+        s = (lo + hi) / 2.0;
+    }
+    coord(y1, y2, s)
+}
+
+fn damping_ratio(stiffness: f64, damping: f64, mass: f64) -> f64 {
+    damping.max(0.0) / (2.0 * (stiffness.max(1e-6) * mass.max(1e-6)).sqrt())
+}
+
+/// Displacement of a damped spring released at rest from 0 toward 1,
+/// with `t` spanning the time it takes to settle.
+fn spring(stiffness: f64, damping: f64, mass: f64, t: f64) -> f64 {
+    let omega = (stiffness.max(1e-6) / mass.max(1e-6)).sqrt();
+    // Keep undamped springs from oscillating forever.
+    let zeta = damping_ratio(stiffness, damping, mass).max(0.05);
+
+    let position = |tau: f64| -> f64 {
+        if zeta < 1.0 {
+            let omega_d = omega * (1.0 - zeta * zeta).sqrt();
+            1.0 - (-zeta * omega * tau).exp()
+                * ((omega_d * tau).cos() + zeta * omega / omega_d * (omega_d * tau).sin())
+        } else if zeta == 1.0 {
+            1.0 - (-omega * tau).exp() * (1.0 + omega * tau)
+        } else {
+            let root = (zeta * zeta - 1.0).sqrt();
+            let r1 = -omega * (zeta - root);
+            let r2 = -omega * (zeta + root);
+            1.0 - (r2 * (r1 * tau).exp() - r1 * (r2 * tau).exp()) / (r2 - r1)
+        }
+    };
+
+    // Slowest decay rate of the solution's envelope.
+    let decay = if zeta < 1.0 {
+        zeta * omega
+    } else {
+        omega * (zeta - (zeta * zeta - 1.0).sqrt())
+    };
+    let settle = -SPRING_SETTLE_EPSILON.ln() / decay;
+
+    // Spread the residual at the settle time across the transition so the
+    // curve lands exactly on 1.
+    let residual = 1.0 - position(settle);
+    position(t * settle) + residual * t
+}
+
 /// Origin of a keyframe.
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum KeyframeSource {
     #[default]
@@ -88,7 +344,7 @@ pub enum KeyframeSource {
 }
 
 /// An effect applied during rendering.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum Effect {
     /// Cursor motion smoothing.
@@ -117,7 +373,7 @@ pub enum Effect {
 }
 
 /// Cursor rendering configuration.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct CursorConfig {
     /// Smoothing algorithm.
     pub smoothing: SmoothingAlgorithm,
@@ -137,6 +393,10 @@ pub struct CursorConfig {
     /// Motion trail rendering controls.
     #[serde(default)]
     pub motion_trail: CursorMotionTrailConfig,
+
+    /// Whether export draws its own cursor over the screen track.
+    #[serde(default)]
+    pub overlay: CursorOverlay,
 }
 
 impl Default for CursorConfig {
@@ -148,12 +408,27 @@ impl Default for CursorConfig {
             custom_asset: None,
             show_click_animation: true,
             motion_trail: CursorMotionTrailConfig::default(),
+            overlay: CursorOverlay::default(),
         }
     }
 }
 
+/// When export draws the synthetic cursor.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CursorOverlay {
+    /// Draw it unless the recording already shows the system cursor, as
+    /// recorded by the capture or detected in the frames.
+    #[default]
+    Auto,
+    /// Always draw it.
+    Show,
+    /// Never draw it.
+    Hide,
+}
+
 /// Cursor motion-trail rendering config.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct CursorMotionTrailConfig {
     /// Enable ghosted cursor trail rendering.
@@ -178,7 +453,7 @@ impl Default for CursorMotionTrailConfig {
 }
 
 /// Smoothing algorithm for cursor motion.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum SmoothingAlgorithm {
     /// Exponential Moving Average — simple and effective.
@@ -192,7 +467,7 @@ pub enum SmoothingAlgorithm {
 }
 
 /// A segment to cut/trim from the recording.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct CutSegment {
     /// Start time in seconds.
     pub start_secs: f64,
@@ -204,7 +479,7 @@ pub struct CutSegment {
 }
 
 /// Why a segment was cut.
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum CutReason {
     #[default]
@@ -213,6 +488,76 @@ pub enum CutReason {
     Idle,
 }
 
+/// A stretch of the recording played back at a different speed.
+///
+/// Times are in source seconds, like keyframes and cuts; the export
+/// stretches everything after a segment by the time it adds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SpeedSegment {
+    /// Play `start_secs..end_secs` at `rate` times real speed.
+    SlowMotion {
+        start_secs: f64,
+        end_secs: f64,
+        /// Playback rate, clamped to
+        /// [`SpeedSegment::MIN_RATE`]..=[`SpeedSegment::MAX_RATE`].
+        rate: f64,
+        #[serde(default)]
+        audio: SlowMotionAudio,
+        /// Blend neighbouring frames instead of repeating them.
+        #[serde(default)]
+        interpolate: bool,
+    },
+    /// Hold the frame at `at_secs` for `duration_secs`.
+    Freeze { at_secs: f64, duration_secs: f64 },
+}
+
+/// What the audio does during a slow-motion segment.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SlowMotionAudio {
+    /// Silence, like a freeze frame.
+    #[default]
+    Mute,
+    /// Slow the audio down with the picture, keeping its pitch.
+    Stretch,
+}
+
+impl SpeedSegment {
+    /// Slowest supported slow-motion rate.
+    pub const MIN_RATE: f64 = 0.25;
+    /// Fastest supported slow-motion rate.
+    pub const MAX_RATE: f64 = 0.5;
+
+    /// Source range the segment covers; empty for freezes.
+    pub fn source_range(&self) -> (f64, f64) {
+        match *self {
+            Self::SlowMotion {
+                start_secs,
+                end_secs,
+                ..
+            } => (start_secs, end_secs),
+            Self::Freeze { at_secs, .. } => (at_secs, at_secs),
+        }
+    }
+
+    /// Time the segment adds to the output.
+    pub fn added_secs(&self) -> f64 {
+        match *self {
+            Self::SlowMotion {
+                start_secs,
+                end_secs,
+                rate,
+                ..
+            } => {
+                let span = (end_secs - start_secs).max(0.0);
+                span / rate.clamp(Self::MIN_RATE, Self::MAX_RATE) - span
+            }
+            Self::Freeze { duration_secs, .. } => duration_secs.max(0.0),
+        }
+    }
+}
+
 impl Timeline {
     /// Create a new empty timeline.
     pub fn new() -> Self {
@@ -223,13 +568,48 @@ impl Timeline {
                 viewport: Viewport::FULL,
                 easing: EasingFunction::default(),
                 source: KeyframeSource::Auto,
+                confidence: None,
             }],
             effects: vec![],
             cursor_config: CursorConfig::default(),
             cuts: vec![],
+            speed_segments: vec![],
+            start_offset_secs: None,
+            end_offset_secs: None,
         }
     }
 
+    /// Segments whose governing keyframe scored below `threshold`.
+    ///
+    /// Each segment runs from its keyframe to the next one, or to
+    /// `duration_secs` for the last keyframe. Unscored keyframes are skipped.
+    pub fn low_confidence_segments(
+        &self,
+        threshold: f64,
+        duration_secs: f64,
+    ) -> Vec<ConfidenceSegment> {
+        let mut sorted: Vec<&CameraKeyframe> = self.keyframes.iter().collect();
+        sorted.sort_by(|a, b| a.time_secs.total_cmp(&b.time_secs));
+
+        sorted
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, kf)| {
+                let confidence = kf.confidence.filter(|c| *c < threshold)?;
+                let end_secs = sorted
+                    .get(idx + 1)
+                    .map(|next| next.time_secs)
+                    .unwrap_or(duration_secs)
+                    .max(kf.time_secs);
+                Some(ConfidenceSegment {
+                    start_secs: kf.time_secs,
+                    end_secs,
+                    confidence,
+                })
+            })
+            .collect()
+    }
+
     /// Get the interpolated viewport at a given time.
     pub fn viewport_at(&self, time_secs: f64) -> Viewport {
         if self.keyframes.is_empty() {
@@ -268,7 +648,7 @@ impl Timeline {
                 }
                 let linear_t = (time_secs - kf_a.time_secs) / duration;
                 let eased_t = kf_a.easing.apply(linear_t);
-                return Viewport::lerp(&kf_a.viewport, &kf_b.viewport, eased_t);
+                return Viewport::lerp_unclamped(&kf_a.viewport, &kf_b.viewport, eased_t);
             }
         }
 
@@ -289,6 +669,60 @@ impl Timeline {
         self.keyframes.insert(pos, keyframe);
     }
 
+    /// Zoom from the camera at `time_secs` to a viewport framing `region`
+    /// in the shape of `lock` (see [`AspectLock::framing`]), arriving
+    /// `duration_secs` later. Both new keyframes are manual and use
+    /// `easing`; keyframes in between are replaced so the transition runs
+    /// as requested.
+    pub fn zoom_to_region(
+        &mut self,
+        time_secs: f64,
+        region: Viewport,
+        easing: EasingFunction,
+        duration_secs: f64,
+        lock: AspectLock,
+    ) {
+        let start = time_secs.max(0.0);
+        let end = start + duration_secs.max(0.0);
+        let from = self.viewport_at(start);
+        self.keyframes
+            .retain(|kf| kf.time_secs < start - 1e-9 || kf.time_secs > end + 1e-9);
+
+        let keyframe = |time_secs, viewport| CameraKeyframe {
+            time_secs,
+            viewport,
+            easing,
+            source: KeyframeSource::Manual,
+            confidence: None,
+        };
+        if end > start {
+            self.add_keyframe(keyframe(start, from));
+        }
+        self.add_keyframe(keyframe(end, lock.framing(region)));
+    }
+
+    /// Indices of keyframes whose viewport doesn't have the shape of
+    /// `lock`, and would come out squashed or stretched.
+    pub fn aspect_mismatches(&self, lock: AspectLock) -> Vec<usize> {
+        self.keyframes
+            .iter()
+            .enumerate()
+            .filter(|(_, kf)| !lock.matches(&kf.viewport))
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    /// Reshape every keyframe that doesn't match `lock` (see
+    /// [`AspectLock::correct`]). Returns how many were changed.
+    pub fn lock_aspect(&mut self, lock: AspectLock) -> usize {
+        let mismatches = self.aspect_mismatches(lock);
+        for &idx in &mismatches {
+            let keyframe = &mut self.keyframes[idx];
+            keyframe.viewport = lock.correct(keyframe.viewport);
+        }
+        mismatches.len()
+    }
+
     /// Check if a time falls within a cut segment.
     pub fn is_cut(&self, time_secs: f64) -> bool {
         self.cuts
@@ -323,12 +757,14 @@ mod tests {
             viewport: Viewport::FULL,
             easing: EasingFunction::Linear,
             source: KeyframeSource::Auto,
+            confidence: None,
         });
         tl.keyframes.push(CameraKeyframe {
             time_secs: 10.0,
             viewport: Viewport::new(0.25, 0.25, 0.5, 0.5),
             easing: EasingFunction::Linear,
             source: KeyframeSource::Auto,
+            confidence: None,
         });
 
         let mid = tl.viewport_at(5.0);
@@ -351,18 +787,72 @@ mod tests {
             viewport: Viewport::new(0.1, 0.1, 0.8, 0.8),
             easing: EasingFunction::EaseInOut,
             source: KeyframeSource::Manual,
+            confidence: None,
         });
         tl.add_keyframe(CameraKeyframe {
             time_secs: 2.0,
             viewport: Viewport::new(0.2, 0.2, 0.6, 0.6),
             easing: EasingFunction::EaseIn,
             source: KeyframeSource::Auto,
+            confidence: None,
         });
 
         let times: Vec<f64> = tl.keyframes.iter().map(|kf| kf.time_secs).collect();
         assert!(times.windows(2).all(|w| w[0] <= w[1]));
     }
 
+    #[test]
+    fn test_zoom_to_region_replaces_keyframes_in_the_transition() {
+        let mut tl = Timeline::new();
+        for t in [1.5, 4.0] {
+            tl.add_keyframe(CameraKeyframe {
+                time_secs: t,
+                viewport: Viewport::new(0.0, 0.0, 0.5, 0.5),
+                easing: EasingFunction::Linear,
+                source: KeyframeSource::Auto,
+                confidence: None,
+            });
+        }
+
+        let from = tl.viewport_at(1.0);
+        let region = Viewport::new(0.6, 0.6, 0.2, 0.1);
+        tl.zoom_to_region(
+            1.0,
+            region,
+            EasingFunction::EaseOutCubic,
+            1.0,
+            AspectLock::CAPTURE,
+        );
+
+        let times: Vec<f64> = tl.keyframes.iter().map(|kf| kf.time_secs).collect();
+        assert_eq!(times, vec![0.0, 1.0, 2.0, 4.0]);
+        assert_eq!(tl.keyframes[1].easing, EasingFunction::EaseOutCubic);
+        assert_eq!(tl.keyframes[1].source, KeyframeSource::Manual);
+        assert_eq!(tl.viewport_at(2.0), Viewport::framing(region));
+        assert_eq!(tl.viewport_at(1.0), from);
+    }
+
+    #[test]
+    fn test_lock_aspect_reshapes_mismatched_keyframes() {
+        let lock = AspectLock::new(1920, 1080, 1080, 1080);
+        let mut tl = Timeline::new();
+        tl.add_keyframe(CameraKeyframe {
+            time_secs: 2.0,
+            viewport: Viewport::new(0.2, 0.2, 0.5625 * 0.4, 0.4),
+            easing: EasingFunction::Linear,
+            source: KeyframeSource::Manual,
+            confidence: None,
+        });
+        assert_eq!(tl.aspect_mismatches(lock), vec![0]);
+
+        assert_eq!(tl.lock_aspect(lock), 1);
+        assert!(tl.aspect_mismatches(lock).is_empty());
+        // The full frame grows to show the whole recording, letterboxed.
+        assert!((tl.keyframes[0].viewport.w - 1.0).abs() < 1e-9);
+        assert!((tl.keyframes[0].viewport.h - 1.0 / lock.ratio).abs() < 1e-9);
+        assert_eq!(tl.lock_aspect(lock), 0);
+    }
+
     #[test]
     fn test_easing_functions() {
         assert!((EasingFunction::Linear.apply(0.5) - 0.5).abs() < 1e-9);
@@ -372,6 +862,129 @@ mod tests {
         assert!((EasingFunction::EaseInOut.apply(1.0) - 1.0).abs() < 1e-9);
     }
 
+    #[test]
+    fn test_every_easing_starts_at_zero_and_ends_at_one() {
+        let curves = [
+            EasingFunction::EaseInCubic,
+            EasingFunction::EaseOutCubic,
+            EasingFunction::EaseInOutCubic,
+            EasingFunction::EaseInSine,
+            EasingFunction::EaseOutSine,
+            EasingFunction::EaseInOutSine,
+            EasingFunction::EaseInExpo,
+            EasingFunction::EaseOutExpo,
+            EasingFunction::EaseInOutExpo,
+            EasingFunction::EaseInBack,
+            EasingFunction::EaseOutBack,
+            EasingFunction::EaseInOutBack,
+            EasingFunction::CubicBezier {
+                x1: 0.25,
+                y1: 0.1,
+                x2: 0.25,
+                y2: 1.0,
+            },
+            EasingFunction::SPRING,
+            EasingFunction::Spring {
+                stiffness: 100.0,
+                damping: 40.0,
+                mass: 1.0,
+            },
+        ];
+        for curve in curves {
+            assert!(curve.apply(0.0).abs() < 1e-6, "{curve:?}");
+            assert!((curve.apply(1.0) - 1.0).abs() < 1e-6, "{curve:?}");
+        }
+    }
+
+    #[test]
+    fn test_cubic_bezier_matches_named_curves() {
+        let linear = EasingFunction::CubicBezier {
+            x1: 0.0,
+            y1: 0.0,
+            x2: 1.0,
+            y2: 1.0,
+        };
+        // CSS `ease-out`.
+        let ease_out = EasingFunction::CubicBezier {
+            x1: 0.0,
+            y1: 0.0,
+            x2: 0.58,
+            y2: 1.0,
+        };
+        for i in 1..10 {
+            let t = i as f64 / 10.0;
+            assert!((linear.apply(t) - t).abs() < 1e-5);
+            assert!(ease_out.apply(t) > t);
+        }
+        assert!(!ease_out.overshoots());
+
+        let overshoot = EasingFunction::CubicBezier {
+            x1: 0.3,
+            y1: 0.0,
+            x2: 0.2,
+            y2: 1.6,
+        };
+        assert!(overshoot.overshoots());
+        assert!((0..=100).any(|i| overshoot.apply(i as f64 / 100.0) > 1.0));
+    }
+
+    #[test]
+    fn test_spring_damping_controls_overshoot() {
+        let peak = |easing: EasingFunction| {
+            (0..=200)
+                .map(|i| easing.apply(i as f64 / 200.0))
+                .fold(f64::MIN, f64::max)
+        };
+        let bouncy = EasingFunction::Spring {
+            stiffness: 200.0,
+            damping: 8.0,
+            mass: 1.0,
+        };
+        let critical = EasingFunction::Spring {
+            stiffness: 100.0,
+            damping: 20.0,
+            mass: 1.0,
+        };
+        assert!(bouncy.overshoots());
+        assert!(peak(bouncy) > 1.2);
+        assert!(!critical.overshoots());
+        assert!(peak(critical) <= 1.0 + 1e-6);
+    }
+
+    #[test]
+    fn test_easing_serialization() {
+        assert_eq!(
+            serde_json::to_string(&EasingFunction::EaseOutBack).unwrap(),
+            "\"ease_out_back\""
+        );
+        let json = r#"{"spring":{"stiffness":170.0,"damping":18.0,"mass":1.0}}"#;
+        let parsed: EasingFunction = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed, EasingFunction::SPRING);
+        assert_eq!(serde_json::to_string(&parsed).unwrap(), json);
+    }
+
+    #[test]
+    fn test_viewport_at_follows_overshoot() {
+        let mut tl = Timeline::new();
+        tl.keyframes.clear();
+        tl.add_keyframe(CameraKeyframe {
+            time_secs: 0.0,
+            viewport: Viewport::FULL,
+            easing: EasingFunction::EaseOutBack,
+            source: KeyframeSource::Manual,
+            confidence: None,
+        });
+        tl.add_keyframe(CameraKeyframe {
+            time_secs: 1.0,
+            viewport: Viewport::new(0.25, 0.25, 0.5, 0.5),
+            easing: EasingFunction::Linear,
+            source: KeyframeSource::Manual,
+            confidence: None,
+        });
+        let vp = tl.viewport_at(0.7);
+        assert!(vp.w < 0.5, "camera should zoom past the target, got {vp:?}");
+    }
+
     #[test]
     fn test_cut_detection() {
         let mut tl = Timeline::new();
@@ -385,6 +998,39 @@ mod tests {
         assert!(!tl.is_cut(9.0));
     }
 
+    #[test]
+    fn test_speed_segments_default_and_roundtrip() {
+        let legacy: Timeline =
+            serde_json::from_str(r#"{"version":"1.0","keyframes":[],"effects":[]}"#).unwrap();
+        assert!(legacy.speed_segments.is_empty());
+
+        let parsed: SpeedSegment = serde_json::from_str(
+            r#"{"type":"slow_motion","start_secs":2.0,"end_secs":3.0,"rate":0.5}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            parsed,
+            SpeedSegment::SlowMotion {
+                start_secs: 2.0,
+                end_secs: 3.0,
+                rate: 0.5,
+                audio: SlowMotionAudio::Mute,
+                interpolate: false,
+            }
+        );
+        assert!((parsed.added_secs() - 1.0).abs() < 1e-9);
+
+        let freeze = SpeedSegment::Freeze {
+            at_secs: 4.0,
+            duration_secs: 1.5,
+        };
+        let json = serde_json::to_string(&freeze).unwrap();
+        assert!(json.contains(r#""type":"freeze""#));
+        assert_eq!(serde_json::from_str::<SpeedSegment>(&json).unwrap(), freeze);
+        assert_eq!(freeze.source_range(), (4.0, 4.0));
+        assert_eq!(freeze.added_secs(), 1.5);
+    }
+
     #[test]
     fn test_timeline_serialization() {
         let tl = Timeline::new();
@@ -403,15 +1049,54 @@ mod tests {
             viewport: Viewport::FULL,
             easing: EasingFunction::Linear,
             source: KeyframeSource::Auto,
+            confidence: None,
         });
         tl.keyframes.push(CameraKeyframe {
             time_secs: 0.0,
             viewport: Viewport::new(0.2, 0.2, 0.6, 0.6),
             easing: EasingFunction::Linear,
             source: KeyframeSource::Manual,
+            confidence: None,
         });
 
         let vp = tl.viewport_at(0.0);
         assert_eq!(vp, Viewport::new(0.2, 0.2, 0.6, 0.6));
     }
+
+    #[test]
+    fn test_low_confidence_segments_span_to_next_keyframe() {
+        let mut tl = Timeline::new();
+        tl.keyframes = [
+            (0.0, Some(0.9)),
+            (2.0, Some(0.2)),
+            (5.0, None),
+            (7.0, Some(0.4)),
+        ]
+        .into_iter()
+        .map(|(t, confidence)| CameraKeyframe {
+            time_secs: t,
+            viewport: Viewport::FULL,
+            easing: EasingFunction::Linear,
+            source: KeyframeSource::Auto,
+            confidence,
+        })
+        .collect();
+
+        let segments = tl.low_confidence_segments(LOW_CONFIDENCE_THRESHOLD, 10.0);
+        assert_eq!(
+            segments,
+            vec![
+                ConfidenceSegment {
+                    start_secs: 2.0,
+                    end_secs: 5.0,
+                    confidence: 0.2,
+                },
+                ConfidenceSegment {
+                    start_secs: 7.0,
+                    end_secs: 10.0,
+                    confidence: 0.4,
+                },
+            ]
+        );
+    }
 }
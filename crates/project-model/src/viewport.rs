@@ -2,13 +2,15 @@
 //!
 //! All coordinates are normalized to `[0.0, 1.0]` range.
 
+use grabme_platform_core::coords::CaptureNorm;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// A rectangular viewport within the capture region.
 ///
 /// Coordinates are normalized: `(0.0, 0.0)` is top-left,
 /// `(1.0, 1.0)` is bottom-right of the full capture area.
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct Viewport {
     /// Left edge (normalized).
     pub x: f64,
@@ -52,8 +54,8 @@ impl Viewport {
     }
 
     /// The center point of this viewport.
-    pub fn center(&self) -> (f64, f64) {
-        (self.x + self.w / 2.0, self.y + self.h / 2.0)
+    pub fn center(&self) -> CaptureNorm {
+        CaptureNorm::new(self.x + self.w / 2.0, self.y + self.h / 2.0)
     }
 
     /// Right edge.
@@ -71,9 +73,12 @@ impl Viewport {
         1.0 / self.w.min(self.h)
     }
 
-    /// Check if a normalized point is within this viewport.
-    pub fn contains(&self, px: f64, py: f64) -> bool {
-        px >= self.x && px <= self.right() && py >= self.y && py <= self.bottom()
+    /// Check if a point is within this viewport.
+    pub fn contains(&self, point: CaptureNorm) -> bool {
+        point.x >= self.x
+            && point.x <= self.right()
+            && point.y >= self.y
+            && point.y <= self.bottom()
     }
 
     /// Linearly interpolate between two viewports.
@@ -87,6 +92,18 @@ impl Viewport {
         }
     }
 
+    /// Like [`Viewport::lerp`], but `t` may leave [0, 1] so overshooting
+    /// easing curves can carry the camera past either end. The result is
+    /// kept within the extended bounds of [`Viewport::new`].
+    pub fn lerp_unclamped(a: &Viewport, b: &Viewport, t: f64) -> Viewport {
+        Viewport::new(
+            a.x + (b.x - a.x) * t,
+            a.y + (b.y - a.y) * t,
+            a.w + (b.w - a.w) * t,
+            a.h + (b.h - a.h) * t,
+        )
+    }
+
     /// Area of the viewport (0.0 to 1.0).
     pub fn area(&self) -> f64 {
         self.w * self.h
@@ -94,11 +111,23 @@ impl Viewport {
 
     /// Convert a point from capture-space to viewport-local coordinates.
     /// Returns `None` if the point is outside the viewport.
-    pub fn to_local(&self, px: f64, py: f64) -> Option<(f64, f64)> {
-        if !self.contains(px, py) {
+    pub fn to_local(&self, point: CaptureNorm) -> Option<(f64, f64)> {
+        if !self.contains(point) {
             return None;
         }
-        Some(((px - self.x) / self.w, (py - self.y) / self.h))
+        Some(((point.x - self.x) / self.w, (point.y - self.y) / self.h))
+    }
+
+    /// The smallest viewport with the capture's aspect ratio that shows all
+    /// of `region`, centred on it and kept inside the capture where it fits.
+    pub fn framing(region: Viewport) -> Self {
+        AspectLock::CAPTURE.framing(region)
+    }
+
+    /// Shape of the viewport in pixels, `width / height`, on a capture of
+    /// `capture_aspect`.
+    pub fn pixel_aspect(&self, capture_aspect: f64) -> f64 {
+        self.w * capture_aspect / self.h
     }
 
     /// Create a 9:16 vertical viewport centered at the given point.
@@ -108,39 +137,101 @@ impl Viewport {
     }
 }
 
-impl Default for Viewport {
-    fn default() -> Self {
-        Self::FULL
-    }
+/// Keeps viewports in the shape of the export frame. The export scales
+/// each viewport to the output size on both axes, so one of another shape
+/// comes out squashed or stretched.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AspectLock {
+    /// Normalized `w / h` a viewport needs to fill the output undistorted.
+    pub ratio: f64,
 }
 
-/// A 2D normalized point.
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
-pub struct Point2D {
-    pub x: f64,
-    pub y: f64,
-}
+impl AspectLock {
+    /// Viewports in the capture's own shape (`w == h`), for an export with
+    /// the capture's aspect ratio.
+    pub const CAPTURE: AspectLock = AspectLock { ratio: 1.0 };
+
+    /// Relative difference in shape a viewport may have and still match.
+    pub const TOLERANCE: f64 = 0.02;
 
-impl Point2D {
-    pub fn new(x: f64, y: f64) -> Self {
-        Self { x, y }
+    /// Lock for a `capture_width`x`capture_height` recording exported at
+    /// `output_width`x`output_height`. Zero sizes count as 1.
+    pub fn new(
+        capture_width: u32,
+        capture_height: u32,
+        output_width: u32,
+        output_height: u32,
+    ) -> Self {
+        let capture_aspect = capture_width.max(1) as f64 / capture_height.max(1) as f64;
+        let output_aspect = output_width.max(1) as f64 / output_height.max(1) as f64;
+        Self {
+            ratio: output_aspect / capture_aspect,
+        }
     }
 
-    /// Euclidean distance to another point.
-    pub fn distance_to(&self, other: &Point2D) -> f64 {
-        ((self.x - other.x).powi(2) + (self.y - other.y).powi(2)).sqrt()
+    /// Whether `viewport` has the locked shape, within [`Self::TOLERANCE`].
+    pub fn matches(&self, viewport: &Viewport) -> bool {
+        ((viewport.w / viewport.h) / self.ratio - 1.0).abs() <= Self::TOLERANCE
     }
 
-    /// Linear interpolation between two points.
-    pub fn lerp(a: &Point2D, b: &Point2D, t: f64) -> Point2D {
-        let t = t.clamp(0.0, 1.0);
-        Point2D {
-            x: a.x + (b.x - a.x) * t,
-            y: a.y + (b.y - a.y) * t,
-        }
+    /// The smallest locked viewport that shows all of `viewport`: the
+    /// short side grows around the same centre, so nothing framed is cut
+    /// off. It is kept inside the capture where it fits.
+    pub fn correct(&self, viewport: Viewport) -> Viewport {
+        let (w, h) = if viewport.w / viewport.h > self.ratio {
+            (viewport.w, viewport.w / self.ratio)
+        } else {
+            (viewport.h * self.ratio, viewport.h)
+        };
+        self.place(viewport.center(), w, h)
+    }
+
+    /// `viewport` resized to width `w` around its centre, with the height
+    /// following.
+    pub fn with_width(&self, viewport: Viewport, w: f64) -> Viewport {
+        self.place(viewport.center(), w, w / self.ratio)
+    }
+
+    /// `viewport` resized to height `h` around its centre, with the width
+    /// following.
+    pub fn with_height(&self, viewport: Viewport, h: f64) -> Viewport {
+        self.place(viewport.center(), h * self.ratio, h)
+    }
+
+    /// The smallest locked viewport that shows all of `region`, centred on
+    /// it and kept inside the capture where it fits.
+    pub fn framing(&self, region: Viewport) -> Viewport {
+        let (w, h) = (region.w.abs(), region.h.abs());
+        let scale = (w / self.ratio)
+            .max(h)
+            .clamp(0.01, 1.0 / self.ratio.max(1.0));
+        self.place(region.center(), scale * self.ratio, scale)
+    }
+
+    /// A `w`x`h` viewport centred on `center`, shifted inside the capture
+    /// on each axis where it fits.
+    fn place(&self, center: CaptureNorm, w: f64, h: f64) -> Viewport {
+        let axis = |center: f64, size: f64| {
+            let start = center - size / 2.0;
+            if size <= 1.0 {
+                start.clamp(0.0, 1.0 - size)
+            } else {
+                start
+            }
+        };
+        Viewport::new(axis(center.x, w), axis(center.y, h), w, h)
+    }
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Self::FULL
     }
 }
 
+/// A 2D point normalized against the capture region.
+pub type Point2D = CaptureNorm;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -149,9 +240,9 @@ mod tests {
     fn test_full_viewport() {
         let vp = Viewport::FULL;
         assert_eq!(vp.zoom_factor(), 1.0);
-        assert!(vp.contains(0.5, 0.5));
-        assert!(vp.contains(0.0, 0.0));
-        assert!(vp.contains(1.0, 1.0));
+        assert!(vp.contains(CaptureNorm::new(0.5, 0.5)));
+        assert!(vp.contains(CaptureNorm::new(0.0, 0.0)));
+        assert!(vp.contains(CaptureNorm::new(1.0, 1.0)));
     }
 
     #[test]
@@ -193,11 +284,52 @@ mod tests {
     #[test]
     fn test_to_local() {
         let vp = Viewport::new(0.2, 0.3, 0.4, 0.4);
-        let local = vp.to_local(0.4, 0.5).unwrap();
+        let local = vp.to_local(CaptureNorm::new(0.4, 0.5)).unwrap();
         assert!((local.0 - 0.5).abs() < 1e-9);
         assert!((local.1 - 0.5).abs() < 1e-9);
 
-        assert!(vp.to_local(0.0, 0.0).is_none());
+        assert!(vp.to_local(CaptureNorm::new(0.0, 0.0)).is_none());
+    }
+
+    #[test]
+    fn test_framing_keeps_aspect_and_stays_on_screen() {
+        let vp = Viewport::framing(Viewport::new(0.2, 0.3, 0.4, 0.2));
+        assert!((vp.w - 0.4).abs() < 1e-9 && (vp.h - 0.4).abs() < 1e-9);
+        assert!((vp.x - 0.2).abs() < 1e-9);
+        assert!((vp.y - 0.2).abs() < 1e-9);
+
+        // A region at the edge is framed without leaving the capture.
+        let vp = Viewport::framing(Viewport::new(0.9, 0.95, 0.1, 0.05));
+        assert!((vp.right() - 1.0).abs() < 1e-9);
+        assert!((vp.bottom() - 1.0).abs() < 1e-9);
+        assert!(vp.contains(CaptureNorm::new(0.95, 0.975)));
+    }
+
+    #[test]
+    fn test_aspect_lock_grows_the_short_side() {
+        // A 16:10 recording exported at 16:9.
+        let lock = AspectLock::new(1920, 1200, 1920, 1080);
+        assert!((lock.ratio - 1200.0 / 1080.0).abs() < 1e-9);
+        assert!(!lock.matches(&Viewport::FULL));
+
+        let squashed = Viewport::new(0.2, 0.2, 0.4, 0.2);
+        let fixed = lock.correct(squashed);
+        assert!(lock.matches(&fixed));
+        assert!((fixed.w - 0.4).abs() < 1e-9);
+        assert!((fixed.h - 0.4 / lock.ratio).abs() < 1e-9);
+        assert!((fixed.center().x - 0.4).abs() < 1e-9);
+        assert!((fixed.center().y - 0.3).abs() < 1e-9);
+        assert!(fixed.y >= 0.0);
+
+        let resized = lock.with_height(fixed, 0.5);
+        assert!(lock.matches(&resized));
+        assert!((resized.w - 0.5 * lock.ratio).abs() < 1e-9);
+        assert!(lock.matches(&lock.with_width(fixed, 0.3)));
+
+        // Framing a wide region never leaves the capture.
+        let framed = lock.framing(Viewport::new(0.0, 0.4, 1.0, 0.1));
+        assert!(lock.matches(&framed));
+        assert!(framed.right() <= 1.0 + 1e-9 && framed.x >= 0.0);
     }
 
     #[test]
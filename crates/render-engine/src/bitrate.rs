@@ -0,0 +1,356 @@
+//! Export bitrate estimation.
+//!
+//! Screen recordings range from a static terminal to full-screen video, so a
+//! fixed bitrate is either wasteful or blocky. The estimate combines a quick
+//! constant-quality encode of a few sampled seconds, the source bitrate from
+//! ffprobe, and how much the pointer moves (zoom and pan follow it, which
+//! re-encodes more of each frame).
+
+use std::path::Path;
+use std::process::Command;
+
+use grabme_common::error::{GrabmeError, GrabmeResult};
+use grabme_project_model::event::{EventKind, InputEvent};
+use grabme_project_model::project::{ExportConfig, ExportFormat};
+use grabme_project_model::LoadedProject;
+
+use crate::ffmpeg_loglevel;
+use crate::probe::probe_media;
+use crate::stills::load_events;
+
+/// Lowest video bitrate the encoders are given.
+pub const MIN_VIDEO_BITRATE_KBPS: u32 = 250;
+
+/// Number of one-second windows encoded by the sample probe.
+const SAMPLE_WINDOWS: usize = 3;
+const SAMPLE_WINDOW_SECS: f64 = 1.0;
+/// The probe encodes with `ultrafast`; the export's `medium` preset reaches
+/// the same quality with fewer bits.
+const PRESET_FACTOR: f64 = 0.75;
+/// Bits per pixel per frame bounds, and the fallback when nothing is probed.
+const MIN_BITS_PER_PIXEL: f64 = 0.02;
+const MAX_BITS_PER_PIXEL: f64 = 0.30;
+const FALLBACK_BITS_PER_PIXEL: f64 = 0.07;
+/// Container overhead reserved when solving for a target size.
+const CONTAINER_OVERHEAD: f64 = 0.03;
+
+/// What the probe learned about the recording.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ContentComplexity {
+    /// Bitrate of the sampled windows at constant quality, at output size.
+    pub sample_kbps: Option<f64>,
+    /// Source bitrate scaled to the output pixel count.
+    pub source_kbps: Option<f64>,
+    /// Pointer and scroll activity in `[0.0, 1.0]`.
+    pub motion: f64,
+}
+
+/// Probe `project`'s screen track and events over the export range.
+pub fn probe_project_complexity(
+    project: &LoadedProject,
+    config: &ExportConfig,
+    start_secs: f64,
+    end_secs: f64,
+) -> GrabmeResult<ContentComplexity> {
+    let track = project
+        .project
+        .tracks
+        .screen
+        .as_ref()
+        .ok_or_else(|| GrabmeError::render("Project has no screen track"))?;
+    let events = load_events(&project.root).unwrap_or_default();
+    Ok(probe_complexity(
+        &project.root.join(&track.path),
+        config,
+        start_secs,
+        end_secs,
+        &events,
+    ))
+}
+
+/// Probe the screen track of the export range `[start_secs, end_secs]`.
+pub fn probe_complexity(
+    screen_path: &Path,
+    config: &ExportConfig,
+    start_secs: f64,
+    end_secs: f64,
+    events: &[InputEvent],
+) -> ContentComplexity {
+    let out_pixels = config.width as f64 * config.height as f64;
+    let source_kbps = probe_media(screen_path).ok().and_then(|info| {
+        let video = info.video?;
+        let src_pixels = video.width as f64 * video.height as f64;
+        let kbps = info.bit_rate_kbps? as f64;
+        (src_pixels > 0.0).then(|| kbps * out_pixels / src_pixels)
+    });
+
+    let sizes: Vec<u64> = sample_window_starts(start_secs, end_secs)
+        .into_iter()
+        .filter_map(|t| sample_window_bytes(screen_path, t, config))
+        .collect();
+    let sample_kbps = (!sizes.is_empty()).then(|| {
+        let bytes = sizes.iter().sum::<u64>() as f64 / sizes.len() as f64;
+        bytes * 8.0 / 1000.0 / SAMPLE_WINDOW_SECS
+    });
+
+    ContentComplexity {
+        sample_kbps,
+        source_kbps,
+        motion: motion_score(events, start_secs, end_secs),
+    }
+}
+
+/// Recommended video bitrate in kbps for `config`.
+pub fn recommend_video_bitrate_kbps(complexity: &ContentComplexity, config: &ExportConfig) -> u32 {
+    let pixel_rate = config.width as f64 * config.height as f64 * config.fps.max(1) as f64 / 1000.0;
+    let base = complexity
+        .sample_kbps
+        .map(|kbps| kbps * PRESET_FACTOR)
+        .or(complexity.source_kbps)
+        .unwrap_or(pixel_rate * FALLBACK_BITS_PER_PIXEL);
+    let codec_factor = match config.format {
+        ExportFormat::Mp4H265 => 0.6,
+        ExportFormat::Webm => 0.7,
+        _ => 1.0,
+    };
+    let kbps = base * (1.0 + 0.5 * complexity.motion.clamp(0.0, 1.0)) * codec_factor;
+    let floor = (pixel_rate * MIN_BITS_PER_PIXEL * codec_factor).max(MIN_VIDEO_BITRATE_KBPS as f64);
+    let ceiling = (pixel_rate * MAX_BITS_PER_PIXEL * codec_factor).max(floor);
+    kbps.clamp(floor, ceiling).round() as u32
+}
+
+/// Video bitrate that makes a `duration_secs` export about `target_bytes`.
+pub fn video_kbps_for_target_size(
+    target_bytes: u64,
+    duration_secs: f64,
+    audio_kbps: u32,
+) -> GrabmeResult<u32> {
+    if duration_secs <= 0.0 {
+        return Err(GrabmeError::render(
+            "Cannot solve a target size for an empty export",
+        ));
+    }
+    let total_kbps =
+        target_bytes as f64 * 8.0 / 1000.0 / duration_secs * (1.0 - CONTAINER_OVERHEAD);
+    let video_kbps = total_kbps - audio_kbps as f64;
+    if video_kbps < MIN_VIDEO_BITRATE_KBPS as f64 {
+        let needed_mb = (MIN_VIDEO_BITRATE_KBPS + audio_kbps) as f64 * duration_secs
+            / 8.0
+            / 1000.0
+            / (1.0 - CONTAINER_OVERHEAD);
+        return Err(GrabmeError::render(format!(
+            "Target size is too small for {duration_secs:.1}s of video; it needs at least {needed_mb:.1}MB"
+        )));
+    }
+    Ok(video_kbps.floor() as u32)
+}
+
+/// Audio bitrate the export will use, or 0 when it has no audio.
+pub fn export_audio_kbps(config: &ExportConfig, has_audio: bool) -> u32 {
+    if !has_audio || config.format.is_image_sequence() || config.format == ExportFormat::Gif {
+        return 0;
+    }
+    match config.format {
+        ExportFormat::Webm => 128,
+        _ => config.audio_bitrate_kbps.max(64),
+    }
+}
+
+/// Parse a size like `50MB`, `1.5GB`, or `800KB` into bytes. Units are
+/// decimal; a bare number is megabytes.
+pub fn parse_size(raw: &str) -> GrabmeResult<u64> {
+    let trimmed = raw.trim();
+    let split = trimmed
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(trimmed.len());
+    let (number, unit) = trimmed.split_at(split);
+    let value: f64 = number
+        .parse()
+        .map_err(|_| GrabmeError::render(format!("Invalid size: {raw}")))?;
+    let multiplier = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "m" | "mb" => 1e6,
+        "k" | "kb" => 1e3,
+        "g" | "gb" => 1e9,
+        "b" => 1.0,
+        other => {
+            return Err(GrabmeError::render(format!(
+                "Unknown size unit '{other}' in {raw}; use KB, MB, or GB"
+            )))
+        }
+    };
+    let bytes = (value * multiplier).round();
+    if !bytes.is_finite() || bytes <= 0.0 {
+        return Err(GrabmeError::render(format!("Invalid size: {raw}")));
+    }
+    Ok(bytes as u64)
+}
+
+/// Pointer travel and scrolling per second, mapped to `[0.0, 1.0]`.
+///
+/// Half a screen of pointer travel per second, or four scroll steps per
+/// second, counts as full motion.
+pub fn motion_score(events: &[InputEvent], start_secs: f64, end_secs: f64) -> f64 {
+    let span = end_secs - start_secs;
+    if span <= 0.0 {
+        return 0.0;
+    }
+    let mut travel = 0.0;
+    let mut scrolls = 0usize;
+    let mut last: Option<(f64, f64)> = None;
+    for event in events {
+        let t = event.timestamp_secs();
+        if t < start_secs || t > end_secs {
+            continue;
+        }
+        match event.kind {
+            EventKind::Pointer { x, y } => {
+                if let Some((lx, ly)) = last {
+                    travel += ((x - lx).powi(2) + (y - ly).powi(2)).sqrt();
+                }
+                last = Some((x, y));
+            }
+            EventKind::Scroll { .. } => scrolls += 1,
+            _ => {}
+        }
+    }
+    let travel_score = (travel / span / 0.5).min(1.0);
+    let scroll_score = (scrolls as f64 / span / 4.0).min(1.0);
+    (0.7 * travel_score + 0.3 * scroll_score).clamp(0.0, 1.0)
+}
+
+/// Start times of the sampled windows, spread over the range.
+fn sample_window_starts(start_secs: f64, end_secs: f64) -> Vec<f64> {
+    let span = end_secs - start_secs;
+    if span < SAMPLE_WINDOW_SECS {
+        return vec![];
+    }
+    (1..=SAMPLE_WINDOWS)
+        .map(|i| {
+            let center = start_secs + span * i as f64 / (SAMPLE_WINDOWS + 1) as f64;
+            (center - SAMPLE_WINDOW_SECS / 2.0).clamp(start_secs, end_secs - SAMPLE_WINDOW_SECS)
+        })
+        .collect()
+}
+
+/// Encode one window at constant quality and return its size in bytes.
+fn sample_window_bytes(screen_path: &Path, start_secs: f64, config: &ExportConfig) -> Option<u64> {
+    let output = Command::new("ffmpeg")
+        .args(["-hide_banner", "-nostats", "-loglevel"])
+        .arg(ffmpeg_loglevel())
+        .arg("-ss")
+        .arg(format!("{start_secs:.3}"))
+        .arg("-t")
+        .arg(format!("{SAMPLE_WINDOW_SECS:.3}"))
+        .arg("-i")
+        .arg(screen_path)
+        .arg("-an")
+        .arg("-vf")
+        .arg(format!(
+            "scale={}:{}:force_original_aspect_ratio=decrease:force_divisible_by=2",
+            config.width, config.height
+        ))
+        .arg("-r")
+        .arg(config.fps.max(1).to_string())
+        .args([
+            "-c:v",
+            "libx264",
+            "-preset",
+            "ultrafast",
+            "-crf",
+            "23",
+            "-f",
+            "matroska",
+            "-",
+        ])
+        .output()
+        .ok()?;
+    (output.status.success() && !output.stdout.is_empty()).then_some(output.stdout.len() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use grabme_project_model::event::ScrollSource;
+    use grabme_project_model::project::Project;
+
+    fn config() -> ExportConfig {
+        Project::new("test", 1920, 1080, 30).export
+    }
+
+    #[test]
+    fn test_recommend_clamps_to_pixel_rate_bounds() {
+        let config = config();
+        let quiet = ContentComplexity {
+            sample_kbps: Some(10.0),
+            ..Default::default()
+        };
+        let busy = ContentComplexity {
+            sample_kbps: Some(1_000_000.0),
+            motion: 1.0,
+            ..Default::default()
+        };
+        // 1920x1080x30 is ~62 Mpx/s: 0.02 and 0.30 bits per pixel.
+        assert_eq!(recommend_video_bitrate_kbps(&quiet, &config), 1244);
+        assert_eq!(recommend_video_bitrate_kbps(&busy, &config), 18662);
+    }
+
+    #[test]
+    fn test_recommend_prefers_sample_over_source_and_scales_by_motion() {
+        let config = config();
+        let still = ContentComplexity {
+            sample_kbps: Some(4000.0),
+            source_kbps: Some(9000.0),
+            motion: 0.0,
+        };
+        let moving = ContentComplexity {
+            motion: 1.0,
+            ..still.clone()
+        };
+        assert_eq!(recommend_video_bitrate_kbps(&still, &config), 3000);
+        assert_eq!(recommend_video_bitrate_kbps(&moving, &config), 4500);
+
+        let mut h265 = config.clone();
+        h265.format = ExportFormat::Mp4H265;
+        assert_eq!(recommend_video_bitrate_kbps(&still, &h265), 1800);
+    }
+
+    #[test]
+    fn test_video_kbps_for_target_size() {
+        // 50MB over 100s is 4000kbps; 3% overhead and 192k audio come off.
+        assert_eq!(
+            video_kbps_for_target_size(50_000_000, 100.0, 192).unwrap(),
+            3688
+        );
+        let err = video_kbps_for_target_size(1_000_000, 600.0, 192).unwrap_err();
+        assert!(err.to_string().contains("at least"));
+    }
+
+    #[test]
+    fn test_parse_size_units() {
+        assert_eq!(parse_size("50MB").unwrap(), 50_000_000);
+        assert_eq!(parse_size("1.5 GB").unwrap(), 1_500_000_000);
+        assert_eq!(parse_size("800k").unwrap(), 800_000);
+        assert_eq!(parse_size("25").unwrap(), 25_000_000);
+        assert!(parse_size("ten MB").is_err());
+        assert!(parse_size("5TB").is_err());
+    }
+
+    #[test]
+    fn test_motion_score_counts_travel_and_scrolls() {
+        let events = vec![
+            InputEvent::pointer(0, 0.0, 0.0),
+            InputEvent::pointer(500_000_000, 0.5, 0.0),
+            InputEvent::scroll(600_000_000, 0.0, 1.0, ScrollSource::Wheel, 0.5, 0.0),
+        ];
+        let score = motion_score(&events, 0.0, 1.0);
+        assert!((score - (0.7 + 0.3 * 0.25)).abs() < 1e-9);
+        assert_eq!(motion_score(&events, 2.0, 3.0), 0.0);
+    }
+
+    #[test]
+    fn test_sample_windows_stay_inside_range() {
+        let starts = sample_window_starts(10.0, 14.0);
+        assert_eq!(starts, vec![10.5, 11.5, 12.5]);
+        assert!(sample_window_starts(0.0, 0.5).is_empty());
+    }
+}
@@ -0,0 +1,193 @@
+//! Put exported media on the system clipboard.
+//!
+//! Images (GIF, PNG) are copied as their bytes under their own MIME type, so
+//! chat apps and issue trackers paste the image itself rather than a path.
+//! Videos are too large for apps to accept as clipboard data; they are
+//! copied as a `text/uri-list` file reference, which file managers and most
+//! upload fields accept on paste.
+//!
+//! Uses `wl-copy` (wl-clipboard) on Wayland and `xclip` on X11.
+
+use std::fs::File;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use grabme_common::error::{GrabmeError, GrabmeResult};
+
+/// How a file ended up on the clipboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClipboardContent {
+    /// The file's bytes, offered as this MIME type.
+    Image(&'static str),
+    /// A `file://` reference to the file.
+    FileReference,
+}
+
+/// Clipboard tool to drive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ClipboardTool {
+    WlCopy,
+    Xclip,
+}
+
+/// MIME type of an exported file, from its extension.
+pub fn media_mime_type(path: &Path) -> Option<&'static str> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    Some(match extension.as_str() {
+        "gif" => "image/gif",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        "mkv" => "video/x-matroska",
+        _ => return None,
+    })
+}
+
+/// Copy `path` to the clipboard: image bytes for images, a file reference
+/// for everything else.
+pub fn copy_file_to_clipboard(path: &Path) -> GrabmeResult<ClipboardContent> {
+    if !path.is_file() {
+        return Err(GrabmeError::render(format!(
+            "Cannot copy {} to the clipboard: not a file",
+            path.display()
+        )));
+    }
+    let tool = clipboard_tool()?;
+
+    let (content, mime) = match media_mime_type(path) {
+        Some(mime) if mime.starts_with("image/") => (ClipboardContent::Image(mime), mime),
+        _ => (ClipboardContent::FileReference, "text/uri-list"),
+    };
+
+    let input =
+        match content {
+            ClipboardContent::Image(_) => Stdio::from(File::open(path).map_err(|e| {
+                GrabmeError::render(format!("Failed to open {}: {e}", path.display()))
+            })?),
+            ClipboardContent::FileReference => Stdio::piped(),
+        };
+
+    let mut child = clipboard_command(tool, mime)
+        .stdin(input)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| GrabmeError::render(format!("Failed to launch {}: {e}", tool.binary())))?;
+
+    if content == ClipboardContent::FileReference {
+        use std::io::Write;
+
+        let absolute = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        let uri = file_uri(&absolute);
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(format!("{uri}\r\n").as_bytes())
+                .map_err(|e| GrabmeError::render(format!("Failed to write to clipboard: {e}")))?;
+        }
+    }
+
+    // Both tools fork a background process that serves the selection, so
+    // the foreground one exits as soon as it has taken ownership.
+    let output = child
+        .wait_with_output()
+        .map_err(|e| GrabmeError::render(format!("{} failed: {e}", tool.binary())))?;
+    if !output.status.success() {
+        return Err(GrabmeError::render(format!(
+            "{} failed: {}",
+            tool.binary(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    tracing::info!(path = %path.display(), ?content, "Copied export to clipboard");
+    Ok(content)
+}
+
+impl ClipboardTool {
+    fn binary(self) -> &'static str {
+        match self {
+            ClipboardTool::WlCopy => "wl-copy",
+            ClipboardTool::Xclip => "xclip",
+        }
+    }
+}
+
+fn clipboard_tool() -> GrabmeResult<ClipboardTool> {
+    let wayland = std::env::var_os("WAYLAND_DISPLAY").is_some();
+    let x11 = std::env::var_os("DISPLAY").is_some();
+    if wayland && crate::export::command_exists("wl-copy") {
+        return Ok(ClipboardTool::WlCopy);
+    }
+    if x11 && crate::export::command_exists("xclip") {
+        return Ok(ClipboardTool::Xclip);
+    }
+    Err(GrabmeError::unsupported(if wayland {
+        "Copying to the clipboard needs wl-copy (install wl-clipboard)"
+    } else {
+        "Copying to the clipboard needs xclip"
+    }))
+}
+
+fn clipboard_command(tool: ClipboardTool, mime: &str) -> Command {
+    let mut command = Command::new(tool.binary());
+    match tool {
+        ClipboardTool::WlCopy => {
+            command.args(["--type", mime]);
+        }
+        ClipboardTool::Xclip => {
+            command.args(["-selection", "clipboard", "-t", mime, "-i"]);
+        }
+    }
+    command
+}
+
+/// `file://` URI for an absolute path, percent-encoding everything outside
+/// the unreserved set and `/`.
+fn file_uri(path: &Path) -> String {
+    let mut uri = String::from("file://");
+    for byte in path.to_string_lossy().bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                uri.push(byte as char)
+            }
+            _ => uri.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    uri
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_media_mime_type_by_extension() {
+        assert_eq!(media_mime_type(Path::new("out.GIF")), Some("image/gif"));
+        assert_eq!(media_mime_type(Path::new("out.mp4")), Some("video/mp4"));
+        assert_eq!(media_mime_type(Path::new("frames")), None);
+    }
+
+    #[test]
+    fn test_file_uri_escapes_spaces() {
+        assert_eq!(
+            file_uri(Path::new("/home/me/My Clip #1.mp4")),
+            "file:///home/me/My%20Clip%20%231.mp4"
+        );
+    }
+
+    #[test]
+    fn test_clipboard_command_passes_mime_type() {
+        let args = |tool| {
+            clipboard_command(tool, "image/gif")
+                .get_args()
+                .map(|a| a.to_string_lossy().to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(args(ClipboardTool::WlCopy), ["--type", "image/gif"]);
+        assert_eq!(
+            args(ClipboardTool::Xclip),
+            ["-selection", "clipboard", "-t", "image/gif", "-i"]
+        );
+    }
+}
@@ -0,0 +1,89 @@
+//! Wall-clock burn-in.
+//!
+//! Draws the time of day each frame was recorded with ffmpeg's `drawtext`,
+//! whose `pts` expansion adds the frame time to the wall-clock time of the
+//! first screen frame. The filter runs before speed segments are applied,
+//! while frame times are still recording time, so a freeze holds the clock
+//! and slow motion slows it down.
+
+use grabme_project_model::project::{ClockOverlayConfig, RecordingClockInfo, WebcamCorner};
+
+/// Unix time, in seconds, of the first screen frame: the recording epoch
+/// plus the screen track offset. `None` for recordings without an epoch.
+pub fn clock_start_unix_secs(clock: &RecordingClockInfo, screen_offset_ns: i64) -> Option<f64> {
+    let wall = clock.event_to_wall(screen_offset_ns.max(0) as u64)?;
+    Some(wall.timestamp() as f64 + wall.timestamp_subsec_nanos() as f64 / 1_000_000_000.0)
+}
+
+/// `drawtext` text showing `start_unix_secs` plus the frame time in the
+/// configured format. Option and filtergraph escaping are left to the
+/// caller.
+pub fn clock_text(config: &ClockOverlayConfig, start_unix_secs: f64) -> String {
+    let zone = if config.utc { "gmtime" } else { "localtime" };
+    let mut format = String::with_capacity(config.format.len());
+    for c in config.format.chars() {
+        // Argument separators and the closing brace of the expansion.
+        if matches!(c, '\\' | ':' | '}') {
+            format.push('\\');
+        }
+        format.push(c);
+    }
+    format!("%{{pts:{zone}:{start_unix_secs:.3}:{format}}}")
+}
+
+/// `drawtext` size and position options for a `width`x`height` output.
+pub fn clock_layout(config: &ClockOverlayConfig, height: u32) -> String {
+    let font_size = (height as f64 * config.size_ratio.clamp(0.01, 0.2))
+        .round()
+        .max(8.0) as u32;
+    let margin = (height as f64 * config.margin_ratio.clamp(0.0, 0.2)).round() as u32;
+    let padding = (font_size / 4).max(2);
+    let inset = margin + padding;
+    let (x, y) = match config.corner {
+        WebcamCorner::TopLeft => (inset.to_string(), inset.to_string()),
+        WebcamCorner::TopRight => (format!("w-tw-{inset}"), inset.to_string()),
+        WebcamCorner::BottomLeft => (inset.to_string(), format!("h-th-{inset}")),
+        WebcamCorner::BottomRight => (format!("w-tw-{inset}"), format!("h-th-{inset}")),
+    };
+    format!(
+        "fontsize={font_size}:fontcolor=white:box=1:boxcolor=black@0.55:boxborderw={padding}:x={x}:y={y}"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clock_starts_at_the_first_screen_frame() {
+        let clock = RecordingClockInfo {
+            epoch_wall: "2026-01-02T03:04:05Z".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            clock_start_unix_secs(&clock, 250_000_000),
+            Some(1_767_323_045.25)
+        );
+        assert_eq!(
+            clock_start_unix_secs(&RecordingClockInfo::default(), 0),
+            None
+        );
+    }
+
+    #[test]
+    fn test_clock_text_escapes_the_format() {
+        let config = ClockOverlayConfig {
+            format: "%H:%M:%S".to_string(),
+            utc: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            clock_text(&config, 1_767_323_045.25),
+            "%{pts:gmtime:1767323045.250:%H\\:%M\\:%S}"
+        );
+        assert_eq!(
+            clock_layout(&config, 1080),
+            "fontsize=38:fontcolor=white:box=1:boxcolor=black@0.55:boxborderw=9:x=31:y=h-th-31"
+        );
+    }
+}
@@ -54,6 +54,7 @@ pub struct WebcamOverlay {
 }
 
 /// Compute the composition for each frame in the export.
+#[allow(clippy::too_many_arguments)]
 pub fn compute_compositions(
     timeline: &Timeline,
     smoothed_cursor: &[(u64, f64, f64)],
@@ -62,13 +63,15 @@ pub fn compute_compositions(
     fps: u32,
     duration_secs: f64,
     webcam_config: Option<WebcamConfig>,
+    webcam_size: Option<(u32, u32)>,
 ) -> Vec<FrameComposition> {
     let total_frames = (duration_secs * fps as f64).ceil() as u64;
     let frame_duration_ns = 1_000_000_000u64 / fps as u64;
     let mut compositions = Vec::with_capacity(total_frames as usize);
+    let webcam_config = webcam_config.filter(|cfg| cfg.enabled);
     let webcam_overlay = webcam_config
-        .filter(|cfg| cfg.enabled)
-        .map(|cfg| compute_webcam_overlay(cfg, output_width, output_height));
+        .clone()
+        .map(|cfg| compute_webcam_overlay(cfg, output_width, output_height, webcam_size));
 
     for frame in 0..total_frames {
         let time_secs = frame as f64 / fps as f64;
@@ -86,10 +89,8 @@ pub fn compute_compositions(
         let cursor = if !smoothed_cursor.is_empty() {
             let cursor_pos = CursorSmoother::position_at(smoothed_cursor, time_ns);
             cursor_pos.map(|pos| {
-                let cx = pos.x;
-                let cy = pos.y;
                 // Transform cursor from capture coords to output coords
-                let local = viewport.to_local(cx, cy);
+                let local = viewport.to_local(pos);
                 match local {
                     Some((lx, ly)) => CursorOverlay {
                         x: lx * output_width as f64,
@@ -99,8 +100,10 @@ pub fn compute_compositions(
                     },
                     None => CursorOverlay {
                         // Cursor outside viewport, clamp to edge
-                        x: ((cx - viewport.x) / viewport.w).clamp(0.0, 1.0) * output_width as f64,
-                        y: ((cy - viewport.y) / viewport.h).clamp(0.0, 1.0) * output_height as f64,
+                        x: ((pos.x - viewport.x) / viewport.w).clamp(0.0, 1.0)
+                            * output_width as f64,
+                        y: ((pos.y - viewport.y) / viewport.h).clamp(0.0, 1.0)
+                            * output_height as f64,
                         scale: viewport.zoom_factor(),
                         clicking: false,
                     },
@@ -119,6 +122,28 @@ pub fn compute_compositions(
         });
     }
 
+    if let Some(config) = webcam_config.filter(|cfg| cfg.auto_corner) {
+        let focus: Vec<_> = compositions
+            .iter()
+            .filter_map(|comp| Some((comp.time_secs, comp.cursor.as_ref()?)))
+            .map(|(secs, cursor)| (secs, cursor.x, cursor.y))
+            .collect();
+        let switches =
+            plan_webcam_corners(&config, &focus, output_width, output_height, webcam_size);
+        for comp in &mut compositions {
+            if let Some(webcam) = &mut comp.webcam {
+                (webcam.x, webcam.y) = webcam_position_at(
+                    &config,
+                    &switches,
+                    comp.time_secs,
+                    output_width,
+                    output_height,
+                    (webcam.width, webcam.height),
+                );
+            }
+        }
+    }
+
     compositions
 }
 
@@ -126,30 +151,227 @@ fn compute_webcam_overlay(
     config: WebcamConfig,
     output_width: u32,
     output_height: u32,
+    webcam_size: Option<(u32, u32)>,
 ) -> WebcamOverlay {
-    let size_ratio = config.size_ratio.clamp(0.08, 0.50);
+    let (width, height) = webcam_overlay_size(&config, output_width, output_height, webcam_size);
+    let (width, height) = (width as f64, height as f64);
+    let (x, y) = webcam_corner_origin(
+        &config,
+        config.corner,
+        output_width,
+        output_height,
+        (width, height),
+    );
+
+    WebcamOverlay {
+        x,
+        y,
+        width,
+        height,
+    }
+}
+
+/// Seconds the webcam stays in a corner before it may move again.
+const CORNER_MIN_HOLD_SECS: f64 = 1.5;
+
+/// Focus samples per second considered when planning corner moves.
+const CORNER_SAMPLE_HZ: f64 = 10.0;
+
+/// Points per slide in [`webcam_path_points`].
+const CORNER_SLIDE_STEPS: u32 = 8;
+
+const CORNERS: [WebcamCorner; 4] = [
+    WebcamCorner::TopLeft,
+    WebcamCorner::TopRight,
+    WebcamCorner::BottomLeft,
+    WebcamCorner::BottomRight,
+];
+
+/// The webcam overlay starting to slide to `corner` at `at_secs`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WebcamCornerSwitch {
+    pub at_secs: f64,
+    pub corner: WebcamCorner,
+}
+
+/// Corner moves of the webcam overlay when `auto_corner` is on.
+///
+/// `focus` holds `(secs, x, y)` samples in output pixels, in time order: the
+/// pointer as it appears after the zoom. When the focus comes within a
+/// margin of the overlay, the overlay moves to the corner farthest from it,
+/// and then stays for at least [`CORNER_MIN_HOLD_SECS`]. Without focus
+/// samples the overlay stays in `corner`.
+pub fn plan_webcam_corners(
+    config: &WebcamConfig,
+    focus: &[(f64, f64, f64)],
+    output_width: u32,
+    output_height: u32,
+    webcam_size: Option<(u32, u32)>,
+) -> Vec<WebcamCornerSwitch> {
+    if !config.enabled || !config.auto_corner {
+        return Vec::new();
+    }
+    let (width, height) = webcam_overlay_size(config, output_width, output_height, webcam_size);
+    let size = (width as f64, height as f64);
     let margin_ratio = config.margin_ratio.clamp(0.0, 0.20);
+    let pad_x = output_width as f64 * margin_ratio;
+    let pad_y = output_height as f64 * margin_ratio;
+    let hold_secs = CORNER_MIN_HOLD_SECS.max(config.corner_slide_secs);
+    let origin = |corner| webcam_corner_origin(config, corner, output_width, output_height, size);
+
+    let mut current = config.corner;
+    let mut last_switch = f64::NEG_INFINITY;
+    let mut next_sample = f64::NEG_INFINITY;
+    let mut switches = Vec::new();
+    for &(secs, x, y) in focus {
+        if secs < next_sample || secs - last_switch < hold_secs {
+            continue;
+        }
+        next_sample = secs + 1.0 / CORNER_SAMPLE_HZ;
+
+        let (left, top) = origin(current);
+        let covered = x >= left - pad_x
+            && x <= left + size.0 + pad_x
+            && y >= top - pad_y
+            && y <= top + size.1 + pad_y;
+        if !covered {
+            continue;
+        }
+        let distance = |corner| {
+            let (left, top) = origin(corner);
+            (left + size.0 / 2.0 - x).hypot(top + size.1 / 2.0 - y)
+        };
+        let farthest = CORNERS
+            .into_iter()
+            .max_by(|a, b| distance(*a).total_cmp(&distance(*b)))
+            .unwrap_or(current);
+        if farthest != current {
+            switches.push(WebcamCornerSwitch {
+                at_secs: secs,
+                corner: farthest,
+            });
+            current = farthest;
+            last_switch = secs;
+        }
+    }
+    switches
+}
+
+/// Top-left of the webcam overlay at `time_secs`, easing between corners
+/// over `corner_slide_secs` after each switch.
+pub fn webcam_position_at(
+    config: &WebcamConfig,
+    switches: &[WebcamCornerSwitch],
+    time_secs: f64,
+    output_width: u32,
+    output_height: u32,
+    size: (f64, f64),
+) -> (f64, f64) {
+    let origin = |corner| webcam_corner_origin(config, corner, output_width, output_height, size);
+    let slide_secs = config.corner_slide_secs.max(0.0);
+    let mut from = config.corner;
+    for switch in switches {
+        if time_secs < switch.at_secs {
+            break;
+        }
+        let progress = if slide_secs > 0.0 {
+            (time_secs - switch.at_secs) / slide_secs
+        } else {
+            1.0
+        };
+        if progress < 1.0 {
+            return lerp_point(origin(from), origin(switch.corner), smoothstep(progress));
+        }
+        from = switch.corner;
+    }
+    origin(from)
+}
+
+/// `(secs, x, y)` keyframes of the webcam overlay's top-left corner for
+/// linear interpolation: a hold until each switch, then the eased slide in
+/// [`CORNER_SLIDE_STEPS`] pieces.
+pub fn webcam_path_points(
+    config: &WebcamConfig,
+    switches: &[WebcamCornerSwitch],
+    output_width: u32,
+    output_height: u32,
+    size: (f64, f64),
+) -> Vec<(f64, f64, f64)> {
+    let origin = |corner| webcam_corner_origin(config, corner, output_width, output_height, size);
+    let slide_secs = config.corner_slide_secs.max(0.0);
+    let (x, y) = origin(config.corner);
+    let mut points = vec![(0.0, x, y)];
+    let mut from = config.corner;
+    for switch in switches {
+        let start = origin(from);
+        let end = origin(switch.corner);
+        points.push((switch.at_secs, start.0, start.1));
+        for step in 1..=CORNER_SLIDE_STEPS {
+            let progress = step as f64 / CORNER_SLIDE_STEPS as f64;
+            let (x, y) = lerp_point(start, end, smoothstep(progress));
+            points.push((switch.at_secs + slide_secs * progress, x, y));
+        }
+        from = switch.corner;
+    }
+    points
+}
 
-    let width = even_dimension(output_width as f64 * size_ratio) as f64;
-    let height = even_dimension(output_height as f64 * size_ratio) as f64;
+/// Top-left of an overlay of `size` placed in `corner` with the configured
+/// margin.
+fn webcam_corner_origin(
+    config: &WebcamConfig,
+    corner: WebcamCorner,
+    output_width: u32,
+    output_height: u32,
+    (width, height): (f64, f64),
+) -> (f64, f64) {
+    let margin_ratio = config.margin_ratio.clamp(0.0, 0.20);
     let margin_x = (output_width as f64 * margin_ratio).round();
     let margin_y = (output_height as f64 * margin_ratio).round();
-
-    let (x, y) = match config.corner {
+    let right = output_width as f64 - width - margin_x;
+    let bottom = output_height as f64 - height - margin_y;
+    let (x, y) = match corner {
         WebcamCorner::TopLeft => (margin_x, margin_y),
-        WebcamCorner::TopRight => (output_width as f64 - width - margin_x, margin_y),
-        WebcamCorner::BottomLeft => (margin_x, output_height as f64 - height - margin_y),
-        WebcamCorner::BottomRight => (
-            output_width as f64 - width - margin_x,
-            output_height as f64 - height - margin_y,
-        ),
+        WebcamCorner::TopRight => (right, margin_y),
+        WebcamCorner::BottomLeft => (margin_x, bottom),
+        WebcamCorner::BottomRight => (right, bottom),
     };
+    (x.max(0.0), y.max(0.0))
+}
 
-    WebcamOverlay {
-        x: x.max(0.0),
-        y: y.max(0.0),
-        width,
-        height,
+fn smoothstep(progress: f64) -> f64 {
+    let p = progress.clamp(0.0, 1.0);
+    p * p * (3.0 - 2.0 * p)
+}
+
+fn lerp_point(from: (f64, f64), to: (f64, f64), amount: f64) -> (f64, f64) {
+    (
+        from.0 + (to.0 - from.0) * amount,
+        from.1 + (to.1 - from.1) * amount,
+    )
+}
+
+/// Webcam overlay size in output pixels. The box is `size_ratio` of the
+/// output; a camera of known `webcam_size` fills it at its own aspect ratio,
+/// so a portrait or 4:3 camera gets a narrower overlay rather than bars.
+pub fn webcam_overlay_size(
+    config: &WebcamConfig,
+    output_width: u32,
+    output_height: u32,
+    webcam_size: Option<(u32, u32)>,
+) -> (u32, u32) {
+    let size_ratio = config.size_ratio.clamp(0.08, 0.50);
+    let box_w = output_width as f64 * size_ratio;
+    let box_h = output_height as f64 * size_ratio;
+    match webcam_size {
+        Some((width, height)) if width > 0 && height > 0 => {
+            let scale = (box_w / width as f64).min(box_h / height as f64);
+            (
+                even_dimension(width as f64 * scale),
+                even_dimension(height as f64 * scale),
+            )
+        }
+        _ => (even_dimension(box_w), even_dimension(box_h)),
     }
 }
 
@@ -171,7 +393,7 @@ mod tests {
         let timeline = Timeline::new();
         let cursor = vec![(0u64, 0.0, 0.0), (1_000_000_000u64, 1.0, 1.0)];
 
-        let frames = compute_compositions(&timeline, &cursor, 100, 100, 2, 1.0, None);
+        let frames = compute_compositions(&timeline, &cursor, 100, 100, 2, 1.0, None, None);
         assert_eq!(frames.len(), 2);
 
         let mid = frames[1].cursor.as_ref().unwrap();
@@ -190,6 +412,7 @@ mod tests {
             30,
             1.0,
             Some(WebcamConfig::default()),
+            None,
         );
 
         let webcam = frames[0].webcam.as_ref().expect("webcam overlay present");
@@ -207,10 +430,89 @@ mod tests {
             ..WebcamConfig::default()
         };
 
-        let frames = compute_compositions(&timeline, &[], 1280, 720, 30, 1.0, Some(webcam_cfg));
+        let frames =
+            compute_compositions(&timeline, &[], 1280, 720, 30, 1.0, Some(webcam_cfg), None);
         let webcam = frames[0].webcam.as_ref().expect("webcam overlay present");
 
         assert!((webcam.x - 38.0).abs() < 1.0);
         assert!((webcam.y - 22.0).abs() < 1.0);
     }
+
+    #[test]
+    fn test_webcam_overlay_keeps_the_camera_aspect_ratio() {
+        let config = WebcamConfig::default();
+        // A portrait phone camera keeps the box height and narrows.
+        assert_eq!(
+            webcam_overlay_size(&config, 1920, 1080, Some((1080, 1920))),
+            (146, 258)
+        );
+        // 4:3 in a 16:9 output.
+        assert_eq!(
+            webcam_overlay_size(&config, 1920, 1080, Some((640, 480))),
+            (346, 258)
+        );
+        assert_eq!(webcam_overlay_size(&config, 1920, 1080, None), (460, 258));
+
+        let frames = compute_compositions(
+            &Timeline::new(),
+            &[],
+            1920,
+            1080,
+            30,
+            1.0,
+            Some(config),
+            Some((1080, 1920)),
+        );
+        let webcam = frames[0].webcam.as_ref().expect("webcam overlay present");
+        // Still flush with the bottom-right margin.
+        assert!((webcam.x - (1920.0 - 146.0 - 58.0)).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_webcam_auto_corner_slides_away_from_pointer() {
+        let config = WebcamConfig {
+            auto_corner: true,
+            ..WebcamConfig::default()
+        };
+        let focus = [
+            (0.0, 960.0, 540.0),
+            // Under the bottom-right overlay.
+            (1.0, 1600.0, 900.0),
+            // Under the new top-left spot, but too soon to move again.
+            (1.2, 100.0, 100.0),
+            (3.0, 100.0, 100.0),
+        ];
+        let switches = plan_webcam_corners(&config, &focus, 1920, 1080, None);
+        assert_eq!(
+            switches,
+            vec![
+                WebcamCornerSwitch {
+                    at_secs: 1.0,
+                    corner: WebcamCorner::TopLeft,
+                },
+                WebcamCornerSwitch {
+                    at_secs: 3.0,
+                    corner: WebcamCorner::BottomRight,
+                },
+            ]
+        );
+
+        let size = (460.0, 258.0);
+        let at = |secs| webcam_position_at(&config, &switches, secs, 1920, 1080, size);
+        assert_eq!(at(0.5), (1402.0, 790.0));
+        // Halfway through the 0.4 s slide.
+        let (x, y) = at(1.2);
+        assert!((x - 730.0).abs() < 0.001);
+        assert!((y - 411.0).abs() < 0.001);
+        assert_eq!(at(2.0), (58.0, 32.0));
+
+        let points = webcam_path_points(&config, &switches, 1920, 1080, size);
+        assert_eq!(points.len(), 1 + 2 * (1 + CORNER_SLIDE_STEPS as usize));
+        let (secs, x, y) = *points.last().unwrap();
+        assert!((secs - 3.4).abs() < 0.001);
+        assert_eq!((x, y), (1402.0, 790.0));
+
+        let fixed = WebcamConfig::default();
+        assert!(plan_webcam_corners(&fixed, &focus, 1920, 1080, None).is_empty());
+    }
 }
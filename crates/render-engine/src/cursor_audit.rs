@@ -0,0 +1,379 @@
+//! Cursor click accuracy audit for exported videos.
+//!
+//! Export draws its cursor from pointer samples projected through the
+//! camera, so a wrong projection or timing shows up as a cursor that is
+//! off the thing being clicked. This checks a finished export: at every
+//! recorded press it decodes the exported frame, finds the cursor sprite
+//! by template matching near where the export should have drawn it, and
+//! reports how far the hotspot landed from the recorded click. It is the
+//! export-side counterpart of the drift checks `e2e-cursor-drift` runs on
+//! raw captures.
+
+use std::path::Path;
+use std::process::Command;
+
+use grabme_common::error::{GrabmeError, GrabmeResult};
+use serde::Serialize;
+
+use crate::cursor_probe::{extract_gray_frame, GrayFrame};
+use crate::export::{
+    command_exists, ensure_cursor_icon_file, expected_click_positions, ExpectedClick, ExportJob,
+    CURSOR_HOTSPOT_X, CURSOR_HOTSPOT_Y, CURSOR_ICON_SIZE,
+};
+use crate::probe::probe_media;
+
+/// How far from the expected hotspot the sprite is searched for, in output
+/// pixels.
+pub const DEFAULT_SEARCH_RADIUS_PX: u32 = 48;
+
+/// Sprite pixels at least this opaque are compared; the soft shadow and
+/// anti-aliased edges blend with whatever is underneath.
+const OPAQUE_ALPHA: u8 = 200;
+
+/// Mean gray-level difference over the opaque sprite pixels above which a
+/// position is not the cursor.
+const MAX_MATCH_DIFF: f64 = 40.0;
+
+/// The cursor sprite as export draws it, in grayscale.
+#[derive(Debug, Clone)]
+pub(crate) struct CursorTemplate {
+    size: u32,
+    hotspot: (u32, u32),
+    /// Offset from the sprite's top left and gray level of every opaque
+    /// pixel.
+    opaque: Vec<(u32, u32, u8)>,
+}
+
+impl CursorTemplate {
+    /// Build a template from a `size`x`size` gray + alpha raster.
+    fn from_gray_alpha(size: u32, hotspot: (u32, u32), gray_alpha: &[u8]) -> Self {
+        let opaque = gray_alpha
+            .chunks_exact(2)
+            .enumerate()
+            .filter(|(_, px)| px[1] >= OPAQUE_ALPHA)
+            .map(|(i, px)| (i as u32 % size, i as u32 / size, px[0]))
+            .collect();
+        Self {
+            size,
+            hotspot,
+            opaque,
+        }
+    }
+
+    /// Rasterize the export's cursor icon the way the export filter graph
+    /// scales it.
+    fn load() -> GrabmeResult<Self> {
+        let icon_path = ensure_cursor_icon_file()?;
+        let output = Command::new("ffmpeg")
+            .args(["-hide_banner", "-loglevel", "error", "-i"])
+            .arg(&icon_path)
+            .args(["-frames:v", "1", "-vf"])
+            .arg(format!(
+                "format=rgba,scale={size}:{size}:flags=lanczos,format=ya8",
+                size = CURSOR_ICON_SIZE
+            ))
+            .args(["-f", "rawvideo", "-"])
+            .output()
+            .map_err(|e| GrabmeError::render(format!("Failed to launch ffmpeg: {e}")))?;
+        let expected_len = (CURSOR_ICON_SIZE * CURSOR_ICON_SIZE * 2) as usize;
+        if !output.status.success() || output.stdout.len() != expected_len {
+            return Err(GrabmeError::render(format!(
+                "Failed to rasterize cursor icon {}: {}",
+                icon_path.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(Self::from_gray_alpha(
+            CURSOR_ICON_SIZE,
+            (CURSOR_HOTSPOT_X, CURSOR_HOTSPOT_Y),
+            &output.stdout,
+        ))
+    }
+
+    /// Mean gray-level difference with the sprite's top left at
+    /// (`left`, `top`). `None` when the sprite leaves the frame.
+    fn difference_at(&self, frame: &GrayFrame, left: i64, top: i64) -> Option<f64> {
+        let size = i64::from(self.size);
+        if left < 0
+            || top < 0
+            || left + size > i64::from(frame.width)
+            || top + size > i64::from(frame.height)
+            || self.opaque.is_empty()
+        {
+            return None;
+        }
+        let stride = frame.width as usize;
+        let total: u64 = self
+            .opaque
+            .iter()
+            .map(|&(dx, dy, gray)| {
+                let idx = (top as usize + dy as usize) * stride + left as usize + dx as usize;
+                u64::from(frame.pixels[idx].abs_diff(gray))
+            })
+            .sum();
+        Some(total as f64 / self.opaque.len() as f64)
+    }
+
+    /// Best match within `radius` pixels of the hotspot at `expected`: the
+    /// matched hotspot and its difference. `None` when nothing nearby
+    /// looks like the cursor.
+    fn find(&self, frame: &GrayFrame, expected: (f64, f64), radius: u32) -> Option<CursorMatch> {
+        let left0 = expected.0.round() as i64 - i64::from(self.hotspot.0);
+        let top0 = expected.1.round() as i64 - i64::from(self.hotspot.1);
+        let radius = i64::from(radius);
+        let mut best: Option<(f64, i64, i64)> = None;
+        for dy in -radius..=radius {
+            for dx in -radius..=radius {
+                let Some(diff) = self.difference_at(frame, left0 + dx, top0 + dy) else {
+                    continue;
+                };
+                // Ties go to the position closer to the expected one.
+                let better = best.map_or(true, |(best_diff, bx, by)| {
+                    diff < best_diff || (diff == best_diff && dx * dx + dy * dy < bx * bx + by * by)
+                });
+                if better {
+                    best = Some((diff, dx, dy));
+                }
+            }
+        }
+        let (diff, dx, dy) = best.filter(|(diff, _, _)| *diff <= MAX_MATCH_DIFF)?;
+        Some(CursorMatch {
+            x: (left0 + dx + i64::from(self.hotspot.0)) as f64,
+            y: (top0 + dy + i64::from(self.hotspot.1)) as f64,
+            difference: diff,
+        })
+    }
+}
+
+/// Where the cursor sprite was found in a frame.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct CursorMatch {
+    /// Hotspot of the matched sprite, in output pixels.
+    pub x: f64,
+    pub y: f64,
+    /// Mean gray-level difference to the template; lower is a closer match.
+    pub difference: f64,
+}
+
+/// One recorded press checked against the exported frame.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ClickAudit {
+    /// Where the export should have drawn the cursor.
+    pub expected: ExpectedClick,
+    /// Where the sprite was found, if it was.
+    pub detected: Option<CursorMatch>,
+}
+
+impl ClickAudit {
+    /// Distance between the expected and the found hotspot, in pixels.
+    pub fn error_px(&self) -> Option<f64> {
+        self.detected
+            .map(|found| (found.x - self.expected.x).hypot(found.y - self.expected.y))
+    }
+}
+
+/// Pixel error over the presses where the cursor was found.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ErrorStats {
+    pub mean_px: f64,
+    pub median_px: f64,
+    pub p95_px: f64,
+    pub max_px: f64,
+}
+
+impl ErrorStats {
+    /// Statistics of `errors`; `None` when there are none.
+    pub fn from_errors(errors: &[f64]) -> Option<Self> {
+        if errors.is_empty() {
+            return None;
+        }
+        let mut sorted = errors.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        // Nearest-rank percentile.
+        let percentile = |p: f64| {
+            let rank = (p * sorted.len() as f64).ceil() as usize;
+            sorted[rank.clamp(1, sorted.len()) - 1]
+        };
+        Some(Self {
+            mean_px: sorted.iter().sum::<f64>() / sorted.len() as f64,
+            median_px: percentile(0.5),
+            p95_px: percentile(0.95),
+            max_px: sorted[sorted.len() - 1],
+        })
+    }
+}
+
+/// Result of auditing an export's cursor at the recorded presses.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CursorAuditReport {
+    /// Size of the audited video.
+    pub width: u32,
+    pub height: u32,
+    /// Every press inside the exported range, in order.
+    pub clicks: Vec<ClickAudit>,
+    /// Error over the presses where the cursor was found.
+    pub stats: Option<ErrorStats>,
+}
+
+impl CursorAuditReport {
+    fn new(width: u32, height: u32, clicks: Vec<ClickAudit>) -> Self {
+        let errors: Vec<f64> = clicks.iter().filter_map(ClickAudit::error_px).collect();
+        Self {
+            width,
+            height,
+            stats: ErrorStats::from_errors(&errors),
+            clicks,
+        }
+    }
+
+    /// Presses where the cursor sprite was found.
+    pub fn detected(&self) -> usize {
+        self.clicks.iter().filter(|c| c.detected.is_some()).count()
+    }
+}
+
+/// Audit `export_path`, rendered from `job`, at every recorded press.
+/// Searches `search_radius_px` output pixels around each expected hotspot.
+/// An export of a different size than `job.config` (a draft, say) has the
+/// expected positions scaled to fit.
+pub fn audit_exported_cursor(
+    job: &ExportJob,
+    export_path: &Path,
+    search_radius_px: u32,
+) -> GrabmeResult<CursorAuditReport> {
+    if !command_exists("ffmpeg") {
+        return Err(GrabmeError::unsupported(
+            "ffmpeg is required to audit an export",
+        ));
+    }
+    let (width, height) = probe_media(export_path)?.video_size().ok_or_else(|| {
+        GrabmeError::render(format!(
+            "Could not read the video size of {}",
+            export_path.display()
+        ))
+    })?;
+    let scale_x = width as f64 / job.config.width.max(1) as f64;
+    let scale_y = height as f64 / job.config.height.max(1) as f64;
+    let template = CursorTemplate::load()?;
+
+    let mut clicks = Vec::new();
+    for mut expected in expected_click_positions(job)? {
+        expected.x *= scale_x;
+        expected.y *= scale_y;
+        let frame = extract_gray_frame(export_path, expected.output_secs, width, height)?;
+        let detected = template.find(&frame, (expected.x, expected.y), search_radius_px);
+        if detected.is_none() {
+            tracing::debug!(
+                output_secs = expected.output_secs,
+                "No cursor found near a recorded press"
+            );
+        }
+        clicks.push(ClickAudit { expected, detected });
+    }
+    Ok(CursorAuditReport::new(width, height, clicks))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPRITE: u32 = 16;
+
+    /// White arrow with a black outline and a transparent surround.
+    fn arrow_template() -> CursorTemplate {
+        let mut gray_alpha = vec![0u8; (SPRITE * SPRITE * 2) as usize];
+        for row in 2..14 {
+            for col in 2..=(row / 2 + 2) {
+                let edge = col == 2 || col == row / 2 + 2 || row == 13;
+                let idx = ((row * SPRITE + col) * 2) as usize;
+                gray_alpha[idx] = if edge { 0 } else { 255 };
+                gray_alpha[idx + 1] = 255;
+            }
+        }
+        CursorTemplate::from_gray_alpha(SPRITE, (2, 2), &gray_alpha)
+    }
+
+    /// Textured mid-gray frame.
+    fn background(width: u32, height: u32) -> GrayFrame {
+        GrayFrame {
+            width,
+            height,
+            pixels: (0..width * height)
+                .map(|i| ((i * 7 + (i / width) * 13) % 60 + 100) as u8)
+                .collect(),
+        }
+    }
+
+    /// Draw `template` with its hotspot at (`x`, `y`).
+    fn draw(frame: &mut GrayFrame, template: &CursorTemplate, x: u32, y: u32) {
+        for &(dx, dy, gray) in &template.opaque {
+            let px = x - template.hotspot.0 + dx;
+            let py = y - template.hotspot.1 + dy;
+            frame.pixels[(py * frame.width + px) as usize] = gray;
+        }
+    }
+
+    #[test]
+    fn test_template_finds_drawn_cursor_near_expected_position() {
+        let template = arrow_template();
+        let mut frame = background(200, 120);
+        draw(&mut frame, &template, 90, 50);
+
+        let found = template.find(&frame, (84.0, 57.0), 12).unwrap();
+        assert_eq!((found.x, found.y), (90.0, 50.0));
+        assert_eq!(found.difference, 0.0);
+    }
+
+    #[test]
+    fn test_cursor_outside_search_radius_is_a_miss() {
+        let template = arrow_template();
+        let mut frame = background(200, 120);
+        draw(&mut frame, &template, 150, 80);
+
+        assert!(template.find(&frame, (40.0, 30.0), 12).is_none());
+        assert!(template
+            .find(&background(200, 120), (150.0, 80.0), 12)
+            .is_none());
+    }
+
+    #[test]
+    fn test_error_stats_use_nearest_rank_percentiles() {
+        let errors: Vec<f64> = (1..=20).map(f64::from).collect();
+        let stats = ErrorStats::from_errors(&errors).unwrap();
+        assert_eq!(stats.mean_px, 10.5);
+        assert_eq!(stats.median_px, 10.0);
+        assert_eq!(stats.p95_px, 19.0);
+        assert_eq!(stats.max_px, 20.0);
+        assert!(ErrorStats::from_errors(&[]).is_none());
+    }
+
+    #[test]
+    fn test_report_counts_only_detected_presses() {
+        let expected = ExpectedClick {
+            timeline_secs: 1.0,
+            output_secs: 1.0,
+            x: 10.0,
+            y: 10.0,
+        };
+        let report = CursorAuditReport::new(
+            100,
+            100,
+            vec![
+                ClickAudit {
+                    expected,
+                    detected: Some(CursorMatch {
+                        x: 13.0,
+                        y: 14.0,
+                        difference: 2.0,
+                    }),
+                },
+                ClickAudit {
+                    expected,
+                    detected: None,
+                },
+            ],
+        );
+        assert_eq!(report.detected(), 1);
+        assert_eq!(report.stats.unwrap().max_px, 5.0);
+    }
+}
@@ -0,0 +1,405 @@
+//! Captured-cursor detection.
+//!
+//! X11 captures record the system cursor unless told otherwise, and some
+//! compositors draw it into portal streams even when asked for metadata
+//! only. Export would then draw its synthetic cursor on top of the real
+//! one. This looks at a few moments where the pointer was resting: if the
+//! frame there differs from a frame where the pointer was elsewhere by the
+//! same small shape every time, the cursor is in the frames.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::Command;
+
+use grabme_common::error::{GrabmeError, GrabmeResult};
+
+use crate::export::command_exists;
+
+/// At most this many resting positions are compared.
+const MAX_PROBE_SAMPLES: usize = 8;
+
+/// Fewer comparisons than this can't tell a cursor from coincidence.
+const MIN_PROBE_SAMPLES: usize = 3;
+
+/// The pointer must stay put this long for its frame to be sampled.
+const REST_NS: u64 = 200_000_000;
+
+/// Movement within this many pixels still counts as resting.
+const REST_RADIUS_PX: f64 = 2.0;
+
+/// Side of the compared patch in logical pixels; large enough for common
+/// cursor themes.
+const PATCH_LOGICAL_PX: f64 = 32.0;
+
+/// Gray-level difference at which a pixel counts as changed.
+const CHANGE_THRESHOLD: u8 = 40;
+
+/// Share of changed patch pixels a cursor covers. Less is noise, more is
+/// the content under the pointer changing.
+const CURSOR_CHANGED_FRACTION: std::ops::RangeInclusive<f64> = 0.03..=0.5;
+
+/// Share of comparisons that must find a cursor-sized change.
+const MIN_MATCH_SHARE: f64 = 0.6;
+
+/// Share of each change that must fall on the shape the changes agree on.
+const MIN_AGREEMENT: f64 = 0.5;
+
+/// A pointer position in source pixels at timeline time (ns).
+pub(crate) type PixelPoint = (u64, f64, f64);
+
+/// Result of probing a screen recording for a captured cursor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CursorProbe {
+    /// Resting positions compared against a frame with the pointer elsewhere.
+    pub samples: usize,
+    /// Comparisons that found a cursor-sized change at the pointer.
+    pub matches: usize,
+    /// How much the changes share one shape [0.0, 1.0].
+    pub agreement: f64,
+}
+
+impl CursorProbe {
+    /// Whether the recording shows the system cursor.
+    pub fn cursor_visible(&self) -> bool {
+        self.samples >= MIN_PROBE_SAMPLES
+            && self.matches as f64 >= self.samples as f64 * MIN_MATCH_SHARE
+            && self.agreement >= MIN_AGREEMENT
+    }
+}
+
+/// Look for the system cursor in `screen_path`, a `width`x`height` video,
+/// at the resting positions of `pointer`. `scale_factor` sizes the compared
+/// patch to the cursor. `Ok(None)` when the recording has too few rests to
+/// tell.
+pub(crate) fn probe_captured_cursor(
+    screen_path: &Path,
+    screen_offset_ns: i64,
+    (width, height): (u32, u32),
+    pointer: &[PixelPoint],
+    scale_factor: f64,
+) -> GrabmeResult<Option<CursorProbe>> {
+    if !command_exists("ffmpeg") {
+        return Err(GrabmeError::unsupported(
+            "Cursor detection requires ffmpeg in PATH",
+        ));
+    }
+
+    let patch_size = (PATCH_LOGICAL_PX * scale_factor.max(1.0)).round() as u32;
+    let rests = rest_points(pointer);
+    let pairs = pair_rests(&rests, patch_size as f64 * 1.5);
+    if pairs.len() < MIN_PROBE_SAMPLES {
+        return Ok(None);
+    }
+
+    let mut frames = BTreeMap::new();
+    for &index in pairs.iter().flat_map(|(at, away)| [at, away]) {
+        if frames.contains_key(&index) {
+            continue;
+        }
+        let media_ns = rests[index].0 as i64 - screen_offset_ns;
+        if media_ns < 0 {
+            continue;
+        }
+        match extract_gray_frame(
+            screen_path,
+            media_ns as f64 / 1_000_000_000.0,
+            width,
+            height,
+        ) {
+            Ok(frame) => {
+                frames.insert(index, frame);
+            }
+            Err(e) => tracing::debug!(error = %e, "Skipping cursor probe frame"),
+        }
+    }
+
+    let patches: Vec<(Vec<u8>, Vec<u8>)> = pairs
+        .iter()
+        .filter_map(|(at, away)| {
+            let (_, x, y) = rests[*at];
+            Some((
+                frames.get(at)?.patch(x, y, patch_size)?,
+                frames.get(away)?.patch(x, y, patch_size)?,
+            ))
+        })
+        .collect();
+    if patches.len() < MIN_PROBE_SAMPLES {
+        return Ok(None);
+    }
+
+    let probe = score_patches(&patches);
+    tracing::info!(
+        samples = probe.samples,
+        matches = probe.matches,
+        agreement = probe.agreement,
+        visible = probe.cursor_visible(),
+        "Probed screen recording for a captured cursor"
+    );
+    Ok(Some(probe))
+}
+
+/// Where the pointer rested for at least [`REST_NS`]: the middle of each
+/// rest, at its position. The pointer holds still until the first sample
+/// that moved away.
+fn rest_points(pointer: &[PixelPoint]) -> Vec<PixelPoint> {
+    let mut rests = Vec::new();
+    let mut start = 0;
+    while start < pointer.len() {
+        let (t0, x0, y0) = pointer[start];
+        let mut end = start + 1;
+        while end < pointer.len()
+            && (pointer[end].1 - x0).hypot(pointer[end].2 - y0) <= REST_RADIUS_PX
+        {
+            end += 1;
+        }
+        if let Some(&(t1, _, _)) = pointer.get(end) {
+            if t1.saturating_sub(t0) >= REST_NS {
+                rests.push((t0 + (t1 - t0) / 2, x0, y0));
+            }
+        }
+        start = end;
+    }
+    rests
+}
+
+/// Pick up to [`MAX_PROBE_SAMPLES`] rests spread over the recording, each
+/// paired with the nearest-in-time rest at least `min_distance_px` away,
+/// whose frame shows the same spot without the pointer.
+fn pair_rests(rests: &[PixelPoint], min_distance_px: f64) -> Vec<(usize, usize)> {
+    let stride = (rests.len() as f64 / MAX_PROBE_SAMPLES as f64).max(1.0);
+    (0..MAX_PROBE_SAMPLES)
+        .map(|k| (k as f64 * stride) as usize)
+        .take_while(|&index| index < rests.len())
+        .filter_map(|index| {
+            let (t, x, y) = rests[index];
+            rests
+                .iter()
+                .enumerate()
+                .filter(|(_, &(_, ox, oy))| (ox - x).hypot(oy - y) >= min_distance_px)
+                .min_by_key(|(_, &(ot, _, _))| ot.abs_diff(t))
+                .map(|(other, _)| (index, other))
+        })
+        .collect()
+}
+
+/// An 8-bit grayscale video frame.
+#[derive(Debug, Clone)]
+pub(crate) struct GrayFrame {
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    pub(crate) pixels: Vec<u8>,
+}
+
+impl GrayFrame {
+    /// Square patch of side `size` around a cursor hotspot at (`x`, `y`).
+    /// Cursors extend right and down from their hotspot, so it sits a
+    /// quarter in from the top left. `None` when the patch leaves the frame.
+    fn patch(&self, x: f64, y: f64, size: u32) -> Option<Vec<u8>> {
+        let left = x.round() as i64 - i64::from(size / 4);
+        let top = y.round() as i64 - i64::from(size / 4);
+        if left < 0
+            || top < 0
+            || left + i64::from(size) > i64::from(self.width)
+            || top + i64::from(size) > i64::from(self.height)
+        {
+            return None;
+        }
+        let (left, top, size) = (left as usize, top as usize, size as usize);
+        let stride = self.width as usize;
+        Some(
+            (top..top + size)
+                .flat_map(|row| &self.pixels[row * stride + left..row * stride + left + size])
+                .copied()
+                .collect(),
+        )
+    }
+}
+
+/// Decode the frame at `media_secs` of a `width`x`height` video as
+/// grayscale.
+pub(crate) fn extract_gray_frame(
+    path: &Path,
+    media_secs: f64,
+    width: u32,
+    height: u32,
+) -> GrabmeResult<GrayFrame> {
+    let output = Command::new("ffmpeg")
+        .args(["-hide_banner", "-loglevel", "error", "-ss"])
+        .arg(format!("{media_secs:.3}"))
+        .arg("-i")
+        .arg(path)
+        .args([
+            "-map",
+            "0:v:0",
+            "-frames:v",
+            "1",
+            "-vf",
+            "format=gray",
+            "-f",
+            "rawvideo",
+            "-",
+        ])
+        .output()
+        .map_err(|e| GrabmeError::render(format!("Failed to launch ffmpeg: {e}")))?;
+    if !output.status.success() {
+        return Err(GrabmeError::render(format!(
+            "Failed to extract frame at {media_secs:.3}s of {}: {}",
+            path.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    if output.stdout.len() != width as usize * height as usize {
+        return Err(GrabmeError::render(format!(
+            "Frame at {media_secs:.3}s of {} is not {width}x{height}",
+            path.display()
+        )));
+    }
+    Ok(GrayFrame {
+        width,
+        height,
+        pixels: output.stdout,
+    })
+}
+
+/// Compare patches at the pointer with the same spot while the pointer was
+/// away.
+fn score_patches(pairs: &[(Vec<u8>, Vec<u8>)]) -> CursorProbe {
+    let masks: Vec<Vec<bool>> = pairs
+        .iter()
+        .map(|(at, away)| {
+            at.iter()
+                .zip(away)
+                .map(|(a, b)| a.abs_diff(*b) > CHANGE_THRESHOLD)
+                .collect()
+        })
+        .collect();
+    let cursor_like: Vec<&Vec<bool>> = masks
+        .iter()
+        .filter(|mask| {
+            let changed = mask.iter().filter(|&&changed| changed).count();
+            CURSOR_CHANGED_FRACTION.contains(&(changed as f64 / mask.len().max(1) as f64))
+        })
+        .collect();
+
+    // The shape most changes agree on, and how much of each change it covers.
+    let agreement = match cursor_like.first() {
+        None => 0.0,
+        Some(first) => {
+            let consensus: Vec<bool> = (0..first.len())
+                .map(|i| cursor_like.iter().filter(|mask| mask[i]).count() * 2 > cursor_like.len())
+                .collect();
+            cursor_like
+                .iter()
+                .map(|mask| {
+                    let changed = mask.iter().filter(|&&changed| changed).count();
+                    let shared = mask
+                        .iter()
+                        .zip(&consensus)
+                        .filter(|(&changed, &common)| changed && common)
+                        .count();
+                    shared as f64 / changed as f64
+                })
+                .sum::<f64>()
+                / cursor_like.len() as f64
+        }
+    };
+
+    CursorProbe {
+        samples: pairs.len(),
+        matches: cursor_like.len(),
+        agreement,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZE: usize = 32;
+
+    /// Textured background that differs per sample.
+    fn background(seed: usize) -> Vec<u8> {
+        (0..SIZE * SIZE)
+            .map(|i| ((i * 7 + seed * 31 + (i / SIZE) * 13) % 60 + 100) as u8)
+            .collect()
+    }
+
+    /// Draw an arrow cursor with its hotspot a quarter into the patch.
+    fn with_cursor(mut patch: Vec<u8>) -> Vec<u8> {
+        let hotspot = SIZE / 4;
+        for row in 0..16 {
+            for col in 0..=row / 2 + 1 {
+                patch[(hotspot + row) * SIZE + hotspot + col] = 255;
+            }
+        }
+        patch
+    }
+
+    #[test]
+    fn test_consistent_cursor_shape_is_detected() {
+        let pairs: Vec<_> = (0..6)
+            .map(|seed| (with_cursor(background(seed)), background(seed)))
+            .collect();
+        let probe = score_patches(&pairs);
+        assert_eq!(probe.matches, 6);
+        assert!(probe.agreement > 0.99);
+        assert!(probe.cursor_visible());
+
+        let pairs: Vec<_> = (0..6)
+            .map(|seed| (background(seed), background(seed)))
+            .collect();
+        assert!(!score_patches(&pairs).cursor_visible());
+    }
+
+    #[test]
+    fn test_unrelated_content_changes_are_not_a_cursor() {
+        // A different small block lights up in each comparison.
+        let pairs: Vec<_> = (0..6)
+            .map(|seed| {
+                let away = background(seed);
+                let mut at = away.clone();
+                for row in 0..8 {
+                    for col in 0..8 {
+                        at[(seed * 4 + row) * SIZE + (seed * 4 + col) % SIZE] = 255;
+                    }
+                }
+                (at, away)
+            })
+            .collect();
+        let probe = score_patches(&pairs);
+        assert_eq!(probe.matches, 6);
+        assert!(!probe.cursor_visible());
+    }
+
+    #[test]
+    fn test_rests_are_paired_with_distant_rests() {
+        let ms = 1_000_000;
+        let pointer = vec![
+            (0, 100.0, 100.0),
+            (100 * ms, 101.0, 100.0),
+            (500 * ms, 400.0, 300.0),
+            (550 * ms, 420.0, 300.0),
+            (1_000 * ms, 100.0, 120.0),
+            (1_500 * ms, 800.0, 600.0),
+        ];
+        let rests = rest_points(&pointer);
+        assert_eq!(
+            rests,
+            vec![
+                (250 * ms, 100.0, 100.0),
+                (775 * ms, 420.0, 300.0),
+                (1_250 * ms, 100.0, 120.0),
+            ]
+        );
+        assert_eq!(pair_rests(&rests, 48.0), vec![(0, 1), (1, 2), (2, 1)]);
+
+        let frame = GrayFrame {
+            width: 64,
+            height: 64,
+            pixels: (0..64 * 64).map(|i| (i % 251) as u8).collect(),
+        };
+        assert_eq!(frame.patch(8.0, 8.0, 32).unwrap()[0], 0);
+        assert!(frame.patch(4.0, 8.0, 32).is_none());
+        assert!(frame.patch(41.0, 8.0, 32).is_none());
+    }
+}
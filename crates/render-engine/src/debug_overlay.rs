@@ -0,0 +1,381 @@
+//! Diagnostic overlay for verification exports.
+//!
+//! `grabme export --debug-overlay` renders the whole screen instead of
+//! following the camera, and draws on top of it what the export would have
+//! done: the timeline viewport as an outline, the raw and smoothed cursor
+//! as dots, markers for clicks, keys and scrolls, a timecode, and how
+//! pointer coordinates were mapped onto the screen. Drift and zoom bugs
+//! can then be filed with a video that shows where the numbers went wrong.
+//!
+//! Everything is one ASS script in the pixel space of the (pre-cropped)
+//! screen source.
+
+use grabme_processing_core::cursor_smooth::CursorSmoother;
+use grabme_project_model::event::{ButtonState, ColorScheme, EventKind, InputEvent, MouseButton};
+use grabme_project_model::timeline::Timeline;
+
+use crate::subtitles::{ass_drawing_header, format_ass_time};
+
+/// Highest rate the moving layers are sampled at.
+const MAX_SAMPLE_HZ: u32 = 30;
+
+/// Timecode refresh interval.
+const TIMECODE_STEP_SECS: f64 = 0.1;
+
+/// How long an event marker stays on screen.
+const MARKER_SECS: f64 = 0.8;
+
+/// Scrolls closer together than this share one marker.
+const SCROLL_MERGE_SECS: f64 = 0.25;
+
+const VIEWPORT_COLOR: &str = "&H0000D7FF";
+const RAW_CURSOR_COLOR: &str = "&H003030FF";
+const SMOOTHED_CURSOR_COLOR: &str = "&H0050E050";
+const MARKER_COLOR: &str = "&H00FFC040";
+
+/// A labelled moment in the recording.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DebugMarker {
+    /// Recording time in seconds.
+    pub t_secs: f64,
+    /// Text shown in the marker list.
+    pub label: String,
+    /// Normalized screen position to ring, if the event has one.
+    pub position: Option<(f64, f64)>,
+}
+
+/// Inputs for [`build_debug_overlay_ass`].
+#[derive(Debug, Clone, Copy)]
+pub struct DebugOverlay<'a> {
+    /// Timeline whose viewport is outlined.
+    pub timeline: &'a Timeline,
+    /// Unsmoothed cursor samples, normalized to the screen.
+    pub raw_cursor: &'a [(u64, f64, f64)],
+    /// Smoothed cursor samples, normalized to the screen.
+    pub smoothed_cursor: &'a [(u64, f64, f64)],
+    pub markers: &'a [DebugMarker],
+    /// Lines shown in the top-right corner for the whole video.
+    pub annotation: &'a [String],
+    /// Last recording second to draw.
+    pub end_secs: f64,
+    /// Export frame rate, for the frame counter and sampling.
+    pub fps: u32,
+}
+
+/// Markers for button presses and releases, key presses, scrolls, and
+/// theme or brightness changes.
+/// `project` maps recorded coordinates onto the screen.
+pub fn event_markers(
+    events: &[InputEvent],
+    project: impl Fn(f64, f64) -> Option<(f64, f64)>,
+) -> Vec<DebugMarker> {
+    let mut markers: Vec<DebugMarker> = Vec::new();
+    let mut last_scroll_secs = f64::NEG_INFINITY;
+
+    for event in events {
+        let t_secs = event.timestamp_secs();
+        let (label, position) = match &event.kind {
+            EventKind::Click {
+                button,
+                state,
+                x,
+                y,
+            } => (
+                format!("{} {}", button_name(*button), state_name(*state)),
+                project(*x, *y),
+            ),
+            EventKind::Key {
+                code,
+                state: ButtonState::Down,
+            } => (format!("key {code}"), None),
+            EventKind::Scroll { dx, dy, x, y, .. } => {
+                let merged = t_secs - last_scroll_secs < SCROLL_MERGE_SECS;
+                last_scroll_secs = t_secs;
+                if merged {
+                    continue;
+                }
+                (format!("scroll {dx:+.1},{dy:+.1}"), project(*x, *y))
+            }
+            EventKind::ColorScheme { scheme } => (format!("theme {}", scheme_name(*scheme)), None),
+            EventKind::Brightness { level } => (format!("brightness {:.0}%", level * 100.0), None),
+            _ => continue,
+        };
+        markers.push(DebugMarker {
+            t_secs,
+            label: format!("{t_secs:.3}s {label}"),
+            position,
+        });
+    }
+
+    markers
+}
+
+/// Build the overlay script for a `width`x`height` source.
+pub fn build_debug_overlay_ass(overlay: &DebugOverlay<'_>, width: u32, height: u32) -> String {
+    let mut script = ass_drawing_header(width, height);
+    let end_secs = overlay.end_secs.max(0.0);
+    let fps = overlay.fps.max(1);
+    let line = (height as f64 / 360.0).max(1.0);
+    let font_size = (height as f64 / 40.0).round().max(10.0);
+    let margin = (font_size / 2.0).round();
+    let px = |x: f64, y: f64| {
+        (
+            (x * width as f64).round() as i64,
+            (y * height as f64).round() as i64,
+        )
+    };
+
+    // Viewport outline. Consecutive samples that round to the same
+    // rectangle become one event, so holds cost a single line.
+    let step = 1.0 / fps.min(MAX_SAMPLE_HZ) as f64;
+    let times: Vec<f64> = (0..)
+        .map(|i| i as f64 * step)
+        .take_while(|t| *t < end_secs)
+        .collect();
+    let rects = times.iter().map(|t| {
+        let vp = overlay.timeline.viewport_at(*t);
+        let (x0, y0) = px(vp.x, vp.y);
+        let (x1, y1) = px(vp.x + vp.w, vp.y + vp.h);
+        Some(format!(
+            "{{\\an7\\pos(0,0)\\bord{line:.1}\\shad0\\1a&HFF&\\3c{VIEWPORT_COLOR}&\\p1}}m {x0} {y0} l {x1} {y0} l {x1} {y1} l {x0} {y1} l {x0} {y0}{{\\p0}}"
+        ))
+    });
+    push_coalesced(&mut script, 1, &times, rects, end_secs);
+
+    // Cursor dots: raw underneath, smoothed on top.
+    let radius = (line * 4.0).round() as i64;
+    for (samples, color, layer) in [
+        (overlay.raw_cursor, RAW_CURSOR_COLOR, 2),
+        (overlay.smoothed_cursor, SMOOTHED_CURSOR_COLOR, 3),
+    ] {
+        let dots = times.iter().map(|t| {
+            let pos = CursorSmoother::position_at(samples, (t * 1e9).round() as u64)?;
+            let (x, y) = px(pos.x, pos.y);
+            Some(format!(
+                "{{\\an7\\pos(0,0)\\bord{:.1}\\shad0\\1c{color}&\\3c&H00000000&\\p1}}{}{{\\p0}}",
+                line / 2.0,
+                dot_drawing(x, y, radius)
+            ))
+        });
+        push_coalesced(&mut script, layer, &times, dots, end_secs);
+    }
+
+    // Event markers: a ring where the event happened and a line in the
+    // bottom-left list. Unpositioned lines stack instead of overlapping.
+    for marker in overlay.markers {
+        if marker.t_secs > end_secs {
+            break;
+        }
+        let start = format_ass_time(marker.t_secs);
+        let end = format_ass_time((marker.t_secs + MARKER_SECS).min(end_secs));
+        if let Some((x, y)) = marker.position {
+            let (x, y) = px(x, y);
+            script.push_str(&format!(
+                "Dialogue: 4,{start},{end},Default,,0,0,0,,{{\\an7\\pos(0,0)\\bord{line:.1}\\shad0\\1a&HFF&\\3c{MARKER_COLOR}&\\p1}}{}{{\\p0}}\n",
+                dot_drawing(x, y, radius * 3)
+            ));
+        }
+        script.push_str(&format!(
+            "Dialogue: 5,{start},{end},Default,,{margin},{margin},{margin},,{{\\an1\\fs{font_size}\\bord2\\1c{MARKER_COLOR}&}}{}\n",
+            escape_text(&marker.label)
+        ));
+    }
+
+    // Timecode and frame counter.
+    for t in (0..)
+        .map(|i| i as f64 * TIMECODE_STEP_SECS)
+        .take_while(|t| *t < end_secs)
+    {
+        let next = (t + TIMECODE_STEP_SECS).min(end_secs);
+        script.push_str(&format!(
+            "Dialogue: 6,{},{},Default,,0,0,0,,{{\\an7\\pos({margin},{margin})\\fs{font_size}\\bord2}}{} · frame {}\n",
+            format_ass_time(t),
+            format_ass_time(next),
+            format_ass_time(t),
+            (t * fps as f64 + 1e-6).floor() as u64
+        ));
+    }
+
+    if !overlay.annotation.is_empty() {
+        let text = overlay
+            .annotation
+            .iter()
+            .map(|line| escape_text(line))
+            .collect::<Vec<_>>()
+            .join("\\N");
+        script.push_str(&format!(
+            "Dialogue: 6,{},{},Default,,0,0,0,,{{\\an9\\pos({},{margin})\\fs{}\\bord2}}{text}\n",
+            format_ass_time(0.0),
+            format_ass_time(end_secs),
+            width as f64 - margin,
+            (font_size * 0.8).round(),
+        ));
+    }
+
+    script
+}
+
+/// Write one Dialogue line per run of equal `texts`, each sample lasting
+/// until the next sample time (the last one until `end_secs`).
+fn push_coalesced(
+    script: &mut String,
+    layer: u32,
+    times: &[f64],
+    texts: impl Iterator<Item = Option<String>>,
+    end_secs: f64,
+) {
+    let mut open: Option<(f64, String)> = None;
+    let flush = |script: &mut String, run: Option<(f64, String)>, until: f64| {
+        if let Some((start, text)) = run {
+            if until > start {
+                script.push_str(&format!(
+                    "Dialogue: {layer},{},{},Default,,0,0,0,,{text}\n",
+                    format_ass_time(start),
+                    format_ass_time(until)
+                ));
+            }
+        }
+    };
+
+    for (t, text) in times.iter().zip(texts) {
+        if open.as_ref().map(|(_, current)| current) == text.as_ref() {
+            continue;
+        }
+        flush(script, open.take(), *t);
+        open = text.map(|text| (*t, text));
+    }
+    flush(script, open, end_secs);
+}
+
+/// A filled circle of radius `r` around `(cx, cy)`, as four Bézier arcs.
+pub(crate) fn dot_drawing(cx: i64, cy: i64, r: i64) -> String {
+    // Control-point offset for a quarter circle.
+    let k = (r as f64 * 0.5523).round() as i64;
+    format!(
+        "m {cx} {top} b {r1} {top} {right} {k1} {right} {cy} b {right} {k2} {r1} {bottom} {cx} {bottom} b {l1} {bottom} {left} {k2} {left} {cy} b {left} {k1} {l1} {top} {cx} {top}",
+        top = cy - r,
+        bottom = cy + r,
+        left = cx - r,
+        right = cx + r,
+        r1 = cx + k,
+        l1 = cx - k,
+        k1 = cy - k,
+        k2 = cy + k,
+    )
+}
+
+fn escape_text(text: &str) -> String {
+    text.replace('{', "(").replace('}', ")").replace('\n', " ")
+}
+
+fn button_name(button: MouseButton) -> String {
+    match button {
+        MouseButton::Left => "left".to_string(),
+        MouseButton::Right => "right".to_string(),
+        MouseButton::Middle => "middle".to_string(),
+        other => format!("{other:?}").to_lowercase(),
+    }
+}
+
+fn scheme_name(scheme: ColorScheme) -> &'static str {
+    match scheme {
+        ColorScheme::Dark => "dark",
+        ColorScheme::Light => "light",
+        ColorScheme::NoPreference => "default",
+    }
+}
+
+fn state_name(state: ButtonState) -> &'static str {
+    match state {
+        ButtonState::Down => "down",
+        ButtonState::Up => "up",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use grabme_project_model::event::ScrollSource;
+    use grabme_project_model::viewport::Viewport;
+
+    #[test]
+    fn test_event_markers_merge_scrolls_and_skip_key_releases() {
+        let scroll =
+            |ms: u64| InputEvent::scroll(ms * 1_000_000, 0.0, 1.0, ScrollSource::Wheel, 0.5, 0.5);
+        let events = vec![
+            InputEvent::click(0, MouseButton::Left, ButtonState::Down, 0.25, 0.5),
+            InputEvent::key(100_000_000, "KeyA", ButtonState::Down),
+            InputEvent::key(150_000_000, "KeyA", ButtonState::Up),
+            scroll(1000),
+            scroll(1100),
+            scroll(2000),
+            InputEvent::color_scheme(2_500_000_000, ColorScheme::Dark),
+            InputEvent::brightness(3_000_000_000, 0.4),
+        ];
+        let markers = event_markers(&events, |x, y| Some((x * 2.0, y)));
+
+        let labels: Vec<&str> = markers.iter().map(|m| m.label.as_str()).collect();
+        assert_eq!(
+            labels,
+            [
+                "0.000s left down",
+                "0.100s key KeyA",
+                "1.000s scroll +0.0,+1.0",
+                "2.000s scroll +0.0,+1.0",
+                "2.500s theme dark",
+                "3.000s brightness 40%"
+            ]
+        );
+        assert_eq!(markers[0].position, Some((0.5, 0.5)));
+        assert_eq!(markers[1].position, None);
+    }
+
+    #[test]
+    fn test_static_viewport_is_one_event() {
+        let mut timeline = Timeline::new();
+        timeline.keyframes[0].viewport = Viewport::new(0.25, 0.25, 0.5, 0.5);
+        let overlay = DebugOverlay {
+            timeline: &timeline,
+            raw_cursor: &[],
+            smoothed_cursor: &[],
+            markers: &[],
+            annotation: &["projection: capture_normalized".to_string()],
+            end_secs: 2.0,
+            fps: 30,
+        };
+        let ass = build_debug_overlay_ass(&overlay, 1920, 1080);
+
+        let outlines: Vec<&str> = ass
+            .lines()
+            .filter(|line| line.starts_with("Dialogue: 1,"))
+            .collect();
+        assert_eq!(outlines.len(), 1);
+        assert!(outlines[0].starts_with("Dialogue: 1,0:00:00.00,0:00:02.00,"));
+        assert!(outlines[0].contains("m 480 270 l 1440 270 l 1440 810 l 480 810"));
+        assert!(ass.contains("0:00:01.50 · frame 45"));
+        assert!(ass.contains("projection: capture_normalized"));
+    }
+
+    #[test]
+    fn test_cursor_dots_follow_samples() {
+        let timeline = Timeline::new();
+        let smoothed = [(0, 0.0, 0.0), (1_000_000_000, 1.0, 1.0)];
+        let overlay = DebugOverlay {
+            timeline: &timeline,
+            raw_cursor: &[],
+            smoothed_cursor: &smoothed,
+            markers: &[],
+            annotation: &[],
+            end_secs: 1.0,
+            fps: 10,
+        };
+        let ass = build_debug_overlay_ass(&overlay, 100, 100);
+
+        let dots = ass
+            .lines()
+            .filter(|line| line.starts_with("Dialogue: 3,"))
+            .count();
+        assert_eq!(dots, 10);
+        assert!(!ass.contains("Dialogue: 2,"));
+    }
+}
@@ -0,0 +1,121 @@
+//! Draft (preview quality) exports for review cycles.
+//!
+//! A draft renders the same edit as the final export, but small and fast:
+//! the output is scaled down to at most a 540-pixel short edge, capped at
+//! 30 fps, and encoded with the `ultrafast` preset. A running timecode is
+//! burned into the corner so reviewers can point at moments, and a DRAFT
+//! watermark can be added so the file isn't mistaken for the final cut.
+
+use crate::subtitles::{ass_drawing_header, format_ass_time};
+
+/// Longest short edge of a draft export, in pixels.
+pub const DRAFT_MAX_SHORT_EDGE: u32 = 540;
+
+/// Highest frame rate of a draft export.
+pub const DRAFT_MAX_FPS: u32 = 30;
+
+/// Encoder preset of a draft export.
+pub const DRAFT_PRESET: &str = "ultrafast";
+
+/// Timecode refresh interval.
+const TIMECODE_STEP_SECS: f64 = 0.1;
+
+/// Options for a draft export.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DraftOptions {
+    /// Draw a translucent DRAFT across the frame.
+    pub watermark: bool,
+}
+
+/// Scale `width`x`height` down so the short edge is at most
+/// [`DRAFT_MAX_SHORT_EDGE`], keeping the aspect ratio and even sizes.
+pub fn draft_dimensions(width: u32, height: u32) -> (u32, u32) {
+    let short = width.min(height).max(1);
+    if short <= DRAFT_MAX_SHORT_EDGE {
+        return (width, height);
+    }
+    let scale = DRAFT_MAX_SHORT_EDGE as f64 / short as f64;
+    let even = |v: u32| (((v as f64 * scale) / 2.0).round() as u32 * 2).max(2);
+    (even(width), even(height))
+}
+
+/// Build the timecode (and optional watermark) script for a
+/// `width`x`height` output covering `0..end_secs` of the timeline.
+pub fn build_draft_ass(width: u32, height: u32, end_secs: f64, options: DraftOptions) -> String {
+    let mut script = ass_drawing_header(width, height);
+    let end_secs = end_secs.max(0.0);
+    let font_size = (height as f64 / 24.0).round().max(10.0);
+    let margin = (font_size / 2.0).round();
+
+    if options.watermark {
+        script.push_str(&format!(
+            "Dialogue: 0,{},{},Default,,0,0,0,,{{\\an5\\pos({},{})\\fs{}\\b1\\bord0\\shad0\\frz20\\1a&HB0&}}DRAFT\n",
+            format_ass_time(0.0),
+            format_ass_time(end_secs),
+            width / 2,
+            height / 2,
+            (height as f64 / 4.0).round(),
+        ));
+    }
+
+    for t in (0..)
+        .map(|i| i as f64 * TIMECODE_STEP_SECS)
+        .take_while(|t| *t < end_secs)
+    {
+        let next = (t + TIMECODE_STEP_SECS).min(end_secs);
+        script.push_str(&format!(
+            "Dialogue: 1,{},{},Default,,0,0,0,,{{\\an3\\pos({},{})\\fs{font_size}\\bord2\\shad0}}{}\n",
+            format_ass_time(t),
+            format_ass_time(next),
+            width as f64 - margin,
+            height as f64 - margin,
+            format_timecode(t),
+        ));
+    }
+
+    script
+}
+
+/// `m:ss.t`, or `h:mm:ss.t` past an hour.
+fn format_timecode(secs: f64) -> String {
+    let tenths = (secs.max(0.0) * 10.0).round() as u64;
+    let hours = tenths / 36_000;
+    let minutes = (tenths % 36_000) / 600;
+    let seconds = (tenths % 600) / 10;
+    let tenth = tenths % 10;
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}.{tenth}")
+    } else {
+        format!("{minutes}:{seconds:02}.{tenth}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_draft_dimensions_cap_short_edge() {
+        assert_eq!(draft_dimensions(1920, 1080), (960, 540));
+        assert_eq!(draft_dimensions(1080, 1920), (540, 960));
+        assert_eq!(draft_dimensions(2560, 1080), (1280, 540));
+        assert_eq!(draft_dimensions(640, 360), (640, 360));
+    }
+
+    #[test]
+    fn test_timecode_format() {
+        assert_eq!(format_timecode(0.0), "0:00.0");
+        assert_eq!(format_timecode(83.45), "1:23.5");
+        assert_eq!(format_timecode(3723.0), "1:02:03.0");
+    }
+
+    #[test]
+    fn test_watermark_is_optional() {
+        let plain = build_draft_ass(960, 540, 1.0, DraftOptions::default());
+        assert!(!plain.contains("DRAFT"));
+        assert_eq!(plain.matches("Dialogue: 1,").count(), 10);
+
+        let marked = build_draft_ass(960, 540, 1.0, DraftOptions { watermark: true });
+        assert_eq!(marked.matches("}DRAFT").count(), 1);
+    }
+}
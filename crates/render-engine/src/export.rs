@@ -1,18 +1,47 @@
 //! Export configuration and job management.
 
 use std::io::{BufRead, BufReader, Read};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 
 use grabme_common::error::{GrabmeError, GrabmeResult};
-use grabme_processing_core::cursor_smooth::CursorSmoother;
+use grabme_common::temp::session_temp_dir;
+use grabme_platform_core::coords::{CaptureNorm, PhysicalRect, VirtualNorm};
+use grabme_processing_core::cursor_smooth::{CursorSmoother, SmoothingAlgorithm};
 use grabme_project_model::event::{
-    parse_events, EventStreamHeader, InputEvent, PointerCoordinateSpace,
+    apply_pointer_hits, parse_events, ButtonState, EventKind, EventStream, EventStreamHeader,
+    InputEvent, PointerCoordinateSpace,
 };
-use grabme_project_model::project::{ExportConfig, ExportFormat, LoadedProject, WebcamCorner};
-use grabme_project_model::viewport::Viewport;
+use grabme_project_model::frame_times::FrameTimes;
+use grabme_project_model::metrics::SystemMetrics;
+use grabme_project_model::project::{
+    AspectMode, ColorRange, ExportConfig, ExportFormat, ExportRecord, LoadedProject,
+    RecordingConfig, WaveformStyle, WebcamCorner, WebcamGap, WebcamGapMode,
+};
+use grabme_project_model::timeline::{
+    CameraKeyframe, CursorOverlay, EasingFunction, KeyframeSource, Timeline,
+};
+use grabme_project_model::viewport::{Point2D, Viewport};
 
-use crate::compositor::compute_compositions;
+use crate::clock_overlay::{clock_layout, clock_start_unix_secs, clock_text};
+use crate::compositor::{
+    compute_compositions, plan_webcam_corners, webcam_overlay_size, webcam_path_points,
+    WebcamCornerSwitch,
+};
+use crate::cursor_probe::{probe_captured_cursor, CursorProbe, PixelPoint};
+use crate::debug_overlay::{build_debug_overlay_ass, event_markers, DebugOverlay};
+use crate::draft::{build_draft_ass, draft_dimensions, DraftOptions, DRAFT_MAX_FPS, DRAFT_PRESET};
+use crate::gamepad_overlay::build_gamepad_ass;
+use crate::metadata::{ffmetadata_chapters, metadata_args, output_chapters};
+use crate::metrics_overlay::build_metrics_ass;
+use crate::preroll::detect_screen_preroll;
+use crate::probe::{probe_media, MediaInfo};
+use crate::reactions::{build_reactions_ass, collect_reactions, Reaction};
+use crate::resources::ExportResources;
+use crate::retime::{append_retime, audio_source_label, RetimePlan};
+use crate::strokes::{build_strokes_ass, collect_strokes, Stroke};
+use crate::subtitles::{build_ass, parse_srt};
+use crate::tail::detect_recording_tail;
 
 /// An export job ready to be rendered.
 #[derive(Debug, Clone)]
@@ -31,6 +60,32 @@ pub struct ExportJob {
 
     /// End time (for partial exports).
     pub end_secs: Option<f64>,
+
+    /// Skip the black pre-roll at the start of the recording when the
+    /// timeline has no editor-set start offset. See [`crate::preroll`].
+    pub trim_preroll: bool,
+
+    /// End before the idle tail at the end of the recording when the
+    /// timeline has no editor-set end offset. See [`crate::tail`].
+    pub trim_tail: bool,
+
+    /// Render the whole screen with diagnostic layers instead of following
+    /// the camera. See [`crate::debug_overlay`].
+    pub debug_overlay: bool,
+
+    /// Thread, priority, and preset limits for the encode.
+    pub resources: ExportResources,
+
+    /// Stream-copy the screen video and mux audio without compositing.
+    ///
+    /// Skips the timeline, cursor, and canvas passes entirely; only MP4
+    /// output is supported.
+    pub fast_copy: bool,
+
+    /// Burn the review timecode (and watermark) of a draft export. Set by
+    /// [`ExportJob::into_draft`], which also lowers size, frame rate and
+    /// preset. See [`crate::draft`].
+    pub draft: Option<DraftOptions>,
 }
 
 /// Progress callback for export rendering.
@@ -91,14 +146,7 @@ pub async fn export_project(
         "Starting export"
     );
 
-    // Validate inputs
-    if !job.project_dir.exists() {
-        return Err(GrabmeError::render("Project directory does not exist"));
-    }
-
-    if let Some(parent) = job.output_path.parent() {
-        std::fs::create_dir_all(parent)?;
-    }
+    prepare_output(&job)?;
 
     if let Some(cb) = &progress {
         cb(ExportProgress {
@@ -119,29 +167,168 @@ pub async fn export_project(
 
     tracing::info!(backend = backend.name(), "Using render backend");
     backend.render(&job, progress)?;
+    record_last_export(&job);
 
     Ok(job.output_path)
 }
 
+/// Progress callback for [`export_variants`], told which variant is rendering.
+pub type VariantProgressCallback = std::sync::Arc<dyn Fn(AspectMode, ExportProgress) + Send + Sync>;
+
+/// Export several aspect variants of one project back to back, from inputs
+/// loaded once. `jobs` come from [`ExportJob::variant`] on one base job.
+/// Stops at the first variant that fails.
+pub async fn export_variants(
+    jobs: Vec<ExportJob>,
+    progress: Option<VariantProgressCallback>,
+) -> GrabmeResult<Vec<PathBuf>> {
+    let Some(first) = jobs.first() else {
+        return Ok(Vec::new());
+    };
+    if jobs.iter().any(|job| job.fast_copy) {
+        return Err(GrabmeError::unsupported(
+            "Fast copy can't render aspect variants",
+        ));
+    }
+    for job in &jobs {
+        prepare_output(job)?;
+    }
+
+    let backend = FfmpegBackend::new();
+    if !backend.is_available() {
+        return Err(GrabmeError::unsupported(
+            "No supported render backend found (expected ffmpeg in PATH)",
+        ));
+    }
+    let inputs = backend.load_inputs(first)?;
+    tracing::info!(
+        variants = jobs.len(),
+        events = inputs.events.len(),
+        duration_secs = inputs.duration_secs,
+        "Export inputs loaded for variants"
+    );
+
+    let mut outputs = Vec::with_capacity(jobs.len());
+    for job in jobs {
+        let aspect = job.config.aspect_mode;
+        tracing::info!(
+            aspect = aspect.as_str(),
+            output = %job.output_path.display(),
+            width = job.config.width,
+            height = job.config.height,
+            "Starting export variant"
+        );
+        let variant_progress = progress
+            .clone()
+            .map(|cb| -> ProgressCallback { Box::new(move |update| cb(aspect, update)) });
+        backend.render_loaded(&job, &inputs, variant_progress)?;
+        record_last_export(&job);
+        outputs.push(job.output_path);
+    }
+    Ok(outputs)
+}
+
+impl ExportJob {
+    /// This job as a preview-quality draft: scaled down, frame rate capped
+    /// and encoded with the `ultrafast` preset.
+    pub fn into_draft(mut self, options: DraftOptions) -> ExportJob {
+        (self.config.width, self.config.height) =
+            draft_dimensions(self.config.width, self.config.height);
+        self.config.fps = self.config.fps.clamp(1, DRAFT_MAX_FPS);
+        self.resources.preset = Some(DRAFT_PRESET.to_string());
+        self.draft = Some(options);
+        self
+    }
+
+    /// This job in another aspect: the output keeps the short edge of the
+    /// configured size and gets the aspect's name appended, e.g.
+    /// `output-vertical.mp4`.
+    pub fn variant(&self, aspect: AspectMode) -> ExportJob {
+        let mut job = self.clone();
+        (job.config.width, job.config.height) =
+            aspect.dimensions(self.config.width, self.config.height);
+        job.config.aspect_mode = aspect;
+        job.output_path = variant_output_path(&self.output_path, aspect);
+        job
+    }
+}
+
+/// `output.mp4` -> `output-square.mp4`; `frames` -> `frames-square`.
+fn variant_output_path(output_path: &Path, aspect: AspectMode) -> PathBuf {
+    let stem = output_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "output".to_string());
+    let name = match output_path.extension() {
+        Some(ext) => format!("{stem}-{}.{}", aspect.as_str(), ext.to_string_lossy()),
+        None => format!("{stem}-{}", aspect.as_str()),
+    };
+    output_path.with_file_name(name)
+}
+
+/// Check the project exists and create the output directory.
+fn prepare_output(job: &ExportJob) -> GrabmeResult<()> {
+    if !job.project_dir.exists() {
+        return Err(GrabmeError::render("Project directory does not exist"));
+    }
+
+    if job.config.format.is_image_sequence() {
+        std::fs::create_dir_all(&job.output_path)?;
+    } else if let Some(parent) = job.output_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    Ok(())
+}
+
+/// Remember the finished export in `project.json` for `grabme status`.
+fn record_last_export(job: &ExportJob) {
+    let result = LoadedProject::load(&job.project_dir).and_then(|mut loaded| {
+        loaded.project.last_export = Some(ExportRecord::now(
+            &job.project_dir,
+            &job.output_path,
+            job.config.format,
+        ));
+        loaded.save()
+    });
+    if let Err(e) = result {
+        tracing::warn!(error = %e, "Failed to record export in project");
+    }
+}
+
 #[derive(Debug, Clone)]
 struct LoadedExportInputs {
     project: LoadedProject,
     screen_path: PathBuf,
     screen_offset_ns: i64,
     screen_duration_secs: Option<f64>,
+    /// The screen recording has its own audio stream.
+    screen_has_audio: bool,
     source_width: u32,
     source_height: u32,
     webcam_path: Option<PathBuf>,
     webcam_offset_ns: Option<i64>,
     webcam_duration_secs: Option<f64>,
+    /// Frame size the webcam was recorded at, when the track records it.
+    webcam_size: Option<(u32, u32)>,
+    pip_path: Option<PathBuf>,
+    pip_offset_ns: Option<i64>,
+    pip_duration_secs: Option<f64>,
     mic_path: Option<PathBuf>,
     mic_offset_ns: Option<i64>,
     mic_duration_secs: Option<f64>,
     system_audio_path: Option<PathBuf>,
     system_audio_offset_ns: Option<i64>,
     system_audio_duration_secs: Option<f64>,
+    music_path: Option<PathBuf>,
+    subtitles_path: Option<PathBuf>,
     events_header: Option<EventStreamHeader>,
     events: Vec<InputEvent>,
+    /// Pointer positions are capture-time hits, already capture-normalized.
+    pointer_hits: bool,
+    /// Logged screen capture timestamps; events are already re-timed to them.
+    frame_times: Option<FrameTimes>,
+    /// Timeline time the export starts at.
+    start_secs: f64,
     duration_secs: f64,
 }
 
@@ -196,12 +383,18 @@ const MAX_CURSOR_EXPR_POINTS: usize = 96;
 const CURSOR_EXPR_POINTS_PER_SEC: f64 = 8.0;
 #[allow(dead_code)]
 const CURSOR_SIMPLIFY_TOLERANCE_PX: f64 = 0.1;
-const CURSOR_ICON_SIZE: u32 = 32;
-const CURSOR_HOTSPOT_X: u32 = 5;
-const CURSOR_HOTSPOT_Y: u32 = 5;
+/// Longest gap between pointer samples that is still drawn as a curve.
+const CURSOR_CURVE_MAX_GAP_NS: u64 = 100_000_000;
+/// Longest gap between samples of a reframed camera.
+const REFRAME_STEP_SECS: f64 = 1.0;
+pub(crate) const CURSOR_ICON_SIZE: u32 = 32;
+pub(crate) const CURSOR_HOTSPOT_X: u32 = 5;
+pub(crate) const CURSOR_HOTSPOT_Y: u32 = 5;
 const CURSOR_ICON_SVG: &str = include_str!("../assets/cursor-pointer-lucide.svg");
 const SYNC_DRIFT_WARN_THRESHOLD_SECS: f64 = 0.120;
 const LEGACY_WEBCAM_OPACITY_DEFAULT: f64 = 0.92;
+const OUTPUT_COLOR_RANGE: ColorRange = ColorRange::Limited;
+const PNG_SEQUENCE_PATTERN: &str = "frame_%06d.png";
 
 impl FfmpegBackend {
     fn new() -> Self {
@@ -214,9 +407,17 @@ impl FfmpegBackend {
 
         let (screen_path, screen_offset_ns, mut duration_secs) =
             resolve_screen_source(&job.project_dir, &project)?;
-        let screen_duration_secs = probe_media_duration(&screen_path);
+        let screen_info = match probe_media(&screen_path) {
+            Ok(info) => info,
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to probe the screen recording; slow-motion and freezes will leave out its audio");
+                MediaInfo::default()
+            }
+        };
+        let screen_duration_secs = screen_info.duration_secs;
+        let screen_has_audio = screen_info.audio.is_some();
 
-        let (source_width, source_height) = probe_video_dimensions(&screen_path).unwrap_or((
+        let (source_width, source_height) = screen_info.video_size().unwrap_or((
             project.project.recording.capture_width,
             project.project.recording.capture_height,
         ));
@@ -253,7 +454,27 @@ impl FfmpegBackend {
         };
         let webcam_duration_secs = webcam_path
             .as_ref()
-            .and_then(|path| probe_media_duration(path));
+            .and_then(|path| probe_media(path).ok()?.duration_secs);
+        let webcam_size = webcam_track
+            .and_then(|track| track.video)
+            .map(|format| (format.width, format.height));
+
+        let pip_track = project.project.tracks.pip.as_ref();
+        let pip_path = pip_track.and_then(|track| {
+            let path = job.project_dir.join(&track.path);
+            if path.exists() {
+                Some(path)
+            } else {
+                tracing::warn!(path = %path.display(), "Picture-in-picture track is referenced but file is missing; skipping it");
+                None
+            }
+        });
+        let pip_offset_ns = pip_track
+            .map(|track| track.offset_ns)
+            .filter(|_| pip_path.is_some());
+        let pip_duration_secs = pip_path
+            .as_ref()
+            .and_then(|path| probe_media(path).ok()?.duration_secs);
 
         let mic_track = project.project.tracks.mic.as_ref();
         let mic_offset_ns = mic_track.map(|track| track.offset_ns);
@@ -267,7 +488,7 @@ impl FfmpegBackend {
         };
         let mic_duration_secs = mic_path
             .as_ref()
-            .and_then(|path| probe_media_duration(path));
+            .and_then(|path| probe_media(path).ok()?.duration_secs);
 
         let system_track = project.project.tracks.system_audio.as_ref();
         let system_audio_offset_ns = system_track.map(|track| track.offset_ns);
@@ -281,22 +502,56 @@ impl FfmpegBackend {
         };
         let system_audio_duration_secs = system_audio_path
             .as_ref()
-            .and_then(|path| probe_media_duration(path));
+            .and_then(|path| probe_media(path).ok()?.duration_secs);
+
+        let music_path = project.project.tracks.music.as_ref().and_then(|track| {
+            let path = job.project_dir.join(&track.path);
+            if path.exists() {
+                Some(path)
+            } else {
+                tracing::warn!(path = %path.display(), "Music track is referenced but file is missing; skipping background music");
+                None
+            }
+        });
+
+        let subtitles_path = if job.config.burn_subtitles {
+            let path = job.project_dir.join(subtitle_source_path(&project));
+            if path.exists() {
+                Some(path)
+            } else {
+                tracing::warn!(path = %path.display(), "Subtitle burn-in requested but subtitle file is missing; skipping subtitles");
+                None
+            }
+        } else {
+            None
+        };
 
         let events_path = job.project_dir.join("meta").join("events.jsonl");
-        let events_content = std::fs::read_to_string(&events_path).map_err(|e| {
-            GrabmeError::render(format!(
-                "Failed to read events file {}: {e}",
-                events_path.display()
-            ))
-        })?;
-        let events_header = parse_events_header(&events_content);
-        let events_jsonl = strip_events_header(&events_content);
-        let events = parse_events(&events_jsonl)
-            .map_err(|e| GrabmeError::render(format!("Failed to parse events stream: {e}")))?;
+        let (events_header, mut events) = read_events(&events_path, job.resources.stream_events)?;
+        let pointer_hits = apply_pointer_hits(&mut events);
+        if pointer_hits {
+            tracing::info!("Using capture-time pointer hits; skipping projection heuristics");
+        }
+        let frame_times = match FrameTimes::load(&job.project_dir) {
+            Ok(frame_times) => frame_times.filter(FrameTimes::is_usable),
+            Err(e) => {
+                tracing::warn!(error = %e, "Ignoring unreadable frame times");
+                None
+            }
+        };
+        if let Some(frame_times) = &frame_times {
+            apply_frame_times(&mut events, frame_times);
+        }
+        apply_resolution_changes(
+            &mut events,
+            &project.project.recording,
+            screen_offset_ns,
+            source_width,
+            source_height,
+        );
 
         if duration_secs <= 0.0 {
-            if let Some(probed_secs) = probe_media_duration(&screen_path) {
+            if let Some(probed_secs) = screen_duration_secs {
                 duration_secs = probed_secs;
                 tracing::info!(
                     duration_secs,
@@ -322,31 +577,44 @@ impl FfmpegBackend {
             ));
         }
 
-        if let Some(end) = job.end_secs {
-            duration_secs = duration_secs.min(end);
-        }
-        if let Some(start) = job.start_secs {
-            duration_secs = (duration_secs - start).max(0.0);
+        let mic = mic_path.as_deref().zip(mic_offset_ns);
+        duration_secs = export_end_secs(job, &project, &events, mic, duration_secs);
+        let start_secs = export_start_secs(job, &project, &screen_path, screen_offset_ns);
+        if start_secs >= duration_secs {
+            return Err(GrabmeError::render(format!(
+                "Export starts at {start_secs:.2}s, at or past its end at {duration_secs:.2}s"
+            )));
         }
+        duration_secs -= start_secs;
 
         Ok(LoadedExportInputs {
             project,
             screen_path,
             screen_offset_ns,
             screen_duration_secs,
+            screen_has_audio,
             source_width,
             source_height,
             webcam_path,
             webcam_offset_ns,
             webcam_duration_secs,
+            webcam_size,
+            pip_path,
+            pip_offset_ns,
+            pip_duration_secs,
             mic_path,
             mic_offset_ns,
             mic_duration_secs,
             system_audio_path,
             system_audio_offset_ns,
             system_audio_duration_secs,
+            music_path,
+            subtitles_path,
             events_header,
             events,
+            pointer_hits,
+            frame_times,
+            start_secs,
             duration_secs,
         })
     }
@@ -359,55 +627,69 @@ impl FfmpegBackend {
             ));
         }
 
-        let mut cursor_config = inputs.project.timeline.cursor_config.clone();
-        if let Some(effect_strength) =
-            inputs
-                .project
-                .timeline
-                .effects
-                .iter()
-                .rev()
-                .find_map(|effect| match effect {
-                    grabme_project_model::timeline::Effect::CursorSmooth { strength } => {
-                        Some(*strength)
-                    }
-                    _ => None,
-                })
-        {
-            cursor_config.smoothing_factor = effect_strength.clamp(0.0, 1.0);
-        }
-
+        let cursor_config = effective_cursor_config(&inputs.project.timeline);
         let smoothing = CursorSmoother::algorithm_from_cursor_config(&cursor_config);
 
         let smoothed_cursor = CursorSmoother::new(smoothing).smooth(&inputs.events);
-        let force_full_screen = force_full_screen_render();
-        let cursor_projection = maybe_override_cursor_projection(
-            select_cursor_projection(
-                inputs.events_header.as_ref(),
-                &inputs.project.project.recording,
-                &smoothed_cursor,
-            ),
-            &inputs.project.project.recording,
-        );
+        let force_full_screen = force_full_screen_render() || job.debug_overlay;
+        let cursor_projection = resolve_cursor_projection(inputs, &smoothed_cursor);
         let smoothed_cursor =
             apply_cursor_projection(&smoothed_cursor, cursor_projection.transform);
         let fps = job.config.fps.max(1);
-        let total_frames = (inputs.duration_secs * fps as f64).ceil() as u64;
+        // Partial exports render the timeline from zero and drop everything
+        // before `start_secs` with an output-side seek, so viewport and
+        // cursor expressions keep using source timestamps.
+        let range_start_secs = inputs.start_secs;
+        let timeline_end_secs = range_start_secs + inputs.duration_secs;
+        // Speed segments stretch the output; the seek and duration are in
+        // output time.
+        let retime = RetimePlan::new(&inputs.project.timeline.speed_segments, timeline_end_secs);
+        let (output_start_secs, output_duration_secs) = match &retime {
+            Some(plan) => {
+                let start = plan.output_secs(range_start_secs);
+                (start, plan.output_secs(timeline_end_secs) - start)
+            }
+            None => (range_start_secs, inputs.duration_secs),
+        };
+        let total_frames = (output_duration_secs * fps as f64).ceil() as u64;
         let monitor_precrop = derive_monitor_precrop(
             &inputs.project.project.recording,
             inputs.source_width,
             inputs.source_height,
         );
 
+        // Waveform exports draw the audio instead of the screen, so nothing
+        // placed on the screen is drawn either.
+        let waveform = job.config.waveform.enabled && !job.debug_overlay;
+        let strokes_ass = if job.config.strokes.enabled && !waveform {
+            write_strokes_overlay(job, inputs, cursor_projection.transform, monitor_precrop)?
+        } else {
+            None
+        };
+        let reactions_ass = if job.config.reactions.enabled && !waveform {
+            write_reactions_overlay(job, inputs, cursor_projection.transform, monitor_precrop)?
+        } else {
+            None
+        };
+        let debug_ass = if job.debug_overlay {
+            Some(write_debug_overlay(
+                job,
+                inputs,
+                cursor_projection,
+                &smoothed_cursor,
+                monitor_precrop,
+                timeline_end_secs,
+            )?)
+        } else {
+            None
+        };
+
         let viewport_points = if force_full_screen {
-            vec![
-                (0.0, Viewport::FULL),
-                (inputs.duration_secs, Viewport::FULL),
-            ]
+            vec![(0.0, Viewport::FULL), (timeline_end_secs, Viewport::FULL)]
         } else {
             sample_viewport_points(
                 &inputs.project.timeline,
-                inputs.duration_secs,
+                timeline_end_secs,
                 MAX_VIEWPORT_EXPR_POINTS,
             )
         };
@@ -420,13 +702,18 @@ impl FfmpegBackend {
         let h_expr =
             build_piecewise_expr(viewport_points.iter().map(|(t, vp)| (*t, vp.h)).collect());
         let viewport_scale_is_dynamic = viewport_scale_is_dynamic(&viewport_points);
+        let cursor_interpolation = CursorInterpolation::for_rates(
+            fps,
+            pointer_sample_rate_hz(inputs.events_header.as_ref(), &smoothed_cursor),
+        );
         let cursor_points = if force_full_screen {
             sample_cursor_points_full_screen(
                 &smoothed_cursor,
                 job.config.width,
                 job.config.height,
-                inputs.duration_secs,
+                timeline_end_secs,
                 fps,
+                cursor_interpolation,
             )
         } else {
             sample_cursor_points(
@@ -434,14 +721,28 @@ impl FfmpegBackend {
                 &inputs.project.timeline,
                 job.config.width,
                 job.config.height,
-                inputs.duration_secs,
+                timeline_end_secs,
                 fps,
+                cursor_interpolation,
             )
         };
         let cursor_x_expr =
             build_piecewise_expr(cursor_points.iter().map(|(t, x, _)| (*t, *x)).collect());
         let cursor_y_expr =
             build_piecewise_expr(cursor_points.iter().map(|(t, _, y)| (*t, *y)).collect());
+        // The pointer as it lands in the output is what the webcam keeps clear
+        // of; waveform exports have no pointer on screen.
+        let webcam_corners = if waveform {
+            Vec::new()
+        } else {
+            plan_webcam_corners(
+                &job.config.webcam,
+                &cursor_points,
+                job.config.width,
+                job.config.height,
+                inputs.webcam_size,
+            )
+        };
         let cursor_trail_plan = build_cursor_trail_plan(
             &cursor_points,
             &cursor_config.motion_trail,
@@ -451,17 +752,30 @@ impl FfmpegBackend {
         );
 
         let cursor_icon_path = ensure_cursor_icon_file()?;
-        let cursor_input_index = 1usize;
+        let draw_cursor = match cursor_config.overlay {
+            _ if waveform => false,
+            CursorOverlay::Show => true,
+            CursorOverlay::Hide => false,
+            // A cursor captured into the frames is not drawn a second time.
+            CursorOverlay::Auto => {
+                !cursor_captured(inputs, cursor_projection.transform, monitor_precrop)
+            }
+        };
+        let cursor_input_index = draw_cursor.then_some(1usize);
+        let mut next_input_index = 1 + usize::from(cursor_input_index.is_some());
         let webcam_index = if inputs.webcam_path.is_some() {
-            Some(2usize)
+            let idx = next_input_index;
+            next_input_index += 1;
+            Some(idx)
         } else {
             None
         };
-
-        let mut next_input_index = if webcam_index.is_some() {
-            3usize
+        let pip_index = if inputs.pip_path.is_some() {
+            let idx = next_input_index;
+            next_input_index += 1;
+            Some(idx)
         } else {
-            2usize
+            None
         };
         let mic_index = if inputs.mic_path.is_some() {
             let idx = next_input_index;
@@ -471,29 +785,110 @@ impl FfmpegBackend {
             None
         };
         let system_audio_index = if inputs.system_audio_path.is_some() {
-            Some(next_input_index)
+            let idx = next_input_index;
+            next_input_index += 1;
+            Some(idx)
         } else {
             None
         };
+        let music_track = inputs.project.project.tracks.music.as_ref();
+        let music_mix = inputs
+            .music_path
+            .as_ref()
+            .zip(music_track)
+            .map(|(_, track)| MusicMix {
+                input_index: next_input_index,
+                gain_db: track.gain_db,
+                fade_in_secs: track.fade_in_secs,
+                fade_out_secs: track.fade_out_secs,
+                ducking: track.ducking,
+                duration_secs: timeline_end_secs,
+            });
 
-        let mut filter = build_filter_graph(
-            &job.config,
-            &x_expr,
-            &y_expr,
-            &w_expr,
-            &h_expr,
-            &cursor_x_expr,
-            &cursor_y_expr,
-            cursor_input_index,
-            webcam_index,
-            monitor_precrop,
-            cursor_trail_plan.as_ref(),
-            viewport_scale_is_dynamic,
+        let webcam_gaps = webcam_gap_spans(
+            &inputs.project.project.recording.webcam_gaps,
+            inputs
+                .webcam_offset_ns
+                .map_or(0, |offset| offset - inputs.screen_offset_ns),
         );
+        let (mut filter, waveform_audio_map) = if waveform {
+            build_waveform_graph(
+                &job.config,
+                fps,
+                mic_index,
+                system_audio_index,
+                music_mix.as_ref(),
+                webcam_index,
+                &webcam_gaps,
+                inputs.webcam_size,
+            )?
+        } else {
+            let filter = build_filter_graph(
+                &job.config,
+                &x_expr,
+                &y_expr,
+                &w_expr,
+                &h_expr,
+                &cursor_x_expr,
+                &cursor_y_expr,
+                cursor_input_index,
+                webcam_index,
+                &webcam_gaps,
+                &webcam_corners,
+                inputs.webcam_size,
+                monitor_precrop,
+                cursor_trail_plan.as_ref(),
+                viewport_scale_is_dynamic,
+                inputs.project.project.recording.color_range,
+                strokes_ass.as_deref(),
+                reactions_ass.as_deref(),
+                debug_ass.as_deref(),
+            );
+            (filter, None)
+        };
+        if let Some(pip_idx) = pip_index.filter(|_| job.config.pip.enabled) {
+            append_pip_overlay(&mut filter, pip_idx, &job.config);
+        }
+        if let Some(srt_path) = &inputs.subtitles_path {
+            let ass_path = write_styled_subtitles(job, srt_path)?;
+            append_subtitle_burn(&mut filter, &ass_path);
+        }
+        if job.config.gamepad.enabled {
+            if let Some(ass_path) = write_gamepad_overlay(job, &inputs.events)? {
+                append_ass_burn(&mut filter, &ass_path, "vpad");
+            }
+        }
+        if job.config.metrics.enabled {
+            if let Some(ass_path) = write_metrics_overlay(job)? {
+                append_ass_burn(&mut filter, &ass_path, "vmetrics");
+            }
+        }
+        if job.config.clock.enabled {
+            match clock_start_unix_secs(
+                &inputs.project.project.recording.clock,
+                inputs.screen_offset_ns,
+            ) {
+                Some(start) => append_clock_burn(&mut filter, &job.config, start),
+                None => tracing::warn!(
+                    "Clock overlay requested but the recording has no wall-clock epoch"
+                ),
+            }
+        }
+        if job.config.overscan.enabled {
+            append_overscan_pad(&mut filter, &job.config);
+        }
+        if let Some(options) = job.draft {
+            let ass_path = write_draft_overlay(job, timeline_end_secs, options)?;
+            append_ass_burn(&mut filter, &ass_path, "vdraft");
+        }
         let webcam_offset_delta_ns = inputs
             .webcam_offset_ns
             .map(|offset| offset - inputs.screen_offset_ns)
             .unwrap_or(0);
+        let pip_offset_delta_ns = inputs
+            .pip_offset_ns
+            .map(|offset| offset - inputs.screen_offset_ns)
+            .unwrap_or(0);
         let mic_offset_delta_ns = inputs
             .mic_offset_ns
             .map(|offset| offset - inputs.screen_offset_ns)
@@ -503,7 +898,29 @@ impl FfmpegBackend {
             .map(|offset| offset - inputs.screen_offset_ns)
             .unwrap_or(0);
 
-        let audio_map = append_audio_mix_if_needed(&mut filter, mic_index, system_audio_index);
+        // Still-frame sequences carry no audio.
+        let audio_map = if job.config.format.is_image_sequence() {
+            None
+        } else if waveform {
+            waveform_audio_map
+        } else {
+            Some(append_audio_mix_if_needed(
+                &mut filter,
+                mic_index,
+                system_audio_index,
+                music_mix.as_ref(),
+            ))
+        };
+        let audio_map = match &retime {
+            Some(plan) => append_retime(
+                &mut filter,
+                plan,
+                fps,
+                audio_map.as_deref(),
+                inputs.screen_has_audio,
+            ),
+            None => audio_map,
+        };
         let filter_len = filter.len();
 
         let mut args = vec![
@@ -518,15 +935,21 @@ impl FfmpegBackend {
             inputs.screen_path.display().to_string(),
         ];
 
-        args.push("-loop".to_string());
-        args.push("1".to_string());
-        args.push("-i".to_string());
-        args.push(cursor_icon_path.display().to_string());
+        if cursor_input_index.is_some() {
+            args.push("-loop".to_string());
+            args.push("1".to_string());
+            args.push("-i".to_string());
+            args.push(cursor_icon_path.display().to_string());
+        }
 
         if let Some(webcam) = &inputs.webcam_path {
             append_input_with_offset(&mut args, webcam, webcam_offset_delta_ns);
         }
 
+        if let Some(pip) = &inputs.pip_path {
+            append_input_with_offset(&mut args, pip, pip_offset_delta_ns);
+        }
+
         if let Some(mic) = &inputs.mic_path {
             append_input_with_offset(&mut args, mic, mic_offset_delta_ns);
         }
@@ -535,25 +958,70 @@ impl FfmpegBackend {
             append_input_with_offset(&mut args, system_audio, system_offset_delta_ns);
         }
 
+        if let Some(music) = &inputs.music_path {
+            if music_track.is_some_and(|track| track.looped) {
+                args.push("-stream_loop".to_string());
+                args.push("-1".to_string());
+            }
+            append_input_with_offset(&mut args, music, 0);
+        }
+
+        let carries_metadata =
+            !job.config.format.is_image_sequence() && job.config.format != ExportFormat::Gif;
+        let chapters_path = if carries_metadata {
+            write_chapters(
+                job,
+                |secs| {
+                    retime.as_ref().map_or(secs, |plan| plan.output_secs(secs)) - output_start_secs
+                },
+                output_duration_secs,
+            )?
+        } else {
+            None
+        };
+        let chapters_index = args.iter().filter(|arg| *arg == "-i").count();
+        if let Some(path) = &chapters_path {
+            args.push("-i".to_string());
+            args.push(path.display().to_string());
+        }
+
         args.push("-filter_complex".to_string());
         args.push(filter);
         args.push("-map".to_string());
         args.push("[vout]".to_string());
-        args.push("-map".to_string());
-        args.push(audio_map);
+        if let Some(audio_map) = audio_map {
+            args.push("-map".to_string());
+            args.push(audio_map);
+        }
         args.push("-r".to_string());
         args.push(job.config.fps.to_string());
+        if output_start_secs > 0.0 {
+            args.push("-ss".to_string());
+            args.push(format!("{output_start_secs:.6}"));
+        }
         args.push("-t".to_string());
-        args.push(format!("{:.6}", inputs.duration_secs));
+        args.push(format!("{output_duration_secs:.6}"));
 
         let mut codec_args = codec_args_for_config(&job.config);
         args.append(&mut codec_args);
+        if carries_metadata {
+            args.extend(metadata_args(
+                &job.config.metadata,
+                &inputs.project.project.name,
+                inputs.project.project.recording.clock.event_to_wall(0),
+            ));
+            if chapters_path.is_some() {
+                args.push("-map_chapters".to_string());
+                args.push(chapters_index.to_string());
+            }
+        }
 
-        args.push(job.output_path.display().to_string());
+        args.push(export_output_target(&job.config, &job.output_path));
 
         let sync_report_json = build_sync_report(
             inputs,
             webcam_offset_delta_ns,
+            pip_offset_delta_ns,
             mic_offset_delta_ns,
             system_offset_delta_ns,
             force_full_screen,
@@ -561,10 +1029,18 @@ impl FfmpegBackend {
         );
 
         let debug_report = format!(
-            "duration_secs={:.3}\nframes={}\nviewport_mode={}\nviewport_keyframes={}\nviewport_points={}\nviewport_scale_dynamic={}\ncursor_projection_model={}\ncursor_projection_score={:.4}\ncursor_icon={}\ncursor_trail_layers={}\nwebcam_enabled={}\nwebcam_size_ratio={:.3}\nwebcam_corner={}\nwebcam_margin_ratio={:.3}\nwebcam_opacity={:.3}\nwebcam_offset_delta_ns={}\nmic_offset_delta_ns={}\nsystem_offset_delta_ns={}\nsource_width={}\nsource_height={}\nmonitor_precrop={}\nsmoothed_cursor_points={}\ncursor_points={}\nexpr_len_x={}\nexpr_len_y={}\nexpr_len_w={}\nexpr_len_h={}\nexpr_len_cursor_x={}\nexpr_len_cursor_y={}\nfilter_len={}\nffmpeg_args={}\nplan_build_ms={}\n",
-            inputs.duration_secs,
+            "duration_secs={:.3}\nframes={}\nviewport_mode={}\nviewport_keyframes={}\nviewport_points={}\nviewport_scale_dynamic={}\ncursor_projection_model={}\ncursor_projection_score={:.4}\ncursor_icon={}\ncursor_trail_layers={}\nwebcam_enabled={}\nwebcam_size_ratio={:.3}\nwebcam_corner={}\nwebcam_margin_ratio={:.3}\nwebcam_opacity={:.3}\nwebcam_offset_delta_ns={}\nmic_offset_delta_ns={}\nsystem_offset_delta_ns={}\nsource_width={}\nsource_height={}\nmonitor_precrop={}\nsmoothed_cursor_points={}\ncursor_interpolation={}\ncursor_points={}\nretime_pieces={}\nexpr_len_x={}\nexpr_len_y={}\nexpr_len_w={}\nexpr_len_h={}\nexpr_len_cursor_x={}\nexpr_len_cursor_y={}\nfilter_len={}\nffmpeg_args={}\nplan_build_ms={}\n",
+            output_duration_secs,
             total_frames,
-            if force_full_screen { "full_screen" } else { "timeline" },
+            if job.debug_overlay {
+                "debug_overlay"
+            } else if waveform {
+                "waveform"
+            } else if force_full_screen {
+                "full_screen"
+            } else {
+                "timeline"
+            },
             inputs.project.timeline.keyframes.len(),
             viewport_points.len(),
             viewport_scale_is_dynamic,
@@ -589,7 +1065,9 @@ impl FfmpegBackend {
                 .map(|crop| format!("{}x{}+{}+{}", crop.width, crop.height, crop.x, crop.y))
                 .unwrap_or_else(|| "none".to_string()),
             smoothed_cursor.len(),
+            cursor_interpolation.as_str(),
             cursor_points.len(),
+            retime.as_ref().map_or(0, |plan| plan.pieces.len()),
             x_expr.len(),
             y_expr.len(),
             w_expr.len(),
@@ -602,7 +1080,7 @@ impl FfmpegBackend {
         );
 
         tracing::info!(
-            duration_secs = inputs.duration_secs,
+            duration_secs = output_duration_secs,
             frames = total_frames,
             viewport_keyframes = inputs.project.timeline.keyframes.len(),
             viewport_points = viewport_points.len(),
@@ -618,7 +1096,7 @@ impl FfmpegBackend {
         Ok(ExportPlan {
             ffmpeg_args: args,
             total_frames,
-            expected_duration_secs: inputs.duration_secs,
+            expected_duration_secs: output_duration_secs,
             smoothed_cursor,
             cursor_projection_model: cursor_projection.model,
             force_full_screen_render: force_full_screen,
@@ -627,14 +1105,86 @@ impl FfmpegBackend {
         })
     }
 
+    fn render_fast_copy(
+        &self,
+        job: &ExportJob,
+        inputs: &LoadedExportInputs,
+        progress: Option<ProgressCallback>,
+    ) -> GrabmeResult<()> {
+        if !matches!(
+            job.config.format,
+            ExportFormat::Mp4H264 | ExportFormat::Mp4H265
+        ) {
+            return Err(GrabmeError::render(
+                "Fast copy export only supports MP4 output",
+            ));
+        }
+
+        let mut audio_inputs = Vec::new();
+        if let Some(mic) = &inputs.mic_path {
+            let delta = inputs.mic_offset_ns.unwrap_or(0) - inputs.screen_offset_ns;
+            audio_inputs.push((mic.as_path(), delta));
+        }
+        if let Some(system) = &inputs.system_audio_path {
+            let delta = inputs.system_audio_offset_ns.unwrap_or(0) - inputs.screen_offset_ns;
+            audio_inputs.push((system.as_path(), delta));
+        }
+
+        let args = fast_copy_ffmpeg_args(
+            &inputs.screen_path,
+            &audio_inputs,
+            inputs.duration_secs,
+            job.config.audio_bitrate_kbps,
+            &job.output_path,
+        );
+        let total_frames = (inputs.duration_secs
+            * inputs.project.project.recording.fps.max(1) as f64)
+            .ceil() as u64;
+
+        if let Some(cb) = &progress {
+            cb(ExportProgress {
+                progress: 0.0,
+                frames_rendered: 0,
+                total_frames,
+                eta_secs: 0.0,
+                stage: ExportStage::Encoding,
+            });
+        }
+
+        let started = std::time::Instant::now();
+        self.run_ffmpeg(
+            &args,
+            &job.resources,
+            total_frames,
+            inputs.duration_secs,
+            progress,
+        )?;
+        tracing::info!(
+            elapsed_secs = started.elapsed().as_secs_f64(),
+            "Fast copy export finished"
+        );
+        Ok(())
+    }
+
     fn run_ffmpeg(
         &self,
-        plan: &ExportPlan,
+        ffmpeg_args: &[String],
+        resources: &ExportResources,
+        total_frames: u64,
+        expected_duration_secs: f64,
         progress: Option<ProgressCallback>,
     ) -> GrabmeResult<()> {
-        tracing::debug!(args = ?plan.ffmpeg_args, "Running ffmpeg");
-        let mut cmd = Command::new("ffmpeg");
-        cmd.args(&plan.ffmpeg_args)
+        let mut ffmpeg_args = ffmpeg_args.to_vec();
+        resources.apply_to_args(&mut ffmpeg_args);
+        tracing::debug!(args = ?ffmpeg_args, "Running ffmpeg");
+        tracing::info!(
+            threads = ?resources.threads,
+            priority = %resources.priority,
+            preset = ?resources.preset,
+            "ffmpeg resource limits"
+        );
+        let mut cmd = resources.ffmpeg_command();
+        cmd.args(&ffmpeg_args)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
 
@@ -645,8 +1195,8 @@ impl FfmpegBackend {
 
         tracing::info!(
             pid = child.id(),
-            args_len = plan.ffmpeg_args.len(),
-            total_frames = plan.total_frames,
+            args_len = ffmpeg_args.len(),
+            total_frames = total_frames,
             "ffmpeg process started"
         );
 
@@ -700,8 +1250,8 @@ impl FfmpegBackend {
                     if let Some(cb) = &progress {
                         cb(progress_report(
                             &latest_progress,
-                            plan.total_frames,
-                            plan.expected_duration_secs,
+                            total_frames,
+                            expected_duration_secs,
                             start.elapsed().as_secs_f64(),
                         ));
                     }
@@ -736,8 +1286,8 @@ impl FfmpegBackend {
         if let Some(cb) = &progress {
             cb(ExportProgress {
                 progress: 1.0,
-                frames_rendered: plan.total_frames,
-                total_frames: plan.total_frames,
+                frames_rendered: total_frames,
+                total_frames,
                 eta_secs: 0.0,
                 stage: ExportStage::Complete,
             });
@@ -773,12 +1323,15 @@ impl FfmpegBackend {
             job.config.fps,
             inputs.duration_secs,
             webcam_overlay,
+            inputs.webcam_size,
         );
 
         let mut summary = VerificationSummary {
             sampled_frames: compositions.len(),
             out_of_bounds_cursors: 0,
-            cut_frames_skipped: plan.total_frames as usize - compositions.len(),
+            cut_frames_skipped: ((inputs.duration_secs * job.config.fps.max(1) as f64).ceil()
+                as usize)
+                .saturating_sub(compositions.len()),
         };
 
         for comp in &compositions {
@@ -808,18 +1361,23 @@ impl FfmpegBackend {
     }
 }
 
-impl RenderBackend for FfmpegBackend {
-    fn render(&mut self, job: &ExportJob, progress: Option<ProgressCallback>) -> GrabmeResult<()> {
+impl FfmpegBackend {
+    /// Render `job` from inputs that are already loaded. Variants of one
+    /// export share their inputs this way.
+    fn render_loaded(
+        &self,
+        job: &ExportJob,
+        inputs: &LoadedExportInputs,
+        progress: Option<ProgressCallback>,
+    ) -> GrabmeResult<()> {
         let started = std::time::Instant::now();
-        let inputs = self.load_inputs(job)?;
-        tracing::info!(
-            load_ms = started.elapsed().as_millis(),
-            events = inputs.events.len(),
-            duration_secs = inputs.duration_secs,
-            "Export inputs loaded"
-        );
+        if job.fast_copy {
+            return self.render_fast_copy(job, inputs, progress);
+        }
 
-        let plan = self.build_plan(job, &inputs)?;
+        let reframed = reframe_inputs(job, inputs);
+        let inputs = reframed.as_ref().unwrap_or(inputs);
+        let plan = self.build_plan(job, inputs)?;
         let debug_path = job.output_path.with_extension("ffmpeg-debug.txt");
         if let Err(err) = std::fs::write(&debug_path, &plan.debug_report) {
             tracing::warn!(error = %err, path = %debug_path.display(), "Failed to write ffmpeg debug report");
@@ -844,8 +1402,14 @@ impl RenderBackend for FfmpegBackend {
             });
         }
 
-        self.run_ffmpeg(&plan, progress)?;
-        let summary = self.run_visual_verification(job, &inputs, &plan)?;
+        self.run_ffmpeg(
+            &plan.ffmpeg_args,
+            &job.resources,
+            plan.total_frames,
+            plan.expected_duration_secs,
+            progress,
+        )?;
+        let summary = self.run_visual_verification(job, inputs, &plan)?;
         if summary.out_of_bounds_cursors > 0 {
             tracing::warn!(
                 out_of_bounds = summary.out_of_bounds_cursors,
@@ -859,17 +1423,57 @@ impl RenderBackend for FfmpegBackend {
         );
         Ok(())
     }
+}
 
-    fn is_available(&self) -> bool {
-        command_exists("ffmpeg")
-    }
-
-    fn name(&self) -> &str {
+impl RenderBackend for FfmpegBackend {
+    fn render(&mut self, job: &ExportJob, progress: Option<ProgressCallback>) -> GrabmeResult<()> {
+        let started = std::time::Instant::now();
+        let inputs = self.load_inputs(job)?;
+        tracing::info!(
+            load_ms = started.elapsed().as_millis(),
+            events = inputs.events.len(),
+            duration_secs = inputs.duration_secs,
+            "Export inputs loaded"
+        );
+        self.render_loaded(job, &inputs, progress)
+    }
+
+    fn is_available(&self) -> bool {
+        command_exists("ffmpeg")
+    }
+
+    fn name(&self) -> &str {
         "ffmpeg"
     }
 }
 
-fn strip_events_header(events_content: &str) -> String {
+/// Header and events of the stream at `path`, read a line at a time when
+/// `stream` is set.
+fn read_events(
+    path: &Path,
+    stream: bool,
+) -> GrabmeResult<(Option<EventStreamHeader>, Vec<InputEvent>)> {
+    let read_error = |e: std::io::Error| {
+        GrabmeError::render(format!(
+            "Failed to read events file {}: {e}",
+            path.display()
+        ))
+    };
+    if stream {
+        let stream = EventStream::open(path).map_err(read_error)?;
+        let header = stream.header().cloned();
+        let events = stream
+            .collect::<std::io::Result<Vec<_>>>()
+            .map_err(|e| GrabmeError::render(format!("Failed to parse events stream: {e}")))?;
+        return Ok((header, events));
+    }
+    let events_content = std::fs::read_to_string(path).map_err(read_error)?;
+    let events = parse_events(&strip_events_header(&events_content))
+        .map_err(|e| GrabmeError::render(format!("Failed to parse events stream: {e}")))?;
+    Ok((parse_events_header(&events_content), events))
+}
+
+pub(crate) fn strip_events_header(events_content: &str) -> String {
     events_content
         .lines()
         .filter(|line| {
@@ -889,6 +1493,24 @@ fn parse_events_header(events_content: &str) -> Option<EventStreamHeader> {
     serde_json::from_str::<EventStreamHeader>(json).ok()
 }
 
+/// The timeline's cursor settings with the last cursor-smooth effect's
+/// strength applied.
+fn effective_cursor_config(timeline: &Timeline) -> grabme_project_model::timeline::CursorConfig {
+    let mut cursor_config = timeline.cursor_config.clone();
+    if let Some(effect_strength) = timeline
+        .effects
+        .iter()
+        .rev()
+        .find_map(|effect| match effect {
+            grabme_project_model::timeline::Effect::CursorSmooth { strength } => Some(*strength),
+            _ => None,
+        })
+    {
+        cursor_config.smoothing_factor = effect_strength.clamp(0.0, 1.0);
+    }
+    cursor_config
+}
+
 fn force_full_screen_render() -> bool {
     let Ok(raw) = std::env::var("GRABME_FORCE_FULL_SCREEN_RENDER") else {
         return false;
@@ -977,6 +1599,7 @@ fn projection_candidate_for_space(
 fn build_sync_report(
     inputs: &LoadedExportInputs,
     webcam_offset_delta_ns: i64,
+    pip_offset_delta_ns: i64,
     mic_offset_delta_ns: i64,
     system_offset_delta_ns: i64,
     force_full_screen_render: bool,
@@ -1033,6 +1656,12 @@ fn build_sync_report(
         webcam_offset_delta_ns,
         inputs.webcam_duration_secs,
     );
+    push_track(
+        "pip",
+        inputs.pip_offset_ns,
+        pip_offset_delta_ns,
+        inputs.pip_duration_secs,
+    );
     push_track(
         "mic",
         inputs.mic_offset_ns,
@@ -1046,6 +1675,40 @@ fn build_sync_report(
         inputs.system_audio_duration_secs,
     );
 
+    let pacing = &inputs.project.project.recording.frame_pacing;
+    if pacing.uneven {
+        warnings.push(format!(
+            "screen was captured at {}fps against {}Hz refresh; motion may judder",
+            inputs.project.project.recording.fps, pacing.monitor_refresh_hz
+        ));
+    }
+
+    let frame_times = inputs.frame_times.as_ref().map(|frame_times| {
+        let max_drift_ns = frame_times.max_drift_ns();
+        if (max_drift_ns as f64).abs() > SYNC_DRIFT_WARN_THRESHOLD_SECS * 1_000_000_000.0 {
+            warnings.push(format!(
+                "screen track drifted {:.0}ms from the input clock; events were re-timed to it",
+                max_drift_ns as f64 / 1_000_000.0
+            ));
+        }
+        let fps = frame_times.interval_fps();
+        let min_fps = fps.iter().copied().reduce(f64::min);
+        let max_fps = fps.iter().copied().reduce(f64::max);
+        let recorded_fps = f64::from(inputs.project.project.recording.fps);
+        if min_fps.is_some_and(|min| min < recorded_fps * 0.9) {
+            warnings.push(format!(
+                "screen capture delivered as few as {:.1}fps of {recorded_fps}fps",
+                min_fps.unwrap_or_default()
+            ));
+        }
+        serde_json::json!({
+            "samples": frame_times.samples().len(),
+            "max_drift_ns": max_drift_ns,
+            "min_fps": min_fps,
+            "max_fps": max_fps,
+        })
+    });
+
     let report = serde_json::json!({
         "duration_secs": inputs.duration_secs,
         "force_full_screen_render": force_full_screen_render,
@@ -1059,6 +1722,8 @@ fn build_sync_report(
             "width": crop.width,
             "height": crop.height,
         })),
+        "frame_pacing": pacing,
+        "frame_times": frame_times,
         "tracks": track_reports,
         "warnings": warnings,
     });
@@ -1076,22 +1741,107 @@ fn sample_viewport_points(
         return vec![(0.0, timeline.viewport_at(0.0))];
     }
 
-    // Sample uniformly across duration so easing curves are captured in
-    // FFmpeg piecewise expressions (keyframe-only sampling flattens easing).
     let target_points = max_points.max(2);
+    let mut boundaries = vec![0.0];
+    boundaries.extend(
+        timeline
+            .keyframes
+            .iter()
+            .map(|kf| kf.time_secs)
+            .filter(|t| *t > 0.0 && *t < duration_secs),
+    );
+    boundaries.push(duration_secs);
+    boundaries.dedup_by(|a, b| (*a - *b).abs() < 1e-6);
+
+    // Too many keyframes to keep them all: sample uniformly so easing is
+    // still approximated across the whole export.
+    if boundaries.len() > target_points {
+        return (0..target_points)
+            .map(|i| {
+                let t = duration_secs * (i as f64 / (target_points - 1) as f64);
+                (t, timeline.viewport_at(t))
+            })
+            .collect();
+    }
+
+    // Keyframe times are always sampled, so holds and linear moves are
+    // exact. The remaining points go to eased transitions, weighted by
+    // length and by how far the curve strays from a straight line
+    // (keyframe-only sampling flattens easing).
+    let weights: Vec<f64> = boundaries
+        .windows(2)
+        .map(|w| {
+            let (start, end) = (w[0], w[1]);
+            let Some(kf) = timeline
+                .keyframes
+                .iter()
+                .rev()
+                .find(|kf| kf.time_secs <= start + 1e-6)
+            else {
+                return 0.0;
+            };
+            let next = timeline
+                .keyframes
+                .iter()
+                .find(|next| next.time_secs > kf.time_secs + 1e-9);
+            let moves = next.is_some_and(|next| next.viewport != kf.viewport);
+            if !moves {
+                return 0.0;
+            }
+            (end - start) * easing_sample_weight(&kf.easing)
+        })
+        .collect();
+
+    let spare = target_points - boundaries.len();
+    let total_weight: f64 = weights.iter().sum();
+    let mut interior = vec![0usize; weights.len()];
+    if total_weight > 0.0 {
+        let shares: Vec<f64> = weights
+            .iter()
+            .map(|w| spare as f64 * w / total_weight)
+            .collect();
+        for (count, share) in interior.iter_mut().zip(&shares) {
+            *count = share.floor() as usize;
+        }
+        // Hand out what rounding left over by largest remainder.
+        let mut order: Vec<usize> = (0..shares.len()).filter(|&i| weights[i] > 0.0).collect();
+        order.sort_by(|&a, &b| {
+            (shares[b] - shares[b].floor()).total_cmp(&(shares[a] - shares[a].floor()))
+        });
+        let leftover = spare - interior.iter().sum::<usize>();
+        for &i in order.iter().cycle().take(leftover) {
+            interior[i] += 1;
+        }
+    }
+
     let mut points = Vec::with_capacity(target_points);
-    for i in 0..target_points {
-        let t = if target_points == 1 {
-            0.0
-        } else {
-            duration_secs * (i as f64 / (target_points - 1) as f64)
-        };
-        points.push((t, timeline.viewport_at(t)));
+    for (i, window) in boundaries.windows(2).enumerate() {
+        let (start, end) = (window[0], window[1]);
+        points.push((start, timeline.viewport_at(start)));
+        let n = interior[i];
+        for j in 1..=n {
+            let t = start + (end - start) * (j as f64 / (n + 1) as f64);
+            points.push((t, timeline.viewport_at(t)));
+        }
     }
+    points.push((duration_secs, timeline.viewport_at(duration_secs)));
 
     points
 }
 
+/// Relative number of expression points an eased transition needs to be
+/// drawn faithfully by FFmpeg's piecewise-linear interpolation.
+fn easing_sample_weight(easing: &grabme_project_model::timeline::EasingFunction) -> f64 {
+    use grabme_project_model::timeline::EasingFunction;
+
+    match easing {
+        EasingFunction::Linear => 0.0,
+        EasingFunction::Spring { .. } => 3.0,
+        easing if easing.overshoots() => 2.0,
+        _ => 1.0,
+    }
+}
+
 fn viewport_scale_is_dynamic(
     viewport_points: &[(f64, grabme_project_model::viewport::Viewport)],
 ) -> bool {
@@ -1310,6 +2060,68 @@ fn select_focus_monitor_index(
     ((fallback_x * monitor_count as f64).floor() as usize).min(monitor_count - 1)
 }
 
+/// How cursor positions are filled in between pointer samples.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CursorInterpolation {
+    Linear,
+    CatmullRom,
+}
+
+impl CursorInterpolation {
+    /// Curves only when frames come faster than pointer samples; otherwise
+    /// every frame already lands on or next to a real sample.
+    fn for_rates(fps: u32, pointer_sample_rate_hz: u32) -> Self {
+        if pointer_sample_rate_hz > 0 && fps > pointer_sample_rate_hz {
+            CursorInterpolation::CatmullRom
+        } else {
+            CursorInterpolation::Linear
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            CursorInterpolation::Linear => "linear",
+            CursorInterpolation::CatmullRom => "catmull_rom",
+        }
+    }
+
+    fn position_at(self, smoothed_cursor: &[(u64, f64, f64)], t_ns: u64) -> Option<Point2D> {
+        match self {
+            CursorInterpolation::Linear => CursorSmoother::position_at(smoothed_cursor, t_ns),
+            CursorInterpolation::CatmullRom => CursorSmoother::position_at_catmull_rom(
+                smoothed_cursor,
+                t_ns,
+                CURSOR_CURVE_MAX_GAP_NS,
+            ),
+        }
+    }
+}
+
+/// Pointer sample rate from the event header, or measured from the samples
+/// (median spacing) for streams without one.
+fn pointer_sample_rate_hz(
+    header: Option<&EventStreamHeader>,
+    smoothed_cursor: &[(u64, f64, f64)],
+) -> u32 {
+    if let Some(rate) = header
+        .map(|h| h.pointer_sample_rate_hz)
+        .filter(|rate| *rate > 0)
+    {
+        return rate;
+    }
+    let mut gaps: Vec<u64> = smoothed_cursor
+        .windows(2)
+        .map(|w| w[1].0.saturating_sub(w[0].0))
+        .filter(|gap| *gap > 0)
+        .collect();
+    if gaps.is_empty() {
+        return 0;
+    }
+    let mid = gaps.len() / 2;
+    let (_, median, _) = gaps.select_nth_unstable(mid);
+    (1_000_000_000.0 / *median as f64).round() as u32
+}
+
 #[allow(dead_code)]
 fn sample_cursor_points_full_screen(
     smoothed_cursor: &[(u64, f64, f64)],
@@ -1317,6 +2129,7 @@ fn sample_cursor_points_full_screen(
     out_h: u32,
     duration_secs: f64,
     fps: u32,
+    interpolation: CursorInterpolation,
 ) -> Vec<(f64, f64, f64)> {
     if smoothed_cursor.is_empty() {
         return vec![
@@ -1330,7 +2143,7 @@ fn sample_cursor_points_full_screen(
     for frame in 0..total_frames {
         let t_secs = frame as f64 / fps.max(1) as f64;
         let t_ns = (t_secs * 1_000_000_000.0).round() as u64;
-        let Some(pos) = CursorSmoother::position_at(smoothed_cursor, t_ns) else {
+        let Some(pos) = interpolation.position_at(smoothed_cursor, t_ns) else {
             continue;
         };
         points.push((
@@ -1373,6 +2186,7 @@ fn sample_cursor_points(
     out_h: u32,
     duration_secs: f64,
     fps: u32,
+    interpolation: CursorInterpolation,
 ) -> Vec<(f64, f64, f64)> {
     if smoothed_cursor.is_empty() {
         return vec![
@@ -1386,7 +2200,7 @@ fn sample_cursor_points(
     for frame in 0..total_frames {
         let t_secs = frame as f64 / fps.max(1) as f64;
         let t_ns = (t_secs * 1_000_000_000.0).round() as u64;
-        let Some(pos) = CursorSmoother::position_at(smoothed_cursor, t_ns) else {
+        let Some(pos) = interpolation.position_at(smoothed_cursor, t_ns) else {
             continue;
         };
         let viewport = timeline.viewport_at(t_secs);
@@ -1626,6 +2440,20 @@ struct CursorProjection {
 }
 
 impl CursorProjection {
+    /// Identity projection for events already moved to their capture-time
+    /// hits.
+    fn capture_hits(smoothed_cursor: &[(u64, f64, f64)]) -> Self {
+        let candidate = ProjectionCandidate {
+            model: CursorCoordinateModel::CaptureHits,
+            transform: PlaneTransform::identity(),
+        };
+        Self {
+            model: candidate.model,
+            transform: candidate.transform,
+            score: score_projection_candidate(candidate, smoothed_cursor),
+        }
+    }
+
     fn from_recording_geometry(
         recording: &grabme_project_model::project::RecordingConfig,
         smoothed_cursor: &[(u64, f64, f64)],
@@ -1658,6 +2486,8 @@ impl CursorProjection {
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum CursorCoordinateModel {
+    /// Positions resolved against the monitor layout while recording.
+    CaptureHits,
     CaptureNormalized,
     VirtualDesktopNormalized,
     VirtualDesktopRootOrigin,
@@ -1666,6 +2496,7 @@ enum CursorCoordinateModel {
 impl CursorCoordinateModel {
     fn as_str(self) -> &'static str {
         match self {
+            CursorCoordinateModel::CaptureHits => "capture_hits",
             CursorCoordinateModel::CaptureNormalized => "capture_normalized",
             CursorCoordinateModel::VirtualDesktopNormalized => "virtual_desktop_normalized",
             CursorCoordinateModel::VirtualDesktopRootOrigin => "virtual_desktop_root_origin",
@@ -1697,51 +2528,61 @@ impl PlaneTransform {
         }
     }
 
-    fn project(self, x: f64, y: f64) -> Option<(f64, f64)> {
+    /// Affine map taking `from` positions to capture-normalized ones,
+    /// fitted to two reference points.
+    fn from_mapping(from: impl Fn(f64, f64) -> CaptureNorm) -> Self {
+        let origin = from(0.0, 0.0);
+        let unit = from(1.0, 1.0);
+        Self::from_affine(unit.x - origin.x, unit.y - origin.y, origin.x, origin.y)
+    }
+
+    /// Project a recorded pointer value into the captured region.
+    fn project(self, x: f64, y: f64) -> Option<CaptureNorm> {
         let xh = self.m[0][0] * x + self.m[0][1] * y + self.m[0][2];
         let yh = self.m[1][0] * x + self.m[1][1] * y + self.m[1][2];
         let wh = self.m[2][0] * x + self.m[2][1] * y + self.m[2][2];
         if wh.abs() < 1e-9 {
             return None;
         }
-        Some((xh / wh, yh / wh))
+        Some(CaptureNorm::new(xh / wh, yh / wh))
     }
 }
 
 fn virtual_desktop_projection_candidates(
     recording: &grabme_project_model::project::RecordingConfig,
 ) -> Vec<ProjectionCandidate> {
-    let monitor_w = recording.monitor_width as f64;
-    let monitor_h = recording.monitor_height as f64;
-    let virtual_w = recording.virtual_width as f64;
-    let virtual_h = recording.virtual_height as f64;
-    if monitor_w <= 0.0 || monitor_h <= 0.0 || virtual_w <= 0.0 || virtual_h <= 0.0 {
+    let capture = recording.monitor_rect();
+    let bounds = recording.virtual_desktop_rect();
+    if capture.is_empty() || bounds.is_empty() {
         return vec![];
     }
 
     // Event coordinates in [0,1] are interpreted in virtual-desktop-normalized
-    // space and projected into capture-normalized (monitor) space.
-    let scale_x = virtual_w / monitor_w;
-    let scale_y = virtual_h / monitor_h;
-    let tx_bounds = (recording.virtual_x as f64 - recording.monitor_x as f64) / monitor_w;
-    let ty_bounds = (recording.virtual_y as f64 - recording.monitor_y as f64) / monitor_h;
-    let tx_root = -(recording.monitor_x as f64) / monitor_w;
-    let ty_root = -(recording.monitor_y as f64) / monitor_h;
-
+    // space and projected into capture-normalized (monitor) space. Legacy
+    // recordings normalized against the desktop size from the root origin.
     let bounds_candidate = ProjectionCandidate {
         model: CursorCoordinateModel::VirtualDesktopNormalized,
-        transform: PlaneTransform::from_affine(scale_x, scale_y, tx_bounds, ty_bounds),
+        transform: PlaneTransform::from_mapping(|x, y| {
+            VirtualNorm::new(x, y).to_capture(bounds, capture)
+        }),
     };
 
-    if (tx_bounds - tx_root).abs() < 1e-9 && (ty_bounds - ty_root).abs() < 1e-9 {
+    if bounds.x == 0 && bounds.y == 0 {
         return vec![bounds_candidate];
     }
 
+    let root = PhysicalRect {
+        x: 0,
+        y: 0,
+        ..bounds
+    };
     vec![
         bounds_candidate,
         ProjectionCandidate {
             model: CursorCoordinateModel::VirtualDesktopRootOrigin,
-            transform: PlaneTransform::from_affine(scale_x, scale_y, tx_root, ty_root),
+            transform: PlaneTransform::from_mapping(|x, y| {
+                VirtualNorm::new(x, y).to_capture(root, capture)
+            }),
         },
     ]
 }
@@ -1801,8 +2642,11 @@ fn apply_cursor_projection(
     smoothed_cursor
         .iter()
         .map(|(t, x, y)| {
-            let (px, py) = transform.project(*x, *y).unwrap_or((*x, *y));
-            (*t, px.clamp(0.0, 1.0), py.clamp(0.0, 1.0))
+            let point = transform
+                .project(*x, *y)
+                .unwrap_or(CaptureNorm::new(*x, *y))
+                .clamped();
+            (*t, point.x, point.y)
         })
         .collect()
 }
@@ -1822,15 +2666,15 @@ fn projection_in_bounds_ratio(
     let mut in_bounds = 0usize;
 
     for (_, x, y) in smoothed_cursor.iter().step_by(sample_stride) {
-        let Some((px, py)) = candidate.transform.project(*x, *y) else {
+        let Some(point) = candidate.transform.project(*x, *y) else {
             continue;
         };
-        if !px.is_finite() || !py.is_finite() {
+        if !point.x.is_finite() || !point.y.is_finite() {
             continue;
         }
 
         sampled += 1;
-        if (0.0..=1.0).contains(&px) && (0.0..=1.0).contains(&py) {
+        if point.is_inside() {
             in_bounds += 1;
         }
     }
@@ -1862,7 +2706,7 @@ fn score_projection_candidate(
     let mut max_y = f64::NEG_INFINITY;
 
     for (_, x, y) in smoothed_cursor.iter().step_by(sample_stride) {
-        let Some((px, py)) = candidate.transform.project(*x, *y) else {
+        let Some(CaptureNorm { x: px, y: py }) = candidate.transform.project(*x, *y) else {
             continue;
         };
         if !px.is_finite() || !py.is_finite() {
@@ -1870,7 +2714,7 @@ fn score_projection_candidate(
         }
 
         sampled += 1;
-        if (0.0..=1.0).contains(&px) && (0.0..=1.0).contains(&py) {
+        if CaptureNorm::new(px, py).is_inside() {
             in_bounds += 1;
             min_x = min_x.min(px);
             max_x = max_x.max(px);
@@ -1963,11 +2807,18 @@ fn build_filter_graph(
     h_expr: &str,
     cursor_x_expr: &str,
     cursor_y_expr: &str,
-    cursor_input_index: usize,
+    cursor_input_index: Option<usize>,
     webcam_index: Option<usize>,
+    webcam_gaps: &[(f64, f64)],
+    webcam_corners: &[WebcamCornerSwitch],
+    webcam_size: Option<(u32, u32)>,
     monitor_precrop: Option<MonitorPreCrop>,
     cursor_trail_plan: Option<&CursorTrailPlan>,
     viewport_scale_is_dynamic: bool,
+    source_color_range: ColorRange,
+    strokes_ass: Option<&std::path::Path>,
+    reactions_ass: Option<&std::path::Path>,
+    debug_ass: Option<&std::path::Path>,
 ) -> String {
     let mut graph = String::new();
 
@@ -1987,6 +2838,14 @@ fn build_filter_graph(
         out_h = config.height
     ));
 
+    // Convert the recorded range to limited range explicitly; the encoder
+    // flags in `codec_args_for_config` tag the output to match.
+    let range = format!(
+        "in_range={}:out_range={}",
+        source_color_range.ffmpeg_name(),
+        OUTPUT_COLOR_RANGE.ffmpeg_name()
+    );
+
     let screen_input_label = if let Some(crop) = monitor_precrop {
         graph.push_str(&format!(
             "[0:v]crop=w={w}:h={h}:x={x}:y={y}[screen_src];",
@@ -2000,13 +2859,42 @@ fn build_filter_graph(
         "[0:v]"
     };
 
+    // Strokes are drawn on the source so zoom and crop apply to them too.
+    let screen_input_label = if let Some(ass_path) = strokes_ass {
+        graph.push_str(&format!(
+            "{screen_input_label}subtitles=filename={}[screen_strokes];",
+            escape_filter_value(&ass_path.display().to_string())
+        ));
+        "[screen_strokes]"
+    } else {
+        screen_input_label
+    };
+    let screen_input_label = if let Some(ass_path) = reactions_ass {
+        graph.push_str(&format!(
+            "{screen_input_label}subtitles=filename={}[screen_reactions];",
+            escape_filter_value(&ass_path.display().to_string())
+        ));
+        "[screen_reactions]"
+    } else {
+        screen_input_label
+    };
+    let screen_input_label = if let Some(ass_path) = debug_ass {
+        graph.push_str(&format!(
+            "{screen_input_label}subtitles=filename={}[screen_debug];",
+            escape_filter_value(&ass_path.display().to_string())
+        ));
+        "[screen_debug]"
+    } else {
+        screen_input_label
+    };
+
     // Screen Layer
     // - Static-scale viewport: use polished rounded-corner + shadow stack.
     // - Dynamic-scale viewport: fallback to direct composite to avoid filtergraph
     //   frame-size reconfiguration failures on some ffmpeg builds.
     if viewport_scale_is_dynamic {
         graph.push_str(&format!(
-            "{screen_src}scale=w='max(2,trunc(({out_w}/({w}))/2)*2)':h='max(2,trunc(({out_h}/({h}))/2)*2)':eval=frame:flags=lanczos[screen_scaled];\
+            "{screen_src}scale=w='max(2,trunc(({out_w}/({w}))/2)*2)':h='max(2,trunc(({out_h}/({h}))/2)*2)':eval=frame:flags=lanczos:{range}[screen_scaled];\
              [bg][screen_scaled]overlay=x='({out_w})*(-{x})/({w})':y='({out_h})*(-{y})/({h})':eval=frame[base];",
             screen_src = screen_input_label,
             out_w = config.width,
@@ -2018,7 +2906,7 @@ fn build_filter_graph(
         ));
     } else {
         graph.push_str(&format!(
-            "{screen_src}scale=w='max(2,trunc(({out_w}/({w}))/2)*2)':h='max(2,trunc(({out_h}/({h}))/2)*2)':eval=frame:flags=lanczos,format=yuva420p[screen_scaled];\
+            "{screen_src}scale=w='max(2,trunc(({out_w}/({w}))/2)*2)':h='max(2,trunc(({out_h}/({h}))/2)*2)':eval=frame:flags=lanczos:{range},format=yuva420p[screen_scaled];\
              [screen_scaled]split[screen_for_mask][screen_for_comp];\
              [screen_for_mask]format=gray,geq=lum='if(gt(abs(W/2-X),W/2-{R})*gt(abs(H/2-Y),H/2-{R}),if(lte((abs(W/2-X)-(W/2-{R}))^2+(abs(H/2-Y)-(H/2-{R}))^2,{R}^2),255,0),255)'[screen_mask];\
              [screen_for_comp][screen_mask]alphamerge[screen_rounded];\
@@ -2047,9 +2935,20 @@ fn build_filter_graph(
     }
 
     // 5. Cursor Overlay (+ optional trail layers)
+    let Some(cursor_idx) = cursor_input_index else {
+        graph.push_str("[base]null[scene]");
+        append_webcam_overlay(
+            &mut graph,
+            config,
+            webcam_index,
+            webcam_gaps,
+            webcam_corners,
+            webcam_size,
+        );
+        return graph;
+    };
     graph.push_str(&format!(
         "[{cursor_idx}:v]format=rgba,scale={cursor_size}:{cursor_size}:flags=lanczos[cursor_sprite];",
-        cursor_idx = cursor_input_index,
         cursor_size = CURSOR_ICON_SIZE,
     ));
 
@@ -2081,28 +2980,147 @@ fn build_filter_graph(
         hot_x = CURSOR_HOTSPOT_X,
         hot_y = CURSOR_HOTSPOT_Y,
     ));
+    append_webcam_overlay(
+        &mut graph,
+        config,
+        webcam_index,
+        webcam_gaps,
+        webcam_corners,
+        webcam_size,
+    );
+    graph
+}
+
+/// Filter graph for a waveform export: the mixed audio drawn over the canvas
+/// background with the webcam on top, ending at `[vout]`. The audio is split
+/// so the same mix is also encoded; the returned map selects it.
+#[allow(clippy::too_many_arguments)]
+fn build_waveform_graph(
+    config: &ExportConfig,
+    fps: u32,
+    mic_index: Option<usize>,
+    system_audio_index: Option<usize>,
+    music: Option<&MusicMix>,
+    webcam_index: Option<usize>,
+    webcam_gaps: &[(f64, f64)],
+    webcam_size: Option<(u32, u32)>,
+) -> GrabmeResult<(String, Option<String>)> {
+    let mut audio_graph = String::new();
+    let audio_map =
+        append_audio_mix_if_needed(&mut audio_graph, mic_index, system_audio_index, music);
+    let source = audio_source_label(&audio_map).ok_or_else(|| {
+        GrabmeError::render("Waveform export needs a mic, system audio or music track")
+    })?;
+
+    let mut graph = audio_graph.trim_start_matches(';').to_string();
+    if !graph.is_empty() {
+        graph.push(';');
+    }
+    // Still-frame sequences have no audio stream to feed.
+    let audio_out = if config.format.is_image_sequence() {
+        graph.push_str(&format!("{source}anull[awave];"));
+        None
+    } else {
+        graph.push_str(&format!("{source}asplit=2[awave][awaveout];"));
+        Some("[awaveout]".to_string())
+    };
+
+    let pad = config.canvas.padding.min(config.width / 4);
+    let wave_w = even_dimension(config.width.saturating_sub(pad * 2) as f64);
+    let wave_h =
+        even_dimension(config.height as f64 * config.waveform.height_ratio.clamp(0.1, 1.0));
+    let visual = match config.waveform.style {
+        WaveformStyle::Waves => format!(
+            "showwaves=s={wave_w}x{wave_h}:mode=cline:rate={fps}:scale=sqrt:draw=full:colors={color}",
+            color = normalize_ffmpeg_color(&config.waveform.color),
+        ),
+        WaveformStyle::Spectrum => format!(
+            "showspectrum=s={wave_w}x{wave_h}:slide=scroll:mode=combined:color=intensity:scale=log,fps={fps}"
+        ),
+    };
+    graph.push_str(&format!(
+        "[awave]{visual},format=yuva420p[wave];\
+         color=c={bg}:s={out_w}x{out_h}:r={fps}[bg];\
+         [bg][wave]overlay=x=(W-w)/2:y=(H-h)/2[scene]",
+        bg = normalize_ffmpeg_color(&config.canvas.background),
+        out_w = config.width,
+        out_h = config.height,
+    ));
+    append_webcam_overlay(
+        &mut graph,
+        config,
+        webcam_index,
+        webcam_gaps,
+        &[],
+        webcam_size,
+    );
+    Ok((graph, audio_out))
+}
 
+/// Overlay the webcam onto `[scene]`, ending the graph at `[vout]`.
+fn append_webcam_overlay(
+    graph: &mut String,
+    config: &ExportConfig,
+    webcam_index: Option<usize>,
+    webcam_gaps: &[(f64, f64)],
+    webcam_corners: &[WebcamCornerSwitch],
+    webcam_size: Option<(u32, u32)>,
+) {
     if let Some(webcam_idx) = webcam_index.filter(|_| config.webcam.enabled) {
-        let webcam_size_ratio = config.webcam.size_ratio.clamp(0.08, 0.50);
         let webcam_margin_ratio = config.webcam.margin_ratio.clamp(0.0, 0.20);
         let webcam_opacity = effective_webcam_opacity(config.webcam.opacity);
 
-        let webcam_w = even_dimension(config.width as f64 * webcam_size_ratio);
-        let webcam_h = even_dimension(config.height as f64 * webcam_size_ratio);
+        let (webcam_w, webcam_h) =
+            webcam_overlay_size(&config.webcam, config.width, config.height, webcam_size);
         let margin_x = (config.width as f64 * webcam_margin_ratio).round() as u32;
         let margin_y = (config.height as f64 * webcam_margin_ratio).round() as u32;
-        let (overlay_x, overlay_y) = match config.webcam.corner {
-            WebcamCorner::TopLeft => (format!("{margin_x}"), format!("{margin_y}")),
-            WebcamCorner::TopRight => (format!("W-w-{margin_x}"), format!("{margin_y}")),
-            WebcamCorner::BottomLeft => (format!("{margin_x}"), format!("H-h-{margin_y}")),
-            WebcamCorner::BottomRight => (format!("W-w-{margin_x}"), format!("H-h-{margin_y}")),
+        // Corner moves slide along keyframes in source time, like the
+        // viewport expressions.
+        let (overlay_x, overlay_y) = if webcam_corners.is_empty() {
+            corner_overlay_position(config.webcam.corner, margin_x, margin_y)
+        } else {
+            let points = webcam_path_points(
+                &config.webcam,
+                webcam_corners,
+                config.width,
+                config.height,
+                (webcam_w as f64, webcam_h as f64),
+            );
+            (
+                format!(
+                    "'{}'",
+                    build_piecewise_expr(points.iter().map(|(t, x, _)| (*t, *x)).collect())
+                ),
+                format!(
+                    "'{}'",
+                    build_piecewise_expr(points.iter().map(|(t, _, y)| (*t, *y)).collect())
+                ),
+            )
+        };
+
+        // Disconnected spans: dropping their frames makes overlay hold the
+        // last frame before the gap; hiding disables the overlay instead.
+        let gap_expr = webcam_gaps
+            .iter()
+            .map(|(start, end)| format!("between(t,{start:.3},{end:.3})"))
+            .collect::<Vec<_>>()
+            .join("+");
+        let (webcam_select, overlay_enable) = match config.webcam.gap_mode {
+            _ if gap_expr.is_empty() => (String::new(), String::new()),
+            WebcamGapMode::Freeze => (format!("select='not({gap_expr})',"), String::new()),
+            WebcamGapMode::Hide => (String::new(), format!(":enable='not({gap_expr})'")),
         };
 
+        // With the camera's frame size known the overlay already has its
+        // aspect ratio; otherwise fit inside the box and pad transparently.
+        let fit = if webcam_size.is_some() {
+            format!("scale=w={webcam_w}:h={webcam_h}:flags=lanczos,setsar=1")
+        } else {
+            format!("scale=w={webcam_w}:h={webcam_h}:force_original_aspect_ratio=decrease:flags=lanczos,pad={webcam_w}:{webcam_h}:(ow-iw)/2:(oh-ih)/2:color=black@0")
+        };
         graph.push_str(&format!(
-            ";[{webcam}:v]scale=w={webcam_w}:h={webcam_h}:force_original_aspect_ratio=decrease:flags=lanczos,pad={webcam_w}:{webcam_h}:(ow-iw)/2:(oh-ih)/2:color=black@0,format=yuva420p,colorchannelmixer=aa={opacity:.3}[webcam];[scene][webcam]overlay=x={overlay_x}:y={overlay_y}:eof_action=pass[vout]",
+            ";[{webcam}:v]{webcam_select}{fit},format=yuva420p,colorchannelmixer=aa={opacity:.3}[webcam];[scene][webcam]overlay=x={overlay_x}:y={overlay_y}:eval=frame:eof_action=pass{overlay_enable}[vout]",
             webcam = webcam_idx,
-            webcam_w = webcam_w,
-            webcam_h = webcam_h,
             opacity = webcam_opacity,
             overlay_x = overlay_x,
             overlay_y = overlay_y,
@@ -2110,117 +3128,1036 @@ fn build_filter_graph(
     } else {
         graph.push_str(";[scene]null[vout]");
     }
-
-    graph
-}
-
-fn append_audio_mix_if_needed(
-    filter_graph: &mut String,
-    mic_index: Option<usize>,
-    system_audio_index: Option<usize>,
-) -> String {
-    match (mic_index, system_audio_index) {
-        (Some(mic), Some(system)) => {
-            filter_graph.push_str(&format!(
-                ";[{mic}:a:0]aresample=async=1:first_pts=0[amic];[{system}:a:0]aresample=async=1:first_pts=0[asystem];[amic][asystem]amix=inputs=2:weights='1 1':normalize=0[aout]"
-            ));
-            "[aout]".to_string()
-        }
-        (Some(mic), None) => format!("{mic}:a:0?"),
-        (None, Some(system)) => format!("{system}:a:0?"),
-        (None, None) => "0:a?".to_string(),
-    }
 }
 
-fn webcam_corner_label(corner: WebcamCorner) -> &'static str {
+/// Overlay expressions placing a layer in `corner`, `margin` pixels in.
+fn corner_overlay_position(corner: WebcamCorner, margin_x: u32, margin_y: u32) -> (String, String) {
     match corner {
-        WebcamCorner::TopLeft => "top_left",
-        WebcamCorner::TopRight => "top_right",
-        WebcamCorner::BottomLeft => "bottom_left",
-        WebcamCorner::BottomRight => "bottom_right",
+        WebcamCorner::TopLeft => (format!("{margin_x}"), format!("{margin_y}")),
+        WebcamCorner::TopRight => (format!("W-w-{margin_x}"), format!("{margin_y}")),
+        WebcamCorner::BottomLeft => (format!("{margin_x}"), format!("H-h-{margin_y}")),
+        WebcamCorner::BottomRight => (format!("W-w-{margin_x}"), format!("H-h-{margin_y}")),
     }
 }
 
-fn append_input_with_offset(args: &mut Vec<String>, path: &std::path::Path, offset_ns: i64) {
-    if offset_ns != 0 {
-        args.push("-itsoffset".to_string());
-        args.push(format!("{:.6}", offset_ns as f64 / 1_000_000_000.0));
+/// Composite the auxiliary track onto the final video label of
+/// `filter_graph`. The layer keeps its aspect ratio inside the configured
+/// box and is not padded, so a portrait phone screen hugs its corner.
+fn append_pip_overlay(filter_graph: &mut String, pip_index: usize, config: &ExportConfig) {
+    if !filter_graph.ends_with("[vout]") {
+        return;
     }
+    let size_ratio = config.pip.size_ratio.clamp(0.08, 0.60);
+    let margin_ratio = config.pip.margin_ratio.clamp(0.0, 0.20);
+    let opacity = config.pip.opacity.clamp(0.0, 1.0);
+    let box_w = even_dimension(config.width as f64 * size_ratio);
+    let box_h = even_dimension(config.height as f64 * size_ratio);
+    let margin_x = (config.width as f64 * margin_ratio).round() as u32;
+    let margin_y = (config.height as f64 * margin_ratio).round() as u32;
+    let (overlay_x, overlay_y) = corner_overlay_position(config.pip.corner, margin_x, margin_y);
+
+    filter_graph.truncate(filter_graph.len() - "[vout]".len());
+    filter_graph.push_str(&format!(
+        "[vpip_base];[{pip_index}:v]scale=w={box_w}:h={box_h}:force_original_aspect_ratio=decrease:force_divisible_by=2:flags=lanczos,format=yuva420p,colorchannelmixer=aa={opacity:.3}[pip];[vpip_base][pip]overlay=x={overlay_x}:y={overlay_y}:eof_action=pass[vout]"
+    ));
+}
 
-    args.push("-i".to_string());
-    args.push(path.display().to_string());
+/// Relative path of the SRT to burn in: the timeline's subtitle effect,
+/// otherwise `meta/subtitles.srt`.
+pub(crate) fn subtitle_source_path(project: &LoadedProject) -> String {
+    project
+        .timeline
+        .effects
+        .iter()
+        .rev()
+        .find_map(|effect| match effect {
+            grabme_project_model::timeline::Effect::Subtitles { path, .. } => Some(path.clone()),
+            _ => None,
+        })
+        .unwrap_or_else(|| "meta/subtitles.srt".to_string())
 }
 
-fn even_dimension(raw: f64) -> u32 {
-    let mut value = raw.round() as u32;
-    value = value.max(2);
-    if value % 2 != 0 {
-        value = value.saturating_sub(1).max(2);
-    }
-    value
+/// Convert the SRT into a styled ASS script under `cache/`.
+fn write_styled_subtitles(job: &ExportJob, srt_path: &std::path::Path) -> GrabmeResult<PathBuf> {
+    let srt = std::fs::read_to_string(srt_path).map_err(|e| {
+        GrabmeError::render(format!(
+            "Failed to read subtitles {}: {e}",
+            srt_path.display()
+        ))
+    })?;
+    let cues = parse_srt(&srt);
+    let ass = build_ass(
+        &cues,
+        &job.config.subtitle_style,
+        job.config.width,
+        job.config.height,
+    );
+
+    let cache_dir = job.project_dir.join("cache");
+    std::fs::create_dir_all(&cache_dir)?;
+    let ass_path = cache_dir.join("subtitles.ass");
+    std::fs::write(&ass_path, ass)?;
+    tracing::info!(cues = cues.len(), path = %ass_path.display(), "Prepared styled subtitles");
+    Ok(ass_path)
 }
 
-fn normalize_ffmpeg_color(input: &str) -> String {
-    let trimmed = input.trim();
-    if let Some(hex) = trimmed.strip_prefix('#') {
-        if hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()) {
-            return format!("0x{}", hex.to_ascii_lowercase());
-        }
+/// Write the chapters that land in the output to `cache/chapters.ffmeta`.
+/// `None` when there are none.
+fn write_chapters(
+    job: &ExportJob,
+    to_output: impl Fn(f64) -> f64,
+    output_duration_secs: f64,
+) -> GrabmeResult<Option<PathBuf>> {
+    let chapters = output_chapters(
+        &job.config.metadata.chapters,
+        to_output,
+        output_duration_secs,
+    );
+    if chapters.is_empty() {
+        return Ok(None);
     }
+    let cache_dir = job.project_dir.join("cache");
+    std::fs::create_dir_all(&cache_dir)?;
+    let path = cache_dir.join("chapters.ffmeta");
+    std::fs::write(&path, ffmetadata_chapters(&chapters))?;
+    tracing::info!(chapters = chapters.len(), path = %path.display(), "Prepared chapters");
+    Ok(Some(path))
+}
 
-    if trimmed.starts_with("0x")
-        && trimmed.len() == 8
-        && trimmed[2..].chars().all(|c| c.is_ascii_hexdigit())
+/// Square and vertical exports of a wider (or taller) recording: narrow
+/// every viewport to the output aspect, panning the crop inside it to
+/// follow the cursor. `None` when the camera is used as is.
+fn reframe_inputs(job: &ExportJob, inputs: &LoadedExportInputs) -> Option<LoadedExportInputs> {
+    if !matches!(
+        job.config.aspect_mode,
+        AspectMode::Square | AspectMode::Portrait
+    ) || job.debug_overlay
     {
-        return trimmed.to_ascii_lowercase();
+        return None;
     }
 
-    "0x1a1a1a".to_string()
-}
+    let (source_width, source_height) = derive_monitor_precrop(
+        &inputs.project.project.recording,
+        inputs.source_width,
+        inputs.source_height,
+    )
+    .map(|crop| (crop.width, crop.height))
+    .unwrap_or((inputs.source_width, inputs.source_height));
+    let source_aspect = source_width.max(1) as f64 / source_height.max(1) as f64;
+    let target_aspect = job.config.width.max(1) as f64 / job.config.height.max(1) as f64;
+
+    let timeline = &inputs.project.timeline;
+    let smoothing = CursorSmoother::algorithm_from_cursor_config(&timeline.cursor_config);
+    let smoothed_cursor = CursorSmoother::new(smoothing).smooth(&inputs.events);
+    let projection = resolve_cursor_projection(inputs, &smoothed_cursor);
+    let cursor = apply_cursor_projection(&smoothed_cursor, projection.transform);
+    let keyframes = reframe_keyframes(
+        timeline,
+        &cursor,
+        source_aspect,
+        target_aspect,
+        inputs.start_secs + inputs.duration_secs,
+    );
 
-fn effective_webcam_opacity(raw: f64) -> f64 {
-    let clamped = raw.clamp(0.0, 1.0);
-    if (clamped - LEGACY_WEBCAM_OPACITY_DEFAULT).abs() < 1e-6 {
-        1.0
-    } else {
-        clamped
-    }
+    tracing::info!(
+        aspect = job.config.aspect_mode.as_str(),
+        keyframes = keyframes.len(),
+        "Reframed camera to the output aspect"
+    );
+    let mut reframed = inputs.clone();
+    reframed.project.timeline.keyframes = keyframes;
+    Some(reframed)
 }
 
-fn ffmpeg_loglevel() -> String {
-    std::env::var("GRABME_FFMPEG_LOGLEVEL")
-        .ok()
-        .map(|v| v.trim().to_string())
-        .filter(|v| !v.is_empty())
-        .unwrap_or_else(|| "error".to_string())
+/// Linear keyframes tracing `timeline`'s camera at the output aspect. The
+/// timeline's own transitions get half of the viewport expression points;
+/// a regular grid for panning to the cursor gets the other half.
+fn reframe_keyframes(
+    timeline: &Timeline,
+    cursor: &[(u64, f64, f64)],
+    source_aspect: f64,
+    target_aspect: f64,
+    end_secs: f64,
+) -> Vec<CameraKeyframe> {
+    let budget = MAX_VIEWPORT_EXPR_POINTS / 2;
+    let step = REFRAME_STEP_SECS.max(end_secs / budget as f64);
+    let mut times: Vec<f64> = sample_viewport_points(timeline, end_secs, budget)
+        .into_iter()
+        .map(|(t, _)| t)
+        .chain((0..).map(|i| i as f64 * step).take_while(|t| *t < end_secs))
+        .collect();
+    times.sort_by(f64::total_cmp);
+    times.dedup_by(|a, b| (*a - *b).abs() < 1e-3);
+
+    times
+        .into_iter()
+        .map(|t| {
+            let viewport = timeline.viewport_at(t);
+            let focus = mean_cursor_between(cursor, t - step / 2.0, t + step / 2.0)
+                .unwrap_or_else(|| viewport.center());
+            CameraKeyframe {
+                time_secs: t,
+                viewport: fit_viewport_aspect(viewport, focus, source_aspect, target_aspect),
+                easing: EasingFunction::Linear,
+                source: KeyframeSource::Auto,
+                confidence: None,
+            }
+        })
+        .collect()
 }
 
-fn ensure_cursor_icon_file() -> GrabmeResult<PathBuf> {
-    let icon_path = std::env::temp_dir().join("grabme-cursor-pointer-lucide.svg");
-    let desired = CURSOR_ICON_SVG.as_bytes();
-
-    let needs_write = match std::fs::read(&icon_path) {
-        Ok(existing) => existing != desired,
-        Err(_) => true,
-    };
+/// Average cursor position between `start_secs` and `end_secs`.
+fn mean_cursor_between(
+    cursor: &[(u64, f64, f64)],
+    start_secs: f64,
+    end_secs: f64,
+) -> Option<CaptureNorm> {
+    let (count, sum_x, sum_y) = cursor
+        .iter()
+        .filter(|(t, _, _)| {
+            let secs = *t as f64 / 1_000_000_000.0;
+            secs >= start_secs && secs < end_secs
+        })
+        .fold((0usize, 0.0, 0.0), |(n, sx, sy), (_, x, y)| {
+            (n + 1, sx + x, sy + y)
+        });
+    (count > 0).then(|| CaptureNorm::new(sum_x / count as f64, sum_y / count as f64))
+}
 
-    if needs_write {
-        std::fs::write(&icon_path, desired).map_err(|e| {
-            GrabmeError::render(format!(
-                "Failed to materialize cursor icon {}: {e}",
-                icon_path.display()
-            ))
-        })?;
+/// The largest part of `viewport` with the `target_aspect` (in pixels of a
+/// `source_aspect` screen), centred on `focus` as far as it stays inside.
+fn fit_viewport_aspect(
+    viewport: Viewport,
+    focus: CaptureNorm,
+    source_aspect: f64,
+    target_aspect: f64,
+) -> Viewport {
+    let aspect = viewport.w * source_aspect / viewport.h;
+    if aspect > target_aspect {
+        let w = viewport.h * target_aspect / source_aspect;
+        let x = (focus.x - w / 2.0).clamp(viewport.x, viewport.x + viewport.w - w);
+        Viewport::new(x, viewport.y, w, viewport.h)
+    } else {
+        let h = viewport.w * source_aspect / target_aspect;
+        let y = (focus.y - h / 2.0).clamp(viewport.y, viewport.y + viewport.h - h);
+        Viewport::new(viewport.x, y, viewport.w, h)
     }
-
-    Ok(icon_path)
 }
 
-fn codec_args_for_config(config: &ExportConfig) -> Vec<String> {
-    let video_bitrate = format!("{}k", config.video_bitrate_kbps.max(1000));
+/// How recorded pointer positions map onto the screen track.
+fn resolve_cursor_projection(
+    inputs: &LoadedExportInputs,
+    smoothed_cursor: &[(u64, f64, f64)],
+) -> CursorProjection {
+    if inputs.pointer_hits {
+        CursorProjection::capture_hits(smoothed_cursor)
+    } else {
+        maybe_override_cursor_projection(
+            select_cursor_projection(
+                inputs.events_header.as_ref(),
+                &inputs.project.project.recording,
+                smoothed_cursor,
+            ),
+            &inputs.project.project.recording,
+        )
+    }
+}
+
+/// Whether the screen track already shows the system cursor: recorded as
+/// captured, or else found in the frames.
+fn cursor_captured(
+    inputs: &LoadedExportInputs,
+    transform: PlaneTransform,
+    monitor_precrop: Option<MonitorPreCrop>,
+) -> bool {
+    if inputs.project.project.recording.cursor_in_frames() {
+        return true;
+    }
+    match probe_inputs_cursor(inputs, transform, monitor_precrop) {
+        Ok(Some(probe)) if probe.cursor_visible() => {
+            tracing::warn!(
+                samples = probe.samples,
+                matches = probe.matches,
+                "Screen recording shows the system cursor; not drawing a second one. \
+                 Set the cursor overlay to show to draw it anyway"
+            );
+            true
+        }
+        Ok(_) => false,
+        Err(e) => {
+            tracing::debug!(error = %e, "Captured cursor probe failed; drawing the cursor");
+            false
+        }
+    }
+}
+
+fn probe_inputs_cursor(
+    inputs: &LoadedExportInputs,
+    transform: PlaneTransform,
+    monitor_precrop: Option<MonitorPreCrop>,
+) -> GrabmeResult<Option<CursorProbe>> {
+    let (left, top, width, height) = monitor_precrop
+        .map(|crop| (crop.x, crop.y, crop.width, crop.height))
+        .unwrap_or((0, 0, inputs.source_width, inputs.source_height));
+    let pointer: Vec<PixelPoint> = inputs
+        .events
+        .iter()
+        .filter_map(|event| {
+            let (x, y) = event.pointer_position()?;
+            let point = transform.project(x, y)?.clamped();
+            Some((
+                event.timestamp_ns,
+                left as f64 + point.x * width as f64,
+                top as f64 + point.y * height as f64,
+            ))
+        })
+        .collect();
+    probe_captured_cursor(
+        &inputs.screen_path,
+        inputs.screen_offset_ns,
+        (inputs.source_width, inputs.source_height),
+        &pointer,
+        inputs.project.project.recording.scale_factor,
+    )
+}
+
+/// Probe the project's screen recording for a system cursor captured into
+/// the frames, the check `auto` cursor overlays run at export. `Ok(None)`
+/// when the pointer rested too rarely to tell.
+pub fn detect_captured_cursor(project_dir: &Path) -> GrabmeResult<Option<CursorProbe>> {
+    let project = LoadedProject::load(project_dir)
+        .map_err(|e| GrabmeError::render(format!("Failed to load project: {e}")))?;
+    let job = ExportJob {
+        project_dir: project_dir.to_path_buf(),
+        output_path: PathBuf::new(),
+        config: project.project.export.clone(),
+        start_secs: None,
+        end_secs: None,
+        trim_preroll: false,
+        trim_tail: false,
+        debug_overlay: false,
+        resources: ExportResources::default(),
+        fast_copy: false,
+        draft: None,
+    };
+    let inputs = FfmpegBackend::new().load_inputs(&job)?;
+    let smoothing =
+        CursorSmoother::algorithm_from_cursor_config(&inputs.project.timeline.cursor_config);
+    let smoothed_cursor = CursorSmoother::new(smoothing).smooth(&inputs.events);
+    let projection = resolve_cursor_projection(&inputs, &smoothed_cursor);
+    let monitor_precrop = derive_monitor_precrop(
+        &inputs.project.project.recording,
+        inputs.source_width,
+        inputs.source_height,
+    );
+    probe_inputs_cursor(&inputs, projection.transform, monitor_precrop)
+}
+
+/// Where an export draws the cursor at a recorded button press.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+pub struct ExpectedClick {
+    /// Press time on the timeline, in seconds.
+    pub timeline_secs: f64,
+    /// The same moment in the exported video, in seconds.
+    pub output_secs: f64,
+    /// Cursor hotspot in output pixels.
+    pub x: f64,
+    pub y: f64,
+}
+
+/// Cursor hotspot positions `job` draws at each recorded button press, in
+/// the pixel space of `job.config`. Presses outside the exported range are
+/// left out.
+pub fn expected_click_positions(job: &ExportJob) -> GrabmeResult<Vec<ExpectedClick>> {
+    let inputs = FfmpegBackend::new().load_inputs(job)?;
+    let reframed = reframe_inputs(job, &inputs);
+    let inputs = reframed.as_ref().unwrap_or(&inputs);
+
+    let cursor_config = effective_cursor_config(&inputs.project.timeline);
+    let smoothing = CursorSmoother::algorithm_from_cursor_config(&cursor_config);
+    let smoothed_cursor = CursorSmoother::new(smoothing).smooth(&inputs.events);
+    let projection = resolve_cursor_projection(inputs, &smoothed_cursor);
+    let smoothed_cursor = apply_cursor_projection(&smoothed_cursor, projection.transform);
+    let interpolation = CursorInterpolation::for_rates(
+        job.config.fps.max(1),
+        pointer_sample_rate_hz(inputs.events_header.as_ref(), &smoothed_cursor),
+    );
+    let full_screen = force_full_screen_render() || job.debug_overlay;
+
+    let range_start_secs = inputs.start_secs;
+    let timeline_end_secs = range_start_secs + inputs.duration_secs;
+    let retime = RetimePlan::new(&inputs.project.timeline.speed_segments, timeline_end_secs);
+    let output_secs = |secs: f64| match &retime {
+        Some(plan) => plan.output_secs(secs) - plan.output_secs(range_start_secs),
+        None => secs - range_start_secs,
+    };
+
+    Ok(inputs
+        .events
+        .iter()
+        .filter(|event| {
+            matches!(
+                event.kind,
+                EventKind::Click {
+                    state: ButtonState::Down,
+                    ..
+                }
+            )
+        })
+        .filter_map(|event| {
+            let timeline_secs = event.timestamp_secs();
+            if timeline_secs < range_start_secs || timeline_secs >= timeline_end_secs {
+                return None;
+            }
+            let pos = interpolation.position_at(&smoothed_cursor, event.timestamp_ns)?;
+            let viewport = if full_screen {
+                Viewport::FULL
+            } else {
+                inputs.project.timeline.viewport_at(timeline_secs)
+            };
+            let (x, y) = project_to_output_coords(
+                pos.x,
+                pos.y,
+                viewport,
+                job.config.width,
+                job.config.height,
+            );
+            Some(ExpectedClick {
+                timeline_secs,
+                output_secs: output_secs(timeline_secs),
+                x,
+                y,
+            })
+        })
+        .collect())
+}
+
+/// Write the pen/touch stroke overlay under `cache/`, in the pixel space of
+/// the (pre-cropped) screen source. Returns `None` when nothing was drawn.
+fn write_strokes_overlay(
+    job: &ExportJob,
+    inputs: &LoadedExportInputs,
+    transform: PlaneTransform,
+    monitor_precrop: Option<MonitorPreCrop>,
+) -> GrabmeResult<Option<PathBuf>> {
+    let strokes: Vec<Stroke> = collect_strokes(&inputs.events)
+        .into_iter()
+        .map(|mut stroke| {
+            stroke.points.retain_mut(|point| {
+                let Some(projected) = transform.project(point.x, point.y) else {
+                    return false;
+                };
+                (point.x, point.y) = (projected.x, projected.y);
+                true
+            });
+            stroke
+        })
+        .filter(|stroke| !stroke.points.is_empty())
+        .collect();
+    if strokes.is_empty() {
+        return Ok(None);
+    }
+
+    let (width, height) = monitor_precrop
+        .map(|crop| (crop.width, crop.height))
+        .unwrap_or((inputs.source_width, inputs.source_height));
+    let ass = build_strokes_ass(&strokes, &job.config.strokes, width, height);
+
+    let cache_dir = job.project_dir.join("cache");
+    std::fs::create_dir_all(&cache_dir)?;
+    let ass_path = cache_dir.join("strokes.ass");
+    std::fs::write(&ass_path, ass)?;
+    tracing::info!(strokes = strokes.len(), path = %ass_path.display(), "Prepared stroke overlay");
+    Ok(Some(ass_path))
+}
+
+/// Write the reaction overlay under `cache/`, in the pixel space of the
+/// (pre-cropped) screen source. Returns `None` when there are no reactions.
+fn write_reactions_overlay(
+    job: &ExportJob,
+    inputs: &LoadedExportInputs,
+    transform: PlaneTransform,
+    monitor_precrop: Option<MonitorPreCrop>,
+) -> GrabmeResult<Option<PathBuf>> {
+    let reactions: Vec<Reaction> = collect_reactions(&inputs.events)
+        .into_iter()
+        .filter_map(|mut reaction| {
+            let projected = transform.project(reaction.x, reaction.y)?;
+            (reaction.x, reaction.y) = (projected.x, projected.y);
+            Some(reaction)
+        })
+        .collect();
+    if reactions.is_empty() {
+        return Ok(None);
+    }
+
+    let (width, height) = monitor_precrop
+        .map(|crop| (crop.width, crop.height))
+        .unwrap_or((inputs.source_width, inputs.source_height));
+    let ass = build_reactions_ass(&reactions, &job.config.reactions, width, height);
+
+    let cache_dir = job.project_dir.join("cache");
+    std::fs::create_dir_all(&cache_dir)?;
+    let ass_path = cache_dir.join("reactions.ass");
+    std::fs::write(&ass_path, ass)?;
+    tracing::info!(reactions = reactions.len(), path = %ass_path.display(), "Prepared reaction overlay");
+    Ok(Some(ass_path))
+}
+
+/// Write the debug overlay under `cache/`, in the pixel space of the
+/// (pre-cropped) screen source.
+fn write_debug_overlay(
+    job: &ExportJob,
+    inputs: &LoadedExportInputs,
+    projection: CursorProjection,
+    smoothed_cursor: &[(u64, f64, f64)],
+    monitor_precrop: Option<MonitorPreCrop>,
+    end_secs: f64,
+) -> GrabmeResult<PathBuf> {
+    let raw_cursor = apply_cursor_projection(
+        &CursorSmoother::new(SmoothingAlgorithm::None).smooth(&inputs.events),
+        projection.transform,
+    );
+    let markers = event_markers(&inputs.events, |x, y| {
+        let point = projection.transform.project(x, y)?;
+        Some((point.x, point.y))
+    });
+
+    let recording = &inputs.project.project.recording;
+    let recorded_space = inputs
+        .events_header
+        .as_ref()
+        .map(|header| header.pointer_coordinate_space)
+        .unwrap_or(recording.pointer_coordinate_space);
+    let (width, height) = monitor_precrop
+        .map(|crop| (crop.width, crop.height))
+        .unwrap_or((inputs.source_width, inputs.source_height));
+    let annotation = vec![
+        format!(
+            "pointer space: {}",
+            serde_json::to_value(recorded_space)
+                .ok()
+                .and_then(|value| value.as_str().map(str::to_string))
+                .unwrap_or_default()
+        ),
+        format!(
+            "projection: {} (score {:.3})",
+            projection.model.as_str(),
+            projection.score
+        ),
+        format!(
+            "source {}x{} · pre-crop {}",
+            inputs.source_width,
+            inputs.source_height,
+            monitor_precrop
+                .map(|crop| format!("{}x{}+{}+{}", crop.width, crop.height, crop.x, crop.y))
+                .unwrap_or_else(|| "none".to_string())
+        ),
+        format!(
+            "monitor {} {}x{}+{}+{} · virtual {}x{}+{}+{}",
+            recording.monitor_name,
+            recording.monitor_width,
+            recording.monitor_height,
+            recording.monitor_x,
+            recording.monitor_y,
+            recording.virtual_width,
+            recording.virtual_height,
+            recording.virtual_x,
+            recording.virtual_y
+        ),
+        "yellow viewport · red raw cursor · green smoothed cursor".to_string(),
+    ];
+
+    let ass = build_debug_overlay_ass(
+        &DebugOverlay {
+            timeline: &inputs.project.timeline,
+            raw_cursor: &raw_cursor,
+            smoothed_cursor,
+            markers: &markers,
+            annotation: &annotation,
+            end_secs,
+            fps: job.config.fps,
+        },
+        width,
+        height,
+    );
+
+    let cache_dir = job.project_dir.join("cache");
+    std::fs::create_dir_all(&cache_dir)?;
+    let ass_path = cache_dir.join("debug-overlay.ass");
+    std::fs::write(&ass_path, ass)?;
+    tracing::info!(markers = markers.len(), path = %ass_path.display(), "Prepared debug overlay");
+    Ok(ass_path)
+}
+
+/// Write the gamepad diagram under `cache/`. Returns `None` when the
+/// recording has no gamepad events.
+fn write_gamepad_overlay(job: &ExportJob, events: &[InputEvent]) -> GrabmeResult<Option<PathBuf>> {
+    let Some(ass) = build_gamepad_ass(
+        events,
+        &job.config.gamepad,
+        job.config.width,
+        job.config.height,
+    ) else {
+        tracing::info!("Gamepad overlay requested but the recording has no gamepad events");
+        return Ok(None);
+    };
+
+    let cache_dir = job.project_dir.join("cache");
+    std::fs::create_dir_all(&cache_dir)?;
+    let ass_path = cache_dir.join("gamepad.ass");
+    std::fs::write(&ass_path, ass)?;
+    tracing::info!(path = %ass_path.display(), "Prepared gamepad overlay");
+    Ok(Some(ass_path))
+}
+
+/// Write the system usage graph under `cache/`. Returns `None` when the
+/// recording has no metrics track.
+fn write_metrics_overlay(job: &ExportJob) -> GrabmeResult<Option<PathBuf>> {
+    let metrics = match SystemMetrics::load(&job.project_dir) {
+        Ok(metrics) => metrics,
+        Err(e) => {
+            tracing::warn!(error = %e, "Ignoring unreadable system metrics");
+            None
+        }
+    };
+    let Some(ass) = metrics.and_then(|metrics| {
+        build_metrics_ass(
+            &metrics,
+            &job.config.metrics,
+            job.config.width,
+            job.config.height,
+        )
+    }) else {
+        tracing::info!("Metrics overlay requested but the recording has no metrics track");
+        return Ok(None);
+    };
+
+    let cache_dir = job.project_dir.join("cache");
+    std::fs::create_dir_all(&cache_dir)?;
+    let ass_path = cache_dir.join("metrics.ass");
+    std::fs::write(&ass_path, ass)?;
+    tracing::info!(path = %ass_path.display(), "Prepared metrics overlay");
+    Ok(Some(ass_path))
+}
+
+/// Write the draft timecode and watermark under `cache/`, in output pixels.
+fn write_draft_overlay(
+    job: &ExportJob,
+    end_secs: f64,
+    options: DraftOptions,
+) -> GrabmeResult<PathBuf> {
+    let ass = build_draft_ass(job.config.width, job.config.height, end_secs, options);
+
+    let cache_dir = job.project_dir.join("cache");
+    std::fs::create_dir_all(&cache_dir)?;
+    let ass_path = cache_dir.join("draft.ass");
+    std::fs::write(&ass_path, ass)?;
+    tracing::info!(watermark = options.watermark, path = %ass_path.display(), "Prepared draft overlay");
+    Ok(ass_path)
+}
+
+/// Burn subtitles onto the final video label of `filter_graph`.
+fn append_subtitle_burn(filter_graph: &mut String, ass_path: &std::path::Path) {
+    append_ass_burn(filter_graph, ass_path, "vsub");
+}
+
+/// Render an ASS script onto the final video label of `filter_graph`,
+/// passing through the intermediate `label`.
+fn append_ass_burn(filter_graph: &mut String, ass_path: &std::path::Path, label: &str) {
+    if filter_graph.ends_with("[vout]") {
+        filter_graph.truncate(filter_graph.len() - "[vout]".len());
+        filter_graph.push_str(&format!(
+            "[{label}];[{label}]subtitles=filename={}[vout]",
+            escape_filter_value(&ass_path.display().to_string())
+        ));
+    }
+}
+
+/// Draw the wall clock onto the final video label of `filter_graph`.
+fn append_clock_burn(filter_graph: &mut String, config: &ExportConfig, start_unix_secs: f64) {
+    if filter_graph.ends_with("[vout]") {
+        filter_graph.truncate(filter_graph.len() - "[vout]".len());
+        filter_graph.push_str(&format!(
+            "[vclock];[vclock]drawtext=text={}:{}[vout]",
+            escape_filter_value(&clock_text(&config.clock, start_unix_secs)),
+            clock_layout(&config.clock, config.height)
+        ));
+    }
+}
+
+/// Shrink the final video label of `filter_graph` into the overscan-safe
+/// area and pad the margin with the canvas background. Runs after the
+/// burned-in captions and clock so they stay inside the safe area too.
+fn append_overscan_pad(filter_graph: &mut String, config: &ExportConfig) {
+    if filter_graph.ends_with("[vout]") {
+        let margin = config.overscan.margin_ratio.clamp(0.0, 0.10);
+        let inner_w = even_dimension(config.width as f64 * (1.0 - 2.0 * margin));
+        let inner_h = even_dimension(config.height as f64 * (1.0 - 2.0 * margin));
+        filter_graph.truncate(filter_graph.len() - "[vout]".len());
+        filter_graph.push_str(&format!(
+            "[vsafe];[vsafe]scale=w={inner_w}:h={inner_h}:flags=lanczos,pad={}:{}:(ow-iw)/2:(oh-ih)/2:color={}[vout]",
+            config.width,
+            config.height,
+            normalize_ffmpeg_color(&config.canvas.background)
+        ));
+    }
+}
+
+/// Escape a value for both the filter option parser and the filtergraph
+/// parser, so paths with `:`, `'`, or `,` survive intact.
+fn escape_filter_value(raw: &str) -> String {
+    let mut option_level = String::with_capacity(raw.len());
+    for c in raw.chars() {
+        if matches!(c, '\\' | '\'' | ':') {
+            option_level.push('\\');
+        }
+        option_level.push(c);
+    }
+    let mut graph_level = String::with_capacity(option_level.len());
+    for c in option_level.chars() {
+        if matches!(c, '\\' | '\'' | '[' | ']' | ',' | ';') {
+            graph_level.push('\\');
+        }
+        graph_level.push(c);
+    }
+    graph_level
+}
+
+/// Background music input and mix settings for one export.
+#[derive(Debug, Clone, Copy)]
+struct MusicMix {
+    input_index: usize,
+    gain_db: f64,
+    fade_in_secs: f64,
+    fade_out_secs: f64,
+    ducking: bool,
+    duration_secs: f64,
+}
+
+fn append_audio_mix_if_needed(
+    filter_graph: &mut String,
+    mic_index: Option<usize>,
+    system_audio_index: Option<usize>,
+    music: Option<&MusicMix>,
+) -> String {
+    if let Some(music) = music {
+        return append_music_mix(filter_graph, mic_index, system_audio_index, music);
+    }
+
+    match (mic_index, system_audio_index) {
+        (Some(mic), Some(system)) => {
+            filter_graph.push_str(&format!(
+                ";{}[amic];{}[asystem];[amic][asystem]amix=inputs=2:weights='1 1':normalize=0[aout]",
+                audio_input_chain(mic),
+                audio_input_chain(system)
+            ));
+            "[aout]".to_string()
+        }
+        (Some(index), None) | (None, Some(index)) => {
+            filter_graph.push_str(&format!(";{}[aout]", audio_input_chain(index)));
+            "[aout]".to_string()
+        }
+        (None, None) => "0:a?".to_string(),
+    }
+}
+
+/// Sample rate of exported audio, whatever each track was captured at.
+const EXPORT_AUDIO_SAMPLE_RATE: u32 = 48_000;
+
+/// Bring audio input `index` to the export rate and a stereo layout, so
+/// tracks captured in different formats mix and encode the same way.
+fn audio_input_chain(index: usize) -> String {
+    format!(
+        "[{index}:a:0]aresample={EXPORT_AUDIO_SAMPLE_RATE}:async=1:first_pts=0,aformat=channel_layouts=stereo"
+    )
+}
+
+/// Mix background music with narration and system audio.
+///
+/// Music is trimmed to the export duration and faded at both ends. When
+/// ducking is enabled and a mic track exists, the mic keys a sidechain
+/// compressor so the music drops under speech.
+fn append_music_mix(
+    filter_graph: &mut String,
+    mic_index: Option<usize>,
+    system_audio_index: Option<usize>,
+    music: &MusicMix,
+) -> String {
+    let duck = music.ducking && mic_index.is_some();
+    let mut labels = Vec::new();
+
+    if let Some(mic) = mic_index {
+        if duck {
+            filter_graph.push_str(&format!(
+                ";{},asplit=2[amic][aduckkey]",
+                audio_input_chain(mic)
+            ));
+        } else {
+            filter_graph.push_str(&format!(";{}[amic]", audio_input_chain(mic)));
+        }
+        labels.push("[amic]");
+    }
+    if let Some(system) = system_audio_index {
+        filter_graph.push_str(&format!(";{}[asystem]", audio_input_chain(system)));
+        labels.push("[asystem]");
+    }
+
+    let duration = music.duration_secs.max(0.0);
+    let mut chain = format!(
+        ";[{}:a:0]atrim=0:{duration:.6},asetpts=PTS-STARTPTS,volume={:.2}dB",
+        music.input_index, music.gain_db
+    );
+    let fade_in = music.fade_in_secs.clamp(0.0, duration);
+    if fade_in > 0.0 {
+        chain.push_str(&format!(",afade=t=in:st=0:d={fade_in:.3}"));
+    }
+    let fade_out = music.fade_out_secs.clamp(0.0, duration);
+    if fade_out > 0.0 {
+        chain.push_str(&format!(
+            ",afade=t=out:st={:.6}:d={fade_out:.3}",
+            duration - fade_out
+        ));
+    }
+    chain.push_str("[amusicbed]");
+    filter_graph.push_str(&chain);
+
+    if duck {
+        filter_graph.push_str(
+            ";[amusicbed][aduckkey]sidechaincompress=threshold=0.02:ratio=8:attack=20:release=400[amusic]",
+        );
+    } else {
+        filter_graph.push_str(";[amusicbed]anull[amusic]");
+    }
+    labels.push("[amusic]");
+
+    if labels.len() == 1 {
+        return "[amusic]".to_string();
+    }
+
+    let weights = vec!["1"; labels.len()].join(" ");
+    filter_graph.push_str(&format!(
+        ";{}amix=inputs={}:weights='{weights}':normalize=0:duration=longest[aout]",
+        labels.concat(),
+        labels.len()
+    ));
+    "[aout]".to_string()
+}
+
+/// FFmpeg arguments for a fast-copy export.
+///
+/// The screen video stream is copied as-is; audio tracks are aligned with
+/// `-itsoffset`, mixed when there are several, and encoded to AAC.
+fn fast_copy_ffmpeg_args(
+    screen_path: &std::path::Path,
+    audio_inputs: &[(&std::path::Path, i64)],
+    duration_secs: f64,
+    audio_bitrate_kbps: u32,
+    output_path: &std::path::Path,
+) -> Vec<String> {
+    let mut args = vec![
+        "-y".to_string(),
+        "-hide_banner".to_string(),
+        "-loglevel".to_string(),
+        ffmpeg_loglevel(),
+        "-nostats".to_string(),
+        "-progress".to_string(),
+        "pipe:1".to_string(),
+        "-i".to_string(),
+        screen_path.display().to_string(),
+    ];
+    for (path, offset_ns) in audio_inputs {
+        append_input_with_offset(&mut args, path, *offset_ns);
+    }
+
+    let mic_index = audio_inputs.first().map(|_| 1usize);
+    let system_audio_index = audio_inputs.get(1).map(|_| 2usize);
+    let mut filter = String::new();
+    let audio_map = append_audio_mix_if_needed(&mut filter, mic_index, system_audio_index, None);
+    if !filter.is_empty() {
+        args.push("-filter_complex".to_string());
+        args.push(filter.trim_start_matches(';').to_string());
+    }
+
+    args.extend([
+        "-map".to_string(),
+        "0:v:0".to_string(),
+        "-map".to_string(),
+        audio_map,
+        "-t".to_string(),
+        format!("{duration_secs:.6}"),
+        "-c:v".to_string(),
+        "copy".to_string(),
+        "-c:a".to_string(),
+        "aac".to_string(),
+        "-b:a".to_string(),
+        format!("{}k", audio_bitrate_kbps.max(64)),
+        "-movflags".to_string(),
+        "+faststart".to_string(),
+        output_path.display().to_string(),
+    ]);
+    args
+}
+
+fn webcam_corner_label(corner: WebcamCorner) -> &'static str {
+    match corner {
+        WebcamCorner::TopLeft => "top_left",
+        WebcamCorner::TopRight => "top_right",
+        WebcamCorner::BottomLeft => "bottom_left",
+        WebcamCorner::BottomRight => "bottom_right",
+    }
+}
+
+/// Shift events onto the screen track's PTS clock, so a track that drifted
+/// from the recording clock stays in step with the input.
+fn apply_frame_times(events: &mut [InputEvent], frame_times: &FrameTimes) {
+    tracing::info!(
+        samples = frame_times.samples().len(),
+        max_drift_ms = frame_times.max_drift_ns() as f64 / 1_000_000.0,
+        "Re-timing events from logged frame times"
+    );
+    for event in events {
+        let drift_ns = frame_times.drift_ns_at(event.timestamp_ns);
+        event.timestamp_ns = event.timestamp_ns.saturating_add_signed(drift_ns);
+    }
+}
+
+/// Move input positions recorded after a display resolution change onto
+/// the scaled-down picture the screen track holds from then on.
+fn apply_resolution_changes(
+    events: &mut [InputEvent],
+    recording: &RecordingConfig,
+    screen_offset_ns: i64,
+    track_width: u32,
+    track_height: u32,
+) {
+    if recording.resolution_changes.is_empty() {
+        return;
+    }
+    tracing::info!(
+        changes = recording.resolution_changes.len(),
+        "Mapping input positions across display resolution changes"
+    );
+    for event in events {
+        let track_ns = (event.timestamp_ns as i64 - screen_offset_ns).max(0) as u64;
+        let Some(change) = recording.resolution_change_at(track_ns) else {
+            continue;
+        };
+        if let Some((x, y)) = event.position_mut() {
+            let mapped = change.map_position(CaptureNorm::new(*x, *y), track_width, track_height);
+            (*x, *y) = (mapped.x, mapped.y);
+        }
+    }
+}
+
+/// Webcam gaps in filter-graph seconds: track time shifted by the webcam's
+/// offset from the screen, as `append_input_with_offset` shifts the input.
+fn webcam_gap_spans(gaps: &[WebcamGap], offset_delta_ns: i64) -> Vec<(f64, f64)> {
+    gaps.iter()
+        .map(|gap| {
+            let shift = |ns: u64| ((ns as i64 + offset_delta_ns) as f64 / 1_000_000_000.0).max(0.0);
+            (shift(gap.start_ns), shift(gap.end_ns))
+        })
+        .filter(|(start, end)| end > start)
+        .collect()
+}
+
+fn append_input_with_offset(args: &mut Vec<String>, path: &std::path::Path, offset_ns: i64) {
+    if offset_ns != 0 {
+        args.push("-itsoffset".to_string());
+        args.push(format!("{:.6}", offset_ns as f64 / 1_000_000_000.0));
+    }
+
+    args.push("-i".to_string());
+    args.push(path.display().to_string());
+}
+
+fn even_dimension(raw: f64) -> u32 {
+    let mut value = raw.round() as u32;
+    value = value.max(2);
+    if value % 2 != 0 {
+        value = value.saturating_sub(1).max(2);
+    }
+    value
+}
+
+fn normalize_ffmpeg_color(input: &str) -> String {
+    let trimmed = input.trim();
+    if let Some(hex) = trimmed.strip_prefix('#') {
+        if hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return format!("0x{}", hex.to_ascii_lowercase());
+        }
+    }
+
+    if trimmed.starts_with("0x")
+        && trimmed.len() == 8
+        && trimmed[2..].chars().all(|c| c.is_ascii_hexdigit())
+    {
+        return trimmed.to_ascii_lowercase();
+    }
+
+    "0x1a1a1a".to_string()
+}
+
+fn effective_webcam_opacity(raw: f64) -> f64 {
+    let clamped = raw.clamp(0.0, 1.0);
+    if (clamped - LEGACY_WEBCAM_OPACITY_DEFAULT).abs() < 1e-6 {
+        1.0
+    } else {
+        clamped
+    }
+}
+
+pub(crate) fn ffmpeg_loglevel() -> String {
+    std::env::var("GRABME_FFMPEG_LOGLEVEL")
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| "error".to_string())
+}
+
+pub(crate) fn ensure_cursor_icon_file() -> GrabmeResult<PathBuf> {
+    let icon_path = session_temp_dir()?.join("cursor-pointer-lucide.svg");
+    let desired = CURSOR_ICON_SVG.as_bytes();
+
+    let needs_write = match std::fs::read(&icon_path) {
+        Ok(existing) => existing != desired,
+        Err(_) => true,
+    };
+
+    if needs_write {
+        std::fs::write(&icon_path, desired).map_err(|e| {
+            GrabmeError::render(format!(
+                "Failed to materialize cursor icon {}: {e}",
+                icon_path.display()
+            ))
+        })?;
+    }
+
+    Ok(icon_path)
+}
+
+/// FFmpeg encoders an export to `format` uses.
+pub fn required_ffmpeg_encoders(format: ExportFormat) -> &'static [&'static str] {
+    match format {
+        ExportFormat::Mp4H264 => &["libx264", "aac"],
+        ExportFormat::Mp4H265 => &["libx265", "aac"],
+        ExportFormat::Gif => &["gif"],
+        ExportFormat::PngSequence => &["png"],
+        ExportFormat::Webm => &["libvpx-vp9", "libopus"],
+    }
+}
+
+fn codec_args_for_config(config: &ExportConfig) -> Vec<String> {
+    let video_bitrate = format!(
+        "{}k",
+        config
+            .video_bitrate_kbps
+            .max(crate::bitrate::MIN_VIDEO_BITRATE_KBPS)
+    );
     let audio_bitrate = format!("{}k", config.audio_bitrate_kbps.max(64));
 
-    match config.format {
+    let mut args = match config.format {
         ExportFormat::Mp4H264 => vec![
             "-c:v".to_string(),
             "libx264".to_string(),
@@ -2259,6 +4196,14 @@ fn codec_args_for_config(config: &ExportConfig) -> Vec<String> {
             "-vf".to_string(),
             "fps=15,split[s0][s1];[s0]palettegen[p];[s1][p]paletteuse".to_string(),
         ],
+        ExportFormat::PngSequence => vec![
+            "-c:v".to_string(),
+            "png".to_string(),
+            "-f".to_string(),
+            "image2".to_string(),
+            "-start_number".to_string(),
+            "0".to_string(),
+        ],
         ExportFormat::Webm => vec![
             "-c:v".to_string(),
             "libvpx-vp9".to_string(),
@@ -2269,10 +4214,39 @@ fn codec_args_for_config(config: &ExportConfig) -> Vec<String> {
             "-b:a".to_string(),
             "128k".to_string(),
         ],
+    };
+
+    if !matches!(config.format, ExportFormat::Gif | ExportFormat::PngSequence) {
+        args.extend(color_tag_args(OUTPUT_COLOR_RANGE));
     }
+    args
+}
+
+/// Output argument for ffmpeg: the file itself, or a numbered frame pattern
+/// inside the output directory for image sequences.
+fn export_output_target(config: &ExportConfig, output_path: &std::path::Path) -> String {
+    if config.format.is_image_sequence() {
+        output_path.join(PNG_SEQUENCE_PATTERN).display().to_string()
+    } else {
+        output_path.display().to_string()
+    }
+}
+
+/// Explicit BT.709 colorspace and range tags so players don't guess.
+fn color_tag_args(range: ColorRange) -> Vec<String> {
+    vec![
+        "-color_range".to_string(),
+        range.ffmpeg_name().to_string(),
+        "-colorspace".to_string(),
+        "bt709".to_string(),
+        "-color_primaries".to_string(),
+        "bt709".to_string(),
+        "-color_trc".to_string(),
+        "bt709".to_string(),
+    ]
 }
 
-fn command_exists(binary: &str) -> bool {
+pub(crate) fn command_exists(binary: &str) -> bool {
     Command::new("sh")
         .arg("-c")
         .arg(format!("command -v {binary} >/dev/null 2>&1"))
@@ -2281,7 +4255,74 @@ fn command_exists(binary: &str) -> bool {
         .unwrap_or(false)
 }
 
-fn resolve_screen_source(
+/// Timeline time the export starts at: the requested range start, moved
+/// past the pre-roll. An editor-set start offset wins over detection.
+/// Fast-copy exports never seek, so they only honor the requested start.
+fn export_start_secs(
+    job: &ExportJob,
+    project: &LoadedProject,
+    screen_path: &std::path::Path,
+    screen_offset_ns: i64,
+) -> f64 {
+    let requested = job.start_secs.unwrap_or(0.0).max(0.0);
+    if job.fast_copy {
+        return requested;
+    }
+    let preroll = match project.timeline.start_offset_secs {
+        Some(secs) => secs.max(0.0),
+        None if job.trim_preroll => detect_screen_preroll(screen_path, screen_offset_ns)
+            .unwrap_or_else(|e| {
+                tracing::warn!(error = %e, "Pre-roll detection failed; exporting from the start");
+                0.0
+            }),
+        None => 0.0,
+    };
+    if preroll > requested {
+        tracing::info!(start_secs = preroll, "Starting export after pre-roll");
+    }
+    requested.max(preroll)
+}
+
+/// Timeline time the export ends at: the requested range end, moved
+/// before the idle tail. An editor-set end offset wins over detection.
+/// Fast-copy exports only honor the requested end, like the start.
+fn export_end_secs(
+    job: &ExportJob,
+    project: &LoadedProject,
+    events: &[InputEvent],
+    mic: Option<(&std::path::Path, i64)>,
+    recording_end_secs: f64,
+) -> f64 {
+    let requested = job
+        .end_secs
+        .map_or(recording_end_secs, |end| end.min(recording_end_secs));
+    if job.fast_copy {
+        return requested;
+    }
+    let content_end = match project.timeline.end_offset_secs {
+        Some(secs) => secs.max(0.0),
+        None if job.trim_tail => match detect_recording_tail(events, mic, recording_end_secs) {
+            Ok(Some(tail)) => {
+                tracing::info!(
+                    end_secs = tail.end_secs,
+                    trimmed_secs = tail.trimmed_secs,
+                    reach = tail.reach,
+                    "Ending export before idle tail"
+                );
+                tail.end_secs
+            }
+            Ok(None) => recording_end_secs,
+            Err(e) => {
+                tracing::warn!(error = %e, "Tail detection failed; exporting to the end");
+                recording_end_secs
+            }
+        },
+        None => recording_end_secs,
+    };
+    requested.min(content_end)
+}
+
+pub(crate) fn resolve_screen_source(
     project_dir: &std::path::Path,
     project: &LoadedProject,
 ) -> GrabmeResult<(PathBuf, i64, f64)> {
@@ -2297,6 +4338,12 @@ fn resolve_screen_source(
         if declared_path.exists() {
             return Ok((declared_path, declared_offset_ns, declared_duration_secs));
         }
+        if project.project.source_deleted(&screen_track.path) {
+            return Err(GrabmeError::render(
+                "The raw screen recording was deleted by `grabme slim`; \
+                 this project can no longer be rendered",
+            ));
+        }
 
         tracing::warn!(
             path = %declared_path.display(),
@@ -2325,64 +4372,6 @@ fn resolve_screen_source(
     ))
 }
 
-fn probe_video_dimensions(path: &std::path::Path) -> Option<(u32, u32)> {
-    let output = Command::new("ffprobe")
-        .args([
-            "-v",
-            "error",
-            "-select_streams",
-            "v:0",
-            "-show_entries",
-            "stream=width,height",
-            "-of",
-            "csv=p=0:s=x",
-        ])
-        .arg(path)
-        .output()
-        .ok()?;
-
-    if !output.status.success() {
-        return None;
-    }
-
-    let raw = String::from_utf8(output.stdout).ok()?;
-    let line = raw.lines().next()?.trim();
-    let (w, h) = line.split_once('x')?;
-    let width = w.parse::<u32>().ok()?;
-    let height = h.parse::<u32>().ok()?;
-    if width == 0 || height == 0 {
-        return None;
-    }
-    Some((width, height))
-}
-
-fn probe_media_duration(path: &std::path::Path) -> Option<f64> {
-    let output = Command::new("ffprobe")
-        .args([
-            "-v",
-            "error",
-            "-show_entries",
-            "format=duration",
-            "-of",
-            "default=noprint_wrappers=1:nokey=1",
-        ])
-        .arg(path)
-        .output()
-        .ok()?;
-
-    if !output.status.success() {
-        return None;
-    }
-
-    let raw = String::from_utf8(output.stdout).ok()?;
-    let duration = raw.lines().next()?.trim().parse::<f64>().ok()?;
-    if !duration.is_finite() || duration <= 0.0 {
-        return None;
-    }
-
-    Some(duration)
-}
-
 fn latest_event_timestamp_secs(events: &[InputEvent]) -> Option<f64> {
     let secs = events.last()?.timestamp_secs();
     if !secs.is_finite() || secs <= 0.0 {
@@ -2452,15 +4441,27 @@ fn progress_report(
 }
 
 /// Quick export to clipboard: render to temp file, then copy to clipboard.
+///
+/// GIFs land on the clipboard as images; videos as a file reference, which
+/// stays valid until the session temp directory is removed on exit.
 pub async fn export_to_clipboard(job: ExportJob) -> GrabmeResult<()> {
-    let temp_path = std::env::temp_dir().join("grabme_clipboard_export.mp4");
+    if job.config.format.is_image_sequence() {
+        return Err(GrabmeError::unsupported(
+            "Image sequences can't be copied to the clipboard",
+        ));
+    }
+    let extension = match job.config.format {
+        ExportFormat::Gif => "gif",
+        ExportFormat::Webm => "webm",
+        _ => "mp4",
+    };
+    let temp_path = session_temp_dir()?.join(format!("clipboard-export.{extension}"));
     let mut clipboard_job = job;
     clipboard_job.output_path = temp_path.clone();
 
     export_project(clipboard_job, None).await?;
 
-    // TODO: Use xclip/wl-copy to put file handle in clipboard
-    tracing::info!(path = %temp_path.display(), "Exported to clipboard");
+    crate::clipboard::copy_file_to_clipboard(&temp_path)?;
     Ok(())
 }
 
@@ -2524,6 +4525,7 @@ mod tests {
                 viewport: grabme_project_model::viewport::Viewport::new(0.0, 0.0, 1.0, 1.0),
                 easing: grabme_project_model::timeline::EasingFunction::EaseInOut,
                 source: grabme_project_model::timeline::KeyframeSource::Auto,
+                confidence: None,
             });
         timeline
             .keyframes
@@ -2532,6 +4534,7 @@ mod tests {
                 viewport: grabme_project_model::viewport::Viewport::new(0.4, 0.2, 0.6, 0.6),
                 easing: grabme_project_model::timeline::EasingFunction::Linear,
                 source: grabme_project_model::timeline::KeyframeSource::Auto,
+                confidence: None,
             });
 
         let points = sample_viewport_points(&timeline, 10.0, 7);
@@ -2544,6 +4547,43 @@ mod tests {
         assert!(points[3].1.w < 1.0);
     }
 
+    #[test]
+    fn test_sample_viewport_points_spend_budget_on_eased_transitions() {
+        use grabme_project_model::timeline::{
+            CameraKeyframe, EasingFunction, KeyframeSource, Timeline,
+        };
+        use grabme_project_model::viewport::Viewport;
+
+        let zoomed = Viewport::new(0.25, 0.25, 0.5, 0.5);
+        let mut timeline = Timeline::new();
+        timeline.keyframes.clear();
+        for (time_secs, viewport, easing) in [
+            (0.0, Viewport::FULL, EasingFunction::Linear),
+            (4.0, zoomed, EasingFunction::Linear),
+            (6.0, zoomed, EasingFunction::SPRING),
+            (8.0, Viewport::FULL, EasingFunction::Linear),
+        ] {
+            timeline.keyframes.push(CameraKeyframe {
+                time_secs,
+                viewport,
+                easing,
+                source: KeyframeSource::Manual,
+                confidence: None,
+            });
+        }
+
+        let points = sample_viewport_points(&timeline, 10.0, 20);
+        assert_eq!(points.len(), 20);
+        for kf in &timeline.keyframes {
+            assert!(points.iter().any(|(t, _)| (t - kf.time_secs).abs() < 1e-9));
+        }
+        // The linear move and the holds need no interior points.
+        let in_spring = points.iter().filter(|(t, _)| *t > 6.0 && *t < 8.0).count();
+        assert_eq!(in_spring, 15);
+        // The bounce zooms past the full frame.
+        assert!(points.iter().any(|(_, vp)| vp.w > 1.0));
+    }
+
     #[test]
     fn test_sample_viewport_points_prefers_latest_duplicate_time() {
         let mut timeline = grabme_project_model::timeline::Timeline::new();
@@ -2555,6 +4595,7 @@ mod tests {
                 viewport: grabme_project_model::viewport::Viewport::FULL,
                 easing: grabme_project_model::timeline::EasingFunction::Linear,
                 source: grabme_project_model::timeline::KeyframeSource::Auto,
+                confidence: None,
             });
         timeline
             .keyframes
@@ -2563,6 +4604,7 @@ mod tests {
                 viewport: grabme_project_model::viewport::Viewport::new(0.2, 0.2, 0.6, 0.6),
                 easing: grabme_project_model::timeline::EasingFunction::Linear,
                 source: grabme_project_model::timeline::KeyframeSource::Manual,
+                confidence: None,
             });
 
         let points = sample_viewport_points(&timeline, 2.0, 4);
@@ -2629,6 +4671,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_cursor_interpolation_curves_only_above_pointer_rate() {
+        assert_eq!(
+            CursorInterpolation::for_rates(120, 60),
+            CursorInterpolation::CatmullRom
+        );
+        assert_eq!(
+            CursorInterpolation::for_rates(60, 60),
+            CursorInterpolation::Linear
+        );
+        assert_eq!(
+            CursorInterpolation::for_rates(120, 0),
+            CursorInterpolation::Linear
+        );
+
+        // Without a header the rate is measured from the samples.
+        let smoothed: Vec<(u64, f64, f64)> = (0..10u64)
+            .map(|i| (i * 16_666_667, 0.5, 0.5))
+            .chain(std::iter::once((5_000_000_000, 0.5, 0.5)))
+            .collect();
+        assert_eq!(pointer_sample_rate_hz(None, &smoothed), 60);
+        assert_eq!(pointer_sample_rate_hz(None, &[]), 0);
+    }
+
     #[test]
     fn test_sample_cursor_points_applies_viewport_projection() {
         let mut timeline = grabme_project_model::timeline::Timeline::new();
@@ -2640,39 +4706,201 @@ mod tests {
                 viewport: grabme_project_model::viewport::Viewport::new(0.25, 0.25, 0.5, 0.5),
                 easing: grabme_project_model::timeline::EasingFunction::Linear,
                 source: grabme_project_model::timeline::KeyframeSource::Auto,
+                confidence: None,
             });
 
         let smoothed = vec![(0u64, 0.25, 0.25), (1_000_000_000u64, 0.25, 0.25)];
-        let points = sample_cursor_points(&smoothed, &timeline, 1920, 1080, 1.0, 2);
+        let points = sample_cursor_points(
+            &smoothed,
+            &timeline,
+            1920,
+            1080,
+            1.0,
+            2,
+            CursorInterpolation::Linear,
+        );
+
+        assert!(!points.is_empty());
+        assert!(points[0].1.abs() < 1e-6);
+        assert!(points[0].2.abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_build_cursor_trail_plan_generates_layers_when_enabled() {
+        let points = vec![
+            (0.0, 100.0, 100.0),
+            (0.05, 180.0, 120.0),
+            (0.10, 260.0, 150.0),
+            (0.15, 320.0, 180.0),
+        ];
+        let config = grabme_project_model::timeline::CursorMotionTrailConfig {
+            enabled: true,
+            ghost_count: 3,
+            speed_threshold: 0.01,
+            frame_spacing: 1,
+        };
+
+        let plan = build_cursor_trail_plan(&points, &config, 60, 1920, 1080)
+            .expect("trail plan should be generated");
+        assert_eq!(plan.layers.len(), 3);
+        assert!(plan.layers[0].x_expr.contains("if("));
+    }
+
+    #[test]
+    fn test_build_filter_graph_webcam_uses_aspect_safe_scaling() {
+        let config = grabme_project_model::project::Project::new("test", 1920, 1080, 60).export;
+
+        let graph = build_filter_graph(
+            &config,
+            "0",
+            "0",
+            "1",
+            "1",
+            "100",
+            "200",
+            Some(1),
+            Some(2),
+            &[],
+            &[],
+            None,
+            None,
+            None,
+            false,
+            ColorRange::Limited,
+            None,
+            None,
+            None,
+        );
+
+        assert!(graph.contains("force_original_aspect_ratio=decrease"));
+        assert!(graph.contains("pad="));
+        assert!(graph.contains("eof_action=pass"));
+    }
+
+    #[test]
+    fn test_build_filter_graph_sizes_webcam_to_its_recorded_format() {
+        let config = grabme_project_model::project::Project::new("test", 1920, 1080, 60).export;
+
+        let graph = build_filter_graph(
+            &config,
+            "0",
+            "0",
+            "1",
+            "1",
+            "100",
+            "200",
+            Some(1),
+            Some(2),
+            &[],
+            &[],
+            Some((1080, 1920)),
+            None,
+            None,
+            false,
+            ColorRange::Limited,
+            None,
+            None,
+            None,
+        );
+
+        assert!(graph.contains("[2:v]scale=w=146:h=258:flags=lanczos,setsar=1,format=yuva420p"));
+        assert!(!graph.contains("color=black@0,format=yuva420p"));
+    }
+
+    #[test]
+    fn test_build_waveform_graph_draws_mixed_audio_under_webcam() {
+        let mut config = grabme_project_model::project::Project::new("test", 1920, 1080, 30).export;
+        config.waveform.enabled = true;
+        config.webcam.enabled = true;
+
+        let (graph, audio_map) =
+            build_waveform_graph(&config, 30, Some(2), Some(3), None, Some(1), &[], None).unwrap();
+        assert!(graph.starts_with("[2:a:0]aresample"));
+        assert!(graph.contains("[aout]asplit=2[awave][awaveout]"));
+        assert!(graph.contains("showwaves=s="));
+        assert!(graph.contains("colors=0xffffff"));
+        let wave = graph.find("[bg][wave]overlay").unwrap();
+        let webcam = graph.find("[scene][webcam]overlay").unwrap();
+        assert!(wave < webcam);
+        assert!(graph.ends_with("[vout]"));
+        assert_eq!(audio_map.as_deref(), Some("[awaveout]"));
+
+        config.waveform.style = WaveformStyle::Spectrum;
+        config.format = ExportFormat::PngSequence;
+        let (graph, audio_map) =
+            build_waveform_graph(&config, 30, Some(2), None, None, None, &[], None).unwrap();
+        assert!(graph.starts_with("[2:a:0]aresample=48000:"));
+        assert!(graph.contains("[aout];[aout]anull[awave];"));
+        assert!(graph.contains("showspectrum="));
+        assert_eq!(audio_map, None);
+
+        let err = build_waveform_graph(&config, 30, None, None, None, None, &[], None).unwrap_err();
+        assert!(err.to_string().contains("needs a mic"));
+    }
+
+    #[test]
+    fn test_build_filter_graph_draws_strokes_before_zoom() {
+        let config = grabme_project_model::project::Project::new("test", 1920, 1080, 60).export;
+
+        let graph = build_filter_graph(
+            &config,
+            "0",
+            "0",
+            "1",
+            "1",
+            "100",
+            "200",
+            Some(1),
+            None,
+            &[],
+            &[],
+            None,
+            None,
+            None,
+            false,
+            ColorRange::Limited,
+            Some(std::path::Path::new("/tmp/strokes.ass")),
+            None,
+            None,
+        );
 
-        assert!(!points.is_empty());
-        assert!(points[0].1.abs() < 1e-6);
-        assert!(points[0].2.abs() < 1e-6);
+        assert!(graph.starts_with("color="));
+        assert!(graph.contains("[0:v]subtitles=filename=/tmp/strokes.ass[screen_strokes];"));
+        assert!(graph.contains("[screen_strokes]scale="));
     }
 
     #[test]
-    fn test_build_cursor_trail_plan_generates_layers_when_enabled() {
-        let points = vec![
-            (0.0, 100.0, 100.0),
-            (0.05, 180.0, 120.0),
-            (0.10, 260.0, 150.0),
-            (0.15, 320.0, 180.0),
-        ];
-        let config = grabme_project_model::timeline::CursorMotionTrailConfig {
-            enabled: true,
-            ghost_count: 3,
-            speed_threshold: 0.01,
-            frame_spacing: 1,
-        };
+    fn test_build_filter_graph_draws_debug_overlay_over_strokes() {
+        let config = grabme_project_model::project::Project::new("test", 1920, 1080, 60).export;
 
-        let plan = build_cursor_trail_plan(&points, &config, 60, 1920, 1080)
-            .expect("trail plan should be generated");
-        assert_eq!(plan.layers.len(), 3);
-        assert!(plan.layers[0].x_expr.contains("if("));
+        let graph = build_filter_graph(
+            &config,
+            "0",
+            "0",
+            "1",
+            "1",
+            "100",
+            "200",
+            Some(1),
+            None,
+            &[],
+            &[],
+            None,
+            None,
+            None,
+            false,
+            ColorRange::Limited,
+            Some(std::path::Path::new("/tmp/strokes.ass")),
+            None,
+            Some(std::path::Path::new("/tmp/debug.ass")),
+        );
+
+        assert!(graph.contains("[screen_strokes]subtitles=filename=/tmp/debug.ass[screen_debug];"));
+        assert!(graph.contains("[screen_debug]scale="));
     }
 
     #[test]
-    fn test_build_filter_graph_webcam_uses_aspect_safe_scaling() {
+    fn test_build_filter_graph_draws_reactions_between_strokes_and_debug() {
         let config = grabme_project_model::project::Project::new("test", 1920, 1080, 60).export;
 
         let graph = build_filter_graph(
@@ -2683,16 +4911,25 @@ mod tests {
             "1",
             "100",
             "200",
-            1,
-            Some(2),
+            Some(1),
+            None,
+            &[],
+            &[],
+            None,
             None,
             None,
             false,
+            ColorRange::Limited,
+            Some(std::path::Path::new("/tmp/strokes.ass")),
+            Some(std::path::Path::new("/tmp/reactions.ass")),
+            Some(std::path::Path::new("/tmp/debug.ass")),
         );
 
-        assert!(graph.contains("force_original_aspect_ratio=decrease"));
-        assert!(graph.contains("pad="));
-        assert!(graph.contains("eof_action=pass"));
+        assert!(graph
+            .contains("[screen_strokes]subtitles=filename=/tmp/reactions.ass[screen_reactions];"));
+        assert!(
+            graph.contains("[screen_reactions]subtitles=filename=/tmp/debug.ass[screen_debug];")
+        );
     }
 
     #[test]
@@ -2700,7 +4937,25 @@ mod tests {
         let config = grabme_project_model::project::Project::new("test", 1920, 1080, 60).export;
 
         let graph = build_filter_graph(
-            &config, "0.1", "0.1", "0.83", "0.83", "100", "200", 1, None, None, None, false,
+            &config,
+            "0.1",
+            "0.1",
+            "0.83",
+            "0.83",
+            "100",
+            "200",
+            Some(1),
+            None,
+            &[],
+            &[],
+            None,
+            None,
+            None,
+            false,
+            ColorRange::Limited,
+            None,
+            None,
+            None,
         );
 
         assert!(graph.contains("scale=w='max(2,trunc((1920/(0.83))/2)*2)'"));
@@ -2712,7 +4967,25 @@ mod tests {
         let config = grabme_project_model::project::Project::new("test", 1920, 1080, 60).export;
 
         let graph = build_filter_graph(
-            &config, "-0.2", "0.1", "0.8", "0.8", "100", "200", 1, None, None, None, true,
+            &config,
+            "-0.2",
+            "0.1",
+            "0.8",
+            "0.8",
+            "100",
+            "200",
+            Some(1),
+            None,
+            &[],
+            &[],
+            None,
+            None,
+            None,
+            true,
+            ColorRange::Limited,
+            None,
+            None,
+            None,
         );
 
         assert!(graph.contains("[bg][screen_scaled]overlay"));
@@ -2720,6 +4993,64 @@ mod tests {
         assert!(!graph.contains("[shadow_layer]"));
     }
 
+    #[test]
+    fn test_build_filter_graph_converts_full_range_source_to_limited() {
+        let config = grabme_project_model::project::Project::new("test", 1920, 1080, 60).export;
+
+        let graph = build_filter_graph(
+            &config,
+            "0",
+            "0",
+            "1",
+            "1",
+            "100",
+            "200",
+            Some(1),
+            None,
+            &[],
+            &[],
+            None,
+            None,
+            None,
+            false,
+            ColorRange::Full,
+            None,
+            None,
+            None,
+        );
+
+        assert!(graph.contains("flags=lanczos:in_range=pc:out_range=tv"));
+    }
+
+    #[test]
+    fn test_codec_args_tag_output_color_range() {
+        let mut config = grabme_project_model::project::Project::new("test", 1920, 1080, 60).export;
+        let args = codec_args_for_config(&config);
+        let range_at = args.iter().position(|arg| arg == "-color_range").unwrap();
+        assert_eq!(args[range_at + 1], "tv");
+        assert!(args.iter().any(|arg| arg == "-colorspace"));
+
+        config.format = ExportFormat::Gif;
+        assert!(!codec_args_for_config(&config)
+            .iter()
+            .any(|arg| arg == "-color_range"));
+    }
+
+    #[test]
+    fn test_png_sequence_writes_numbered_frames_without_audio_codec() {
+        let mut config = grabme_project_model::project::Project::new("test", 1920, 1080, 60).export;
+        config.format = ExportFormat::PngSequence;
+
+        let args = codec_args_for_config(&config);
+        assert!(args.windows(2).any(|w| w[0] == "-c:v" && w[1] == "png"));
+        assert!(args.windows(2).any(|w| w[0] == "-f" && w[1] == "image2"));
+        assert!(!args.iter().any(|arg| arg == "-c:a"));
+        assert!(!args.iter().any(|arg| arg == "-color_range"));
+
+        let target = export_output_target(&config, std::path::Path::new("/tmp/frames"));
+        assert_eq!(target, "/tmp/frames/frame_%06d.png");
+    }
+
     #[test]
     fn test_viewport_scale_is_dynamic_detects_size_changes() {
         let static_points = vec![
@@ -2770,17 +5101,196 @@ mod tests {
             "1",
             "100",
             "200",
-            1,
+            Some(1),
             Some(2),
+            &[],
+            &[],
+            None,
             None,
             None,
             false,
+            ColorRange::Limited,
+            None,
+            None,
+            None,
         );
 
         assert!(graph.contains(";[scene]null[vout]"));
         assert!(!graph.contains("[2:v]scale"));
     }
 
+    #[test]
+    fn test_build_filter_graph_skips_cursor_captured_in_frames() {
+        let config = grabme_project_model::project::Project::new("test", 1920, 1080, 60).export;
+
+        let graph = build_filter_graph(
+            &config,
+            "0",
+            "0",
+            "1",
+            "1",
+            "100",
+            "200",
+            None,
+            Some(1),
+            &[],
+            &[],
+            None,
+            None,
+            None,
+            false,
+            ColorRange::Limited,
+            None,
+            None,
+            None,
+        );
+
+        assert!(!graph.contains("cursor_sprite"));
+        assert!(graph.contains("[base]null[scene];[1:v]"));
+        assert!(graph.ends_with("[vout]"));
+    }
+
+    #[test]
+    fn test_build_filter_graph_slides_webcam_between_corners() {
+        let config = grabme_project_model::project::Project::new("test", 1920, 1080, 60).export;
+        let corners = [WebcamCornerSwitch {
+            at_secs: 2.0,
+            corner: WebcamCorner::TopLeft,
+        }];
+        let graph = build_filter_graph(
+            &config,
+            "0",
+            "0",
+            "1",
+            "1",
+            "100",
+            "200",
+            Some(1),
+            Some(2),
+            &[],
+            &corners,
+            None,
+            None,
+            None,
+            false,
+            ColorRange::Limited,
+            None,
+            None,
+            None,
+        );
+
+        assert!(graph.contains("[scene][webcam]overlay=x='if(lt(t,2.000000),1402.000000"));
+        assert!(graph.contains(":eval=frame:eof_action=pass[vout]"));
+        assert!(!graph.contains("x=W-w-"));
+    }
+
+    #[test]
+    fn test_build_filter_graph_covers_webcam_gaps() {
+        let mut config = grabme_project_model::project::Project::new("test", 1920, 1080, 60).export;
+        let build = |config: &ExportConfig| {
+            build_filter_graph(
+                config,
+                "0",
+                "0",
+                "1",
+                "1",
+                "100",
+                "200",
+                Some(1),
+                Some(2),
+                &[(1.5, 4.0), (10.0, 12.25)],
+                &[],
+                None,
+                None,
+                None,
+                false,
+                ColorRange::Limited,
+                None,
+                None,
+                None,
+            )
+        };
+
+        let frozen = build(&config);
+        assert!(frozen
+            .contains("[2:v]select='not(between(t,1.500,4.000)+between(t,10.000,12.250))',scale="));
+        assert!(!frozen.contains(":enable="));
+
+        config.webcam.gap_mode = WebcamGapMode::Hide;
+        let hidden = build(&config);
+        assert!(!hidden.contains("select="));
+        assert!(hidden.contains(
+            "eof_action=pass:enable='not(between(t,1.500,4.000)+between(t,10.000,12.250))'[vout]"
+        ));
+    }
+
+    #[test]
+    fn test_webcam_gap_spans_follow_track_offset() {
+        let gaps = [
+            WebcamGap {
+                start_ns: 2_000_000_000,
+                end_ns: 3_000_000_000,
+            },
+            WebcamGap {
+                start_ns: 100_000_000,
+                end_ns: 200_000_000,
+            },
+        ];
+        assert_eq!(
+            webcam_gap_spans(&gaps, 500_000_000),
+            vec![(2.5, 3.5), (0.6, 0.7)]
+        );
+        // Starting 0.5s before the screen puts the second gap before the
+        // export's zero, where it is clipped to nothing.
+        assert_eq!(webcam_gap_spans(&gaps, -500_000_000), vec![(1.5, 2.5)]);
+    }
+
+    #[test]
+    fn test_apply_resolution_changes_uses_screen_track_time() {
+        let mut recording =
+            grabme_project_model::project::Project::new("test", 1920, 1080, 60).recording;
+        recording.resolution_changes = vec![grabme_project_model::project::ResolutionChange {
+            t_ns: 2_000_000_000,
+            width: 960,
+            height: 1080,
+        }];
+        let mut events = vec![
+            InputEvent::pointer(2_000_000_000, 0.5, 0.5),
+            InputEvent::click(
+                2_600_000_000,
+                grabme_project_model::event::MouseButton::Left,
+                grabme_project_model::event::ButtonState::Down,
+                0.0,
+                0.5,
+            ),
+        ];
+        // The screen track starts 0.5s into the event clock.
+        apply_resolution_changes(&mut events, &recording, 500_000_000, 1920, 1080);
+
+        assert_eq!(events[0].pointer_position(), Some((0.5, 0.5)));
+        // A 960 wide display sits unscaled in the middle half of the frame.
+        assert_eq!(events[1].pointer_position(), Some((0.25, 0.5)));
+    }
+
+    #[test]
+    fn test_apply_frame_times_follows_track_drift() {
+        // The track's PTS gains 40ms on the recording clock over four seconds.
+        let frame_times = FrameTimes::parse(
+            "{\"frame\":1,\"pts_ns\":0,\"wall_ns\":1000000000}\n\
+             {\"frame\":241,\"pts_ns\":4040000000,\"wall_ns\":5000000000}\n",
+        )
+        .unwrap();
+        let mut events = vec![
+            InputEvent::pointer(500_000_000, 0.5, 0.5),
+            InputEvent::pointer(3_000_000_000, 0.5, 0.5),
+            InputEvent::pointer(8_000_000_000, 0.5, 0.5),
+        ];
+        apply_frame_times(&mut events, &frame_times);
+
+        let timestamps: Vec<u64> = events.iter().map(|event| event.timestamp_ns).collect();
+        assert_eq!(timestamps, vec![500_000_000, 3_020_000_000, 8_040_000_000]);
+    }
+
     #[test]
     fn test_append_input_with_offset_includes_itsoffset() {
         let mut args = Vec::new();
@@ -2814,13 +5324,149 @@ mod tests {
     #[test]
     fn test_append_audio_mix_if_needed_builds_dual_source_mix() {
         let mut filter = "[scene]null[vout]".to_string();
-        let map = append_audio_mix_if_needed(&mut filter, Some(3), Some(4));
+        let map = append_audio_mix_if_needed(&mut filter, Some(3), Some(4), None);
         assert_eq!(map, "[aout]");
         assert!(filter.contains("amix=inputs=2"));
         assert!(filter.contains("[3:a:0]"));
         assert!(filter.contains("[4:a:0]"));
     }
 
+    #[test]
+    fn test_fast_copy_args_copy_video_and_mix_audio() {
+        let args = fast_copy_ffmpeg_args(
+            std::path::Path::new("/tmp/screen.mkv"),
+            &[
+                (std::path::Path::new("/tmp/mic.wav"), 0),
+                (std::path::Path::new("/tmp/system.wav"), 50_000_000),
+            ],
+            12.5,
+            192,
+            std::path::Path::new("/tmp/clip.mp4"),
+        );
+
+        let copy_at = args.iter().position(|arg| arg == "-c:v").unwrap();
+        assert_eq!(args[copy_at + 1], "copy");
+        let filter_at = args
+            .iter()
+            .position(|arg| arg == "-filter_complex")
+            .unwrap();
+        assert!(args[filter_at + 1].starts_with("[1:a:0]"));
+        assert!(args[filter_at + 1].contains("amix=inputs=2"));
+        assert!(args
+            .windows(2)
+            .any(|w| w[0] == "-itsoffset" && w[1] == "0.050000"));
+        assert!(args.windows(2).any(|w| w[0] == "-map" && w[1] == "[aout]"));
+        assert_eq!(args.last().unwrap(), "/tmp/clip.mp4");
+    }
+
+    #[test]
+    fn test_fast_copy_args_without_audio_maps_optional_screen_audio() {
+        let args = fast_copy_ffmpeg_args(
+            std::path::Path::new("/tmp/screen.mkv"),
+            &[],
+            5.0,
+            192,
+            std::path::Path::new("/tmp/clip.mp4"),
+        );
+        assert!(!args.iter().any(|arg| arg == "-filter_complex"));
+        assert!(args.windows(2).any(|w| w[0] == "-map" && w[1] == "0:a?"));
+    }
+
+    #[test]
+    fn test_append_audio_mix_ducks_music_under_mic() {
+        let mut filter = "[scene]null[vout]".to_string();
+        let music = MusicMix {
+            input_index: 4,
+            gain_db: -18.0,
+            fade_in_secs: 1.5,
+            fade_out_secs: 2.0,
+            ducking: true,
+            duration_secs: 30.0,
+        };
+        let map = append_audio_mix_if_needed(&mut filter, Some(3), None, Some(&music));
+        assert_eq!(map, "[aout]");
+        assert!(filter.contains("asplit=2[amic][aduckkey]"));
+        assert!(filter.contains("[4:a:0]atrim=0:30.000000"));
+        assert!(filter.contains("volume=-18.00dB"));
+        assert!(filter.contains("afade=t=out:st=28.000000:d=2.000"));
+        assert!(filter.contains("[amusicbed][aduckkey]sidechaincompress"));
+        assert!(filter.contains("[amic][amusic]amix=inputs=2"));
+    }
+
+    #[test]
+    fn test_append_audio_mix_music_only_maps_music_label() {
+        let mut filter = "[scene]null[vout]".to_string();
+        let music = MusicMix {
+            input_index: 2,
+            gain_db: -12.0,
+            fade_in_secs: 0.0,
+            fade_out_secs: 0.0,
+            ducking: true,
+            duration_secs: 10.0,
+        };
+        let map = append_audio_mix_if_needed(&mut filter, None, None, Some(&music));
+        assert_eq!(map, "[amusic]");
+        assert!(!filter.contains("afade"));
+        assert!(!filter.contains("sidechaincompress"));
+        assert!(!filter.contains("amix"));
+    }
+
+    #[test]
+    fn test_append_subtitle_burn_relabels_final_video_and_escapes_path() {
+        let mut filter = "[scene]null[vout]".to_string();
+        append_subtitle_burn(
+            &mut filter,
+            std::path::Path::new("/tmp/it's:here/cache/subtitles.ass"),
+        );
+        assert_eq!(
+            filter,
+            r"[scene]null[vsub];[vsub]subtitles=filename=/tmp/it\\\'s\\:here/cache/subtitles.ass[vout]"
+        );
+    }
+
+    #[test]
+    fn test_append_clock_burn_escapes_the_expansion_for_drawtext() {
+        let mut config = grabme_project_model::project::Project::new("test", 1920, 1080, 60).export;
+        config.clock.format = "%H:%M".to_string();
+        let mut filter = "[scene]null[vout]".to_string();
+        append_clock_burn(&mut filter, &config, 100.0);
+
+        assert_eq!(
+            filter,
+            format!(
+                r"[scene]null[vclock];[vclock]drawtext=text=%{{pts\\:localtime\\:100.000\\:%H\\\\\\:%M}}:{}[vout]",
+                clock_layout(&config.clock, 1080)
+            )
+        );
+    }
+
+    #[test]
+    fn test_append_overscan_pad_shrinks_into_safe_area() {
+        let mut config = grabme_project_model::project::Project::new("test", 1920, 1080, 60).export;
+        config.canvas.background = "#112233".to_string();
+        config.overscan.margin_ratio = 0.5;
+        let mut filter = "[scene]null[vout]".to_string();
+        append_overscan_pad(&mut filter, &config);
+
+        // The margin is capped at 10% per side.
+        assert_eq!(
+            filter,
+            "[scene]null[vsafe];[vsafe]scale=w=1536:h=864:flags=lanczos,pad=1920:1080:(ow-iw)/2:(oh-ih)/2:color=0x112233[vout]"
+        );
+    }
+
+    #[test]
+    fn test_append_pip_overlay_places_layer_in_corner() {
+        let mut config = grabme_project_model::project::Project::new("test", 1920, 1080, 60).export;
+        config.pip.corner = WebcamCorner::BottomLeft;
+        config.pip.size_ratio = 0.5;
+        let mut filter = "[scene]null[vout]".to_string();
+        append_pip_overlay(&mut filter, 3, &config);
+
+        assert!(filter.starts_with("[scene]null[vpip_base];[3:v]scale=w=960:h=540:"));
+        assert!(filter.ends_with("[vpip_base][pip]overlay=x=58:y=H-h-32:eof_action=pass[vout]"));
+    }
+
     #[test]
     fn test_derive_monitor_precrop_from_virtual_bounds_metadata() {
         let recording = grabme_project_model::project::RecordingConfig {
@@ -2830,6 +5476,7 @@ mod tests {
             scale_factor: 1.0,
             display_server: grabme_project_model::project::DisplayServer::X11,
             cursor_hidden: true,
+            cursor_mode: None,
             monitor_index: 1,
             monitor_name: "DP-1".to_string(),
             monitor_x: 1920,
@@ -2842,7 +5489,14 @@ mod tests {
             virtual_height: 1440,
             monitors: vec![],
             pointer_coordinate_space: PointerCoordinateSpace::LegacyUnspecified,
+            window_track: None,
             audio_sample_rate: 48_000,
+            color_range: grabme_project_model::project::ColorRange::Limited,
+            audio_device_fallbacks: vec![],
+            webcam_gaps: vec![],
+            resolution_changes: vec![],
+            clock: Default::default(),
+            frame_pacing: Default::default(),
         };
 
         let crop = derive_monitor_precrop(&recording, 4480, 1440).expect("crop should be derived");
@@ -2861,6 +5515,7 @@ mod tests {
             scale_factor: 1.0,
             display_server: grabme_project_model::project::DisplayServer::X11,
             cursor_hidden: true,
+            cursor_mode: None,
             monitor_index: 1,
             monitor_name: "HDMI-1".to_string(),
             monitor_x: 1700,
@@ -2873,7 +5528,14 @@ mod tests {
             virtual_height: 1080,
             monitors: vec![],
             pointer_coordinate_space: PointerCoordinateSpace::LegacyUnspecified,
+            window_track: None,
             audio_sample_rate: 48_000,
+            color_range: grabme_project_model::project::ColorRange::Limited,
+            audio_device_fallbacks: vec![],
+            webcam_gaps: vec![],
+            resolution_changes: vec![],
+            clock: Default::default(),
+            frame_pacing: Default::default(),
         };
 
         let crop = derive_monitor_precrop(&recording, 1920, 1080).expect("crop should be derived");
@@ -2892,6 +5554,7 @@ mod tests {
             scale_factor: 1.0,
             display_server: grabme_project_model::project::DisplayServer::X11,
             cursor_hidden: true,
+            cursor_mode: None,
             monitor_index: 1,
             monitor_name: "HDMI-2".to_string(),
             monitor_x: 5000,
@@ -2904,13 +5567,125 @@ mod tests {
             virtual_height: 2160,
             monitors: vec![],
             pointer_coordinate_space: PointerCoordinateSpace::LegacyUnspecified,
+            window_track: None,
             audio_sample_rate: 48_000,
+            color_range: grabme_project_model::project::ColorRange::Limited,
+            audio_device_fallbacks: vec![],
+            webcam_gaps: vec![],
+            resolution_changes: vec![],
+            clock: Default::default(),
+            frame_pacing: Default::default(),
         };
 
         let crop = derive_monitor_precrop(&recording, 1920, 1080);
         assert!(crop.is_none());
     }
 
+    #[test]
+    fn test_fit_viewport_aspect_follows_focus_inside_viewport() {
+        let source_aspect = 16.0 / 9.0;
+        let vertical = fit_viewport_aspect(
+            Viewport::FULL,
+            CaptureNorm::new(0.9, 0.5),
+            source_aspect,
+            9.0 / 16.0,
+        );
+        let expected_w = (9.0 / 16.0) / source_aspect;
+        assert!((vertical.w - expected_w).abs() < 1e-9);
+        assert!((vertical.h - 1.0).abs() < 1e-9);
+        assert!((vertical.x - (1.0 - expected_w)).abs() < 1e-9);
+
+        let zoomed = Viewport::new(0.2, 0.2, 0.5, 0.5);
+        let square = fit_viewport_aspect(zoomed, CaptureNorm::new(0.3, 0.4), source_aspect, 1.0);
+        assert!((square.w * source_aspect / square.h - 1.0).abs() < 1e-9);
+        assert!((square.x - 0.2).abs() < 1e-9);
+        assert!((square.y - 0.2).abs() < 1e-9);
+
+        // A portrait screen in a landscape output loses height instead.
+        let wide = fit_viewport_aspect(
+            Viewport::FULL,
+            CaptureNorm::new(0.5, 0.5),
+            9.0 / 16.0,
+            16.0 / 9.0,
+        );
+        assert!((wide.w - 1.0).abs() < 1e-9);
+        assert!((wide.y + wide.h / 2.0 - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_reframe_keyframes_pan_to_the_cursor() {
+        let timeline = Timeline::new();
+        let cursor = vec![(200_000_000, 0.1, 0.5), (4_800_000_000, 0.9, 0.5)];
+        let keyframes = reframe_keyframes(&timeline, &cursor, 16.0 / 9.0, 1.0, 5.0);
+
+        assert!(keyframes.len() >= 5);
+        assert!(keyframes.len() <= MAX_VIEWPORT_EXPR_POINTS);
+        assert!(keyframes
+            .iter()
+            .all(|keyframe| keyframe.easing == EasingFunction::Linear));
+        let first = keyframes.first().unwrap().viewport;
+        let last = keyframes.last().unwrap().viewport;
+        assert!((first.x - 0.0).abs() < 1e-9);
+        assert!((last.x + last.w - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_variant_jobs_resize_and_rename_outputs() {
+        let job = ExportJob {
+            project_dir: PathBuf::from("/tmp/demo"),
+            output_path: PathBuf::from("/tmp/demo/exports/output.mp4"),
+            config: grabme_project_model::project::Project::new("test", 1920, 1080, 60).export,
+            start_secs: None,
+            end_secs: None,
+            trim_preroll: false,
+            trim_tail: false,
+            debug_overlay: false,
+            resources: ExportResources::default(),
+            fast_copy: false,
+            draft: None,
+        };
+
+        let vertical = job.variant(AspectMode::Portrait);
+        assert_eq!(
+            (vertical.config.width, vertical.config.height),
+            (1080, 1920)
+        );
+        assert_eq!(vertical.config.aspect_mode, AspectMode::Portrait);
+        assert_eq!(
+            vertical.output_path,
+            PathBuf::from("/tmp/demo/exports/output-vertical.mp4")
+        );
+        assert_eq!(
+            variant_output_path(Path::new("exports/frames"), AspectMode::Square),
+            PathBuf::from("exports/frames-square")
+        );
+    }
+
+    #[test]
+    fn test_draft_job_shrinks_and_speeds_up_encode() {
+        let mut config = grabme_project_model::project::Project::new("test", 1920, 1080, 60).export;
+        config.fps = 60;
+        let job = ExportJob {
+            project_dir: PathBuf::from("/tmp/demo"),
+            output_path: PathBuf::from("/tmp/demo/exports/draft.mp4"),
+            config,
+            start_secs: None,
+            end_secs: None,
+            trim_preroll: false,
+            trim_tail: false,
+            debug_overlay: false,
+            resources: ExportResources::default(),
+            fast_copy: false,
+            draft: None,
+        }
+        .into_draft(DraftOptions { watermark: true });
+
+        assert_eq!((job.config.width, job.config.height), (960, 540));
+        assert_eq!(job.config.fps, 30);
+        assert_eq!(job.resources.preset.as_deref(), Some("ultrafast"));
+        assert_eq!(job.draft, Some(DraftOptions { watermark: true }));
+    }
+
     #[test]
     fn test_select_cursor_projection_prefers_explicit_schema_mapping() {
         let project = mock_project_with_geometry(0, 0, 2560, 1440, -1920, 0, 4480, 1440);
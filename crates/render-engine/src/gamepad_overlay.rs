@@ -0,0 +1,358 @@
+//! Gamepad diagram overlay.
+//!
+//! Draws a controller outline in a corner of the output with the buttons
+//! lit while they are held and the stick caps following the sticks. The
+//! diagram is an ASS script of vector drawings, shown from the first to
+//! the last gamepad event of the first pad in the recording.
+
+use std::collections::HashMap;
+
+use grabme_project_model::event::{ButtonState, EventKind, GamepadAxis, GamepadButton, InputEvent};
+use grabme_project_model::project::{GamepadOverlayConfig, WebcamCorner};
+
+use crate::subtitles::{ass_color, ass_drawing_header, format_ass_time};
+
+/// Design size of the diagram; everything below is laid out in these units.
+const DESIGN_W: f64 = 200.0;
+const DESIGN_H: f64 = 130.0;
+
+/// How long the diagram stays after the last gamepad event.
+const TAIL_SECS: f64 = 2.0;
+
+/// Analog trigger travel that counts as pressed.
+const TRIGGER_PRESSED: f64 = 0.5;
+
+/// How far a stick cap moves from centre at full deflection.
+const STICK_TRAVEL: f64 = 6.0;
+
+const BODY: &[(f64, f64)] = &[
+    (30.0, 20.0),
+    (170.0, 20.0),
+    (196.0, 60.0),
+    (195.0, 100.0),
+    (170.0, 122.0),
+    (150.0, 102.0),
+    (130.0, 82.0),
+    (70.0, 82.0),
+    (50.0, 102.0),
+    (30.0, 122.0),
+    (5.0, 100.0),
+    (4.0, 60.0),
+];
+
+#[derive(Debug, Clone, Copy)]
+enum Shape {
+    Circle { cx: f64, cy: f64, r: f64 },
+    Rect { x0: f64, y0: f64, x1: f64, y1: f64 },
+}
+
+fn button_shape(button: GamepadButton) -> Option<Shape> {
+    let circle = |cx, cy, r| Some(Shape::Circle { cx, cy, r });
+    let rect = |x0, y0, x1, y1| Some(Shape::Rect { x0, y0, x1, y1 });
+    match button {
+        GamepadButton::South => circle(150.0, 62.0, 7.0),
+        GamepadButton::East => circle(166.0, 46.0, 7.0),
+        GamepadButton::North => circle(150.0, 30.0, 7.0),
+        GamepadButton::West => circle(134.0, 46.0, 7.0),
+        GamepadButton::DpadUp => rect(44.0, 26.0, 56.0, 38.0),
+        GamepadButton::DpadDown => rect(44.0, 54.0, 56.0, 66.0),
+        GamepadButton::DpadLeft => rect(30.0, 40.0, 42.0, 52.0),
+        GamepadButton::DpadRight => rect(58.0, 40.0, 70.0, 52.0),
+        GamepadButton::LeftBumper => rect(30.0, 9.0, 70.0, 16.0),
+        GamepadButton::RightBumper => rect(130.0, 9.0, 170.0, 16.0),
+        GamepadButton::LeftTrigger => rect(36.0, 0.0, 64.0, 6.0),
+        GamepadButton::RightTrigger => rect(136.0, 0.0, 164.0, 6.0),
+        GamepadButton::Select => circle(86.0, 42.0, 4.0),
+        GamepadButton::Start => circle(114.0, 42.0, 4.0),
+        GamepadButton::Mode => circle(100.0, 58.0, 6.0),
+        GamepadButton::LeftStick => circle(75.0, 92.0, 12.0),
+        GamepadButton::RightStick => circle(125.0, 92.0, 12.0),
+        GamepadButton::Other(_) => None,
+    }
+}
+
+const DIAGRAM_BUTTONS: [GamepadButton; 17] = [
+    GamepadButton::South,
+    GamepadButton::East,
+    GamepadButton::North,
+    GamepadButton::West,
+    GamepadButton::DpadUp,
+    GamepadButton::DpadDown,
+    GamepadButton::DpadLeft,
+    GamepadButton::DpadRight,
+    GamepadButton::LeftBumper,
+    GamepadButton::RightBumper,
+    GamepadButton::LeftTrigger,
+    GamepadButton::RightTrigger,
+    GamepadButton::Select,
+    GamepadButton::Start,
+    GamepadButton::Mode,
+    GamepadButton::LeftStick,
+    GamepadButton::RightStick,
+];
+
+/// Maps design units onto output pixels.
+#[derive(Debug, Clone, Copy)]
+struct Placement {
+    x: f64,
+    y: f64,
+    scale: f64,
+}
+
+impl Placement {
+    fn new(config: &GamepadOverlayConfig, width: u32, height: u32) -> Self {
+        let scale = height as f64 * config.size_ratio.clamp(0.05, 0.5) / DESIGN_H;
+        let margin = height as f64 * config.margin_ratio.clamp(0.0, 0.2);
+        let right = width as f64 - DESIGN_W * scale - margin;
+        let bottom = height as f64 - DESIGN_H * scale - margin;
+        let (x, y) = match config.corner {
+            WebcamCorner::TopLeft => (margin, margin),
+            WebcamCorner::TopRight => (right, margin),
+            WebcamCorner::BottomLeft => (margin, bottom),
+            WebcamCorner::BottomRight => (right, bottom),
+        };
+        Self { x, y, scale }
+    }
+
+    fn point(self, u: f64, v: f64) -> String {
+        format!(
+            "{} {}",
+            (self.x + u * self.scale).round() as i64,
+            (self.y + v * self.scale).round() as i64
+        )
+    }
+
+    fn polygon(self, points: &[(f64, f64)]) -> String {
+        let mut path = format!("m {}", self.point(points[0].0, points[0].1));
+        for (u, v) in &points[1..] {
+            path.push_str(&format!(" l {}", self.point(*u, *v)));
+        }
+        path
+    }
+
+    fn shape(self, shape: Shape) -> String {
+        match shape {
+            Shape::Rect { x0, y0, x1, y1 } => {
+                self.polygon(&[(x0, y0), (x1, y0), (x1, y1), (x0, y1)])
+            }
+            Shape::Circle { cx, cy, r } => {
+                // Four cubic Béziers approximate a circle.
+                let k = 0.5523 * r;
+                format!(
+                    "m {} b {} {} {} b {} {} {} b {} {} {} b {} {} {}",
+                    self.point(cx - r, cy),
+                    self.point(cx - r, cy - k),
+                    self.point(cx - k, cy - r),
+                    self.point(cx, cy - r),
+                    self.point(cx + k, cy - r),
+                    self.point(cx + r, cy - k),
+                    self.point(cx + r, cy),
+                    self.point(cx + r, cy + k),
+                    self.point(cx + k, cy + r),
+                    self.point(cx, cy + r),
+                    self.point(cx - k, cy + r),
+                    self.point(cx - r, cy + k),
+                    self.point(cx - r, cy),
+                )
+            }
+        }
+    }
+}
+
+/// Build the overlay script for a `width`x`height` output, or `None` when
+/// the recording has no gamepad events.
+pub fn build_gamepad_ass(
+    events: &[InputEvent],
+    config: &GamepadOverlayConfig,
+    width: u32,
+    height: u32,
+) -> Option<String> {
+    let pad = events.iter().find_map(|event| match event.kind {
+        EventKind::GamepadButton { pad, .. } | EventKind::GamepadAxis { pad, .. } => Some(pad),
+        _ => None,
+    })?;
+    let pad_events: Vec<&InputEvent> = events
+        .iter()
+        .filter(|event| {
+            matches!(event.kind,
+                EventKind::GamepadButton { pad: p, .. } | EventKind::GamepadAxis { pad: p, .. }
+                    if p == pad)
+        })
+        .collect();
+    let start = pad_events.first()?.timestamp_secs();
+    let end = pad_events.last()?.timestamp_secs() + TAIL_SECS;
+
+    let placement = Placement::new(config, width, height);
+    let line = |layer: u32, from: f64, to: f64, style: &str, path: String| {
+        format!(
+            "Dialogue: {layer},{},{},Default,,0,0,0,,{{\\an7\\pos(0,0)\\shad0{style}\\p1}}{path}{{\\p0}}\n",
+            format_ass_time(from),
+            format_ass_time(to),
+        )
+    };
+
+    let mut script = ass_drawing_header(width, height);
+
+    // Body, then every button idle.
+    script.push_str(&line(
+        0,
+        start,
+        end,
+        "\\bord1\\1c&H202020&\\1a&H40&\\3c&HFFFFFF&\\3a&H80&",
+        placement.polygon(BODY),
+    ));
+    let idle = DIAGRAM_BUTTONS
+        .iter()
+        .filter_map(|button| button_shape(*button))
+        .map(|shape| placement.shape(shape))
+        .collect::<Vec<_>>()
+        .join(" ");
+    script.push_str(&line(1, start, end, "\\bord0\\1c&H707070&\\1a&H20&", idle));
+
+    let highlight = format!(
+        "\\bord0\\1c{}&\\1a&H00&",
+        ass_color(&config.highlight_color, "&H0018C5F5")
+    );
+    for (button, from, to) in press_intervals(&pad_events, end) {
+        if let Some(shape) = button_shape(button) {
+            script.push_str(&line(2, from, to, &highlight, placement.shape(shape)));
+        }
+    }
+
+    for (stick, from, to, x, y) in stick_positions(&pad_events, end) {
+        let Some(Shape::Circle { cx, cy, .. }) = button_shape(stick) else {
+            continue;
+        };
+        let cap = Shape::Circle {
+            cx: cx + x * STICK_TRAVEL,
+            cy: cy + y * STICK_TRAVEL,
+            r: 7.0,
+        };
+        script.push_str(&line(
+            3,
+            from,
+            to,
+            "\\bord0\\1c&HD0D0D0&\\1a&H00&",
+            placement.shape(cap),
+        ));
+    }
+
+    Some(script)
+}
+
+/// Held intervals per button, with analog triggers past
+/// [`TRIGGER_PRESSED`] counted as their trigger button.
+fn press_intervals(events: &[&InputEvent], end: f64) -> Vec<(GamepadButton, f64, f64)> {
+    let mut held: HashMap<GamepadButton, f64> = HashMap::new();
+    let mut intervals = Vec::new();
+    for event in events {
+        let t = event.timestamp_secs();
+        let (button, down) = match event.kind {
+            EventKind::GamepadButton { button, state, .. } => (button, state == ButtonState::Down),
+            EventKind::GamepadAxis {
+                axis: GamepadAxis::LeftTrigger,
+                value,
+                ..
+            } => (GamepadButton::LeftTrigger, value >= TRIGGER_PRESSED),
+            EventKind::GamepadAxis {
+                axis: GamepadAxis::RightTrigger,
+                value,
+                ..
+            } => (GamepadButton::RightTrigger, value >= TRIGGER_PRESSED),
+            _ => continue,
+        };
+        if down {
+            held.entry(button).or_insert(t);
+        } else if let Some(from) = held.remove(&button) {
+            intervals.push((button, from, t));
+        }
+    }
+    intervals.extend(held.into_iter().map(|(button, from)| (button, from, end)));
+    intervals.retain(|(_, from, to)| to > from);
+    intervals.sort_by(|a, b| a.1.total_cmp(&b.1));
+    intervals
+}
+
+/// Stick cap positions as `(stick, from, to, x, y)`, one entry per change.
+fn stick_positions(events: &[&InputEvent], end: f64) -> Vec<(GamepadButton, f64, f64, f64, f64)> {
+    let mut positions = Vec::new();
+    for stick in [GamepadButton::LeftStick, GamepadButton::RightStick] {
+        let (axis_x, axis_y) = if stick == GamepadButton::LeftStick {
+            (GamepadAxis::LeftStickX, GamepadAxis::LeftStickY)
+        } else {
+            (GamepadAxis::RightStickX, GamepadAxis::RightStickY)
+        };
+
+        let mut current = (
+            events.first().map(|e| e.timestamp_secs()).unwrap_or(0.0),
+            0.0,
+            0.0,
+        );
+        for event in events {
+            let EventKind::GamepadAxis { axis, value, .. } = event.kind else {
+                continue;
+            };
+            let (from, mut x, mut y) = current;
+            if axis == axis_x {
+                x = value;
+            } else if axis == axis_y {
+                y = value;
+            } else {
+                continue;
+            }
+            let t = event.timestamp_secs();
+            positions.push((stick, from, t, current.1, current.2));
+            current = (t, x, y);
+        }
+        positions.push((stick, current.0, end, current.1, current.2));
+    }
+    positions.retain(|(_, from, to, _, _)| to > from);
+    positions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn events() -> Vec<InputEvent> {
+        vec![
+            InputEvent::gamepad_button(1_000_000_000, 0, GamepadButton::South, ButtonState::Down),
+            InputEvent::gamepad_axis(1_200_000_000, 0, GamepadAxis::RightTrigger, 0.9),
+            InputEvent::gamepad_button(1_500_000_000, 0, GamepadButton::South, ButtonState::Up),
+            InputEvent::gamepad_axis(1_600_000_000, 0, GamepadAxis::LeftStickX, 1.0),
+            InputEvent::gamepad_axis(1_800_000_000, 0, GamepadAxis::RightTrigger, 0.1),
+            // A second pad is ignored.
+            InputEvent::gamepad_button(1_900_000_000, 1, GamepadButton::North, ButtonState::Down),
+        ]
+    }
+
+    #[test]
+    fn test_press_intervals_include_analog_triggers() {
+        let events = events();
+        let refs: Vec<&InputEvent> = events.iter().collect();
+        let intervals = press_intervals(&refs, 10.0);
+        assert!(intervals.contains(&(GamepadButton::South, 1.0, 1.5)));
+        assert!(intervals.contains(&(GamepadButton::RightTrigger, 1.2, 1.8)));
+    }
+
+    #[test]
+    fn test_stick_positions_follow_axes() {
+        let events = events();
+        let refs: Vec<&InputEvent> = events.iter().collect();
+        let positions = stick_positions(&refs, 10.0);
+        assert!(positions.contains(&(GamepadButton::LeftStick, 1.0, 1.6, 0.0, 0.0)));
+        assert!(positions.contains(&(GamepadButton::LeftStick, 1.6, 10.0, 1.0, 0.0)));
+    }
+
+    #[test]
+    fn test_build_gamepad_ass_uses_first_pad_and_window() {
+        let config = GamepadOverlayConfig::default();
+        let ass = build_gamepad_ass(&events(), &config, 1920, 1080).unwrap();
+        assert!(ass.contains("PlayResX: 1920"));
+        // Body from the first pad-0 event to two seconds after its last one.
+        assert!(ass.contains("Dialogue: 0,0:00:01.00,0:00:03.80,"));
+        // South held from 1.0s to 1.5s.
+        assert!(ass.contains("Dialogue: 2,0:00:01.00,0:00:01.50,"));
+        assert!(build_gamepad_ass(&[], &config, 1920, 1080).is_none());
+    }
+}
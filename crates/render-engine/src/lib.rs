@@ -23,7 +23,28 @@
 //!                                                  output.mp4
 //! ```
 
+pub mod bitrate;
+pub mod clipboard;
+pub mod clock_overlay;
 pub mod compositor;
+pub mod cursor_audit;
+pub mod cursor_probe;
+pub mod debug_overlay;
+pub mod draft;
 pub mod export;
+pub mod gamepad_overlay;
+pub mod metadata;
+pub mod metrics_overlay;
+pub mod preroll;
+pub mod probe;
+pub mod reactions;
+pub mod resources;
+pub mod retime;
+pub mod slim;
+pub mod stills;
+pub mod strokes;
+pub mod subtitles;
+pub mod tail;
+pub mod webcam_thumbs;
 
 pub use export::*;
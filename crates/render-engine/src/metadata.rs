@@ -0,0 +1,201 @@
+//! Container metadata for exports.
+//!
+//! Tags are passed to ffmpeg with `-metadata`. Chapters are written to an
+//! `FFMETADATA1` file that is added as one more input, and its chapters are
+//! mapped to the output. Chapter markers are set in recording time and are
+//! moved onto the output timeline, so speed segments and partial exports
+//! keep them on the right frames.
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use grabme_project_model::project::{ChapterMarker, ExportMetadata};
+
+/// A chapter on the output timeline.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutputChapter {
+    pub start_secs: f64,
+    pub end_secs: f64,
+    pub title: String,
+}
+
+/// `-metadata` arguments for `metadata`. The title falls back to
+/// `project_name` and the creation time to `recorded_at`.
+pub fn metadata_args(
+    metadata: &ExportMetadata,
+    project_name: &str,
+    recorded_at: Option<DateTime<Utc>>,
+) -> Vec<String> {
+    let creation_time = match metadata.creation_time.as_deref() {
+        Some(time) => match DateTime::parse_from_rfc3339(time) {
+            Ok(time) => Some(time.with_timezone(&Utc)),
+            Err(e) => {
+                tracing::warn!(time, error = %e, "Ignoring creation time that is not RFC 3339");
+                recorded_at
+            }
+        },
+        None => recorded_at,
+    };
+    let tags = [
+        (
+            "title",
+            Some(
+                metadata
+                    .title
+                    .as_deref()
+                    .unwrap_or(project_name)
+                    .to_string(),
+            ),
+        ),
+        ("artist", metadata.author.clone()),
+        ("comment", metadata.comment.clone()),
+        (
+            "creation_time",
+            creation_time.map(|time| time.to_rfc3339_opts(SecondsFormat::Secs, true)),
+        ),
+    ];
+    tags.into_iter()
+        .filter_map(|(key, value)| Some((key, value.filter(|value| !value.is_empty())?)))
+        .flat_map(|(key, value)| ["-metadata".to_string(), format!("{key}={value}")])
+        .collect()
+}
+
+/// Place `chapters` on an output `duration_secs` long, with `to_output`
+/// mapping recording seconds to output seconds. The chapter under way when
+/// the output starts is kept and starts at zero; chapters from the end on
+/// are dropped. Each chapter runs until the next one.
+pub fn output_chapters(
+    chapters: &[ChapterMarker],
+    to_output: impl Fn(f64) -> f64,
+    duration_secs: f64,
+) -> Vec<OutputChapter> {
+    let mut starts: Vec<(f64, &str)> = chapters
+        .iter()
+        .map(|chapter| (to_output(chapter.time_secs), chapter.title.as_str()))
+        .filter(|(start, _)| start.is_finite() && *start < duration_secs)
+        .collect();
+    starts.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let first = starts
+        .iter()
+        .rposition(|(start, _)| *start <= 0.0)
+        .unwrap_or(0);
+    let starts = &starts[first..];
+
+    starts
+        .iter()
+        .enumerate()
+        .map(|(i, (start, title))| OutputChapter {
+            start_secs: start.max(0.0),
+            end_secs: starts.get(i + 1).map_or(duration_secs, |next| next.0),
+            title: title.to_string(),
+        })
+        .filter(|chapter| chapter.end_secs > chapter.start_secs)
+        .collect()
+}
+
+/// `FFMETADATA1` file holding `chapters`, in milliseconds.
+pub fn ffmetadata_chapters(chapters: &[OutputChapter]) -> String {
+    let mut file = String::from(";FFMETADATA1\n");
+    for chapter in chapters {
+        file.push_str(&format!(
+            "\n[CHAPTER]\nTIMEBASE=1/1000\nSTART={}\nEND={}\ntitle={}\n",
+            (chapter.start_secs * 1000.0).round() as u64,
+            (chapter.end_secs * 1000.0).round() as u64,
+            escape_ffmetadata(&chapter.title)
+        ));
+    }
+    file
+}
+
+fn escape_ffmetadata(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '=' | ';' | '#' | '\\' | '\n') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chapter(time_secs: f64, title: &str) -> ChapterMarker {
+        ChapterMarker {
+            time_secs,
+            title: title.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_metadata_args_fall_back_to_project_name_and_epoch() {
+        let recorded_at = DateTime::parse_from_rfc3339("2026-01-02T03:04:05.250Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let metadata = ExportMetadata {
+            author: Some("Ada".to_string()),
+            comment: Some(String::new()),
+            ..Default::default()
+        };
+        assert_eq!(
+            metadata_args(&metadata, "Demo", Some(recorded_at)),
+            [
+                "-metadata",
+                "title=Demo",
+                "-metadata",
+                "artist=Ada",
+                "-metadata",
+                "creation_time=2026-01-02T03:04:05Z",
+            ]
+        );
+
+        let metadata = ExportMetadata {
+            title: Some("Release notes".to_string()),
+            creation_time: Some("2026-03-04T05:06:07+01:00".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            metadata_args(&metadata, "Demo", None),
+            [
+                "-metadata",
+                "title=Release notes",
+                "-metadata",
+                "creation_time=2026-03-04T04:06:07Z",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_output_chapters_follow_the_output_timeline() {
+        let chapters = [
+            chapter(40.0, "Outro"),
+            chapter(0.0, "Intro"),
+            chapter(5.0, "Setup"),
+            chapter(12.0, "Demo"),
+        ];
+        // A partial export from 8 s to 38 s: "Setup" is under way at the
+        // start and "Outro" is past the end.
+        let placed = output_chapters(&chapters, |secs| secs - 8.0, 30.0);
+        assert_eq!(
+            placed,
+            [
+                OutputChapter {
+                    start_secs: 0.0,
+                    end_secs: 4.0,
+                    title: "Setup".to_string(),
+                },
+                OutputChapter {
+                    start_secs: 4.0,
+                    end_secs: 30.0,
+                    title: "Demo".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            ffmetadata_chapters(&placed[..1]),
+            ";FFMETADATA1\n\n[CHAPTER]\nTIMEBASE=1/1000\nSTART=0\nEND=4000\ntitle=Setup\n"
+        );
+        assert_eq!(escape_ffmetadata("Q&A; a=b #1"), "Q&A\\; a\\=b \\#1");
+        assert!(output_chapters(&[], |secs| secs, 30.0).is_empty());
+    }
+}
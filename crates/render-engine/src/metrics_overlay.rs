@@ -0,0 +1,265 @@
+//! System usage graph overlay.
+//!
+//! Draws the CPU, memory and GPU usage sampled during capture as a small
+//! scrolling area chart in a corner of the output, with the current values
+//! above it. Each sample gets its own frame of the chart, shown until the
+//! next sample arrives.
+
+use grabme_project_model::metrics::{SystemMetrics, SystemMetricsSample};
+use grabme_project_model::project::{MetricsOverlayConfig, WebcamCorner};
+
+use crate::subtitles::{ass_drawing_header, format_ass_time};
+
+/// Design size of the panel; everything below is laid out in these units.
+const DESIGN_W: f64 = 240.0;
+const DESIGN_H: f64 = 120.0;
+
+/// Plot area inside the panel.
+const PLOT_LEFT: f64 = 8.0;
+const PLOT_RIGHT: f64 = 232.0;
+const PLOT_TOP: f64 = 30.0;
+const PLOT_BOTTOM: f64 = 112.0;
+
+/// Height of the value labels above the plot.
+const LABEL_SIZE: f64 = 15.0;
+
+/// How long the last sample stays on screen.
+const TAIL_SECS: f64 = 1.0;
+
+/// One plotted quantity: label, ASS fill colour, and its value in a sample.
+struct Series {
+    label: &'static str,
+    color: &'static str,
+    value: fn(&SystemMetricsSample) -> Option<f64>,
+}
+
+const SERIES: [Series; 3] = [
+    Series {
+        label: "CPU",
+        color: "&H0050C8F0",
+        value: |sample| Some(sample.cpu_percent),
+    },
+    Series {
+        label: "RAM",
+        color: "&H00E0A040",
+        value: |sample| Some(sample.ram_percent),
+    },
+    Series {
+        label: "GPU",
+        color: "&H0060D070",
+        value: |sample| sample.gpu_percent,
+    },
+];
+
+/// Maps design units onto output pixels.
+#[derive(Debug, Clone, Copy)]
+struct Placement {
+    x: f64,
+    y: f64,
+    scale: f64,
+}
+
+impl Placement {
+    fn new(config: &MetricsOverlayConfig, width: u32, height: u32) -> Self {
+        let scale = height as f64 * config.size_ratio.clamp(0.05, 0.5) / DESIGN_H;
+        let margin = height as f64 * config.margin_ratio.clamp(0.0, 0.2);
+        let right = width as f64 - DESIGN_W * scale - margin;
+        let bottom = height as f64 - DESIGN_H * scale - margin;
+        let (x, y) = match config.corner {
+            WebcamCorner::TopLeft => (margin, margin),
+            WebcamCorner::TopRight => (right, margin),
+            WebcamCorner::BottomLeft => (margin, bottom),
+            WebcamCorner::BottomRight => (right, bottom),
+        };
+        Self { x, y, scale }
+    }
+
+    fn point(self, u: f64, v: f64) -> String {
+        format!(
+            "{} {}",
+            (self.x + u * self.scale).round() as i64,
+            (self.y + v * self.scale).round() as i64
+        )
+    }
+
+    fn polygon(self, points: &[(f64, f64)]) -> String {
+        let mut path = format!("m {}", self.point(points[0].0, points[0].1));
+        for (u, v) in &points[1..] {
+            path.push_str(&format!(" l {}", self.point(*u, *v)));
+        }
+        path
+    }
+}
+
+/// Build the overlay script for a `width`x`height` output, or `None` when
+/// there are no samples.
+pub fn build_metrics_ass(
+    metrics: &SystemMetrics,
+    config: &MetricsOverlayConfig,
+    width: u32,
+    height: u32,
+) -> Option<String> {
+    let samples = metrics.samples();
+    let first = samples.first()?;
+    let last = samples.last()?;
+    let history_secs = config.history_secs.max(1.0);
+    let series: Vec<&Series> = SERIES
+        .iter()
+        .filter(|series| {
+            samples
+                .iter()
+                .any(|sample| (series.value)(sample).is_some())
+        })
+        .collect();
+
+    let placement = Placement::new(config, width, height);
+    let drawing = |layer: usize, from: f64, to: f64, style: &str, path: String| {
+        format!(
+            "Dialogue: {layer},{},{},Default,,0,0,0,,{{\\an7\\pos(0,0)\\shad0{style}\\p1}}{path}{{\\p0}}\n",
+            format_ass_time(from),
+            format_ass_time(to),
+        )
+    };
+
+    let mut script = ass_drawing_header(width, height);
+    script.push_str(&drawing(
+        0,
+        first.timestamp_secs(),
+        last.timestamp_secs() + TAIL_SECS,
+        "\\bord0\\1c&H202020&\\1a&H50&",
+        placement.polygon(&[
+            (0.0, 0.0),
+            (DESIGN_W, 0.0),
+            (DESIGN_W, DESIGN_H),
+            (0.0, DESIGN_H),
+        ]),
+    ));
+
+    let font_size = (LABEL_SIZE * placement.scale).round().max(1.0) as i64;
+    for (idx, sample) in samples.iter().enumerate() {
+        let from = sample.timestamp_secs();
+        let to = samples
+            .get(idx + 1)
+            .map(SystemMetricsSample::timestamp_secs)
+            .unwrap_or(from + TAIL_SECS);
+        if to <= from {
+            continue;
+        }
+        let window = &samples[..=idx];
+        let window_start = window.partition_point(|s| s.timestamp_secs() < from - history_secs);
+        let window = &window[window_start..];
+
+        let mut labels = Vec::new();
+        for (layer, series) in series.iter().enumerate() {
+            if let Some(area) = area_points(window, from, history_secs, series.value) {
+                script.push_str(&drawing(
+                    layer + 1,
+                    from,
+                    to,
+                    &format!("\\bord0\\1c{}&\\1a&H60&", series.color),
+                    placement.polygon(&area),
+                ));
+            }
+            let value = (series.value)(sample)
+                .map(|percent| format!("{percent:.0}%"))
+                .unwrap_or_else(|| "-".to_string());
+            labels.push(format!(
+                "{{\\1c{}&}}{} {{\\1c&HFFFFFF&}}{value}",
+                series.color, series.label
+            ));
+        }
+        script.push_str(&format!(
+            "Dialogue: {},{},{},Default,,0,0,0,,{{\\an7\\pos({},{})\\fs{font_size}\\bord0\\shad0}}{}\n",
+            series.len() + 1,
+            format_ass_time(from),
+            format_ass_time(to),
+            (placement.x + PLOT_LEFT * placement.scale).round() as i64,
+            (placement.y + 6.0 * placement.scale).round() as i64,
+            labels.join("  "),
+        ));
+    }
+
+    Some(script)
+}
+
+/// Outline of one series' area chart in design units, with the newest
+/// sample at the right edge of the plot and `history_secs` across it.
+/// `None` with fewer than two points to join.
+fn area_points(
+    window: &[SystemMetricsSample],
+    now_secs: f64,
+    history_secs: f64,
+    value: fn(&SystemMetricsSample) -> Option<f64>,
+) -> Option<Vec<(f64, f64)>> {
+    let plot_w = PLOT_RIGHT - PLOT_LEFT;
+    let plot_h = PLOT_BOTTOM - PLOT_TOP;
+    let line: Vec<(f64, f64)> = window
+        .iter()
+        .filter_map(|sample| {
+            let percent = value(sample)?.clamp(0.0, 100.0);
+            let age = (now_secs - sample.timestamp_secs()).clamp(0.0, history_secs);
+            Some((
+                PLOT_RIGHT - age / history_secs * plot_w,
+                PLOT_BOTTOM - percent / 100.0 * plot_h,
+            ))
+        })
+        .collect();
+    if line.len() < 2 {
+        return None;
+    }
+    let mut points = Vec::with_capacity(line.len() + 2);
+    points.push((line[0].0, PLOT_BOTTOM));
+    points.extend_from_slice(&line);
+    points.push((line[line.len() - 1].0, PLOT_BOTTOM));
+    Some(points)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(t_ms: u64, cpu: f64, gpu: Option<f64>) -> SystemMetricsSample {
+        SystemMetricsSample {
+            t_ns: t_ms * 1_000_000,
+            cpu_percent: cpu,
+            ram_percent: 50.0,
+            gpu_percent: gpu,
+        }
+    }
+
+    #[test]
+    fn test_area_puts_newest_sample_at_the_right_edge() {
+        let window = [sample(0, 0.0, None), sample(15_000, 100.0, None)];
+        let points = area_points(&window, 15.0, 30.0, |s| Some(s.cpu_percent)).unwrap();
+        assert_eq!(points.len(), 4);
+        assert_eq!(points[1], ((PLOT_LEFT + PLOT_RIGHT) / 2.0, PLOT_BOTTOM));
+        assert_eq!(points[2], (PLOT_RIGHT, PLOT_TOP));
+        assert_eq!(points[3], (PLOT_RIGHT, PLOT_BOTTOM));
+        assert!(area_points(&window[..1], 0.0, 30.0, |s| Some(s.cpu_percent)).is_none());
+    }
+
+    #[test]
+    fn test_build_metrics_ass_shows_each_sample_until_the_next() {
+        let metrics = SystemMetrics::parse(
+            &[
+                sample(1_000, 20.0, None),
+                sample(1_500, 80.0, None),
+                sample(2_000, 40.0, None),
+            ]
+            .iter()
+            .map(|s| serde_json::to_string(s).unwrap())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        )
+        .unwrap();
+        let config = MetricsOverlayConfig::default();
+        let ass = build_metrics_ass(&metrics, &config, 1920, 1080).unwrap();
+
+        assert!(ass.contains("Dialogue: 0,0:00:01.00,0:00:03.00,"));
+        assert!(ass.contains("0:00:01.50,0:00:02.00,Default"));
+        assert!(ass.contains("CPU {\\1c&HFFFFFF&}80%"));
+        // No sample reports the GPU, so it is neither plotted nor labelled.
+        assert!(!ass.contains("GPU"));
+        assert!(build_metrics_ass(&SystemMetrics::default(), &config, 1920, 1080).is_none());
+    }
+}
@@ -0,0 +1,168 @@
+//! Pre-roll detection.
+//!
+//! Screen recordings usually open on a second or two of dead time: PipeWire
+//! streams deliver black frames until the portal's share dialog has closed,
+//! and X11 captures can start on a blank frame while the pipeline spins up.
+//! This finds where the first real picture appears so export can start
+//! there instead.
+
+use std::path::Path;
+use std::process::Command;
+
+use grabme_common::error::{GrabmeError, GrabmeResult};
+use grabme_project_model::project::LoadedProject;
+
+use crate::export::{command_exists, resolve_screen_source};
+
+/// Only this much of the start of a recording is scanned. Dead time longer
+/// than this is not negotiation and is left for the user to cut.
+pub const PREROLL_SCAN_SECS: f64 = 5.0;
+
+/// A black run must start within this many seconds of the beginning to
+/// count as pre-roll.
+const LEADING_TOLERANCE_SECS: f64 = 0.1;
+
+/// Timeline time (seconds) of the first non-black frame of the project's
+/// screen recording, or `0.0` when it opens on a picture.
+pub fn detect_project_preroll(project_dir: &Path) -> GrabmeResult<f64> {
+    let project = LoadedProject::load(project_dir)
+        .map_err(|e| GrabmeError::render(format!("Failed to load project: {e}")))?;
+    let (screen_path, screen_offset_ns, _) = resolve_screen_source(project_dir, &project)?;
+    detect_screen_preroll(&screen_path, screen_offset_ns)
+}
+
+/// [`detect_preroll_secs`] for a screen track placed at `screen_offset_ns`,
+/// in timeline time.
+pub(crate) fn detect_screen_preroll(
+    screen_path: &Path,
+    screen_offset_ns: i64,
+) -> GrabmeResult<f64> {
+    let media_secs = detect_preroll_secs(screen_path)?;
+    if media_secs <= 0.0 {
+        return Ok(0.0);
+    }
+    Ok((media_secs + screen_offset_ns as f64 / 1_000_000_000.0).max(0.0))
+}
+
+/// Media time (seconds) of the first non-black frame in `path`, scanning at
+/// most [`PREROLL_SCAN_SECS`].
+pub fn detect_preroll_secs(path: &Path) -> GrabmeResult<f64> {
+    if !command_exists("ffmpeg") {
+        return Err(GrabmeError::unsupported(
+            "Pre-roll detection requires ffmpeg in PATH",
+        ));
+    }
+
+    // blackdetect reports at info level; everything else stays quiet.
+    let output = Command::new("ffmpeg")
+        .args(["-hide_banner", "-nostats", "-loglevel", "info", "-t"])
+        .arg(format!("{PREROLL_SCAN_SECS:.3}"))
+        .arg("-i")
+        .arg(path)
+        .args([
+            "-map",
+            "0:v:0",
+            "-vf",
+            "blackdetect=d=0:pic_th=0.98:pix_th=0.10",
+            "-an",
+            "-f",
+            "null",
+            "-",
+        ])
+        .output()
+        .map_err(|e| GrabmeError::render(format!("Failed to launch ffmpeg: {e}")))?;
+    if !output.status.success() {
+        return Err(GrabmeError::render(format!(
+            "Pre-roll detection failed on {}: {}",
+            path.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    match parse_leading_black_end(&stderr, PREROLL_SCAN_SECS) {
+        LeadingBlack::None => Ok(0.0),
+        LeadingBlack::Until(secs) => {
+            tracing::info!(path = %path.display(), secs, "Detected black pre-roll");
+            Ok(secs)
+        }
+        LeadingBlack::WholeScan => {
+            tracing::warn!(
+                path = %path.display(),
+                scan_secs = PREROLL_SCAN_SECS,
+                "Recording stays black for the whole pre-roll scan; not trimming"
+            );
+            Ok(0.0)
+        }
+    }
+}
+
+/// Leading black run found in blackdetect output.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum LeadingBlack {
+    /// The recording opens on a picture.
+    None,
+    /// Black until this many seconds.
+    Until(f64),
+    /// Black through the end of the scanned window.
+    WholeScan,
+}
+
+/// Parse `black_start:<s> black_end:<s>` lines from blackdetect and return
+/// the run that starts the recording, if any.
+fn parse_leading_black_end(stderr: &str, scan_secs: f64) -> LeadingBlack {
+    let field = |line: &str, key: &str| -> Option<f64> {
+        let rest = &line[line.find(key)? + key.len()..];
+        rest.split_whitespace().next()?.parse().ok()
+    };
+
+    let first_run = stderr
+        .lines()
+        .filter(|line| line.contains("blackdetect"))
+        .find_map(|line| Some((field(line, "black_start:")?, field(line, "black_end:")?)));
+
+    match first_run {
+        Some((start, end)) if start <= LEADING_TOLERANCE_SECS && end > start => {
+            if end >= scan_secs - LEADING_TOLERANCE_SECS {
+                LeadingBlack::WholeScan
+            } else {
+                LeadingBlack::Until(end)
+            }
+        }
+        _ => LeadingBlack::None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_leading_black_run() {
+        let stderr = "\
+Input #0, matroska,webm, from 'screen.mkv':
+[blackdetect @ 0x5581] black_start:0 black_end:1.466667 black_duration:1.466667
+[blackdetect @ 0x5581] black_start:3.2 black_end:3.4 black_duration:0.2
+";
+        assert_eq!(
+            parse_leading_black_end(stderr, 5.0),
+            LeadingBlack::Until(1.466667)
+        );
+    }
+
+    #[test]
+    fn test_parse_ignores_black_later_in_recording() {
+        let stderr = "[blackdetect @ 0x1] black_start:2.5 black_end:3 black_duration:0.5\n";
+        assert_eq!(parse_leading_black_end(stderr, 5.0), LeadingBlack::None);
+        assert_eq!(parse_leading_black_end("", 5.0), LeadingBlack::None);
+    }
+
+    #[test]
+    fn test_parse_all_black_scan_is_not_trimmed() {
+        let stderr = "[blackdetect @ 0x1] black_start:0 black_end:5 black_duration:5\n";
+        assert_eq!(
+            parse_leading_black_end(stderr, 5.0),
+            LeadingBlack::WholeScan
+        );
+    }
+}
@@ -0,0 +1,247 @@
+//! Media checks against project metadata.
+//!
+//! Reads what is actually in a source file and compares it with the track
+//! metadata in `project.json`, so mismatches show up before an export does
+//! something surprising with them. Probing itself lives in
+//! [`grabme_common::media`].
+
+use std::path::Path;
+use std::process::Command;
+
+use grabme_common::error::{GrabmeError, GrabmeResult};
+use grabme_project_model::project::{RecordingConfig, TrackRef};
+
+pub use grabme_common::media::{
+    parse_ffprobe_json, probe_media, AudioStreamInfo, MediaInfo, VideoStreamInfo,
+};
+
+/// Durations may differ by this much (or 5%, whichever is larger) before
+/// they are reported.
+const DURATION_TOLERANCE_SECS: f64 = 0.5;
+
+/// What kind of media a track is expected to hold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrackMediaKind {
+    /// The screen recording; resolution is checked against the monitor.
+    Screen,
+    /// Webcam video.
+    Webcam,
+    /// Mic or system audio.
+    Audio,
+}
+
+/// Number of video frames in `path`, counted from its packets.
+pub fn count_video_frames(path: &Path) -> GrabmeResult<u64> {
+    let output = Command::new("ffprobe")
+        .args([
+            "-v",
+            "error",
+            "-select_streams",
+            "v:0",
+            "-count_packets",
+            "-show_entries",
+            "stream=nb_read_packets",
+            "-of",
+            "csv=p=0",
+        ])
+        .arg(path)
+        .output()
+        .map_err(|e| GrabmeError::render(format!("Failed to launch ffprobe: {e}")))?;
+
+    if !output.status.success() {
+        return Err(GrabmeError::render(format!(
+            "ffprobe failed on {}: {}",
+            path.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse()
+        .map_err(|e| GrabmeError::render(format!("Unexpected ffprobe frame count: {e}")))
+}
+
+/// Names of the encoders the installed ffmpeg provides.
+pub fn ffmpeg_encoders() -> GrabmeResult<Vec<String>> {
+    let output = Command::new("ffmpeg")
+        .args(["-hide_banner", "-encoders"])
+        .output()
+        .map_err(|e| GrabmeError::render(format!("Failed to launch ffmpeg: {e}")))?;
+    if !output.status.success() {
+        return Err(GrabmeError::render(format!(
+            "ffmpeg -encoders failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(parse_ffmpeg_encoders(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+/// Parse `ffmpeg -encoders` output: a legend, a `------` separator, then
+/// one `<flags> <name> <description>` line per encoder.
+pub fn parse_ffmpeg_encoders(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .skip_while(|line| !line.trim_start().starts_with("---"))
+        .skip(1)
+        .filter_map(|line| line.split_whitespace().nth(1))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Differences between track metadata and the probed media.
+pub fn track_mismatches(
+    kind: TrackMediaKind,
+    track: &TrackRef,
+    info: &MediaInfo,
+    recording: &RecordingConfig,
+) -> Vec<String> {
+    let mut issues = Vec::new();
+
+    match kind {
+        TrackMediaKind::Screen | TrackMediaKind::Webcam => match &info.video {
+            Some(video) => {
+                if !codec_matches(&track.codec, &video.codec) {
+                    issues.push(format!(
+                        "codec is {} but metadata says {}",
+                        video.codec, track.codec
+                    ));
+                }
+                if kind == TrackMediaKind::Screen {
+                    let (expected_w, expected_h) =
+                        if recording.monitor_width > 0 && recording.monitor_height > 0 {
+                            (recording.monitor_width, recording.monitor_height)
+                        } else {
+                            (recording.capture_width, recording.capture_height)
+                        };
+                    if (video.width, video.height) != (expected_w, expected_h) {
+                        issues.push(format!(
+                            "resolution is {}x{} but the recorded monitor is {expected_w}x{expected_h}; export will apply a pre-crop fallback",
+                            video.width, video.height
+                        ));
+                    }
+                    if let Some(fps) = video.fps {
+                        if recording.fps > 0 && (fps - recording.fps as f64).abs() > 1.0 {
+                            issues.push(format!(
+                                "frame rate is {fps:.2} but recording requested {}",
+                                recording.fps
+                            ));
+                        }
+                    }
+                }
+            }
+            None => issues.push("no video stream".to_string()),
+        },
+        TrackMediaKind::Audio => match &info.audio {
+            Some(audio) => {
+                if !codec_matches(&track.codec, &audio.codec) {
+                    issues.push(format!(
+                        "codec is {} but metadata says {}",
+                        audio.codec, track.codec
+                    ));
+                }
+                if audio.channels == 0 {
+                    issues.push("audio stream reports zero channels".to_string());
+                }
+            }
+            None => issues.push("no audio stream".to_string()),
+        },
+    }
+
+    match info.duration_secs {
+        Some(probed) if track.duration_secs > 0.0 => {
+            let tolerance = DURATION_TOLERANCE_SECS.max(track.duration_secs * 0.05);
+            if (probed - track.duration_secs).abs() > tolerance {
+                issues.push(format!(
+                    "duration is {probed:.2}s but metadata says {:.2}s",
+                    track.duration_secs
+                ));
+            }
+        }
+        None => issues.push("container reports no duration".to_string()),
+        _ => {}
+    }
+
+    issues
+}
+
+/// Metadata stores codec families (`pcm`, `h264`); ffprobe reports concrete
+/// names (`pcm_s16le`, `h264`).
+fn codec_matches(declared: &str, probed: &str) -> bool {
+    let declared = declared.trim().to_ascii_lowercase();
+    let probed = probed.trim().to_ascii_lowercase();
+    declared.is_empty() || probed == declared || probed.starts_with(&format!("{declared}_"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use grabme_project_model::project::Project;
+
+    const SCREEN_JSON: &str = r#"{
+        "streams": [
+            {"codec_type": "video", "codec_name": "h264", "width": 2560, "height": 1440, "avg_frame_rate": "30/1"},
+            {"codec_type": "audio", "codec_name": "aac", "channels": 2, "sample_rate": "48000"}
+        ],
+        "format": {"duration": "12.500000", "bit_rate": "8123456"}
+    }"#;
+
+    #[test]
+    fn test_track_mismatches_flags_resolution_fps_and_duration() {
+        let mut project = Project::new("probe", 1920, 1080, 60);
+        project.recording.monitor_width = 1920;
+        project.recording.monitor_height = 1080;
+        let track = TrackRef {
+            path: "sources/screen.mkv".to_string(),
+            duration_secs: 20.0,
+            codec: "h264".to_string(),
+            offset_ns: 0,
+            audio: None,
+            video: None,
+        };
+        let info = parse_ffprobe_json(SCREEN_JSON).unwrap();
+
+        let issues = track_mismatches(TrackMediaKind::Screen, &track, &info, &project.recording);
+        assert_eq!(issues.len(), 3, "{issues:?}");
+        assert!(issues[0].starts_with("resolution is 2560x1440"));
+        assert!(issues[1].starts_with("frame rate is 30.00"));
+        assert!(issues[2].starts_with("duration is 12.50s"));
+    }
+
+    #[test]
+    fn test_track_mismatches_accepts_pcm_family_codec() {
+        let project = Project::new("probe", 1920, 1080, 60);
+        let track = TrackRef {
+            path: "sources/mic.wav".to_string(),
+            duration_secs: 10.2,
+            codec: "pcm".to_string(),
+            offset_ns: 0,
+            audio: None,
+            video: None,
+        };
+        let info = parse_ffprobe_json(
+            r#"{"streams":[{"codec_type":"audio","codec_name":"pcm_s16le","channels":1,"sample_rate":"48000"}],
+                "format":{"duration":"10.0"}}"#,
+        )
+        .unwrap();
+
+        assert!(
+            track_mismatches(TrackMediaKind::Audio, &track, &info, &project.recording).is_empty()
+        );
+    }
+
+    #[test]
+    fn test_parse_ffmpeg_encoders_skips_legend() {
+        let output = "Encoders:
+ V..... = Video
+ A..... = Audio
+ ------
+ V....D libx264              libx264 H.264 / AVC / MPEG-4 AVC (codec h264)
+ V....D png                  PNG (Portable Network Graphics) image
+ A....D aac                  AAC (Advanced Audio Coding)
+";
+        assert_eq!(parse_ffmpeg_encoders(output), vec!["libx264", "png", "aac"]);
+    }
+}
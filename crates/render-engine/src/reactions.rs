@@ -0,0 +1,149 @@
+//! Reaction overlay.
+//!
+//! Reactions dropped with a hotkey while recording are drawn as a short
+//! animation at the pointer: a ring bursts outwards while the emoji pops up
+//! above the pointer, floats upwards, and fades out. The ring keeps the
+//! moment visible even when no installed font can draw the emoji.
+
+use grabme_project_model::event::{EventKind, InputEvent};
+use grabme_project_model::project::ReactionOverlayConfig;
+
+use crate::debug_overlay::dot_drawing;
+use crate::subtitles::{ass_drawing_header, format_ass_time};
+
+const RING_COLOR: &str = "&H0000C8FF";
+/// Share of the reaction's time spent on the ring burst.
+const RING_SHARE: f64 = 0.4;
+
+/// A reaction to draw.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reaction {
+    pub t_secs: f64,
+    pub emoji: String,
+    /// Normalized pointer position.
+    pub x: f64,
+    pub y: f64,
+}
+
+/// Reaction markers in `events`, in recording order.
+pub fn collect_reactions(events: &[InputEvent]) -> Vec<Reaction> {
+    events
+        .iter()
+        .filter_map(|event| match &event.kind {
+            EventKind::Reaction { emoji, x, y } => Some(Reaction {
+                t_secs: event.timestamp_secs(),
+                emoji: emoji.clone(),
+                x: *x,
+                y: *y,
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Build an ASS script animating `reactions` on a `width`x`height` frame.
+pub fn build_reactions_ass(
+    reactions: &[Reaction],
+    style: &ReactionOverlayConfig,
+    width: u32,
+    height: u32,
+) -> String {
+    let mut script = ass_drawing_header(width, height);
+
+    let duration = style.duration_secs.max(0.2);
+    let duration_ms = (duration * 1000.0).round() as i64;
+    let ring_ms = (duration_ms as f64 * RING_SHARE).round() as i64;
+    let size = (style.size_ratio.clamp(0.01, 0.5) * height as f64)
+        .round()
+        .max(8.0) as i64;
+    let radius = (size / 2).max(4);
+    let rise = size;
+
+    for reaction in reactions {
+        let x = (reaction.x.clamp(0.0, 1.0) * width as f64).round() as i64;
+        let y = (reaction.y.clamp(0.0, 1.0) * height as f64).round() as i64;
+        let start = format_ass_time(reaction.t_secs);
+        let end = format_ass_time(reaction.t_secs + duration);
+
+        // The ring is drawn around its own centre so scaling grows it in place.
+        script.push_str(&format!(
+            "Dialogue: 0,{start},{end},Default,,0,0,0,,{{\\an5\\pos({x},{y})\\bord{border}\\shad0\\1a&HFF&\\3c{RING_COLOR}&\\fscx40\\fscy40\\t(0,{ring_ms},\\fscx160\\fscy160\\alpha&HFF&)\\p1}}{}{{\\p0}}\n",
+            dot_drawing(radius, radius, radius),
+            border = (radius / 6).max(2),
+        ));
+
+        let emoji = reaction.emoji.replace(['{', '}', '\n'], "");
+        if emoji.trim().is_empty() {
+            continue;
+        }
+        let top = y - radius;
+        script.push_str(&format!(
+            "Dialogue: 1,{start},{end},Default,,0,0,0,,{{\\an2\\move({x},{top},{x},{risen},0,{duration_ms})\\fs{size}\\bord0\\shad0\\fscx50\\fscy50\\t(0,150,\\fscx115\\fscy115)\\t(150,300,\\fscx100\\fscy100)\\fad(80,{fade_ms})}}{emoji}\n",
+            risen = top - rise,
+            fade_ms = duration_ms / 3,
+        ));
+    }
+
+    script
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect_reactions_skips_other_events() {
+        let events = vec![
+            InputEvent::pointer(0, 0.1, 0.1),
+            InputEvent::reaction(1_500_000_000, "🎉", 0.25, 0.5),
+        ];
+        assert_eq!(
+            collect_reactions(&events),
+            vec![Reaction {
+                t_secs: 1.5,
+                emoji: "🎉".to_string(),
+                x: 0.25,
+                y: 0.5,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_reaction_is_drawn_at_pointer_for_its_duration() {
+        let reactions = vec![Reaction {
+            t_secs: 2.0,
+            emoji: "🔥".to_string(),
+            x: 0.5,
+            y: 0.5,
+        }];
+        let style = ReactionOverlayConfig {
+            duration_secs: 1.0,
+            size_ratio: 0.1,
+            ..Default::default()
+        };
+        let ass = build_reactions_ass(&reactions, &style, 1920, 1080);
+
+        let lines: Vec<&str> = ass.lines().filter(|l| l.starts_with("Dialogue:")).collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("Dialogue: 0,0:00:02.00,0:00:03.00,"));
+        assert!(lines[0].contains("\\pos(960,540)"));
+        assert!(lines[1].contains("\\move(960,486,960,378,0,1000)"));
+        assert!(lines[1].ends_with("🔥"));
+    }
+
+    #[test]
+    fn test_override_braces_are_stripped_from_emoji() {
+        let reactions = vec![Reaction {
+            t_secs: 0.0,
+            emoji: "{}".to_string(),
+            x: 0.5,
+            y: 0.5,
+        }];
+        let ass = build_reactions_ass(&reactions, &ReactionOverlayConfig::default(), 640, 480);
+        // Only the ring is left.
+        assert_eq!(
+            ass.lines().filter(|l| l.starts_with("Dialogue:")).count(),
+            1
+        );
+    }
+}
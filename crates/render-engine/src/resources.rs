@@ -0,0 +1,272 @@
+//! CPU, memory and I/O limits for the ffmpeg encode.
+//!
+//! Left alone, ffmpeg spreads the encode over every core at normal
+//! priority, which makes the desktop sluggish for the whole export. An
+//! export can cap encoder and filter threads, run at lower CPU and I/O
+//! priority (`nice`/`ionice` on Unix, a lower priority class on Windows),
+//! and pick a faster x264/x265 preset. On machines short of memory it can
+//! also read the event stream a line at a time.
+
+use std::fmt;
+use std::process::Command;
+use std::str::FromStr;
+
+use grabme_common::memory::MemoryProfile;
+
+/// x264/x265 presets, fastest first.
+pub const ENCODER_PRESETS: [&str; 10] = [
+    "ultrafast",
+    "superfast",
+    "veryfast",
+    "faster",
+    "fast",
+    "medium",
+    "slow",
+    "slower",
+    "veryslow",
+    "placebo",
+];
+
+/// Scheduling priority of the ffmpeg process.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExportPriority {
+    /// Same priority as everything else.
+    #[default]
+    Normal,
+    /// Yields CPU and disk to interactive work (`nice 10`, best-effort
+    /// I/O at the lowest level).
+    Low,
+    /// Only runs when nothing else wants the machine (`nice 19`, idle I/O).
+    Idle,
+}
+
+impl ExportPriority {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ExportPriority::Normal => "normal",
+            ExportPriority::Low => "low",
+            ExportPriority::Idle => "idle",
+        }
+    }
+}
+
+impl fmt::Display for ExportPriority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ExportPriority {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "normal" => Ok(ExportPriority::Normal),
+            "low" => Ok(ExportPriority::Low),
+            "idle" => Ok(ExportPriority::Idle),
+            other => Err(format!("Unknown priority: {other}. Use: normal, low, idle")),
+        }
+    }
+}
+
+/// Resource limits for one export.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExportResources {
+    /// Encoder and filter threads; `None` lets ffmpeg use every core.
+    pub threads: Option<u32>,
+    /// Scheduling priority.
+    pub priority: ExportPriority,
+    /// x264/x265 preset replacing the format default (`medium`). Other
+    /// encoders ignore it.
+    pub preset: Option<String>,
+    /// Read `events.jsonl` a line at a time rather than loading the text
+    /// whole, as the low-memory profile asks.
+    pub stream_events: bool,
+}
+
+impl ExportResources {
+    /// Limits for exports that run while the user keeps working: half the
+    /// cores, low priority, and a faster preset.
+    pub fn background() -> Self {
+        let cores = std::thread::available_parallelism().map_or(2, |n| n.get()) as u32;
+        Self {
+            threads: Some((cores / 2).max(1)),
+            priority: ExportPriority::Low,
+            preset: Some("faster".to_string()),
+            stream_events: false,
+        }
+    }
+
+    /// These limits, reading events the way `memory` asks.
+    pub fn with_memory(self, memory: &MemoryProfile) -> Self {
+        Self {
+            stream_events: memory.stream_events,
+            ..self
+        }
+    }
+
+    /// `ffmpeg` wrapped in `nice`/`ionice` (or given a priority class) as
+    /// `priority` asks. Wrappers that aren't installed are skipped.
+    pub(crate) fn ffmpeg_command(&self) -> Command {
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::CommandExt;
+            const BELOW_NORMAL_PRIORITY_CLASS: u32 = 0x0000_4000;
+            const IDLE_PRIORITY_CLASS: u32 = 0x0000_0040;
+
+            let mut command = Command::new("ffmpeg");
+            match self.priority {
+                ExportPriority::Normal => {}
+                ExportPriority::Low => {
+                    command.creation_flags(BELOW_NORMAL_PRIORITY_CLASS);
+                }
+                ExportPriority::Idle => {
+                    command.creation_flags(IDLE_PRIORITY_CLASS);
+                }
+            }
+            command
+        }
+        #[cfg(not(windows))]
+        {
+            let wrapper = priority_wrapper(
+                self.priority,
+                crate::export::command_exists("nice"),
+                cfg!(target_os = "linux") && crate::export::command_exists("ionice"),
+            );
+            let Some((program, args)) = wrapper.split_first() else {
+                return Command::new("ffmpeg");
+            };
+            let mut command = Command::new(program);
+            command.args(args).arg("ffmpeg");
+            command
+        }
+    }
+
+    /// Add thread limits and the preset override to a complete ffmpeg
+    /// argument list whose last argument is the output.
+    pub(crate) fn apply_to_args(&self, args: &mut Vec<String>) {
+        if let Some(preset) = &self.preset {
+            if let Some(idx) = args.iter().position(|arg| arg == "-preset") {
+                if let Some(value) = args.get_mut(idx + 1) {
+                    *value = preset.clone();
+                }
+            }
+        }
+
+        if let Some(threads) = self.threads.filter(|t| *t > 0) {
+            let threads = threads.to_string();
+            let output_at = args.len().saturating_sub(1);
+            args.splice(
+                output_at..output_at,
+                ["-threads".to_string(), threads.clone()],
+            );
+            // Global options go before the first input.
+            let first_input = args.iter().position(|arg| arg == "-i").unwrap_or(0);
+            args.splice(
+                first_input..first_input,
+                ["-filter_complex_threads".to_string(), threads],
+            );
+        }
+    }
+}
+
+/// Validate an x264/x265 preset name.
+pub fn parse_encoder_preset(raw: &str) -> Result<String, String> {
+    let preset = raw.trim().to_ascii_lowercase();
+    if ENCODER_PRESETS.contains(&preset.as_str()) {
+        Ok(preset)
+    } else {
+        Err(format!(
+            "Unknown preset: {raw}. Use one of: {}",
+            ENCODER_PRESETS.join(", ")
+        ))
+    }
+}
+
+/// Command prefix that runs a program at `priority`.
+#[cfg_attr(windows, allow(dead_code))]
+fn priority_wrapper(priority: ExportPriority, has_nice: bool, has_ionice: bool) -> Vec<String> {
+    let (niceness, io_class) = match priority {
+        ExportPriority::Normal => return Vec::new(),
+        ExportPriority::Low => ("10", ["-c", "2", "-n", "7"].as_slice()),
+        ExportPriority::Idle => ("19", ["-c", "3"].as_slice()),
+    };
+
+    let mut wrapper = Vec::new();
+    if has_nice {
+        wrapper.extend(["nice", "-n", niceness].map(String::from));
+    }
+    if has_ionice {
+        wrapper.push("ionice".to_string());
+        wrapper.extend(io_class.iter().map(|arg| arg.to_string()));
+    }
+    wrapper
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_priority_wrapper_chains_nice_and_ionice() {
+        assert!(priority_wrapper(ExportPriority::Normal, true, true).is_empty());
+        assert_eq!(
+            priority_wrapper(ExportPriority::Low, true, true),
+            ["nice", "-n", "10", "ionice", "-c", "2", "-n", "7"]
+        );
+        assert_eq!(
+            priority_wrapper(ExportPriority::Idle, true, false),
+            ["nice", "-n", "19"]
+        );
+        assert!(priority_wrapper(ExportPriority::Idle, false, false).is_empty());
+    }
+
+    #[test]
+    fn test_apply_to_args_limits_threads_and_overrides_preset() {
+        let resources = ExportResources {
+            threads: Some(4),
+            priority: ExportPriority::Low,
+            preset: Some("veryfast".to_string()),
+            stream_events: false,
+        };
+        let mut args: Vec<String> = [
+            "-y", "-i", "in.mkv", "-c:v", "libx264", "-preset", "medium", "out.mp4",
+        ]
+        .map(String::from)
+        .to_vec();
+        resources.apply_to_args(&mut args);
+
+        assert_eq!(
+            args,
+            [
+                "-y",
+                "-filter_complex_threads",
+                "4",
+                "-i",
+                "in.mkv",
+                "-c:v",
+                "libx264",
+                "-preset",
+                "veryfast",
+                "-threads",
+                "4",
+                "out.mp4"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_default_resources_leave_args_alone() {
+        let mut args: Vec<String> = ["-i", "in.mkv", "out.gif"].map(String::from).to_vec();
+        ExportResources::default().apply_to_args(&mut args);
+        assert_eq!(args, ["-i", "in.mkv", "out.gif"]);
+    }
+
+    #[test]
+    fn test_parse_priority_and_preset() {
+        assert_eq!("Idle".parse::<ExportPriority>(), Ok(ExportPriority::Idle));
+        assert!("high".parse::<ExportPriority>().is_err());
+        assert_eq!(parse_encoder_preset("Faster").unwrap(), "faster");
+        assert!(parse_encoder_preset("quick").is_err());
+    }
+}
@@ -0,0 +1,543 @@
+//! Slow-motion and freeze-frame retiming.
+//!
+//! Speed segments are applied last, to the finished `[vout]` picture, so
+//! camera, cursor, and overlay expressions keep working in source time.
+//! The picture is normalised to the output frame rate, split into one
+//! branch per piece of the plan, and the pieces are concatenated again:
+//!
+//! - normal pieces are trimmed out unchanged;
+//! - slow-motion pieces have their timestamps stretched and are filled
+//!   back up to the frame rate by repeating frames, or by blending them
+//!   with `minterpolate` when the segment asks for it;
+//! - freezes take a single frame and hold it with `tpad`.
+//!
+//! Audio, including the screen recording's own, follows the same plan. Freezes are always silent, slow-motion is
+//! silent or time-stretched with `atempo` as the segment says, and every
+//! piece is padded to the exact length of its picture so later pieces stay
+//! in sync.
+
+use grabme_project_model::timeline::{SlowMotionAudio, SpeedSegment};
+
+/// Sample rate and layout the audio is brought to before it is split, so
+/// silent pieces concatenate with it.
+const AUDIO_FORMAT: &str = "sample_rates=48000:channel_layouts=stereo";
+const SILENCE_SOURCE: &str = "anullsrc=r=48000:cl=stereo";
+
+/// Audio map of the screen recording's own audio, when it has any.
+const SCREEN_AUDIO_MAP: &str = "0:a?";
+
+/// Shortest slow-motion or freeze piece worth rendering.
+const MIN_PIECE_SECS: f64 = 0.05;
+
+/// One stretch of output, in source seconds.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RetimePiece {
+    /// Source played unchanged; `end_secs` is `None` for the final piece.
+    Play {
+        start_secs: f64,
+        end_secs: Option<f64>,
+    },
+    Slow {
+        start_secs: f64,
+        end_secs: f64,
+        rate: f64,
+        audio: SlowMotionAudio,
+        interpolate: bool,
+    },
+    Freeze {
+        at_secs: f64,
+        duration_secs: f64,
+    },
+}
+
+/// The timeline's speed segments turned into a gapless run of pieces.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetimePlan {
+    pub pieces: Vec<RetimePiece>,
+}
+
+impl RetimePlan {
+    /// Plan `segments` over a render ending at `end_secs` of source time.
+    ///
+    /// Segments past the end, empty ones, and ones overlapping an earlier
+    /// slow-motion segment are dropped. Returns `None` when nothing is
+    /// left to retime.
+    pub fn new(segments: &[SpeedSegment], end_secs: f64) -> Option<Self> {
+        let mut sorted: Vec<&SpeedSegment> = segments.iter().collect();
+        // Freezes sort before a slow-motion segment starting at the same time.
+        sorted.sort_by(|a, b| {
+            let (a_start, a_end) = a.source_range();
+            let (b_start, b_end) = b.source_range();
+            a_start.total_cmp(&b_start).then(a_end.total_cmp(&b_end))
+        });
+
+        let mut pieces = Vec::new();
+        let mut cursor = 0.0_f64;
+        for segment in sorted {
+            let (start, end) = segment.source_range();
+            if !start.is_finite() || !end.is_finite() || start < 0.0 || start >= end_secs {
+                continue;
+            }
+            if start < cursor - 1e-9 {
+                tracing::warn!(
+                    ?segment,
+                    "Dropping speed segment overlapping an earlier one"
+                );
+                continue;
+            }
+
+            let piece = match *segment {
+                SpeedSegment::SlowMotion {
+                    rate,
+                    audio,
+                    interpolate,
+                    ..
+                } => {
+                    let end = end.min(end_secs);
+                    if end - start < MIN_PIECE_SECS {
+                        continue;
+                    }
+                    RetimePiece::Slow {
+                        start_secs: start,
+                        end_secs: end,
+                        rate: rate.clamp(SpeedSegment::MIN_RATE, SpeedSegment::MAX_RATE),
+                        audio,
+                        interpolate,
+                    }
+                }
+                SpeedSegment::Freeze { duration_secs, .. } => {
+                    if !duration_secs.is_finite() || duration_secs < MIN_PIECE_SECS {
+                        continue;
+                    }
+                    RetimePiece::Freeze {
+                        at_secs: start,
+                        duration_secs,
+                    }
+                }
+            };
+
+            if start > cursor {
+                pieces.push(RetimePiece::Play {
+                    start_secs: cursor,
+                    end_secs: Some(start),
+                });
+            }
+            cursor = cursor.max(end.min(end_secs));
+            pieces.push(piece);
+        }
+
+        if pieces.is_empty() {
+            return None;
+        }
+        pieces.push(RetimePiece::Play {
+            start_secs: cursor,
+            end_secs: None,
+        });
+        Some(Self { pieces })
+    }
+
+    /// Output time at which source time `source_secs` is shown. A time
+    /// with a freeze maps to the start of the freeze.
+    pub fn output_secs(&self, source_secs: f64) -> f64 {
+        let source_secs = source_secs.max(0.0);
+        let mut output = 0.0;
+        for piece in &self.pieces {
+            match *piece {
+                RetimePiece::Play {
+                    start_secs,
+                    end_secs,
+                } => {
+                    let end = end_secs.unwrap_or(f64::INFINITY);
+                    if source_secs <= end {
+                        return output + (source_secs - start_secs);
+                    }
+                    output += end - start_secs;
+                }
+                RetimePiece::Slow {
+                    start_secs,
+                    end_secs,
+                    rate,
+                    ..
+                } => {
+                    if source_secs <= end_secs {
+                        return output + (source_secs - start_secs) / rate;
+                    }
+                    output += (end_secs - start_secs) / rate;
+                }
+                RetimePiece::Freeze { duration_secs, .. } => output += duration_secs,
+            }
+        }
+        output
+    }
+
+    /// Source time shown at output time `output_secs`; the inverse of
+    /// [`Self::output_secs`]. Any time during a freeze maps to the frozen
+    /// frame.
+    pub fn source_secs(&self, output_secs: f64) -> f64 {
+        let mut remaining = output_secs.max(0.0);
+        for piece in &self.pieces {
+            match *piece {
+                RetimePiece::Play {
+                    start_secs,
+                    end_secs,
+                } => {
+                    let len = end_secs.map_or(f64::INFINITY, |end| end - start_secs);
+                    if remaining <= len {
+                        return start_secs + remaining;
+                    }
+                    remaining -= len;
+                }
+                RetimePiece::Slow {
+                    start_secs,
+                    end_secs,
+                    rate,
+                    ..
+                } => {
+                    let len = (end_secs - start_secs) / rate;
+                    if remaining <= len {
+                        return start_secs + remaining * rate;
+                    }
+                    remaining -= len;
+                }
+                RetimePiece::Freeze {
+                    at_secs,
+                    duration_secs,
+                } => {
+                    if remaining <= duration_secs {
+                        return at_secs;
+                    }
+                    remaining -= duration_secs;
+                }
+            }
+        }
+        remaining
+    }
+}
+
+/// Retime the final `[vout]` picture of `filter_graph` by `plan`, at `fps`.
+///
+/// `audio_map` is the audio the export would otherwise map. It is retimed
+/// alongside the picture and the new label returned. Screen audio (`0:a?`)
+/// is retimed when `screen_has_audio` says input 0 has an audio stream; an
+/// optional map of a stream that isn't there maps nothing either way.
+pub fn append_retime(
+    filter_graph: &mut String,
+    plan: &RetimePlan,
+    fps: u32,
+    audio_map: Option<&str>,
+    screen_has_audio: bool,
+) -> Option<String> {
+    if !filter_graph.ends_with("[vout]") {
+        return audio_map.map(str::to_string);
+    }
+    let fps = fps.max(1);
+    let audio_source = audio_map.and_then(|map| match audio_source_label(map) {
+        Some(label) => Some(label),
+        None => (map == SCREEN_AUDIO_MAP && screen_has_audio).then(|| "[0:a:0]".to_string()),
+    });
+
+    let pieces = &plan.pieces;
+    filter_graph.truncate(filter_graph.len() - "[vout]".len());
+    filter_graph.push_str(&format!(
+        "[vretime];[vretime]fps={fps},split={}",
+        pieces.len()
+    ));
+    for idx in 0..pieces.len() {
+        filter_graph.push_str(&format!("[vr{idx}]"));
+    }
+    for (idx, piece) in pieces.iter().enumerate() {
+        filter_graph.push_str(&format!(";[vr{idx}]{}[vp{idx}]", video_piece(piece, fps)));
+    }
+
+    if let Some(source) = &audio_source {
+        let branches: Vec<usize> = pieces
+            .iter()
+            .enumerate()
+            .filter(|(_, piece)| audio_uses_source(piece))
+            .map(|(idx, _)| idx)
+            .collect();
+        filter_graph.push_str(&format!(
+            ";{source}aresample=async=1:first_pts=0,aformat={AUDIO_FORMAT},asplit={}",
+            branches.len()
+        ));
+        // The final piece always plays the source, so there is a branch.
+        for idx in &branches {
+            filter_graph.push_str(&format!("[ar{idx}]"));
+        }
+        for (idx, piece) in pieces.iter().enumerate() {
+            let chain = audio_piece(piece);
+            if audio_uses_source(piece) {
+                filter_graph.push_str(&format!(";[ar{idx}]{chain}[ap{idx}]"));
+            } else {
+                filter_graph.push_str(&format!(";{chain}[ap{idx}]"));
+            }
+        }
+    }
+
+    filter_graph.push(';');
+    for idx in 0..pieces.len() {
+        filter_graph.push_str(&format!("[vp{idx}]"));
+        if audio_source.is_some() {
+            filter_graph.push_str(&format!("[ap{idx}]"));
+        }
+    }
+    if audio_source.is_some() {
+        filter_graph.push_str(&format!(
+            "concat=n={}:v=1:a=1[vout][aretimed]",
+            pieces.len()
+        ));
+        Some("[aretimed]".to_string())
+    } else {
+        filter_graph.push_str(&format!("concat=n={}:v=1:a=0[vout]", pieces.len()));
+        None
+    }
+}
+
+/// Filter label for an export audio map: mixes are labels already, and a
+/// single track `N:a:0?` becomes its input stream. Screen audio is `None`.
+pub(crate) fn audio_source_label(audio_map: &str) -> Option<String> {
+    if audio_map.starts_with('[') {
+        return Some(audio_map.to_string());
+    }
+    let index = audio_map.strip_suffix(":a:0?")?;
+    (index != "0").then(|| format!("[{index}:a:0]"))
+}
+
+fn video_piece(piece: &RetimePiece, fps: u32) -> String {
+    match *piece {
+        RetimePiece::Play {
+            start_secs,
+            end_secs: Some(end_secs),
+        } => format!("trim=start={start_secs:.6}:end={end_secs:.6},setpts=PTS-STARTPTS"),
+        RetimePiece::Play {
+            start_secs,
+            end_secs: None,
+        } => format!("trim=start={start_secs:.6},setpts=PTS-STARTPTS"),
+        RetimePiece::Slow {
+            start_secs,
+            end_secs,
+            rate,
+            interpolate,
+            ..
+        } => {
+            let fill = if interpolate {
+                format!("minterpolate=fps={fps}:mi_mode=blend")
+            } else {
+                format!("fps={fps}")
+            };
+            format!(
+                "trim=start={start_secs:.6}:end={end_secs:.6},setpts=(PTS-STARTPTS)/{rate:.4},{fill},trim=duration={:.6}",
+                (end_secs - start_secs) / rate
+            )
+        }
+        // The picture is at `fps` already, so one frame interval holds
+        // exactly one frame.
+        RetimePiece::Freeze {
+            at_secs,
+            duration_secs,
+        } => format!(
+            "trim=start={at_secs:.6}:duration={:.6},setpts=PTS-STARTPTS,tpad=stop_mode=clone:stop_duration={duration_secs:.6},trim=duration={duration_secs:.6}",
+            1.0 / fps as f64
+        ),
+    }
+}
+
+fn audio_uses_source(piece: &RetimePiece) -> bool {
+    match piece {
+        RetimePiece::Play { .. } => true,
+        RetimePiece::Slow { audio, .. } => *audio == SlowMotionAudio::Stretch,
+        RetimePiece::Freeze { .. } => false,
+    }
+}
+
+fn audio_piece(piece: &RetimePiece) -> String {
+    match *piece {
+        RetimePiece::Play {
+            start_secs,
+            end_secs: Some(end_secs),
+        } => format!(
+            "atrim=start={start_secs:.6}:end={end_secs:.6},asetpts=PTS-STARTPTS,apad,atrim=duration={:.6}",
+            end_secs - start_secs
+        ),
+        RetimePiece::Play {
+            start_secs,
+            end_secs: None,
+        } => format!("atrim=start={start_secs:.6},asetpts=PTS-STARTPTS"),
+        RetimePiece::Slow {
+            start_secs,
+            end_secs,
+            rate,
+            audio: SlowMotionAudio::Stretch,
+            ..
+        } => format!(
+            "atrim=start={start_secs:.6}:end={end_secs:.6},asetpts=PTS-STARTPTS,{},apad,atrim=duration={:.6}",
+            atempo_chain(rate),
+            (end_secs - start_secs) / rate
+        ),
+        RetimePiece::Slow {
+            start_secs,
+            end_secs,
+            rate,
+            ..
+        } => silence((end_secs - start_secs) / rate),
+        RetimePiece::Freeze { duration_secs, .. } => silence(duration_secs),
+    }
+}
+
+fn silence(duration_secs: f64) -> String {
+    format!("{SILENCE_SOURCE},atrim=duration={duration_secs:.6}")
+}
+
+/// `atempo` filters slowing audio to `rate`. A single `atempo` only goes
+/// down to half speed, so slower rates are chained.
+fn atempo_chain(rate: f64) -> String {
+    let mut remaining = rate;
+    let mut stages = Vec::new();
+    while remaining < 0.5 - 1e-9 {
+        stages.push("atempo=0.5".to_string());
+        remaining /= 0.5;
+    }
+    stages.push(format!("atempo={remaining:.4}"));
+    stages.join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slow(start_secs: f64, end_secs: f64, rate: f64) -> SpeedSegment {
+        SpeedSegment::SlowMotion {
+            start_secs,
+            end_secs,
+            rate,
+            audio: SlowMotionAudio::Mute,
+            interpolate: false,
+        }
+    }
+
+    #[test]
+    fn test_plan_fills_gaps_and_drops_overlaps() {
+        let segments = vec![
+            SpeedSegment::Freeze {
+                at_secs: 6.0,
+                duration_secs: 1.5,
+            },
+            slow(2.0, 4.0, 0.5),
+            slow(3.0, 5.0, 0.25),
+            slow(20.0, 21.0, 0.5),
+        ];
+        let plan = RetimePlan::new(&segments, 10.0).unwrap();
+        assert_eq!(
+            plan.pieces,
+            vec![
+                RetimePiece::Play {
+                    start_secs: 0.0,
+                    end_secs: Some(2.0),
+                },
+                RetimePiece::Slow {
+                    start_secs: 2.0,
+                    end_secs: 4.0,
+                    rate: 0.5,
+                    audio: SlowMotionAudio::Mute,
+                    interpolate: false,
+                },
+                RetimePiece::Play {
+                    start_secs: 4.0,
+                    end_secs: Some(6.0),
+                },
+                RetimePiece::Freeze {
+                    at_secs: 6.0,
+                    duration_secs: 1.5,
+                },
+                RetimePiece::Play {
+                    start_secs: 6.0,
+                    end_secs: None,
+                },
+            ]
+        );
+        assert!(RetimePlan::new(&[slow(12.0, 13.0, 0.5)], 10.0).is_none());
+    }
+
+    #[test]
+    fn test_output_secs_stretches_after_segments() {
+        let segments = vec![
+            slow(2.0, 4.0, 0.5),
+            SpeedSegment::Freeze {
+                at_secs: 6.0,
+                duration_secs: 1.5,
+            },
+        ];
+        let plan = RetimePlan::new(&segments, 10.0).unwrap();
+        assert_eq!(plan.output_secs(1.0), 1.0);
+        assert_eq!(plan.output_secs(3.0), 4.0);
+        assert_eq!(plan.output_secs(5.0), 7.0);
+        // A freeze shows after its time is reached.
+        assert_eq!(plan.output_secs(6.0), 8.0);
+        assert_eq!(plan.output_secs(10.0), 13.5);
+
+        assert_eq!(plan.source_secs(4.0), 3.0);
+        assert_eq!(plan.source_secs(8.5), 6.0);
+        assert_eq!(plan.source_secs(13.5), 10.0);
+    }
+
+    #[test]
+    fn test_append_retime_concats_video_and_stretched_audio() {
+        let segments = vec![
+            SpeedSegment::SlowMotion {
+                start_secs: 1.0,
+                end_secs: 2.0,
+                rate: 0.25,
+                audio: SlowMotionAudio::Stretch,
+                interpolate: true,
+            },
+            SpeedSegment::Freeze {
+                at_secs: 3.0,
+                duration_secs: 1.0,
+            },
+        ];
+        let plan = RetimePlan::new(&segments, 5.0).unwrap();
+        let mut filter = "[scene]null[vout]".to_string();
+        let audio = append_retime(&mut filter, &plan, 30, Some("4:a:0?"), false);
+
+        assert_eq!(audio.as_deref(), Some("[aretimed]"));
+        assert!(filter.starts_with("[scene]null[vretime];[vretime]fps=30,split=5"));
+        assert!(filter.contains(
+            "[vr1]trim=start=1.000000:end=2.000000,setpts=(PTS-STARTPTS)/0.2500,minterpolate=fps=30:mi_mode=blend,trim=duration=4.000000[vp1]"
+        ));
+        assert!(filter.contains("tpad=stop_mode=clone:stop_duration=1.000000"));
+        assert!(filter.contains(";[4:a:0]aresample=async=1:first_pts=0,aformat="));
+        assert!(filter.contains("asplit=4[ar0][ar1][ar2][ar4]"));
+        assert!(filter.contains("atempo=0.5,atempo=0.5000,apad"));
+        assert!(filter.contains(";anullsrc=r=48000:cl=stereo,atrim=duration=1.000000[ap3]"));
+        assert!(filter.ends_with(
+            "[vp0][ap0][vp1][ap1][vp2][ap2][vp3][ap3][vp4][ap4]concat=n=5:v=1:a=1[vout][aretimed]"
+        ));
+    }
+
+    #[test]
+    fn test_append_retime_retimes_screen_audio_when_present() {
+        let plan = RetimePlan::new(
+            &[SpeedSegment::Freeze {
+                at_secs: 1.0,
+                duration_secs: 1.0,
+            }],
+            5.0,
+        )
+        .unwrap();
+        let mut filter = "[scene]null[vout]".to_string();
+        let audio = append_retime(&mut filter, &plan, 30, Some("0:a?"), true);
+        assert_eq!(audio.as_deref(), Some("[aretimed]"));
+        assert!(filter.contains(";[0:a:0]aresample=async=1:first_pts=0,aformat="));
+        assert!(
+            filter.ends_with("[vp0][ap0][vp1][ap1][vp2][ap2]concat=n=3:v=1:a=1[vout][aretimed]")
+        );
+
+        // A screen recording without audio has nothing to retime.
+        let mut filter = "[scene]null[vout]".to_string();
+        assert_eq!(
+            append_retime(&mut filter, &plan, 30, Some("0:a?"), false),
+            None
+        );
+        assert!(filter.ends_with("[vp0][vp1][vp2]concat=n=3:v=1:a=0[vout]"));
+    }
+}
@@ -0,0 +1,427 @@
+//! Shrinking a project's raw sources once it has been exported.
+//!
+//! Raw captures are encoded for speed, not size, and dominate a project's
+//! footprint. Slimming either re-encodes them — video with a slower CRF
+//! encode, PCM audio losslessly to FLAC — so the project stays editable, or
+//! deletes them outright. Events, the timeline, and exports are never
+//! touched, and every change is recorded in `project.json` under
+//! `slimmed_sources`.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use grabme_common::error::{GrabmeError, GrabmeResult};
+use grabme_project_model::project::{LoadedProject, SlimAction, SlimmedSource, TrackRef, Tracks};
+
+use crate::export::{command_exists, ffmpeg_loglevel};
+use crate::probe::probe_media;
+
+/// x264 CRF used when re-encoding video sources.
+pub const DEFAULT_SLIM_CRF: u32 = 26;
+
+/// Only files under this directory are raw sources; music beds and other
+/// user-supplied files are left alone.
+const SOURCES_PREFIX: &str = "sources/";
+
+/// What to do with the raw sources.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlimMode {
+    /// Re-encode to smaller files, keeping the project editable.
+    Reencode { crf: u32 },
+    /// Delete them; the project can no longer be rendered.
+    Delete,
+}
+
+/// Kind of media in a raw source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceKind {
+    Video,
+    Audio,
+}
+
+/// A raw source that slimming would act on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlimTarget {
+    /// Track name for display (`Screen`, `Mic`, ...).
+    pub label: String,
+    /// Path relative to the project root.
+    pub path: String,
+    pub kind: SourceKind,
+    /// Current size on disk.
+    pub bytes: u64,
+}
+
+/// Raw sources of `loaded` that `mode` would act on.
+///
+/// Re-encoding skips sources it has re-encoded before and audio that is
+/// not WAV.
+pub fn slim_targets(loaded: &LoadedProject, mode: SlimMode) -> Vec<SlimTarget> {
+    let project = &loaded.project;
+    source_tracks(&project.tracks)
+        .into_iter()
+        .filter(|(_, _, track)| track.path.starts_with(SOURCES_PREFIX))
+        .filter(|(_, kind, track)| match mode {
+            SlimMode::Delete => true,
+            SlimMode::Reencode { .. } => {
+                let reencoded = project.slimmed_sources.iter().any(|slimmed| {
+                    slimmed.action == SlimAction::Reencoded
+                        && slimmed.new_path.as_deref().unwrap_or(&slimmed.path) == track.path
+                });
+                !reencoded && (*kind == SourceKind::Video || is_wav(&track.path))
+            }
+        })
+        .filter_map(|(label, kind, track)| {
+            let bytes = std::fs::metadata(loaded.root.join(&track.path)).ok()?.len();
+            Some(SlimTarget {
+                label,
+                path: track.path.clone(),
+                kind,
+                bytes,
+            })
+        })
+        .collect()
+}
+
+/// Check that the project's last export exists and holds playable media,
+/// returning its path.
+pub fn verify_last_export(loaded: &LoadedProject) -> GrabmeResult<PathBuf> {
+    let record =
+        loaded.project.last_export.as_ref().ok_or_else(|| {
+            GrabmeError::render("Project has no recorded export; export it first")
+        })?;
+    let path = record.resolve(&loaded.root);
+    if !path.exists() {
+        return Err(GrabmeError::FileNotFound { path });
+    }
+
+    if record.format.is_image_sequence() {
+        let has_frames = std::fs::read_dir(&path)?
+            .flatten()
+            .any(|entry| entry.path().extension().is_some_and(|ext| ext == "png"));
+        if !has_frames {
+            return Err(GrabmeError::render(format!(
+                "Last export {} holds no frames",
+                path.display()
+            )));
+        }
+        return Ok(path);
+    }
+
+    let info = probe_media(&path)?;
+    let playable = info.video.is_some() && info.duration_secs.is_some_and(|secs| secs > 0.0);
+    if !playable {
+        return Err(GrabmeError::render(format!(
+            "Last export {} has no playable video",
+            path.display()
+        )));
+    }
+    Ok(path)
+}
+
+/// Slim the raw sources of the project at `project_dir`.
+///
+/// `project.json` is saved after each source, so an interrupted run still
+/// records what it did. Re-encodes that come out larger than the original
+/// are discarded.
+pub fn slim_project(project_dir: &Path, mode: SlimMode) -> GrabmeResult<Vec<SlimmedSource>> {
+    if matches!(mode, SlimMode::Reencode { .. }) && !command_exists("ffmpeg") {
+        return Err(GrabmeError::unsupported(
+            "Re-encoding sources requires ffmpeg in PATH",
+        ));
+    }
+
+    let mut loaded = LoadedProject::load(project_dir)
+        .map_err(|e| GrabmeError::render(format!("Failed to load project: {e}")))?;
+    let mut slimmed = Vec::new();
+    for target in slim_targets(&loaded, mode) {
+        let source_path = loaded.root.join(&target.path);
+        let record = match mode {
+            SlimMode::Delete => {
+                std::fs::remove_file(&source_path)?;
+                tracing::info!(path = %target.path, bytes = target.bytes, "Deleted raw source");
+                slimmed_source(&target, SlimAction::Deleted, 0, None)
+            }
+            SlimMode::Reencode { crf } => {
+                let Some(record) = reencode_source(&mut loaded, &target, crf)? else {
+                    continue;
+                };
+                record
+            }
+        };
+        loaded.project.slimmed_sources.push(record.clone());
+        loaded
+            .save()
+            .map_err(|e| GrabmeError::render(format!("Failed to save project: {e}")))?;
+        slimmed.push(record);
+    }
+    Ok(slimmed)
+}
+
+/// Re-encode one source, pointing its track at the result. `None` when the
+/// re-encode was not smaller and the original was kept.
+fn reencode_source(
+    loaded: &mut LoadedProject,
+    target: &SlimTarget,
+    crf: u32,
+) -> GrabmeResult<Option<SlimmedSource>> {
+    let new_path = reencoded_path(&target.path, target.kind);
+    let input = loaded.root.join(&target.path);
+    let output = match target.kind {
+        // Written beside the original and renamed over it once it is good.
+        SourceKind::Video => loaded.root.join(&new_path).with_extension("slim.mkv"),
+        SourceKind::Audio => loaded.root.join(&new_path),
+    };
+
+    let result = Command::new("ffmpeg")
+        .args(reencode_args(&input, &output, target.kind, crf))
+        .output()
+        .map_err(|e| GrabmeError::render(format!("Failed to launch ffmpeg: {e}")))?;
+    if !result.status.success() {
+        let _ = std::fs::remove_file(&output);
+        return Err(GrabmeError::render(format!(
+            "ffmpeg failed to re-encode {}: {}",
+            target.path,
+            String::from_utf8_lossy(&result.stderr).trim()
+        )));
+    }
+
+    let slimmed_bytes = std::fs::metadata(&output)?.len();
+    if slimmed_bytes >= target.bytes {
+        tracing::info!(
+            path = %target.path,
+            original_bytes = target.bytes,
+            slimmed_bytes,
+            "Re-encode is not smaller; keeping the original"
+        );
+        std::fs::remove_file(&output)?;
+        return Ok(None);
+    }
+
+    match target.kind {
+        SourceKind::Video => std::fs::rename(&output, &input)?,
+        SourceKind::Audio => {
+            std::fs::remove_file(&input)?;
+            if let Some(track) = track_mut(&mut loaded.project.tracks, &target.path) {
+                track.path = new_path.clone();
+                track.codec = "flac".to_string();
+            }
+        }
+    }
+    tracing::info!(
+        path = %target.path,
+        original_bytes = target.bytes,
+        slimmed_bytes,
+        "Re-encoded raw source"
+    );
+    let changed_path = (new_path != target.path).then_some(new_path);
+    Ok(Some(slimmed_source(
+        target,
+        SlimAction::Reencoded,
+        slimmed_bytes,
+        changed_path,
+    )))
+}
+
+fn slimmed_source(
+    target: &SlimTarget,
+    action: SlimAction,
+    slimmed_bytes: u64,
+    new_path: Option<String>,
+) -> SlimmedSource {
+    SlimmedSource {
+        path: target.path.clone(),
+        action,
+        original_bytes: target.bytes,
+        slimmed_bytes,
+        new_path,
+        slimmed_at: chrono::Utc::now().to_rfc3339(),
+    }
+}
+
+/// ffmpeg arguments re-encoding `input` to `output`.
+///
+/// Video keeps its timestamps and variable frame timing so events stay in
+/// sync, and its audio is copied. Audio becomes lossless FLAC.
+fn reencode_args(input: &Path, output: &Path, kind: SourceKind, crf: u32) -> Vec<String> {
+    let mut args: Vec<String> = vec![
+        "-y".into(),
+        "-hide_banner".into(),
+        "-loglevel".into(),
+        ffmpeg_loglevel(),
+        "-i".into(),
+        input.display().to_string(),
+    ];
+    match kind {
+        SourceKind::Video => args.extend(
+            [
+                "-map",
+                "0",
+                "-c:v",
+                "libx264",
+                "-preset",
+                "slow",
+                "-crf",
+                &crf.to_string(),
+                "-c:a",
+                "copy",
+                "-fps_mode",
+                "passthrough",
+                "-copyts",
+            ]
+            .map(String::from),
+        ),
+        SourceKind::Audio => args.extend(["-map", "0:a", "-c:a", "flac"].map(String::from)),
+    }
+    args.push(output.display().to_string());
+    args
+}
+
+/// Track path after re-encoding: video keeps its path, WAV becomes FLAC.
+fn reencoded_path(path: &str, kind: SourceKind) -> String {
+    match kind {
+        SourceKind::Video => path.to_string(),
+        SourceKind::Audio => Path::new(path)
+            .with_extension("flac")
+            .to_string_lossy()
+            .into_owned(),
+    }
+}
+
+fn is_wav(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("wav"))
+}
+
+/// Every recorded track with a display label and media kind.
+fn source_tracks(tracks: &Tracks) -> Vec<(String, SourceKind, &TrackRef)> {
+    let mut sources = Vec::new();
+    let fixed = [
+        ("Screen", SourceKind::Video, &tracks.screen),
+        ("Webcam", SourceKind::Video, &tracks.webcam),
+        ("PiP", SourceKind::Video, &tracks.pip),
+        ("Mic", SourceKind::Audio, &tracks.mic),
+        ("System audio", SourceKind::Audio, &tracks.system_audio),
+    ];
+    for (label, kind, track) in fixed {
+        if let Some(track) = track {
+            sources.push((label.to_string(), kind, track));
+        }
+    }
+    for app in &tracks.app_audio {
+        sources.push((
+            format!("App audio ({})", app.app_name),
+            SourceKind::Audio,
+            &app.track,
+        ));
+    }
+    sources
+}
+
+fn track_mut<'a>(tracks: &'a mut Tracks, path: &str) -> Option<&'a mut TrackRef> {
+    [
+        &mut tracks.screen,
+        &mut tracks.webcam,
+        &mut tracks.pip,
+        &mut tracks.mic,
+        &mut tracks.system_audio,
+    ]
+    .into_iter()
+    .flatten()
+    .chain(tracks.app_audio.iter_mut().map(|app| &mut app.track))
+    .find(|track| track.path == path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(path: &str) -> TrackRef {
+        TrackRef {
+            path: path.to_string(),
+            duration_secs: 10.0,
+            codec: "h264".to_string(),
+            offset_ns: 0,
+            audio: None,
+            video: None,
+        }
+    }
+
+    #[test]
+    fn test_slim_targets_skip_reencoded_and_compressed_sources() {
+        let root = std::env::temp_dir().join(format!("grabme-slim-test-{}", std::process::id()));
+        let mut loaded = LoadedProject::create(&root, "Slim", 1920, 1080, 60).unwrap();
+        for name in ["screen.mkv", "webcam.mkv", "mic.wav", "system.flac"] {
+            std::fs::write(root.join("sources").join(name), [0u8; 16]).unwrap();
+        }
+        let tracks = &mut loaded.project.tracks;
+        tracks.screen = Some(track("sources/screen.mkv"));
+        tracks.webcam = Some(track("sources/webcam.mkv"));
+        tracks.mic = Some(track("sources/mic.wav"));
+        tracks.system_audio = Some(track("sources/system.flac"));
+        // Declared but never written.
+        tracks.pip = Some(track("sources/pip.mkv"));
+        loaded.project.slimmed_sources.push(SlimmedSource {
+            path: "sources/webcam.mkv".to_string(),
+            action: SlimAction::Reencoded,
+            original_bytes: 64,
+            slimmed_bytes: 16,
+            new_path: None,
+            slimmed_at: "2026-01-01T00:00:00Z".to_string(),
+        });
+
+        let paths = |mode| -> Vec<String> {
+            slim_targets(&loaded, mode)
+                .into_iter()
+                .map(|target| target.path)
+                .collect()
+        };
+        assert_eq!(
+            paths(SlimMode::Reencode { crf: 26 }),
+            vec!["sources/screen.mkv", "sources/mic.wav"]
+        );
+        assert_eq!(
+            paths(SlimMode::Delete),
+            vec![
+                "sources/screen.mkv",
+                "sources/webcam.mkv",
+                "sources/mic.wav",
+                "sources/system.flac"
+            ]
+        );
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_reencode_args_keep_video_timing_and_compress_audio_losslessly() {
+        let video = reencode_args(
+            Path::new("in.mkv"),
+            Path::new("out.mkv"),
+            SourceKind::Video,
+            28,
+        );
+        let joined = video.join(" ");
+        assert!(joined.contains("-c:v libx264 -preset slow -crf 28 -c:a copy"));
+        assert!(joined.contains("-fps_mode passthrough -copyts"));
+        assert_eq!(video.last().map(String::as_str), Some("out.mkv"));
+
+        let audio = reencode_args(
+            Path::new("mic.wav"),
+            Path::new("mic.flac"),
+            SourceKind::Audio,
+            28,
+        );
+        assert!(audio
+            .join(" ")
+            .ends_with("-i mic.wav -map 0:a -c:a flac mic.flac"));
+        assert_eq!(
+            reencoded_path("sources/mic.wav", SourceKind::Audio),
+            "sources/mic.flac"
+        );
+        assert_eq!(
+            reencoded_path("sources/screen.mkv", SourceKind::Video),
+            "sources/screen.mkv"
+        );
+    }
+}
@@ -0,0 +1,390 @@
+//! Periodic still-frame extraction for documentation.
+//!
+//! Pulls PNG frames from the raw screen recording at a fixed interval or at
+//! every left click, and writes an `index.md` that pairs each still with the
+//! transcript line spoken at that moment. Also writes the small per-project
+//! thumbnail shown by `grabme status`.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use grabme_common::error::{GrabmeError, GrabmeResult};
+use grabme_project_model::event::{parse_events, ButtonState, EventKind, InputEvent, MouseButton};
+use grabme_project_model::project::{LoadedProject, TrackRef};
+
+use crate::export::{command_exists, ffmpeg_loglevel, resolve_screen_source, strip_events_header};
+use crate::probe::probe_media;
+use crate::subtitles::{load_project_cues, SubtitleCue};
+
+/// Clicks closer together than this produce a single still.
+const CLICK_STILL_MIN_GAP_SECS: f64 = 0.5;
+
+/// Width of project thumbnails; the height keeps the recording's aspect.
+const THUMBNAIL_WIDTH: u32 = 320;
+
+/// Share of the recording the thumbnail is taken at, past any pre-roll.
+const THUMBNAIL_POSITION: f64 = 0.1;
+
+/// Width of editor preview frames.
+const PREVIEW_WIDTH: u32 = 960;
+
+/// When to take a still.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StillsSchedule {
+    /// One still every N seconds, starting at zero.
+    Every(f64),
+    /// One still per left-button press.
+    PerClick,
+}
+
+/// A still-extraction job.
+#[derive(Debug, Clone)]
+pub struct StillsJob {
+    /// Project root directory.
+    pub project_dir: PathBuf,
+    /// Directory the PNGs and `index.md` are written to.
+    pub output_dir: PathBuf,
+    /// When to take stills.
+    pub schedule: StillsSchedule,
+}
+
+/// One extracted still.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Still {
+    /// Recording time in seconds.
+    pub time_secs: f64,
+    /// Written PNG path.
+    pub path: PathBuf,
+    /// Transcript text at `time_secs`, if subtitles exist.
+    pub caption: Option<String>,
+}
+
+/// Extract stills for `job`, returning them in time order.
+pub fn extract_stills(job: &StillsJob) -> GrabmeResult<Vec<Still>> {
+    if !command_exists("ffmpeg") {
+        return Err(GrabmeError::unsupported(
+            "Still extraction requires ffmpeg in PATH",
+        ));
+    }
+
+    let project = LoadedProject::load(&job.project_dir)
+        .map_err(|e| GrabmeError::render(format!("Failed to load project: {e}")))?;
+    let (screen_path, _, declared_duration_secs) =
+        resolve_screen_source(&job.project_dir, &project)?;
+    let duration_secs = if declared_duration_secs > 0.0 {
+        declared_duration_secs
+    } else {
+        probe_media(&screen_path)
+            .ok()
+            .and_then(|info| info.duration_secs)
+            .unwrap_or(0.0)
+    };
+
+    let events = match job.schedule {
+        StillsSchedule::PerClick => load_events(&job.project_dir)?,
+        StillsSchedule::Every(_) => Vec::new(),
+    };
+    let times = still_times(
+        job.schedule,
+        duration_secs,
+        &events,
+        project.project.tracks.screen.as_ref(),
+    );
+    let cues = load_project_cues(&job.project_dir, &project);
+
+    std::fs::create_dir_all(&job.output_dir)?;
+    let mut stills = Vec::with_capacity(times.len());
+    for (index, time_secs) in times.into_iter().enumerate() {
+        let path = job.output_dir.join(still_file_name(index, time_secs));
+        extract_frame(&screen_path, time_secs, &path, None)?;
+        stills.push(Still {
+            time_secs,
+            path,
+            caption: caption_at(&cues, time_secs),
+        });
+    }
+
+    let index_path = job.output_dir.join("index.md");
+    std::fs::write(&index_path, build_index_markdown(&stills))?;
+    tracing::info!(
+        stills = stills.len(),
+        dir = %job.output_dir.display(),
+        "Extracted stills"
+    );
+
+    Ok(stills)
+}
+
+/// Where a project's thumbnail is cached.
+pub fn project_thumbnail_path(project_dir: &Path) -> PathBuf {
+    project_dir.join("cache").join("thumbnail.png")
+}
+
+/// Write a thumbnail of the screen recording to [`project_thumbnail_path`].
+pub fn write_project_thumbnail(project_dir: &Path) -> GrabmeResult<PathBuf> {
+    if !command_exists("ffmpeg") {
+        return Err(GrabmeError::unsupported(
+            "Thumbnail extraction requires ffmpeg in PATH",
+        ));
+    }
+
+    let project = LoadedProject::load(project_dir)
+        .map_err(|e| GrabmeError::render(format!("Failed to load project: {e}")))?;
+    let (screen_path, _, declared_duration_secs) = resolve_screen_source(project_dir, &project)?;
+    let duration_secs = if declared_duration_secs > 0.0 {
+        declared_duration_secs
+    } else {
+        probe_media(&screen_path)
+            .ok()
+            .and_then(|info| info.duration_secs)
+            .unwrap_or(0.0)
+    };
+
+    let path = project_thumbnail_path(project_dir);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    extract_frame(
+        &screen_path,
+        duration_secs * THUMBNAIL_POSITION,
+        &path,
+        Some(THUMBNAIL_WIDTH),
+    )?;
+    Ok(path)
+}
+
+/// Where the editor's preview frame is cached.
+pub fn preview_frame_path(project_dir: &Path) -> PathBuf {
+    project_dir.join("cache").join("preview.png")
+}
+
+/// Write the screen frame at recording time `time_secs` to
+/// [`preview_frame_path`], for picking regions in the editor.
+pub fn write_preview_frame(project_dir: &Path, time_secs: f64) -> GrabmeResult<PathBuf> {
+    if !command_exists("ffmpeg") {
+        return Err(GrabmeError::unsupported(
+            "Preview frames require ffmpeg in PATH",
+        ));
+    }
+
+    let project = LoadedProject::load(project_dir)
+        .map_err(|e| GrabmeError::render(format!("Failed to load project: {e}")))?;
+    let (screen_path, _, _) = resolve_screen_source(project_dir, &project)?;
+    let time_ns = (time_secs.max(0.0) * 1_000_000_000.0) as u64;
+    let media_secs = project
+        .project
+        .tracks
+        .screen
+        .as_ref()
+        .map_or(time_secs, |track| track.event_to_media_secs(time_ns))
+        .max(0.0);
+
+    let path = preview_frame_path(project_dir);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    extract_frame(&screen_path, media_secs, &path, Some(PREVIEW_WIDTH))?;
+    Ok(path)
+}
+
+/// Screen-track timestamps to capture for `schedule` within `duration_secs`.
+///
+/// Click times are mapped onto the screen track's PTS when its metadata is
+/// known.
+pub fn still_times(
+    schedule: StillsSchedule,
+    duration_secs: f64,
+    events: &[InputEvent],
+    screen: Option<&TrackRef>,
+) -> Vec<f64> {
+    match schedule {
+        StillsSchedule::Every(interval) => {
+            if interval <= 0.0 || duration_secs <= 0.0 {
+                return Vec::new();
+            }
+            (0..)
+                .map(|step| step as f64 * interval)
+                .take_while(|t| *t < duration_secs)
+                .collect()
+        }
+        StillsSchedule::PerClick => {
+            let mut times: Vec<f64> = Vec::new();
+            for event in events {
+                let EventKind::Click {
+                    button: MouseButton::Left,
+                    state: ButtonState::Down,
+                    ..
+                } = event.kind
+                else {
+                    continue;
+                };
+                let t = screen.map_or(event.timestamp_secs(), |track| {
+                    track.event_to_media_secs(event.timestamp_ns)
+                });
+                if t < 0.0 || (duration_secs > 0.0 && t >= duration_secs) {
+                    continue;
+                }
+                if times
+                    .last()
+                    .is_some_and(|last| t - last < CLICK_STILL_MIN_GAP_SECS)
+                {
+                    continue;
+                }
+                times.push(t);
+            }
+            times
+        }
+    }
+}
+
+/// File name for the `index`th still, e.g. `still_003_01m05s250.png`.
+pub fn still_file_name(index: usize, time_secs: f64) -> String {
+    let total_ms = (time_secs.max(0.0) * 1000.0).round() as u64;
+    let minutes = total_ms / 60_000;
+    let seconds = (total_ms % 60_000) / 1000;
+    let millis = total_ms % 1000;
+    format!("still_{index:03}_{minutes:02}m{seconds:02}s{millis:03}.png")
+}
+
+pub(crate) fn load_events(project_dir: &Path) -> GrabmeResult<Vec<InputEvent>> {
+    let events_path = project_dir.join("meta").join("events.jsonl");
+    let content = std::fs::read_to_string(&events_path).map_err(|e| {
+        GrabmeError::render(format!(
+            "Failed to read events file {}: {e}",
+            events_path.display()
+        ))
+    })?;
+    parse_events(&strip_events_header(&content))
+        .map_err(|e| GrabmeError::render(format!("Failed to parse events stream: {e}")))
+}
+
+fn caption_at(cues: &[SubtitleCue], time_secs: f64) -> Option<String> {
+    cues.iter()
+        .find(|cue| cue.start_secs <= time_secs && time_secs <= cue.end_secs)
+        .map(|cue| cue.plain_text().replace('\n', " "))
+}
+
+/// Write the frame at `time_secs` to `output`, scaled to `width` if given.
+fn extract_frame(
+    screen_path: &Path,
+    time_secs: f64,
+    output: &Path,
+    width: Option<u32>,
+) -> GrabmeResult<()> {
+    let mut command = Command::new("ffmpeg");
+    command
+        .args(["-y", "-hide_banner", "-loglevel"])
+        .arg(ffmpeg_loglevel())
+        .arg("-ss")
+        .arg(format!("{time_secs:.3}"))
+        .arg("-i")
+        .arg(screen_path)
+        .args(["-frames:v", "1"]);
+    if let Some(width) = width {
+        command.arg("-vf").arg(format!("scale={width}:-2"));
+    }
+    let result = command
+        .arg(output)
+        .output()
+        .map_err(|e| GrabmeError::render(format!("Failed to launch ffmpeg: {e}")))?;
+
+    if !result.status.success() {
+        return Err(GrabmeError::render(format!(
+            "ffmpeg failed to extract still at {time_secs:.3}s: {}",
+            String::from_utf8_lossy(&result.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+fn build_index_markdown(stills: &[Still]) -> String {
+    let mut markdown = String::from("# Stills\n");
+    for still in stills {
+        let file_name = still
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        markdown.push_str(&format!(
+            "\n## {:.1}s\n\n![{:.1}s]({file_name})\n",
+            still.time_secs, still.time_secs
+        ));
+        if let Some(caption) = &still.caption {
+            markdown.push_str(&format!("\n{caption}\n"));
+        }
+    }
+    markdown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_still_times_every_interval_stays_inside_duration() {
+        let times = still_times(StillsSchedule::Every(10.0), 25.0, &[], None);
+        assert_eq!(times, vec![0.0, 10.0, 20.0]);
+        assert!(still_times(StillsSchedule::Every(0.0), 25.0, &[], None).is_empty());
+    }
+
+    #[test]
+    fn test_still_times_per_click_collapses_double_clicks() {
+        let events = vec![
+            InputEvent::click(
+                1_000_000_000,
+                MouseButton::Left,
+                ButtonState::Down,
+                0.5,
+                0.5,
+            ),
+            InputEvent::click(1_100_000_000, MouseButton::Left, ButtonState::Up, 0.5, 0.5),
+            InputEvent::click(
+                1_200_000_000,
+                MouseButton::Left,
+                ButtonState::Down,
+                0.5,
+                0.5,
+            ),
+            InputEvent::click(
+                3_000_000_000,
+                MouseButton::Right,
+                ButtonState::Down,
+                0.5,
+                0.5,
+            ),
+            InputEvent::click(
+                4_000_000_000,
+                MouseButton::Left,
+                ButtonState::Down,
+                0.2,
+                0.2,
+            ),
+        ];
+        let times = still_times(StillsSchedule::PerClick, 10.0, &events, None);
+        assert_eq!(times, vec![1.0, 4.0]);
+
+        let screen = TrackRef {
+            path: "sources/screen.mkv".to_string(),
+            duration_secs: 10.0,
+            codec: "h264".to_string(),
+            offset_ns: 500_000_000,
+            audio: None,
+            video: None,
+        };
+        let times = still_times(StillsSchedule::PerClick, 10.0, &events, Some(&screen));
+        assert_eq!(times, vec![0.5, 3.5]);
+    }
+
+    #[test]
+    fn test_still_file_name_and_caption_lookup() {
+        assert_eq!(still_file_name(3, 65.25), "still_003_01m05s250.png");
+
+        let cues = vec![SubtitleCue {
+            start_secs: 4.0,
+            end_secs: 6.0,
+            text: "Open the\nsettings".to_string(),
+        }];
+        assert_eq!(caption_at(&cues, 5.0).as_deref(), Some("Open the settings"));
+        assert_eq!(caption_at(&cues, 7.0), None);
+    }
+}
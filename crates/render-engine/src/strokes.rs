@@ -0,0 +1,250 @@
+//! Pen and touch stroke overlay.
+//!
+//! Stylus and touch contacts are grouped into strokes and written as an ASS
+//! script of vector drawings. Each stroke is revealed point by point while
+//! it is drawn and stays on screen for `linger_secs` after the pen lifts.
+
+use std::collections::HashMap;
+
+use grabme_project_model::event::{EventKind, InputEvent, StylusTool, TouchPhase};
+use grabme_project_model::project::StrokeOverlayConfig;
+
+use crate::subtitles::{ass_color, ass_drawing_header, format_ass_time};
+
+/// Points kept per stroke; longer strokes are thinned evenly.
+const MAX_STROKE_POINTS: usize = 48;
+
+/// One sample of a stroke.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StrokePoint {
+    pub t_secs: f64,
+    /// Normalized position.
+    pub x: f64,
+    pub y: f64,
+    /// Normalized pressure; 1.0 when the device doesn't report it.
+    pub pressure: f64,
+}
+
+/// A continuous pen or finger contact, from down to up.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Stroke {
+    pub points: Vec<StrokePoint>,
+}
+
+/// Group stylus and touch events into strokes, ordered by start time.
+/// Eraser contacts are skipped.
+pub fn collect_strokes(events: &[InputEvent]) -> Vec<Stroke> {
+    let mut finished = Vec::new();
+    let mut pen: Option<Stroke> = None;
+    let mut touches: HashMap<u32, Option<Stroke>> = HashMap::new();
+
+    for event in events {
+        let t_secs = event.timestamp_secs();
+        let (active, phase, point) = match event.kind {
+            EventKind::Stylus {
+                tool: StylusTool::Pen,
+                phase,
+                x,
+                y,
+                pressure,
+            } => (
+                &mut pen,
+                phase,
+                StrokePoint {
+                    t_secs,
+                    x,
+                    y,
+                    pressure,
+                },
+            ),
+            EventKind::Touch {
+                id,
+                phase,
+                x,
+                y,
+                pressure,
+            } => {
+                let point = StrokePoint {
+                    t_secs,
+                    x,
+                    y,
+                    pressure: pressure.unwrap_or(1.0),
+                };
+                (touches.entry(id).or_default(), phase, point)
+            }
+            _ => continue,
+        };
+
+        if phase == TouchPhase::Down {
+            finished.extend(active.replace(Stroke::default()));
+        }
+        if let Some(stroke) = active.as_mut() {
+            stroke.points.push(point);
+        }
+        if phase == TouchPhase::Up {
+            finished.extend(active.take());
+        }
+    }
+
+    finished.extend(pen);
+    finished.extend(touches.into_values().flatten());
+    finished.retain(|stroke| !stroke.points.is_empty());
+    finished.sort_by(|a, b| a.points[0].t_secs.total_cmp(&b.points[0].t_secs));
+    finished
+}
+
+/// Build an ASS script drawing `strokes` on a `width`x`height` frame.
+pub fn build_strokes_ass(
+    strokes: &[Stroke],
+    style: &StrokeOverlayConfig,
+    width: u32,
+    height: u32,
+) -> String {
+    let mut script = ass_drawing_header(width, height);
+
+    let color = ass_color(&style.color, "&H00303BFF");
+    let linger = style.linger_secs.max(0.0);
+    for stroke in strokes {
+        let points = thin_points(&stroke.points, MAX_STROKE_POINTS);
+        let pressure = points
+            .iter()
+            .map(|p| p.pressure.clamp(0.0, 1.0))
+            .sum::<f64>()
+            / points.len() as f64;
+        // ASS borders grow outwards from the path on both sides.
+        let border = (style.width.max(0.5) * (0.35 + 0.65 * pressure)) / 2.0;
+        let pixels: Vec<(i64, i64)> = points
+            .iter()
+            .map(|p| {
+                (
+                    (p.x.clamp(0.0, 1.0) * width as f64).round() as i64,
+                    (p.y.clamp(0.0, 1.0) * height as f64).round() as i64,
+                )
+            })
+            .collect();
+
+        // Reveal the stroke as it is drawn; the last segment holds the
+        // whole stroke for the linger time.
+        for idx in 0..points.len() {
+            let start = points[idx].t_secs;
+            let end = points
+                .get(idx + 1)
+                .map(|next| next.t_secs)
+                .unwrap_or(start + linger);
+            if end <= start {
+                continue;
+            }
+            script.push_str(&format!(
+                "Dialogue: 0,{},{},Default,,0,0,0,,{{\\an7\\pos(0,0)\\bord{border:.1}\\shad0\\1a&HFF&\\3c{color}&\\p1}}{}{{\\p0}}\n",
+                format_ass_time(start),
+                format_ass_time(end),
+                polyline_drawing(&pixels[..=idx]),
+            ));
+        }
+    }
+
+    script
+}
+
+/// Evenly thin `points` to at most `max` entries, keeping the last one.
+fn thin_points(points: &[StrokePoint], max: usize) -> Vec<StrokePoint> {
+    if points.len() <= max {
+        return points.to_vec();
+    }
+    let mut kept: Vec<StrokePoint> = (0..max - 1)
+        .map(|i| points[i * (points.len() - 1) / (max - 1)])
+        .collect();
+    kept.push(points[points.len() - 1]);
+    kept
+}
+
+/// ASS drawings are filled shapes, so an open line is drawn as a path that
+/// goes out and comes back. It has no area; only its border shows.
+fn polyline_drawing(points: &[(i64, i64)]) -> String {
+    let (x0, y0) = points[0];
+    let mut path = format!("m {x0} {y0}");
+    if points.len() == 1 {
+        // A tap: a one-pixel segment gives the border something to round.
+        path.push_str(&format!(" l {} {y0} l {x0} {y0}", x0 + 1));
+        return path;
+    }
+    for (x, y) in points[1..]
+        .iter()
+        .chain(points[..points.len() - 1].iter().rev())
+    {
+        path.push_str(&format!(" l {x} {y}"));
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect_strokes_groups_pen_and_touches() {
+        let events = vec![
+            InputEvent::stylus(0, StylusTool::Pen, TouchPhase::Down, 0.1, 0.1, 0.5),
+            InputEvent::touch(10_000_000, 1, TouchPhase::Down, 0.8, 0.8, None),
+            InputEvent::stylus(20_000_000, StylusTool::Pen, TouchPhase::Move, 0.2, 0.1, 0.5),
+            InputEvent::stylus(30_000_000, StylusTool::Pen, TouchPhase::Up, 0.3, 0.1, 0.0),
+            InputEvent::stylus(
+                40_000_000,
+                StylusTool::Eraser,
+                TouchPhase::Down,
+                0.5,
+                0.5,
+                1.0,
+            ),
+            InputEvent::touch(50_000_000, 1, TouchPhase::Up, 0.9, 0.8, None),
+        ];
+
+        let strokes = collect_strokes(&events);
+        assert_eq!(strokes.len(), 2);
+        assert_eq!(strokes[0].points.len(), 3);
+        assert_eq!(strokes[1].points.len(), 2);
+        assert_eq!(strokes[1].points[0].pressure, 1.0);
+    }
+
+    #[test]
+    fn test_build_strokes_ass_reveals_and_lingers() {
+        let stroke = Stroke {
+            points: vec![
+                StrokePoint {
+                    t_secs: 1.0,
+                    x: 0.0,
+                    y: 0.0,
+                    pressure: 1.0,
+                },
+                StrokePoint {
+                    t_secs: 1.5,
+                    x: 0.5,
+                    y: 0.5,
+                    pressure: 1.0,
+                },
+            ],
+        };
+        let style = StrokeOverlayConfig::default();
+        let ass = build_strokes_ass(&[stroke], &style, 200, 100);
+
+        assert!(ass.contains("PlayResX: 200"));
+        assert!(ass.contains("Dialogue: 0,0:00:01.00,0:00:01.50,"));
+        assert!(ass.contains("Dialogue: 0,0:00:01.50,0:00:03.00,"));
+        assert!(ass.contains("m 0 0 l 100 50 l 0 0{\\p0}"));
+    }
+
+    #[test]
+    fn test_thin_points_keeps_last() {
+        let points: Vec<_> = (0..100)
+            .map(|i| StrokePoint {
+                t_secs: i as f64,
+                x: 0.0,
+                y: 0.0,
+                pressure: 1.0,
+            })
+            .collect();
+        let thinned = thin_points(&points, 10);
+        assert_eq!(thinned.len(), 10);
+        assert_eq!(thinned.last().unwrap().t_secs, 99.0);
+    }
+}
@@ -0,0 +1,313 @@
+//! Subtitle burn-in: SRT parsing and styled ASS generation.
+//!
+//! ffmpeg's `subtitles` filter renders SRT with libass defaults only, so
+//! export converts the cues into an ASS script whose style header carries
+//! the project's `SubtitleStyle`.
+
+use std::path::Path;
+
+use grabme_project_model::project::{LoadedProject, SubtitlePosition, SubtitleStyle};
+
+use crate::export::subtitle_source_path;
+
+/// A single timed subtitle cue.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubtitleCue {
+    /// Start time in seconds.
+    pub start_secs: f64,
+    /// End time in seconds.
+    pub end_secs: f64,
+    /// Cue text; lines are separated by `\n`. May carry SRT formatting
+    /// tags such as `<i>`.
+    pub text: String,
+}
+
+impl SubtitleCue {
+    /// Cue text without SRT formatting tags.
+    pub fn plain_text(&self) -> String {
+        replace_srt_tags(&self.text, |tag| ass_override(tag).map(|_| String::new()))
+    }
+}
+
+/// Cues of the project's transcript, or none when it has no subtitle file.
+pub fn load_project_cues(project_dir: &Path, project: &LoadedProject) -> Vec<SubtitleCue> {
+    let path = project_dir.join(subtitle_source_path(project));
+    std::fs::read_to_string(path)
+        .map(|srt| parse_srt(&srt))
+        .unwrap_or_default()
+}
+
+/// Parse SRT content into cues.
+///
+/// Malformed blocks are skipped rather than failing the whole file.
+pub fn parse_srt(content: &str) -> Vec<SubtitleCue> {
+    let normalized = content.replace("\r\n", "\n");
+    let mut cues = Vec::new();
+
+    for block in normalized.split("\n\n") {
+        let mut lines = block.lines().skip_while(|line| line.trim().is_empty());
+        let Some(first) = lines.next() else {
+            continue;
+        };
+        // The numeric index line is optional in practice.
+        let timing = if first.contains("-->") {
+            first
+        } else {
+            match lines.next() {
+                Some(line) => line,
+                None => continue,
+            }
+        };
+        let Some((start, end)) = timing.split_once("-->") else {
+            continue;
+        };
+        let (Some(start_secs), Some(end_secs)) = (
+            parse_srt_timestamp(start.trim()),
+            parse_srt_timestamp(end.split_whitespace().next().unwrap_or("")),
+        ) else {
+            continue;
+        };
+        let text = lines.collect::<Vec<_>>().join("\n");
+        if text.trim().is_empty() || end_secs <= start_secs {
+            continue;
+        }
+        cues.push(SubtitleCue {
+            start_secs,
+            end_secs,
+            text,
+        });
+    }
+
+    cues
+}
+
+/// Parse an SRT timestamp (`HH:MM:SS,mmm`; `.` is accepted as separator).
+fn parse_srt_timestamp(raw: &str) -> Option<f64> {
+    let (hms, millis) = raw.split_once([',', '.']).unwrap_or((raw, "0"));
+    let mut parts = hms.split(':');
+    let hours: u64 = parts.next()?.trim().parse().ok()?;
+    let minutes: u64 = parts.next()?.parse().ok()?;
+    let seconds: u64 = parts.next()?.parse().ok()?;
+    if parts.next().is_some() {
+        return None;
+    }
+    let millis: u64 = millis.trim().parse().ok()?;
+    Some((hours * 3600 + minutes * 60 + seconds) as f64 + millis as f64 / 1000.0)
+}
+
+/// Build an ASS script for `cues`, styled for a `width`x`height` output.
+///
+/// `PlayResX/Y` match the output size so style values are output pixels.
+pub fn build_ass(cues: &[SubtitleCue], style: &SubtitleStyle, width: u32, height: u32) -> String {
+    let safe_area = style.safe_area_ratio.clamp(0.0, 0.25);
+    let margin_h = (width as f64 * safe_area).round() as u32;
+    let margin_v = (height as f64 * safe_area).round() as u32;
+    // Numpad-style alignment, horizontally centred.
+    let alignment = match style.position {
+        SubtitlePosition::Bottom => 2,
+        SubtitlePosition::Middle => 5,
+        SubtitlePosition::Top => 8,
+    };
+    let outline = style.outline_width.max(0.0);
+
+    let mut script = format!(
+        "[Script Info]\n\
+         ScriptType: v4.00+\n\
+         PlayResX: {width}\n\
+         PlayResY: {height}\n\
+         WrapStyle: 0\n\
+         ScaledBorderAndShadow: yes\n\
+         \n\
+         [V4+ Styles]\n\
+         Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding\n\
+         Style: Default,{font},{size},{primary},{primary},{outline_color},&H80000000,0,0,0,0,100,100,0,0,1,{outline:.1},0,{alignment},{margin_h},{margin_h},{margin_v},1\n\
+         \n\
+         [Events]\n\
+         Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\n",
+        font = style.font.replace(',', " "),
+        size = style.font_size.max(1),
+        primary = ass_color(&style.color, "&H00FFFFFF"),
+        outline_color = ass_color(&style.outline_color, "&H00000000"),
+    );
+
+    for cue in cues {
+        script.push_str(&format!(
+            "Dialogue: 0,{},{},Default,,0,0,0,,{}\n",
+            format_ass_time(cue.start_secs),
+            format_ass_time(cue.end_secs),
+            escape_ass_text(&cue.text),
+        ));
+    }
+
+    script
+}
+
+/// Script header for overlays made of `\p1` vector drawings on a
+/// `width`x`height` frame. Drawings are positioned from the top-left.
+pub(crate) fn ass_drawing_header(width: u32, height: u32) -> String {
+    format!(
+        "[Script Info]\n\
+         ScriptType: v4.00+\n\
+         PlayResX: {width}\n\
+         PlayResY: {height}\n\
+         ScaledBorderAndShadow: yes\n\
+         \n\
+         [V4+ Styles]\n\
+         Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding\n\
+         Style: Default,Sans,20,&H00FFFFFF,&H00FFFFFF,&H00FFFFFF,&H00000000,0,0,0,0,100,100,0,0,1,0,0,7,0,0,0,1\n\
+         \n\
+         [Events]\n\
+         Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\n"
+    )
+}
+
+/// Convert `#rrggbb` into ASS `&HAABBGGRR`, falling back on parse errors.
+pub(crate) fn ass_color(hex: &str, fallback: &str) -> String {
+    let digits = hex.trim().trim_start_matches('#');
+    if digits.len() != 6 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return fallback.to_string();
+    }
+    let (r, g, b) = (&digits[0..2], &digits[2..4], &digits[4..6]);
+    format!("&H00{b}{g}{r}").to_ascii_uppercase()
+}
+
+/// Format seconds as an ASS timestamp: `H:MM:SS.cc`.
+pub(crate) fn format_ass_time(secs: f64) -> String {
+    let total_cs = (secs.max(0.0) * 100.0).round() as u64;
+    let hours = total_cs / 360_000;
+    let minutes = (total_cs % 360_000) / 6000;
+    let seconds = (total_cs % 6000) / 100;
+    let centis = total_cs % 100;
+    format!("{hours}:{minutes:02}:{seconds:02}.{centis:02}")
+}
+
+/// Make cue text safe for a Dialogue line: hard line breaks and no
+/// override blocks other than those for SRT formatting tags. Low-confidence
+/// captions are italic and grey this way.
+fn escape_ass_text(text: &str) -> String {
+    text.lines()
+        .map(|line| {
+            let line = line.trim().replace('{', "(").replace('}', ")");
+            replace_srt_tags(&line, ass_override)
+        })
+        .collect::<Vec<_>>()
+        .join("\\N")
+}
+
+/// Replace each `<tag>` in `text` that `convert` maps. Other text,
+/// unknown tags included, is kept.
+fn replace_srt_tags(text: &str, convert: impl Fn(&str) -> Option<String>) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(open) = rest.find('<') {
+        out.push_str(&rest[..open]);
+        let tag = &rest[open..];
+        match tag
+            .find('>')
+            .and_then(|close| Some((close, convert(&tag[1..close])?)))
+        {
+            Some((close, replacement)) => {
+                out.push_str(&replacement);
+                rest = &tag[close + 1..];
+            }
+            None => {
+                out.push('<');
+                rest = &tag[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// ASS override block for an SRT formatting tag: `i`, `b`, `u`,
+/// `font color="#rrggbb"` and their closing tags.
+fn ass_override(tag: &str) -> Option<String> {
+    let tag = tag.trim().to_ascii_lowercase();
+    let code = match tag.as_str() {
+        "i" => "\\i1".to_string(),
+        "/i" => "\\i0".to_string(),
+        "b" => "\\b1".to_string(),
+        "/b" => "\\b0".to_string(),
+        "u" => "\\u1".to_string(),
+        "/u" => "\\u0".to_string(),
+        // Back to the style colour.
+        "/font" => "\\c".to_string(),
+        _ => {
+            let color = tag
+                .strip_prefix("font")?
+                .trim()
+                .strip_prefix("color=")?
+                .trim_matches(['"', '\'']);
+            let color = ass_color(color, "");
+            if color.is_empty() {
+                return None;
+            }
+            format!("\\c{color}&")
+        }
+    };
+    Some(format!("{{{code}}}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_srt_reads_cues_and_skips_malformed_blocks() {
+        let srt = "1\r\n00:00:01,000 --> 00:00:02,500\r\nHello\r\nworld\r\n\r\n\
+                   2\r\nnot a timing line\r\n\r\n\
+                   3\r\n00:01:00.250 --> 00:01:01,000 X1:0\r\nBye\r\n";
+        let cues = parse_srt(srt);
+        assert_eq!(cues.len(), 2);
+        assert_eq!(cues[0].start_secs, 1.0);
+        assert_eq!(cues[0].end_secs, 2.5);
+        assert_eq!(cues[0].text, "Hello\nworld");
+        assert_eq!(cues[1].start_secs, 60.25);
+        assert_eq!(cues[1].text, "Bye");
+    }
+
+    #[test]
+    fn test_build_ass_applies_style_and_safe_area() {
+        let style = SubtitleStyle {
+            font: "Inter".to_string(),
+            font_size: 40,
+            color: "#ffcc00".to_string(),
+            outline_color: "not-a-color".to_string(),
+            outline_width: 2.0,
+            position: SubtitlePosition::Top,
+            safe_area_ratio: 0.1,
+        };
+        let cues = vec![SubtitleCue {
+            start_secs: 1.0,
+            end_secs: 3.456,
+            text: "Line one\n{bold} two".to_string(),
+        }];
+        let ass = build_ass(&cues, &style, 1920, 1080);
+
+        assert!(ass.contains("PlayResX: 1920\nPlayResY: 1080"));
+        assert!(ass.contains(
+            "Style: Default,Inter,40,&H0000CCFF,&H0000CCFF,&H00000000,&H80000000,0,0,0,0,100,100,0,0,1,2.0,0,8,192,192,108,1"
+        ));
+        assert!(
+            ass.contains("Dialogue: 0,0:00:01.00,0:00:03.46,Default,,0,0,0,,Line one\\N(bold) two")
+        );
+    }
+
+    #[test]
+    fn test_srt_formatting_tags_become_ass_overrides() {
+        let cue = SubtitleCue {
+            start_secs: 0.0,
+            end_secs: 1.0,
+            text: "<i><font color=\"#a0a0a0\">clack</font></i> a <b>b</b> <c>".to_string(),
+        };
+        let ass = build_ass(
+            std::slice::from_ref(&cue),
+            &SubtitleStyle::default(),
+            1920,
+            1080,
+        );
+        assert!(ass.contains(",,{\\i1}{\\c&H00A0A0A0&}clack{\\c}{\\i0} a {\\b1}b{\\b0} <c>\n"));
+        assert_eq!(cue.plain_text(), "clack a b <c>");
+    }
+}
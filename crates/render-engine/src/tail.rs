@@ -0,0 +1,390 @@
+//! Idle tail detection.
+//!
+//! Recordings usually end with a few seconds of reaching for the stop
+//! button. This finds where the content ends instead: the last sound on the
+//! mic track and the last click, key press, scroll or pointer movement in
+//! the recorded area. A final pointer movement straight toward the edge of
+//! the recorded area, where the overlay or a terminal sits, is the reach
+//! for the stop button and doesn't count, and neither do inputs in the last
+//! moment before the end, which are the stop click or key itself.
+
+use std::path::Path;
+use std::process::Command;
+
+use grabme_common::error::{GrabmeError, GrabmeResult};
+use grabme_project_model::event::{events_in_range, parse_events, EventKind, InputEvent};
+use grabme_project_model::project::LoadedProject;
+
+use crate::export::{command_exists, resolve_screen_source, strip_events_header};
+use crate::probe::probe_media;
+
+/// Only this much of the end of a recording is looked at.
+pub const TAIL_SCAN_SECS: f64 = 10.0;
+
+/// Kept after the last activity, so the export doesn't end abruptly.
+pub const TAIL_PADDING_SECS: f64 = 0.5;
+
+/// Inputs this close to the end are taken to be the stop click or key.
+const STOP_GRACE_SECS: f64 = 0.75;
+
+/// Tails shorter than this are left alone.
+const MIN_TRIM_SECS: f64 = 0.5;
+
+/// A pointer at rest for longer than this ends a movement.
+const REACH_MAX_PAUSE_SECS: f64 = 0.3;
+
+/// A reach covers at least this share of the recorded area...
+const REACH_MIN_DISTANCE: f64 = 0.15;
+
+/// ...in a path at least this straight (distance over path length)...
+const REACH_MIN_STRAIGHTNESS: f64 = 0.6;
+
+/// ...and ends this close to an edge of the recorded area, or outside it.
+const REACH_EDGE_MARGIN: f64 = 0.05;
+
+/// Sound quieter than this, in dBFS, is silence.
+const SILENCE_NOISE_DB: i32 = -35;
+
+/// Shortest quiet stretch counted as silence.
+const SILENCE_MIN_SECS: f64 = 0.4;
+
+/// Where an export should end to leave out the idle tail.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TailTrim {
+    /// Timeline time the export ends at.
+    pub end_secs: f64,
+    /// How much is cut off the end.
+    pub trimmed_secs: f64,
+    /// Whether the tail ends with a reach for the stop button.
+    pub reach: bool,
+}
+
+/// Trim for the project's recording, or `None` when its end has nothing to
+/// trim.
+pub fn detect_project_tail(project_dir: &Path) -> GrabmeResult<Option<TailTrim>> {
+    let project = LoadedProject::load(project_dir)
+        .map_err(|e| GrabmeError::render(format!("Failed to load project: {e}")))?;
+    let (screen_path, _, declared_secs) = resolve_screen_source(project_dir, &project)?;
+    let end_secs = if declared_secs > 0.0 {
+        declared_secs
+    } else {
+        probe_media(&screen_path)
+            .ok()
+            .and_then(|info| info.duration_secs)
+            .unwrap_or(0.0)
+    };
+
+    let events_path = project_dir.join("meta").join("events.jsonl");
+    let events_content = std::fs::read_to_string(&events_path).map_err(|e| {
+        GrabmeError::render(format!(
+            "Failed to read events file {}: {e}",
+            events_path.display()
+        ))
+    })?;
+    let events = parse_events(&strip_events_header(&events_content))
+        .map_err(|e| GrabmeError::render(format!("Failed to parse events stream: {e}")))?;
+
+    let mic = project
+        .project
+        .tracks
+        .mic
+        .as_ref()
+        .map(|track| (project_dir.join(&track.path), track.offset_ns))
+        .filter(|(path, _)| path.exists());
+    detect_recording_tail(
+        &events,
+        mic.as_ref()
+            .map(|(path, offset_ns)| (path.as_path(), *offset_ns)),
+        end_secs,
+    )
+}
+
+/// [`detect_tail`] for a recording ending at `end_secs`, with speech taken
+/// from the mic track at `mic` (path and offset) when there is one.
+pub(crate) fn detect_recording_tail(
+    events: &[InputEvent],
+    mic: Option<(&Path, i64)>,
+    end_secs: f64,
+) -> GrabmeResult<Option<TailTrim>> {
+    let speech_end_secs = match mic {
+        Some((path, offset_ns)) => detect_sound_end(path, offset_ns, end_secs)?,
+        None => None,
+    };
+    Ok(detect_tail(events, speech_end_secs, end_secs))
+}
+
+/// Where the recording's content ends, given its events and the timeline
+/// time the last sound on the mic stopped (`None` when the end is silent).
+/// `None` when nothing marks an end, or the tail is too short to trim.
+pub fn detect_tail(
+    events: &[InputEvent],
+    speech_end_secs: Option<f64>,
+    end_secs: f64,
+) -> Option<TailTrim> {
+    let scan_start_secs = (end_secs - TAIL_SCAN_SECS).max(0.0);
+    let window = events_in_range(events, scan_start_secs, end_secs);
+    let reach_start_secs = find_reach_start(window);
+    let input_cutoff_secs = reach_start_secs
+        .unwrap_or(f64::INFINITY)
+        .min(end_secs - STOP_GRACE_SECS);
+    let pointer_cutoff_secs = reach_start_secs.unwrap_or(end_secs);
+
+    let mut last_input_secs = None;
+    let mut last_pointer: Option<(f64, f64)> = None;
+    for event in window {
+        let secs = event.timestamp_secs();
+        let active = match &event.kind {
+            // Samples of a pointer at rest are not activity.
+            EventKind::Pointer { x, y } => {
+                let moved = last_pointer.is_some_and(|(px, py)| (x - px).hypot(y - py) > 1e-4);
+                last_pointer = Some((*x, *y));
+                moved && secs < pointer_cutoff_secs && in_recorded_area(event)
+            }
+            EventKind::Click { .. } | EventKind::Scroll { .. } => {
+                secs < input_cutoff_secs && in_recorded_area(event)
+            }
+            EventKind::Key { .. }
+            | EventKind::Touch { .. }
+            | EventKind::Stylus { .. }
+            | EventKind::GamepadButton { .. }
+            | EventKind::GamepadAxis { .. }
+            | EventKind::Reaction { .. } => secs < input_cutoff_secs,
+            _ => false,
+        };
+        if active {
+            last_input_secs = Some(secs);
+        }
+    }
+    let last_activity_secs = [
+        last_input_secs,
+        speech_end_secs.filter(|secs| *secs > scan_start_secs),
+    ]
+    .into_iter()
+    .flatten()
+    .reduce(f64::max);
+
+    let cut_secs = match (last_activity_secs, reach_start_secs) {
+        (Some(secs), _) => secs + TAIL_PADDING_SECS,
+        (None, Some(secs)) => secs,
+        (None, None) => return None,
+    }
+    .min(end_secs);
+    let trimmed_secs = end_secs - cut_secs;
+    (trimmed_secs >= MIN_TRIM_SECS).then_some(TailTrim {
+        end_secs: cut_secs,
+        trimmed_secs,
+        reach: reach_start_secs.is_some(),
+    })
+}
+
+/// Whether a pointer-positioned event was inside the recorded area. Events
+/// without a capture-time hit are assumed to be.
+fn in_recorded_area(event: &InputEvent) -> bool {
+    event.hit.map_or(true, |hit| {
+        (0.0..=1.0).contains(&hit.capture_x) && (0.0..=1.0).contains(&hit.capture_y)
+    })
+}
+
+/// Start of the final pointer movement when it is a reach toward the edge
+/// of the recorded area.
+fn find_reach_start(window: &[InputEvent]) -> Option<f64> {
+    let samples: Vec<(f64, f64, f64)> = window
+        .iter()
+        .filter(|event| matches!(event.kind, EventKind::Pointer { .. }))
+        .filter_map(|event| {
+            let hit = event.hit?;
+            Some((event.timestamp_secs(), hit.capture_x, hit.capture_y))
+        })
+        .collect();
+    let step = |i: usize| {
+        let (_, x0, y0) = samples[i - 1];
+        let (_, x1, y1) = samples[i];
+        (x1 - x0).hypot(y1 - y0)
+    };
+
+    // Samples repeated while the pointer rests on the stop button are not
+    // part of the movement.
+    let last = (1..samples.len()).rev().find(|&i| step(i) > 1e-4)?;
+    let mut first = last;
+    while first > 0
+        && samples[first].0 - samples[first - 1].0 <= REACH_MAX_PAUSE_SECS
+        && step(first) > 1e-4
+    {
+        first -= 1;
+    }
+
+    let path: f64 = (first + 1..=last).map(step).sum();
+    let (start_secs, x0, y0) = samples[first];
+    let (_, x1, y1) = samples[last];
+    let distance = (x1 - x0).hypot(y1 - y0);
+    let at_edge = [x1, y1]
+        .iter()
+        .any(|v| *v <= REACH_EDGE_MARGIN || *v >= 1.0 - REACH_EDGE_MARGIN);
+    (distance >= REACH_MIN_DISTANCE && distance >= path * REACH_MIN_STRAIGHTNESS && at_edge)
+        .then_some(start_secs)
+}
+
+/// Timeline time the last sound before `end_secs` stops on the audio track
+/// at `path` placed at `offset_ns`, looking at most [`TAIL_SCAN_SECS`]
+/// back. `None` when that stretch is silent.
+fn detect_sound_end(path: &Path, offset_ns: i64, end_secs: f64) -> GrabmeResult<Option<f64>> {
+    if !command_exists("ffmpeg") {
+        return Err(GrabmeError::unsupported(
+            "Tail detection requires ffmpeg in PATH",
+        ));
+    }
+    let offset_secs = offset_ns as f64 / 1_000_000_000.0;
+    let scan_start_media_secs = (end_secs - TAIL_SCAN_SECS - offset_secs).max(0.0);
+    let scan_secs = end_secs - offset_secs - scan_start_media_secs;
+    if scan_secs <= 0.0 {
+        return Ok(None);
+    }
+
+    // silencedetect reports at info level; everything else stays quiet.
+    let output = Command::new("ffmpeg")
+        .args(["-hide_banner", "-nostats", "-loglevel", "info", "-ss"])
+        .arg(format!("{scan_start_media_secs:.3}"))
+        .arg("-t")
+        .arg(format!("{scan_secs:.3}"))
+        .arg("-i")
+        .arg(path)
+        .args(["-map", "0:a:0", "-af"])
+        .arg(format!(
+            "silencedetect=noise={SILENCE_NOISE_DB}dB:d={SILENCE_MIN_SECS}"
+        ))
+        .args(["-f", "null", "-"])
+        .output()
+        .map_err(|e| GrabmeError::render(format!("Failed to launch ffmpeg: {e}")))?;
+    if !output.status.success() {
+        return Err(GrabmeError::render(format!(
+            "Tail detection failed on {}: {}",
+            path.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    Ok(parse_sound_end(&stderr, scan_secs).map(|secs| scan_start_media_secs + secs + offset_secs))
+}
+
+/// Where the last sound stops in silencedetect output for a `scan_secs`
+/// long stretch: the start of a silence that runs to the end, or the end
+/// itself when the stretch ends on sound. `None` when it is all silence.
+fn parse_sound_end(stderr: &str, scan_secs: f64) -> Option<f64> {
+    let field = |line: &str, key: &str| -> Option<f64> {
+        let rest = &line[line.find(key)? + key.len()..];
+        rest.split_whitespace().next()?.parse().ok()
+    };
+
+    // Each silence as (start, end); a silence still running at the end of
+    // the input may not be closed.
+    let mut silences: Vec<(f64, Option<f64>)> = Vec::new();
+    for line in stderr.lines() {
+        if let Some(start) = field(line, "silence_start: ") {
+            silences.push((start, None));
+        } else if let Some(end) = field(line, "silence_end: ") {
+            if let Some(last) = silences.last_mut() {
+                last.1 = Some(end);
+            }
+        }
+    }
+
+    match silences.last() {
+        Some(&(start, end)) if end.map_or(true, |end| end >= scan_secs - 0.05) => {
+            (start > 0.0).then_some(start)
+        }
+        _ => Some(scan_secs),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use grabme_project_model::event::{ButtonState, MouseButton, PointerHit};
+
+    fn secs_ns(secs: f64) -> u64 {
+        (secs * 1_000_000_000.0) as u64
+    }
+
+    fn pointer(secs: f64, x: f64, y: f64) -> InputEvent {
+        InputEvent {
+            hit: Some(PointerHit {
+                monitor: Some(0),
+                capture_x: x,
+                capture_y: y,
+            }),
+            ..InputEvent::pointer(secs_ns(secs), x, y)
+        }
+    }
+
+    fn click(secs: f64, x: f64, y: f64) -> InputEvent {
+        InputEvent {
+            hit: Some(PointerHit {
+                monitor: None,
+                capture_x: x,
+                capture_y: y,
+            }),
+            ..InputEvent::click(secs_ns(secs), MouseButton::Left, ButtonState::Down, x, y)
+        }
+    }
+
+    #[test]
+    fn test_reach_for_the_stop_button_is_trimmed() {
+        let mut events = vec![click(21.0, 0.5, 0.5)];
+        // Resting until 25 s, then a quick move to the top-right corner
+        // and on to the overlay on the next monitor.
+        events.extend((0..=10).map(|i| {
+            pointer(
+                25.0 + i as f64 * 0.05,
+                0.5 + i as f64 * 0.06,
+                0.5 - i as f64 * 0.045,
+            )
+        }));
+        events.push(pointer(25.6, 1.1, 0.05));
+        events.push(pointer(25.6 + 1.0, 1.1, 0.05));
+        events.push(click(27.5, 1.1, 0.05));
+
+        let trim = detect_tail(&events, Some(22.0), 28.0).unwrap();
+        assert!(trim.reach);
+        assert_eq!(trim.end_secs, 22.0 + TAIL_PADDING_SECS);
+        assert_eq!(trim.trimmed_secs, 5.5);
+
+        // Without speech or earlier input the reach itself is the end.
+        let trim = detect_tail(&events[1..], None, 28.0).unwrap();
+        assert_eq!(trim.end_secs, 25.0);
+    }
+
+    #[test]
+    fn test_busy_or_unmarked_endings_are_kept() {
+        // Typing until the stop key: only the stop key is ignored.
+        let events = [
+            InputEvent::key(secs_ns(29.2), "KeyA", ButtonState::Down),
+            InputEvent::key(secs_ns(29.7), "KeyC", ButtonState::Down),
+        ];
+        assert_eq!(detect_tail(&events, None, 30.0), None);
+        // Nothing at all happens at the end: no sign of where it stops.
+        assert_eq!(detect_tail(&[], None, 30.0), None);
+        // Wandering pointer that ends mid-screen is not a reach.
+        let events: Vec<_> = (0..20)
+            .map(|i| pointer(26.0 + i as f64 * 0.2, 0.4 + (i % 2) as f64 * 0.1, 0.5))
+            .collect();
+        assert_eq!(detect_tail(&events, None, 30.0), None);
+    }
+
+    #[test]
+    fn test_sound_end_comes_from_trailing_silence() {
+        let closed = "[silencedetect @ 0x1] silence_start: 1.2\n\
+                      [silencedetect @ 0x1] silence_end: 2.0 | silence_duration: 0.8\n\
+                      [silencedetect @ 0x1] silence_start: 6.25\n\
+                      [silencedetect @ 0x1] silence_end: 10 | silence_duration: 3.75\n";
+        assert_eq!(parse_sound_end(closed, 10.0), Some(6.25));
+        let open = "[silencedetect @ 0x1] silence_start: 4.5\n";
+        assert_eq!(parse_sound_end(open, 10.0), Some(4.5));
+        let ends_loud = "[silencedetect @ 0x1] silence_start: 1\n\
+                         [silencedetect @ 0x1] silence_end: 3 | silence_duration: 2\n";
+        assert_eq!(parse_sound_end(ends_loud, 10.0), Some(10.0));
+        assert_eq!(parse_sound_end("", 10.0), Some(10.0));
+        let silent = "[silencedetect @ 0x1] silence_start: 0\n";
+        assert_eq!(parse_sound_end(silent, 10.0), None);
+    }
+}
@@ -0,0 +1,191 @@
+//! Webcam thumbnails for the editor timeline.
+//!
+//! Samples small JPEGs from the webcam track at a fixed interval so the
+//! editor can show the presenter's face along the timeline. Thumbnails live
+//! in `meta/webcam-thumbs/` next to an `index.json` that maps each file to
+//! its screen-timeline time.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use grabme_common::error::{GrabmeError, GrabmeResult};
+use grabme_project_model::project::{LoadedProject, TrackRef};
+use serde::{Deserialize, Serialize};
+
+use crate::export::{command_exists, ffmpeg_loglevel};
+
+/// Directory, relative to the project root, holding the thumbnails.
+pub const WEBCAM_THUMBS_DIR: &str = "meta/webcam-thumbs";
+
+/// Default seconds between thumbnails.
+pub const DEFAULT_WEBCAM_THUMB_INTERVAL_SECS: f64 = 2.0;
+
+/// Thumbnail height in pixels; width follows the webcam aspect ratio.
+const WEBCAM_THUMB_HEIGHT: u32 = 72;
+
+const INDEX_FILE: &str = "index.json";
+
+/// One generated thumbnail.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebcamThumb {
+    /// Position on the screen timeline, in seconds.
+    pub time_secs: f64,
+    /// File name inside [`WEBCAM_THUMBS_DIR`].
+    pub file: String,
+}
+
+/// Generate thumbnails every `interval_secs` from the project's webcam track.
+///
+/// Replaces any existing thumbnails. Returns an empty list when the project
+/// has no webcam track.
+pub fn generate_webcam_thumbnails(
+    project_dir: &Path,
+    interval_secs: f64,
+) -> GrabmeResult<Vec<WebcamThumb>> {
+    let project = LoadedProject::load(project_dir)
+        .map_err(|e| GrabmeError::render(format!("Failed to load project: {e}")))?;
+    let Some(webcam) = project.project.tracks.webcam.as_ref() else {
+        return Ok(Vec::new());
+    };
+    let webcam_path = project_dir.join(&webcam.path);
+    if !webcam_path.exists() {
+        return Err(GrabmeError::render(format!(
+            "Webcam track is missing: {}",
+            webcam_path.display()
+        )));
+    }
+    if interval_secs <= 0.0 {
+        return Err(GrabmeError::render("Thumbnail interval must be positive"));
+    }
+    if !command_exists("ffmpeg") {
+        return Err(GrabmeError::unsupported(
+            "Webcam thumbnails require ffmpeg in PATH",
+        ));
+    }
+
+    let thumbs_dir = project_dir.join(WEBCAM_THUMBS_DIR);
+    if thumbs_dir.exists() {
+        std::fs::remove_dir_all(&thumbs_dir)?;
+    }
+    std::fs::create_dir_all(&thumbs_dir)?;
+
+    let result = Command::new("ffmpeg")
+        .args(["-y", "-hide_banner", "-loglevel"])
+        .arg(ffmpeg_loglevel())
+        .arg("-i")
+        .arg(&webcam_path)
+        .arg("-vf")
+        .arg(format!(
+            "fps=1/{interval_secs},scale=-2:{WEBCAM_THUMB_HEIGHT}"
+        ))
+        .args(["-q:v", "5"])
+        .arg(thumbs_dir.join("thumb_%05d.jpg"))
+        .output()
+        .map_err(|e| GrabmeError::render(format!("Failed to launch ffmpeg: {e}")))?;
+    if !result.status.success() {
+        return Err(GrabmeError::render(format!(
+            "ffmpeg failed to generate webcam thumbnails: {}",
+            String::from_utf8_lossy(&result.stderr).trim()
+        )));
+    }
+
+    let mut files: Vec<String> = std::fs::read_dir(&thumbs_dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .filter(|name| name.starts_with("thumb_") && name.ends_with(".jpg"))
+        .collect();
+    files.sort();
+
+    let thumbs = thumb_index(
+        files,
+        interval_secs,
+        webcam,
+        project.project.tracks.screen.as_ref(),
+    );
+    let index = serde_json::to_string_pretty(&thumbs)
+        .map_err(|e| GrabmeError::render(format!("Failed to encode thumbnail index: {e}")))?;
+    std::fs::write(thumbs_dir.join(INDEX_FILE), index)?;
+    tracing::info!(thumbnails = thumbs.len(), "Generated webcam thumbnails");
+
+    Ok(thumbs)
+}
+
+/// Thumbnails previously written by [`generate_webcam_thumbnails`], or an
+/// empty list when there are none.
+pub fn list_webcam_thumbnails(project_dir: &Path) -> GrabmeResult<Vec<WebcamThumb>> {
+    let index_path = webcam_thumbs_dir(project_dir).join(INDEX_FILE);
+    match std::fs::read_to_string(&index_path) {
+        Ok(json) => serde_json::from_str(&json).map_err(|e| {
+            GrabmeError::render(format!(
+                "Unreadable thumbnail index {}: {e}",
+                index_path.display()
+            ))
+        }),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Absolute thumbnail directory for `project_dir`.
+pub fn webcam_thumbs_dir(project_dir: &Path) -> PathBuf {
+    project_dir.join(WEBCAM_THUMBS_DIR)
+}
+
+/// Place the `n`th sampled frame (webcam PTS `n * interval`) on the screen
+/// timeline, dropping frames that land before the screen track starts.
+fn thumb_index(
+    files: Vec<String>,
+    interval_secs: f64,
+    webcam: &TrackRef,
+    screen: Option<&TrackRef>,
+) -> Vec<WebcamThumb> {
+    files
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, file)| {
+            let event_ns = webcam.media_secs_to_event_ns(index as f64 * interval_secs);
+            let time_secs = screen.map_or(event_ns as f64 / 1_000_000_000.0, |track| {
+                track.event_to_media_secs(event_ns)
+            });
+            (time_secs >= 0.0).then_some(WebcamThumb { time_secs, file })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(path: &str, offset_ns: i64) -> TrackRef {
+        TrackRef {
+            path: path.to_string(),
+            duration_secs: 10.0,
+            codec: "h264".to_string(),
+            offset_ns,
+            audio: None,
+            video: None,
+        }
+    }
+
+    #[test]
+    fn test_thumb_index_maps_webcam_pts_onto_screen_timeline() {
+        let files = vec!["thumb_00001.jpg".to_string(), "thumb_00002.jpg".to_string()];
+        let thumbs = thumb_index(
+            files,
+            2.0,
+            &track("sources/webcam.mkv", 300_000_000),
+            Some(&track("sources/screen.mkv", 100_000_000)),
+        );
+
+        assert_eq!(thumbs.len(), 2);
+        assert!((thumbs[0].time_secs - 0.2).abs() < 1e-9);
+        assert!((thumbs[1].time_secs - 2.2).abs() < 1e-9);
+        assert_eq!(thumbs[1].file, "thumb_00002.jpg");
+    }
+
+    #[test]
+    fn test_list_webcam_thumbnails_without_index_is_empty() {
+        let dir = std::env::temp_dir().join("grabme-webcam-thumbs-empty");
+        assert!(list_webcam_thumbnails(&dir).unwrap().is_empty());
+    }
+}
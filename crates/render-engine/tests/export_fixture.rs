@@ -0,0 +1,92 @@
+use grabme_render_engine::probe::probe_media;
+use grabme_render_engine::resources::ExportResources;
+use grabme_render_engine::{export_project, ExportJob};
+use grabme_testkit::{
+    ffmpeg_available, EventPattern, Media, MonitorLayout, ProjectFixture, TestDir,
+};
+
+#[tokio::test]
+async fn export_of_a_synthetic_project_has_video_and_audio() {
+    if !ffmpeg_available() {
+        eprintln!("skipping: ffmpeg is not installed");
+        return;
+    }
+
+    let dir = TestDir::new("export-fixture");
+    let project = ProjectFixture::new("demo")
+        .with_duration_secs(2.0)
+        .with_layout(MonitorLayout::side_by_side((640, 360), (640, 360)), 1)
+        .with_events(EventPattern::Sweep { period_secs: 1.0 })
+        .with_events(EventPattern::Clicks { every_secs: 0.5 })
+        .with_media(Media::Screen)
+        .with_media(Media::Mic)
+        .build(&dir.path().join("demo"))
+        .unwrap();
+
+    let mut config = project.project.export.clone();
+    config.width = 640;
+    config.height = 360;
+    let output = export_project(
+        ExportJob {
+            project_dir: project.root.clone(),
+            output_path: project.root.join("exports").join("output.mp4"),
+            config,
+            start_secs: None,
+            end_secs: None,
+            trim_preroll: false,
+            trim_tail: false,
+            debug_overlay: false,
+            resources: ExportResources::default(),
+            fast_copy: false,
+            draft: None,
+        },
+        None,
+    )
+    .await
+    .unwrap();
+
+    let info = probe_media(&output).unwrap();
+    let video = info.video.expect("export should have video");
+    assert_eq!((video.width, video.height), (640, 360));
+    assert!(info.audio.is_some(), "export should carry the mic");
+    let duration = info.duration_secs.unwrap();
+    assert!((duration - 2.0).abs() < 0.5, "export is {duration}s long");
+}
+
+#[tokio::test]
+async fn export_refuses_a_start_past_the_recording() {
+    if !ffmpeg_available() {
+        eprintln!("skipping: ffmpeg is not installed");
+        return;
+    }
+
+    let dir = TestDir::new("export-late-start");
+    let project = ProjectFixture::new("demo")
+        .with_duration_secs(2.0)
+        .with_media(Media::Screen)
+        .build(&dir.path().join("demo"))
+        .unwrap();
+
+    let output = project.root.join("exports").join("output.mp4");
+    let err = export_project(
+        ExportJob {
+            project_dir: project.root.clone(),
+            output_path: output.clone(),
+            config: project.project.export.clone(),
+            start_secs: Some(2.0),
+            end_secs: None,
+            trim_preroll: false,
+            trim_tail: false,
+            debug_overlay: false,
+            resources: ExportResources::default(),
+            fast_copy: false,
+            draft: None,
+        },
+        None,
+    )
+    .await
+    .unwrap_err();
+
+    assert!(err.to_string().contains("at or past its end"), "{err}");
+    assert!(!output.exists());
+}
@@ -0,0 +1,110 @@
+//! Input event patterns for synthetic recordings.
+//!
+//! Patterns are generated with positions normalized to the recorded
+//! monitor and are deterministic, so analysis results can be asserted.
+
+use grabme_project_model::event::{ButtonState, InputEvent, MouseButton, ScrollSource};
+
+const NANOS_PER_SEC: f64 = 1e9;
+
+/// How long a click or key is held.
+const HOLD_SECS: f64 = 0.08;
+
+/// Something the user did throughout a synthetic recording.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EventPattern {
+    /// The pointer rests in the middle of the screen.
+    Idle,
+    /// The pointer sweeps from the left edge to the right and back once
+    /// every `period_secs`, across the middle.
+    Sweep { period_secs: f64 },
+    /// A left click every `every_secs`, each at a different spot.
+    Clicks { every_secs: f64 },
+    /// A burst of `keys` key presses every `every_secs`.
+    Typing { every_secs: f64, keys: usize },
+    /// A wheel step down every `every_secs` where the pointer rests.
+    Scroll { every_secs: f64 },
+}
+
+impl EventPattern {
+    /// Events of this pattern over `duration_secs`, with pointer samples at
+    /// `pointer_hz`. Positions are normalized to the recorded monitor.
+    pub fn events(self, duration_secs: f64, pointer_hz: u32) -> Vec<InputEvent> {
+        match self {
+            EventPattern::Idle => sample_pointer(duration_secs, pointer_hz, |_| (0.5, 0.5)),
+            EventPattern::Sweep { period_secs } => sample_pointer(duration_secs, pointer_hz, |t| {
+                let phase = (t / period_secs.max(f64::EPSILON)).fract();
+                let x = 1.0 - (2.0 * phase - 1.0).abs();
+                (0.05 + 0.9 * x, 0.5)
+            }),
+            EventPattern::Clicks { every_secs } => {
+                let mut events = Vec::new();
+                for (n, t) in beats(duration_secs, every_secs).enumerate() {
+                    let (x, y) = spot(n);
+                    events.push(InputEvent::pointer(ns(t), x, y));
+                    for (offset, state) in [(0.0, ButtonState::Down), (HOLD_SECS, ButtonState::Up)]
+                    {
+                        events.push(InputEvent::click(
+                            ns(t + offset),
+                            MouseButton::Left,
+                            state,
+                            x,
+                            y,
+                        ));
+                    }
+                }
+                events
+            }
+            EventPattern::Typing { every_secs, keys } => {
+                let mut events = Vec::new();
+                for t in beats(duration_secs, every_secs) {
+                    for k in 0..keys {
+                        let at = t + k as f64 * 2.0 * HOLD_SECS;
+                        if at + HOLD_SECS >= duration_secs {
+                            break;
+                        }
+                        let code = format!("Key{}", (b'A' + (k % 26) as u8) as char);
+                        events.push(InputEvent::key(ns(at), code.clone(), ButtonState::Down));
+                        events.push(InputEvent::key(ns(at + HOLD_SECS), code, ButtonState::Up));
+                    }
+                }
+                events
+            }
+            EventPattern::Scroll { every_secs } => beats(duration_secs, every_secs)
+                .map(|t| InputEvent::scroll(ns(t), 0.0, 1.0, ScrollSource::Wheel, 0.5, 0.5))
+                .collect(),
+        }
+    }
+}
+
+/// Pointer samples at `hz` over `duration_secs`, positioned by `at(t)`.
+fn sample_pointer(duration_secs: f64, hz: u32, at: impl Fn(f64) -> (f64, f64)) -> Vec<InputEvent> {
+    let samples = (duration_secs * hz.max(1) as f64).floor() as u64;
+    (0..samples)
+        .map(|i| {
+            let t = i as f64 / hz.max(1) as f64;
+            let (x, y) = at(t);
+            InputEvent::pointer(ns(t), x, y)
+        })
+        .collect()
+}
+
+/// `every_secs`, `2 * every_secs`, ... up to half an interval before the end.
+fn beats(duration_secs: f64, every_secs: f64) -> impl Iterator<Item = f64> {
+    let every_secs = every_secs.max(0.01);
+    (1..)
+        .map(move |n| n as f64 * every_secs)
+        .take_while(move |t| *t + every_secs / 2.0 <= duration_secs)
+}
+
+/// The `n`th spot of a sequence that wanders over the screen without
+/// repeating or touching the edges.
+fn spot(n: usize) -> (f64, f64) {
+    let x = (n as f64 * 0.618_034 + 0.2).fract();
+    let y = (n as f64 * 0.381_966 + 0.3).fract();
+    (0.15 + 0.7 * x, 0.15 + 0.7 * y)
+}
+
+fn ns(secs: f64) -> u64 {
+    (secs * NANOS_PER_SEC).round() as u64
+}
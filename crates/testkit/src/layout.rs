@@ -0,0 +1,79 @@
+//! Monitor layouts a synthetic recording was made on.
+
+use grabme_project_model::project::RecordedMonitor;
+
+/// Monitors of the virtual desktop, in physical pixels.
+#[derive(Debug, Clone)]
+pub struct MonitorLayout {
+    pub monitors: Vec<RecordedMonitor>,
+}
+
+impl MonitorLayout {
+    /// One monitor at the desktop origin.
+    pub fn single(width: u32, height: u32) -> Self {
+        Self {
+            monitors: vec![monitor("DP-1", 0, 0, width, height, true)],
+        }
+    }
+
+    /// A primary monitor with a second one to its right, top edges aligned.
+    pub fn side_by_side(left: (u32, u32), right: (u32, u32)) -> Self {
+        Self {
+            monitors: vec![
+                monitor("DP-1", 0, 0, left.0, left.1, true),
+                monitor("HDMI-1", left.0 as i32, 0, right.0, right.1, false),
+            ],
+        }
+    }
+
+    /// A primary monitor with a second one above it, left edges aligned,
+    /// so the desktop origin is negative.
+    pub fn stacked(bottom: (u32, u32), top: (u32, u32)) -> Self {
+        Self {
+            monitors: vec![
+                monitor("DP-1", 0, 0, bottom.0, bottom.1, true),
+                monitor("HDMI-1", 0, -(top.1 as i32), top.0, top.1, false),
+            ],
+        }
+    }
+
+    /// Bounding box of all monitors: `(x, y, width, height)`.
+    pub fn virtual_bounds(&self) -> (i32, i32, u32, u32) {
+        let left = self.monitors.iter().map(|m| m.x).min().unwrap_or(0);
+        let top = self.monitors.iter().map(|m| m.y).min().unwrap_or(0);
+        let right = self
+            .monitors
+            .iter()
+            .map(|m| m.x + m.width as i32)
+            .max()
+            .unwrap_or(0);
+        let bottom = self
+            .monitors
+            .iter()
+            .map(|m| m.y + m.height as i32)
+            .max()
+            .unwrap_or(0);
+        (left, top, (right - left) as u32, (bottom - top) as u32)
+    }
+
+    /// `(x, y)` normalized to `monitor` as a position normalized to the
+    /// virtual desktop, as recordings store pointer positions.
+    pub fn to_virtual(&self, monitor: &RecordedMonitor, x: f64, y: f64) -> (f64, f64) {
+        let (vx, vy, vw, vh) = self.virtual_bounds();
+        (
+            (monitor.x as f64 + x * monitor.width as f64 - vx as f64) / vw as f64,
+            (monitor.y as f64 + y * monitor.height as f64 - vy as f64) / vh as f64,
+        )
+    }
+}
+
+fn monitor(name: &str, x: i32, y: i32, width: u32, height: u32, primary: bool) -> RecordedMonitor {
+    RecordedMonitor {
+        name: name.to_string(),
+        x,
+        y,
+        width,
+        height,
+        primary,
+    }
+}
@@ -0,0 +1,270 @@
+//! GrabMe Test Kit
+//!
+//! Synthetic project bundles for tests. Instead of assembling
+//! `project.json`, `events.jsonl` and source media by hand, a test
+//! describes the recording it needs:
+//!
+//! ```no_run
+//! use grabme_testkit::{EventPattern, Media, MonitorLayout, ProjectFixture, TestDir};
+//!
+//! let dir = TestDir::new("export");
+//! let project = ProjectFixture::new("demo")
+//!     .with_duration_secs(4.0)
+//!     .with_layout(MonitorLayout::side_by_side((1920, 1080), (2560, 1440)), 1)
+//!     .with_events(EventPattern::Sweep { period_secs: 2.0 })
+//!     .with_events(EventPattern::Clicks { every_secs: 1.0 })
+//!     .with_media(Media::Screen)
+//!     .build(&dir.path().join("demo"))?;
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+//!
+//! Pointer positions are written the way the capture engine writes them,
+//! normalized to the virtual desktop, so the remapping onto the recorded
+//! monitor is exercised too. Media comes from ffmpeg's lavfi sources; tests
+//! that ask for it should check [`ffmpeg_available`] and skip without it.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use anyhow::Context;
+use grabme_project_model::event::{
+    serialize_events, EventStreamHeader, InputEvent, PointerCoordinateSpace, EVENT_SCHEMA_VERSION,
+};
+use grabme_project_model::LoadedProject;
+
+pub mod events;
+pub mod layout;
+pub mod media;
+
+pub use events::EventPattern;
+pub use layout::MonitorLayout;
+pub use media::{ffmpeg_available, Media};
+
+/// Wall-clock start of every synthetic recording.
+pub const FIXTURE_EPOCH_WALL: &str = "2026-01-01T00:00:00Z";
+
+/// Description of a synthetic recording.
+#[derive(Debug, Clone)]
+pub struct ProjectFixture {
+    name: String,
+    duration_secs: f64,
+    fps: u32,
+    pointer_hz: u32,
+    layout: MonitorLayout,
+    monitor_index: usize,
+    patterns: Vec<EventPattern>,
+    media: Vec<Media>,
+}
+
+impl ProjectFixture {
+    /// A 10 second, 30 fps recording of one 1920x1080 monitor, with no
+    /// events or media until they are asked for.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            duration_secs: 10.0,
+            fps: 30,
+            pointer_hz: 60,
+            layout: MonitorLayout::single(1920, 1080),
+            monitor_index: 0,
+            patterns: Vec::new(),
+            media: Vec::new(),
+        }
+    }
+
+    pub fn with_duration_secs(mut self, duration_secs: f64) -> Self {
+        self.duration_secs = duration_secs;
+        self
+    }
+
+    pub fn with_fps(mut self, fps: u32) -> Self {
+        self.fps = fps;
+        self
+    }
+
+    /// Pointer samples per second of the pointer patterns.
+    pub fn with_pointer_hz(mut self, pointer_hz: u32) -> Self {
+        self.pointer_hz = pointer_hz;
+        self
+    }
+
+    /// Record monitor `monitor_index` of `layout`.
+    pub fn with_layout(mut self, layout: MonitorLayout, monitor_index: usize) -> Self {
+        self.layout = layout;
+        self.monitor_index = monitor_index;
+        self
+    }
+
+    /// Add a pattern of input events; patterns are interleaved in time.
+    pub fn with_events(mut self, pattern: EventPattern) -> Self {
+        self.patterns.push(pattern);
+        self
+    }
+
+    /// Add a source track of real media.
+    pub fn with_media(mut self, media: Media) -> Self {
+        self.media.push(media);
+        self
+    }
+
+    /// Events of the recording in time order, with positions normalized to
+    /// the recorded monitor, i.e. what analysis sees after remapping.
+    pub fn capture_events(&self) -> Vec<InputEvent> {
+        let mut events: Vec<InputEvent> = self
+            .patterns
+            .iter()
+            .flat_map(|pattern| pattern.events(self.duration_secs, self.pointer_hz))
+            .collect();
+        events.sort_by_key(|event| event.timestamp_ns);
+        events
+    }
+
+    /// Write the project bundle to `root` and return it loaded.
+    pub fn build(&self, root: &Path) -> anyhow::Result<LoadedProject> {
+        let monitor = self
+            .layout
+            .monitors
+            .get(self.monitor_index)
+            .with_context(|| {
+                format!(
+                    "Monitor {} is not in a layout of {}",
+                    self.monitor_index,
+                    self.layout.monitors.len()
+                )
+            })?
+            .clone();
+        let capture = (monitor.width, monitor.height);
+        let mut loaded = LoadedProject::create(root, &self.name, capture.0, capture.1, self.fps)
+            .context("Failed to create project")?;
+
+        let (vx, vy, vw, vh) = self.layout.virtual_bounds();
+        let recording = &mut loaded.project.recording;
+        recording.monitor_index = self.monitor_index;
+        recording.monitor_name = monitor.name.clone();
+        recording.monitor_x = monitor.x;
+        recording.monitor_y = monitor.y;
+        recording.monitor_width = monitor.width;
+        recording.monitor_height = monitor.height;
+        recording.virtual_x = vx;
+        recording.virtual_y = vy;
+        recording.virtual_width = vw;
+        recording.virtual_height = vh;
+        recording.monitors = self.layout.monitors.clone();
+        recording.pointer_coordinate_space = PointerCoordinateSpace::VirtualDesktopNormalized;
+
+        let mut events = self.capture_events();
+        for event in &mut events {
+            if let Some((x, y)) = event.position_mut() {
+                (*x, *y) = self.layout.to_virtual(&monitor, *x, *y);
+            }
+        }
+        let header = EventStreamHeader {
+            schema_version: EVENT_SCHEMA_VERSION.to_string(),
+            epoch_monotonic_ns: 0,
+            epoch_wall: FIXTURE_EPOCH_WALL.to_string(),
+            capture_width: capture.0,
+            capture_height: capture.1,
+            scale_factor: 1.0,
+            pointer_sample_rate_hz: self.pointer_hz,
+            pointer_coordinate_space: PointerCoordinateSpace::VirtualDesktopNormalized,
+        };
+        let jsonl = format!(
+            "# {}\n{}",
+            serde_json::to_string(&header)?,
+            serialize_events(&events)?
+        );
+        std::fs::write(root.join("meta").join("events.jsonl"), jsonl)
+            .context("Failed to write events")?;
+
+        for &media in &self.media {
+            let track = media::write_media(root, media, capture, self.fps, self.duration_secs)?;
+            let tracks = &mut loaded.project.tracks;
+            match media {
+                Media::Screen => tracks.screen = Some(track),
+                Media::Webcam => tracks.webcam = Some(track),
+                Media::Mic => tracks.mic = Some(track),
+                Media::SystemAudio => tracks.system_audio = Some(track),
+            }
+        }
+
+        loaded.save().context("Failed to save project")?;
+        Ok(loaded)
+    }
+}
+
+/// A scratch directory under the system temp directory, removed on drop.
+#[derive(Debug)]
+pub struct TestDir {
+    path: PathBuf,
+}
+
+impl TestDir {
+    /// A fresh, empty directory whose name starts with `label`.
+    pub fn new(label: &str) -> Self {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "grabme-{label}-{}-{}",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).expect("temp directory should be writable");
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use grabme_project_model::event::{EventKind, EventStream};
+
+    #[test]
+    fn test_fixture_writes_events_normalized_to_the_virtual_desktop() {
+        let dir = TestDir::new("testkit");
+        let fixture = ProjectFixture::new("demo")
+            .with_duration_secs(3.0)
+            .with_layout(MonitorLayout::side_by_side((1920, 1080), (1280, 720)), 1)
+            .with_events(EventPattern::Idle)
+            .with_events(EventPattern::Clicks { every_secs: 1.0 });
+        let loaded = fixture.build(&dir.path().join("demo")).unwrap();
+
+        let recording = &loaded.project.recording;
+        assert_eq!(
+            (recording.capture_width, recording.capture_height),
+            (1280, 720)
+        );
+        assert_eq!(
+            (recording.virtual_width, recording.virtual_height),
+            (3200, 1080)
+        );
+        assert!(loaded.validate_sources().is_empty());
+
+        let stream = EventStream::open(&loaded.root.join("meta").join("events.jsonl")).unwrap();
+        assert_eq!(stream.header().unwrap().capture_width, 1280);
+        let events: Vec<InputEvent> = stream.map(Result::unwrap).collect();
+        assert_eq!(events.len(), fixture.capture_events().len());
+        let clicks = events
+            .iter()
+            .filter(|event| matches!(event.kind, EventKind::Click { .. }))
+            .count();
+        // Two clicks, at 1s and 2s, each a press and a release.
+        assert_eq!(clicks, 4);
+        // The middle of the second monitor.
+        let (x, y) = events[0].pointer_position().unwrap();
+        assert!((x - 2560.0 / 3200.0).abs() < 1e-9);
+        assert!((y - 360.0 / 1080.0).abs() < 1e-9);
+        assert!(events
+            .windows(2)
+            .all(|pair| pair[0].timestamp_ns <= pair[1].timestamp_ns));
+    }
+}
@@ -0,0 +1,129 @@
+//! Tiny real media from ffmpeg's lavfi sources.
+//!
+//! Screen and webcam tracks are the `testsrc2` pattern, H.264 in Matroska
+//! like a real capture; audio tracks are sine tones in WAV.
+
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use anyhow::Context;
+use grabme_project_model::project::{AudioFormat, TrackRef, VideoFormat};
+
+/// Webcam frame size; cameras rarely match the screen.
+const WEBCAM_SIZE: (u32, u32) = (640, 480);
+
+/// Audio sample rate, as most captures are.
+const SAMPLE_RATE: u32 = 48_000;
+
+/// A source track of a synthetic recording.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Media {
+    Screen,
+    Webcam,
+    Mic,
+    SystemAudio,
+}
+
+/// Whether `ffmpeg` can be run. Tests that need media skip without it.
+pub fn ffmpeg_available() -> bool {
+    Command::new("ffmpeg")
+        .arg("-version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Write `media` for a recording of `capture` size at `fps` into the
+/// project at `root` and return its track.
+pub(crate) fn write_media(
+    root: &Path,
+    media: Media,
+    capture: (u32, u32),
+    fps: u32,
+    duration_secs: f64,
+) -> anyhow::Result<TrackRef> {
+    let (path, source, codec_args, codec): (_, _, &[&str], _) = match media {
+        Media::Screen => (
+            "sources/screen.mkv",
+            video_source(capture, fps, duration_secs),
+            &VIDEO_CODEC,
+            "h264",
+        ),
+        Media::Webcam => (
+            "sources/webcam.mkv",
+            video_source(WEBCAM_SIZE, fps, duration_secs),
+            &VIDEO_CODEC,
+            "h264",
+        ),
+        Media::Mic => (
+            "sources/mic.wav",
+            audio_source(440, duration_secs),
+            &AUDIO_CODEC,
+            "pcm",
+        ),
+        Media::SystemAudio => (
+            "sources/system.wav",
+            audio_source(880, duration_secs),
+            &AUDIO_CODEC,
+            "pcm",
+        ),
+    };
+
+    let output = Command::new("ffmpeg")
+        .args([
+            "-hide_banner",
+            "-loglevel",
+            "error",
+            "-y",
+            "-f",
+            "lavfi",
+            "-i",
+        ])
+        .arg(&source)
+        .args(codec_args)
+        .arg(root.join(path))
+        .output()
+        .context("Failed to run ffmpeg")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "ffmpeg failed to write {path}: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(TrackRef {
+        path: path.to_string(),
+        duration_secs,
+        codec: codec.to_string(),
+        offset_ns: 0,
+        audio: matches!(media, Media::Mic | Media::SystemAudio).then_some(AudioFormat {
+            sample_rate: SAMPLE_RATE,
+            channels: 1,
+        }),
+        video: (media == Media::Webcam).then_some(VideoFormat {
+            width: WEBCAM_SIZE.0,
+            height: WEBCAM_SIZE.1,
+            fps,
+        }),
+    })
+}
+
+const VIDEO_CODEC: [&str; 6] = [
+    "-c:v",
+    "libx264",
+    "-preset",
+    "ultrafast",
+    "-pix_fmt",
+    "yuv420p",
+];
+
+const AUDIO_CODEC: [&str; 2] = ["-c:a", "pcm_s16le"];
+
+fn video_source((width, height): (u32, u32), fps: u32, duration_secs: f64) -> String {
+    format!("testsrc2=size={width}x{height}:rate={fps}:duration={duration_secs:.3}")
+}
+
+fn audio_source(frequency: u32, duration_secs: f64) -> String {
+    format!("sine=frequency={frequency}:sample_rate={SAMPLE_RATE}:duration={duration_secs:.3}")
+}
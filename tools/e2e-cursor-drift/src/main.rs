@@ -58,6 +58,8 @@ async fn main() -> Result<()> {
         screen: ScreenCaptureConfig {
             mode: CaptureMode::FullScreen { monitor_index: 0 },
             hide_cursor: false,
+            cursor_mode: None,
+            monitor_splits: Default::default(),
         },
         audio: AudioCaptureConfig {
             mic: false,
@@ -67,6 +69,7 @@ async fn main() -> Result<()> {
         },
         fps: 30,
         pointer_sample_rate_hz: 60,
+        frame_times: true,
         ..Default::default()
     };
 
@@ -1,21 +1,35 @@
 //! Run Auto-Director analysis on a project.
 
 use std::cmp::Ordering;
-use std::path::PathBuf;
-
-use grabme_processing_core::auto_zoom::{AutoZoomAnalyzer, AutoZoomConfig};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use grabme_common::config::AppConfig;
+use grabme_common::memory::MemoryProfile;
+use grabme_processing_core::auto_zoom::{
+    AutoZoomAnalyzer, AutoZoomConfig, DisplayMetrics, DwellUnit,
+};
+use grabme_processing_core::click_burst::{aggregate_click_bursts, ClickBurstConfig};
 use grabme_processing_core::cursor_smooth::CursorSmoother;
+use grabme_processing_core::pacing::{ClickPacing, NarrationPace};
+use grabme_processing_core::pass::wasm::WasmPass;
+use grabme_processing_core::pass::{PassError, PassRegistry, PassStage, TimelinePass};
+use grabme_processing_core::progress::{AnalysisProgressCallback, AnalysisStage};
+use grabme_processing_core::vertical::VerticalPass;
 use grabme_project_model::event::{
-    parse_events, ButtonState, EventKind, EventStreamHeader, InputEvent, MouseButton,
-    PointerCoordinateSpace,
+    parse_events, parse_events_header, EventStream, EventStreamHeader, InputEvent,
+    PointerCoordinateSpace, PointerThinner,
 };
 use grabme_project_model::project::RecordingConfig;
+use grabme_project_model::projection::project_events_to_capture_space;
 use grabme_project_model::timeline::{
     CameraKeyframe, EasingFunction, KeyframeSource,
-    SmoothingAlgorithm as TimelineSmoothingAlgorithm, Timeline,
+    SmoothingAlgorithm as TimelineSmoothingAlgorithm, Timeline, LOW_CONFIDENCE_THRESHOLD,
 };
 use grabme_project_model::viewport::Viewport;
 use grabme_project_model::LoadedProject;
+use grabme_render_engine::subtitles::load_project_cues;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum CameraStyle {
@@ -50,44 +64,36 @@ pub fn run(
     camera_style: String,
     hover_zoom: f64,
     scan_zoom: f64,
-    dwell_radius: f64,
-    dwell_velocity: f64,
+    dwell_radius: Option<f64>,
+    dwell_velocity: Option<f64>,
+    dwell_unit: DwellUnit,
     smooth_window: usize,
     cursor_smoothing: String,
     cursor_smoothing_factor: f64,
     monitor_count: usize,
     focused_monitor: usize,
+    click_burst_window: f64,
+    pass_plugins: Vec<PathBuf>,
+    report: bool,
 ) -> anyhow::Result<()> {
     println!("Analyzing project at: {}", path.display());
 
     let mut project =
         LoadedProject::load(&path).map_err(|e| anyhow::anyhow!("Failed to load project: {e}"))?;
 
-    // Load events
-    let events_path = path.join("meta").join("events.jsonl");
-    let events_content = std::fs::read_to_string(&events_path)
-        .map_err(|_| anyhow::anyhow!("Events file not found: {}", events_path.display()))?;
-
-    let events_header = parse_events_header(&events_content);
+    let memory = AppConfig::load().memory.profile();
+    let (events_header, events) = load_events(&path, &memory)?;
 
-    // Filter out header lines (starting with #)
-    let event_lines: String = events_content
-        .lines()
-        .filter(|l| !l.starts_with('#') && !l.trim().is_empty())
-        .collect::<Vec<_>>()
-        .join("\n");
-
-    let events =
-        parse_events(&event_lines).map_err(|e| anyhow::anyhow!("Failed to parse events: {e}"))?;
-
-    let (analysis_events, projection_model) = project_events_to_capture_space(
+    let projection = project_events_to_capture_space(
         &events,
         events_header.as_ref(),
         &project.project.recording,
     );
+    let mapping_confidence = projection.confidence();
+    let analysis_events = projection.events;
 
     println!("  Loaded {} events", events.len());
-    println!("  Pointer mapping: {}", projection_model.as_str());
+    println!("  Pointer mapping: {}", projection.model.as_str());
 
     if analysis_events.is_empty() {
         println!("  No events to analyze.");
@@ -115,61 +121,80 @@ pub fn run(
     }
 
     let smoothing = CursorSmoother::algorithm_from_cursor_config(&cursor_config);
-    let smoother = CursorSmoother::new(smoothing);
+    let smoother = CursorSmoother::new(smoothing).with_progress(progress_bar());
     let smoothed = smoother.smooth(&analysis_events);
     println!("  Smoothed {} pointer positions", smoothed.len());
 
     let camera_style = CameraStyle::parse(&camera_style)?;
+    check_window_track_style(&project.project.recording, camera_style, vertical)?;
     println!("  Camera style: {}", camera_style.as_str());
 
-    // Build camera keyframes
+    // Build camera keyframes: one camera pass, then any plugin passes.
+    let mut passes = PassRegistry::new();
     if vertical {
         println!("  Running vertical (9:16) analysis...");
-        let config = grabme_processing_core::vertical::VerticalConfig::default();
-        let keyframes =
-            grabme_processing_core::vertical::generate_vertical_timeline(&analysis_events, &config);
-        project.timeline.keyframes = keyframes;
-        println!(
-            "  Generated {} vertical keyframes",
-            project.timeline.keyframes.len()
-        );
+        passes.register(VerticalPass::default());
     } else {
         match camera_style {
             CameraStyle::Production => {
                 println!("  Running production camera analysis (full-frame + click emphasis)...");
-                let timeline = build_production_timeline(
-                    &analysis_events,
-                    &events,
-                    events_header.as_ref(),
-                    &project.project.recording,
-                );
-                project.timeline.keyframes = timeline.keyframes;
-                println!(
-                    "  Generated {} production keyframes",
-                    project.timeline.keyframes.len()
-                );
+                let pace = load_narration_pace(&path, &project);
+                passes.register(ProductionPass {
+                    raw_events: &events,
+                    events_header: events_header.as_ref(),
+                    recording: &project.project.recording,
+                    burst_config: ClickBurstConfig {
+                        window_secs: click_burst_window.max(0.0),
+                        ..Default::default()
+                    },
+                    pace,
+                });
             }
             CameraStyle::Auto => {
                 let effective_chunk_secs = adaptive_chunk_secs(chunk_secs, &analysis_events);
                 println!("  Running auto-zoom analysis (chunk={effective_chunk_secs}s)...");
+                let (default_radius, default_velocity) = dwell_unit.default_thresholds();
                 let config = AutoZoomConfig {
                     chunk_duration_secs: effective_chunk_secs,
                     hover_zoom,
                     scan_zoom,
-                    dwell_radius,
-                    dwell_velocity_threshold: dwell_velocity,
+                    dwell_radius: dwell_radius.unwrap_or(default_radius),
+                    dwell_velocity_threshold: dwell_velocity.unwrap_or(default_velocity),
+                    dwell_unit,
+                    display: DisplayMetrics::from_recording(&project.project.recording),
                     smoothing_window: smooth_window,
                     monitor_count,
                     focused_monitor_index: focused_monitor,
                     ..Default::default()
                 };
-                let analyzer = AutoZoomAnalyzer::new(config);
-                let timeline = analyzer.analyze(&analysis_events);
-                project.timeline.keyframes = timeline.keyframes;
-                println!("  Generated {} keyframes", project.timeline.keyframes.len());
+                passes.register(AutoZoomAnalyzer::new(config).with_progress(progress_bar()));
             }
         }
     }
+    for plugin in &pass_plugins {
+        let name = plugin
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| plugin.display().to_string());
+        let wasm = std::fs::read(plugin)
+            .map_err(|e| anyhow::anyhow!("Failed to read pass plugin {}: {e}", plugin.display()))?;
+        passes.register(WasmPass::from_bytes(name, &wasm)?);
+    }
+    println!("  Passes: {}", passes.names().join(" -> "));
+
+    let timeline = passes.run(&analysis_events, project.timeline.clone())?;
+    drop(passes);
+    project.timeline = timeline;
+    println!("  Generated {} keyframes", project.timeline.keyframes.len());
+
+    apply_mapping_confidence(&mut project.timeline.keyframes, mapping_confidence);
+    if report {
+        print_confidence_report(
+            &project.timeline,
+            analysis_duration_secs(&analysis_events),
+            mapping_confidence,
+        );
+    }
 
     // Save updated timeline
     project.timeline.cursor_config = cursor_config;
@@ -186,19 +211,152 @@ pub fn run(
     Ok(())
 }
 
+/// Progress bar redrawn on one line, ended when the stage completes.
+fn progress_bar() -> AnalysisProgressCallback {
+    const WIDTH: usize = 30;
+    Arc::new(|p| {
+        let label = match p.stage {
+            AnalysisStage::Smoothing => "Smoothing",
+            AnalysisStage::AutoZoom => "Auto-zoom",
+        };
+        let filled = ((p.fraction * WIDTH as f64).round() as usize).min(WIDTH);
+        print!(
+            "\r  {label}: [{}{}] {:>3.0}%",
+            "#".repeat(filled),
+            "-".repeat(WIDTH - filled),
+            p.fraction * 100.0
+        );
+        if p.fraction >= 1.0 {
+            println!();
+        }
+        let _ = std::io::stdout().flush();
+    })
+}
+
+/// Fold the pointer-mapping confidence into every generated keyframe.
+///
+/// Keyframes the analyzer didn't score (click pulses, window/monitor
+/// follow) are treated as certain apart from the mapping itself.
+fn apply_mapping_confidence(keyframes: &mut [CameraKeyframe], mapping_confidence: f64) {
+    for keyframe in keyframes
+        .iter_mut()
+        .filter(|kf| kf.source == KeyframeSource::Auto)
+    {
+        let base = keyframe.confidence.unwrap_or(1.0);
+        keyframe.confidence = Some((base * mapping_confidence).clamp(0.0, 1.0));
+    }
+}
+
+fn analysis_duration_secs(events: &[InputEvent]) -> f64 {
+    match (events.first(), events.last()) {
+        (Some(first), Some(last)) => {
+            last.timestamp_ns.saturating_sub(first.timestamp_ns) as f64 / 1_000_000_000.0
+        }
+        _ => 0.0,
+    }
+}
+
+fn print_confidence_report(timeline: &Timeline, duration_secs: f64, mapping_confidence: f64) {
+    let scores: Vec<f64> = timeline
+        .keyframes
+        .iter()
+        .filter_map(|kf| kf.confidence)
+        .collect();
+    let mean = if scores.is_empty() {
+        0.0
+    } else {
+        scores.iter().sum::<f64>() / scores.len() as f64
+    };
+    let low = timeline.low_confidence_segments(LOW_CONFIDENCE_THRESHOLD, duration_secs);
+
+    println!("\n  Confidence report:");
+    println!("    Pointer mapping confidence: {mapping_confidence:.2}");
+    println!("    Keyframes scored: {} (mean {mean:.2})", scores.len());
+    println!(
+        "    Low-confidence segments (< {LOW_CONFIDENCE_THRESHOLD:.2}): {}",
+        low.len()
+    );
+    for segment in &low {
+        println!(
+            "      {:>7.2}s - {:>7.2}s  confidence {:.2}",
+            segment.start_secs, segment.end_secs, segment.confidence
+        );
+    }
+}
+
 const PRODUCTION_CLICK_ZOOM_SIZE: f64 = 0.97;
 const PRODUCTION_CLICK_LEAD_SECS: f64 = 0.05;
 const PRODUCTION_CLICK_HOLD_SECS: f64 = 0.08;
 const PRODUCTION_CLICK_RELEASE_SECS: f64 = 0.22;
 const PRODUCTION_CLICK_COOLDOWN_SECS: f64 = 3.0;
+const PRODUCTION_CLICK_PACING: ClickPacing = ClickPacing {
+    cooldown_secs: PRODUCTION_CLICK_COOLDOWN_SECS,
+    hold_secs: PRODUCTION_CLICK_HOLD_SECS,
+};
+
+/// Narration pace from the project's transcript, when it has one.
+fn load_narration_pace(path: &Path, project: &LoadedProject) -> Option<NarrationPace> {
+    let cues = load_project_cues(path, project);
+    let texts: Vec<String> = cues.iter().map(|cue| cue.plain_text()).collect();
+    let pace = NarrationPace::from_segments(
+        cues.iter()
+            .zip(&texts)
+            .map(|(cue, text)| (cue.start_secs, cue.end_secs, text.as_str())),
+    );
+    if pace.is_empty() {
+        return None;
+    }
+    println!(
+        "  Click timing follows narration pace ({} cues)",
+        cues.len()
+    );
+    Some(pace)
+}
+
+/// Production style as a camera pass: full frame (or window/monitor
+/// follow) with a short zoom pulse per click burst.
+struct ProductionPass<'a> {
+    raw_events: &'a [InputEvent],
+    events_header: Option<&'a EventStreamHeader>,
+    recording: &'a RecordingConfig,
+    burst_config: ClickBurstConfig,
+    /// Narration pace; click cooldown and hold follow it when present.
+    pace: Option<NarrationPace>,
+}
+
+impl TimelinePass for ProductionPass<'_> {
+    fn name(&self) -> &str {
+        "production"
+    }
+
+    fn stage(&self) -> PassStage {
+        PassStage::Camera
+    }
+
+    fn run(&self, events: &[InputEvent], mut timeline: Timeline) -> Result<Timeline, PassError> {
+        timeline.keyframes = build_production_timeline(
+            events,
+            self.raw_events,
+            self.events_header,
+            self.recording,
+            &self.burst_config,
+            self.pace.as_ref(),
+        )
+        .keyframes;
+        Ok(timeline)
+    }
+}
 
 fn build_production_timeline(
     events: &[InputEvent],
     raw_events: &[InputEvent],
     events_header: Option<&EventStreamHeader>,
     recording: &RecordingConfig,
+    burst_config: &ClickBurstConfig,
+    pace: Option<&NarrationPace>,
 ) -> Timeline {
-    let monitor_follow = build_monitor_follow_keyframes(raw_events, events_header, recording);
+    let monitor_follow = build_window_follow_keyframes(recording)
+        .or_else(|| build_monitor_follow_keyframes(raw_events, events_header, recording));
     let mut keyframes = if let Some(mut follow) = monitor_follow {
         if follow.is_empty() {
             vec![full_keyframe(0.0, EasingFunction::EaseInOut)]
@@ -226,30 +384,24 @@ fn build_production_timeline(
     let start_ns = events.first().map(|e| e.timestamp_ns).unwrap_or(0);
     let mut last_click_t = f64::NEG_INFINITY;
 
-    for event in events {
-        let EventKind::Click {
-            button,
-            state,
-            x,
-            y,
-        } = &event.kind
-        else {
-            continue;
-        };
-        if *button != MouseButton::Left || *state != ButtonState::Down {
-            continue;
-        }
-
-        let click_t = event.timestamp_ns.saturating_sub(start_ns) as f64 / 1_000_000_000.0;
-        if click_t - last_click_t < PRODUCTION_CLICK_COOLDOWN_SECS {
+    // Double/triple clicks become one burst so their pulses don't overlap;
+    // the hold stretches to the burst's last click. With a transcript, fast
+    // narration shortens the cooldown and hold and slow narration lengthens
+    // them.
+    for burst in aggregate_click_bursts(events, burst_config) {
+        let click_t = burst.start_ns.saturating_sub(start_ns) as f64 / 1_000_000_000.0;
+        let pacing = pace
+            .map(|pace| pace.click_pacing_at(click_t, PRODUCTION_CLICK_PACING))
+            .unwrap_or(PRODUCTION_CLICK_PACING);
+        if click_t - last_click_t < pacing.cooldown_secs {
             continue;
         }
         last_click_t = click_t;
 
         let pre_t = (click_t - PRODUCTION_CLICK_LEAD_SECS).max(0.0);
-        let hold_t = click_t + PRODUCTION_CLICK_HOLD_SECS;
+        let hold_t = click_t + burst.duration_secs() + pacing.hold_secs;
         let settle_t = hold_t + PRODUCTION_CLICK_RELEASE_SECS;
-        let focus_viewport = centered_square_viewport(*x, *y, PRODUCTION_CLICK_ZOOM_SIZE);
+        let focus_viewport = centered_square_viewport(burst.x, burst.y, PRODUCTION_CLICK_ZOOM_SIZE);
 
         let pre_viewport = baseline.viewport_at(pre_t);
         let settle_viewport = baseline.viewport_at(settle_t);
@@ -259,24 +411,28 @@ fn build_production_timeline(
             viewport: pre_viewport,
             easing: EasingFunction::EaseInOut,
             source: KeyframeSource::Auto,
+            confidence: None,
         });
         keyframes.push(CameraKeyframe {
             time_secs: click_t,
             viewport: focus_viewport,
             easing: EasingFunction::EaseOut,
             source: KeyframeSource::Auto,
+            confidence: None,
         });
         keyframes.push(CameraKeyframe {
             time_secs: hold_t,
             viewport: focus_viewport,
             easing: EasingFunction::EaseInOut,
             source: KeyframeSource::Auto,
+            confidence: None,
         });
         keyframes.push(CameraKeyframe {
             time_secs: settle_t,
             viewport: settle_viewport,
             easing: EasingFunction::EaseInOut,
             source: KeyframeSource::Auto,
+            confidence: None,
         });
     }
 
@@ -301,6 +457,7 @@ fn full_keyframe(time_secs: f64, easing: EasingFunction) -> CameraKeyframe {
         viewport: Viewport::FULL,
         easing,
         source: KeyframeSource::Auto,
+        confidence: None,
     }
 }
 
@@ -359,12 +516,14 @@ fn build_monitor_follow_keyframes(
         return None;
     }
 
-    // Monitor-follow requires coordinates that are meaningful in virtual-desktop
-    // normalized space. Our Linux tracker emits this by default.
+    // Monitor-follow requires the monitor under each sample: resolved at
+    // capture time in newer recordings, or else derived from coordinates
+    // in virtual-desktop normalized space, which our Linux tracker emits.
+    let has_hits = raw_events.iter().any(|event| event.hit.is_some());
     let pointer_space = events_header
         .map(|h| h.pointer_coordinate_space)
         .unwrap_or(recording.pointer_coordinate_space);
-    if pointer_space != PointerCoordinateSpace::VirtualDesktopNormalized {
+    if !has_hits && pointer_space != PointerCoordinateSpace::VirtualDesktopNormalized {
         return None;
     }
 
@@ -379,17 +538,21 @@ fn build_monitor_follow_keyframes(
             continue;
         };
 
-        let px = recording.virtual_x as f64 + x.clamp(0.0, 1.0) * virtual_w;
-        let py = recording.virtual_y as f64 + y.clamp(0.0, 1.0) * virtual_h;
-        let monitor_idx = recording.monitors.iter().position(|m| {
-            let left = m.x as f64;
-            let top = m.y as f64;
-            let right = left + m.width as f64;
-            let bottom = top + m.height as f64;
-            px >= left && px < right && py >= top && py < bottom
-        });
+        let monitor_idx = if has_hits {
+            event.hit.and_then(|hit| hit.monitor)
+        } else {
+            let px = recording.virtual_x as f64 + x.clamp(0.0, 1.0) * virtual_w;
+            let py = recording.virtual_y as f64 + y.clamp(0.0, 1.0) * virtual_h;
+            recording.monitors.iter().position(|m| {
+                let left = m.x as f64;
+                let top = m.y as f64;
+                let right = left + m.width as f64;
+                let bottom = top + m.height as f64;
+                px >= left && px < right && py >= top && py < bottom
+            })
+        };
 
-        let Some(monitor_idx) = monitor_idx else {
+        let Some(monitor_idx) = monitor_idx.filter(|idx| *idx < recording.monitors.len()) else {
             continue;
         };
 
@@ -416,6 +579,7 @@ fn build_monitor_follow_keyframes(
             viewport: Viewport::new(x_norm, y_norm, w_norm, h_norm),
             easing: EasingFunction::EaseInOut,
             source: KeyframeSource::Auto,
+            confidence: None,
         });
 
         active_monitor = Some(monitor_idx);
@@ -442,6 +606,7 @@ fn build_monitor_follow_keyframes(
                 ),
                 easing: EasingFunction::EaseInOut,
                 source: KeyframeSource::Auto,
+                confidence: None,
             });
         }
     }
@@ -449,328 +614,124 @@ fn build_monitor_follow_keyframes(
     Some(normalize_keyframes(keyframes))
 }
 
-fn adaptive_chunk_secs(requested_secs: f64, events: &[InputEvent]) -> f64 {
-    let requested = requested_secs.max(0.25);
-    if events.len() < 2 {
-        return requested;
+/// Only the production style frames a recorded window, so refuse the others
+/// rather than export the whole desktop.
+fn check_window_track_style(
+    recording: &RecordingConfig,
+    camera_style: CameraStyle,
+    vertical: bool,
+) -> anyhow::Result<()> {
+    if recording.window_track.is_none() {
+        return Ok(());
     }
-
-    let start = events.first().map(|e| e.timestamp_ns).unwrap_or(0);
-    let end = events.last().map(|e| e.timestamp_ns).unwrap_or(start);
-    if end <= start {
-        return requested;
+    if vertical {
+        anyhow::bail!(
+            "This recording follows a window, which --vertical can't frame. \
+             Run analyze without --vertical."
+        );
     }
-
-    let duration_secs = (end - start) as f64 / 1_000_000_000.0;
-    let adaptive_target = (duration_secs / 10.0).max(0.35);
-    requested.min(adaptive_target).max(0.25)
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum AnalysisPointerModel {
-    CaptureNormalized,
-    VirtualDesktopNormalized,
-    VirtualDesktopRootOrigin,
-}
-
-impl AnalysisPointerModel {
-    fn as_str(self) -> &'static str {
-        match self {
-            AnalysisPointerModel::CaptureNormalized => "capture_normalized",
-            AnalysisPointerModel::VirtualDesktopNormalized => {
-                "virtual_desktop_normalized -> capture_normalized"
-            }
-            AnalysisPointerModel::VirtualDesktopRootOrigin => {
-                "virtual_desktop_root_origin -> capture_normalized"
-            }
-        }
+    if camera_style != CameraStyle::Production {
+        anyhow::bail!(
+            "This recording follows a window, which only --camera-style production frames. \
+             Run analyze with --camera-style production."
+        );
     }
+    Ok(())
 }
 
-#[derive(Debug, Clone, Copy)]
-struct ProjectionCandidate {
-    model: AnalysisPointerModel,
-    transform: PointerTransform,
-}
+/// Frame the recorded window, cutting to each new position as it moves.
+fn build_window_follow_keyframes(recording: &RecordingConfig) -> Option<Vec<CameraKeyframe>> {
+    // Geometry is polled every 100ms; hold the previous framing until then so
+    // a move doesn't turn into a slow pan across the idle stretch before it.
+    const HOLD_BEFORE_MOVE_SECS: f64 = 0.1;
 
-#[derive(Debug, Clone, Copy)]
-struct PointerTransform {
-    scale_x: f64,
-    scale_y: f64,
-    tx: f64,
-    ty: f64,
-}
-
-impl PointerTransform {
-    fn identity() -> Self {
-        Self {
-            scale_x: 1.0,
-            scale_y: 1.0,
-            tx: 0.0,
-            ty: 0.0,
-        }
+    let viewports = recording.window_track.as_ref()?.viewports(recording);
+    if viewports.is_empty() {
+        return None;
     }
 
-    fn from_affine(scale_x: f64, scale_y: f64, tx: f64, ty: f64) -> Self {
-        Self {
-            scale_x,
-            scale_y,
-            tx,
-            ty,
+    let mut keyframes = Vec::with_capacity(viewports.len() * 2);
+    let mut previous: Option<Viewport> = None;
+    for (t, viewport) in viewports {
+        if let Some(previous) = previous {
+            keyframes.push(CameraKeyframe {
+                time_secs: (t - HOLD_BEFORE_MOVE_SECS).max(0.0),
+                viewport: previous,
+                easing: EasingFunction::Linear,
+                source: KeyframeSource::Auto,
+                confidence: None,
+            });
         }
-    }
-
-    fn project(self, x: f64, y: f64) -> (f64, f64) {
-        (self.scale_x * x + self.tx, self.scale_y * y + self.ty)
-    }
-}
-
-fn parse_events_header(events_content: &str) -> Option<EventStreamHeader> {
-    let header_line = events_content
-        .lines()
-        .map(str::trim)
-        .find(|line| line.starts_with('#'))?;
-    let json = header_line.trim_start_matches('#').trim();
-    serde_json::from_str::<EventStreamHeader>(json).ok()
-}
-
-fn project_events_to_capture_space(
-    events: &[InputEvent],
-    events_header: Option<&EventStreamHeader>,
-    recording: &RecordingConfig,
-) -> (Vec<InputEvent>, AnalysisPointerModel) {
-    const EXPLICIT_PROJECTION_FALLBACK_DELTA: f64 = 0.35;
-
-    if events.is_empty() {
-        return (Vec::new(), AnalysisPointerModel::CaptureNormalized);
-    }
-
-    let explicit_space = events_header
-        .map(|header| header.pointer_coordinate_space)
-        .filter(|space| *space != PointerCoordinateSpace::LegacyUnspecified)
-        .or_else(|| {
-            let space = recording.pointer_coordinate_space;
-            if space == PointerCoordinateSpace::LegacyUnspecified {
-                None
-            } else {
-                Some(space)
-            }
+        keyframes.push(CameraKeyframe {
+            time_secs: t,
+            viewport,
+            easing: EasingFunction::Linear,
+            source: KeyframeSource::Auto,
+            confidence: None,
         });
-
-    let best_fit = select_best_projection_candidate(events, recording);
-
-    let selected = if let Some(space) = explicit_space {
-        if let Some(explicit) = projection_candidate_for_space(space, recording) {
-            let explicit_score = score_projection_candidate(explicit, events);
-            let best_fit_score = score_projection_candidate(best_fit, events);
-            if best_fit.model == AnalysisPointerModel::CaptureNormalized
-                && best_fit_score > explicit_score + EXPLICIT_PROJECTION_FALLBACK_DELTA
-            {
-                best_fit
-            } else {
-                explicit
-            }
-        } else {
-            best_fit
-        }
-    } else {
-        best_fit
-    };
-
-    let projected_events = events
-        .iter()
-        .map(|event| project_event(event, selected.transform))
-        .collect();
-
-    (projected_events, selected.model)
-}
-
-fn projection_candidate_for_space(
-    space: PointerCoordinateSpace,
-    recording: &RecordingConfig,
-) -> Option<ProjectionCandidate> {
-    match space {
-        PointerCoordinateSpace::CaptureNormalized => Some(ProjectionCandidate {
-            model: AnalysisPointerModel::CaptureNormalized,
-            transform: PointerTransform::identity(),
-        }),
-        PointerCoordinateSpace::VirtualDesktopNormalized => {
-            virtual_desktop_projection_candidates(recording)
-                .into_iter()
-                .find(|candidate| candidate.model == AnalysisPointerModel::VirtualDesktopNormalized)
-        }
-        PointerCoordinateSpace::VirtualDesktopRootOrigin => {
-            virtual_desktop_projection_candidates(recording)
-                .into_iter()
-                .find(|candidate| candidate.model == AnalysisPointerModel::VirtualDesktopRootOrigin)
-        }
-        PointerCoordinateSpace::LegacyUnspecified => None,
+        previous = Some(viewport);
     }
-}
 
-fn select_best_projection_candidate(
-    events: &[InputEvent],
-    recording: &RecordingConfig,
-) -> ProjectionCandidate {
-    let capture_candidate = ProjectionCandidate {
-        model: AnalysisPointerModel::CaptureNormalized,
-        transform: PointerTransform::identity(),
-    };
-
-    let mut candidates = vec![capture_candidate];
-    candidates.extend(virtual_desktop_projection_candidates(recording));
-
-    let mut best = capture_candidate;
-    let mut best_score = score_projection_candidate(capture_candidate, events);
-    for candidate in candidates.into_iter().skip(1) {
-        let score = score_projection_candidate(candidate, events);
-        if score > best_score {
-            best = candidate;
-            best_score = score;
-        }
-    }
-
-    best
+    Some(normalize_keyframes(keyframes))
 }
 
-fn virtual_desktop_projection_candidates(recording: &RecordingConfig) -> Vec<ProjectionCandidate> {
-    let monitor_w = recording.monitor_width as f64;
-    let monitor_h = recording.monitor_height as f64;
-    let virtual_w = recording.virtual_width as f64;
-    let virtual_h = recording.virtual_height as f64;
-    if monitor_w <= 0.0 || monitor_h <= 0.0 || virtual_w <= 0.0 || virtual_h <= 0.0 {
-        return vec![];
+fn adaptive_chunk_secs(requested_secs: f64, events: &[InputEvent]) -> f64 {
+    let requested = requested_secs.max(0.25);
+    if events.len() < 2 {
+        return requested;
     }
 
-    let scale_x = virtual_w / monitor_w;
-    let scale_y = virtual_h / monitor_h;
-    let tx_bounds = (recording.virtual_x as f64 - recording.monitor_x as f64) / monitor_w;
-    let ty_bounds = (recording.virtual_y as f64 - recording.monitor_y as f64) / monitor_h;
-    let tx_root = -(recording.monitor_x as f64) / monitor_w;
-    let ty_root = -(recording.monitor_y as f64) / monitor_h;
-
-    let bounds_candidate = ProjectionCandidate {
-        model: AnalysisPointerModel::VirtualDesktopNormalized,
-        transform: PointerTransform::from_affine(scale_x, scale_y, tx_bounds, ty_bounds),
-    };
-
-    if (tx_bounds - tx_root).abs() < 1e-9 && (ty_bounds - ty_root).abs() < 1e-9 {
-        return vec![bounds_candidate];
+    let start = events.first().map(|e| e.timestamp_ns).unwrap_or(0);
+    let end = events.last().map(|e| e.timestamp_ns).unwrap_or(start);
+    if end <= start {
+        return requested;
     }
 
-    vec![
-        bounds_candidate,
-        ProjectionCandidate {
-            model: AnalysisPointerModel::VirtualDesktopRootOrigin,
-            transform: PointerTransform::from_affine(scale_x, scale_y, tx_root, ty_root),
-        },
-    ]
+    let duration_secs = (end - start) as f64 / 1_000_000_000.0;
+    let adaptive_target = (duration_secs / 10.0).max(0.35);
+    requested.min(adaptive_target).max(0.25)
 }
 
-fn score_projection_candidate(candidate: ProjectionCandidate, events: &[InputEvent]) -> f64 {
-    let sample_stride = ((events.len() as f64) / 1024.0).ceil() as usize;
-    let sample_stride = sample_stride.max(1);
-
-    let mut sampled = 0usize;
-    let mut in_bounds = 0usize;
-    let mut near_border = 0usize;
-    let mut min_x = f64::INFINITY;
-    let mut max_x = f64::NEG_INFINITY;
-    let mut min_y = f64::INFINITY;
-    let mut max_y = f64::NEG_INFINITY;
-
-    for (idx, event) in events.iter().enumerate() {
-        if idx % sample_stride != 0 {
-            continue;
-        }
-        let Some((x, y)) = event.pointer_position() else {
-            continue;
-        };
-
-        let (px, py) = candidate.transform.project(x, y);
-        if !px.is_finite() || !py.is_finite() {
-            continue;
-        }
-
-        sampled += 1;
-        if (0.0..=1.0).contains(&px) && (0.0..=1.0).contains(&py) {
-            in_bounds += 1;
-            min_x = min_x.min(px);
-            max_x = max_x.max(px);
-            min_y = min_y.min(py);
-            max_y = max_y.max(py);
-            if px <= 0.01 || px >= 0.99 || py <= 0.01 || py >= 0.99 {
-                near_border += 1;
+/// The header and events of the project at `path`. The low-memory profile
+/// streams the file and thins pointer moves as they are read.
+pub(crate) fn load_events(
+    path: &Path,
+    memory: &MemoryProfile,
+) -> anyhow::Result<(Option<EventStreamHeader>, Vec<InputEvent>)> {
+    let events_path = path.join("meta").join("events.jsonl");
+    if memory.stream_events {
+        let stream = EventStream::open(&events_path)
+            .map_err(|_| anyhow::anyhow!("Events file not found: {}", events_path.display()))?;
+        let header = stream.header().cloned();
+        let mut thinner = memory.analysis_pointer_hz.map(PointerThinner::new);
+        let mut events = Vec::new();
+        for event in stream {
+            let event = event.map_err(|e| anyhow::anyhow!("Failed to parse events: {e}"))?;
+            if thinner
+                .as_mut()
+                .map_or(true, |thinner| thinner.keep(&event))
+            {
+                events.push(event);
             }
         }
+        return Ok((header, events));
     }
 
-    if sampled == 0 {
-        return -1.0;
-    }
-
-    let in_bounds_ratio = in_bounds as f64 / sampled as f64;
-    let span_x = if min_x.is_finite() && max_x.is_finite() {
-        (max_x - min_x).clamp(0.0, 1.0)
-    } else {
-        0.0
-    };
-    let span_y = if min_y.is_finite() && max_y.is_finite() {
-        (max_y - min_y).clamp(0.0, 1.0)
-    } else {
-        0.0
-    };
-    let span_score = (span_x + span_y).clamp(0.0, 1.5);
-    let border_ratio = if in_bounds > 0 {
-        near_border as f64 / in_bounds as f64
-    } else {
-        1.0
-    };
-
-    in_bounds_ratio * 4.0 + span_score - border_ratio * 0.75
-}
+    let events_content = std::fs::read_to_string(&events_path)
+        .map_err(|_| anyhow::anyhow!("Events file not found: {}", events_path.display()))?;
 
-fn project_event(event: &InputEvent, transform: PointerTransform) -> InputEvent {
-    let kind = match &event.kind {
-        EventKind::Pointer { x, y } => {
-            let (px, py) = project_pointer_xy(*x, *y, transform);
-            EventKind::Pointer { x: px, y: py }
-        }
-        EventKind::Click {
-            button,
-            state,
-            x,
-            y,
-        } => {
-            let (px, py) = project_pointer_xy(*x, *y, transform);
-            EventKind::Click {
-                button: *button,
-                state: *state,
-                x: px,
-                y: py,
-            }
-        }
-        EventKind::Scroll { dx, dy, x, y } => {
-            let (px, py) = project_pointer_xy(*x, *y, transform);
-            EventKind::Scroll {
-                dx: *dx,
-                dy: *dy,
-                x: px,
-                y: py,
-            }
-        }
-        other => other.clone(),
-    };
+    let events_header = parse_events_header(&events_content);
 
-    InputEvent {
-        timestamp_ns: event.timestamp_ns,
-        kind,
-    }
-}
+    // Filter out header lines (starting with #)
+    let event_lines: String = events_content
+        .lines()
+        .filter(|l| !l.starts_with('#') && !l.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
 
-fn project_pointer_xy(x: f64, y: f64, transform: PointerTransform) -> (f64, f64) {
-    let (px, py) = transform.project(x, y);
-    (px.clamp(0.0, 1.0), py.clamp(0.0, 1.0))
+    let events =
+        parse_events(&event_lines).map_err(|e| anyhow::anyhow!("Failed to parse events: {e}"))?;
+    Ok((events_header, events))
 }
 
 fn parse_cursor_smoothing(raw: &str) -> anyhow::Result<TimelineSmoothingAlgorithm> {
@@ -788,8 +749,10 @@ fn parse_cursor_smoothing(raw: &str) -> anyhow::Result<TimelineSmoothingAlgorith
 #[cfg(test)]
 mod tests {
     use super::*;
-    use grabme_project_model::event::MouseButton;
-    use grabme_project_model::project::{Project, RecordedMonitor};
+    use grabme_project_model::event::{ButtonState, MouseButton};
+    use grabme_project_model::project::{
+        Project, RecordedMonitor, WindowGeometrySample, WindowTrack,
+    };
 
     #[test]
     fn test_camera_style_parser_accepts_aliases() {
@@ -816,7 +779,14 @@ mod tests {
 
         let project = Project::new("test", 1920, 1080, 60);
 
-        let timeline = build_production_timeline(&events, &events, None, &project.recording);
+        let timeline = build_production_timeline(
+            &events,
+            &events,
+            None,
+            &project.recording,
+            &ClickBurstConfig::default(),
+            None,
+        );
         assert_eq!(timeline.keyframes.len(), 1);
         assert_eq!(timeline.keyframes[0].viewport, Viewport::FULL);
     }
@@ -837,7 +807,14 @@ mod tests {
 
         let project = Project::new("test", 1920, 1080, 60);
 
-        let timeline = build_production_timeline(&events, &events, None, &project.recording);
+        let timeline = build_production_timeline(
+            &events,
+            &events,
+            None,
+            &project.recording,
+            &ClickBurstConfig::default(),
+            None,
+        );
         assert!(timeline.keyframes.len() >= 4);
         assert_eq!(timeline.keyframes[0].time_secs, 0.0);
         assert_eq!(timeline.keyframes[0].viewport, Viewport::FULL);
@@ -877,7 +854,14 @@ mod tests {
 
         let project = Project::new("test", 1920, 1080, 60);
 
-        let timeline = build_production_timeline(&events, &events, None, &project.recording);
+        let timeline = build_production_timeline(
+            &events,
+            &events,
+            None,
+            &project.recording,
+            &ClickBurstConfig::default(),
+            None,
+        );
         let zoom_keyframes = timeline
             .keyframes
             .iter()
@@ -889,107 +873,110 @@ mod tests {
     }
 
     #[test]
-    fn test_adaptive_chunk_secs_short_recording_uses_finer_chunks() {
+    fn test_fast_narration_shortens_click_cooldown() {
+        let click = |ns: u64, pos: f64| {
+            InputEvent::click(ns, MouseButton::Left, ButtonState::Down, pos, pos)
+        };
         let events = vec![
-            InputEvent::pointer(0, 0.1, 0.1),
-            InputEvent::pointer(4_000_000_000, 0.2, 0.2),
+            InputEvent::pointer(0, 0.5, 0.5),
+            click(1_000_000_000, 0.5),
+            click(2_600_000_000, 0.6),
+            click(4_200_000_000, 0.7),
         ];
-        let chunk = adaptive_chunk_secs(2.0, &events);
-        assert!((chunk - 0.4).abs() < 1e-9);
+        let project = Project::new("test", 1920, 1080, 60);
+        let zoom_keyframes = |pace: Option<&NarrationPace>| {
+            build_production_timeline(
+                &events,
+                &events,
+                None,
+                &project.recording,
+                &ClickBurstConfig::default(),
+                pace,
+            )
+            .keyframes
+            .iter()
+            .filter(|kf| kf.viewport.w < 1.0 || kf.viewport.h < 1.0)
+            .count()
+        };
+
+        // 20 words in 4 seconds is 300 wpm: the cooldown halves to 1.5s.
+        let words = vec!["word"; 20].join(" ");
+        let fast = NarrationPace::from_segments([(0.0, 4.0, words.as_str())]);
+        assert_eq!(zoom_keyframes(None), 4);
+        assert_eq!(zoom_keyframes(Some(&fast)), 6);
     }
 
     #[test]
-    fn test_adaptive_chunk_secs_long_recording_preserves_requested_chunk() {
+    fn test_build_production_timeline_merges_click_bursts() {
+        let down = |ns: u64| InputEvent::click(ns, MouseButton::Left, ButtonState::Down, 0.4, 0.4);
         let events = vec![
-            InputEvent::pointer(0, 0.1, 0.1),
-            InputEvent::pointer(60_000_000_000, 0.2, 0.2),
+            InputEvent::pointer(0, 0.5, 0.5),
+            down(1_000_000_000),
+            down(1_180_000_000),
+            down(1_360_000_000),
         ];
-        let chunk = adaptive_chunk_secs(2.0, &events);
-        assert!((chunk - 2.0).abs() < 1e-9);
-    }
 
-    #[test]
-    fn test_project_events_to_capture_space_uses_virtual_bounds_mapping() {
-        let mut project = Project::new("test", 1920, 1080, 60);
-        project.recording.monitor_x = 0;
-        project.recording.monitor_y = 0;
-        project.recording.monitor_width = 1920;
-        project.recording.monitor_height = 1080;
-        project.recording.virtual_x = 0;
-        project.recording.virtual_y = 0;
-        project.recording.virtual_width = 4480;
-        project.recording.virtual_height = 1440;
-        project.recording.pointer_coordinate_space =
-            PointerCoordinateSpace::VirtualDesktopNormalized;
+        let project = Project::new("test", 1920, 1080, 60);
 
-        let events = vec![InputEvent::pointer(0, 0.25, 0.5)];
-        let (mapped, model) = project_events_to_capture_space(&events, None, &project.recording);
+        let timeline = build_production_timeline(
+            &events,
+            &events,
+            None,
+            &project.recording,
+            &ClickBurstConfig::default(),
+            None,
+        );
+        let zoomed: Vec<&CameraKeyframe> = timeline
+            .keyframes
+            .iter()
+            .filter(|kf| kf.viewport.w < 1.0)
+            .collect();
 
-        assert_eq!(model, AnalysisPointerModel::VirtualDesktopNormalized);
-        let (x, y) = mapped[0].pointer_position().unwrap();
-        assert!((x - 0.583333).abs() < 1e-5);
-        assert!((y - 0.666666).abs() < 1e-5);
+        // One pulse, held until after the triple click's last press.
+        assert_eq!(zoomed.len(), 2);
+        assert!((zoomed[0].time_secs - 1.0).abs() < 1e-9);
+        assert!(zoomed[1].time_secs > 1.36);
     }
 
     #[test]
-    fn test_project_events_to_capture_space_honors_explicit_root_origin_header() {
-        let mut project = Project::new("test", 2560, 1440, 60);
-        project.recording.monitor_x = -1920;
-        project.recording.monitor_y = 0;
-        project.recording.monitor_width = 2560;
-        project.recording.monitor_height = 1440;
-        project.recording.virtual_x = -1920;
-        project.recording.virtual_y = 0;
-        project.recording.virtual_width = 4480;
-        project.recording.virtual_height = 1440;
-        project.recording.pointer_coordinate_space = PointerCoordinateSpace::LegacyUnspecified;
-
-        let events = vec![
-            InputEvent::pointer(0, 0.1429, 0.3000),
-            InputEvent::pointer(16_000_000, 0.2143, 0.3200),
-            InputEvent::pointer(32_000_000, 0.2679, 0.3500),
+    fn test_apply_mapping_confidence_scales_auto_keyframes_only() {
+        let mut keyframes = vec![
+            full_keyframe(0.0, EasingFunction::EaseInOut),
+            CameraKeyframe {
+                confidence: Some(0.5),
+                ..full_keyframe(1.0, EasingFunction::EaseInOut)
+            },
+            CameraKeyframe {
+                source: KeyframeSource::Manual,
+                ..full_keyframe(2.0, EasingFunction::EaseInOut)
+            },
         ];
 
-        let header = EventStreamHeader {
-            schema_version: "1.0".to_string(),
-            epoch_monotonic_ns: 0,
-            epoch_wall: "2026-01-01T00:00:00Z".to_string(),
-            capture_width: 2560,
-            capture_height: 1440,
-            scale_factor: 1.0,
-            pointer_sample_rate_hz: 60,
-            pointer_coordinate_space: PointerCoordinateSpace::VirtualDesktopRootOrigin,
-        };
+        apply_mapping_confidence(&mut keyframes, 0.8);
 
-        let (_mapped, model) =
-            project_events_to_capture_space(&events, Some(&header), &project.recording);
-        assert_eq!(model, AnalysisPointerModel::VirtualDesktopRootOrigin);
+        assert_eq!(keyframes[0].confidence, Some(0.8));
+        assert!((keyframes[1].confidence.unwrap() - 0.4).abs() < 1e-9);
+        assert_eq!(keyframes[2].confidence, None);
     }
 
     #[test]
-    fn test_project_events_to_capture_space_falls_back_when_explicit_mapping_is_inconsistent() {
-        let mut project = Project::new("test", 1920, 1080, 60);
-        project.recording.monitor_x = 0;
-        project.recording.monitor_y = 0;
-        project.recording.monitor_width = 1920;
-        project.recording.monitor_height = 1080;
-        project.recording.virtual_x = 0;
-        project.recording.virtual_y = 0;
-        project.recording.virtual_width = 4480;
-        project.recording.virtual_height = 1440;
-        project.recording.pointer_coordinate_space =
-            PointerCoordinateSpace::VirtualDesktopNormalized;
-
-        // These coordinates resemble a stream normalized against a narrower width,
-        // where forcing virtual_desktop projection would push many points out of bounds.
+    fn test_adaptive_chunk_secs_short_recording_uses_finer_chunks() {
         let events = vec![
-            InputEvent::pointer(0, 0.38, 0.45),
-            InputEvent::pointer(16_000_000, 0.44, 0.47),
-            InputEvent::pointer(32_000_000, 0.52, 0.50),
+            InputEvent::pointer(0, 0.1, 0.1),
+            InputEvent::pointer(4_000_000_000, 0.2, 0.2),
         ];
+        let chunk = adaptive_chunk_secs(2.0, &events);
+        assert!((chunk - 0.4).abs() < 1e-9);
+    }
 
-        let (_mapped, model) = project_events_to_capture_space(&events, None, &project.recording);
-        assert_eq!(model, AnalysisPointerModel::CaptureNormalized);
+    #[test]
+    fn test_adaptive_chunk_secs_long_recording_preserves_requested_chunk() {
+        let events = vec![
+            InputEvent::pointer(0, 0.1, 0.1),
+            InputEvent::pointer(60_000_000_000, 0.2, 0.2),
+        ];
+        let chunk = adaptive_chunk_secs(2.0, &events);
+        assert!((chunk - 2.0).abs() < 1e-9);
     }
 
     #[test]
@@ -1050,4 +1037,67 @@ mod tests {
             .iter()
             .any(|kf| (kf.viewport.x - (1920.0 / 4480.0)).abs() < 1e-6));
     }
+
+    #[test]
+    fn test_window_track_needs_the_production_style() {
+        let mut project = Project::new("window", 1920, 1080, 60);
+        assert!(check_window_track_style(&project.recording, CameraStyle::Auto, true).is_ok());
+
+        project.recording.window_track = Some(WindowTrack {
+            window_id: "0x3a00007".to_string(),
+            samples: Vec::new(),
+        });
+        assert!(
+            check_window_track_style(&project.recording, CameraStyle::Production, false).is_ok()
+        );
+        let err = check_window_track_style(&project.recording, CameraStyle::Auto, false)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("--camera-style production"), "{err}");
+        assert!(
+            check_window_track_style(&project.recording, CameraStyle::Production, true).is_err()
+        );
+    }
+
+    #[test]
+    fn test_build_window_follow_keyframes_holds_until_window_moves() {
+        let mut project = Project::new("window", 3840, 1080, 60);
+        project.recording.virtual_width = 3840;
+        project.recording.virtual_height = 1080;
+        project.recording.window_track = Some(WindowTrack {
+            window_id: "0x3a00007".to_string(),
+            samples: vec![
+                WindowGeometrySample {
+                    t_ns: 0,
+                    x: 0,
+                    y: 0,
+                    width: 1920,
+                    height: 1080,
+                },
+                WindowGeometrySample {
+                    t_ns: 5_000_000_000,
+                    x: 1920,
+                    y: 0,
+                    width: 1920,
+                    height: 1080,
+                },
+            ],
+        });
+
+        let keyframes = build_window_follow_keyframes(&project.recording).unwrap();
+        assert_eq!(keyframes.len(), 3);
+        assert!((keyframes[1].time_secs - 4.9).abs() < 1e-9);
+        assert_eq!(keyframes[1].viewport, keyframes[0].viewport);
+        assert!((keyframes[2].viewport.x - 0.5).abs() < 1e-9);
+
+        let timeline = build_production_timeline(
+            &[],
+            &[],
+            None,
+            &project.recording,
+            &ClickBurstConfig::default(),
+            None,
+        );
+        assert!((timeline.viewport_at(6.0).x - 0.5).abs() < 1e-9);
+    }
 }
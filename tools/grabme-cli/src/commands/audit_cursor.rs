@@ -0,0 +1,79 @@
+//! Check where an export drew the cursor at recorded clicks.
+
+use std::path::PathBuf;
+
+use grabme_common::config::AppConfig;
+use grabme_project_model::LoadedProject;
+use grabme_render_engine::cursor_audit::{audit_exported_cursor, DEFAULT_SEARCH_RADIUS_PX};
+use grabme_render_engine::resources::ExportResources;
+use grabme_render_engine::ExportJob;
+
+pub fn run(
+    project: PathBuf,
+    export: PathBuf,
+    start: Option<f64>,
+    end: Option<f64>,
+    keep_preroll: bool,
+    radius: Option<u32>,
+    json: bool,
+) -> anyhow::Result<()> {
+    let loaded = LoadedProject::load(&project)
+        .map_err(|e| anyhow::anyhow!("Failed to load project: {e}"))?;
+    if !export.exists() {
+        anyhow::bail!("Export not found: {}", export.display());
+    }
+
+    let job = ExportJob {
+        project_dir: project,
+        output_path: export.clone(),
+        config: loaded.project.export.clone(),
+        start_secs: start,
+        end_secs: end,
+        trim_preroll: !keep_preroll && AppConfig::load().recording.trim_preroll,
+        trim_tail: AppConfig::load().recording.trim_tail,
+        debug_overlay: false,
+        resources: ExportResources::default(),
+        fast_copy: false,
+        draft: None,
+    };
+    let report = audit_exported_cursor(&job, &export, radius.unwrap_or(DEFAULT_SEARCH_RADIUS_PX))
+        .map_err(|e| anyhow::anyhow!("Cursor audit failed: {e}"))?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    println!("Cursor audit: {}", export.display());
+    println!("  Video:    {}x{}", report.width, report.height);
+    println!(
+        "  Clicks:   {} ({} found, {} missed)",
+        report.clicks.len(),
+        report.detected(),
+        report.clicks.len() - report.detected()
+    );
+    for click in &report.clicks {
+        let expected = click.expected;
+        match click.error_px() {
+            Some(error) => println!(
+                "  {:>8.3}s  expected ({:.0}, {:.0})  off by {error:.1}px",
+                expected.output_secs, expected.x, expected.y
+            ),
+            None => println!(
+                "  {:>8.3}s  expected ({:.0}, {:.0})  cursor not found",
+                expected.output_secs, expected.x, expected.y
+            ),
+        }
+    }
+    match report.stats {
+        Some(stats) => println!(
+            "  Error:    mean {:.1}px, median {:.1}px, p95 {:.1}px, max {:.1}px",
+            stats.mean_px, stats.median_px, stats.p95_px, stats.max_px
+        ),
+        None if report.clicks.is_empty() => {
+            println!("  No clicks were recorded in the exported range.")
+        }
+        None => println!("  The cursor was not found at any click."),
+    }
+    Ok(())
+}
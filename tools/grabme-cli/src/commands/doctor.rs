@@ -0,0 +1,466 @@
+//! Deep environment diagnostics with fixes written to the config.
+//!
+//! Where `grabme check` looks for the pieces, `doctor` exercises them: it
+//! records two seconds on every usable capture backend, checks the
+//! GStreamer elements and FFmpeg encoders recording and export rely on, and
+//! times a write to the projects directory. Problems it can work around
+//! (a broken backend, an fps the machine can't sustain) become config
+//! changes the user can accept.
+
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use grabme_capture_engine::pipeline::{capture_elements, missing_gst_elements};
+use grabme_capture_engine::{
+    default_monitor_index, list_monitors, AudioCaptureConfig, CaptureMode, CaptureSession,
+    ScreenCaptureConfig, SessionConfig,
+};
+use grabme_common::config::AppConfig;
+use grabme_common::temp::SessionTempDir;
+use grabme_platform_linux::{
+    detect_display_server, set_display_target, x11_display_name, DisplayServer, DisplayTarget,
+};
+use grabme_project_model::project::ExportFormat;
+use grabme_render_engine::probe::{count_video_frames, ffmpeg_encoders};
+use grabme_render_engine::required_ffmpeg_encoders;
+
+/// Length of each test recording.
+const CAPTURE_TEST_SECS: f64 = 2.0;
+
+/// Bytes written by the disk throughput test.
+const DISK_TEST_BYTES: usize = 64 * 1024 * 1024;
+
+/// Below this, long recordings with webcam and audio tracks can stall on
+/// writes (typically a network or USB 2 drive).
+const MIN_DISK_MB_PER_SEC: f64 = 20.0;
+
+/// Share of all CPU cores a test recording may use before a lower frame
+/// rate is suggested; the rest is headroom for the app being recorded.
+const MAX_CAPTURE_CPU_SHARE: f64 = 0.6;
+
+/// Frame rates offered when the configured one is too expensive.
+const FALLBACK_FPS: [u32; 4] = [30, 25, 24, 15];
+
+/// A config change that works around a detected problem.
+#[derive(Debug, Clone, PartialEq)]
+enum ConfigFix {
+    DisplayServer(DisplayServer),
+    Fps(u32),
+}
+
+impl ConfigFix {
+    fn describe(&self) -> String {
+        match self {
+            ConfigFix::DisplayServer(server) => {
+                format!("record with the {} backend", backend_name(*server))
+            }
+            ConfigFix::Fps(fps) => format!("record at {fps} fps by default"),
+        }
+    }
+
+    fn apply(&self, config: &mut AppConfig) {
+        match self {
+            ConfigFix::DisplayServer(server) => {
+                config.recording.display_server = Some(backend_name(*server).to_string());
+            }
+            ConfigFix::Fps(fps) => config.recording.fps = *fps,
+        }
+    }
+}
+
+/// Outcome of a test recording on one backend.
+struct CaptureTest {
+    server: DisplayServer,
+    result: anyhow::Result<CaptureMeasurement>,
+}
+
+struct CaptureMeasurement {
+    bytes: u64,
+    frames: Option<u64>,
+    /// Share of all cores the process used while recording.
+    cpu_share: Option<f64>,
+}
+
+pub async fn run(skip_capture: bool, yes: bool) -> anyhow::Result<()> {
+    let mut config = AppConfig::load();
+    let detected = detect_display_server();
+    let preferred = config
+        .recording
+        .display_server
+        .as_deref()
+        .and_then(|name| name.parse().ok())
+        .unwrap_or(detected);
+    let backends = available_backends();
+    let mut fixes: Vec<ConfigFix> = Vec::new();
+
+    println!("GrabMe Doctor");
+    println!("{}", "=".repeat(50));
+    println!("Display server: {detected:?} (recording uses {preferred:?})");
+    println!(
+        "Capture backends: {}",
+        if backends.is_empty() {
+            "none".to_string()
+        } else {
+            backends
+                .iter()
+                .map(|server| backend_name(*server))
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+    );
+
+    println!();
+    println!("GStreamer elements");
+    if backends.is_empty() {
+        println!("  [SKIP] no capture backend available (is a desktop session running?)");
+    }
+    for server in &backends {
+        match missing_gst_elements(capture_elements(*server)) {
+            Ok(missing) if missing.is_empty() => {
+                println!(
+                    "  [OK] {}: all capture elements present",
+                    backend_name(*server)
+                )
+            }
+            Ok(missing) => println!(
+                "  [FAIL] {}: missing {} (install the GStreamer plugin packages that provide them)",
+                backend_name(*server),
+                missing.join(", ")
+            ),
+            Err(e) => println!("  [FAIL] {e}"),
+        }
+    }
+
+    println!();
+    println!("FFmpeg encoders");
+    match ffmpeg_encoders() {
+        Ok(encoders) => {
+            for format in [
+                ExportFormat::Mp4H264,
+                ExportFormat::Mp4H265,
+                ExportFormat::Webm,
+                ExportFormat::Gif,
+                ExportFormat::PngSequence,
+            ] {
+                let missing: Vec<&str> = required_ffmpeg_encoders(format)
+                    .iter()
+                    .copied()
+                    .filter(|name| !encoders.iter().any(|e| e == name))
+                    .collect();
+                if missing.is_empty() {
+                    println!("  [OK] {format:?}");
+                } else {
+                    println!("  [FAIL] {format:?}: missing {}", missing.join(", "));
+                }
+            }
+        }
+        Err(e) => println!("  [FAIL] {e} (export needs ffmpeg on PATH)"),
+    }
+
+    println!();
+    println!("Disk throughput");
+    let projects_dir = config.projects_dir.clone();
+    match measure_disk_throughput(&projects_dir) {
+        Ok(mb_per_sec) if mb_per_sec < MIN_DISK_MB_PER_SEC => println!(
+            "  [WARN] {}: {mb_per_sec:.0} MB/s; recordings may stall. Point `projects_dir` at a local disk.",
+            projects_dir.display()
+        ),
+        Ok(mb_per_sec) => println!("  [OK] {}: {mb_per_sec:.0} MB/s", projects_dir.display()),
+        Err(e) => println!("  [FAIL] {}: {e}", projects_dir.display()),
+    }
+
+    if skip_capture {
+        println!();
+        println!("Skipping test recordings (--skip-capture).");
+    } else {
+        println!();
+        println!("Test recordings ({CAPTURE_TEST_SECS:.0}s each, no audio)");
+        let mut tests = Vec::new();
+        for server in &backends {
+            if *server == DisplayServer::Wayland {
+                println!("  Approve the screen-share dialog to test portal permissions...");
+            }
+            let test = CaptureTest {
+                server: *server,
+                result: test_capture(*server, config.recording.fps).await,
+            };
+            report_capture(&test, config.recording.fps);
+            tests.push(test);
+        }
+        set_display_target(DisplayTarget::default());
+        fixes.extend(capture_fixes(&tests, preferred, config.recording.fps));
+    }
+
+    println!();
+    if fixes.is_empty() {
+        println!("No config changes needed.");
+        return Ok(());
+    }
+
+    let interactive = std::io::stdin().is_terminal();
+    let mut applied = 0;
+    for fix in &fixes {
+        println!("Suggested fix: {}", fix.describe());
+        let accept = yes || (interactive && confirm("  Write this to the config?"));
+        if accept {
+            fix.apply(&mut config);
+            applied += 1;
+        }
+    }
+    if applied > 0 {
+        config
+            .save()
+            .map_err(|e| anyhow::anyhow!("Failed to save config: {e}"))?;
+        println!("Saved {applied} change(s) to the config.");
+    } else if !interactive && !yes {
+        println!("Run `grabme doctor --yes` to apply these fixes.");
+    }
+
+    Ok(())
+}
+
+/// Backends this session can record with.
+fn available_backends() -> Vec<DisplayServer> {
+    let mut backends = Vec::new();
+    if x11_display_name().is_some() {
+        backends.push(DisplayServer::X11);
+    }
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        backends.push(DisplayServer::Wayland);
+    }
+    backends
+}
+
+fn backend_name(server: DisplayServer) -> &'static str {
+    match server {
+        DisplayServer::X11 => "x11",
+        DisplayServer::Wayland => "wayland",
+        DisplayServer::Windows => "windows",
+        DisplayServer::MacOS => "macos",
+        DisplayServer::Unknown => "unknown",
+    }
+}
+
+/// Record a short clip on `server` into a scratch directory.
+async fn test_capture(server: DisplayServer, fps: u32) -> anyhow::Result<CaptureMeasurement> {
+    let display = DisplayTarget {
+        server: Some(server),
+        x11_display: None,
+    };
+    set_display_target(display.clone());
+    let monitors = list_monitors().unwrap_or_default();
+    let scratch = SessionTempDir::new()?;
+
+    let config = SessionConfig {
+        name: "doctor".to_string(),
+        output_dir: scratch.path().to_path_buf(),
+        screen: ScreenCaptureConfig {
+            mode: CaptureMode::FullScreen {
+                monitor_index: default_monitor_index(&monitors),
+            },
+            hide_cursor: true,
+            cursor_mode: None,
+            monitor_splits: Default::default(),
+        },
+        audio: AudioCaptureConfig {
+            mic: false,
+            system: false,
+            app_isolation: None,
+            sample_rate: 48000,
+        },
+        fps,
+        display,
+        ..Default::default()
+    };
+
+    async {
+        let mut session = CaptureSession::new(config);
+        session.start().await?;
+        let cpu_before = process_cpu_secs();
+        let started = Instant::now();
+        tokio::time::sleep(Duration::from_secs_f64(CAPTURE_TEST_SECS)).await;
+        let cpu_share = cpu_before.zip(process_cpu_secs()).map(|(before, after)| {
+            let cores = std::thread::available_parallelism().map_or(1, |n| n.get());
+            (after - before) / started.elapsed().as_secs_f64() / cores as f64
+        });
+        let project = session.stop().await?;
+
+        let screen = project.join("sources").join("screen.mkv");
+        let bytes = std::fs::metadata(&screen).map(|m| m.len()).unwrap_or(0);
+        if bytes == 0 {
+            anyhow::bail!("no video was written to {}", screen.display());
+        }
+        Ok(CaptureMeasurement {
+            bytes,
+            frames: count_video_frames(&screen).ok(),
+            cpu_share,
+        })
+    }
+    .await
+}
+
+fn report_capture(test: &CaptureTest, fps: u32) {
+    let name = backend_name(test.server);
+    match &test.result {
+        Ok(m) => {
+            let mut line = format!("  [OK] {name}: {} KB", m.bytes / 1024);
+            if let Some(frames) = m.frames {
+                line.push_str(&format!(
+                    ", {frames} frames (expected ~{:.0})",
+                    fps as f64 * CAPTURE_TEST_SECS
+                ));
+            }
+            if let Some(share) = m.cpu_share {
+                line.push_str(&format!(", {:.0}% CPU", share * 100.0));
+            }
+            println!("{line}");
+        }
+        Err(e) if test.server == DisplayServer::Wayland => {
+            println!("  [FAIL] {name}: {e}");
+            println!("         If the dialog never appeared or was denied, check that xdg-desktop-portal and your desktop's portal backend are running.");
+        }
+        Err(e) => println!("  [FAIL] {name}: {e}"),
+    }
+}
+
+/// Fixes suggested by the test recordings.
+fn capture_fixes(tests: &[CaptureTest], preferred: DisplayServer, fps: u32) -> Vec<ConfigFix> {
+    let mut fixes = Vec::new();
+    let working: Vec<&CaptureTest> = tests.iter().filter(|t| t.result.is_ok()).collect();
+    let preferred_works = working.iter().any(|t| t.server == preferred);
+
+    let chosen = if preferred_works {
+        working.iter().find(|t| t.server == preferred)
+    } else {
+        let fallback = working.first();
+        if let Some(test) = fallback {
+            fixes.push(ConfigFix::DisplayServer(test.server));
+        }
+        fallback
+    };
+
+    let cpu_share = chosen
+        .and_then(|t| t.result.as_ref().ok())
+        .and_then(|m| m.cpu_share);
+    if let Some(lower) = cpu_share.and_then(|share| suggested_fps(fps, share)) {
+        fixes.push(ConfigFix::Fps(lower));
+    }
+    fixes
+}
+
+/// A lower frame rate when recording at `fps` used more than
+/// [`MAX_CAPTURE_CPU_SHARE`] of the machine. Encoding cost scales roughly
+/// with frame rate.
+fn suggested_fps(fps: u32, cpu_share: f64) -> Option<u32> {
+    if cpu_share <= MAX_CAPTURE_CPU_SHARE {
+        return None;
+    }
+    let sustainable = fps as f64 * MAX_CAPTURE_CPU_SHARE / cpu_share;
+    FALLBACK_FPS
+        .iter()
+        .copied()
+        .find(|candidate| *candidate < fps && *candidate as f64 <= sustainable)
+        .or_else(|| FALLBACK_FPS.iter().copied().rfind(|c| *c < fps))
+}
+
+/// CPU time used by this process so far, from `/proc/self/stat`.
+fn process_cpu_secs() -> Option<f64> {
+    let stat = std::fs::read_to_string("/proc/self/stat").ok()?;
+    // Fields after the parenthesized command name; utime and stime are
+    // the 14th and 15th fields overall.
+    let rest = stat.rsplit_once(')')?.1;
+    let fields: Vec<&str> = rest.split_whitespace().collect();
+    let utime: f64 = fields.get(11)?.parse().ok()?;
+    let stime: f64 = fields.get(12)?.parse().ok()?;
+    // Linux reports these in USER_HZ, which is 100 on every mainstream
+    // architecture.
+    Some((utime + stime) / 100.0)
+}
+
+/// Write and sync a scratch file in `dir`, returning MB/s.
+fn measure_disk_throughput(dir: &Path) -> anyhow::Result<f64> {
+    std::fs::create_dir_all(dir)?;
+    let path: PathBuf = dir.join(format!(".grabme-doctor-{}", std::process::id()));
+    let chunk = vec![0x5au8; 1024 * 1024];
+
+    let started = Instant::now();
+    let result = (|| -> std::io::Result<()> {
+        let mut file = std::fs::File::create(&path)?;
+        for _ in 0..DISK_TEST_BYTES / chunk.len() {
+            file.write_all(&chunk)?;
+        }
+        file.sync_all()
+    })();
+    let elapsed = started.elapsed().as_secs_f64();
+    let _ = std::fs::remove_file(&path);
+    result?;
+
+    Ok(DISK_TEST_BYTES as f64 / (1024.0 * 1024.0) / elapsed.max(1e-6))
+}
+
+pub(crate) fn confirm(prompt: &str) -> bool {
+    print!("{prompt} [y/N] ");
+    let _ = std::io::stdout().flush();
+    let mut answer = String::new();
+    if std::io::stdin().lock().read_line(&mut answer).is_err() {
+        return false;
+    }
+    matches!(answer.trim().to_ascii_lowercase().as_str(), "y" | "yes")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test(server: DisplayServer, ok: bool, cpu_share: f64) -> CaptureTest {
+        CaptureTest {
+            server,
+            result: if ok {
+                Ok(CaptureMeasurement {
+                    bytes: 1,
+                    frames: None,
+                    cpu_share: Some(cpu_share),
+                })
+            } else {
+                Err(anyhow::anyhow!("portal denied"))
+            },
+        }
+    }
+
+    #[test]
+    fn test_suggested_fps_scales_with_cpu_use() {
+        assert_eq!(suggested_fps(60, 0.3), None);
+        assert_eq!(suggested_fps(60, 0.9), Some(30));
+        assert_eq!(suggested_fps(60, 2.0), Some(15));
+        assert_eq!(suggested_fps(15, 2.0), None);
+    }
+
+    #[test]
+    fn test_capture_fixes_switch_to_working_backend() {
+        let tests = [
+            test(DisplayServer::X11, true, 0.2),
+            test(DisplayServer::Wayland, false, 0.0),
+        ];
+        assert_eq!(
+            capture_fixes(&tests, DisplayServer::Wayland, 60),
+            vec![ConfigFix::DisplayServer(DisplayServer::X11)]
+        );
+        assert!(capture_fixes(&tests, DisplayServer::X11, 60).is_empty());
+    }
+
+    #[test]
+    fn test_capture_fixes_lower_fps_for_busy_backend() {
+        let tests = [test(DisplayServer::X11, true, 0.9)];
+        assert_eq!(
+            capture_fixes(&tests, DisplayServer::X11, 60),
+            vec![ConfigFix::Fps(30)]
+        );
+
+        let mut config = AppConfig::default();
+        ConfigFix::Fps(30).apply(&mut config);
+        ConfigFix::DisplayServer(DisplayServer::X11).apply(&mut config);
+        assert_eq!(config.recording.fps, 30);
+        assert_eq!(config.recording.display_server.as_deref(), Some("x11"));
+    }
+}
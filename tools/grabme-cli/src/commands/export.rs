@@ -1,62 +1,377 @@
 //! Export a project to video.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use grabme_project_model::project::{AspectMode, ExportConfig, ExportFormat};
+use grabme_common::config::AppConfig;
+use grabme_project_model::project::{
+    AspectMode, ChapterMarker, ClockOverlayConfig, ExportConfig, ExportFormat, ExportMetadata,
+    GamepadOverlayConfig, MetricsOverlayConfig, MusicTrack, OverscanConfig, ReactionOverlayConfig,
+    StrokeOverlayConfig, WaveformConfig, WaveformStyle,
+};
 use grabme_project_model::LoadedProject;
-use grabme_render_engine::export::{export_project, ExportJob, ExportProgress};
-
-pub async fn run(
-    path: PathBuf,
-    output: Option<PathBuf>,
-    format: String,
-    width: u32,
-    height: u32,
-) -> anyhow::Result<()> {
+use grabme_render_engine::bitrate::{
+    export_audio_kbps, parse_size, probe_project_complexity, recommend_video_bitrate_kbps,
+    video_kbps_for_target_size,
+};
+use grabme_render_engine::clipboard::{copy_file_to_clipboard, ClipboardContent};
+use grabme_render_engine::draft::{DraftOptions, DRAFT_PRESET};
+use grabme_render_engine::export::{
+    export_project, export_variants, ExportJob, ExportProgress, VariantProgressCallback,
+};
+use grabme_render_engine::resources::ExportResources;
+use grabme_render_engine::tail::detect_project_tail;
+
+/// Parse a `--chapter` value: `TIME=TITLE`, with the time in seconds or
+/// `[HH:]MM:SS`.
+pub fn parse_chapter(value: &str) -> Result<ChapterMarker, String> {
+    let (time, title) = value
+        .split_once('=')
+        .ok_or_else(|| format!("expected TIME=TITLE, got `{value}`"))?;
+    let time_secs = time
+        .trim()
+        .split(':')
+        .try_fold((0.0, 0), |(secs, fields), field| {
+            let field = field.parse::<f64>().ok().filter(|n| *n >= 0.0)?;
+            Some((secs * 60.0 + field, fields + 1))
+        })
+        .filter(|(secs, fields): &(f64, u32)| *fields <= 3 && secs.is_finite())
+        .map(|(secs, _)| secs)
+        .ok_or_else(|| format!("`{time}` is not a time in seconds or [HH:]MM:SS"))?;
+    if title.trim().is_empty() {
+        return Err(format!("chapter at `{time}` has no title"));
+    }
+    Ok(ChapterMarker {
+        time_secs,
+        title: title.trim().to_string(),
+    })
+}
+
+/// What an export was asked for on the command line, mirroring the flags of
+/// `grabme export`. Options left at their default fall back to the project
+/// and the app config.
+#[derive(Debug, Clone)]
+pub struct ExportArgs {
+    /// Defaults to a file in the project's `exports` directory.
+    pub output: Option<PathBuf>,
+    /// `mp4-h264`, `mp4-h265`, `gif`, `webm` or `png-sequence`.
+    pub format: String,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub music: Option<PathBuf>,
+    pub music_gain: Option<f64>,
+    pub fast_copy: bool,
+    pub fps: Option<u32>,
+    pub start: Option<f64>,
+    pub end: Option<f64>,
+    pub keep_preroll: bool,
+    /// `None` follows `recording.trim_tail` from the config.
+    pub trim_tail: Option<bool>,
+    pub debug_overlay: bool,
+    pub resources: ExportResources,
+    pub strokes: bool,
+    pub no_reactions: bool,
+    pub gamepad_overlay: bool,
+    pub metrics_overlay: bool,
+    pub burn_clock: bool,
+    pub overscan: bool,
+    pub video_bitrate: Option<u32>,
+    pub target_size: Option<String>,
+    pub copy: bool,
+    /// Don't copy a GIF to the clipboard, as GIFs are by default.
+    pub no_copy: bool,
+    pub variants: Vec<AspectMode>,
+    pub waveform: Option<WaveformStyle>,
+    pub draft: Option<DraftOptions>,
+    pub metadata: ExportMetadata,
+}
+
+impl Default for ExportArgs {
+    fn default() -> Self {
+        Self {
+            output: None,
+            format: "mp4-h264".to_string(),
+            width: None,
+            height: None,
+            music: None,
+            music_gain: None,
+            fast_copy: false,
+            fps: None,
+            start: None,
+            end: None,
+            keep_preroll: false,
+            trim_tail: None,
+            debug_overlay: false,
+            resources: ExportResources::default(),
+            strokes: false,
+            no_reactions: false,
+            gamepad_overlay: false,
+            metrics_overlay: false,
+            burn_clock: false,
+            overscan: false,
+            video_bitrate: None,
+            target_size: None,
+            copy: false,
+            no_copy: false,
+            variants: Vec::new(),
+            waveform: None,
+            draft: None,
+            metadata: ExportMetadata::default(),
+        }
+    }
+}
+
+pub async fn run(path: PathBuf, args: ExportArgs) -> anyhow::Result<()> {
+    let ExportArgs {
+        output,
+        format,
+        width,
+        height,
+        music,
+        music_gain,
+        fast_copy,
+        fps,
+        start,
+        end,
+        keep_preroll,
+        trim_tail,
+        debug_overlay,
+        resources,
+        strokes,
+        no_reactions,
+        gamepad_overlay,
+        metrics_overlay,
+        burn_clock,
+        overscan,
+        video_bitrate,
+        target_size,
+        copy,
+        no_copy,
+        variants,
+        waveform,
+        draft,
+        metadata,
+    } = args;
     println!("Exporting project at: {}", path.display());
 
-    let project =
+    let mut project =
         LoadedProject::load(&path).map_err(|e| anyhow::anyhow!("Failed to load project: {e}"))?;
 
-    let output_path = output.unwrap_or_else(|| path.join("exports").join("output.mp4"));
+    if music.is_some() || music_gain.is_some() {
+        attach_music(&mut project, music.as_deref(), music_gain)?;
+    }
 
     let export_format = match format.as_str() {
         "mp4-h264" => ExportFormat::Mp4H264,
         "mp4-h265" => ExportFormat::Mp4H265,
         "gif" => ExportFormat::Gif,
         "webm" => ExportFormat::Webm,
+        "png-sequence" => ExportFormat::PngSequence,
         _ => {
             return Err(anyhow::anyhow!(
-                "Unknown format: {format}. Use: mp4-h264, mp4-h265, gif, webm"
+                "Unknown format: {format}. Use: mp4-h264, mp4-h265, gif, webm, png-sequence"
             ));
         }
     };
 
+    if let (Some(start), Some(end)) = (start, end) {
+        if end <= start {
+            return Err(anyhow::anyhow!(
+                "--end ({end}) must be after --start ({start})"
+            ));
+        }
+    }
+    let recorded_secs = project
+        .project
+        .tracks
+        .screen
+        .as_ref()
+        .map_or(0.0, |track| track.duration_secs);
+    if let Some(start) = start {
+        if recorded_secs > 0.0 && start >= recorded_secs {
+            return Err(anyhow::anyhow!(
+                "--start ({start}) must be before the end of the recording ({recorded_secs:.2}s)"
+            ));
+        }
+    }
+
+    let trim_tail = trim_tail.unwrap_or_else(|| AppConfig::load().recording.trim_tail);
+    let end = if trim_tail && !fast_copy && project.timeline.end_offset_secs.is_none() {
+        match detect_project_tail(&path) {
+            Ok(Some(tail)) if end.map_or(true, |end| tail.end_secs < end) => {
+                println!(
+                    "Trimming {:.1}s idle tail{}: export ends at {:.1}s. Keep it with --keep-tail \
+                     or pick the end with --end.",
+                    tail.trimmed_secs,
+                    if tail.reach {
+                        " (reaching for the stop button)"
+                    } else {
+                        ""
+                    },
+                    tail.end_secs
+                );
+                Some(tail.end_secs)
+            }
+            Ok(_) => end,
+            Err(e) => {
+                println!("Tail detection failed; exporting to the end: {e}");
+                end
+            }
+        }
+    } else {
+        end
+    };
+
+    let default_name = if fast_copy {
+        "clip.mp4"
+    } else if export_format.is_image_sequence() {
+        "frames"
+    } else if debug_overlay {
+        "debug.mp4"
+    } else if draft.is_some() {
+        "draft.mp4"
+    } else {
+        "output.mp4"
+    };
+    let output_path = output.unwrap_or_else(|| path.join("exports").join(default_name));
+
+    let (width, height) = project
+        .project
+        .recording
+        .export_size(export_format, width, height);
+
     let config = ExportConfig {
         format: export_format,
         width,
         height,
-        fps: project.project.recording.fps,
+        fps: fps.unwrap_or(project.project.recording.fps),
         video_bitrate_kbps: 8000,
         audio_bitrate_kbps: 192,
         aspect_mode: AspectMode::Landscape,
-        burn_subtitles: false,
+        burn_subtitles: project.project.export.burn_subtitles,
+        subtitle_style: project.project.export.subtitle_style.clone(),
         webcam: project.project.export.webcam.clone(),
         canvas: project.project.export.canvas.clone(),
+        strokes: StrokeOverlayConfig {
+            enabled: strokes || project.project.export.strokes.enabled,
+            ..project.project.export.strokes.clone()
+        },
+        reactions: ReactionOverlayConfig {
+            enabled: !no_reactions && project.project.export.reactions.enabled,
+            ..project.project.export.reactions.clone()
+        },
+        gamepad: GamepadOverlayConfig {
+            enabled: gamepad_overlay || project.project.export.gamepad.enabled,
+            ..project.project.export.gamepad.clone()
+        },
+        metrics: MetricsOverlayConfig {
+            enabled: metrics_overlay || project.project.export.metrics.enabled,
+            ..project.project.export.metrics.clone()
+        },
+        clock: ClockOverlayConfig {
+            enabled: burn_clock || project.project.export.clock.enabled,
+            ..project.project.export.clock.clone()
+        },
+        pip: project.project.export.pip.clone(),
+        waveform: WaveformConfig {
+            enabled: waveform.is_some() || project.project.export.waveform.enabled,
+            style: waveform.unwrap_or(project.project.export.waveform.style),
+            ..project.project.export.waveform.clone()
+        },
+        metadata: merge_metadata(metadata, &project.project.export.metadata),
+        overscan: OverscanConfig {
+            enabled: overscan || project.project.export.overscan.enabled,
+            ..project.project.export.overscan.clone()
+        },
     };
 
-    println!("  Output: {}", output_path.display());
-    println!("  Format: {:?}", export_format);
-    println!("  Resolution: {width}x{height}");
-
-    let job = ExportJob {
+    let encodes_video =
+        !fast_copy && !export_format.is_image_sequence() && export_format != ExportFormat::Gif;
+    if target_size.is_some() && !encodes_video {
+        return Err(anyhow::anyhow!(
+            "--target-size needs an mp4 or webm export without --fast-copy"
+        ));
+    }
+    let mut job = ExportJob {
         project_dir: path,
         output_path: output_path.clone(),
         config,
-        start_secs: None,
-        end_secs: None,
+        start_secs: start,
+        end_secs: end,
+        trim_preroll: !keep_preroll && AppConfig::load().recording.trim_preroll,
+        // Resolved above so the trimmed amount can be reported.
+        trim_tail: false,
+        debug_overlay,
+        resources,
+        fast_copy,
+        draft: None,
+    };
+    if let Some(options) = draft {
+        job = job.into_draft(options);
+    }
+
+    if !variants.is_empty() {
+        let jobs = variants
+            .iter()
+            .map(|&aspect| job.variant(aspect))
+            .collect::<Vec<_>>();
+        return run_variants(
+            &project,
+            jobs,
+            encodes_video.then_some((video_bitrate, target_size.as_deref())),
+        )
+        .await;
+    }
+
+    let bitrate_note = if encodes_video {
+        let (kbps, note) = resolve_video_bitrate(
+            &project,
+            &job.config,
+            start,
+            end,
+            video_bitrate,
+            target_size.as_deref(),
+        )?;
+        job.config.video_bitrate_kbps = kbps;
+        Some(note)
+    } else {
+        None
     };
 
+    println!("  Output: {}", output_path.display());
+    println!("  Format: {:?}", export_format);
+    if fast_copy {
+        println!("  Mode: fast copy (no compositing)");
+    } else if debug_overlay {
+        println!("  Mode: debug overlay (full screen, diagnostics drawn)");
+        println!("  Resolution: {width}x{height}");
+    } else if let Some(options) = job.draft {
+        println!(
+            "  Mode: draft ({} fps, {} preset{})",
+            job.config.fps,
+            DRAFT_PRESET,
+            if options.watermark {
+                ", watermarked"
+            } else {
+                ""
+            }
+        );
+        println!("  Resolution: {}x{}", job.config.width, job.config.height);
+    } else if job.config.waveform.enabled {
+        println!("  Mode: audio waveform ({:?})", job.config.waveform.style);
+        println!("  Resolution: {width}x{height}");
+    } else {
+        println!("  Resolution: {width}x{height}");
+    }
+    if let Some(note) = bitrate_note {
+        println!(
+            "  Video bitrate: {} kbps ({note})",
+            job.config.video_bitrate_kbps
+        );
+    }
+    if let Some(music) = &project.project.tracks.music {
+        println!("  Music: {} ({:.1} dB)", music.path, music.gain_db);
+    }
+
     let progress_cb: Box<dyn Fn(ExportProgress) + Send> = Box::new(|p| {
         print!(
             "\r  Progress: {:.1}% ({}/{} frames, ETA: {:.0}s)  ",
@@ -70,6 +385,20 @@ pub async fn run(
     match export_project(job, Some(progress_cb)).await {
         Ok(_) => {
             println!("\nExport complete: {}", output_path.display());
+            let copy = copy || (export_format == ExportFormat::Gif && !no_copy);
+            if copy && !export_format.is_image_sequence() {
+                match copy_file_to_clipboard(&output_path) {
+                    Ok(ClipboardContent::Image(mime)) => {
+                        println!("  Copied to clipboard as {mime}")
+                    }
+                    Ok(ClipboardContent::FileReference) => {
+                        println!("  Copied to clipboard as a file reference")
+                    }
+                    Err(e) => println!("  Clipboard copy failed: {e}"),
+                }
+            } else if copy {
+                println!("  Image sequences can't be copied to the clipboard");
+            }
         }
         Err(e) => {
             println!("\nExport failed: {e}");
@@ -78,3 +407,178 @@ pub async fn run(
 
     Ok(())
 }
+
+/// Render aspect variants back to back. `bitrate` holds the requested
+/// bitrate and target size when the format encodes video; each variant
+/// gets its own estimate for its size.
+async fn run_variants(
+    project: &LoadedProject,
+    mut jobs: Vec<ExportJob>,
+    bitrate: Option<(Option<u32>, Option<&str>)>,
+) -> anyhow::Result<()> {
+    println!("  Format: {:?}", jobs[0].config.format);
+    for job in &mut jobs {
+        let note = match bitrate {
+            Some((video_bitrate, target_size)) => {
+                let (kbps, note) = resolve_video_bitrate(
+                    project,
+                    &job.config,
+                    job.start_secs,
+                    job.end_secs,
+                    video_bitrate,
+                    target_size,
+                )?;
+                job.config.video_bitrate_kbps = kbps;
+                format!(", {kbps} kbps ({note})")
+            }
+            None => String::new(),
+        };
+        println!(
+            "  {}: {}x{}{note} -> {}",
+            job.config.aspect_mode.as_str(),
+            job.config.width,
+            job.config.height,
+            job.output_path.display()
+        );
+    }
+    if let Some(music) = &project.project.tracks.music {
+        println!("  Music: {} ({:.1} dB)", music.path, music.gain_db);
+    }
+
+    let progress_cb: VariantProgressCallback = std::sync::Arc::new(|aspect, p| {
+        print!(
+            "\r  Progress ({}): {:.1}% ({}/{} frames, ETA: {:.0}s)  ",
+            aspect.as_str(),
+            p.progress * 100.0,
+            p.frames_rendered,
+            p.total_frames,
+            p.eta_secs,
+        );
+    });
+
+    match export_variants(jobs, Some(progress_cb)).await {
+        Ok(outputs) => {
+            println!("\nExport complete:");
+            for output in outputs {
+                println!("  {}", output.display());
+            }
+        }
+        Err(e) => {
+            println!("\nExport failed: {e}");
+        }
+    }
+    Ok(())
+}
+
+/// Video bitrate for the export: the explicit one, the one solving for
+/// `target_size`, or an estimate from the content.
+/// The project's export metadata with the command-line values on top.
+/// Chapters given on the command line replace the project's.
+fn merge_metadata(overrides: ExportMetadata, project: &ExportMetadata) -> ExportMetadata {
+    ExportMetadata {
+        title: overrides.title.or_else(|| project.title.clone()),
+        author: overrides.author.or_else(|| project.author.clone()),
+        comment: overrides.comment.or_else(|| project.comment.clone()),
+        creation_time: overrides
+            .creation_time
+            .or_else(|| project.creation_time.clone()),
+        chapters: if overrides.chapters.is_empty() {
+            project.chapters.clone()
+        } else {
+            overrides.chapters
+        },
+    }
+}
+
+fn resolve_video_bitrate(
+    project: &LoadedProject,
+    config: &ExportConfig,
+    start: Option<f64>,
+    end: Option<f64>,
+    video_bitrate: Option<u32>,
+    target_size: Option<&str>,
+) -> anyhow::Result<(u32, String)> {
+    if let Some(kbps) = video_bitrate {
+        return Ok((kbps, "requested".to_string()));
+    }
+
+    let recorded_secs = project
+        .project
+        .tracks
+        .screen
+        .as_ref()
+        .map(|track| track.duration_secs)
+        .unwrap_or(0.0);
+    let start_secs = start.unwrap_or(0.0).max(0.0);
+    let end_secs = end
+        .unwrap_or(recorded_secs)
+        .min(recorded_secs.max(start_secs));
+
+    if let Some(raw) = target_size {
+        let tracks = &project.project.tracks;
+        let has_audio =
+            tracks.mic.is_some() || tracks.system_audio.is_some() || tracks.music.is_some();
+        let bytes = parse_size(raw)?;
+        let kbps = video_kbps_for_target_size(
+            bytes,
+            end_secs - start_secs,
+            export_audio_kbps(config, has_audio),
+        )?;
+        return Ok((kbps, format!("solved for {raw}")));
+    }
+
+    let complexity = probe_project_complexity(project, config, start_secs, end_secs)?;
+    let source = if complexity.sample_kbps.is_some() {
+        "estimated from sampled frames"
+    } else {
+        "estimated without frame samples"
+    };
+    Ok((
+        recommend_video_bitrate_kbps(&complexity, config),
+        format!("{source}, motion {:.2}", complexity.motion),
+    ))
+}
+
+/// Copy a music file into the project sources and record it in `tracks.music`.
+///
+/// With only `gain_db`, the existing music track is re-levelled in place.
+fn attach_music(
+    project: &mut LoadedProject,
+    music: Option<&Path>,
+    gain_db: Option<f64>,
+) -> anyhow::Result<()> {
+    if let Some(source) = music {
+        if !source.is_file() {
+            return Err(anyhow::anyhow!(
+                "Music file not found: {}",
+                source.display()
+            ));
+        }
+        let extension = source
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("audio");
+        let relative = format!("sources/music.{extension}");
+        std::fs::copy(source, project.root.join(&relative))
+            .map_err(|e| anyhow::anyhow!("Failed to copy music file: {e}"))?;
+
+        let previous = project.project.tracks.music.take();
+        project.project.tracks.music = Some(MusicTrack {
+            path: relative,
+            ..previous.unwrap_or_default()
+        });
+    }
+
+    let Some(track) = project.project.tracks.music.as_mut() else {
+        return Err(anyhow::anyhow!(
+            "--music-gain requires a music track; pass --music <file>"
+        ));
+    };
+    if let Some(gain_db) = gain_db {
+        track.gain_db = gain_db;
+    }
+
+    project
+        .save()
+        .map_err(|e| anyhow::anyhow!("Failed to save project: {e}"))
+}
@@ -2,7 +2,9 @@
 
 use std::path::PathBuf;
 
+use grabme_project_model::project::TrackRef;
 use grabme_project_model::LoadedProject;
+use grabme_render_engine::probe::{probe_media, track_mismatches, MediaInfo, TrackMediaKind};
 
 pub fn run(path: PathBuf) -> anyhow::Result<()> {
     let project =
@@ -24,6 +26,9 @@ pub fn run(path: PathBuf) -> anyhow::Result<()> {
     println!("  Scale factor: {}", p.recording.scale_factor);
     println!("  Display server: {:?}", p.recording.display_server);
     println!("  Cursor hidden: {}", p.recording.cursor_hidden);
+    if let Some(mode) = p.recording.cursor_mode {
+        println!("  Cursor mode: {mode:?}");
+    }
     println!("  Monitor index: {}", p.recording.monitor_index);
     println!(
         "  Monitor geometry: {}x{} @ ({}, {})",
@@ -58,6 +63,8 @@ pub fn run(path: PathBuf) -> anyhow::Result<()> {
     }
     println!();
 
+    print_media_section(&path, &project);
+
     println!("Timeline:");
     println!("  Keyframes: {}", project.timeline.keyframes.len());
     println!("  Effects: {}", project.timeline.effects.len());
@@ -77,3 +84,79 @@ pub fn run(path: PathBuf) -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// Probe each track's file and flag where it disagrees with `project.json`.
+fn print_media_section(root: &std::path::Path, project: &LoadedProject) {
+    let p = &project.project;
+    let tracks: Vec<(&str, TrackMediaKind, &TrackRef)> = [
+        ("Screen", TrackMediaKind::Screen, p.tracks.screen.as_ref()),
+        ("Webcam", TrackMediaKind::Webcam, p.tracks.webcam.as_ref()),
+        ("Mic", TrackMediaKind::Audio, p.tracks.mic.as_ref()),
+        (
+            "System audio",
+            TrackMediaKind::Audio,
+            p.tracks.system_audio.as_ref(),
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(label, kind, track)| track.map(|t| (label, kind, t)))
+    .collect();
+
+    if tracks.is_empty() {
+        return;
+    }
+
+    println!("Media (ffprobe):");
+    let mut mismatches = 0usize;
+    for (label, kind, track) in tracks {
+        let file = root.join(&track.path);
+        if !file.exists() {
+            println!("  {label}: missing file {}", file.display());
+            mismatches += 1;
+            continue;
+        }
+        let info = match probe_media(&file) {
+            Ok(info) => info,
+            Err(e) => {
+                println!("  {label}: probe failed ({e})");
+                continue;
+            }
+        };
+        println!("  {label}: {}", describe_media(&info));
+        for issue in track_mismatches(kind, track, &info, &p.recording) {
+            println!("    ! {issue}");
+            mismatches += 1;
+        }
+    }
+    if mismatches > 0 {
+        println!("  {mismatches} mismatch(es) between metadata and media");
+    }
+    println!();
+}
+
+fn describe_media(info: &MediaInfo) -> String {
+    let mut parts = Vec::new();
+    if let Some(video) = &info.video {
+        let fps = video
+            .fps
+            .map(|fps| format!(" @ {fps:.2}fps"))
+            .unwrap_or_default();
+        parts.push(format!(
+            "{} {}x{}{fps}",
+            video.codec, video.width, video.height
+        ));
+    }
+    if let Some(audio) = &info.audio {
+        parts.push(format!(
+            "{} {}ch {}Hz",
+            audio.codec, audio.channels, audio.sample_rate
+        ));
+    }
+    if let Some(duration) = info.duration_secs {
+        parts.push(format!("{duration:.2}s"));
+    }
+    if let Some(kbps) = info.bit_rate_kbps {
+        parts.push(format!("{kbps} kbps"));
+    }
+    parts.join(", ")
+}
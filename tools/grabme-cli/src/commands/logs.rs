@@ -0,0 +1,104 @@
+//! Show and follow GrabMe log files.
+
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use grabme_common::config::{log_file_path, AppConfig};
+use grabme_common::logging::{session_log_path, LogRecord};
+use tracing::Level;
+
+/// How often a followed log is checked for new lines.
+const FOLLOW_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+pub async fn run(
+    session: Option<PathBuf>,
+    follow: bool,
+    level: Level,
+    json: bool,
+    lines: usize,
+) -> anyhow::Result<()> {
+    let path = match session {
+        Some(path) if path.is_dir() => session_log_path(&path),
+        Some(path) => path,
+        None => AppConfig::load().logging.file.unwrap_or_else(log_file_path),
+    };
+    if !path.exists() && !follow {
+        return Err(anyhow::anyhow!("No log at {} yet", path.display()));
+    }
+    eprintln!("==> {} <==", path.display());
+
+    let filter = LineFilter { level, json };
+    let mut offset = 0u64;
+    let mut pending = String::new();
+    if path.exists() {
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {e}", path.display()))?;
+        offset = content.len() as u64;
+        let shown: Vec<String> = content.lines().filter_map(|l| filter.apply(l)).collect();
+        for line in &shown[shown.len().saturating_sub(lines)..] {
+            println!("{line}");
+        }
+    }
+
+    if !follow {
+        return Ok(());
+    }
+    loop {
+        tokio::time::sleep(FOLLOW_POLL_INTERVAL).await;
+        let Some(chunk) = read_from(&path, &mut offset) else {
+            continue;
+        };
+        pending.push_str(&chunk);
+        // Keep a partially written last line for the next poll.
+        let complete = pending.rfind('\n').map_or(0, |idx| idx + 1);
+        for line in pending[..complete].lines() {
+            if let Some(line) = filter.apply(line) {
+                println!("{line}");
+            }
+        }
+        pending.drain(..complete);
+    }
+}
+
+/// Level filtering and output format for log lines.
+struct LineFilter {
+    level: Level,
+    json: bool,
+}
+
+impl LineFilter {
+    /// The line to print, or `None` when it is filtered out. Lines that are
+    /// not JSON records are only shown when nothing is filtered.
+    fn apply(&self, line: &str) -> Option<String> {
+        match LogRecord::parse(line) {
+            Some(record) if record.at_least(self.level) => Some(if self.json {
+                line.to_string()
+            } else {
+                record.pretty()
+            }),
+            Some(_) => None,
+            None => {
+                (self.level == Level::TRACE && !line.trim().is_empty()).then(|| line.to_string())
+            }
+        }
+    }
+}
+
+/// Read what was appended to `path` since `offset`. A file that shrank was
+/// rotated or truncated and is read again from the start.
+fn read_from(path: &Path, offset: &mut u64) -> Option<String> {
+    let mut file = std::fs::File::open(path).ok()?;
+    let len = file.metadata().ok()?.len();
+    if len < *offset {
+        *offset = 0;
+    }
+    if len == *offset {
+        return None;
+    }
+    file.seek(SeekFrom::Start(*offset)).ok()?;
+    let mut buf = Vec::new();
+    file.read_to_end(&mut buf).ok()?;
+    *offset += buf.len() as u64;
+    Some(String::from_utf8_lossy(&buf).into_owned())
+}
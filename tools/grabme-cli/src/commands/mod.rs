@@ -1,7 +1,18 @@
 pub mod analyze;
+pub mod audit_cursor;
 pub mod check;
+pub mod doctor;
 pub mod export;
 pub mod info;
 pub mod init;
+pub mod logs;
 pub mod record;
+pub mod review;
+pub mod schema;
+pub mod script;
+pub mod self_update;
+pub mod slim;
+pub mod status;
+pub mod stills;
+pub mod studio;
 pub mod validate;
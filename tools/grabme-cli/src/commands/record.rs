@@ -1,25 +1,115 @@
 //! Start a recording session.
 
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::io::{BufRead, IsTerminal};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
+use grabme_capture_engine::storage::SLOW_SYNC;
+use grabme_capture_engine::suspend::{SuspendDetector, SUSPEND_WAIT};
 use grabme_capture_engine::{
-    list_monitors, AudioCaptureConfig, CaptureMode, CaptureSession, ScreenCaptureConfig,
-    SessionConfig,
+    default_monitor, list_monitors, plan_frame_pacing, split_monitors, AudioCaptureConfig,
+    CaptureMode, CaptureSession, LockResponse, PipSource, RecordingPreset, ScreenCaptureConfig,
+    SessionConfig, VirtualCameraConfig, WebcamFormatRequest,
 };
+use grabme_common::clock::ClockSource;
+use grabme_common::config::{AppConfig, LockAction, RecordingDefaults, ResumeAction};
+use grabme_common::error::GrabmeResult;
+use grabme_input_tracker::inject::detect_injector;
+use grabme_input_tracker::script::InputScript;
+use grabme_platform_core::coords::PhysicalRect;
+use grabme_platform_linux::{
+    detect_display_server, pick_window, set_display_target, DisplayServer, DisplayTarget,
+};
+use grabme_project_model::project::Project;
+use grabme_project_model::LoadedProject;
+use grabme_render_engine::webcam_thumbs::{
+    generate_webcam_thumbnails, DEFAULT_WEBCAM_THUMB_INTERVAL_SECS,
+};
+
+/// How often lock and suspend changes are checked while recording.
+const SYSTEM_EVENT_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(250);
+
+/// Parse a `--split-monitor` value of the form `NAME=N`.
+pub fn parse_monitor_split(value: &str) -> Result<(String, u32), String> {
+    let (name, parts) = value
+        .rsplit_once('=')
+        .ok_or_else(|| format!("expected NAME=N, got `{value}`"))?;
+    let parts = parts
+        .parse::<u32>()
+        .map_err(|_| format!("`{parts}` is not a number of regions"))?;
+    if name.is_empty() || parts == 0 {
+        return Err(format!(
+            "expected a monitor name and at least 1 region, got `{value}`"
+        ));
+    }
+    Ok((name.to_string(), parts))
+}
+
+/// Parse a `--webcam-size` value: `WIDTHxHEIGHT`.
+pub fn parse_webcam_size(value: &str) -> Result<(u32, u32), String> {
+    let (width, height) = value
+        .split_once(['x', 'X'])
+        .ok_or_else(|| format!("expected WIDTHxHEIGHT, got `{value}`"))?;
+    match (width.trim().parse::<u32>(), height.trim().parse::<u32>()) {
+        (Ok(width), Ok(height)) if width > 0 && height > 0 => Ok((width, height)),
+        _ => Err(format!("expected WIDTHxHEIGHT, got `{value}`")),
+    }
+}
+
+/// How the window to record is chosen, if any.
+pub enum WindowSelection {
+    None,
+    Id(String),
+    Pick,
+}
+
+impl WindowSelection {
+    pub fn from_flags(window: Option<String>, pick: bool) -> Self {
+        match (window, pick) {
+            (Some(id), _) => Self::Id(id),
+            (None, true) => Self::Pick,
+            (None, false) => Self::None,
+        }
+    }
+}
 
 #[allow(clippy::too_many_arguments)]
 pub async fn run(
     name: String,
     output: PathBuf,
-    fps: u32,
-    monitor: usize,
-    mic: bool,
-    system_audio: bool,
-    webcam: bool,
+    fps: Option<u32>,
+    mut monitor: Option<usize>,
+    mut mic: bool,
+    mut system_audio: bool,
+    mut webcam: bool,
+    webcam_size: Option<(u32, u32)>,
+    webcam_fps: Option<u32>,
     list_only: bool,
+    monitor_splits: BTreeMap<String, u32>,
+    window: WindowSelection,
+    stills_every: Option<f64>,
+    clock_source: ClockSource,
+    display: DisplayTarget,
+    mut auto_fps: bool,
+    gamepad: bool,
+    pip: Option<PipSource>,
+    frame_times: bool,
+    system_metrics: bool,
+    on_lock: LockAction,
+    on_resume: ResumeAction,
+    virtual_camera: Option<VirtualCameraConfig>,
+    spool_dir: Option<PathBuf>,
+    like: Option<PathBuf>,
+    script: Option<InputScript>,
 ) -> anyhow::Result<()> {
+    // Apply before listing monitors so `--list-monitors --display :99` shows
+    // that display's layout.
+    set_display_target(display.clone());
+
     // Detect monitors first so we can print the list and validate the index.
-    let monitors = list_monitors().unwrap_or_default();
+    let monitors = split_monitors(&list_monitors().unwrap_or_default(), &monitor_splits);
 
     if list_only {
         println!("Available monitors:");
@@ -41,6 +131,58 @@ pub async fn run(
         return Ok(());
     }
 
+    let app_config = AppConfig::load();
+    let memory = app_config.memory.profile();
+    let defaults = app_config.recording;
+    let preset = like
+        .as_deref()
+        .map(load_like_project)
+        .transpose()?
+        .map(|project| RecordingPreset::from_project(&project, &monitors));
+    if let Some(preset) = &preset {
+        if monitor.is_none() && matches!(window, WindowSelection::None) {
+            if preset.window {
+                println!(
+                    "note: the --like project followed a window; recording its monitor. \
+                     Use --window or --pick-window to follow one again."
+                );
+            }
+            monitor = Some(preset.monitor_index.ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Monitor {} from the --like project is not connected. \
+                     Pick one with --monitor (see `grabme record --list-monitors`).",
+                    preset.monitor_name
+                )
+            })?);
+        }
+        mic &= preset.mic;
+        system_audio &= preset.system_audio;
+        webcam |= preset.webcam;
+        auto_fps |= preset.auto_fps;
+    }
+    let fps = fps
+        .or(preset.as_ref().map(|preset| preset.fps))
+        .unwrap_or(defaults.fps);
+    let mut webcam_format = WebcamFormatRequest::from_defaults(&defaults);
+    if let Some(format) = preset.as_ref().and_then(|preset| preset.webcam_format) {
+        webcam_format = WebcamFormatRequest {
+            size: Some((format.width, format.height)),
+            fps: Some(format.fps),
+        };
+    }
+    webcam_format.size = webcam_size.or(webcam_format.size);
+    webcam_format.fps = webcam_fps.or(webcam_format.fps);
+
+    // Only a monitor the focused window was actually found on is labelled
+    // as such; a failed lookup falls back to the primary monitor.
+    let (monitor, monitor_from_focus) = match monitor {
+        Some(index) => (index, false),
+        None => {
+            let picked = default_monitor(&monitors);
+            (picked.index, picked.from_focus)
+        }
+    };
+
     // Print monitor list so the user can see which index maps to which screen.
     println!("Available monitors:");
     if monitors.is_empty() {
@@ -74,49 +216,418 @@ pub async fn run(
         );
     }
 
+    if let Some(PipSource::Monitor(pip_monitor)) = &pip {
+        if !monitors.is_empty() && *pip_monitor >= monitors.len() {
+            anyhow::bail!(
+                "Picture-in-picture monitor index {} is out of range. Available monitors: 0..{}.",
+                pip_monitor,
+                monitors.len() - 1
+            );
+        }
+    }
+
+    let mode = match window {
+        WindowSelection::None => CaptureMode::FullScreen {
+            monitor_index: monitor,
+        },
+        WindowSelection::Id(window_id) => CaptureMode::Window { window_id },
+        WindowSelection::Pick => CaptureMode::Window {
+            window_id: pick_window_id()?,
+        },
+    };
+
+    let selected_monitor = match &mode {
+        CaptureMode::FullScreen { monitor_index } => monitors.get(*monitor_index),
+        _ => None,
+    };
+    let pacing = plan_frame_pacing(&monitors, selected_monitor, fps, auto_fps);
+    // Scripts replay onto the recorded monitor, or onto their own screen at
+    // the desktop origin when no monitor was detected.
+    let script_target = script.as_ref().map(|script| match selected_monitor {
+        Some(m) => PhysicalRect::new(m.x, m.y, m.width, m.height),
+        None => PhysicalRect::new(0, 0, script.screen.width, script.screen.height),
+    });
+    for warning in &pacing.warnings {
+        println!("warning: {warning}");
+    }
+
     println!();
     println!("Starting recording session: {name}");
     println!("  Output: {}", output.display());
-    println!("  FPS: {fps}");
-    println!("  Monitor: {monitor}");
+    if pacing.fps != fps {
+        println!("  FPS: {} (requested {fps})", pacing.fps);
+    } else {
+        println!("  FPS: {fps}");
+    }
+    match &mode {
+        CaptureMode::Window { window_id } => println!("  Window: {window_id}"),
+        _ if monitor_from_focus => println!("  Monitor: {monitor} (focused window)"),
+        _ => println!("  Monitor: {monitor}"),
+    }
     println!("  Mic: {mic}");
     println!("  System audio: {system_audio}");
-    println!("  Webcam: {webcam}");
+    match (webcam, webcam_format.size) {
+        (true, Some((width, height))) => println!("  Webcam: {width}x{height}"),
+        _ => println!("  Webcam: {webcam}"),
+    }
+    if gamepad {
+        println!("  Gamepad: true");
+    }
+    match &pip {
+        Some(PipSource::Monitor(index)) => println!("  Picture-in-picture: monitor {index}"),
+        Some(PipSource::Device(device)) => println!("  Picture-in-picture: {device}"),
+        None => {}
+    }
+    println!("  Clock: {}", clock_source.as_str());
+    if frame_times {
+        println!("  Frame times: meta/frame-times.jsonl");
+    }
+    if system_metrics {
+        println!("  System metrics: meta/metrics.jsonl");
+    }
+    if virtual_camera.is_some() && !memory.live_previews {
+        println!("  Virtual camera: off in low-memory mode");
+    } else if let Some(virtual_camera) = &virtual_camera {
+        println!(
+            "  Virtual camera: {} ({}x{}, {}x zoom)",
+            virtual_camera
+                .device
+                .as_deref()
+                .unwrap_or("first v4l2loopback device"),
+            virtual_camera.width,
+            virtual_camera.height,
+            virtual_camera.zoom
+        );
+    }
+    match on_lock {
+        LockAction::Stop => println!("  On lock/suspend: stop"),
+        LockAction::Pause => println!("  On lock/suspend: pause"),
+        LockAction::Ignore => {}
+    }
+    if on_lock != LockAction::Ignore {
+        match on_resume {
+            ResumeAction::Prompt => println!("  After suspend: ask to record a new segment"),
+            ResumeAction::Auto => println!("  After suspend: record a new segment"),
+            ResumeAction::Off => {}
+        }
+    }
+    if let Some(display) = &display.x11_display {
+        println!("  Display: {display}");
+    }
+    if memory.is_low() {
+        println!("  Low-memory mode: smaller capture and event buffers, no live previews");
+    }
     println!();
 
     let config = SessionConfig {
         name,
         output_dir: output,
         screen: ScreenCaptureConfig {
-            mode: CaptureMode::FullScreen {
-                monitor_index: monitor,
-            },
+            mode,
             hide_cursor: true,
+            cursor_mode: None,
+            monitor_splits,
         },
         audio: AudioCaptureConfig {
             mic,
             system: system_audio,
             app_isolation: None,
-            sample_rate: 48000,
+            sample_rate: defaults.audio_sample_rate,
         },
         webcam,
+        webcam_format,
         fps,
         pointer_sample_rate_hz: 60,
+        clock_source,
+        display,
+        auto_adjust_fps: auto_fps,
+        gamepad,
+        pip,
+        frame_times,
+        system_metrics,
+        on_lock,
+        virtual_camera,
+        spool_dir,
+        memory,
     };
 
-    let mut session = CaptureSession::new(config);
+    let mut config = config;
+    // A suspend cuts a script short; its replay doesn't carry on.
+    let on_resume = if script.is_some() {
+        ResumeAction::Off
+    } else {
+        on_resume
+    };
+    let mut script = script;
 
-    println!("Press Ctrl+C to stop recording...");
+    let mut reaction_lines =
+        (std::io::stdin().is_terminal() && !defaults.reactions.is_empty()).then(spawn_stdin_lines);
+
+    // Check for an injector before recording anything.
+    let mut injector = script.as_ref().map(|_| detect_injector()).transpose()?;
+
+    if script.is_some() {
+        println!("Recording stops when the script ends (Ctrl+C stops early)...");
+    } else {
+        println!("Press Ctrl+C to stop recording...");
+    }
+    if reaction_lines.is_some() {
+        print_reaction_hotkeys(&defaults);
+    }
     println!();
 
-    session.start().await?;
+    loop {
+        let mut session = CaptureSession::new(config.clone());
+        session.start().await?;
 
-    // Wait for Ctrl+C
-    tokio::signal::ctrl_c().await?;
+        let stop_script = Arc::new(AtomicBool::new(false));
+        let mut replay = script.take().zip(script_target).zip(injector.take()).map(
+            |((script, target), mut injector)| {
+                let stop = Arc::clone(&stop_script);
+                tokio::task::spawn_blocking(move || script.play(injector.as_mut(), target, &stop))
+            },
+        );
 
-    println!();
-    let project_path = session.stop().await?;
-    println!("Recording saved to: {}", project_path.display());
+        // Wait for Ctrl+C, a screen lock or the end of the script, dropping
+        // reactions as their numbers are entered.
+        let mut suspended = None;
+        let mut system_poll = tokio::time::interval(SYSTEM_EVENT_POLL_INTERVAL);
+        loop {
+            tokio::select! {
+                result = tokio::signal::ctrl_c() => {
+                    result?;
+                    break;
+                }
+                result = replay_finished(&mut replay) => {
+                    replay = None;
+                    match result {
+                        Ok(Ok(())) => println!("Script finished; stopping."),
+                        Ok(Err(e)) => println!("Script failed: {e}; stopping."),
+                        Err(e) => println!("Script replay panicked: {e}; stopping."),
+                    }
+                    break;
+                }
+                Some(line) = next_line(&mut reaction_lines) => {
+                    let emoji = line
+                        .trim()
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|n| defaults.reaction_for_hotkey(n));
+                    match emoji {
+                        Some(emoji) => match session.add_reaction(emoji) {
+                            Ok(()) => println!("  Reaction {emoji}"),
+                            Err(e) => println!("  Reaction failed: {e}"),
+                        },
+                        None => print_reaction_hotkeys(&defaults),
+                    }
+                }
+                _ = system_poll.tick() => match session.poll_system_events() {
+                    Some(LockResponse::Stop) => {
+                        println!("Screen locked; stopping.");
+                        break;
+                    }
+                    Some(LockResponse::Suspend) => {
+                        println!("System suspending; saving the recording.");
+                        suspended = Some((LockResponse::Suspend, SuspendDetector::new()));
+                        break;
+                    }
+                    Some(LockResponse::Slept) => {
+                        println!("System slept while recording; saving what came before.");
+                        suspended = Some((LockResponse::Slept, SuspendDetector::new()));
+                        break;
+                    }
+                    Some(LockResponse::Pause) => println!("  Paused while the screen is locked"),
+                    Some(LockResponse::Resume) => println!("  Resumed"),
+                    None => {}
+                },
+            }
+        }
+
+        if let Some(replay) = replay {
+            stop_script.store(true, Ordering::Relaxed);
+            match replay.await {
+                Ok(Err(e)) => println!("Script failed: {e}"),
+                Err(e) => println!("Script replay panicked: {e}"),
+                Ok(Ok(())) => {}
+            }
+        }
+
+        println!();
+        let project_path = session.stop().await?;
+        println!("Recording saved to: {}", project_path.display());
+        if let Some(stats) = session.input_stats() {
+            println!("  Input events: {}", stats.events_written);
+            if stats.had_backpressure() {
+                println!(
+                "  Event writer fell behind: {} pointer moves coalesced, {} waits (peak queue {})",
+                stats.pointer_coalesced, stats.backpressure_waits, stats.peak_queue_depth
+            );
+            }
+        }
+        if let Some(health) = session.io_health().filter(|health| health.is_degraded()) {
+            println!(
+            "  Output drive fell behind: {} of {} syncs over {} ms (worst {} ms, {:.1} MB/s written)",
+            health.slow_samples,
+            health.samples,
+            SLOW_SYNC.as_millis(),
+            health.worst_sync_ms,
+            health.write_mb_per_sec
+        );
+            println!("  Record to a local drive with --spool-dir to avoid dropped frames.");
+        }
+
+        if webcam {
+            match generate_webcam_thumbnails(&project_path, DEFAULT_WEBCAM_THUMB_INTERVAL_SECS) {
+                Ok(thumbs) if !thumbs.is_empty() => {
+                    println!("Generated {} webcam thumbnails", thumbs.len())
+                }
+                Ok(_) => {}
+                Err(e) => println!("Webcam thumbnail generation failed: {e}"),
+            }
+        }
+
+        if let Some(secs) = stills_every {
+            println!();
+            if let Err(e) = super::stills::run(project_path, Some(secs), false, None) {
+                println!("Still extraction failed: {e}");
+            }
+        }
+
+        let Some((response, detector)) = suspended else {
+            return Ok(());
+        };
+        if !resume_after_suspend(response, detector, on_resume, &mut reaction_lines).await? {
+            return Ok(());
+        }
+        config = config.next_segment();
+        println!();
+        println!("Recording the next segment as {}", config.name);
+    }
+}
+
+/// Whether to record a new segment after a suspend stopped the last one,
+/// once the system is awake again.
+async fn resume_after_suspend(
+    response: LockResponse,
+    mut detector: SuspendDetector,
+    on_resume: ResumeAction,
+    lines: &mut Option<tokio::sync::mpsc::UnboundedReceiver<String>>,
+) -> anyhow::Result<bool> {
+    if on_resume == ResumeAction::Off {
+        return Ok(false);
+    }
+    if response == LockResponse::Suspend {
+        // Sleep comes a few seconds after the warning, or not at all if it
+        // was cancelled.
+        println!("Waiting for the system to wake (Ctrl+C quits)...");
+        let deadline = std::time::Instant::now() + SUSPEND_WAIT;
+        let mut poll = tokio::time::interval(SYSTEM_EVENT_POLL_INTERVAL);
+        while std::time::Instant::now() < deadline {
+            tokio::select! {
+                result = tokio::signal::ctrl_c() => {
+                    result?;
+                    return Ok(false);
+                }
+                _ = poll.tick() => {
+                    if detector.check().is_some() {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+    if on_resume == ResumeAction::Auto {
+        return Ok(true);
+    }
+
+    if lines.is_none() && std::io::stdin().is_terminal() {
+        *lines = Some(spawn_stdin_lines());
+    }
+    if lines.is_none() {
+        println!("Not resuming without a terminal to ask on; `--on-resume auto` resumes unasked.");
+        return Ok(false);
+    }
+    print!("Resume recording in a new segment? [Y/n] ");
+    std::io::Write::flush(&mut std::io::stdout())?;
+    tokio::select! {
+        result = tokio::signal::ctrl_c() => {
+            result?;
+            println!();
+            Ok(false)
+        }
+        line = next_line(lines) => Ok(line.is_some_and(|line| {
+            matches!(line.trim().to_ascii_lowercase().as_str(), "" | "y" | "yes")
+        })),
+    }
+}
+
+/// Project to take `--like` settings from.
+fn load_like_project(path: &Path) -> anyhow::Result<Project> {
+    let loaded = LoadedProject::load(path)
+        .map_err(|e| anyhow::anyhow!("Failed to load --like project: {e}"))?;
+    println!("Using the recording settings of {}", path.display());
+    Ok(loaded.project)
+}
+
+/// Next line from `lines`, or never when stdin isn't read.
+async fn next_line(
+    lines: &mut Option<tokio::sync::mpsc::UnboundedReceiver<String>>,
+) -> Option<String> {
+    match lines {
+        Some(lines) => lines.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Result of the script replay, or never when no script runs.
+async fn replay_finished(
+    replay: &mut Option<tokio::task::JoinHandle<GrabmeResult<()>>>,
+) -> Result<GrabmeResult<()>, tokio::task::JoinError> {
+    match replay {
+        Some(replay) => replay.await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Lines typed on stdin. Read on a plain thread so a pending read never
+/// holds up exit.
+fn spawn_stdin_lines() -> tokio::sync::mpsc::UnboundedReceiver<String> {
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+    std::thread::spawn(move || {
+        for line in std::io::stdin().lock().lines() {
+            let Ok(line) = line else { break };
+            if tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    rx
+}
+
+fn print_reaction_hotkeys(defaults: &RecordingDefaults) {
+    let hotkeys: Vec<String> = (1..=9)
+        .filter_map(|n| Some(format!("{n} {}", defaults.reaction_for_hotkey(n)?)))
+        .collect();
+    println!(
+        "Type a number and Enter to drop a reaction: {}",
+        hotkeys.join("  ")
+    );
+}
+
+/// Ask the user for a window. On Wayland the portal dialog does the picking
+/// once capture starts, so only X11 needs an id up front.
+fn pick_window_id() -> anyhow::Result<String> {
+    if detect_display_server() != DisplayServer::X11 {
+        println!("The screen-share dialog will ask which window to record.");
+        return Ok("portal".to_string());
+    }
 
-    Ok(())
+    println!("Click the window you want to record...");
+    let (window_id, geometry) =
+        pick_window().map_err(|e| anyhow::anyhow!("Failed to pick window: {e}"))?;
+    println!(
+        "  Selected {window_id} — {}x{} at ({},{})",
+        geometry.width, geometry.height, geometry.x, geometry.y
+    );
+    Ok(window_id)
 }
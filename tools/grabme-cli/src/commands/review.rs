@@ -0,0 +1,443 @@
+//! Share a draft export for review and collect timestamped comments.
+//!
+//! `grabme review` renders a watermarked draft and serves it with a small
+//! review page. Comments left on the page are saved to `meta/comments.json`
+//! with the timeline time they refer to, where the desktop editor shows them
+//! as markers. The server only needs the standard library: it answers one
+//! request per connection, which is all a single page and a video need.
+
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use grabme_common::config::AppConfig;
+use grabme_project_model::comments::{ReviewComment, ReviewComments};
+use grabme_project_model::LoadedProject;
+use grabme_render_engine::draft::DraftOptions;
+use grabme_render_engine::retime::RetimePlan;
+use serde::Deserialize;
+
+use super::export::ExportArgs;
+
+const REVIEW_PAGE: &str = include_str!("review.html");
+
+/// Longest comment body accepted, in bytes.
+const MAX_COMMENT_BYTES: usize = 16 * 1024;
+
+pub async fn run(path: PathBuf, listen: SocketAddr, reuse: bool) -> anyhow::Result<()> {
+    check_listen_allowed(listen, AppConfig::load().network.offline)?;
+    let project =
+        LoadedProject::load(&path).map_err(|e| anyhow::anyhow!("Failed to load project: {e}"))?;
+    let draft_path = path.join("exports").join("draft.mp4");
+
+    if reuse && draft_path.exists() {
+        println!("Reusing draft: {}", draft_path.display());
+    } else {
+        // A failed export only reports the error, so make sure a stale
+        // draft isn't shared in its place.
+        if draft_path.exists() {
+            std::fs::remove_file(&draft_path)?;
+        }
+        // Pre-roll is only trimmed at an explicit start offset, which
+        // `DraftTiming` accounts for.
+        super::export::run(
+            path.clone(),
+            ExportArgs {
+                keep_preroll: true,
+                no_copy: true,
+                draft: Some(DraftOptions { watermark: true }),
+                ..ExportArgs::default()
+            },
+        )
+        .await?;
+        if !draft_path.exists() {
+            return Err(anyhow::anyhow!("Draft export failed; nothing to review"));
+        }
+    }
+
+    let listener = TcpListener::bind(listen)
+        .map_err(|e| anyhow::anyhow!("Failed to listen on {listen}: {e}"))?;
+    let local = listener.local_addr()?;
+    let server = Arc::new(ReviewServer {
+        project_dir: path,
+        draft_path,
+        timing: DraftTiming::for_project(&project),
+        comments: Mutex::new(()),
+    });
+
+    println!();
+    println!("Review link: http://{}/", display_addr(local));
+    if local.ip().is_loopback() {
+        println!(
+            "  Only this machine can open it; use --listen 0.0.0.0:{} to share it on the network",
+            local.port()
+        );
+    }
+    println!("  Comments are saved to meta/comments.json");
+    println!("Press Ctrl+C to stop sharing.");
+
+    tokio::task::spawn_blocking(move || {
+        for stream in listener.incoming() {
+            let Ok(stream) = stream else {
+                continue;
+            };
+            let server = Arc::clone(&server);
+            std::thread::spawn(move || {
+                if let Err(e) = server.handle(stream) {
+                    tracing::debug!(error = %e, "Review request failed");
+                }
+            });
+        }
+    })
+    .await?;
+    Ok(())
+}
+
+/// Offline machines only share on loopback: serving the page on another
+/// interface opens it to the network.
+fn check_listen_allowed(listen: SocketAddr, offline: bool) -> anyhow::Result<()> {
+    if offline && !listen.ip().is_loopback() {
+        return Err(anyhow::anyhow!(
+            "Network access is disabled (network.offline in the config); \
+             review can only listen on a loopback address such as 127.0.0.1"
+        ));
+    }
+    Ok(())
+}
+
+/// Host part of the link: the machine's name when listening on all
+/// interfaces, so the link works from another computer.
+fn display_addr(addr: SocketAddr) -> String {
+    if addr.ip().is_unspecified() {
+        let host = std::fs::read_to_string("/etc/hostname")
+            .ok()
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| "localhost".to_string());
+        format!("{host}:{}", addr.port())
+    } else {
+        addr.to_string()
+    }
+}
+
+/// Maps positions in the draft back to timeline time. The draft starts at
+/// the timeline's start offset and is stretched by its speed segments.
+#[derive(Debug, Clone)]
+struct DraftTiming {
+    start_secs: f64,
+    retime: Option<RetimePlan>,
+}
+
+impl DraftTiming {
+    fn for_project(project: &LoadedProject) -> Self {
+        Self {
+            start_secs: project.timeline.start_offset_secs.unwrap_or(0.0).max(0.0),
+            retime: RetimePlan::new(&project.timeline.speed_segments, f64::INFINITY),
+        }
+    }
+
+    fn timeline_secs(&self, video_secs: f64) -> f64 {
+        let video_secs = video_secs.max(0.0);
+        match &self.retime {
+            Some(plan) => plan.source_secs(plan.output_secs(self.start_secs) + video_secs),
+            None => self.start_secs + video_secs,
+        }
+    }
+}
+
+/// A comment as posted by the review page.
+#[derive(Debug, Deserialize)]
+struct NewComment {
+    video_secs: f64,
+    #[serde(default)]
+    author: String,
+    text: String,
+}
+
+struct ReviewServer {
+    project_dir: PathBuf,
+    draft_path: PathBuf,
+    timing: DraftTiming,
+    /// Serialises read-modify-write of the comments file.
+    comments: Mutex<()>,
+}
+
+/// The parts of an HTTP request the server looks at.
+#[derive(Debug, PartialEq)]
+struct Request {
+    method: String,
+    path: String,
+    range: Option<String>,
+    body: Vec<u8>,
+}
+
+impl ReviewServer {
+    fn handle(&self, stream: TcpStream) -> std::io::Result<()> {
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut stream = stream;
+        let Some(request) = read_request(&mut reader)? else {
+            return respond(&mut stream, 400, "text/plain", b"Bad request");
+        };
+
+        match (request.method.as_str(), request.path.as_str()) {
+            ("GET", "/") => respond(
+                &mut stream,
+                200,
+                "text/html; charset=utf-8",
+                REVIEW_PAGE.as_bytes(),
+            ),
+            ("GET", "/video") => serve_video(&mut stream, &self.draft_path, request.range),
+            ("GET", "/comments") => match self.load_comments() {
+                Ok(json) => respond(&mut stream, 200, "application/json", json.as_bytes()),
+                Err(e) => respond(&mut stream, 500, "text/plain", e.as_bytes()),
+            },
+            ("POST", "/comments") => match self.add_comment(&request.body) {
+                Ok(json) => respond(&mut stream, 201, "application/json", json.as_bytes()),
+                Err(e) => respond(&mut stream, 400, "text/plain", e.as_bytes()),
+            },
+            _ => respond(&mut stream, 404, "text/plain", b"Not found"),
+        }
+    }
+
+    fn load_comments(&self) -> Result<String, String> {
+        let _guard = self.comments.lock().unwrap_or_else(|e| e.into_inner());
+        let comments = ReviewComments::load(&self.project_dir).map_err(|e| e.to_string())?;
+        serde_json::to_string(&comments).map_err(|e| e.to_string())
+    }
+
+    fn add_comment(&self, body: &[u8]) -> Result<String, String> {
+        let new: NewComment =
+            serde_json::from_slice(body).map_err(|e| format!("Invalid comment: {e}"))?;
+        let text = new.text.trim();
+        if text.is_empty() {
+            return Err("Comment is empty".to_string());
+        }
+        if !new.video_secs.is_finite() {
+            return Err("Comment has no video time".to_string());
+        }
+
+        let _guard = self.comments.lock().unwrap_or_else(|e| e.into_inner());
+        let mut comments = ReviewComments::load(&self.project_dir).map_err(|e| e.to_string())?;
+        let comment = ReviewComment::new(
+            self.timing.timeline_secs(new.video_secs),
+            new.video_secs.max(0.0),
+            new.author.trim(),
+            text,
+        );
+        println!(
+            "  Comment at {:.1}s{}: {}",
+            comment.time_secs,
+            if comment.author.is_empty() {
+                String::new()
+            } else {
+                format!(" from {}", comment.author)
+            },
+            comment.text
+        );
+        comments.add(comment);
+        comments
+            .save(&self.project_dir)
+            .map_err(|e| format!("Failed to save comment: {e}"))?;
+        serde_json::to_string(&comments).map_err(|e| e.to_string())
+    }
+}
+
+/// Read one request. `None` when it is malformed or too large.
+fn read_request(reader: &mut impl BufRead) -> std::io::Result<Option<Request>> {
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Ok(None);
+    };
+    let method = method.to_string();
+    let path = target.split('?').next().unwrap_or("/").to_string();
+
+    let mut content_length = 0usize;
+    let mut range = None;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            break;
+        }
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        let Some((name, value)) = header.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("content-length") {
+            content_length = value.parse().unwrap_or(0);
+        } else if name.eq_ignore_ascii_case("range") {
+            range = Some(value.to_string());
+        }
+    }
+    if content_length > MAX_COMMENT_BYTES {
+        return Ok(None);
+    }
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+    Ok(Some(Request {
+        method,
+        path,
+        range,
+        body,
+    }))
+}
+
+/// Byte range `(start, end)`, inclusive, of a `bytes=` range header on a
+/// file of `len` bytes. `None` when it can't be satisfied.
+fn parse_range(header: &str, len: u64) -> Option<(u64, u64)> {
+    let spec = header.trim().strip_prefix("bytes=")?;
+    // Only the first range of a multi-range request is served.
+    let spec = spec.split(',').next()?.trim();
+    let (start, end) = spec.split_once('-')?;
+    let (start, end) = match (start.trim(), end.trim()) {
+        ("", "") => return None,
+        // A suffix range: the last N bytes.
+        ("", suffix) => {
+            let suffix: u64 = suffix.parse().ok()?;
+            (len.checked_sub(suffix.min(len))?, len.checked_sub(1)?)
+        }
+        (start, "") => (start.parse().ok()?, len.checked_sub(1)?),
+        (start, end) => (
+            start.parse().ok()?,
+            end.parse::<u64>().ok()?.min(len.checked_sub(1)?),
+        ),
+    };
+    (start <= end && end < len).then_some((start, end))
+}
+
+/// Send the draft, or the part of it the browser asked for so seeking works.
+fn serve_video(stream: &mut TcpStream, path: &Path, range: Option<String>) -> std::io::Result<()> {
+    let mut file = match std::fs::File::open(path) {
+        Ok(file) => file,
+        Err(_) => return respond(stream, 404, "text/plain", b"Draft not found"),
+    };
+    let len = file.metadata()?.len();
+    let (status, start, end) = match range {
+        Some(range) => match parse_range(&range, len) {
+            Some((start, end)) => (206, start, end),
+            None => {
+                return write!(
+                    stream,
+                    "HTTP/1.1 416 Range Not Satisfiable\r\nContent-Range: bytes */{len}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                );
+            }
+        },
+        None if len == 0 => return respond(stream, 200, "video/mp4", b""),
+        None => (200, 0, len - 1),
+    };
+
+    let count = end - start + 1;
+    let mut head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: video/mp4\r\nAccept-Ranges: bytes\r\nContent-Length: {count}\r\nConnection: close\r\n",
+        status,
+        status_text(status)
+    );
+    if status == 206 {
+        head.push_str(&format!("Content-Range: bytes {start}-{end}/{len}\r\n"));
+    }
+    head.push_str("\r\n");
+    stream.write_all(head.as_bytes())?;
+    file.seek(SeekFrom::Start(start))?;
+    std::io::copy(&mut file.take(count), stream)?;
+    stream.flush()
+}
+
+fn respond(
+    stream: &mut TcpStream,
+    status: u16,
+    content_type: &str,
+    body: &[u8],
+) -> std::io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {status} {}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n",
+        status_text(status),
+        body.len()
+    )?;
+    stream.write_all(body)?;
+    stream.flush()
+}
+
+fn status_text(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        206 => "Partial Content",
+        400 => "Bad Request",
+        404 => "Not Found",
+        _ => "Internal Server Error",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use grabme_project_model::timeline::SpeedSegment;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_range_handles_open_and_suffix_ranges() {
+        assert_eq!(parse_range("bytes=0-", 1000), Some((0, 999)));
+        assert_eq!(parse_range("bytes=100-199", 1000), Some((100, 199)));
+        assert_eq!(parse_range("bytes=900-5000", 1000), Some((900, 999)));
+        assert_eq!(parse_range("bytes=-100", 1000), Some((900, 999)));
+        assert_eq!(parse_range("bytes=1000-", 1000), None);
+        assert_eq!(parse_range("bytes=5-2", 1000), None);
+        assert_eq!(parse_range("items=0-1", 1000), None);
+    }
+
+    #[test]
+    fn test_offline_review_listens_on_loopback_only() {
+        let loopback: SocketAddr = "127.0.0.1:8787".parse().unwrap();
+        let network: SocketAddr = "0.0.0.0:8787".parse().unwrap();
+        assert!(check_listen_allowed(loopback, true).is_ok());
+        assert!(check_listen_allowed("[::1]:8787".parse().unwrap(), true).is_ok());
+        assert!(check_listen_allowed(network, true).is_err());
+        assert!(check_listen_allowed(network, false).is_ok());
+    }
+
+    #[test]
+    fn test_read_request_keeps_path_range_and_body() {
+        let raw = "POST /comments?x=1 HTTP/1.1\r\nHost: a\r\nContent-Length: 2\r\nRange: bytes=0-\r\n\r\n{}";
+        let request = read_request(&mut raw.as_bytes()).unwrap().unwrap();
+        assert_eq!(
+            request,
+            Request {
+                method: "POST".to_string(),
+                path: "/comments".to_string(),
+                range: Some("bytes=0-".to_string()),
+                body: b"{}".to_vec(),
+            }
+        );
+        assert_eq!(read_request(&mut "\r\n".as_bytes()).unwrap(), None);
+    }
+
+    #[test]
+    fn test_draft_time_maps_through_start_offset_and_slow_motion() {
+        let dir = std::env::temp_dir().join("grabme_test_review_timing");
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut project = LoadedProject::create(&dir, "review", 1920, 1080, 30).unwrap();
+        project.timeline.start_offset_secs = Some(1.0);
+        project.timeline.speed_segments = vec![SpeedSegment::SlowMotion {
+            start_secs: 2.0,
+            end_secs: 3.0,
+            rate: 0.5,
+            audio: Default::default(),
+            interpolate: false,
+        }];
+
+        let timing = DraftTiming::for_project(&project);
+        assert_eq!(timing.timeline_secs(0.5), 1.5);
+        // One second of source at half speed fills two seconds of draft.
+        assert_eq!(timing.timeline_secs(2.0), 2.5);
+        assert_eq!(timing.timeline_secs(4.0), 4.0);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,18 @@
+//! Print the JSON Schema of a data contract.
+
+use std::path::PathBuf;
+
+use grabme_project_model::schema::{json_schema, SchemaKind};
+
+pub fn run(kind: SchemaKind, output: Option<PathBuf>) -> anyhow::Result<()> {
+    let schema = serde_json::to_string_pretty(&json_schema(kind))?;
+    match output {
+        Some(path) => {
+            std::fs::write(&path, schema + "\n")
+                .map_err(|e| anyhow::anyhow!("Failed to write {}: {e}", path.display()))?;
+            println!("Wrote {} schema to {}", kind.as_str(), path.display());
+        }
+        None => println!("{schema}"),
+    }
+    Ok(())
+}
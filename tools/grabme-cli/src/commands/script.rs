@@ -0,0 +1,179 @@
+//! Replay input scripts and turn recorded takes into scripts.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use grabme_capture_engine::{default_monitor_index, list_monitors, split_monitors};
+use grabme_common::clock::ClockSource;
+use grabme_common::config::AppConfig;
+use grabme_common::memory::MemoryProfile;
+use grabme_input_tracker::inject::detect_injector;
+use grabme_input_tracker::script::{InputScript, ScriptScreen};
+use grabme_platform_core::coords::PhysicalRect;
+use grabme_platform_linux::{set_display_target, DisplayTarget};
+use grabme_project_model::LoadedProject;
+
+use super::analyze::load_events;
+use super::record::WindowSelection;
+
+/// Replay the script at `path`, recording while it runs unless `no_record`.
+#[allow(clippy::too_many_arguments)]
+pub async fn run(
+    path: PathBuf,
+    name: Option<String>,
+    output: PathBuf,
+    fps: Option<u32>,
+    monitor: Option<usize>,
+    mic: bool,
+    system_audio: bool,
+    display: DisplayTarget,
+    no_record: bool,
+) -> anyhow::Result<()> {
+    let script = InputScript::load(&path)
+        .map_err(|e| anyhow::anyhow!("Failed to load script {}: {e}", path.display()))?;
+    println!(
+        "Script {}: {} steps, about {:.1}s",
+        path.display(),
+        script.steps.len(),
+        script.duration_secs()
+    );
+
+    if no_record {
+        return replay_only(script, monitor, display).await;
+    }
+
+    let defaults = AppConfig::load().recording;
+    let name = name.unwrap_or_else(|| {
+        path.file_stem()
+            .map_or_else(|| "script".to_string(), |s| s.to_string_lossy().to_string())
+    });
+    super::record::run(
+        name,
+        output,
+        fps,
+        monitor,
+        mic,
+        system_audio,
+        false,
+        None,
+        None,
+        false,
+        defaults.monitor_splits,
+        WindowSelection::None,
+        None,
+        ClockSource::default(),
+        display,
+        false,
+        false,
+        None,
+        false,
+        false,
+        defaults.on_lock,
+        defaults.on_resume,
+        None,
+        defaults.spool_dir,
+        None,
+        Some(script),
+    )
+    .await
+}
+
+/// Replay `script` onto a monitor without recording. Ctrl+C stops it.
+async fn replay_only(
+    script: InputScript,
+    monitor: Option<usize>,
+    display: DisplayTarget,
+) -> anyhow::Result<()> {
+    set_display_target(display);
+    let splits = AppConfig::load().recording.monitor_splits;
+    let monitors = split_monitors(&list_monitors().unwrap_or_default(), &splits);
+    let monitor = monitor.unwrap_or_else(|| default_monitor_index(&monitors));
+    let target = match monitors.get(monitor) {
+        Some(m) => PhysicalRect::new(m.x, m.y, m.width, m.height),
+        None if monitors.is_empty() => {
+            PhysicalRect::new(0, 0, script.screen.width, script.screen.height)
+        }
+        None => anyhow::bail!(
+            "Monitor index {monitor} is out of range (0..={})",
+            monitors.len() - 1
+        ),
+    };
+    let mut injector = detect_injector()?;
+    println!(
+        "Replaying with {} onto {}x{} at ({},{}) (Ctrl+C stops)...",
+        injector.name(),
+        target.width,
+        target.height,
+        target.x,
+        target.y
+    );
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let replay = {
+        let stop = Arc::clone(&stop);
+        tokio::task::spawn_blocking(move || script.play(injector.as_mut(), target, &stop))
+    };
+    tokio::pin!(replay);
+    let result = tokio::select! {
+        result = &mut replay => result,
+        result = tokio::signal::ctrl_c() => {
+            result?;
+            stop.store(true, Ordering::Relaxed);
+            replay.await
+        }
+    };
+    result??;
+    println!("Script finished.");
+    Ok(())
+}
+
+/// Write the take at `project` as a script to `output`, or to stdout.
+pub fn from_events(project: PathBuf, output: Option<PathBuf>) -> anyhow::Result<()> {
+    let loaded = LoadedProject::load(&project)
+        .map_err(|e| anyhow::anyhow!("Failed to load project: {e}"))?;
+    // Scripts keep every pointer sample; the converter simplifies paths.
+    let memory = MemoryProfile {
+        analysis_pointer_hz: None,
+        ..AppConfig::load().memory.profile()
+    };
+    let (header, events) = load_events(&project, &memory)?;
+
+    let recording = &loaded.project.recording;
+    let layout = recording.pointer_layout(
+        header
+            .map(|header| header.pointer_coordinate_space)
+            .unwrap_or_default(),
+    );
+    // Pointer layouts resolve to the recorded monitor; without one, positions
+    // stay relative to the captured frame.
+    let screen = match &layout {
+        Some(_) => ScriptScreen {
+            width: recording.monitor_width,
+            height: recording.monitor_height,
+        },
+        None => ScriptScreen {
+            width: recording.capture_width,
+            height: recording.capture_height,
+        },
+    };
+    let script = InputScript::from_events(&events, layout.as_ref(), screen);
+    let yaml = script
+        .to_yaml()
+        .map_err(|e| anyhow::anyhow!("Failed to write script: {e}"))?;
+
+    match output {
+        Some(output) => {
+            std::fs::write(&output, &yaml)
+                .map_err(|e| anyhow::anyhow!("Failed to write {}: {e}", output.display()))?;
+            println!(
+                "Wrote {} steps ({:.1}s) to {}",
+                script.steps.len(),
+                script.duration_secs(),
+                output.display()
+            );
+        }
+        None => print!("{yaml}"),
+    }
+    Ok(())
+}
@@ -0,0 +1,29 @@
+//! Check for a newer release and replace this binary with it.
+
+use grabme_common::config::AppConfig;
+use grabme_common::update::{check_for_update, install, CURRENT_VERSION};
+
+pub fn run(check_only: bool) -> anyhow::Result<()> {
+    let config = AppConfig::load();
+    println!("Checking for updates (current version {CURRENT_VERSION})...");
+    let Some(release) = check_for_update(&config)? else {
+        println!("GrabMe {CURRENT_VERSION} is up to date.");
+        return Ok(());
+    };
+    println!(
+        "GrabMe {} is available: {}",
+        release.version, release.page_url
+    );
+    if check_only {
+        println!("Run `grabme self-update` to install it.");
+        return Ok(());
+    }
+
+    let exe = std::env::current_exe()
+        .and_then(|exe| exe.canonicalize())
+        .map_err(|e| anyhow::anyhow!("Failed to locate the running binary: {e}"))?;
+    println!("Installing to {}...", exe.display());
+    install(&config, &release, &exe)?;
+    println!("Updated to GrabMe {}.", release.version);
+    Ok(())
+}
@@ -0,0 +1,96 @@
+//! Shrink or delete a project's raw sources.
+
+use std::io::IsTerminal;
+use std::path::PathBuf;
+
+use grabme_project_model::project::SlimAction;
+use grabme_project_model::LoadedProject;
+use grabme_render_engine::slim::{
+    slim_project, slim_targets, verify_last_export, SlimMode, DEFAULT_SLIM_CRF,
+};
+
+use super::doctor::confirm;
+use super::status::format_size;
+
+pub fn run(
+    path: PathBuf,
+    delete: bool,
+    verify: bool,
+    crf: Option<u32>,
+    dry_run: bool,
+    yes: bool,
+) -> anyhow::Result<()> {
+    let loaded =
+        LoadedProject::load(&path).map_err(|e| anyhow::anyhow!("Failed to load project: {e}"))?;
+    let mode = if delete {
+        SlimMode::Delete
+    } else {
+        SlimMode::Reencode {
+            crf: crf.unwrap_or(DEFAULT_SLIM_CRF),
+        }
+    };
+
+    if verify {
+        let export = verify_last_export(&loaded)
+            .map_err(|e| anyhow::anyhow!("Export check failed, leaving sources alone: {e}"))?;
+        println!("Verified export: {}", export.display());
+    }
+
+    let targets = slim_targets(&loaded, mode);
+    if targets.is_empty() {
+        println!("Nothing to slim in {}", path.display());
+        return Ok(());
+    }
+
+    let verb = if delete { "Delete" } else { "Re-encode" };
+    for target in &targets {
+        println!(
+            "  {verb} {} ({}, {})",
+            target.path,
+            target.label,
+            format_size(target.bytes)
+        );
+    }
+    if dry_run {
+        println!("Dry run; nothing was changed.");
+        return Ok(());
+    }
+
+    if delete && !yes {
+        if loaded.project.last_export.is_none() {
+            println!("No export is recorded for this project.");
+        }
+        if !std::io::stdin().is_terminal() {
+            anyhow::bail!("Deleting sources needs --yes when not run interactively");
+        }
+        if !confirm("Deleted sources cannot be recovered and the project can no longer be re-exported. Continue?") {
+            println!("Nothing was changed.");
+            return Ok(());
+        }
+    }
+
+    let slimmed = slim_project(&path, mode).map_err(|e| anyhow::anyhow!("Slimming failed: {e}"))?;
+    let mut freed = 0;
+    for source in &slimmed {
+        freed += source.original_bytes.saturating_sub(source.slimmed_bytes);
+        match source.action {
+            SlimAction::Deleted => println!("Deleted {}", source.path),
+            SlimAction::Reencoded => println!(
+                "Re-encoded {} -> {}: {} -> {}",
+                source.path,
+                source.new_path.as_deref().unwrap_or(&source.path),
+                format_size(source.original_bytes),
+                format_size(source.slimmed_bytes)
+            ),
+        }
+    }
+    let kept = targets.len() - slimmed.len();
+    if kept > 0 {
+        println!("Kept {kept} source(s) whose re-encode was not smaller.");
+    }
+    println!(
+        "Freed {}. Changes are listed under `slimmed_sources` in meta/project.json.",
+        format_size(freed)
+    );
+    Ok(())
+}
@@ -0,0 +1,300 @@
+//! Dashboard of the projects in a directory.
+
+use std::path::{Path, PathBuf};
+
+use grabme_project_model::timeline::{KeyframeSource, Timeline};
+use grabme_project_model::LoadedProject;
+use grabme_render_engine::stills::{project_thumbnail_path, write_project_thumbnail};
+use serde::Serialize;
+
+/// Files in `exports/` that count as a finished export when the project
+/// predates export records.
+const EXPORT_EXTENSIONS: [&str; 5] = ["mp4", "webm", "gif", "mov", "mkv"];
+
+/// One row of the dashboard.
+#[derive(Debug, Serialize)]
+struct ProjectStatus {
+    name: String,
+    path: PathBuf,
+    duration_secs: f64,
+    size_bytes: u64,
+    sources_bytes: u64,
+    analyzed: bool,
+    exported: bool,
+    last_export: Option<PathBuf>,
+    last_exported_at: Option<String>,
+    thumbnail: Option<PathBuf>,
+}
+
+pub fn run(dir: PathBuf, json: bool, thumbnails: bool) -> anyhow::Result<()> {
+    if !dir.is_dir() {
+        anyhow::bail!("{} is not a directory", dir.display());
+    }
+
+    let mut rows = Vec::new();
+    for path in find_projects(&dir)? {
+        let loaded = match LoadedProject::load(&path) {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                tracing::warn!(path = %path.display(), error = %e, "Skipping unreadable project");
+                continue;
+            }
+        };
+        if thumbnails && !project_thumbnail_path(&path).is_file() {
+            if let Err(e) = write_project_thumbnail(&path) {
+                tracing::warn!(path = %path.display(), error = %e, "Failed to write thumbnail");
+            }
+        }
+        rows.push(project_status(&loaded));
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&rows)?);
+        return Ok(());
+    }
+
+    if rows.is_empty() {
+        println!("No projects in {}", dir.display());
+        return Ok(());
+    }
+    print_table(&rows);
+    print_cleanup(&rows);
+    Ok(())
+}
+
+/// `dir` itself if it is a project, or else the projects directly inside it.
+fn find_projects(dir: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    if is_project_dir(dir) {
+        return Ok(vec![dir.to_path_buf()]);
+    }
+    let mut projects: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map_err(|e| anyhow::anyhow!("Failed to read {}: {e}", dir.display()))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| is_project_dir(path))
+        .collect();
+    projects.sort();
+    Ok(projects)
+}
+
+fn is_project_dir(path: &Path) -> bool {
+    path.join("meta").join("project.json").is_file()
+}
+
+fn project_status(loaded: &LoadedProject) -> ProjectStatus {
+    let root = &loaded.root;
+    let project = &loaded.project;
+    let (last_export, last_exported_at) = match project
+        .last_export
+        .as_ref()
+        .map(|record| (record.resolve(root), record.exported_at.clone()))
+        .filter(|(path, _)| path.exists())
+    {
+        Some((path, at)) => (Some(path), Some(at)),
+        None => (newest_export(&root.join("exports")), None),
+    };
+    let thumbnail = project_thumbnail_path(root);
+
+    ProjectStatus {
+        name: project.name.clone(),
+        path: root.clone(),
+        duration_secs: project
+            .tracks
+            .screen
+            .as_ref()
+            .map_or(0.0, |screen| screen.duration_secs),
+        size_bytes: dir_size(root),
+        sources_bytes: dir_size(&root.join("sources")),
+        analyzed: is_analyzed(&loaded.timeline),
+        exported: last_export.is_some(),
+        last_export,
+        last_exported_at,
+        thumbnail: thumbnail.is_file().then_some(thumbnail),
+    }
+}
+
+/// Whether Auto-Director has written keyframes. A fresh timeline holds only
+/// the unscored full-frame keyframe at zero.
+fn is_analyzed(timeline: &Timeline) -> bool {
+    let mut auto = timeline
+        .keyframes
+        .iter()
+        .filter(|keyframe| keyframe.source == KeyframeSource::Auto);
+    auto.clone().any(|keyframe| keyframe.confidence.is_some()) || auto.nth(1).is_some()
+}
+
+/// Most recently modified video in `exports`, for projects exported before
+/// the last export was recorded.
+fn newest_export(exports: &Path) -> Option<PathBuf> {
+    std::fs::read_dir(exports)
+        .ok()?
+        .flatten()
+        .filter(|entry| {
+            let path = entry.path();
+            path.is_file()
+                && path
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| {
+                        EXPORT_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str())
+                    })
+        })
+        .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+        .max()
+        .map(|(_, path)| path)
+}
+
+/// Total size of the files under `path`, without following symlinks.
+fn dir_size(path: &Path) -> u64 {
+    let Ok(metadata) = std::fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+    std::fs::read_dir(path)
+        .map(|entries| entries.flatten().map(|entry| dir_size(&entry.path())).sum())
+        .unwrap_or(0)
+}
+
+fn print_table(rows: &[ProjectStatus]) {
+    let name_width = rows
+        .iter()
+        .map(|row| row.name.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max("NAME".len());
+    println!(
+        "{:<name_width$}  {:>8}  {:>9}  {:<8}  {:<8}  LAST EXPORT",
+        "NAME", "DURATION", "SIZE", "ANALYZED", "EXPORTED"
+    );
+    for row in rows {
+        let last_export = row
+            .last_export
+            .as_ref()
+            .map_or_else(|| "-".to_string(), |path| path.display().to_string());
+        println!(
+            "{:<name_width$}  {:>8}  {:>9}  {:<8}  {:<8}  {last_export}",
+            row.name,
+            format_duration(row.duration_secs),
+            format_size(row.size_bytes),
+            yes_no(row.analyzed),
+            yes_no(row.exported),
+        );
+    }
+}
+
+/// Suggest removing raw sources of projects that are already exported.
+fn print_cleanup(rows: &[ProjectStatus]) {
+    let candidates: Vec<&ProjectStatus> = rows
+        .iter()
+        .filter(|row| row.exported && row.sources_bytes > 0)
+        .collect();
+    if candidates.is_empty() {
+        return;
+    }
+    let total: u64 = candidates.iter().map(|row| row.sources_bytes).sum();
+    println!();
+    println!(
+        "Cleanup: {} exported project(s) still keep raw sources ({}).",
+        candidates.len(),
+        format_size(total)
+    );
+    for row in candidates {
+        println!(
+            "  {}  {}",
+            format_size(row.sources_bytes),
+            row.path.join("sources").display()
+        );
+    }
+    println!(
+        "Run `grabme slim <PROJECT>` to re-encode them smaller, or add `--delete` to remove them."
+    );
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
+fn format_duration(secs: f64) -> String {
+    let total = secs.max(0.0).round() as u64;
+    format!("{}:{:02}", total / 60, total % 60)
+}
+
+pub(crate) fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1000 {
+        return format!("{bytes} B");
+    }
+    let mut value = bytes as f64;
+    let mut unit = "B";
+    for next in UNITS {
+        if value < 1000.0 {
+            break;
+        }
+        value /= 1000.0;
+        unit = next;
+    }
+    format!("{value:.1} {unit}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use grabme_project_model::timeline::CameraKeyframe;
+    use grabme_project_model::viewport::Viewport;
+
+    #[test]
+    fn test_is_analyzed_ignores_default_keyframe() {
+        let mut timeline = Timeline::new();
+        assert!(!is_analyzed(&timeline));
+
+        timeline.keyframes.push(CameraKeyframe {
+            time_secs: 2.0,
+            viewport: Viewport::FULL,
+            easing: Default::default(),
+            source: KeyframeSource::Manual,
+            confidence: None,
+        });
+        assert!(!is_analyzed(&timeline));
+
+        timeline.keyframes[0].confidence = Some(0.8);
+        assert!(is_analyzed(&timeline));
+    }
+
+    #[test]
+    fn test_project_status_falls_back_to_newest_export() {
+        let root = std::env::temp_dir().join(format!("grabme-status-test-{}", std::process::id()));
+        let loaded = LoadedProject::create(root.join("demo"), "Demo", 1920, 1080, 60).unwrap();
+        std::fs::write(loaded.root.join("sources").join("screen.mkv"), [0u8; 2048]).unwrap();
+        std::fs::write(loaded.root.join("exports").join("notes.txt"), "").unwrap();
+
+        let status = project_status(&loaded);
+        assert!(!status.exported);
+        assert_eq!(status.sources_bytes, 2048);
+        assert!(status.size_bytes > status.sources_bytes);
+
+        std::fs::write(loaded.root.join("exports").join("output.mp4"), "").unwrap();
+        let status = project_status(&loaded);
+        assert_eq!(
+            status.last_export,
+            Some(loaded.root.join("exports").join("output.mp4"))
+        );
+        assert_eq!(status.last_exported_at, None);
+        assert_eq!(find_projects(&root).unwrap(), vec![loaded.root.clone()]);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_format_size_and_duration() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1_500_000), "1.5 MB");
+        assert_eq!(format_size(2_000_000_000), "2.0 GB");
+        assert_eq!(format_duration(125.4), "2:05");
+    }
+}
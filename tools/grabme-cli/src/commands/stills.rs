@@ -0,0 +1,41 @@
+//! Extract still frames from a recording for documentation.
+
+use std::path::PathBuf;
+
+use grabme_render_engine::stills::{extract_stills, StillsJob, StillsSchedule};
+
+pub fn run(
+    path: PathBuf,
+    every: Option<f64>,
+    clicks: bool,
+    output: Option<PathBuf>,
+) -> anyhow::Result<()> {
+    let schedule = match (every, clicks) {
+        (_, true) => StillsSchedule::PerClick,
+        (Some(secs), false) if secs > 0.0 => StillsSchedule::Every(secs),
+        (Some(secs), false) => {
+            return Err(anyhow::anyhow!("--every must be positive (got {secs})"));
+        }
+        (None, false) => StillsSchedule::Every(10.0),
+    };
+    let output_dir = output.unwrap_or_else(|| path.join("exports").join("stills"));
+
+    println!("Extracting stills from: {}", path.display());
+    match schedule {
+        StillsSchedule::Every(secs) => println!("  Schedule: every {secs}s"),
+        StillsSchedule::PerClick => println!("  Schedule: every click"),
+    }
+
+    let stills = extract_stills(&StillsJob {
+        project_dir: path,
+        output_dir: output_dir.clone(),
+        schedule,
+    })
+    .map_err(|e| anyhow::anyhow!("Still extraction failed: {e}"))?;
+
+    let captioned = stills.iter().filter(|s| s.caption.is_some()).count();
+    println!("  Wrote {} stills ({captioned} captioned)", stills.len());
+    println!("  Index: {}", output_dir.join("index.md").display());
+
+    Ok(())
+}
@@ -0,0 +1,154 @@
+//! Open a project in the desktop editor.
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use grabme_common::config::library_index_path;
+use grabme_project_model::library::ProjectLibrary;
+use grabme_project_model::LoadedProject;
+
+/// Environment variable naming the editor binary, for custom installs.
+const EDITOR_ENV: &str = "GRABME_DESKTOP";
+
+/// Names the editor is installed under: the cargo binary, then the
+/// bundled app on Linux and on Windows/macOS.
+const EDITOR_BINARIES: [&str; 3] = ["grabme-desktop", "grab-me", "GrabMe"];
+
+pub fn run(project: Option<PathBuf>, editor: Option<PathBuf>) -> anyhow::Result<()> {
+    let editor = match editor.or_else(|| std::env::var_os(EDITOR_ENV).map(PathBuf::from)) {
+        Some(editor) => editor,
+        None => find_editor().ok_or_else(|| {
+            anyhow::anyhow!(
+                "Desktop editor not found. Install it next to grabme or on PATH \
+                 (as {}), or point --editor or ${EDITOR_ENV} at it",
+                EDITOR_BINARIES.join(", ")
+            )
+        })?,
+    };
+
+    let project = match project {
+        Some(path) => Some(resolve_project(&path)?),
+        None => default_project(),
+    };
+
+    let mut command = Command::new(&editor);
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    if let Some(project) = &project {
+        command.arg(project);
+    }
+    let child = command
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("Failed to launch {}: {e}", editor.display()))?;
+
+    match &project {
+        Some(project) => {
+            remember_project(project);
+            println!(
+                "Opened {} in the editor (pid {})",
+                project.display(),
+                child.id()
+            );
+        }
+        None => println!("Started the editor (pid {})", child.id()),
+    }
+    Ok(())
+}
+
+/// Absolute path of a project directory or `.grabme` archive, checked to
+/// load before the editor is started.
+fn resolve_project(path: &Path) -> anyhow::Result<PathBuf> {
+    let path = std::fs::canonicalize(path)
+        .map_err(|e| anyhow::anyhow!("Project not found at {}: {e}", path.display()))?;
+    if path.is_dir() {
+        LoadedProject::load(&path).map_err(|e| anyhow::anyhow!("Failed to load project: {e}"))?;
+    } else if path.extension().and_then(|ext| ext.to_str()) != Some("grabme") {
+        anyhow::bail!(
+            "{} is not a project directory or .grabme archive",
+            path.display()
+        );
+    }
+    Ok(path)
+}
+
+/// The project in the current directory, or else the most recently used one.
+fn default_project() -> Option<PathBuf> {
+    let cwd = std::env::current_dir().ok()?;
+    if is_project_dir(&cwd) {
+        return Some(cwd);
+    }
+    let mut library = ProjectLibrary::load(library_index_path()).ok()?;
+    library.prune_missing();
+    library.recent(1).first().map(|entry| entry.path.clone())
+}
+
+fn is_project_dir(path: &Path) -> bool {
+    path.join("meta").join("project.json").is_file()
+}
+
+/// Move the project to the front of the recent-project library.
+fn remember_project(path: &Path) {
+    if !is_project_dir(path) {
+        return;
+    }
+    let Ok(loaded) = LoadedProject::load(path) else {
+        return;
+    };
+    let index = library_index_path();
+    let mut library = ProjectLibrary::load(&index).unwrap_or_default();
+    library.touch(loaded.project.name, path);
+    if let Err(e) = library.save(&index) {
+        tracing::warn!(error = %e, "Failed to update project library");
+    }
+}
+
+/// The editor next to the running `grabme`, or else on `PATH`.
+fn find_editor() -> Option<PathBuf> {
+    let sibling_dir = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf));
+    let path_dirs = std::env::var_os("PATH")
+        .map(|path| std::env::split_paths(&path).collect::<Vec<_>>())
+        .unwrap_or_default();
+    find_editor_in(sibling_dir.into_iter().chain(path_dirs))
+}
+
+fn find_editor_in(dirs: impl IntoIterator<Item = PathBuf>) -> Option<PathBuf> {
+    dirs.into_iter().find_map(|dir| {
+        EDITOR_BINARIES.iter().find_map(|name| {
+            let candidate = dir.join(format!("{name}{}", std::env::consts::EXE_SUFFIX));
+            candidate.is_file().then_some(candidate)
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_editor_checks_dirs_in_order() {
+        let root = std::env::temp_dir().join(format!("grabme-studio-test-{}", std::process::id()));
+        let first = root.join("first");
+        let second = root.join("second");
+        std::fs::create_dir_all(&first).unwrap();
+        std::fs::create_dir_all(&second).unwrap();
+        let suffix = std::env::consts::EXE_SUFFIX;
+        std::fs::write(second.join(format!("grab-me{suffix}")), "").unwrap();
+
+        assert_eq!(
+            find_editor_in([first.clone(), second.clone()]),
+            Some(second.join(format!("grab-me{suffix}")))
+        );
+        std::fs::write(first.join(format!("grabme-desktop{suffix}")), "").unwrap();
+        assert_eq!(
+            find_editor_in([first.clone(), second]),
+            Some(first.join(format!("grabme-desktop{suffix}")))
+        );
+        assert_eq!(find_editor_in([root.join("missing")]), None);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -4,10 +4,10 @@ use std::path::PathBuf;
 
 use grabme_project_model::LoadedProject;
 
-pub fn run(path: PathBuf) -> anyhow::Result<()> {
+pub fn run(path: PathBuf, fix_aspect: bool) -> anyhow::Result<()> {
     println!("Validating project at: {}", path.display());
 
-    let project =
+    let mut project =
         LoadedProject::load(&path).map_err(|e| anyhow::anyhow!("Failed to load project: {e}"))?;
 
     println!("  Name: {}", project.project.name);
@@ -19,10 +19,26 @@ pub fn run(path: PathBuf) -> anyhow::Result<()> {
     println!("  FPS: {}", project.project.recording.fps);
     println!("  Timeline keyframes: {}", project.timeline.keyframes.len());
 
-    // Check source files
-    let errors = project.validate_sources();
+    if fix_aspect {
+        if let Some(lock) = project.aspect_lock() {
+            let fixed = project.timeline.lock_aspect(lock);
+            if fixed > 0 {
+                project
+                    .save()
+                    .map_err(|e| anyhow::anyhow!("Failed to save timeline: {e}"))?;
+                println!("  Reshaped {fixed} keyframe(s) to the export aspect");
+            }
+        }
+    }
+
+    // Check source files and keyframe shapes
+    let mut errors = project.validate_sources();
+    let distorted = project.validate_timeline();
+    let any_distorted = !distorted.is_empty();
+    errors.extend(distorted);
     if errors.is_empty() {
         println!("  Sources: All present");
+        println!("  Keyframes: Match the export aspect");
         println!("\nProject is valid.");
     } else {
         println!("\nValidation issues:");
@@ -33,6 +49,9 @@ pub fn run(path: PathBuf) -> anyhow::Result<()> {
             "\n{} issue(s) found. Project may not be fully usable.",
             errors.len()
         );
+        if any_distorted {
+            println!("Run with --fix-aspect to reshape the distorted keyframes.");
+        }
     }
 
     Ok(())
@@ -5,12 +5,27 @@
 //!   grabme validate <PATH>     Validate a project bundle
 //!   grabme analyze <PATH>      Run Auto-Director on a project
 //!   grabme export <PATH>       Export a project to video
+//!   grabme stills <PATH>       Extract still frames for documentation
 //!   grabme info <PATH>         Show project information
+//!   grabme status <DIR>        Dashboard of the projects in a directory
+//!   grabme slim <PATH>         Re-encode or delete raw sources after export
+//!   grabme studio [PATH]       Open a project in the desktop editor
 //!   grabme check               Check system capabilities
+//!   grabme doctor              Test capture, encoders, and disk; offer config fixes
 
 use std::path::PathBuf;
 
 use clap::{Parser, Subcommand};
+use grabme_capture_engine::virtual_camera::DEFAULT_VIRTUAL_CAMERA_ZOOM;
+use grabme_capture_engine::{PipSource, VirtualCameraConfig};
+use grabme_common::clock::ClockSource;
+use grabme_common::config::{LockAction, ResumeAction};
+use grabme_platform_linux::{DisplayServer, DisplayTarget};
+use grabme_processing_core::auto_zoom::DwellUnit;
+use grabme_project_model::project::{AspectMode, ChapterMarker, ExportMetadata, WaveformStyle};
+use grabme_project_model::schema::SchemaKind;
+use grabme_render_engine::draft::DraftOptions;
+use grabme_render_engine::resources::{parse_encoder_preset, ExportPriority, ExportResources};
 
 mod commands;
 
@@ -38,22 +53,28 @@ enum Commands {
         #[arg(short, long, default_value = "recording")]
         name: String,
 
-        /// Output directory
-        #[arg(short, long, default_value = ".")]
-        output: PathBuf,
+        /// Output directory [default: `projects_dir` from config, or ~/Videos/GrabMe]
+        #[arg(short, long)]
+        output: Option<PathBuf>,
 
-        /// Target FPS
-        #[arg(long, default_value = "60")]
-        fps: u32,
+        /// Target FPS [default: the --like project's, else `recording.fps` from config, or 60]
+        #[arg(long)]
+        fps: Option<u32>,
 
-        /// Zero-based monitor index to record (use --list-monitors to see available monitors)
-        #[arg(long, default_value = "0")]
-        monitor: usize,
+        /// Zero-based monitor index to record (use --list-monitors to see available monitors).
+        /// Defaults to the monitor showing the focused window.
+        #[arg(long)]
+        monitor: Option<usize>,
 
         /// List available monitors and exit without recording
         #[arg(long)]
         list_monitors: bool,
 
+        /// Split a monitor into N side-by-side regions listed and recorded like
+        /// separate monitors, e.g. DP-1=2 (repeatable) [default: `recording.monitor_splits` from config]
+        #[arg(long, value_name = "NAME=N", value_parser = commands::record::parse_monitor_split)]
+        split_monitor: Vec<(String, u32)>,
+
         /// Disable microphone capture
         #[arg(long)]
         no_mic: bool,
@@ -65,12 +86,106 @@ enum Commands {
         /// Enable webcam capture
         #[arg(long)]
         webcam: bool,
+
+        /// Webcam frame size to ask for, e.g. 1280x720; the closest size the camera offers is used
+        /// [default: the --like project's, else `recording.webcam_width`/`webcam_height` from config,
+        /// else the camera's current size]
+        #[arg(long, value_name = "WxH", value_parser = commands::record::parse_webcam_size)]
+        webcam_size: Option<(u32, u32)>,
+
+        /// Webcam frame rate to ask for [default: the --like project's, else `recording.webcam_fps`
+        /// from config, else --fps up to 30]
+        #[arg(long)]
+        webcam_fps: Option<u32>,
+
+        /// Record a window by X11 id (e.g. 0x3a00007), following it as it moves
+        #[arg(long, conflicts_with = "pick_window")]
+        window: Option<String>,
+
+        /// Pick the window to record (click it on X11; the portal dialog asks on Wayland)
+        #[arg(long)]
+        pick_window: bool,
+
+        /// After stopping, extract a still every N seconds into exports/stills
+        #[arg(long)]
+        stills_every: Option<f64>,
+
+        /// Clock for event and track timestamps: monotonic, or boottime to keep counting through suspend
+        #[arg(long, default_value = "monotonic")]
+        clock: ClockSource,
+
+        /// X11 display to record instead of $DISPLAY (e.g. :99 for Xvfb)
+        #[arg(long)]
+        display: Option<String>,
+
+        /// Display server to use instead of detecting it (x11 or wayland) [default: `recording.display_server` from config]
+        #[arg(long)]
+        display_server: Option<DisplayServer>,
+
+        /// Switch --fps to a rate that divides the monitor refresh rate when it would judder
+        #[arg(long)]
+        auto_fps: bool,
+
+        /// Record gamepad buttons and sticks (for game tutorials)
+        #[arg(long)]
+        gamepad: bool,
+
+        /// Also record this monitor index as a picture-in-picture track (X11)
+        #[arg(long, conflicts_with = "pip_device")]
+        pip_monitor: Option<usize>,
+
+        /// Also record this capture device (e.g. /dev/video2) as a picture-in-picture track
+        #[arg(long)]
+        pip_device: Option<String>,
+
+        /// Log screen capture timestamps to meta/frame-times.jsonl for drift analysis
+        #[arg(long)]
+        frame_times: bool,
+
+        /// Sample CPU, memory and GPU usage to meta/metrics.jsonl (for export --metrics-overlay)
+        #[arg(long)]
+        metrics: bool,
+
+        /// When the screen locks or the system suspends: stop, pause or ignore
+        /// [default: `recording.on_lock` from config, stop]
+        #[arg(long)]
+        on_lock: Option<LockAction>,
+
+        /// After waking from a suspend that stopped the recording: prompt, auto or off
+        /// [default: `recording.on_resume` from config, prompt]
+        #[arg(long)]
+        on_resume: Option<ResumeAction>,
+
+        /// Mirror a cursor-following view to a virtual webcam (v4l2loopback) while recording
+        #[arg(long)]
+        virtual_camera: bool,
+
+        /// Loopback device for --virtual-camera [default: the first v4l2loopback device]
+        #[arg(long, value_name = "DEVICE", requires = "virtual_camera")]
+        virtual_camera_device: Option<String>,
+
+        /// Magnification of the --virtual-camera view [default: 1.5]
+        #[arg(long, requires = "virtual_camera")]
+        virtual_camera_zoom: Option<f64>,
+
+        /// Record into this local directory and move the project to the output directory on stop,
+        /// for outputs on network or removable drives [default: `recording.spool_dir` from config]
+        #[arg(long, value_name = "DIR")]
+        spool_dir: Option<PathBuf>,
+
+        /// Reuse the monitor, FPS, audio and webcam settings of an existing project
+        #[arg(long, value_name = "PROJECT", conflicts_with = "list_monitors")]
+        like: Option<PathBuf>,
     },
 
     /// Validate a project bundle
     Validate {
         /// Path to the project directory
         path: PathBuf,
+
+        /// Reshape keyframes that would be distorted in the export and save the timeline
+        #[arg(long)]
+        fix_aspect: bool,
     },
 
     /// Run Auto-Director analysis on a project
@@ -98,13 +213,18 @@ enum Commands {
         #[arg(long, default_value = "0.92")]
         scan_zoom: f64,
 
-        /// Dwell radius threshold (normalized). Used with --camera-style auto.
-        #[arg(long, default_value = "0.15")]
-        dwell_radius: f64,
+        /// Dwell radius threshold in --dwell-unit [default: 0.15 normalized, 216 px, 57 mm]. Used with --camera-style auto.
+        #[arg(long)]
+        dwell_radius: Option<f64>,
 
-        /// Dwell velocity threshold (normalized units/sec). Used with --camera-style auto.
-        #[arg(long, default_value = "0.18")]
-        dwell_velocity: f64,
+        /// Dwell velocity threshold in --dwell-unit per second [default: 0.18 normalized, 260 px, 69 mm]. Used with --camera-style auto.
+        #[arg(long)]
+        dwell_velocity: Option<f64>,
+
+        /// Unit of the dwell thresholds: normalized, px (logical pixels) or mm.
+        /// px and mm are resolved against the recorded display's size and scale.
+        #[arg(long, default_value = "normalized")]
+        dwell_unit: DwellUnit,
 
         /// Smoothing window for generated camera keyframes. Used with --camera-style auto.
         #[arg(long, default_value = "3")]
@@ -125,6 +245,18 @@ enum Commands {
         /// Zero-based focused monitor index
         #[arg(long, default_value = "0")]
         focused_monitor: usize,
+
+        /// Clicks closer together than this (seconds) get one zoom pulse. Used with --camera-style production.
+        #[arg(long, default_value = "0.5")]
+        click_burst_window: f64,
+
+        /// WebAssembly timeline pass to run after the camera style (repeatable)
+        #[arg(long = "pass-plugin", value_name = "WASM")]
+        pass_plugins: Vec<PathBuf>,
+
+        /// Print per-keyframe confidence and flag low-confidence segments
+        #[arg(long)]
+        report: bool,
     },
 
     /// Export a project to video
@@ -136,17 +268,198 @@ enum Commands {
         #[arg(short, long)]
         output: Option<PathBuf>,
 
-        /// Output format
+        /// Output format (mp4-h264, mp4-h265, gif, webm, png-sequence)
         #[arg(long, default_value = "mp4-h264")]
         format: String,
 
-        /// Output width
-        #[arg(long, default_value = "1920")]
-        width: u32,
+        /// Output width [default: the recorded monitor's, at most 3840 (1280 for GIF)]
+        #[arg(long)]
+        width: Option<u32>,
 
-        /// Output height
-        #[arg(long, default_value = "1080")]
-        height: u32,
+        /// Output height [default: the recorded monitor's, at most 2160 (720 for GIF)]
+        #[arg(long)]
+        height: Option<u32>,
+
+        /// Background music file to mix under the recording
+        #[arg(long)]
+        music: Option<PathBuf>,
+
+        /// Background music gain in dB (e.g. -18)
+        #[arg(long, allow_hyphen_values = true)]
+        music_gain: Option<f64>,
+
+        /// Stream-copy the raw screen video with audio, skipping compositing
+        #[arg(long)]
+        fast_copy: bool,
+
+        /// Output frame rate (defaults to the recording frame rate)
+        #[arg(long)]
+        fps: Option<u32>,
+
+        /// Start of the exported range in seconds
+        #[arg(long)]
+        start: Option<f64>,
+
+        /// End of the exported range in seconds
+        #[arg(long)]
+        end: Option<f64>,
+
+        /// Keep the black frames at the start of the recording
+        #[arg(long)]
+        keep_preroll: bool,
+
+        /// End before the idle tail: no speech or input while reaching for the stop button
+        /// [default: `recording.trim_tail` from config]
+        #[arg(long, conflicts_with_all = ["keep_tail", "fast_copy"])]
+        trim_tail: bool,
+
+        /// Keep the idle tail at the end of the recording
+        #[arg(long)]
+        keep_tail: bool,
+
+        /// Render the whole screen with the viewport, raw and smoothed
+        /// cursor, input events and timecode drawn on top (for bug reports)
+        #[arg(long, conflicts_with = "fast_copy")]
+        debug_overlay: bool,
+
+        /// Encoder and filter threads [default: all cores]
+        #[arg(long)]
+        threads: Option<u32>,
+
+        /// Process priority for the encode (normal, low, idle)
+        #[arg(long, default_value = "normal")]
+        priority: ExportPriority,
+
+        /// x264/x265 preset (ultrafast ... veryslow) [default: medium]
+        #[arg(long, value_parser = parse_encoder_preset)]
+        preset: Option<String>,
+
+        /// Draw recorded pen and touch strokes over the screen
+        #[arg(long)]
+        strokes: bool,
+
+        /// Don't animate reactions dropped with hotkeys while recording
+        #[arg(long)]
+        no_reactions: bool,
+
+        /// Show a gamepad diagram with held buttons while a gamepad is in use
+        #[arg(long)]
+        gamepad_overlay: bool,
+
+        /// Show a CPU/memory/GPU usage graph from a recording made with --metrics
+        #[arg(long)]
+        metrics_overlay: bool,
+
+        /// Burn in the time of day each frame was recorded
+        #[arg(long)]
+        burn_clock: bool,
+
+        /// Shrink the picture into the TV-safe area and pad the margin
+        #[arg(long)]
+        overscan: bool,
+
+        /// Video bitrate in kbps [default: estimated from the content]
+        #[arg(long, conflicts_with = "target_size")]
+        video_bitrate: Option<u32>,
+
+        /// Pick the video bitrate so the file is about this size (e.g. 50MB)
+        #[arg(long)]
+        target_size: Option<String>,
+
+        /// Copy the result to the clipboard (GIFs are copied unless --no-copy)
+        #[arg(long, conflicts_with = "no_copy")]
+        copy: bool,
+
+        /// Don't copy a GIF export to the clipboard
+        #[arg(long)]
+        no_copy: bool,
+
+        /// Render these aspects instead, one file each (landscape, square,
+        /// vertical), e.g. output-vertical.mp4
+        #[arg(long, value_delimiter = ',', conflicts_with_all = ["fast_copy", "debug_overlay", "copy"])]
+        variants: Vec<AspectMode>,
+
+        /// Draw the audio as a waveform or spectrum instead of the screen, for
+        /// audio-first recordings (waves, spectrum)
+        #[arg(long, num_args = 0..=1, default_missing_value = "waves", conflicts_with_all = ["fast_copy", "debug_overlay"])]
+        waveform: Option<WaveformStyle>,
+
+        /// Render a fast preview for review: at most 540p and 30 fps, the
+        /// ultrafast preset, and a timecode in the corner
+        #[arg(long, conflicts_with_all = ["fast_copy", "debug_overlay"])]
+        draft: bool,
+
+        /// Stamp DRAFT across the preview
+        #[arg(long, requires = "draft")]
+        watermark: bool,
+
+        /// Title written into the file [default: the project's, else its name]
+        #[arg(long)]
+        title: Option<String>,
+
+        /// Author written into the file
+        #[arg(long)]
+        author: Option<String>,
+
+        /// Comment written into the file
+        #[arg(long)]
+        comment: Option<String>,
+
+        /// Creation time written into the file, RFC 3339 [default: when the recording started]
+        #[arg(long, value_name = "TIME")]
+        creation_time: Option<String>,
+
+        /// Chapter starting at a recording time in seconds or [HH:]MM:SS, e.g. 1:30=Demo.
+        /// Repeat for more; replaces the project's chapters
+        #[arg(long = "chapter", value_name = "TIME=TITLE", value_parser = commands::export::parse_chapter)]
+        chapters: Vec<ChapterMarker>,
+    },
+
+    /// Extract still frames (every N seconds or per click) for documentation
+    Stills {
+        /// Path to the project directory
+        path: PathBuf,
+
+        /// Seconds between stills [default: 10]
+        #[arg(long, conflicts_with = "clicks")]
+        every: Option<f64>,
+
+        /// Take one still per left click instead of on a fixed interval
+        #[arg(long)]
+        clicks: bool,
+
+        /// Output directory [default: <PATH>/exports/stills]
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Measure how far from each recorded click an export drew the cursor
+    AuditCursor {
+        /// Path to the project directory
+        project: PathBuf,
+
+        /// Exported video to check
+        export: PathBuf,
+
+        /// Start time the export was made with (seconds)
+        #[arg(long)]
+        start: Option<f64>,
+
+        /// End time the export was made with (seconds)
+        #[arg(long)]
+        end: Option<f64>,
+
+        /// The export kept the black frames at the start of the recording
+        #[arg(long)]
+        keep_preroll: bool,
+
+        /// Pixels around each expected position to search for the cursor [default: 48]
+        #[arg(long)]
+        radius: Option<u32>,
+
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
     },
 
     /// Show project information
@@ -155,17 +468,138 @@ enum Commands {
         path: PathBuf,
     },
 
+    /// Show name, duration, size, and analysis/export state of every project in a directory
+    Status {
+        /// Directory holding project directories, or a single project
+        dir: PathBuf,
+
+        /// Print the dashboard as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Write missing thumbnails to <PROJECT>/cache/thumbnail.png
+        #[arg(long)]
+        thumbnails: bool,
+    },
+
+    /// Re-encode raw sources to save space, or delete them, keeping events, timeline, and exports
+    Slim {
+        /// Path to the project directory
+        path: PathBuf,
+
+        /// Delete the raw sources instead of re-encoding them
+        #[arg(long)]
+        delete: bool,
+
+        /// Check that the last export exists and plays before touching sources
+        #[arg(long)]
+        verify: bool,
+
+        /// x264 CRF for re-encoded video (higher is smaller) [default: 26]
+        #[arg(long, conflicts_with = "delete")]
+        crf: Option<u32>,
+
+        /// List what would be done without changing anything
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Delete without asking
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Export a draft and serve a page where reviewers leave timestamped comments
+    Review {
+        /// Path to the project directory
+        project: PathBuf,
+
+        /// Address to serve the review page on; use 0.0.0.0:<port> to share it on the network
+        #[arg(long, default_value = "127.0.0.1:8787")]
+        listen: std::net::SocketAddr,
+
+        /// Share the existing draft instead of exporting a new one
+        #[arg(long)]
+        reuse: bool,
+    },
+
+    /// Open a project in the desktop editor
+    Studio {
+        /// Project directory or .grabme archive [default: the project in the current directory, or the most recent one]
+        project: Option<PathBuf>,
+
+        /// Editor binary to launch [default: $GRABME_DESKTOP, or grabme-desktop next to grabme or on PATH]
+        #[arg(long)]
+        editor: Option<PathBuf>,
+    },
+
     /// Check system capabilities
     Check,
 
+    /// Test-record on each backend, check encoders and disk speed, and offer config fixes
+    Doctor {
+        /// Skip the test recordings
+        #[arg(long)]
+        skip_capture: bool,
+
+        /// Write every suggested fix to the config without asking
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Show the application log, or a recording's session log
+    Logs {
+        /// Project directory (or log file) whose session log to show
+        #[arg(long)]
+        session: Option<PathBuf>,
+
+        /// Keep printing new lines as they are written
+        #[arg(short, long)]
+        follow: bool,
+
+        /// Only show records at this level or more severe (error, warn, info, debug, trace)
+        #[arg(long, default_value = "trace")]
+        level: tracing::Level,
+
+        /// Print the raw JSON records instead of formatted lines
+        #[arg(long)]
+        json: bool,
+
+        /// Number of trailing records to show before following
+        #[arg(short = 'n', long, default_value = "50")]
+        lines: usize,
+    },
+
+    /// Replay scripted input for reproducible demos
+    Script {
+        #[command(subcommand)]
+        action: ScriptAction,
+    },
+
+    /// Print the JSON Schema of events.jsonl, project.json, or timeline.json
+    Schema {
+        /// Which file: events, project, or timeline
+        kind: SchemaKind,
+
+        /// Write the schema to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Update grabme to the latest release, verifying its checksum and signature
+    SelfUpdate {
+        /// Only report whether a newer release exists
+        #[arg(long)]
+        check: bool,
+    },
+
     /// Create a new empty project
     Init {
         /// Project name
         name: String,
 
-        /// Output directory
-        #[arg(short, long, default_value = ".")]
-        output: PathBuf,
+        /// Output directory [default: `projects_dir` from config, or ~/Videos/GrabMe]
+        #[arg(short, long)]
+        output: Option<PathBuf>,
 
         /// Capture width
         #[arg(long, default_value = "1920")]
@@ -177,17 +611,68 @@ enum Commands {
     },
 }
 
+#[derive(Subcommand)]
+enum ScriptAction {
+    /// Record while replaying a YAML input script; recording stops when it ends
+    Run {
+        /// Script to replay
+        script: PathBuf,
+
+        /// Project name [default: the script's file name]
+        #[arg(short, long)]
+        name: Option<String>,
+
+        /// Output directory [default: `projects_dir` from config, or ~/Videos/GrabMe]
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Target FPS [default: `recording.fps` from config, or 60]
+        #[arg(long)]
+        fps: Option<u32>,
+
+        /// Zero-based monitor index to record and replay onto
+        #[arg(long)]
+        monitor: Option<usize>,
+
+        /// Disable microphone capture
+        #[arg(long)]
+        no_mic: bool,
+
+        /// Disable system audio capture
+        #[arg(long)]
+        no_system_audio: bool,
+
+        /// X11 display to replay on and record instead of $DISPLAY
+        #[arg(long)]
+        display: Option<String>,
+
+        /// Replay the script without recording
+        #[arg(long)]
+        no_record: bool,
+    },
+
+    /// Convert a project's recorded input into an editable script
+    FromEvents {
+        /// Project directory
+        project: PathBuf,
+
+        /// Write the script to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
 
     // Initialize logging
-    let log_level = if cli.verbose { "debug" } else { "info" };
-    grabme_common::logging::init_logging(&grabme_common::config::LoggingConfig {
-        level: log_level.to_string(),
-        json: false,
-        file: None,
-    });
+    let mut logging = grabme_common::config::AppConfig::load().logging;
+    if cli.verbose {
+        logging.level = "debug".to_string();
+    }
+    grabme_common::logging::init_logging(&logging);
+    let _temp = grabme_common::temp::init_session_temp();
 
     match cli.command {
         Commands::Record {
@@ -196,23 +681,81 @@ async fn main() -> anyhow::Result<()> {
             fps,
             monitor,
             list_monitors,
+            split_monitor,
             no_mic,
             no_system_audio,
             webcam,
+            webcam_size,
+            webcam_fps,
+            window,
+            pick_window,
+            stills_every,
+            clock,
+            display,
+            display_server,
+            auto_fps,
+            gamepad,
+            pip_monitor,
+            pip_device,
+            frame_times,
+            metrics,
+            on_lock,
+            on_resume,
+            virtual_camera,
+            virtual_camera_device,
+            virtual_camera_zoom,
+            spool_dir,
+            like,
         } => {
+            let defaults = grabme_common::config::AppConfig::load().recording;
+            let configured_server = defaults
+                .display_server
+                .as_deref()
+                .and_then(|name| name.parse().ok());
+            let mut monitor_splits = defaults.monitor_splits;
+            monitor_splits.extend(split_monitor);
             commands::record::run(
                 name,
-                output,
+                resolve_output_dir(output)?,
                 fps,
                 monitor,
                 !no_mic,
                 !no_system_audio,
                 webcam,
+                webcam_size,
+                webcam_fps,
                 list_monitors,
+                monitor_splits,
+                commands::record::WindowSelection::from_flags(window, pick_window),
+                stills_every,
+                clock,
+                DisplayTarget {
+                    server: display_server
+                        .or(display.as_ref().map(|_| DisplayServer::X11))
+                        .or(configured_server),
+                    x11_display: display,
+                },
+                auto_fps,
+                gamepad,
+                pip_monitor
+                    .map(PipSource::Monitor)
+                    .or(pip_device.map(PipSource::Device)),
+                frame_times,
+                metrics,
+                on_lock.unwrap_or(defaults.on_lock),
+                on_resume.unwrap_or(defaults.on_resume),
+                virtual_camera.then(|| VirtualCameraConfig {
+                    device: virtual_camera_device,
+                    zoom: virtual_camera_zoom.unwrap_or(DEFAULT_VIRTUAL_CAMERA_ZOOM),
+                    ..VirtualCameraConfig::default()
+                }),
+                spool_dir.or(defaults.spool_dir),
+                like,
+                None,
             )
             .await
         }
-        Commands::Validate { path } => commands::validate::run(path),
+        Commands::Validate { path, fix_aspect } => commands::validate::run(path, fix_aspect),
         Commands::Analyze {
             path,
             chunk_secs,
@@ -222,11 +765,15 @@ async fn main() -> anyhow::Result<()> {
             scan_zoom,
             dwell_radius,
             dwell_velocity,
+            dwell_unit,
             smooth_window,
             cursor_smoothing,
             cursor_smoothing_factor,
             monitor_count,
             focused_monitor,
+            click_burst_window,
+            pass_plugins,
+            report,
         } => commands::analyze::run(
             path,
             chunk_secs,
@@ -236,11 +783,15 @@ async fn main() -> anyhow::Result<()> {
             scan_zoom,
             dwell_radius,
             dwell_velocity,
+            dwell_unit,
             smooth_window,
             cursor_smoothing,
             cursor_smoothing_factor,
             monitor_count,
             focused_monitor,
+            click_burst_window,
+            pass_plugins,
+            report,
         ),
         Commands::Export {
             path,
@@ -248,14 +799,186 @@ async fn main() -> anyhow::Result<()> {
             format,
             width,
             height,
-        } => commands::export::run(path, output, format, width, height).await,
+            music,
+            music_gain,
+            fast_copy,
+            fps,
+            start,
+            end,
+            keep_preroll,
+            trim_tail,
+            keep_tail,
+            debug_overlay,
+            threads,
+            priority,
+            preset,
+            strokes,
+            no_reactions,
+            gamepad_overlay,
+            metrics_overlay,
+            burn_clock,
+            overscan,
+            video_bitrate,
+            target_size,
+            copy,
+            no_copy,
+            variants,
+            waveform,
+            draft,
+            watermark,
+            title,
+            author,
+            comment,
+            creation_time,
+            chapters,
+        } => {
+            commands::export::run(
+                path,
+                commands::export::ExportArgs {
+                    output,
+                    format,
+                    width,
+                    height,
+                    music,
+                    music_gain,
+                    fast_copy,
+                    fps,
+                    start,
+                    end,
+                    keep_preroll,
+                    trim_tail: trim_tail.then_some(true).or(keep_tail.then_some(false)),
+                    debug_overlay,
+                    resources: ExportResources {
+                        threads,
+                        priority,
+                        preset,
+                        stream_events: false,
+                    }
+                    .with_memory(&grabme_common::config::AppConfig::load().memory.profile()),
+                    strokes,
+                    no_reactions,
+                    gamepad_overlay,
+                    metrics_overlay,
+                    burn_clock,
+                    overscan,
+                    video_bitrate,
+                    target_size,
+                    copy,
+                    no_copy,
+                    variants,
+                    waveform,
+                    draft: draft.then_some(DraftOptions { watermark }),
+                    metadata: ExportMetadata {
+                        title,
+                        author,
+                        comment,
+                        creation_time,
+                        chapters,
+                    },
+                },
+            )
+            .await
+        }
+        Commands::Stills {
+            path,
+            every,
+            clicks,
+            output,
+        } => commands::stills::run(path, every, clicks, output),
+        Commands::AuditCursor {
+            project,
+            export,
+            start,
+            end,
+            keep_preroll,
+            radius,
+            json,
+        } => commands::audit_cursor::run(project, export, start, end, keep_preroll, radius, json),
         Commands::Info { path } => commands::info::run(path),
+        Commands::Status {
+            dir,
+            json,
+            thumbnails,
+        } => commands::status::run(dir, json, thumbnails),
+        Commands::Slim {
+            path,
+            delete,
+            verify,
+            crf,
+            dry_run,
+            yes,
+        } => commands::slim::run(path, delete, verify, crf, dry_run, yes),
+        Commands::Review {
+            project,
+            listen,
+            reuse,
+        } => commands::review::run(project, listen, reuse).await,
+        Commands::Studio { project, editor } => commands::studio::run(project, editor),
         Commands::Check => commands::check::run(),
+        Commands::Doctor { skip_capture, yes } => commands::doctor::run(skip_capture, yes).await,
+        Commands::Logs {
+            session,
+            follow,
+            level,
+            json,
+            lines,
+        } => commands::logs::run(session, follow, level, json, lines).await,
+        Commands::Script { action } => match action {
+            ScriptAction::Run {
+                script,
+                name,
+                output,
+                fps,
+                monitor,
+                no_mic,
+                no_system_audio,
+                display,
+                no_record,
+            } => {
+                let configured_server = grabme_common::config::AppConfig::load()
+                    .recording
+                    .display_server
+                    .and_then(|name| name.parse().ok());
+                commands::script::run(
+                    script,
+                    name,
+                    resolve_output_dir(output)?,
+                    fps,
+                    monitor,
+                    !no_mic,
+                    !no_system_audio,
+                    DisplayTarget {
+                        server: display
+                            .as_ref()
+                            .map(|_| DisplayServer::X11)
+                            .or(configured_server),
+                        x11_display: display,
+                    },
+                    no_record,
+                )
+                .await
+            }
+            ScriptAction::FromEvents { project, output } => {
+                commands::script::from_events(project, output)
+            }
+        },
+        Commands::Schema { kind, output } => commands::schema::run(kind, output),
+        Commands::SelfUpdate { check } => commands::self_update::run(check),
         Commands::Init {
             name,
             output,
             width,
             height,
-        } => commands::init::run(name, output, width, height),
+        } => commands::init::run(name, resolve_output_dir(output)?, width, height),
+    }
+}
+
+/// Use the explicit `--output`, or the configured projects directory.
+fn resolve_output_dir(output: Option<PathBuf>) -> anyhow::Result<PathBuf> {
+    match output {
+        Some(output) => Ok(output),
+        None => grabme_common::config::AppConfig::load()
+            .ensure_projects_dir()
+            .map_err(|e| anyhow::anyhow!("Failed to create projects directory: {e}")),
     }
 }
@@ -0,0 +1,108 @@
+use std::path::Path;
+use std::process::{Command, Output};
+
+use grabme_project_model::event::{ButtonState, EventKind};
+use grabme_project_model::LoadedProject;
+use grabme_render_engine::probe::probe_media;
+use grabme_testkit::{
+    ffmpeg_available, EventPattern, Media, MonitorLayout, ProjectFixture, TestDir,
+};
+
+/// Run the `grabme` binary with its config read from `config_home`, so the
+/// developer's own settings don't leak into the test.
+fn grabme(config_home: &Path, args: &[&str]) -> Output {
+    let output = Command::new(env!("CARGO_BIN_EXE_grabme"))
+        .args(args)
+        .env("XDG_CONFIG_HOME", config_home)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "grabme {args:?} failed:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    output
+}
+
+#[test]
+fn analyze_follows_clicks_on_the_recorded_monitor() {
+    let dir = TestDir::new("cli-analyze");
+    let fixture = ProjectFixture::new("clicks")
+        .with_duration_secs(12.0)
+        .with_layout(MonitorLayout::side_by_side((1280, 720), (1280, 720)), 1)
+        .with_events(EventPattern::Clicks { every_secs: 3.0 });
+    let project = fixture.build(&dir.path().join("clicks")).unwrap();
+    let path = project.root.to_str().unwrap();
+
+    grabme(
+        &dir.path().join("config"),
+        &["analyze", path, "--camera-style", "auto"],
+    );
+
+    let timeline = LoadedProject::load(&project.root).unwrap().timeline;
+    assert!(!timeline.keyframes.is_empty());
+    // The recording holds only the right monitor, so the camera has to find
+    // each click after the pointer is mapped back into the capture.
+    for event in fixture.capture_events() {
+        if let EventKind::Click {
+            state: ButtonState::Down,
+            x,
+            y,
+            ..
+        } = event.kind
+        {
+            let t = event.timestamp_ns as f64 / 1e9;
+            let keyframe = timeline
+                .keyframes
+                .iter()
+                .rev()
+                .find(|kf| kf.time_secs < t)
+                .unwrap();
+            let v = keyframe.viewport;
+            assert!(
+                (v.x..=v.x + v.w).contains(&x) && (v.y..=v.y + v.h).contains(&y),
+                "click at {t}s ({x}, {y}) is outside {v:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn export_writes_the_requested_width_at_the_recorded_shape() {
+    if !ffmpeg_available() {
+        eprintln!("skipping: ffmpeg is not installed");
+        return;
+    }
+
+    let dir = TestDir::new("cli-export");
+    let project = ProjectFixture::new("demo")
+        .with_duration_secs(2.0)
+        .with_layout(MonitorLayout::side_by_side((640, 360), (640, 360)), 1)
+        .with_events(EventPattern::Sweep { period_secs: 1.0 })
+        .with_events(EventPattern::Clicks { every_secs: 0.5 })
+        .with_media(Media::Screen)
+        .with_media(Media::Mic)
+        .build(&dir.path().join("demo"))
+        .unwrap();
+    let path = project.root.to_str().unwrap();
+    let output = project.root.join("exports").join("cli.mp4");
+    let config_home = dir.path().join("config");
+
+    grabme(&config_home, &["analyze", path]);
+    grabme(
+        &config_home,
+        &[
+            "export",
+            path,
+            "--output",
+            output.to_str().unwrap(),
+            "--width",
+            "320",
+        ],
+    );
+
+    let info = probe_media(&output).unwrap();
+    let video = info.video.expect("export should have video");
+    assert_eq!((video.width, video.height), (320, 180));
+    assert!(info.audio.is_some(), "export should carry the mic");
+}
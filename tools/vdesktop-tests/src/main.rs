@@ -10,7 +10,8 @@
 use anyhow::{Context, Result};
 use clap::Parser;
 use grabme_capture_engine::{
-    AudioCaptureConfig, CaptureMode, CaptureSession, ScreenCaptureConfig, SessionConfig,
+    AudioCaptureConfig, CaptureMode, CaptureSession, DisplayTarget, ScreenCaptureConfig,
+    SessionConfig,
 };
 use std::path::PathBuf;
 use std::process::Command;
@@ -115,8 +116,6 @@ async fn setup_xvfb(args: &Args) -> Result<tokio::process::Child> {
     sleep(Duration::from_secs(2)).await;
 
     // Verify display is working
-    std::env::set_var("DISPLAY", format!(":{}", args.display));
-
     let status = Command::new("xdpyinfo")
         .env("DISPLAY", format!(":{}", args.display))
         .output()
@@ -176,6 +175,8 @@ async fn run_recording_test(args: &Args) -> Result<PathBuf> {
         screen: ScreenCaptureConfig {
             mode: CaptureMode::FullScreen { monitor_index: 0 },
             hide_cursor: false,
+            cursor_mode: None,
+            monitor_splits: Default::default(),
         },
         audio: AudioCaptureConfig {
             mic: false,
@@ -185,6 +186,7 @@ async fn run_recording_test(args: &Args) -> Result<PathBuf> {
         },
         fps: 30,
         pointer_sample_rate_hz: 60,
+        display: DisplayTarget::x11(format!(":{}", args.display)),
         ..Default::default()
     };
 